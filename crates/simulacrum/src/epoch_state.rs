@@ -8,16 +8,23 @@ use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_execution::Executor;
 use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use sui_types::{
+    base_types::{ObjectRef, SuiAddress},
     committee::{Committee, EpochId},
+    crypto::default_hash,
+    digests::TransactionDigest,
     effects::TransactionEffects,
+    error::ExecutionError,
+    execution_mode::ExecutionResult,
+    gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
     metrics::BytecodeVerifierMetrics,
     metrics::LimitsMetrics,
+    object::Object,
     sui_system_state::{
         epoch_start_sui_system_state::{EpochStartSystemState, EpochStartSystemStateTrait},
         SuiSystemState, SuiSystemStateTrait,
     },
-    transaction::VerifiedTransaction,
+    transaction::{TransactionData, TransactionDataAPI, TransactionKind, VerifiedTransaction},
 };
 
 use crate::store::InMemoryStore;
@@ -43,7 +50,7 @@ impl EpochState {
         let registry = prometheus::Registry::new();
         let limits_metrics = Arc::new(LimitsMetrics::new(&registry));
         let bytecode_verifier_metrics = Arc::new(BytecodeVerifierMetrics::new(&registry));
-        let executor = sui_execution::executor(&protocol_config, false, true).unwrap();
+        let executor = sui_execution::executor(&protocol_config, false, true, None).unwrap();
 
         Self {
             epoch_start_state,
@@ -56,6 +63,11 @@ impl EpochState {
         }
     }
 
+    /// Replace the committee derived from the system state, e.g. with one whose keys are known.
+    pub fn with_committee(self, committee: Committee) -> Self {
+        Self { committee, ..self }
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.epoch_start_state.epoch()
     }
@@ -124,4 +136,119 @@ impl EpochState {
             tx_digest,
         ))
     }
+
+    /// Execute `transaction` without requiring it to be signed, paying for gas with `gas_object`
+    /// instead of the transaction's gas payment if one is provided.
+    pub fn dry_run_transaction(
+        &self,
+        store: &InMemoryStore,
+        deny_config: &TransactionDenyConfig,
+        transaction: &TransactionData,
+        transaction_digest: TransactionDigest,
+        gas_object: Option<Object>,
+    ) -> Result<(
+        InnerTemporaryStore,
+        TransactionEffects,
+        Result<(), ExecutionError>,
+    )> {
+        let (gas, (gas_status, input_objects)) = match gas_object {
+            Some(gas_object) => (
+                vec![gas_object.compute_object_reference()],
+                sui_transaction_checks::check_transaction_input_with_given_gas(
+                    store,
+                    &self.protocol_config,
+                    self.epoch_start_state.reference_gas_price(),
+                    self.epoch(),
+                    transaction,
+                    gas_object,
+                    &self.bytecode_verifier_metrics,
+                )?,
+            ),
+            None => (
+                transaction.gas().to_vec(),
+                sui_transaction_checks::check_transaction_input(
+                    store,
+                    &self.protocol_config,
+                    self.epoch_start_state.reference_gas_price(),
+                    self.epoch(),
+                    transaction,
+                    &[],
+                    deny_config,
+                    &self.bytecode_verifier_metrics,
+                )?,
+            ),
+        };
+
+        let (kind, signer, _) = transaction.execution_parts();
+        Ok(self.executor.execute_transaction_to_effects(
+            store,
+            &self.protocol_config,
+            self.limits_metrics.clone(),
+            false,           // enable_expensive_checks
+            &HashSet::new(), // certificate_deny_set
+            &self.epoch_start_state.epoch(),
+            self.epoch_start_state.epoch_start_timestamp_ms(),
+            input_objects,
+            gas,
+            gas_status,
+            kind,
+            signer,
+            transaction_digest,
+        ))
+    }
+
+    /// Execute `kind` in dev-inspect mode on behalf of `sender`, paying for gas with `gas_object`.
+    pub fn dev_inspect_transaction(
+        &self,
+        store: &InMemoryStore,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_price: u64,
+        gas_object: Object,
+    ) -> Result<(
+        ObjectRef,
+        InnerTemporaryStore,
+        TransactionEffects,
+        Result<Vec<ExecutionResult>, ExecutionError>,
+    )> {
+        let max_tx_gas = self.protocol_config.max_tx_gas();
+        let gas_status = SuiGasStatus::new(
+            max_tx_gas,
+            gas_price,
+            self.epoch_start_state.reference_gas_price(),
+            &self.protocol_config,
+        )?;
+        let (gas_object_ref, input_objects) = sui_transaction_checks::check_dev_inspect_input(
+            store,
+            &self.protocol_config,
+            &kind,
+            gas_object,
+            self.epoch(),
+        )?;
+
+        let data = TransactionData::new(kind, sender, gas_object_ref, gas_price, max_tx_gas);
+        let transaction_digest = TransactionDigest::new(default_hash(&data));
+        let (inner_temporary_store, effects, execution_result) =
+            self.executor.dev_inspect_transaction(
+                store,
+                &self.protocol_config,
+                self.limits_metrics.clone(),
+                false,           // enable_expensive_checks
+                &HashSet::new(), // certificate_deny_set
+                &self.epoch_start_state.epoch(),
+                self.epoch_start_state.epoch_start_timestamp_ms(),
+                input_objects,
+                vec![gas_object_ref],
+                gas_status,
+                data.into_kind(),
+                sender,
+                transaction_digest,
+            );
+        Ok((
+            gas_object_ref,
+            inner_temporary_store,
+            effects,
+            execution_result,
+        ))
+    }
 }
@@ -0,0 +1,125 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for seeding a [`Simulacrum`] from the state of an existing chain.
+//!
+//! A [`ForkState`] holds the set of objects (and optionally the checkpoint they were captured at)
+//! that a forked Simulacrum starts from. With the `fork` feature, it can be loaded from a formal
+//! state snapshot, or from a sandbox persisted by `sui-replay`. Objects that are not part of the
+//! fork state are looked up through an [`ObjectFetcher`], if one is provided.
+//!
+//! [`Simulacrum`]: crate::Simulacrum
+
+#[cfg(feature = "fork")]
+use std::{num::NonZeroUsize, path::Path};
+
+#[cfg(feature = "fork")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "fork")]
+use futures::future::AbortHandle;
+#[cfg(feature = "fork")]
+use indicatif::MultiProgress;
+#[cfg(feature = "fork")]
+use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+#[cfg(feature = "fork")]
+use sui_replay::ExecutionSandboxState;
+#[cfg(feature = "fork")]
+use sui_snapshot::reader::StateSnapshotReaderV1;
+#[cfg(feature = "fork")]
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    messages_checkpoint::VerifiedCheckpoint,
+    object::Object,
+};
+
+/// A source of objects that are missing from a forked Simulacrum's local store, e.g. a fullnode,
+/// an archive or, in tests, a stub.
+///
+/// Fetched objects are cached by the store, so each object is requested at most once.
+pub trait ObjectFetcher: Send + Sync {
+    /// Returns the latest version of the object with `id`.
+    fn get_object(&self, id: &ObjectID) -> Result<Option<Object>>;
+
+    /// Returns the object with `id` at exactly `version`.
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>>;
+}
+
+/// The chain state a forked Simulacrum starts from.
+#[derive(Debug, Clone)]
+pub struct ForkState {
+    /// The checkpoint `objects` were captured at. If not provided, the forked chain starts from a
+    /// synthetic checkpoint in the epoch of the forked system state.
+    pub checkpoint: Option<VerifiedCheckpoint>,
+    /// The live objects to seed the Simulacrum's store with.
+    pub objects: Vec<Object>,
+}
+
+impl ForkState {
+    pub fn new(checkpoint: Option<VerifiedCheckpoint>, objects: Vec<Object>) -> Self {
+        Self {
+            checkpoint,
+            objects,
+        }
+    }
+}
+
+#[cfg(feature = "fork")]
+impl ForkState {
+    /// Load the objects required by the transaction captured in a sandbox file written by
+    /// `sui-replay ps`.
+    ///
+    /// A sandbox only contains the inputs of a single transaction, so system objects like the
+    /// clock and the system state are expected to be served by an [`ObjectFetcher`].
+    pub fn from_sandbox_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sandbox file {}", path.display()))?;
+        let sandbox_state: ExecutionSandboxState = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse sandbox file {}", path.display()))?;
+        Ok(Self::new(None, sandbox_state.required_objects))
+    }
+
+    /// Download the formal state snapshot taken at the end of `epoch` from `remote_store_config`
+    /// and load all of its live objects.
+    ///
+    /// The snapshot is staged in `local_dir`. Note that every live object is held in memory by the
+    /// Simulacrum, which for a production network requires a lot of memory.
+    pub async fn from_snapshot(
+        epoch: u64,
+        remote_store_config: &ObjectStoreConfig,
+        local_dir: &Path,
+        download_concurrency: NonZeroUsize,
+    ) -> Result<Self> {
+        let perpetual_db = AuthorityPerpetualTables::open(&local_dir.join("store"), None);
+        let local_store_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(local_dir.join("snapshot")),
+            ..Default::default()
+        };
+        let mut reader = StateSnapshotReaderV1::new(
+            epoch,
+            remote_store_config,
+            &local_store_config,
+            usize::MAX,
+            download_concurrency,
+            MultiProgress::new(),
+        )
+        .await?;
+        let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+        reader.read(&perpetual_db, abort_registration, None).await?;
+
+        let objects = perpetual_db
+            .iter_live_object_set(/* include_wrapped_object */ false)
+            .filter_map(|object| match object {
+                LiveObject::Normal(object) => Some(object),
+                LiveObject::Wrapped(_) => None,
+            })
+            .collect();
+        Ok(Self::new(None, objects))
+    }
+}
@@ -12,7 +12,7 @@
 
 use std::num::NonZeroUsize;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use fastcrypto::traits::Signer;
 use rand::rngs::OsRng;
 use sui_config::{genesis, transaction_deny_config::TransactionDenyConfig};
@@ -22,26 +22,40 @@ use sui_swarm_config::network_config::NetworkConfig;
 use sui_swarm_config::network_config_builder::ConfigBuilder;
 use sui_types::base_types::AuthorityName;
 use sui_types::crypto::AuthoritySignature;
+use sui_types::sui_system_state::{SuiSystemState, SuiSystemStateTrait};
 use sui_types::{
-    base_types::SuiAddress,
+    base_types::{ObjectID, SuiAddress},
     committee::Committee,
+    digests::TransactionDigest,
     effects::TransactionEffects,
     error::ExecutionError,
+    execution_mode::ExecutionResult,
     gas_coin::MIST_PER_SUI,
     inner_temporary_store::InnerTemporaryStore,
-    messages_checkpoint::{EndOfEpochData, VerifiedCheckpoint},
+    messages_checkpoint::{
+        CheckpointContents, CheckpointSummary, EndOfEpochData, VerifiedCheckpoint,
+    },
+    object::{MoveObject, Object, Owner, OBJECT_START_VERSION},
     signature::VerifyParams,
-    transaction::{Transaction, VerifiedTransaction},
+    transaction::{
+        Transaction, TransactionData, TransactionDataAPI, TransactionKind, VerifiedTransaction,
+    },
 };
 
 use self::epoch_state::EpochState;
+pub use self::fork::{ForkState, ObjectFetcher};
 use self::store::KeyStore;
 pub use self::store::{InMemoryStore, SimulatorStore};
 use sui_types::mock_checkpoint_builder::{MockCheckpointBuilder, ValidatorKeypairProvider};
 
 mod epoch_state;
+pub mod fork;
+pub mod rpc;
 mod store;
 
+/// The balance of the mock gas coin used to dry run transactions that don't provide a gas payment.
+const DRY_RUN_GAS_AMOUNT: u64 = 1_000_000_000 * MIST_PER_SUI;
+
 /// A `Simulacrum` of Sui.
 ///
 /// This type represents a simulated instantiation of a Sui blockchain that needs to be driven
@@ -55,7 +69,7 @@ pub struct Simulacrum<R = OsRng, Store: SimulatorStore = InMemoryStore> {
     rng: R,
     keystore: KeyStore,
     #[allow(unused)]
-    genesis: genesis::Genesis,
+    genesis: Option<genesis::Genesis>,
     store: Store,
     checkpoint_builder: MockCheckpointBuilder,
 
@@ -64,6 +78,9 @@ pub struct Simulacrum<R = OsRng, Store: SimulatorStore = InMemoryStore> {
 
     // Other
     deny_config: TransactionDenyConfig,
+    /// Whether this chain was forked from another one, in which case its checkpoints are certified
+    /// by the keystore's committee instead of the committee in the system state.
+    forked: bool,
 }
 
 impl Simulacrum {
@@ -128,12 +145,110 @@ where
         Self {
             rng,
             keystore,
-            genesis: genesis.clone(),
+            genesis: Some(genesis.clone()),
             store,
             checkpoint_builder,
             epoch_state,
             deny_config: TransactionDenyConfig::default(),
+            forked: false,
+        }
+    }
+
+    /// Create a Simulacrum which continues from the state of another chain, e.g. a state snapshot
+    /// of mainnet, to run "what-if" transactions against it offline.
+    ///
+    /// The store is seeded with `fork.objects`, and objects that are missing locally are requested
+    /// from `fetcher`. Since the keys of the forked chain's validators are not available, the
+    /// Simulacrum certifies its checkpoints with a freshly generated single-validator committee.
+    /// The accounts in `account_configs` are funded with newly minted gas coins, so that they can
+    /// be used to sign transactions.
+    ///
+    /// Fails if the forked state does not contain a system state and clock (either directly or
+    /// through `fetcher`), or if `fork.checkpoint` is not from the epoch of that system state.
+    pub fn new_from_fork(
+        mut rng: R,
+        fork: ForkState,
+        fetcher: Option<Box<dyn ObjectFetcher>>,
+        account_configs: Vec<AccountConfig>,
+    ) -> Result<Self> {
+        let keystore = KeyStore::new_with_rng(&mut rng, &account_configs);
+
+        let mut generated_addresses = keystore.accounts().map(|(address, _)| *address);
+        let mut gas_objects = vec![];
+        for account in &account_configs {
+            let owner = match account.address {
+                Some(address) => address,
+                None => generated_addresses.next().ok_or_else(|| {
+                    anyhow!("No key was generated for an account without address")
+                })?,
+            };
+            for amount in &account.gas_amounts {
+                let mut id = [0; ObjectID::LENGTH];
+                rng.fill_bytes(&mut id);
+                let id = ObjectID::new(id);
+                gas_objects.push(Object::new_move(
+                    MoveObject::new_gas_coin(OBJECT_START_VERSION, id, *amount),
+                    Owner::AddressOwner(owner),
+                    TransactionDigest::genesis(),
+                ));
+            }
         }
+
+        let mut store =
+            InMemoryStore::new_forked(fork.objects.into_iter().chain(gas_objects), fetcher);
+        let system_state = sui_types::sui_system_state::get_sui_system_state(&store)?;
+        let epoch = system_state.epoch();
+        let committee = keystore.committee(epoch);
+        store.insert_committee(committee.clone());
+
+        let checkpoint = match fork.checkpoint {
+            Some(checkpoint) => {
+                ensure!(
+                    checkpoint.epoch == epoch,
+                    "fork checkpoint {} is from epoch {}, but the forked system state is at epoch {}",
+                    checkpoint.sequence_number,
+                    checkpoint.epoch,
+                    epoch,
+                );
+                checkpoint
+            }
+            None => {
+                // Without a checkpoint to continue from, start the chain from an empty one in the
+                // forked epoch.
+                let contents = CheckpointContents::new_with_causally_ordered_execution_data([]);
+                let summary = CheckpointSummary {
+                    epoch,
+                    sequence_number: 0,
+                    network_total_transactions: 0,
+                    content_digest: *contents.digest(),
+                    previous_digest: None,
+                    epoch_rolling_gas_cost_summary: Default::default(),
+                    end_of_epoch_data: None,
+                    timestamp_ms: store.get_clock().timestamp_ms(),
+                    version_specific_data: Vec::new(),
+                    checkpoint_commitments: Default::default(),
+                };
+                store.insert_checkpoint_contents(contents);
+                MockCheckpointBuilder::create_certified_checkpoint(
+                    &CommitteeWithKeys::new(&keystore, &committee),
+                    summary,
+                )
+            }
+        };
+        store.insert_checkpoint(checkpoint.clone());
+        let checkpoint_builder = MockCheckpointBuilder::new(checkpoint);
+        let epoch_state = EpochState::new(system_state).with_committee(committee);
+
+        Ok(Self {
+            rng,
+            keystore,
+            genesis: None,
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            forked: true,
+        })
     }
 }
 
@@ -176,6 +291,86 @@ impl<R> Simulacrum<R> {
         Ok((effects, execution_error_opt.err()))
     }
 
+    /// Executes the provided TransactionData without committing its effects to the store.
+    ///
+    /// The transaction doesn't need to be signed. If it doesn't specify a gas payment, a mock gas
+    /// coin owned by the sender is used to pay for it, the ID of which is returned alongside the
+    /// results of the execution.
+    pub fn dry_run_transaction(
+        &self,
+        transaction: &TransactionData,
+        transaction_digest: TransactionDigest,
+    ) -> Result<(
+        InnerTemporaryStore,
+        TransactionEffects,
+        Option<ExecutionError>,
+        Option<ObjectID>,
+    )> {
+        ensure!(
+            !transaction.kind().is_system_tx(),
+            "dry run does not support system transactions"
+        );
+
+        let gas_object = transaction.gas().is_empty().then(|| {
+            Object::new_move(
+                MoveObject::new_gas_coin(
+                    OBJECT_START_VERSION,
+                    ObjectID::random(),
+                    DRY_RUN_GAS_AMOUNT,
+                ),
+                Owner::AddressOwner(transaction.sender()),
+                TransactionDigest::genesis(),
+            )
+        });
+        let mock_gas = gas_object.as_ref().map(|object| object.id());
+
+        let (inner_temporary_store, effects, execution_error_opt) =
+            self.epoch_state.dry_run_transaction(
+                &self.store,
+                &self.deny_config,
+                transaction,
+                transaction_digest,
+                gas_object,
+            )?;
+        Ok((
+            inner_temporary_store,
+            effects,
+            execution_error_opt.err(),
+            mock_gas,
+        ))
+    }
+
+    /// Executes `kind` in dev-inspect mode on behalf of `sender`, without committing its effects
+    /// to the store.
+    ///
+    /// Gas is paid for with a mock gas coin holding twice the maximum transaction budget.
+    /// `gas_price` defaults to the reference gas price.
+    pub fn dev_inspect_transaction(
+        &self,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_price: Option<u64>,
+    ) -> Result<(
+        InnerTemporaryStore,
+        TransactionEffects,
+        Result<Vec<ExecutionResult>, ExecutionError>,
+    )> {
+        let gas_price = gas_price
+            .filter(|price| *price != 0)
+            .unwrap_or_else(|| self.reference_gas_price());
+        let max_tx_gas = self.epoch_state.protocol_config().max_tx_gas();
+        let gas_object = Object::new_move(
+            MoveObject::new_gas_coin(OBJECT_START_VERSION, ObjectID::random(), max_tx_gas * 2),
+            Owner::AddressOwner(sender),
+            TransactionDigest::genesis(),
+        );
+
+        let (_, inner_temporary_store, effects, execution_result) = self
+            .epoch_state
+            .dev_inspect_transaction(&self.store, sender, kind, gas_price, gas_object)?;
+        Ok((inner_temporary_store, effects, execution_result))
+    }
+
     /// Creates the next Checkpoint using the Transactions enqueued since the last checkpoint was
     /// created.
     pub fn create_checkpoint(&mut self) -> VerifiedCheckpoint {
@@ -232,7 +427,7 @@ impl<R> Simulacrum<R> {
         self.execute_transaction(tx.into())
             .expect("advancing the epoch cannot fail");
 
-        let new_epoch_state = EpochState::new(self.store.get_system_state());
+        let new_epoch_state = self.new_epoch_state(self.store.get_system_state());
         let end_of_epoch_data = EndOfEpochData {
             next_epoch_committee: new_epoch_state.committee().voting_rights.clone(),
             next_epoch_protocol_version,
@@ -251,6 +446,16 @@ impl<R> Simulacrum<R> {
         self.epoch_state = new_epoch_state;
     }
 
+    fn new_epoch_state(&self, system_state: SuiSystemState) -> EpochState {
+        let epoch_state = EpochState::new(system_state);
+        if self.forked {
+            let committee = self.keystore.committee(epoch_state.epoch());
+            epoch_state.with_committee(committee)
+        } else {
+            epoch_state
+        }
+    }
+
     pub fn store(&self) -> &InMemoryStore {
         &self.store
     }
@@ -364,8 +569,9 @@ mod tests {
 
     use rand::{rngs::StdRng, SeedableRng};
     use shared_crypto::intent::Intent;
+    use sui_swarm_config::genesis_config::DEFAULT_GAS_AMOUNT;
     use sui_types::{
-        base_types::SuiAddress,
+        base_types::{SequenceNumber, SuiAddress},
         effects::TransactionEffectsAPI,
         gas_coin::GasCoin,
         programmable_transaction_builder::ProgrammableTransactionBuilder,
@@ -440,6 +646,66 @@ mod tests {
         dbg!(chain.store().get_highest_checkpint());
     }
 
+    struct StubFetcher(Simulacrum);
+
+    impl ObjectFetcher for StubFetcher {
+        fn get_object(&self, id: &ObjectID) -> Result<Option<Object>> {
+            Ok(self.0.store().get_object(id).cloned())
+        }
+
+        fn get_object_at_version(
+            &self,
+            id: &ObjectID,
+            version: SequenceNumber,
+        ) -> Result<Option<Object>> {
+            Ok(self.0.store().get_object_at_version(id, version).cloned())
+        }
+    }
+
+    #[test]
+    fn fork() {
+        let source = Simulacrum::new();
+        let source_epoch = source.store().get_system_state().epoch();
+
+        // Start from an empty fork state, so that every object of the source chain, including the
+        // system state, has to be fetched.
+        let mut sim = Simulacrum::new_from_fork(
+            StdRng::from_seed([9; 32]),
+            ForkState::new(None, vec![]),
+            Some(Box::new(StubFetcher(source))),
+            vec![AccountConfig {
+                address: None,
+                gas_amounts: vec![DEFAULT_GAS_AMOUNT],
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            sim.store().get_highest_checkpint().unwrap().epoch,
+            source_epoch
+        );
+
+        let recipient = SuiAddress::generate(sim.rng());
+        sim.request_gas(recipient, MIST_PER_SUI).unwrap();
+        let checkpoint = sim.create_checkpoint();
+        assert_eq!(checkpoint.sequence_number, 1);
+        assert_eq!(checkpoint.network_total_transactions, 1);
+        assert_eq!(
+            MIST_PER_SUI,
+            sim.store()
+                .owned_objects(recipient)
+                .next()
+                .and_then(|object| GasCoin::try_from(object).ok())
+                .unwrap()
+                .value()
+        );
+
+        sim.advance_epoch();
+        assert_eq!(sim.store().get_system_state().epoch(), source_epoch + 1);
+        sim.advance_clock(Duration::from_millis(1));
+        let checkpoint = sim.create_checkpoint();
+        assert_eq!(checkpoint.epoch, source_epoch + 1);
+    }
+
     #[test]
     fn transfer() {
         let mut sim = Simulacrum::new();
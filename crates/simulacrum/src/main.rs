@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+use clap::Parser;
+use prometheus::Registry;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use tracing::info;
+
+use simulacrum::rpc::start_rpc_server;
+use simulacrum::Simulacrum;
+
+/// Serve a local Simulacrum over the fullnode JSON-RPC API.
+#[derive(Parser)]
+#[clap(name = "simulacrum")]
+struct Args {
+    /// The address to serve JSON-RPC requests on.
+    #[clap(long, default_value = "127.0.0.1:9000")]
+    listen_address: SocketAddr,
+
+    /// Seed for the RNG used to build genesis. The chain is fully deterministic when provided.
+    #[clap(long)]
+    seed: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let args = Args::parse();
+    let rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(rng)));
+
+    let handle = start_rpc_server(simulacrum, args.listen_address, &Registry::new()).await?;
+    info!(
+        "Simulacrum JSON-RPC server listening on {}",
+        args.listen_address
+    );
+    handle.stopped().await;
+    Ok(())
+}
@@ -0,0 +1,187 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_json_rpc::api::{cap_page_limit, CoinReadApiServer};
+use sui_json_rpc::coin_api::{parse_to_struct_tag, parse_to_type_tag};
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_types::{Balance, Coin, CoinPage, Page, SuiCoinMetadata};
+use sui_open_rpc::Module;
+use sui_types::balance::Supply;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::coin::{CoinMetadata, TreasuryCap};
+use sui_types::gas_coin::{GAS, TOTAL_SUPPLY_SUI};
+use sui_types::object::Object;
+
+use super::{read_simulacrum, SharedSimulacrum};
+use crate::InMemoryStore;
+
+pub struct CoinReadApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> CoinReadApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+
+    /// Returns a page of the coins owned by `owner`, of `coin_type` if one is provided, ordered by
+    /// object ID.
+    fn coins_page(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<TypeTag>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> CoinPage {
+        let limit = cap_page_limit(limit);
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let mut coins: Vec<_> = simulacrum
+            .store()
+            .owned_objects(owner)
+            .filter_map(|object| {
+                let object_coin_type = object.coin_type_maybe()?;
+                if coin_type.as_ref().map_or(true, |t| *t == object_coin_type) {
+                    Some(to_coin(object, object_coin_type))
+                } else {
+                    None
+                }
+            })
+            .filter(|coin| cursor.map_or(true, |cursor| coin.coin_object_id > cursor))
+            .collect();
+        coins.sort_by_key(|coin| coin.coin_object_id);
+
+        let has_next_page = coins.len() > limit;
+        coins.truncate(limit);
+        let next_cursor = coins.last().map(|coin| coin.coin_object_id);
+        Page {
+            data: coins,
+            next_cursor,
+            has_next_page,
+        }
+    }
+}
+
+fn to_coin(object: &Object, coin_type: TypeTag) -> Coin {
+    let (coin_object_id, version, digest) = object.compute_object_reference();
+    Coin {
+        coin_type: coin_type.to_string(),
+        coin_object_id,
+        version,
+        digest,
+        balance: object.get_coin_value_unsafe(),
+        previous_transaction: object.previous_transaction,
+    }
+}
+
+/// Returns the live object of type `struct_tag`, e.g. a coin's metadata or treasury cap.
+fn find_object_by_type(store: &InMemoryStore, struct_tag: StructTag) -> Option<&Object> {
+    store
+        .live_objects()
+        .find(|object| object.struct_tag().as_ref() == Some(&struct_tag))
+}
+
+#[async_trait]
+impl<R: Send + Sync + 'static> CoinReadApiServer for CoinReadApi<R> {
+    async fn get_coins(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinPage> {
+        // Normalize coin type tag and default to Gas
+        let coin_type = parse_to_type_tag(coin_type)?;
+        Ok(self.coins_page(owner, Some(coin_type), cursor, limit))
+    }
+
+    async fn get_all_coins(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinPage> {
+        Ok(self.coins_page(owner, None, cursor, limit))
+    }
+
+    async fn get_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+    ) -> RpcResult<Balance> {
+        // Normalize coin type tag and default to Gas
+        let coin_type = parse_to_type_tag(coin_type)?;
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let mut balance = Balance::zero(coin_type.to_string());
+        for object in simulacrum.store().owned_objects(owner) {
+            if object.coin_type_maybe().as_ref() == Some(&coin_type) {
+                balance.coin_object_count += 1;
+                balance.total_balance += object.get_coin_value_unsafe() as u128;
+            }
+        }
+        Ok(balance)
+    }
+
+    async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let mut balances = BTreeMap::new();
+        for object in simulacrum.store().owned_objects(owner) {
+            let Some(coin_type) = object.coin_type_maybe() else {
+                continue;
+            };
+            let coin_type = coin_type.to_string();
+            let balance = balances
+                .entry(coin_type.clone())
+                .or_insert_with(|| Balance::zero(coin_type));
+            balance.coin_object_count += 1;
+            balance.total_balance += object.get_coin_value_unsafe() as u128;
+        }
+        Ok(balances.into_values().collect())
+    }
+
+    async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
+        let coin_struct = parse_to_struct_tag(&coin_type)?;
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        find_object_by_type(simulacrum.store(), CoinMetadata::type_(coin_struct))
+            .map(|object| SuiCoinMetadata::try_from(object.clone()))
+            .transpose()
+            .map_err(|e| Error::from(e).into())
+    }
+
+    async fn get_total_supply(&self, coin_type: String) -> RpcResult<Supply> {
+        let coin_struct = parse_to_struct_tag(&coin_type)?;
+        if GAS::is_gas(&coin_struct) {
+            return Ok(Supply {
+                value: TOTAL_SUPPLY_SUI,
+            });
+        }
+
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let object = find_object_by_type(simulacrum.store(), TreasuryCap::type_(coin_struct))
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!(
+                    "Cannot find TreasuryCap for coin type {coin_type}"
+                ))
+            })?;
+        let treasury_cap =
+            TreasuryCap::from_bcs_bytes(object.data.try_as_move().unwrap().contents())
+                .map_err(Error::from)?;
+        Ok(treasury_cap.total_supply)
+    }
+}
+
+impl<R: Send + Sync + 'static> SuiRpcModule for CoinReadApi<R> {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::CoinReadApiOpenRpc::module_doc()
+    }
+}
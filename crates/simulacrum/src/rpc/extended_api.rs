@@ -0,0 +1,178 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use sui_json_rpc::api::{validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS};
+use sui_json_rpc::error::SuiRpcInputError;
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_types::{
+    AddressMetrics, CheckpointedObjectID, EpochInfo, EpochPage, MoveCallMetrics, NetworkMetrics,
+    Page, QueryObjectsPage, SuiObjectResponseQuery,
+};
+use sui_open_rpc::Module;
+use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+
+use super::{method_not_found, read_simulacrum, SharedSimulacrum};
+use crate::Simulacrum;
+
+pub struct ExtendedApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> ExtendedApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+/// Summarize every epoch that has checkpoints in the store, in ascending order.
+///
+/// Validators and the reference gas price are only known for the current epoch, and end of epoch
+/// info isn't tracked, so they are left empty for past epochs.
+fn epochs<R>(simulacrum: &Simulacrum<R>) -> Vec<EpochInfo> {
+    let store = simulacrum.store();
+    let Some(highest) = store.get_highest_checkpint().map(|c| c.sequence_number) else {
+        return vec![];
+    };
+
+    let mut epochs: Vec<EpochInfo> = vec![];
+    let mut previous_timestamp_ms = None;
+    for sequence_number in 0..=highest {
+        // A forked chain doesn't have the checkpoints from before the fork.
+        let Some(checkpoint) = store.get_checkpoint_by_sequence_number(sequence_number) else {
+            continue;
+        };
+        let transactions = store
+            .get_checkpoint_contents(&checkpoint.content_digest)
+            .map_or(0, |contents| contents.size() as u64);
+
+        match epochs.last_mut() {
+            Some(epoch) if epoch.epoch == checkpoint.epoch => {
+                epoch.epoch_total_transactions += transactions
+            }
+            _ => epochs.push(EpochInfo {
+                epoch: checkpoint.epoch,
+                validators: vec![],
+                epoch_total_transactions: transactions,
+                first_checkpoint_id: sequence_number,
+                // Epochs start at the timestamp of the last checkpoint of the previous epoch.
+                epoch_start_timestamp: previous_timestamp_ms.unwrap_or(checkpoint.timestamp_ms),
+                end_of_epoch_info: None,
+                reference_gas_price: None,
+            }),
+        }
+        previous_timestamp_ms = Some(checkpoint.timestamp_ms);
+    }
+
+    // The current epoch has no checkpoints yet right after an epoch change.
+    let system_state = store.get_system_state();
+    if epochs.last().map(|epoch| epoch.epoch) != Some(system_state.epoch()) {
+        epochs.push(EpochInfo {
+            epoch: system_state.epoch(),
+            validators: vec![],
+            epoch_total_transactions: 0,
+            first_checkpoint_id: highest + 1,
+            epoch_start_timestamp: 0,
+            end_of_epoch_info: None,
+            reference_gas_price: None,
+        });
+    }
+
+    let current = epochs.last_mut().unwrap();
+    current.epoch_start_timestamp = system_state.epoch_start_timestamp_ms();
+    current.reference_gas_price = Some(simulacrum.reference_gas_price());
+    current.validators = system_state
+        .into_sui_system_state_summary()
+        .active_validators;
+    epochs
+}
+
+#[async_trait]
+impl<R: Send + Sync + 'static> ExtendedApiServer for ExtendedApi<R> {
+    async fn get_epochs(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EpochPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)
+            .map_err(SuiRpcInputError::from)?;
+        let mut epochs = epochs(&read_simulacrum(&self.simulacrum)?);
+
+        // The cursor is exclusive.
+        if descending_order.unwrap_or(false) {
+            epochs.reverse();
+            epochs.retain(|epoch| cursor.map_or(true, |cursor| epoch.epoch < *cursor));
+        } else {
+            epochs.retain(|epoch| cursor.map_or(true, |cursor| epoch.epoch > *cursor));
+        }
+
+        let has_next_page = epochs.len() > limit;
+        epochs.truncate(limit);
+        let next_cursor = epochs.last().map(|epoch| epoch.epoch.into());
+        Ok(Page {
+            data: epochs,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn get_current_epoch(&self) -> RpcResult<EpochInfo> {
+        let epoch = epochs(&read_simulacrum(&self.simulacrum)?).pop();
+        Ok(epoch.ok_or_else(|| SuiRpcInputError::GenericNotFound("No epochs".to_string()))?)
+    }
+
+    async fn query_objects(
+        &self,
+        _query: SuiObjectResponseQuery,
+        _cursor: Option<CheckpointedObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<QueryObjectsPage> {
+        method_not_found()
+    }
+
+    async fn get_network_metrics(&self) -> RpcResult<NetworkMetrics> {
+        method_not_found()
+    }
+
+    async fn get_move_call_metrics(&self) -> RpcResult<MoveCallMetrics> {
+        method_not_found()
+    }
+
+    async fn get_latest_address_metrics(&self) -> RpcResult<AddressMetrics> {
+        method_not_found()
+    }
+
+    async fn get_checkpoint_address_metrics(&self, _checkpoint: u64) -> RpcResult<AddressMetrics> {
+        method_not_found()
+    }
+
+    async fn get_all_epoch_address_metrics(
+        &self,
+        _descending_order: Option<bool>,
+    ) -> RpcResult<Vec<AddressMetrics>> {
+        method_not_found()
+    }
+
+    async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let total = simulacrum
+            .store()
+            .get_highest_checkpint()
+            .map_or(0, |checkpoint| checkpoint.network_total_transactions);
+        Ok(total.into())
+    }
+}
+
+impl<R: Send + Sync + 'static> SuiRpcModule for ExtendedApi<R> {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::ExtendedApiOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cmp::max;
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use sui_json_rpc::api::GovernanceReadApiServer;
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::governance_api::{calculate_apys, ValidatorExchangeRates};
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_types::{DelegatedStake, Stake, StakeStatus, SuiCommittee, ValidatorApys};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::committee::EpochId;
+use sui_types::dynamic_field::get_dynamic_field_from_store;
+use sui_types::error::UserInputError;
+use sui_types::governance::StakedSui;
+use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::sui_system_state::{PoolTokenExchangeRate, SuiSystemStateTrait};
+
+use super::{read_simulacrum, SharedSimulacrum};
+use crate::InMemoryStore;
+
+pub struct GovernanceReadApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> GovernanceReadApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+/// The exchange rates of the active validators' staking pools, in descending order by epoch.
+///
+/// Unlike a fullnode, the rates of inactive pools are not included, so stakes in them can't be
+/// reported.
+fn exchange_rates(
+    store: &InMemoryStore,
+    system_state_summary: &SuiSystemStateSummary,
+) -> Vec<ValidatorExchangeRates> {
+    system_state_summary
+        .active_validators
+        .iter()
+        .map(|validator| {
+            let rates = (0..=system_state_summary.epoch)
+                .rev()
+                .filter_map(|epoch| {
+                    let rate: PoolTokenExchangeRate =
+                        get_dynamic_field_from_store(store, validator.exchange_rates_id, &epoch)
+                            .ok()?;
+                    Some((epoch, rate))
+                })
+                .collect();
+            ValidatorExchangeRates {
+                address: validator.sui_address,
+                pool_id: validator.staking_pool_id,
+                active: true,
+                rates,
+            }
+        })
+        .collect()
+}
+
+/// Group `stakes` by pool and estimate their rewards. Each stake is paired with whether it is
+/// still live, i.e. hasn't been withdrawn.
+fn delegated_stakes(
+    store: &InMemoryStore,
+    stakes: Vec<(StakedSui, bool)>,
+) -> Result<Vec<DelegatedStake>, Error> {
+    let pools = stakes.into_iter().fold(
+        BTreeMap::<_, Vec<_>>::new(),
+        |mut pools, (stake, exists)| {
+            pools
+                .entry(stake.pool_id())
+                .or_default()
+                .push((stake, exists));
+            pools
+        },
+    );
+
+    let system_state_summary = store.get_system_state().into_sui_system_state_summary();
+    let rates = exchange_rates(store, &system_state_summary)
+        .into_iter()
+        .map(|rates| (rates.pool_id, rates))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut delegated_stakes = vec![];
+    for (pool_id, stakes) in pools {
+        let rate_table = rates.get(&pool_id).ok_or_else(|| {
+            SuiRpcInputError::GenericNotFound(format!(
+                "Cannot find rates for staking pool {pool_id}"
+            ))
+        })?;
+        let current_rate = rate_table.rates.first().map(|(_, rate)| rate);
+
+        let mut delegations = vec![];
+        for (stake, exists) in stakes {
+            let status = if !exists {
+                StakeStatus::Unstaked
+            } else if system_state_summary.epoch >= stake.activation_epoch() {
+                let estimated_reward = if let Some(current_rate) = current_rate {
+                    let stake_rate = rate_table
+                        .rates
+                        .iter()
+                        .find_map(|(epoch, rate)| {
+                            (*epoch == stake.activation_epoch()).then(|| rate.clone())
+                        })
+                        .unwrap_or_default();
+                    let estimated_reward = ((stake_rate.rate() / current_rate.rate()) - 1.0)
+                        * stake.principal() as f64;
+                    max(0, estimated_reward.round() as u64)
+                } else {
+                    0
+                };
+                StakeStatus::Active { estimated_reward }
+            } else {
+                StakeStatus::Pending
+            };
+            delegations.push(Stake {
+                staked_sui_id: stake.id(),
+                stake_request_epoch: stake.activation_epoch() - 1,
+                stake_active_epoch: stake.activation_epoch(),
+                principal: stake.principal(),
+                status,
+            })
+        }
+        delegated_stakes.push(DelegatedStake {
+            validator_address: rate_table.address,
+            staking_pool: pool_id,
+            stakes: delegations,
+        })
+    }
+    Ok(delegated_stakes)
+}
+
+#[async_trait]
+impl<R: Send + Sync + 'static> GovernanceReadApiServer for GovernanceReadApi<R> {
+    async fn get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStake>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let mut stakes = vec![];
+        for id in staked_sui_ids {
+            let stake = match store.get_object(&id) {
+                Some(object) => (StakedSui::try_from(object).map_err(Error::from)?, true),
+                // A withdrawn stake is reported with the last version it had before.
+                None => match store.find_object_lt_or_eq_version(&id, SequenceNumber::MAX) {
+                    Some(object) => (StakedSui::try_from(object).map_err(Error::from)?, false),
+                    None => Err(SuiRpcInputError::UserInputError(
+                        UserInputError::ObjectNotFound {
+                            object_id: id,
+                            version: None,
+                        },
+                    ))?,
+                },
+            };
+            stakes.push(stake);
+        }
+        Ok(delegated_stakes(store, stakes)?)
+    }
+
+    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let stakes = store
+            .owned_objects(owner)
+            .filter(|object| object.type_().map_or(false, |t| t.is_staked_sui()))
+            .map(|object| Ok((StakedSui::try_from(object)?, true)))
+            .collect::<Result<_, Error>>()?;
+        Ok(delegated_stakes(store, stakes)?)
+    }
+
+    async fn get_committee_info(&self, epoch: Option<BigInt<u64>>) -> RpcResult<SuiCommittee> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let epoch: EpochId = match epoch {
+            Some(epoch) => *epoch,
+            None => store.get_system_state().epoch(),
+        };
+        let committee = store.get_committee_by_epoch(epoch).ok_or_else(|| {
+            SuiRpcInputError::GenericNotFound(format!("Committee for epoch {epoch} not found"))
+        })?;
+        Ok(committee.clone().into())
+    }
+
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        Ok(simulacrum
+            .store()
+            .get_system_state()
+            .into_sui_system_state_summary())
+    }
+
+    async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        Ok(simulacrum.reference_gas_price().into())
+    }
+
+    async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let system_state_summary = store.get_system_state().into_sui_system_state_summary();
+        let apys = calculate_apys(
+            system_state_summary.stake_subsidy_start_epoch,
+            exchange_rates(store, &system_state_summary),
+        );
+        Ok(ValidatorApys {
+            apys,
+            epoch: system_state_summary.epoch,
+        })
+    }
+}
+
+impl<R: Send + Sync + 'static> SuiRpcModule for GovernanceReadApi<R> {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::GovernanceReadApiOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A JSON-RPC frontend for a [`Simulacrum`].
+//!
+//! Serves the read, write, coin, governance and extended namespaces of the fullnode JSON-RPC API
+//! on top of a shared [`Simulacrum`], so that regular SDK clients can be pointed at it. Time,
+//! epochs and checkpoints still only advance when asked to, through the test-only methods of the
+//! `simulacrum` namespace (see [`SimulacrumApiServer`]).
+//!
+//! Methods that require indexes or metrics which the Simulacrum doesn't keep return a
+//! `MethodNotFound` error.
+//!
+//! [`Simulacrum`]: crate::Simulacrum
+
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use async_trait::async_trait;
+use prometheus::Registry;
+use rand::rngs::OsRng;
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::{
+    get_balance_changes_from_effect, get_object_changes, JsonRpcServerBuilder, ObjectProvider,
+    ServerHandle, ServerType,
+};
+use sui_json_rpc_types::{
+    SuiTransactionBlock, SuiTransactionBlockEvents, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    digests::TransactionDigest,
+    effects::TransactionEffectsAPI,
+    error::{SuiError, UserInputError},
+    object::Object,
+    transaction::TransactionDataAPI,
+};
+
+use crate::Simulacrum;
+
+pub use self::coin_api::CoinReadApi;
+pub use self::extended_api::ExtendedApi;
+pub use self::governance_api::GovernanceReadApi;
+pub use self::read_api::ReadApi;
+pub use self::simulacrum_api::{
+    SimulacrumApi, SimulacrumApiClient, SimulacrumApiOpenRpc, SimulacrumApiServer,
+};
+pub use self::write_api::WriteApi;
+
+mod coin_api;
+mod extended_api;
+mod governance_api;
+mod read_api;
+mod simulacrum_api;
+mod write_api;
+
+/// A [`Simulacrum`] shared between the handlers of the JSON-RPC server.
+pub type SharedSimulacrum<R = OsRng> = Arc<RwLock<Simulacrum<R>>>;
+
+/// Start a JSON-RPC server for `simulacrum` listening on `listen_address`.
+pub async fn start_rpc_server<R>(
+    simulacrum: SharedSimulacrum<R>,
+    listen_address: SocketAddr,
+    prometheus_registry: &Registry,
+) -> Result<ServerHandle, Error>
+where
+    R: rand::RngCore + rand::CryptoRng + Send + Sync + 'static,
+{
+    let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+    server.register_module(ReadApi::new(simulacrum.clone()))?;
+    server.register_module(WriteApi::new(simulacrum.clone()))?;
+    server.register_module(CoinReadApi::new(simulacrum.clone()))?;
+    server.register_module(GovernanceReadApi::new(simulacrum.clone()))?;
+    server.register_module(ExtendedApi::new(simulacrum.clone()))?;
+    server.register_module(SimulacrumApi::new(simulacrum))?;
+    server
+        .start(listen_address, None, Some(ServerType::Http))
+        .await
+}
+
+/// Lock `simulacrum` for reading. A handler panicking while holding the lock poisons it, as it may
+/// have left the simulacrum half way through an update, so requests fail from then on.
+fn read_simulacrum<R>(
+    simulacrum: &SharedSimulacrum<R>,
+) -> Result<RwLockReadGuard<'_, Simulacrum<R>>, Error> {
+    simulacrum.read().map_err(|_| poisoned())
+}
+
+/// Lock `simulacrum` for writing, see [`read_simulacrum`].
+fn write_simulacrum<R>(
+    simulacrum: &SharedSimulacrum<R>,
+) -> Result<RwLockWriteGuard<'_, Simulacrum<R>>, Error> {
+    simulacrum.write().map_err(|_| poisoned())
+}
+
+fn poisoned() -> Error {
+    Error::UnexpectedError("The simulacrum is poisoned by a failed request".to_string())
+}
+
+/// Failures to run a transaction are caused by its inputs (e.g. missing objects or invalid
+/// signatures), so they are reported as invalid params.
+fn transaction_error(error: anyhow::Error) -> Error {
+    SuiRpcInputError::from(error).into()
+}
+
+fn method_not_found<T>() -> jsonrpsee::core::RpcResult<T> {
+    Err(jsonrpsee::types::error::CallError::Custom(
+        jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+    )
+    .into())
+}
+
+/// Build the response for an executed transaction, including only the fields requested by
+/// `options`.
+async fn transaction_block_response<R: Send + Sync>(
+    simulacrum: &SharedSimulacrum<R>,
+    digest: TransactionDigest,
+    options: SuiTransactionBlockResponseOptions,
+) -> Result<SuiTransactionBlockResponse, Error> {
+    let (mut response, transaction, effects) = {
+        let simulacrum = read_simulacrum(simulacrum)?;
+        let store = simulacrum.store();
+        let transaction = store
+            .get_transaction(&digest)
+            .ok_or(SuiError::TransactionNotFound { digest })?
+            .clone();
+        let effects = store
+            .get_transaction_effects(&digest)
+            .ok_or(SuiError::TransactionNotFound { digest })?
+            .clone();
+        let checkpoint = store.get_transaction_checkpoint(&digest);
+        let timestamp_ms = checkpoint.map(|checkpoint| checkpoint.timestamp_ms);
+
+        let mut response = SuiTransactionBlockResponse::new(digest);
+        response.checkpoint = checkpoint.map(|checkpoint| checkpoint.sequence_number);
+        response.timestamp_ms = timestamp_ms;
+        if options.show_input {
+            response.transaction = Some(SuiTransactionBlock::try_from(
+                transaction.data().clone(),
+                store,
+            )?);
+        }
+        if options.show_raw_input {
+            response.raw_transaction = bcs::to_bytes(transaction.data())?;
+        }
+        if options.show_effects {
+            response.effects = Some(effects.clone().try_into()?);
+        }
+        if options.show_events {
+            let events = effects
+                .events_digest()
+                .and_then(|events_digest| store.get_transaction_events(events_digest))
+                .cloned()
+                .unwrap_or_default();
+            response.events = Some(SuiTransactionBlockEvents::try_from(
+                events,
+                digest,
+                timestamp_ms,
+                store,
+            )?);
+        }
+        (response, transaction, effects)
+    };
+
+    let object_provider = SimulacrumObjectProvider(simulacrum.clone());
+    let transaction_data = &transaction.data().intent_message().value;
+    if options.show_balance_changes {
+        let input_objects = transaction_data.input_objects()?;
+        response.balance_changes = Some(
+            get_balance_changes_from_effect(&object_provider, &effects, input_objects, None)
+                .await?,
+        );
+    }
+    if options.show_object_changes {
+        response.object_changes = Some(
+            get_object_changes(
+                &object_provider,
+                transaction_data.sender(),
+                effects.modified_at_versions(),
+                effects.all_changed_objects(),
+                effects.all_removed_objects(),
+            )
+            .await?,
+        );
+    }
+    Ok(response)
+}
+
+/// Serves the historical objects needed to compute balance and object changes.
+struct SimulacrumObjectProvider<R>(SharedSimulacrum<R>);
+
+#[async_trait]
+impl<R: Send + Sync> ObjectProvider for SimulacrumObjectProvider<R> {
+    type Error = Error;
+
+    async fn get_object(&self, id: &ObjectID, version: &SequenceNumber) -> Result<Object, Error> {
+        read_simulacrum(&self.0)?
+            .store()
+            .get_or_fetch_object_at_version(id, *version)?
+            .ok_or_else(|| {
+                UserInputError::ObjectNotFound {
+                    object_id: *id,
+                    version: Some(*version),
+                }
+                .into()
+            })
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<Object>, Error> {
+        Ok(read_simulacrum(&self.0)?
+            .store()
+            .find_object_lt_or_eq_version(id, *version)
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use sui_json_rpc::api::{CoinReadApiServer, ExtendedApiServer, ReadApiServer};
+    use sui_json_rpc_types::{CheckpointId, SuiTransactionBlockEffectsAPI};
+    use sui_types::base_types::SuiAddress;
+    use sui_types::gas_coin::MIST_PER_SUI;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn request_gas_and_checkpoint() {
+        let mut rng = StdRng::from_seed([9; 32]);
+        let address = SuiAddress::generate(&mut rng);
+        let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(rng)));
+
+        let simulacrum_api = SimulacrumApi::new(simulacrum.clone());
+        let read_api = ReadApi::new(simulacrum.clone());
+        let coin_api = CoinReadApi::new(simulacrum.clone());
+        let extended_api = ExtendedApi::new(simulacrum);
+
+        let effects = simulacrum_api
+            .request_gas(address, MIST_PER_SUI.into())
+            .await
+            .unwrap();
+        let balance = coin_api.get_balance(address, None).await.unwrap();
+        assert_eq!(balance.total_balance, MIST_PER_SUI as u128);
+        assert_eq!(balance.coin_object_count, 1);
+
+        // The transaction is only included in a checkpoint once one is created.
+        let response = read_api
+            .get_transaction_block(*effects.transaction_digest(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.checkpoint, None);
+
+        let checkpoint = simulacrum_api.create_checkpoint().await.unwrap();
+        assert_eq!(checkpoint.transactions, vec![*effects.transaction_digest()]);
+        let fetched = read_api
+            .get_checkpoint(CheckpointId::SequenceNumber(checkpoint.sequence_number))
+            .await
+            .unwrap();
+        assert_eq!(fetched.digest, checkpoint.digest);
+
+        let response = read_api
+            .get_transaction_block(*effects.transaction_digest(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.checkpoint, Some(checkpoint.sequence_number));
+
+        simulacrum_api.advance_epoch().await.unwrap();
+        let epoch = extended_api.get_current_epoch().await.unwrap();
+        assert_eq!(epoch.epoch, 1);
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use sui_json_rpc::api::{
+    validate_limit, ReadApiServer, QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+};
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, CheckpointPage, ProtocolConfigResponse, SuiEvent,
+    SuiGetPastObjectRequest, SuiLoadedChildObjectsResponse, SuiObjectData, SuiObjectDataOptions,
+    SuiObjectResponse, SuiPastObjectResponse, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::{ChainIdentifier, TransactionDigest};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::error::{SuiError, SuiObjectResponseError};
+use sui_types::messages_checkpoint::VerifiedCheckpoint;
+use sui_types::object::{Object, ObjectFormatOptions};
+use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+
+use super::{method_not_found, read_simulacrum, transaction_block_response, SharedSimulacrum};
+use crate::InMemoryStore;
+
+pub struct ReadApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> ReadApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+/// Convert `object` into its JSON-RPC representation, resolving its layout from `store`.
+pub(super) fn object_data(
+    store: &InMemoryStore,
+    object: Object,
+    options: SuiObjectDataOptions,
+) -> Result<SuiObjectData, Error> {
+    let layout = object
+        .data
+        .try_as_move()
+        .map(|object| object.get_layout(ObjectFormatOptions::default(), store))
+        .transpose()?;
+    Ok((object.compute_object_reference(), object, layout, options).try_into()?)
+}
+
+pub(super) fn checkpoint_response(
+    store: &InMemoryStore,
+    checkpoint: &VerifiedCheckpoint,
+) -> Result<Checkpoint, Error> {
+    let contents = store
+        .get_checkpoint_contents(&checkpoint.content_digest)
+        .ok_or_else(|| {
+            SuiRpcInputError::GenericNotFound(format!(
+                "Contents of checkpoint {} not found",
+                checkpoint.sequence_number
+            ))
+        })?;
+    let signature = checkpoint.auth_sig().signature.clone();
+    Ok((checkpoint.data().clone(), contents.clone(), signature).into())
+}
+
+#[async_trait]
+impl<R: Send + Sync + 'static> ReadApiServer for ReadApi<R> {
+    async fn get_transaction_block(
+        &self,
+        digest: TransactionDigest,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        Ok(
+            transaction_block_response(&self.simulacrum, digest, options.unwrap_or_default())
+                .await?,
+        )
+    }
+
+    async fn multi_get_transaction_blocks(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionBlockResponse>> {
+        if digests.len() > *QUERY_MAX_RESULT_LIMIT {
+            Err(SuiRpcInputError::SizeLimitExceeded(
+                QUERY_MAX_RESULT_LIMIT.to_string(),
+            ))?
+        }
+
+        let options = options.unwrap_or_default();
+        let mut responses = vec![];
+        for digest in digests {
+            responses
+                .push(transaction_block_response(&self.simulacrum, digest, options.clone()).await?);
+        }
+        Ok(responses)
+    }
+
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiObjectResponse> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        // Deleted objects are reported as not existing, as the store doesn't keep tombstones.
+        Ok(
+            match store.get_or_fetch_object(&object_id).map_err(Error::from)? {
+                Some(object) => SuiObjectResponse::new_with_data(object_data(
+                    store,
+                    object,
+                    options.unwrap_or_default(),
+                )?),
+                None => SuiObjectResponse::new_with_error(SuiObjectResponseError::NotExists {
+                    object_id,
+                }),
+            },
+        )
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectResponse>> {
+        if object_ids.len() > *QUERY_MAX_RESULT_LIMIT {
+            Err(SuiRpcInputError::SizeLimitExceeded(
+                QUERY_MAX_RESULT_LIMIT.to_string(),
+            ))?
+        }
+
+        let mut responses = vec![];
+        for object_id in object_ids {
+            responses.push(self.get_object(object_id, options.clone()).await?);
+        }
+        Ok(responses)
+    }
+
+    async fn try_get_past_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        if let Some(object) = store
+            .get_or_fetch_object_at_version(&object_id, version)
+            .map_err(Error::from)?
+        {
+            return Ok(SuiPastObjectResponse::VersionFound(object_data(
+                store,
+                object,
+                options.unwrap_or_default(),
+            )?));
+        }
+
+        let latest_version = match store.get_or_fetch_object(&object_id).map_err(Error::from)? {
+            Some(object) => Some(object.version()),
+            None => store
+                .find_object_lt_or_eq_version(&object_id, SequenceNumber::MAX)
+                .map(|object| object.version()),
+        };
+        Ok(match latest_version {
+            Some(latest_version) if version > latest_version => {
+                SuiPastObjectResponse::VersionTooHigh {
+                    object_id,
+                    asked_version: version,
+                    latest_version,
+                }
+            }
+            Some(_) => SuiPastObjectResponse::VersionNotFound(object_id, version),
+            None => SuiPastObjectResponse::ObjectNotExists(object_id),
+        })
+    }
+
+    async fn try_multi_get_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>> {
+        if past_objects.len() > *QUERY_MAX_RESULT_LIMIT {
+            Err(SuiRpcInputError::SizeLimitExceeded(
+                QUERY_MAX_RESULT_LIMIT.to_string(),
+            ))?
+        }
+
+        let mut responses = vec![];
+        for request in past_objects {
+            responses.push(
+                self.try_get_past_object(request.object_id, request.version, options.clone())
+                    .await?,
+            );
+        }
+        Ok(responses)
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        _digest: TransactionDigest,
+    ) -> RpcResult<SuiLoadedChildObjectsResponse> {
+        method_not_found()
+    }
+
+    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let checkpoint = match id {
+            CheckpointId::SequenceNumber(sequence_number) => {
+                store.get_checkpoint_by_sequence_number(sequence_number)
+            }
+            CheckpointId::Digest(digest) => store.get_checkpoint_by_digest(&digest),
+        }
+        .ok_or_else(|| SuiRpcInputError::GenericNotFound(format!("Checkpoint {id:?} not found")))?;
+        Ok(checkpoint_response(store, checkpoint)?)
+    }
+
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)
+            .map_err(SuiRpcInputError::from)?;
+
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let Some(highest) = store.get_highest_checkpint().map(|c| c.sequence_number) else {
+            return Ok(CheckpointPage::empty());
+        };
+
+        // The cursor is exclusive.
+        let sequence_numbers: Box<dyn Iterator<Item = u64>> = match (cursor, descending_order) {
+            (Some(cursor), true) => Box::new((0..*cursor).rev()),
+            (None, true) => Box::new((0..=highest).rev()),
+            (Some(cursor), false) => Box::new(cursor.saturating_add(1)..=highest),
+            (None, false) => Box::new(0..=highest),
+        };
+
+        let mut checkpoints = vec![];
+        for sequence_number in sequence_numbers {
+            // A forked chain doesn't have the checkpoints from before the fork.
+            let Some(checkpoint) = store.get_checkpoint_by_sequence_number(sequence_number) else {
+                continue;
+            };
+            if checkpoints.len() == limit + 1 {
+                break;
+            }
+            checkpoints.push(checkpoint_response(store, checkpoint)?);
+        }
+
+        let has_next_page = checkpoints.len() > limit;
+        checkpoints.truncate(limit);
+        let next_cursor = checkpoints.last().map(|c| c.sequence_number.into());
+        Ok(CheckpointPage {
+            data: checkpoints,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn get_checkpoints_deprecated_limit(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<BigInt<u64>>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        self.get_checkpoints(
+            cursor,
+            limit.map(|l| l.into_inner() as usize),
+            descending_order,
+        )
+        .await
+    }
+
+    async fn get_events(&self, transaction_digest: TransactionDigest) -> RpcResult<Vec<SuiEvent>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let store = simulacrum.store();
+        let effects = store
+            .get_transaction_effects(&transaction_digest)
+            .ok_or(SuiError::TransactionNotFound {
+                digest: transaction_digest,
+            })
+            .map_err(Error::from)?;
+        let Some(events) = effects
+            .events_digest()
+            .and_then(|events_digest| store.get_transaction_events(events_digest))
+        else {
+            return Ok(vec![]);
+        };
+        let events =
+            SuiTransactionBlockEvents::try_from(events.clone(), transaction_digest, None, store)
+                .map_err(Error::from)?;
+        Ok(events.data)
+    }
+
+    async fn get_total_transaction_blocks(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let total = simulacrum
+            .store()
+            .get_highest_checkpint()
+            .map_or(0, |checkpoint| checkpoint.network_total_transactions);
+        Ok(total.into())
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let sequence_number = simulacrum
+            .store()
+            .get_highest_checkpint()
+            .map_or(0, |checkpoint| checkpoint.sequence_number);
+        Ok(sequence_number.into())
+    }
+
+    async fn get_protocol_config(
+        &self,
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<ProtocolConfigResponse> {
+        let version = match version {
+            Some(version) => (*version).into(),
+            None => {
+                let simulacrum = read_simulacrum(&self.simulacrum)?;
+                simulacrum
+                    .store()
+                    .get_system_state()
+                    .protocol_version()
+                    .into()
+            }
+        };
+
+        ProtocolConfig::get_for_version_if_supported(version, Chain::Unknown)
+            .ok_or(SuiRpcInputError::ProtocolVersionUnsupported(
+                ProtocolVersion::MIN.as_u64(),
+                ProtocolVersion::MAX.as_u64(),
+            ))
+            .map_err(Into::into)
+            .map(ProtocolConfigResponse::from)
+    }
+
+    async fn get_chain_identifier(&self) -> RpcResult<String> {
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let genesis_checkpoint = simulacrum
+            .store()
+            .get_checkpoint_by_sequence_number(0)
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound("Genesis checkpoint not found".to_string())
+            })?;
+        Ok(ChainIdentifier::from(*genesis_checkpoint.digest()).to_string())
+    }
+}
+
+impl<R: Send + Sync + 'static> SuiRpcModule for ReadApi<R> {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::ReadApiOpenRpc::module_doc()
+    }
+}
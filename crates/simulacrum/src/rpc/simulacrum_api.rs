@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::RpcModule;
+use rand::{CryptoRng, RngCore};
+use sui_json_rpc::error::Error;
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_types::{Checkpoint, SuiTransactionBlockEffects};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::base_types::SuiAddress;
+use sui_types::sui_serde::BigInt;
+
+use super::read_api::checkpoint_response;
+use super::{transaction_error, write_simulacrum, SharedSimulacrum};
+
+/// Methods to drive a Simulacrum's chain forward, which a real network does on its own.
+#[open_rpc(namespace = "simulacrum", tag = "Simulacrum API")]
+#[rpc(server, client, namespace = "simulacrum")]
+pub trait SimulacrumApi {
+    /// Advance the on-chain clock by `duration_ms`, by executing a consensus commit prologue
+    /// transaction.
+    #[method(name = "advanceClock")]
+    async fn advance_clock(
+        &self,
+        /// the number of milliseconds to advance the clock by
+        duration_ms: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects>;
+
+    /// Advance to the next epoch, by executing an end of epoch transaction and creating the last
+    /// checkpoint of the current epoch.
+    #[method(name = "advanceEpoch")]
+    async fn advance_epoch(&self) -> RpcResult<()>;
+
+    /// Create a checkpoint including the transactions executed since the last one.
+    #[method(name = "createCheckpoint")]
+    async fn create_checkpoint(&self) -> RpcResult<Checkpoint>;
+
+    /// Send `amount` MIST from a faucet account to `address`.
+    #[method(name = "requestGas")]
+    async fn request_gas(
+        &self,
+        /// the address to fund
+        address: SuiAddress,
+        /// the amount of MIST to send
+        amount: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects>;
+}
+
+pub struct SimulacrumApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> SimulacrumApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+#[async_trait]
+impl<R> SimulacrumApiServer for SimulacrumApi<R>
+where
+    R: RngCore + CryptoRng + Send + Sync + 'static,
+{
+    async fn advance_clock(
+        &self,
+        duration_ms: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects> {
+        let effects = self
+            .simulacrum
+            .write()
+            .unwrap()
+            .advance_clock(Duration::from_millis(*duration_ms));
+        Ok(effects.try_into().map_err(Error::from)?)
+    }
+
+    async fn advance_epoch(&self) -> RpcResult<()> {
+        write_simulacrum(&self.simulacrum)?.advance_epoch();
+        Ok(())
+    }
+
+    async fn create_checkpoint(&self) -> RpcResult<Checkpoint> {
+        let mut simulacrum = write_simulacrum(&self.simulacrum)?;
+        let checkpoint = simulacrum.create_checkpoint();
+        Ok(checkpoint_response(simulacrum.store(), &checkpoint)?)
+    }
+
+    async fn request_gas(
+        &self,
+        address: SuiAddress,
+        amount: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects> {
+        let effects = self
+            .simulacrum
+            .write()
+            .unwrap()
+            .request_gas(address, *amount)
+            .map_err(transaction_error)?;
+        Ok(effects.try_into().map_err(Error::from)?)
+    }
+}
+
+impl<R> SuiRpcModule for SimulacrumApi<R>
+where
+    R: RngCore + CryptoRng + Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        SimulacrumApiOpenRpc::module_doc()
+    }
+}
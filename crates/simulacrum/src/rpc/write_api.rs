@@ -0,0 +1,180 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use fastcrypto::traits::ToFromBytes;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use shared_crypto::intent::Intent;
+use sui_json_rpc::api::WriteApiServer;
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::{
+    get_balance_changes_from_effect, get_object_changes, ObjectProviderCache, SuiRpcModule,
+};
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockData,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::default_hash;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::signature::GenericSignature;
+use sui_types::sui_serde::BigInt;
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI, TransactionKind};
+
+use super::{
+    read_simulacrum, transaction_block_response, transaction_error, write_simulacrum,
+    SharedSimulacrum, SimulacrumObjectProvider,
+};
+
+pub struct WriteApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> WriteApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+fn convert_bytes<T: serde::de::DeserializeOwned>(tx_bytes: Base64) -> Result<T, SuiRpcInputError> {
+    Ok(bcs::from_bytes(&tx_bytes.to_vec()?)?)
+}
+
+#[async_trait]
+impl<R: Send + Sync + 'static> WriteApiServer for WriteApi<R> {
+    /// Transactions are executed immediately, regardless of `request_type`, but are only included
+    /// in a checkpoint once one is created through `simulacrum_createCheckpoint`.
+    async fn execute_transaction_block(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+        _request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        let tx_data: TransactionData = convert_bytes(tx_bytes)?;
+        let mut sigs = Vec::new();
+        for sig in signatures {
+            sigs.push(
+                GenericSignature::from_bytes(&sig.to_vec().map_err(SuiRpcInputError::from)?)
+                    .map_err(SuiRpcInputError::from)?,
+            );
+        }
+        let transaction =
+            Transaction::from_generic_sig_data(tx_data, Intent::sui_transaction(), sigs);
+        let digest = *transaction.digest();
+
+        write_simulacrum(&self.simulacrum)?
+            .execute_transaction(transaction)
+            .map_err(transaction_error)?;
+
+        let mut response =
+            transaction_block_response(&self.simulacrum, digest, options.unwrap_or_default())
+                .await?;
+        response.confirmed_local_execution = Some(true);
+        Ok(response)
+    }
+
+    async fn dev_inspect_transaction_block(
+        &self,
+        sender_address: SuiAddress,
+        tx_bytes: Base64,
+        gas_price: Option<BigInt<u64>>,
+        _epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<DevInspectResults> {
+        let kind: TransactionKind = convert_bytes(tx_bytes)?;
+        let simulacrum = read_simulacrum(&self.simulacrum)?;
+        let (inner_temporary_store, effects, execution_result) = simulacrum
+            .dev_inspect_transaction(sender_address, kind, gas_price.map(|price| *price))
+            .map_err(transaction_error)?;
+        Ok(DevInspectResults::new(
+            effects,
+            inner_temporary_store.events,
+            execution_result,
+            simulacrum.store(),
+        )
+        .map_err(Error::from)?)
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<DryRunTransactionBlockResponse> {
+        let tx_data: TransactionData = convert_bytes(tx_bytes)?;
+        let digest = TransactionDigest::new(default_hash(&tx_data));
+        let sender = tx_data.sender();
+        let input_objects = tx_data.input_objects().map_err(Error::from)?;
+
+        let (effects, events, input, written, mock_gas) = {
+            let simulacrum = read_simulacrum(&self.simulacrum)?;
+            let store = simulacrum.store();
+            let (inner_temporary_store, effects, _, mock_gas) = simulacrum
+                .dry_run_transaction(&tx_data, digest)
+                .map_err(transaction_error)?;
+            let events = SuiTransactionBlockEvents::try_from(
+                inner_temporary_store.events,
+                digest,
+                None,
+                store,
+            )
+            .map_err(Error::from)?;
+            let input = SuiTransactionBlockData::try_from(tx_data, store).map_err(Error::from)?;
+            let written = effects
+                .all_changed_objects()
+                .into_iter()
+                .map(|(object_ref, _, kind)| {
+                    let object = inner_temporary_store
+                        .written
+                        .get(&object_ref.0)
+                        .ok_or_else(|| {
+                            Error::UnexpectedError(format!(
+                                "Changed object {} missing from the dry run outputs",
+                                object_ref.0
+                            ))
+                        })?
+                        .clone();
+                    Ok((object_ref.0, (object_ref, object, kind)))
+                })
+                .collect::<Result<_, Error>>()?;
+            (effects, events, input, written, mock_gas)
+        };
+
+        let object_provider = ObjectProviderCache::new_with_cache(
+            SimulacrumObjectProvider(self.simulacrum.clone()),
+            written,
+        );
+        let balance_changes =
+            get_balance_changes_from_effect(&object_provider, &effects, input_objects, mock_gas)
+                .await?;
+        let object_changes = get_object_changes(
+            &object_provider,
+            sender,
+            effects.modified_at_versions(),
+            effects.all_changed_objects(),
+            effects.all_removed_objects(),
+        )
+        .await?;
+
+        Ok(DryRunTransactionBlockResponse {
+            effects: effects.try_into().map_err(Error::from)?,
+            events,
+            object_changes,
+            balance_changes,
+            input,
+        })
+    }
+}
+
+impl<R: Send + Sync + 'static> SuiRpcModule for WriteApi<R> {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::WriteApiOpenRpc::module_doc()
+    }
+}
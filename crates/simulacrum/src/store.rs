@@ -1,16 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::traits::KeyPair;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
 use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
 use sui_config::genesis;
+use sui_swarm_config::genesis_config::AccountConfig;
 use sui_types::storage::{get_module, load_package_object_from_object_store, PackageObjectArc};
 use sui_types::{
     base_types::{AuthorityName, ObjectID, SequenceNumber, SuiAddress},
     committee::{Committee, EpochId},
-    crypto::{AccountKeyPair, AuthorityKeyPair},
+    crypto::{get_key_pair_from_rng, AccountKeyPair, AuthorityKeyPair},
     digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest},
     effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
     error::SuiError,
@@ -25,12 +28,16 @@ use sui_types::{
     transaction::VerifiedTransaction,
 };
 
+use crate::fork::ObjectFetcher;
+
 #[derive(Debug, Default)]
 pub struct InMemoryStore {
     // Checkpoint data
     checkpoints: BTreeMap<CheckpointSequenceNumber, VerifiedCheckpoint>,
     checkpoint_digest_to_sequence_number: HashMap<CheckpointDigest, CheckpointSequenceNumber>,
     checkpoint_contents: HashMap<CheckpointContentsDigest, CheckpointContents>,
+    contents_digest_to_sequence_number: HashMap<CheckpointContentsDigest, CheckpointSequenceNumber>,
+    transaction_to_contents_digest: HashMap<TransactionDigest, CheckpointContentsDigest>,
 
     // Transaction data
     transactions: HashMap<TransactionDigest, VerifiedTransaction>,
@@ -38,11 +45,52 @@ pub struct InMemoryStore {
     events: HashMap<TransactionEventsDigest, TransactionEvents>,
 
     // Committee data
-    epoch_to_committee: Vec<Committee>,
+    epoch_to_committee: BTreeMap<EpochId, Committee>,
 
     // Object data
     live_objects: HashMap<ObjectID, SequenceNumber>,
     objects: HashMap<ObjectID, BTreeMap<SequenceNumber, Object>>,
+
+    // Fallback for objects that have never been seen locally, used by forked chains
+    fetcher: Option<FallbackFetcher>,
+}
+
+/// Caches the objects returned by an [`ObjectFetcher`], keyed by the version they were requested
+/// at (`None` meaning the latest version).
+struct FallbackFetcher {
+    fetcher: Box<dyn ObjectFetcher>,
+    cache: RwLock<HashMap<(ObjectID, Option<SequenceNumber>), Option<Object>>>,
+}
+
+impl std::fmt::Debug for FallbackFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackFetcher")
+            .field("cached", &self.cache.read().unwrap().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FallbackFetcher {
+    fn fetch(
+        &self,
+        id: &ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        if let Some(object) = self.cache.read().unwrap().get(&(*id, version)) {
+            return Ok(object.clone());
+        }
+
+        let object = match version {
+            None => self.fetcher.get_object(id),
+            Some(version) => self.fetcher.get_object_at_version(id, version),
+        }
+        .map_err(|e| SuiError::GenericStorageError(format!("Failed to fetch object {id}: {e}")))?;
+        self.cache
+            .write()
+            .unwrap()
+            .insert((*id, version), object.clone());
+        Ok(object)
+    }
 }
 
 impl InMemoryStore {
@@ -72,6 +120,34 @@ impl InMemoryStore {
         store
     }
 
+    /// Create a store seeded with `objects`, e.g. the live object set of another chain. Objects
+    /// that have never been seen locally are requested from `fetcher`, if provided.
+    pub fn new_forked(
+        objects: impl IntoIterator<Item = Object>,
+        fetcher: Option<Box<dyn ObjectFetcher>>,
+    ) -> Self {
+        let mut store = Self {
+            fetcher: fetcher.map(|fetcher| FallbackFetcher {
+                fetcher,
+                cache: RwLock::new(HashMap::new()),
+            }),
+            ..Self::default()
+        };
+
+        for object in objects {
+            let object_id = object.id();
+            let version = object.version();
+            store.live_objects.insert(object_id, version);
+            store
+                .objects
+                .entry(object_id)
+                .or_default()
+                .insert(version, object);
+        }
+
+        store
+    }
+
     pub fn get_checkpoint_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
@@ -101,8 +177,21 @@ impl InMemoryStore {
         self.checkpoint_contents.get(digest)
     }
 
+    /// The checkpoint that includes the transaction with `digest`, if it is included in one yet.
+    pub fn get_transaction_checkpoint(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Option<&VerifiedCheckpoint> {
+        self.transaction_to_contents_digest
+            .get(digest)
+            .and_then(|contents_digest| {
+                self.contents_digest_to_sequence_number.get(contents_digest)
+            })
+            .and_then(|sequence_number| self.get_checkpoint_by_sequence_number(*sequence_number))
+    }
+
     pub fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee> {
-        self.epoch_to_committee.get(epoch as usize)
+        self.epoch_to_committee.get(&epoch)
     }
 
     pub fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction> {
@@ -134,24 +223,71 @@ impl InMemoryStore {
             .and_then(|versions| versions.get(&version))
     }
 
+    /// Like `get_object`, but falls back to the store's [`ObjectFetcher`] for objects that have
+    /// never been seen locally. Objects deleted locally are never fetched.
+    pub fn get_or_fetch_object(
+        &self,
+        id: &ObjectID,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        if self.objects.contains_key(id) {
+            return Ok(self.get_object(id).cloned());
+        }
+        match &self.fetcher {
+            Some(fetcher) => fetcher.fetch(id, None),
+            None => Ok(None),
+        }
+    }
+
+    /// Like `get_object_at_version`, but falls back to the store's [`ObjectFetcher`] for versions
+    /// that are not available locally.
+    pub fn get_or_fetch_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        if let Some(object) = self.get_object_at_version(id, version) {
+            return Ok(Some(object.clone()));
+        }
+        match &self.fetcher {
+            Some(fetcher) => fetcher.fetch(id, Some(version)),
+            None => Ok(None),
+        }
+    }
+
     pub fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
         sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
     }
 
     pub fn get_clock(&self) -> sui_types::clock::Clock {
-        self.get_object(&sui_types::SUI_CLOCK_OBJECT_ID)
+        self.get_or_fetch_object(&sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should be readable")
             .expect("clock should exist")
             .to_rust()
             .expect("clock object should deserialize")
     }
 
-    pub fn owned_objects(&self, owner: SuiAddress) -> impl Iterator<Item = &Object> {
+    /// Returns the latest version of the object with `id` that is at most `version`.
+    pub fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Option<&Object> {
+        self.objects
+            .get(id)
+            .and_then(|versions| versions.range(..=version).next_back())
+            .map(|(_, object)| object)
+    }
+
+    pub fn live_objects(&self) -> impl Iterator<Item = &Object> {
         self.live_objects
             .iter()
             .flat_map(|(id, version)| self.get_object_at_version(id, *version))
-            .filter(
-                move |object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner),
-            )
+    }
+
+    pub fn owned_objects(&self, owner: SuiAddress) -> impl Iterator<Item = &Object> {
+        self.live_objects().filter(
+            move |object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner),
+        )
     }
 }
 
@@ -169,24 +305,36 @@ impl InMemoryStore {
 
         self.checkpoint_digest_to_sequence_number
             .insert(*checkpoint.digest(), *checkpoint.sequence_number());
+        self.contents_digest_to_sequence_number
+            .insert(checkpoint.content_digest, *checkpoint.sequence_number());
         self.checkpoints
             .insert(*checkpoint.sequence_number(), checkpoint);
     }
 
     pub fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        for execution_digests in contents.iter() {
+            self.transaction_to_contents_digest
+                .insert(execution_digests.transaction, *contents.digest());
+        }
         self.checkpoint_contents
             .insert(*contents.digest(), contents);
     }
 
     pub fn insert_committee(&mut self, committee: Committee) {
-        let epoch = committee.epoch as usize;
+        let epoch = committee.epoch;
 
-        if self.epoch_to_committee.get(epoch).is_some() {
+        if self.epoch_to_committee.get(&epoch).is_some() {
             return;
         }
 
-        if self.epoch_to_committee.len() == epoch {
-            self.epoch_to_committee.push(committee);
+        // A forked store starts from the committee of the epoch it was forked at, rather than
+        // from the genesis committee.
+        let next_epoch = self
+            .epoch_to_committee
+            .last_key_value()
+            .map(|(last_epoch, _)| last_epoch + 1);
+        if next_epoch.map_or(true, |next_epoch| next_epoch == epoch) {
+            self.epoch_to_committee.insert(epoch, committee);
         } else {
             panic!("committee was inserted into EpochCommitteeMap out of order");
         }
@@ -199,7 +347,13 @@ impl InMemoryStore {
         events: TransactionEvents,
         written_objects: BTreeMap<ObjectID, Object>,
     ) {
-        let deleted_objects = effects.deleted();
+        // Wrapped objects are no longer live either, and must not be fetched again by a forked
+        // store.
+        let deleted_objects = effects
+            .deleted()
+            .into_iter()
+            .chain(effects.wrapped())
+            .collect();
         self.insert_transaction(transaction);
         self.insert_transaction_effects(effects);
         self.insert_events(events);
@@ -225,6 +379,8 @@ impl InMemoryStore {
     ) {
         for (object_id, _, _) in deleted_objects {
             self.live_objects.remove(&object_id);
+            // Remember the object, so that it is not requested from the fetcher again
+            self.objects.entry(object_id).or_default();
         }
 
         for (object_id, object) in written_objects {
@@ -254,7 +410,7 @@ impl ChildObjectResolver for InMemoryStore {
         child: &ObjectID,
         child_version_upper_bound: SequenceNumber,
     ) -> sui_types::error::SuiResult<Option<Object>> {
-        let child_object = match self.get_object(child).cloned() {
+        let child_object = match self.get_or_fetch_object(child)? {
             None => return Ok(None),
             Some(obj) => obj,
         };
@@ -285,7 +441,7 @@ impl ChildObjectResolver for InMemoryStore {
         receive_object_at_version: SequenceNumber,
         _epoch_id: EpochId,
     ) -> sui_types::error::SuiResult<Option<Object>> {
-        let recv_object = match self.get_object(receiving_object_id).cloned() {
+        let recv_object = match self.get_or_fetch_object(receiving_object_id)? {
             None => return Ok(None),
             Some(obj) => obj,
         };
@@ -355,7 +511,7 @@ impl ObjectStore for InMemoryStore {
         &self,
         object_id: &ObjectID,
     ) -> Result<Option<Object>, sui_types::error::SuiError> {
-        Ok(self.get_object(object_id).cloned())
+        self.get_or_fetch_object(object_id)
     }
 
     fn get_object_by_key(
@@ -363,7 +519,7 @@ impl ObjectStore for InMemoryStore {
         object_id: &ObjectID,
         version: sui_types::base_types::VersionNumber,
     ) -> Result<Option<Object>, sui_types::error::SuiError> {
-        Ok(self.get_object_at_version(object_id, version).cloned())
+        self.get_or_fetch_object_at_version(object_id, version)
     }
 }
 
@@ -387,8 +543,6 @@ impl KeyStore {
     pub fn from_network_config(
         network_config: &sui_swarm_config::network_config::NetworkConfig,
     ) -> Self {
-        use fastcrypto::traits::KeyPair;
-
         let validator_keys = network_config
             .validator_configs()
             .iter()
@@ -411,6 +565,35 @@ impl KeyStore {
         }
     }
 
+    /// Create a KeyStore with a single, freshly generated validator and one account per entry of
+    /// `accounts`, for chains that don't have access to the keys of their committee, e.g. chains
+    /// forked from another network.
+    pub fn new_with_rng<R>(rng: &mut R, accounts: &[AccountConfig]) -> Self
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let (_, validator_key): (_, AuthorityKeyPair) = get_key_pair_from_rng(rng);
+        let validator_keys = BTreeMap::from([(validator_key.public().into(), validator_key)]);
+
+        let account_keys = accounts
+            .iter()
+            .filter(|account| account.address.is_none())
+            .map(|_| get_key_pair_from_rng(rng))
+            .collect();
+        Self {
+            validator_keys,
+            account_keys,
+        }
+    }
+
+    /// A committee made of this KeyStore's validators, with equal voting power.
+    pub fn committee(&self, epoch: EpochId) -> Committee {
+        Committee::new_for_testing_with_normalized_voting_power(
+            epoch,
+            self.validator_keys.keys().map(|name| (*name, 1)).collect(),
+        )
+    }
+
     pub fn validator(&self, name: &AuthorityName) -> Option<&AuthorityKeyPair> {
         self.validator_keys.get(name)
     }
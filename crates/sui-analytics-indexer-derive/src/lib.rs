@@ -9,7 +9,7 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields};
 pub fn schema_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
-    let (schema, getter_implementation) = match &input.data {
+    let (schema, column_types, getter_implementation) = match &input.data {
         Data::Struct(data_struct) => match &data_struct.fields {
             Fields::Named(fields) => {
                 let (schema_iter, getter_iter): (Vec<_>, Vec<_>) = fields
@@ -27,7 +27,12 @@ pub fn schema_derive(input: TokenStream) -> TokenStream {
                         )
                     })
                     .unzip();
-                (schema_iter.join(", "), getter_iter.join("\n"))
+                let column_types = fields
+                    .named
+                    .iter()
+                    .map(|field| &field.ty)
+                    .collect::<Vec<_>>();
+                (schema_iter.join(", "), column_types, getter_iter.join("\n"))
             }
             _ => panic!("not supported struct for parquet serialization"),
         },
@@ -42,6 +47,10 @@ pub fn schema_derive(input: TokenStream) -> TokenStream {
                 vec![#schema_tokens]
             }
 
+            fn column_types() -> Vec<ParquetType> {
+                vec![#(<#column_types as ParquetColumn>::TYPE),*]
+            }
+
             fn get_column(&self, idx: usize) -> ParquetValue {
                 #getter_implementation_tokens
                 panic!("not supported column {:?}", idx);
@@ -36,6 +36,8 @@ use crate::tables::{
     CheckpointEntry, EventEntry, InputObjectKind, MoveCallEntry, MovePackageEntry, ObjectEntry,
     ObjectStatus, OwnerType, TransactionEntry, TransactionObjectEntry,
 };
+use crate::writers::arrow_writer::ArrowWriter;
+use crate::writers::avro_writer::AvroWriter;
 use crate::writers::csv_writer::CSVWriter;
 use crate::writers::parquet_writer::ParquetWriter;
 use crate::writers::AnalyticsWriter;
@@ -114,6 +116,8 @@ pub struct AnalyticsIndexerConfig {
 pub enum FileFormat {
     CSV = 0,
     PARQUET = 1,
+    ARROW = 2,
+    AVRO = 3,
 }
 
 impl FileFormat {
@@ -121,6 +125,8 @@ impl FileFormat {
         match self {
             FileFormat::CSV => "csv",
             FileFormat::PARQUET => "parquet",
+            FileFormat::ARROW => "arrow",
+            FileFormat::AVRO => "avro",
         }
     }
 }
@@ -248,9 +254,51 @@ impl From<Option<InputObjectKind>> for ParquetValue {
     }
 }
 
+/// The type of the values of a column, declared by the type of the table's field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParquetType {
+    U64,
+    Str,
+    Bool,
+    I64,
+    OptionU64,
+    OptionStr,
+}
+
+/// Types of table fields, whose values are all converted into the same `ParquetValue` variant.
+pub trait ParquetColumn: Into<ParquetValue> {
+    const TYPE: ParquetType;
+}
+
+macro_rules! impl_parquet_column {
+    ($($field_type:ty => $parquet_type:ident),*) => {
+        $(
+            impl ParquetColumn for $field_type {
+                const TYPE: ParquetType = ParquetType::$parquet_type;
+            }
+        )*
+    };
+}
+
+impl_parquet_column!(
+    u64 => U64,
+    i64 => I64,
+    String => Str,
+    bool => Bool,
+    Option<u64> => OptionU64,
+    Option<String> => OptionStr,
+    OwnerType => Str,
+    ObjectStatus => Str,
+    Option<ObjectStatus> => OptionStr,
+    Option<InputObjectKind> => OptionStr
+);
+
 pub trait ParquetSchema {
     fn schema() -> Vec<String>;
 
+    /// The types of the columns, in the order of `schema`.
+    fn column_types() -> Vec<ParquetType>;
+
     fn get_column(&self, idx: usize) -> ParquetValue;
 }
 
@@ -527,6 +575,16 @@ pub fn make_writer<S: Serialize + ParquetSchema>(
             file_type,
             starting_checkpoint_seq_num,
         )?),
+        FileFormat::ARROW => Box::new(ArrowWriter::new(
+            &config.checkpoint_dir,
+            file_type,
+            starting_checkpoint_seq_num,
+        )?),
+        FileFormat::AVRO => Box::new(AvroWriter::new(
+            &config.checkpoint_dir,
+            file_type,
+            starting_checkpoint_seq_num,
+        )?),
     })
 }
 
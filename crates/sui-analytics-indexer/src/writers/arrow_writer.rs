@@ -0,0 +1,162 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::writers::{append_columns, to_record_batch};
+use crate::{AnalyticsWriter, FileFormat, FileType};
+use crate::{ParquetSchema, ParquetValue};
+use anyhow::{anyhow, Result};
+use arrow_ipc::writer::FileWriter;
+use serde::Serialize;
+use std::fs::File;
+use std::fs::{create_dir_all, remove_file};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use sui_types::base_types::EpochId;
+
+use sui_storage::object_store::util::path_to_filesystem;
+
+// Save table entries to arrow IPC files, also known as feather files.
+pub(crate) struct ArrowWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+    data: Vec<Vec<ParquetValue>>,
+}
+
+impl ArrowWriter {
+    pub(crate) fn new(
+        root_dir_path: &Path,
+        file_type: FileType,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        Ok(Self {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            epoch: 0,
+            checkpoint_range,
+            data: vec![],
+        })
+    }
+
+    fn file(&self) -> Result<File> {
+        let file_path = path_to_filesystem(
+            self.root_dir_path.clone(),
+            &self
+                .file_type
+                .file_path(FileFormat::ARROW, self.epoch, self.checkpoint_range.clone()),
+        )?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        if file_path.exists() {
+            remove_file(&file_path)?;
+        }
+        Ok(File::create(&file_path)?)
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ArrowWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::ARROW)
+    }
+
+    fn write(&mut self, rows: &[S]) -> Result<()> {
+        append_columns(&mut self.data, rows);
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<()> {
+        if self.data.is_empty() {
+            return Ok(());
+        }
+        self.checkpoint_range.end = end_checkpoint_seq_num;
+        let batch = to_record_batch::<S>(std::mem::take(&mut self.data))?;
+
+        let mut writer = FileWriter::try_new(self.file()?, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.checkpoint_range.start = start_checkpoint_seq_num;
+        self.checkpoint_range.end = u64::MAX;
+        self.epoch = epoch_num;
+        self.data = vec![];
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{BooleanArray, Int64Array, StringArray, UInt64Array};
+    use arrow_ipc::reader::FileReader;
+    use std::fs::File;
+    use sui_storage::object_store::util::path_to_filesystem;
+
+    use crate::writers::test_utils::{test_entries, TestEntry};
+    use crate::{AnalyticsWriter, FileFormat, FileType};
+
+    use super::ArrowWriter;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = ArrowWriter::new(dir.path(), FileType::Checkpoint, 0).unwrap();
+        AnalyticsWriter::<TestEntry>::write(&mut writer, &test_entries()).unwrap();
+        AnalyticsWriter::<TestEntry>::flush(&mut writer, 10).unwrap();
+
+        let file_path = path_to_filesystem(
+            dir.path().to_path_buf(),
+            &FileType::Checkpoint.file_path(FileFormat::ARROW, 0, 0..10),
+        )
+        .unwrap();
+        let batches = FileReader::try_new(File::open(file_path).unwrap(), None)
+            .unwrap()
+            .map(|batch| batch.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+
+        let entries = test_entries();
+        let column = |name: &str| batch.column_by_name(name).unwrap().as_any();
+        assert_eq!(
+            column("amount").downcast_ref::<UInt64Array>().unwrap(),
+            &UInt64Array::from(entries.iter().map(|e| e.amount).collect::<Vec<_>>())
+        );
+        assert_eq!(
+            column("delta").downcast_ref::<Int64Array>().unwrap(),
+            &Int64Array::from(entries.iter().map(|e| e.delta).collect::<Vec<_>>())
+        );
+        assert_eq!(
+            column("name").downcast_ref::<StringArray>().unwrap(),
+            &StringArray::from(entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>())
+        );
+        assert_eq!(
+            column("flag").downcast_ref::<BooleanArray>().unwrap(),
+            &BooleanArray::from(entries.iter().map(|e| e.flag).collect::<Vec<_>>())
+        );
+        assert_eq!(
+            column("previous_amount")
+                .downcast_ref::<UInt64Array>()
+                .unwrap(),
+            &UInt64Array::from(
+                entries
+                    .iter()
+                    .map(|e| e.previous_amount)
+                    .collect::<Vec<_>>()
+            )
+        );
+        assert_eq!(
+            column("previous_name")
+                .downcast_ref::<StringArray>()
+                .unwrap(),
+            &StringArray::from(
+                entries
+                    .iter()
+                    .map(|e| e.previous_name.clone())
+                    .collect::<Vec<_>>()
+            )
+        );
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{AnalyticsWriter, FileFormat, FileType};
+use crate::{ParquetSchema, ParquetType, ParquetValue};
+use anyhow::{anyhow, Result};
+use apache_avro::types::{Record, Value};
+use apache_avro::{Codec, Decimal, Schema, Writer};
+use serde::Serialize;
+use std::fs::File;
+use std::fs::{create_dir_all, remove_file};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use sui_types::base_types::EpochId;
+
+use sui_storage::object_store::util::path_to_filesystem;
+
+// Save table entries to avro object container files.
+pub(crate) struct AvroWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+    rows: Vec<Vec<ParquetValue>>,
+}
+
+impl AvroWriter {
+    pub(crate) fn new(
+        root_dir_path: &Path,
+        file_type: FileType,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        Ok(Self {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            epoch: 0,
+            checkpoint_range,
+            rows: vec![],
+        })
+    }
+
+    fn file(&self) -> Result<File> {
+        let file_path = path_to_filesystem(
+            self.root_dir_path.clone(),
+            &self
+                .file_type
+                .file_path(FileFormat::AVRO, self.epoch, self.checkpoint_range.clone()),
+        )?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        if file_path.exists() {
+            remove_file(&file_path)?;
+        }
+        Ok(File::create(&file_path)?)
+    }
+
+    /// Build the record schema of the table from the names and declared types of its columns.
+    /// Avro has no unsigned integers, so u64 columns are written as decimals, to keep the full
+    /// range of their values.
+    fn schema<S: ParquetSchema>(&self) -> Result<Schema> {
+        let u64_type =
+            r#"{"type": "bytes", "logicalType": "decimal", "precision": 20, "scale": 0}"#;
+        let fields = S::schema()
+            .iter()
+            .zip(S::column_types())
+            .map(|(name, column_type)| {
+                let type_ = match column_type {
+                    ParquetType::U64 => u64_type.to_string(),
+                    ParquetType::I64 => r#""long""#.to_string(),
+                    ParquetType::Str => r#""string""#.to_string(),
+                    ParquetType::Bool => r#""boolean""#.to_string(),
+                    ParquetType::OptionU64 => format!(r#"["null", {u64_type}]"#),
+                    ParquetType::OptionStr => r#"["null", "string"]"#.to_string(),
+                };
+                format!(r#"{{"name": "{name}", "type": {type_}}}"#)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(Schema::parse_str(&format!(
+            r#"{{"type": "record", "name": "{:?}", "fields": [{fields}]}}"#,
+            self.file_type
+        ))?)
+    }
+}
+
+/// Decimals are big-endian two's complement bytes, the u64 values are prefixed with a zero byte so
+/// that they are all positive.
+fn to_decimal(value: u64) -> Value {
+    let mut bytes = vec![0];
+    bytes.extend_from_slice(&value.to_be_bytes());
+    Value::Decimal(Decimal::from(bytes))
+}
+
+fn to_avro_value(value: ParquetValue) -> Value {
+    // Optional values are unions of null, at index 0, and of the value's type.
+    match value {
+        ParquetValue::U64(value) => to_decimal(value),
+        ParquetValue::I64(value) => Value::Long(value),
+        ParquetValue::Str(value) => Value::String(value),
+        ParquetValue::Bool(value) => Value::Boolean(value),
+        ParquetValue::OptionU64(None) | ParquetValue::OptionStr(None) => {
+            Value::Union(0, Box::new(Value::Null))
+        }
+        ParquetValue::OptionU64(Some(value)) => Value::Union(1, Box::new(to_decimal(value))),
+        ParquetValue::OptionStr(Some(value)) => Value::Union(1, Box::new(Value::String(value))),
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for AvroWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::AVRO)
+    }
+
+    fn write(&mut self, rows: &[S]) -> Result<()> {
+        let num_columns = S::schema().len();
+        for row in rows {
+            self.rows
+                .push((0..num_columns).map(|idx| row.get_column(idx)).collect());
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        self.checkpoint_range.end = end_checkpoint_seq_num;
+        let columns = S::schema();
+        let schema = self.schema::<S>()?;
+
+        let mut writer = Writer::with_codec(&schema, self.file()?, Codec::Deflate);
+        for row in std::mem::take(&mut self.rows) {
+            let mut record =
+                Record::new(&schema).ok_or_else(|| anyhow!("Avro schema isn't a record"))?;
+            for (column, value) in columns.iter().zip(row) {
+                record.put(column, to_avro_value(value));
+            }
+            writer.append(record)?;
+        }
+        writer.into_inner()?;
+        Ok(())
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.checkpoint_range.start = start_checkpoint_seq_num;
+        self.checkpoint_range.end = u64::MAX;
+        self.epoch = epoch_num;
+        self.rows = vec![];
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apache_avro::types::Value;
+    use apache_avro::Reader;
+    use std::fs::File;
+    use sui_storage::object_store::util::path_to_filesystem;
+
+    use crate::writers::test_utils::{test_entries, TestEntry};
+    use crate::{AnalyticsWriter, FileFormat, FileType};
+
+    use super::{to_decimal, AvroWriter};
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = AvroWriter::new(dir.path(), FileType::Checkpoint, 0).unwrap();
+        AnalyticsWriter::<TestEntry>::write(&mut writer, &test_entries()).unwrap();
+        AnalyticsWriter::<TestEntry>::flush(&mut writer, 10).unwrap();
+
+        let file_path = path_to_filesystem(
+            dir.path().to_path_buf(),
+            &FileType::Checkpoint.file_path(FileFormat::AVRO, 0, 0..10),
+        )
+        .unwrap();
+        let records = Reader::new(File::open(file_path).unwrap())
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>();
+
+        let expected = test_entries()
+            .into_iter()
+            .map(|entry| {
+                let optional = |value: Option<Value>| match value {
+                    Some(value) => Value::Union(1, Box::new(value)),
+                    None => Value::Union(0, Box::new(Value::Null)),
+                };
+                Value::Record(vec![
+                    ("amount".to_string(), to_decimal(entry.amount)),
+                    ("delta".to_string(), Value::Long(entry.delta)),
+                    ("name".to_string(), Value::String(entry.name)),
+                    ("flag".to_string(), Value::Boolean(entry.flag)),
+                    (
+                        "previous_amount".to_string(),
+                        optional(entry.previous_amount.map(to_decimal)),
+                    ),
+                    (
+                        "previous_name".to_string(),
+                        optional(entry.previous_name.map(Value::String)),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(records, expected);
+    }
+}
@@ -1,11 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{FileFormat, ParquetSchema};
+use std::sync::Arc;
+
 use anyhow::Result;
+use arrow_array::{ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array};
 use serde::Serialize;
 use sui_types::base_types::EpochId;
 
+use crate::{FileFormat, ParquetSchema, ParquetType, ParquetValue};
+
+pub mod arrow_writer;
+pub mod avro_writer;
 pub mod csv_writer;
 pub mod parquet_writer;
 
@@ -19,3 +25,93 @@ pub trait AnalyticsWriter<S: Serialize + ParquetSchema>: Send + Sync + 'static {
     /// Reset internal state with given epoch and checkpoint sequence number
     fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()>;
 }
+
+macro_rules! convert_to_arrow_array {
+    ($column:ident, $column_type:ident, $target_vector:ident, $($type_:path => $variant:path => $types:ty),*) => {
+        match $column_type {
+            $(
+                $type_ => {
+                    let array = <$types>::from(
+                        $column
+                            .into_iter()
+                            .flat_map(|value| match value {
+                                $variant(value) => Some(value),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>(),
+                    );
+                    $target_vector.push(Arc::new(array) as ArrayRef);
+                }
+            )*
+        }
+    };
+}
+
+/// Buffer the columns of the rows of a file, to write them as arrow record batches.
+pub(crate) fn append_columns<S: ParquetSchema>(data: &mut Vec<Vec<ParquetValue>>, rows: &[S]) {
+    for row in rows {
+        for col_idx in 0..S::schema().len() {
+            if col_idx == data.len() {
+                data.push(vec![]);
+            }
+            data[col_idx].push(row.get_column(col_idx));
+        }
+    }
+}
+
+/// Convert the buffered columns of a file into a record batch, typed by the declared types of the
+/// table's columns.
+pub(crate) fn to_record_batch<S: ParquetSchema>(
+    data: Vec<Vec<ParquetValue>>,
+) -> Result<RecordBatch> {
+    let mut batch_data = vec![];
+    for (column, column_type) in data.into_iter().zip(S::column_types()) {
+        convert_to_arrow_array!(column, column_type, batch_data,
+            ParquetType::U64 => ParquetValue::U64 => UInt64Array, ParquetType::Str => ParquetValue::Str => StringArray, ParquetType::OptionU64 => ParquetValue::OptionU64 => UInt64Array, ParquetType::OptionStr => ParquetValue::OptionStr => StringArray, ParquetType::Bool => ParquetValue::Bool => BooleanArray, ParquetType::I64 => ParquetValue::I64 => Int64Array
+        );
+    }
+    Ok(RecordBatch::try_from_iter(
+        S::schema().iter().zip(batch_data.into_iter()),
+    )?)
+}
+
+#[cfg(test)]
+mod test_utils {
+    use serde::Serialize;
+    use sui_analytics_indexer_derive::SerializeParquet;
+
+    use crate::{ParquetColumn, ParquetSchema, ParquetType, ParquetValue};
+
+    /// A row with a column of every type, whose u64 values don't fit in an i64.
+    #[derive(Serialize, Clone, SerializeParquet)]
+    pub(crate) struct TestEntry {
+        pub(crate) amount: u64,
+        pub(crate) delta: i64,
+        pub(crate) name: String,
+        pub(crate) flag: bool,
+        pub(crate) previous_amount: Option<u64>,
+        pub(crate) previous_name: Option<String>,
+    }
+
+    /// Rows whose optional values are missing in the first row.
+    pub(crate) fn test_entries() -> Vec<TestEntry> {
+        vec![
+            TestEntry {
+                amount: u64::MAX,
+                delta: i64::MIN,
+                name: "first".to_string(),
+                flag: true,
+                previous_amount: None,
+                previous_name: None,
+            },
+            TestEntry {
+                amount: 0,
+                delta: 1,
+                name: "second".to_string(),
+                flag: false,
+                previous_amount: Some(u64::MAX - 1),
+                previous_name: Some("first".to_string()),
+            },
+        ]
+    }
+}
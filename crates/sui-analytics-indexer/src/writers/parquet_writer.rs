@@ -1,16 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::writers::{append_columns, to_record_batch};
 use crate::{AnalyticsWriter, FileFormat, FileType};
 use crate::{ParquetSchema, ParquetValue};
 use anyhow::{anyhow, Result};
-use arrow_array::{ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array};
 use serde::Serialize;
 use std::fs::File;
 use std::fs::{create_dir_all, remove_file};
 use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 use sui_types::base_types::EpochId;
 
 use parquet::arrow::ArrowWriter;
@@ -60,41 +59,13 @@ impl ParquetWriter {
     }
 }
 
-macro_rules! convert_to_arrow_array {
-    ($column:ident, $target_vector:ident, $($variant:path => $types:ty),*) => {
-        match &$column[0] {
-            $(
-                $variant(_) => {
-                    let array = <$types>::from(
-                        $column
-                            .into_iter()
-                            .flat_map(|value| match value {
-                                $variant(value) => Some(value),
-                                _ => None,
-                            })
-                            .collect::<Vec<_>>(),
-                    );
-                    $target_vector.push(Arc::new(array) as ArrayRef);
-                }
-            )*
-        }
-    };
-}
-
 impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
     fn file_format(&self) -> Result<FileFormat> {
         Ok(FileFormat::PARQUET)
     }
 
     fn write(&mut self, rows: &[S]) -> Result<()> {
-        for row in rows {
-            for col_idx in 0..S::schema().len() {
-                if col_idx == self.data.len() {
-                    self.data.push(vec![]);
-                }
-                self.data[col_idx].push(row.get_column(col_idx));
-            }
-        }
+        append_columns(&mut self.data, rows);
         Ok(())
     }
 
@@ -103,13 +74,7 @@ impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
             return Ok(());
         }
         self.checkpoint_range.end = end_checkpoint_seq_num;
-        let mut batch_data = vec![];
-        for column in std::mem::take(&mut self.data) {
-            convert_to_arrow_array!(column, batch_data,
-                ParquetValue::U64 => UInt64Array, ParquetValue::Str => StringArray, ParquetValue::OptionU64 => UInt64Array, ParquetValue::OptionStr => StringArray, ParquetValue::Bool => BooleanArray, ParquetValue::I64 => Int64Array
-            );
-        }
-        let batch = RecordBatch::try_from_iter(S::schema().iter().zip(batch_data.into_iter()))?;
+        let batch = to_record_batch::<S>(std::mem::take(&mut self.data))?;
 
         let properties = WriterProperties::builder()
             .set_compression(Compression::SNAPPY)
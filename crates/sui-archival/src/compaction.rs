@@ -0,0 +1,318 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
+use crate::writer::CheckpointWriter;
+use crate::{
+    read_manifest, write_manifest, CheckpointUpdates, FileMetadata, FileType, Manifest,
+    CHECKPOINT_FILE_MAGIC, EPOCH_DIR_PREFIX, SUMMARY_FILE_MAGIC,
+};
+use anyhow::{anyhow, Context, Result};
+use bytes::buf::Reader;
+use bytes::{Buf, Bytes};
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use prometheus::Registry;
+use std::collections::BTreeSet;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::node::ArchiveReaderConfig;
+use sui_storage::object_store::util::{copy_files, delete_files, get_verified, path_to_filesystem};
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_storage::{make_iterator, FileCompression, StorageFormat};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, FullCheckpointContents as CheckpointContents,
+};
+use tokio::sync::mpsc;
+use tracing::info;
+
+/// ArchiveCompactor rewrites the files of a range of past epochs in an archive into files of a
+/// target size and compression. Archive writers cut files by duration as well as size, so
+/// archives written with different settings (or through quiet periods) end up with many small
+/// files.
+///
+/// Rewritten files are staged in a local directory, where the new MANIFEST is verified with the
+/// same checks as `verify_archive_with_checksums` before the files are uploaded and verified again
+/// in the remote store. Rewritten files belong to the next generation of their epoch, so are
+/// written under new paths and never replace a file referenced by the live MANIFEST. The MANIFEST
+/// is swapped last with a single write, and the old files it no longer references are then
+/// deleted. No archive writer should be writing to the archive while it is compacted, which is
+/// checked before the swap.
+pub struct ArchiveCompactor {
+    remote_store_config: ObjectStoreConfig,
+    remote_object_store: Arc<DynObjectStore>,
+    local_staging_dir_root: PathBuf,
+    local_object_store: Arc<DynObjectStore>,
+    file_compression: FileCompression,
+    compression_level: i32,
+    target_file_size: usize,
+    concurrency: NonZeroUsize,
+    keep_old_files: bool,
+}
+
+impl ArchiveCompactor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        remote_store_config: ObjectStoreConfig,
+        local_staging_dir_root: PathBuf,
+        file_compression: FileCompression,
+        compression_level: i32,
+        target_file_size: usize,
+        concurrency: NonZeroUsize,
+        keep_old_files: bool,
+    ) -> Result<Self> {
+        fs::create_dir_all(&local_staging_dir_root)?;
+        let local_object_store = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(local_staging_dir_root.clone()),
+            ..Default::default()
+        }
+        .make()?;
+        Ok(ArchiveCompactor {
+            remote_object_store: remote_store_config.make()?,
+            remote_store_config,
+            local_staging_dir_root,
+            local_object_store,
+            file_compression,
+            compression_level,
+            target_file_size,
+            concurrency,
+            keep_old_files,
+        })
+    }
+
+    /// Rewrites the files of `epochs` and returns the new MANIFEST of the archive. The epoch
+    /// currently being archived can't be compacted.
+    pub async fn compact(&self, epochs: Range<u64>) -> Result<Manifest> {
+        let manifest = read_manifest(self.remote_object_store.clone()).await?;
+        if epochs.is_empty() || epochs.end > manifest.epoch_num() {
+            return Err(anyhow!(
+                "Invalid epochs to compact: {:?}, only epochs before the one being archived ({}) can be compacted",
+                epochs,
+                manifest.epoch_num()
+            ));
+        }
+        let old_files: Vec<FileMetadata> = manifest
+            .files()
+            .into_iter()
+            .filter(|f| epochs.contains(&f.epoch_num))
+            .collect();
+        let mut new_files = vec![];
+        for epoch in epochs.clone() {
+            let files = self.rewrite_epoch(epoch, &old_files).await?;
+            info!(
+                "Rewrote {} files of epoch {} into {}",
+                old_files.iter().filter(|f| f.epoch_num == epoch).count(),
+                epoch,
+                files.len()
+            );
+            new_files.extend(files);
+        }
+        if checkpoint_range(&old_files) != checkpoint_range(&new_files) {
+            return Err(anyhow!(
+                "Rewritten files cover checkpoints: {:?} instead of: {:?}",
+                checkpoint_range(&new_files),
+                checkpoint_range(&old_files)
+            ));
+        }
+        let mut new_manifest = manifest.clone();
+        new_manifest.replace_epoch_files(&epochs, new_files);
+
+        // Verify the new manifest and the rewritten files while they are only staged locally
+        write_manifest(new_manifest.clone(), self.local_object_store.clone()).await?;
+        let staging_reader = self.archive_reader(ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(self.local_staging_dir_root.clone()),
+            ..Default::default()
+        })?;
+        let rewritten: Vec<_> = staging_reader
+            .verify_manifest(new_manifest.clone())
+            .await?
+            .into_iter()
+            .filter(|(summary, _)| epochs.contains(&summary.epoch_num))
+            .collect();
+        staging_reader
+            .verify_file_consistency(rewritten.clone())
+            .await?;
+
+        // Rewritten files never share a path with an old file, so they can be uploaded while the
+        // old manifest is still in use
+        let old_paths: BTreeSet<Path> = old_files.iter().map(|f| f.file_path()).collect();
+        if let Some((summary, _)) = rewritten.iter().find(|(summary, content)| {
+            old_paths.contains(&summary.file_path()) || old_paths.contains(&content.file_path())
+        }) {
+            return Err(anyhow!(
+                "Rewritten file would replace a live file at: {}",
+                summary.file_path()
+            ));
+        }
+        let remote_reader = self.archive_reader(self.remote_store_config.clone())?;
+        self.upload(&rewritten).await?;
+        remote_reader.verify_file_consistency(rewritten).await?;
+
+        if read_manifest(self.remote_object_store.clone()).await? != manifest {
+            return Err(anyhow!(
+                "Archive manifest changed during compaction, make sure no archive writer is running"
+            ));
+        }
+        write_manifest(new_manifest.clone(), self.remote_object_store.clone()).await?;
+        info!(
+            "Swapped archive manifest for compacted epochs: {:?}",
+            epochs
+        );
+
+        if !self.keep_old_files {
+            let stale: Vec<Path> = old_paths.into_iter().collect();
+            delete_files(&stale, self.remote_object_store.clone(), self.concurrency).await?;
+            info!("Deleted {} replaced files", stale.len());
+        }
+        for epoch in epochs {
+            let epoch_dir = self
+                .local_staging_dir_root
+                .join(format!("{}{}", EPOCH_DIR_PREFIX, epoch));
+            fs::remove_dir_all(epoch_dir)?;
+        }
+        Ok(new_manifest)
+    }
+
+    /// Rewrites the checkpoints in `files` belonging to `epoch` into the local staging directory,
+    /// returning the metadata of the new files. New files are one generation past the latest
+    /// generation of the epoch's files.
+    async fn rewrite_epoch(&self, epoch: u64, files: &[FileMetadata]) -> Result<Vec<FileMetadata>> {
+        let mut summary_files: Vec<_> = files
+            .iter()
+            .filter(|f| f.epoch_num == epoch && f.file_type == FileType::CheckpointSummary)
+            .cloned()
+            .collect();
+        let mut content_files: Vec<_> = files
+            .iter()
+            .filter(|f| f.epoch_num == epoch && f.file_type == FileType::CheckpointContent)
+            .cloned()
+            .collect();
+        summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        content_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        let first_checkpoint = summary_files
+            .first()
+            .context(format!("No files in archive for epoch: {epoch}"))?
+            .checkpoint_seq_range
+            .start;
+        let generation = summary_files
+            .iter()
+            .chain(content_files.iter())
+            .map(|f| f.generation)
+            .max()
+            .unwrap_or_default()
+            .checked_add(1)
+            .context("Generation overflow")?;
+
+        let (sender, mut receiver) = mpsc::channel::<CheckpointUpdates>(100);
+        let collector = tokio::spawn(async move {
+            let mut files = vec![];
+            while let Some(updates) = receiver.recv().await {
+                files.push(updates.checkpoint_file_metadata);
+                files.push(updates.summary_file_metadata);
+            }
+            files
+        });
+        // Files are only cut by size, every file of the epoch is known upfront
+        let mut writer = CheckpointWriter::new(
+            self.local_staging_dir_root.clone(),
+            self.file_compression,
+            self.compression_level,
+            StorageFormat::Blob,
+            sender,
+            Manifest::new(epoch, first_checkpoint),
+            Duration::MAX,
+            self.target_file_size,
+        )?;
+        let remote_object_store = self.remote_object_store.clone();
+        let mut downloads = futures::stream::iter(summary_files.into_iter().zip(content_files))
+            .map(|(summary_metadata, content_metadata)| {
+                let remote_object_store = remote_object_store.clone();
+                async move {
+                    let summary_data = get_verified(
+                        &summary_metadata.file_path(),
+                        remote_object_store.clone(),
+                        &summary_metadata.sha3_digest,
+                    )
+                    .await?;
+                    let content_data = get_verified(
+                        &content_metadata.file_path(),
+                        remote_object_store,
+                        &content_metadata.sha3_digest,
+                    )
+                    .await?;
+                    Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
+                }
+            })
+            .buffered(self.concurrency.get());
+        while let Some(result) = downloads.next().await {
+            let (summary_data, content_data) = result?;
+            writer = tokio::task::spawn_blocking(move || {
+                let summaries = make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+                    SUMMARY_FILE_MAGIC,
+                    summary_data.reader(),
+                )?;
+                let contents = make_iterator::<CheckpointContents, Reader<Bytes>>(
+                    CHECKPOINT_FILE_MAGIC,
+                    content_data.reader(),
+                )?;
+                for (summary, contents) in summaries.zip(contents) {
+                    writer.write(contents, summary)?;
+                }
+                Ok::<CheckpointWriter, anyhow::Error>(writer)
+            })
+            .await??;
+        }
+        tokio::task::spawn_blocking(move || writer.cut()).await??;
+
+        // The writer names files as the first generation, move them to their generation's path
+        let mut files = collector.await?;
+        for file in files.iter_mut() {
+            let written_path =
+                path_to_filesystem(self.local_staging_dir_root.clone(), &file.file_path())?;
+            file.generation = generation;
+            let path = path_to_filesystem(self.local_staging_dir_root.clone(), &file.file_path())?;
+            fs::rename(written_path, path)?;
+        }
+        Ok(files)
+    }
+
+    async fn upload(&self, files: &[(FileMetadata, FileMetadata)]) -> Result<()> {
+        let paths: Vec<Path> = files
+            .iter()
+            .flat_map(|(summary, content)| [summary.file_path(), content.file_path()])
+            .collect();
+        copy_files(
+            &paths,
+            &paths,
+            self.local_object_store.clone(),
+            self.remote_object_store.clone(),
+            self.concurrency,
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn archive_reader(&self, remote_store_config: ObjectStoreConfig) -> Result<ArchiveReader> {
+        let metrics = ArchiveReaderMetrics::new(&Registry::default());
+        let config = ArchiveReaderConfig {
+            remote_store_config,
+            download_concurrency: self.concurrency,
+            use_for_pruning_watermark: false,
+        };
+        ArchiveReader::new(config, &metrics)
+    }
+}
+
+fn checkpoint_range(files: &[FileMetadata]) -> Option<Range<u64>> {
+    let start = files.iter().map(|f| f.checkpoint_seq_range.start).min()?;
+    let end = files.iter().map(|f| f.checkpoint_seq_range.end).max()?;
+    Some(start..end)
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+pub mod compaction;
 pub mod reader;
 pub mod writer;
 
@@ -11,7 +12,8 @@ mod tests;
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use anyhow::{anyhow, Result};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::buf::Reader;
+use bytes::{Buf, Bytes};
 use fastcrypto::hash::{HashFunction, Sha3_256};
 use indicatif::{ProgressBar, ProgressStyle};
 use num_enum::IntoPrimitive;
@@ -29,11 +31,13 @@ use std::time::{Duration, Instant};
 use sui_config::genesis::Genesis;
 use sui_config::node::ArchiveReaderConfig;
 use sui_storage::blob::{Blob, BlobEncoding};
-use sui_storage::object_store::util::{get, put};
+use sui_storage::object_store::util::{get, get_verified, put};
 use sui_storage::object_store::ObjectStoreConfig;
-use sui_storage::{compute_sha3_checksum, SHA3_BYTES};
+use sui_storage::{compute_sha3_checksum, make_iterator, SHA3_BYTES};
 use sui_types::base_types::ExecutionData;
-use sui_types::messages_checkpoint::{FullCheckpointContents, VerifiedCheckpointContents};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, FullCheckpointContents, VerifiedCheckpointContents,
+};
 use sui_types::storage::{ReadStore, SingleCheckpointSharedInMemoryStore, WriteStore};
 use tracing::{error, info};
 
@@ -41,7 +45,10 @@ use tracing::{error, info};
 /// by duration or file size. Committed files are synced with the remote store continuously. Files are
 /// optionally compressed with the zstd compression format. Filenames follow the format
 /// <checkpoint_seq_num>.<suffix> where `checkpoint_seq_num` is the first checkpoint present in that
-/// file. MANIFEST is the index and source of truth for all files present in the archive.
+/// file. Files rewritten by compaction follow the format <checkpoint_seq_num>.<generation>.<suffix>
+/// where `generation` is the number of times the epoch was compacted, so they never replace a file
+/// referenced by an older MANIFEST. MANIFEST is the index and source of truth for all files present
+/// in the archive.
 ///
 /// State Archival Directory Layout
 ///  - archive/
@@ -59,6 +66,10 @@ use tracing::{error, info};
 ///     - epoch_1/
 ///        - 101000.chk
 ///        - ...
+///     - epoch_2/ (compacted)
+///        - 201000.1.chk
+///        - 201000.1.sum
+///        - ...
 /// Blob File Disk Format
 ///┌──────────────────────────────┐
 ///│       magic <4 byte>         │
@@ -112,26 +123,71 @@ pub struct FileMetadata {
     pub epoch_num: u64,
     pub checkpoint_seq_range: Range<u64>,
     pub sha3_digest: [u8; 32],
+    /// Number of times the checkpoints in this file have been rewritten by compaction
+    pub generation: u32,
 }
 
 impl FileMetadata {
     pub fn file_path(&self) -> Path {
         let dir_path = Path::from(format!("{}{}", EPOCH_DIR_PREFIX, self.epoch_num));
-        match self.file_type {
-            FileType::CheckpointContent => dir_path.child(&*format!(
-                "{}.{CHECKPOINT_FILE_SUFFIX}",
-                self.checkpoint_seq_range.start
-            )),
-            FileType::CheckpointSummary => dir_path.child(&*format!(
-                "{}.{SUMMARY_FILE_SUFFIX}",
-                self.checkpoint_seq_range.start
-            )),
+        let suffix = match self.file_type {
+            FileType::CheckpointContent => CHECKPOINT_FILE_SUFFIX,
+            FileType::CheckpointSummary => SUMMARY_FILE_SUFFIX,
+        };
+        if self.generation == 0 {
+            dir_path.child(&*format!("{}.{suffix}", self.checkpoint_seq_range.start))
+        } else {
+            dir_path.child(&*format!(
+                "{}.{}.{suffix}",
+                self.checkpoint_seq_range.start, self.generation
+            ))
+        }
+    }
+}
+
+/// Metadata of a file in a [ManifestV1], which predates compaction so has no generation
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FileMetadataV1 {
+    pub file_type: FileType,
+    pub epoch_num: u64,
+    pub checkpoint_seq_range: Range<u64>,
+    pub sha3_digest: [u8; 32],
+}
+
+impl From<FileMetadataV1> for FileMetadata {
+    fn from(file: FileMetadataV1) -> Self {
+        FileMetadata {
+            file_type: file.file_type,
+            epoch_num: file.epoch_num,
+            checkpoint_seq_range: file.checkpoint_seq_range,
+            sha3_digest: file.sha3_digest,
+            generation: 0,
+        }
+    }
+}
+
+impl From<FileMetadata> for FileMetadataV1 {
+    fn from(file: FileMetadata) -> Self {
+        FileMetadataV1 {
+            file_type: file.file_type,
+            epoch_num: file.epoch_num,
+            checkpoint_seq_range: file.checkpoint_seq_range,
+            sha3_digest: file.sha3_digest,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct ManifestV1 {
+    pub archive_version: u8,
+    pub next_checkpoint_seq_num: u64,
+    pub file_metadata: Vec<FileMetadataV1>,
+    pub epoch: u64,
+}
+
+/// ManifestV2 records the generation of every file, and is written once an archive is compacted
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ManifestV2 {
     pub archive_version: u8,
     pub next_checkpoint_seq_num: u64,
     pub file_metadata: Vec<FileMetadata>,
@@ -141,6 +197,7 @@ pub struct ManifestV1 {
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    V2(ManifestV2),
 }
 
 impl Manifest {
@@ -154,40 +211,58 @@ impl Manifest {
     }
     pub fn files(&self) -> Vec<FileMetadata> {
         match self {
-            Manifest::V1(manifest) => manifest.file_metadata.clone(),
+            Manifest::V1(manifest) => manifest
+                .file_metadata
+                .iter()
+                .cloned()
+                .map(FileMetadata::from)
+                .collect(),
+            Manifest::V2(manifest) => manifest.file_metadata.clone(),
         }
     }
     pub fn epoch_num(&self) -> u64 {
         match self {
             Manifest::V1(manifest) => manifest.epoch,
+            Manifest::V2(manifest) => manifest.epoch,
         }
     }
     pub fn next_checkpoint_seq_num(&self) -> u64 {
         match self {
             Manifest::V1(manifest) => manifest.next_checkpoint_seq_num,
+            Manifest::V2(manifest) => manifest.next_checkpoint_seq_num,
         }
     }
     pub fn next_checkpoint_after_epoch(&self, epoch_num: u64) -> u64 {
-        match self {
-            Manifest::V1(manifest) => {
-                let mut summary_files: Vec<_> = manifest
-                    .file_metadata
-                    .clone()
-                    .into_iter()
-                    .filter(|f| f.file_type == FileType::CheckpointSummary)
-                    .collect();
-                summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
-                assert!(summary_files
-                    .windows(2)
-                    .all(|w| w[1].checkpoint_seq_range.start == w[0].checkpoint_seq_range.end));
-                assert_eq!(summary_files.first().unwrap().checkpoint_seq_range.start, 0);
-                summary_files
-                    .iter()
-                    .find(|f| f.epoch_num > epoch_num)
-                    .map(|f| f.checkpoint_seq_range.start)
-                    .unwrap_or(u64::MAX)
-            }
-        }
+        let mut summary_files: Vec<_> = self
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == FileType::CheckpointSummary)
+            .collect();
+        summary_files.sort_by_key(|f| f.checkpoint_seq_range.start);
+        assert!(summary_files
+            .windows(2)
+            .all(|w| w[1].checkpoint_seq_range.start == w[0].checkpoint_seq_range.end));
+        assert_eq!(summary_files.first().unwrap().checkpoint_seq_range.start, 0);
+        summary_files
+            .iter()
+            .find(|f| f.epoch_num > epoch_num)
+            .map(|f| f.checkpoint_seq_range.start)
+            .unwrap_or(u64::MAX)
+    }
+    /// Replaces the files of all epochs in `epochs` with `files`, keeping files sorted by the
+    /// first checkpoint in them. The manifest is upgraded to V2 to record the generation of the
+    /// replacement files.
+    pub fn replace_epoch_files(&mut self, epochs: &Range<u64>, files: Vec<FileMetadata>) {
+        let mut file_metadata = self.files();
+        file_metadata.retain(|f| !epochs.contains(&f.epoch_num));
+        file_metadata.extend(files);
+        file_metadata.sort_by_key(|f| f.checkpoint_seq_range.start);
+        *self = Manifest::V2(ManifestV2 {
+            archive_version: 2,
+            next_checkpoint_seq_num: self.next_checkpoint_seq_num(),
+            file_metadata,
+            epoch: self.epoch_num(),
+        });
     }
     pub fn update(
         &mut self,
@@ -198,6 +273,17 @@ impl Manifest {
     ) {
         match self {
             Manifest::V1(manifest) => {
+                // Archive writers only write files of the first generation
+                assert_eq!(checkpoint_file_metadata.generation, 0);
+                assert_eq!(summary_file_metadata.generation, 0);
+                manifest.file_metadata.extend(vec![
+                    checkpoint_file_metadata.into(),
+                    summary_file_metadata.into(),
+                ]);
+                manifest.epoch = epoch_num;
+                manifest.next_checkpoint_seq_num = checkpoint_sequence_number;
+            }
+            Manifest::V2(manifest) => {
                 manifest
                     .file_metadata
                     .extend(vec![checkpoint_file_metadata, summary_file_metadata]);
@@ -258,6 +344,7 @@ pub fn create_file_metadata(
         epoch_num,
         checkpoint_seq_range,
         sha3_digest,
+        generation: 0,
     };
     Ok(file_metadata)
 }
@@ -309,6 +396,43 @@ pub async fn write_manifest(manifest: Manifest, remote_store: Arc<DynObjectStore
     Ok(())
 }
 
+/// Read the checkpoints in a pair of summary and content files of the archive. Both files are
+/// verified against their checksums, and the contents against the digests in the summaries, but
+/// the summaries are not verified against a committee.
+pub async fn read_checkpoint_files(
+    summary_metadata: &FileMetadata,
+    content_metadata: &FileMetadata,
+    remote_store: Arc<DynObjectStore>,
+) -> Result<Vec<(CertifiedCheckpointSummary, FullCheckpointContents)>> {
+    let summary_data = get_verified(
+        &summary_metadata.file_path(),
+        remote_store.clone(),
+        &summary_metadata.sha3_digest,
+    )
+    .await?;
+    let content_data = get_verified(
+        &content_metadata.file_path(),
+        remote_store,
+        &content_metadata.sha3_digest,
+    )
+    .await?;
+    let summaries = make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+        SUMMARY_FILE_MAGIC,
+        summary_data.reader(),
+    )?;
+    let contents = make_iterator::<FullCheckpointContents, Reader<Bytes>>(
+        CHECKPOINT_FILE_MAGIC,
+        content_data.reader(),
+    )?;
+    summaries
+        .zip(contents)
+        .map(|(summary, contents)| {
+            contents.verify_digests(summary.content_digest)?;
+            Ok((summary, contents))
+        })
+        .collect()
+}
+
 pub async fn verify_archive_with_genesis_config(
     genesis: &std::path::Path,
     remote_store_config: ObjectStoreConfig,
@@ -18,7 +18,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use sui_config::node::ArchiveReaderConfig;
-use sui_storage::object_store::util::get;
+use sui_storage::object_store::util::{get, get_verified};
 use sui_storage::{compute_sha3_checksum_for_bytes, make_iterator, verify_checkpoint};
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointSequenceNumber,
@@ -280,8 +280,12 @@ impl ArchiveReader {
             .map(|(_, summary_metadata)| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
-                    let summary_data =
-                        get(&summary_metadata.file_path(), remote_object_store.clone()).await?;
+                    let summary_data = get_verified(
+                        &summary_metadata.file_path(),
+                        remote_object_store.clone(),
+                        &summary_metadata.sha3_digest,
+                    )
+                    .await?;
                     Ok::<Bytes, anyhow::Error>(summary_data)
                 }
             })
@@ -404,10 +408,18 @@ impl ArchiveReader {
             .map(|(_, (summary_metadata, content_metadata))| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
-                    let summary_data =
-                        get(&summary_metadata.file_path(), remote_object_store.clone()).await?;
-                    let content_data =
-                        get(&content_metadata.file_path(), remote_object_store.clone()).await?;
+                    let summary_data = get_verified(
+                        &summary_metadata.file_path(),
+                        remote_object_store.clone(),
+                        &summary_metadata.sha3_digest,
+                    )
+                    .await?;
+                    let content_data = get_verified(
+                        &content_metadata.file_path(),
+                        remote_object_store.clone(),
+                        &content_metadata.sha3_digest,
+                    )
+                    .await?;
                     Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
                 }
             })
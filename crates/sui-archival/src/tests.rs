@@ -1,9 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::compaction::ArchiveCompactor;
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use crate::writer::ArchiveWriter;
-use crate::{read_manifest, verify_archive_with_local_store, write_manifest, Manifest};
+use crate::{
+    read_manifest, verify_archive_with_checksums, verify_archive_with_local_store, write_manifest,
+    Manifest,
+};
 use anyhow::{anyhow, Context, Result};
 use more_asserts as ma;
 use object_store::DynObjectStore;
@@ -371,3 +375,102 @@ async fn test_verify_archive_with_oneshot_store_bad_data() -> Result<(), anyhow:
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_archive_compaction() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    let mut latest_archived_checkpoint_seq_num = 0;
+    while latest_archived_checkpoint_seq_num < 10 {
+        insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+        latest_archived_checkpoint_seq_num = test_state
+            .archive_reader
+            .latest_available_checkpoint()
+            .await?;
+    }
+    kill.send(())?;
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // Move the archive on to the next epoch, so that epoch 0 can be compacted
+    let Manifest::V1(mut manifest) = read_manifest(test_state.remote_store.clone()).await? else {
+        panic!("Archive writers should write a V1 manifest");
+    };
+    manifest.epoch = 1;
+    write_manifest(Manifest::V1(manifest), test_state.remote_store.clone()).await?;
+    let manifest = read_manifest(test_state.remote_store.clone()).await?;
+    ma::assert_gt!(manifest.files().len(), 2);
+
+    // Old files are kept, so the old manifest remains readable after compaction
+    let compactor = ArchiveCompactor::new(
+        test_state.remote_store_config.clone(),
+        temp_dir(),
+        FileCompression::Zstd,
+        3,
+        1024 * 1024,
+        NonZeroUsize::new(2).unwrap(),
+        true,
+    )?;
+    // The epoch being archived can't be compacted
+    assert!(compactor.compact(0..2).await.is_err());
+    let first_generation = compactor.compact(0..1).await?;
+
+    // Every checkpoint of the epoch now fits in a single pair of files, written under new paths
+    assert_eq!(first_generation.files().len(), 2);
+    for file in first_generation.files() {
+        assert_eq!(file.generation, 1);
+        assert!(manifest
+            .files()
+            .iter()
+            .all(|old| old.file_path() != file.file_path()));
+    }
+    write_manifest(manifest.clone(), test_state.remote_store.clone()).await?;
+    verify_archive_with_checksums(test_state.remote_store_config.clone(), 1).await?;
+    write_manifest(first_generation.clone(), test_state.remote_store.clone()).await?;
+    verify_archive_with_checksums(test_state.remote_store_config.clone(), 1).await?;
+
+    // Compacting again writes the next generation and deletes the files it replaces
+    let compactor = ArchiveCompactor::new(
+        test_state.remote_store_config.clone(),
+        temp_dir(),
+        FileCompression::Zstd,
+        3,
+        1024 * 1024,
+        NonZeroUsize::new(2).unwrap(),
+        false,
+    )?;
+    let compacted = compactor.compact(0..1).await?;
+    assert_eq!(compacted.files().len(), 2);
+    assert!(compacted.files().iter().all(|f| f.generation == 2));
+    assert_eq!(
+        read_manifest(test_state.remote_store.clone()).await?,
+        compacted
+    );
+    assert_eq!(
+        compacted.next_checkpoint_seq_num(),
+        manifest.next_checkpoint_seq_num()
+    );
+    for file in first_generation.files() {
+        assert!(!test_state
+            .remote_path
+            .join(file.file_path().as_ref())
+            .exists());
+    }
+    verify_archive_with_checksums(test_state.remote_store_config.clone(), 1).await?;
+
+    let genesis_checkpoint = test_store
+        .get_checkpoint_by_sequence_number(0)?
+        .context("Missing genesis checkpoint")?;
+    let genesis_checkpoint_content = test_store
+        .get_full_checkpoint_contents_by_sequence_number(0)?
+        .context("Missing genesis checkpoint")?;
+    let mut read_store = SingleCheckpointSharedInMemoryStore::default();
+    read_store.insert_genesis_state(
+        genesis_checkpoint,
+        VerifiedCheckpointContents::new_unchecked(genesis_checkpoint_content),
+        test_state.committee.committee().to_owned(),
+    );
+    verify_archive_with_local_store(read_store, test_state.remote_store_config.clone(), 1, false)
+        .await?;
+    Ok(())
+}
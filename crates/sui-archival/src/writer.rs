@@ -23,7 +23,9 @@ use std::time::Duration;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::{copy_file, path_to_filesystem};
 use sui_storage::object_store::ObjectStoreConfig;
-use sui_storage::{compress, FileCompression, StorageFormat};
+use sui_storage::{
+    compress_with_level, FileCompression, StorageFormat, DEFAULT_ZSTD_COMPRESSION_LEVEL,
+};
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber,
     FullCheckpointContents as CheckpointContents,
@@ -53,7 +55,7 @@ impl ArchiveMetrics {
 }
 
 /// CheckpointWriter writes checkpoints and summaries. It creates multiple *.chk and *.sum files
-struct CheckpointWriter {
+pub(crate) struct CheckpointWriter {
     root_dir_path: PathBuf,
     epoch_num: u64,
     checkpoint_range: Range<u64>,
@@ -62,6 +64,7 @@ struct CheckpointWriter {
     sender: Sender<CheckpointUpdates>,
     checkpoint_buf_offset: usize,
     file_compression: FileCompression,
+    compression_level: i32,
     storage_format: StorageFormat,
     manifest: Manifest,
     last_commit_instant: Instant,
@@ -70,9 +73,10 @@ struct CheckpointWriter {
 }
 
 impl CheckpointWriter {
-    fn new(
+    pub(crate) fn new(
         root_dir_path: PathBuf,
         file_compression: FileCompression,
+        compression_level: i32,
         storage_format: StorageFormat,
         sender: Sender<CheckpointUpdates>,
         manifest: Manifest,
@@ -111,6 +115,7 @@ impl CheckpointWriter {
             checkpoint_buf_offset: 0,
             sender,
             file_compression,
+            compression_level,
             storage_format,
             manifest,
             last_commit_instant: Instant::now(),
@@ -219,7 +224,7 @@ impl CheckpointWriter {
         )?;
         Ok(file_metadata)
     }
-    fn cut(&mut self) -> Result<()> {
+    pub(crate) fn cut(&mut self) -> Result<()> {
         if !self.checkpoint_range.is_empty() {
             let checkpoint_file_metadata = self.finalize()?;
             let summary_file_metadata = self.finalize_summary()?;
@@ -242,7 +247,7 @@ impl CheckpointWriter {
         let mut input = File::open(source)?;
         let tmp_file_name = source.with_extension("tmp");
         let mut output = File::create(&tmp_file_name)?;
-        compress(&mut input, &mut output)?;
+        compress_with_level(&mut input, &mut output, self.compression_level)?;
         fs::rename(tmp_file_name, source)?;
         Ok(())
     }
@@ -369,6 +374,7 @@ impl ArchiveWriter {
         let checkpoint_writer = CheckpointWriter::new(
             self.local_staging_dir_root.clone(),
             self.file_compression,
+            DEFAULT_ZSTD_COMPRESSION_LEVEL,
             self.storage_format,
             sender,
             manifest,
@@ -6,6 +6,7 @@ use clap::*;
 use strum_macros::EnumString;
 
 use crate::drivers::Interval;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Parser)]
@@ -178,6 +179,9 @@ pub enum RunSpec {
         // relative weight of adversarial transactions in the benchmark workload
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
         shared_deletion: Vec<u32>,
+        // relative weight of the move calls defined by `move_call_config` in the benchmark workload
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        move_call: Vec<u32>,
 
         // --- workload-specific options --- (TODO: use subcommands or similar)
         // 100 for max hotness i.e all requests target
@@ -207,6 +211,10 @@ pub enum RunSpec {
         // Default is (0-0.5) implying random load at 50% load. See `AdversarialPayloadType` enum for `adversarial_type`
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = ["0-1.0".to_string()])]
         adversarial_cfg: Vec<String>,
+        // YAML file defining the Move package to publish, the calls initializing its objects and
+        // the weighted calls of the `move_call` workload. See `MoveCallWorkloadConfig`.
+        #[clap(long)]
+        move_call_config: Option<PathBuf>,
 
         // --- generic options ---
         // Target qps
@@ -223,4 +231,37 @@ pub enum RunSpec {
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [Interval::from_str("unbounded").unwrap()])]
         duration: Vec<Interval>,
     },
+    // Replay the traffic recorded in a range of checkpoints, read either from a checkpoint archive
+    // or from the REST API of a fullnode. Each recorded transaction is replayed as a transaction
+    // of the same shape: the same kinds of commands, shared objects with the same contention, and
+    // created objects of the same size. Publishes and upgrades are not replayed.
+    Trace {
+        // First checkpoint of the trace
+        #[clap(long)]
+        start_checkpoint: u64,
+        // Last checkpoint of the trace
+        #[clap(long)]
+        end_checkpoint: u64,
+        // Local directory of the checkpoint archive to read the trace from. Object sizes are
+        // estimated from storage costs, as the archive does not include objects.
+        #[clap(long)]
+        archive_path: Option<PathBuf>,
+        // Url of the fullnode REST API to read the trace from
+        #[clap(long)]
+        rest_url: Option<String>,
+        // Rate of the replay, in percent of the rate of the trace. For example, 200 replays the
+        // trace twice as fast as it was recorded.
+        #[clap(long, default_value = "100")]
+        speedup_pct: u64,
+        // Maximum number of shared objects to create. The least contended shared objects of the
+        // trace are spread over the others beyond this number.
+        #[clap(long, default_value = "1000")]
+        max_shared_objects: u64,
+        // Number of workers
+        #[clap(long, default_value = "12")]
+        num_workers: u64,
+        // Max in-flight ratio
+        #[clap(long, default_value = "5")]
+        in_flight_ratio: u64,
+    },
 }
@@ -4,9 +4,11 @@
 pub mod adversarial;
 pub mod batch_payment;
 pub mod delegation;
+pub mod move_call;
 pub mod payload;
 pub mod shared_counter;
 pub mod shared_object_deletion;
+pub mod trace;
 pub mod transfer_object;
 pub mod workload;
 pub mod workload_configuration;
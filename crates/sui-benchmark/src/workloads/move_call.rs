@@ -0,0 +1,1054 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A workload of Move calls into a user package, described by a YAML file rather than in Rust.
+//! The package is published when the workload is initialized, then the init calls of the file
+//! create the objects that the benchmarked calls operate on. For example:
+//!
+//! ```yaml
+//! # Relative to the directory of this file
+//! package_path: ../my_package
+//! init:
+//!   # Run once by the publisher
+//!   - module: pool
+//!     function: create_shared_pool
+//!     arguments:
+//!       - owned: $package::pool::AdminCap
+//!   # Run by the sender of each payload, to create the objects it owns
+//!   - module: pool
+//!     function: mint_position
+//!     per_sender: true
+//!     arguments:
+//!       - shared: { object_type: $package::pool::Pool }
+//!       - u64: 1000
+//!     transfer_results: 1
+//! calls:
+//!   - weight: 3
+//!     module: pool
+//!     function: swap
+//!     arguments:
+//!       - shared: { object_type: $package::pool::Pool }
+//!       - random_u64: { min: 1, max: 100 }
+//!   - weight: 1
+//!     module: pool
+//!     function: add_liquidity
+//!     arguments:
+//!       - shared: { object_type: $package::pool::Pool }
+//!       - owned: $package::pool::Position
+//!       - gas
+//! ```
+//!
+//! `$package` stands for the id of the published package in types. Object types without type
+//! arguments match objects of that type with any type arguments. As senders can run out of owned
+//! objects, at least one call of non zero weight must take none.
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_BUDGET, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ProgrammableTransactionBuilder, ValidatorProxy};
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use futures::future::join_all;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Owner;
+use sui_types::transaction::{Argument, ObjectArg, Transaction, TransactionData};
+use sui_types::utils::to_sender_signed_transaction;
+use sui_types::{parse_sui_struct_tag, parse_sui_type_tag};
+use tracing::{error, info};
+
+/// Placeholder for the id of the published package in the types of a workload file
+const PACKAGE_PLACEHOLDER: &str = "$package";
+
+/// Shared objects created during init, by type
+type SharedObjects = BTreeMap<StructTag, Vec<(ObjectID, SequenceNumber)>>;
+/// Objects owned by a sender, by type
+type OwnedObjects = BTreeMap<StructTag, Vec<ObjectRef>>;
+
+/// A workload file
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveCallWorkloadConfig {
+    /// Directory of the Move package to publish. Relative paths are relative to the directory of
+    /// the workload file.
+    pub package_path: PathBuf,
+    /// Calls run in order after the package is published
+    #[serde(default)]
+    pub init: Vec<InitCallConfig>,
+    /// Calls making up the workload, each picked at random in proportion to its weight
+    pub calls: Vec<WeightedCallConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InitCallConfig {
+    #[serde(flatten)]
+    pub call: MoveCallConfig,
+    /// Whether the call is run by the sender of each payload rather than once by the publisher.
+    /// Owned objects created by these calls are the ones available to the payloads.
+    #[serde(default)]
+    pub per_sender: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeightedCallConfig {
+    pub weight: u32,
+    #[serde(flatten)]
+    pub call: MoveCallConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveCallConfig {
+    /// Package to call, the published package if not set
+    #[serde(default)]
+    pub package: Option<ObjectID>,
+    pub module: String,
+    pub function: String,
+    #[serde(default)]
+    pub type_arguments: Vec<String>,
+    #[serde(default)]
+    pub arguments: Vec<ArgumentConfig>,
+    /// Number of values returned by the call to transfer to the sender
+    #[serde(default)]
+    pub transfer_results: u16,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgumentConfig {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Address(SuiAddress),
+    String(String),
+    Bytes(Vec<u8>),
+    /// A u64 drawn uniformly in `[min, max]` for every transaction
+    RandomU64 {
+        min: u64,
+        max: u64,
+    },
+    /// A fresh random address for every transaction
+    RandomAddress,
+    /// A shared object of the given type created during init, picked at random
+    Shared {
+        object_type: String,
+        #[serde(default = "default_mutable")]
+        mutable: bool,
+    },
+    /// An object of the given type owned by the sender, picked at random
+    Owned(String),
+    /// The gas coin of the transaction
+    Gas,
+}
+
+fn default_mutable() -> bool {
+    true
+}
+
+impl MoveCallWorkloadConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open workload file {}", path.display()))?;
+        let mut config: Self = serde_yaml::from_reader(file)
+            .with_context(|| format!("Failed to parse workload file {}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            config.package_path = dir.join(&config.package_path);
+        }
+        let takes_owned_objects = |call: &MoveCallConfig| {
+            call.arguments
+                .iter()
+                .any(|arg| matches!(arg, ArgumentConfig::Owned(_)))
+        };
+        if !config
+            .calls
+            .iter()
+            .any(|call| call.weight > 0 && !takes_owned_objects(&call.call))
+        {
+            bail!(
+                "Workload file {} has no call of non zero weight which takes no owned objects",
+                path.display()
+            );
+        }
+        // Check that all types and identifiers parse before publishing anything
+        config.resolve(ObjectID::ZERO)?;
+        Ok(config)
+    }
+
+    fn resolve(&self, package_id: ObjectID) -> Result<ResolvedCalls> {
+        Ok(ResolvedCalls {
+            init: self
+                .init
+                .iter()
+                .map(|init| Ok((init.call.resolve(package_id)?, init.per_sender)))
+                .collect::<Result<_>>()?,
+            calls: self
+                .calls
+                .iter()
+                .map(|call| Ok((call.weight, call.call.resolve(package_id)?)))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl MoveCallConfig {
+    fn resolve(&self, package_id: ObjectID) -> Result<MoveCall> {
+        let context = || format!("Invalid call to {}::{}", self.module, self.function);
+        let package = package_id.to_string();
+        let type_arguments = self
+            .type_arguments
+            .iter()
+            .map(|ty| parse_sui_type_tag(&ty.replace(PACKAGE_PLACEHOLDER, &package)))
+            .collect::<Result<_>>()
+            .with_context(context)?;
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|arg| arg.resolve(&package))
+            .collect::<Result<_>>()
+            .with_context(context)?;
+        Ok(MoveCall {
+            package: self.package.unwrap_or(package_id),
+            module: Identifier::new(self.module.as_str()).with_context(context)?,
+            function: Identifier::new(self.function.as_str()).with_context(context)?,
+            type_arguments,
+            arguments,
+            transfer_results: self.transfer_results,
+        })
+    }
+}
+
+impl ArgumentConfig {
+    fn resolve(&self, package: &str) -> Result<MoveCallArg> {
+        let object_type =
+            |ty: &str| parse_sui_struct_tag(&ty.replace(PACKAGE_PLACEHOLDER, package));
+        Ok(match self {
+            ArgumentConfig::Bool(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::U8(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::U16(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::U32(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::U64(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::Address(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::String(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::Bytes(value) => MoveCallArg::Pure(bcs::to_bytes(value)?),
+            ArgumentConfig::RandomU64 { min, max } => {
+                if min > max {
+                    bail!("Empty range {min}..={max}");
+                }
+                MoveCallArg::RandomU64(*min, *max)
+            }
+            ArgumentConfig::RandomAddress => MoveCallArg::RandomAddress,
+            ArgumentConfig::Shared {
+                object_type: ty,
+                mutable,
+            } => MoveCallArg::Shared(object_type(ty)?, *mutable),
+            ArgumentConfig::Owned(ty) => MoveCallArg::Owned(object_type(ty)?),
+            ArgumentConfig::Gas => MoveCallArg::Gas,
+        })
+    }
+}
+
+/// The calls of a workload file, against the published package
+#[derive(Debug)]
+struct ResolvedCalls {
+    init: Vec<(MoveCall, bool)>,
+    calls: Vec<(u32, MoveCall)>,
+}
+
+#[derive(Debug)]
+struct MoveCall {
+    package: ObjectID,
+    module: Identifier,
+    function: Identifier,
+    type_arguments: Vec<TypeTag>,
+    arguments: Vec<MoveCallArg>,
+    transfer_results: u16,
+}
+
+#[derive(Debug)]
+enum MoveCallArg {
+    Pure(Vec<u8>),
+    RandomU64(u64, u64),
+    RandomAddress,
+    Shared(StructTag, bool),
+    Owned(StructTag),
+    Gas,
+}
+
+/// Whether an object of type `tag` matches `pattern`, which matches any type arguments if it has
+/// none
+fn matches_type(pattern: &StructTag, tag: &StructTag) -> bool {
+    pattern.address == tag.address
+        && pattern.module == tag.module
+        && pattern.name == tag.name
+        && (pattern.type_params.is_empty() || pattern.type_params == tag.type_params)
+}
+
+impl MoveCall {
+    /// Whether the sender owns enough objects of each type taken by the call
+    fn has_owned_objects(&self, owned: &OwnedObjects) -> bool {
+        self.arguments.iter().all(|arg| {
+            let MoveCallArg::Owned(pattern) = arg else {
+                return true;
+            };
+            let needed = self
+                .arguments
+                .iter()
+                .filter(|arg| matches!(arg, MoveCallArg::Owned(other) if other == pattern))
+                .count();
+            let available: usize = owned
+                .iter()
+                .filter(|(tag, _)| matches_type(pattern, tag))
+                .map(|(_, refs)| refs.len())
+                .sum();
+            available >= needed
+        })
+    }
+
+    fn add_to(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        sender: SuiAddress,
+        shared: &SharedObjects,
+        owned: &OwnedObjects,
+        rng: &mut impl Rng,
+    ) -> Result<()> {
+        let mut used = BTreeSet::new();
+        let mut arguments = vec![];
+        for arg in &self.arguments {
+            arguments.push(match arg {
+                MoveCallArg::Pure(bytes) => builder.pure_bytes(bytes.clone(), false),
+                MoveCallArg::RandomU64(min, max) => builder.pure(rng.gen_range(*min..=*max))?,
+                MoveCallArg::RandomAddress => {
+                    builder.pure(SuiAddress::from(ObjectID::new(rng.gen())))?
+                }
+                MoveCallArg::Shared(pattern, mutable) => {
+                    let candidates: Vec<_> = shared
+                        .iter()
+                        .filter(|(tag, _)| matches_type(pattern, tag))
+                        .flat_map(|(_, objects)| objects)
+                        .collect();
+                    let (id, initial_shared_version) = **candidates
+                        .choose(rng)
+                        .ok_or_else(|| anyhow!("No shared object of type {pattern}"))?;
+                    builder.obj(ObjectArg::SharedObject {
+                        id,
+                        initial_shared_version,
+                        mutable: *mutable,
+                    })?
+                }
+                MoveCallArg::Owned(pattern) => {
+                    // The same object can't be passed twice to a call
+                    let candidates: Vec<_> = owned
+                        .iter()
+                        .filter(|(tag, _)| matches_type(pattern, tag))
+                        .flat_map(|(_, refs)| refs)
+                        .filter(|obj_ref| !used.contains(&obj_ref.0))
+                        .collect();
+                    let obj_ref = **candidates
+                        .choose(rng)
+                        .ok_or_else(|| anyhow!("No object of type {pattern} owned by {sender}"))?;
+                    used.insert(obj_ref.0);
+                    builder.obj(ObjectArg::ImmOrOwnedObject(obj_ref))?
+                }
+                MoveCallArg::Gas => Argument::GasCoin,
+            });
+        }
+        let result = builder.programmable_move_call(
+            self.package,
+            self.module.clone(),
+            self.function.clone(),
+            self.type_arguments.clone(),
+            arguments,
+        );
+        if self.transfer_results > 0 {
+            let Argument::Result(index) = result else {
+                unreachable!("A move call returns a command result");
+            };
+            let results = (0..self.transfer_results)
+                .map(|i| Argument::NestedResult(index, i))
+                .collect();
+            builder.transfer_args(sender, results);
+        }
+        Ok(())
+    }
+
+    fn make_transaction(
+        &self,
+        gas: &Gas,
+        shared: &SharedObjects,
+        owned: &OwnedObjects,
+        gas_price: u64,
+        rng: &mut impl Rng,
+    ) -> Result<Transaction> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        self.add_to(&mut builder, gas.1, shared, owned, rng)?;
+        let data = TransactionData::new_programmable(
+            gas.1,
+            vec![gas.0],
+            builder.finish(),
+            MAX_BUDGET,
+            gas_price,
+        );
+        Ok(to_sender_signed_transaction(data, gas.2.as_ref()))
+    }
+}
+
+/// Updates the objects owned by `sender` with the objects mutated or deleted by a transaction
+fn update_owned_objects(owned: &mut OwnedObjects, effects: &ExecutionEffects, sender: SuiAddress) {
+    let mutated: BTreeMap<_, _> = effects
+        .mutated()
+        .into_iter()
+        .map(|(obj_ref, owner)| (obj_ref.0, (obj_ref, owner)))
+        .collect();
+    let deleted: BTreeSet<_> = effects.deleted().iter().map(|obj_ref| obj_ref.0).collect();
+    for refs in owned.values_mut() {
+        refs.retain_mut(|obj_ref| {
+            if deleted.contains(&obj_ref.0) {
+                return false;
+            }
+            match mutated.get(&obj_ref.0) {
+                Some((new_ref, owner)) => {
+                    *obj_ref = *new_ref;
+                    *owner == Owner::AddressOwner(sender)
+                }
+                None => true,
+            }
+        });
+    }
+}
+
+fn merge_shared_objects(shared: &mut SharedObjects, other: SharedObjects) {
+    for (tag, objects) in other {
+        shared.entry(tag).or_default().extend(objects);
+    }
+}
+
+/// Adds the shared objects, and the objects owned by `sender`, created by a transaction
+async fn record_created_objects(
+    proxy: &Arc<dyn ValidatorProxy + Sync + Send>,
+    effects: &ExecutionEffects,
+    sender: SuiAddress,
+    shared: &mut SharedObjects,
+    owned: &mut OwnedObjects,
+) -> Result<()> {
+    for (obj_ref, owner) in effects.created() {
+        if !matches!(owner, Owner::Shared { .. }) && owner != Owner::AddressOwner(sender) {
+            continue;
+        }
+        let Some(tag) = proxy.get_object(obj_ref.0).await?.struct_tag() else {
+            continue;
+        };
+        match owner {
+            Owner::Shared {
+                initial_shared_version,
+            } => shared
+                .entry(tag)
+                .or_default()
+                .push((obj_ref.0, initial_shared_version)),
+            _ => owned.entry(tag).or_default().push(obj_ref),
+        }
+    }
+    Ok(())
+}
+
+/// Runs an init call, taking shared objects from `shared`, and recording the shared objects it
+/// creates in `created_shared`
+async fn run_init_call(
+    proxy: &Arc<dyn ValidatorProxy + Sync + Send>,
+    call: &MoveCall,
+    gas: &mut Gas,
+    shared: &SharedObjects,
+    created_shared: &mut SharedObjects,
+    owned: &mut OwnedObjects,
+    gas_price: u64,
+) -> Result<()> {
+    let transaction =
+        call.make_transaction(gas, shared, owned, gas_price, &mut rand::thread_rng())?;
+    let effects = proxy.execute_transaction_block(transaction).await?;
+    if !effects.is_ok() {
+        bail!(
+            "Init call {}::{} failed: {}",
+            call.module,
+            call.function,
+            effects.status()
+        );
+    }
+    gas.0 = effects.gas_object().0;
+    update_owned_objects(owned, &effects, gas.1);
+    record_created_objects(proxy, &effects, gas.1, created_shared, owned).await
+}
+
+#[derive(Debug)]
+pub struct MoveCallTestPayload {
+    calls: Arc<ResolvedCalls>,
+    shared_objects: Arc<SharedObjects>,
+    owned_objects: OwnedObjects,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for MoveCallTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "move_call")
+    }
+}
+
+impl Payload for MoveCallTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Move call tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+        update_owned_objects(&mut self.owned_objects, effects, self.gas.1);
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let mut rng = rand::thread_rng();
+        let rgp = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        // Calls taking owned objects that the sender no longer has are skipped, as are calls
+        // failing to build
+        let mut calls: Vec<_> = self
+            .calls
+            .calls
+            .iter()
+            .filter(|(weight, call)| *weight > 0 && call.has_owned_objects(&self.owned_objects))
+            .collect();
+        while let Ok(index) = WeightedIndex::new(calls.iter().map(|(weight, _)| *weight)) {
+            let (_, call) = calls.remove(index.sample(&mut rng));
+            match call.make_transaction(
+                &self.gas,
+                &self.shared_objects,
+                &self.owned_objects,
+                rgp,
+                &mut rng,
+            ) {
+                Ok(transaction) => return transaction,
+                Err(e) => error!(
+                    "Failed to make {}::{} call: {e}",
+                    call.module, call.function
+                ),
+            }
+        }
+        // Loading the workload file checks that it has a call which takes no owned objects, and
+        // init that the shared objects of every call exist, so this is not expected
+        error!("No call of the move call workload can be made, sending the gas coin back instead");
+        TestTransactionBuilder::new(self.gas.1, self.gas.0, rgp)
+            .transfer_sui(None, self.gas.1)
+            .build_and_sign(self.gas.2.as_ref())
+    }
+}
+
+#[derive(Debug)]
+pub struct MoveCallWorkloadBuilder {
+    config: MoveCallWorkloadConfig,
+    num_payloads: u64,
+}
+
+impl MoveCallWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        config: MoveCallWorkloadConfig,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+                group,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(MoveCallWorkloadBuilder {
+                    config,
+                    num_payloads: max_ops,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for MoveCallWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the package and running the init calls of the publisher
+        let (address, keypair) = get_key_pair();
+        vec![GasCoinConfig {
+            amount: MAX_GAS_FOR_TESTING,
+            address,
+            keypair: Arc::new(keypair),
+        }]
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        (0..self.num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(MoveCallWorkload {
+            config: self.config.clone(),
+            calls: None,
+            shared_objects: Arc::new(SharedObjects::new()),
+            init_gas: init_gas[0].clone(),
+            payloads: payload_gas
+                .into_iter()
+                .map(|gas| (gas, OwnedObjects::new()))
+                .collect(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct MoveCallWorkload {
+    config: MoveCallWorkloadConfig,
+    /// Calls of the workload file against the published package, once published
+    calls: Option<Arc<ResolvedCalls>>,
+    shared_objects: Arc<SharedObjects>,
+    init_gas: Gas,
+    /// Gas of each payload, with the objects its sender owns
+    payloads: Vec<(Gas, OwnedObjects)>,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for MoveCallWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.calls.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let publisher = &mut self.init_gas;
+        let mut shared = SharedObjects::new();
+        let mut publisher_owned = OwnedObjects::new();
+
+        info!("Publishing package {}", self.config.package_path.display());
+        let transaction = TestTransactionBuilder::new(publisher.1, publisher.0, gas_price)
+            .publish(self.config.package_path.clone())
+            .build_and_sign(publisher.2.as_ref());
+        let effects = proxy
+            .execute_transaction_block(transaction)
+            .await
+            .expect("Failed to publish package!");
+        let package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|((id, _, _), _)| *id)
+            .expect("Published package not found in effects");
+        publisher.0 = effects.gas_object().0;
+        record_created_objects(
+            &proxy,
+            &effects,
+            publisher.1,
+            &mut shared,
+            &mut publisher_owned,
+        )
+        .await
+        .unwrap();
+        let calls = self.config.resolve(package_id).unwrap();
+
+        for (call, _) in calls.init.iter().filter(|(_, per_sender)| !per_sender) {
+            info!("Running init call {}::{}", call.module, call.function);
+            let mut created_shared = SharedObjects::new();
+            run_init_call(
+                &proxy,
+                call,
+                publisher,
+                &shared,
+                &mut created_shared,
+                &mut publisher_owned,
+                gas_price,
+            )
+            .await
+            .unwrap();
+            merge_shared_objects(&mut shared, created_shared);
+        }
+
+        // Calls run by each sender only see the shared objects created by the publisher
+        let per_sender_calls: Vec<_> = calls
+            .init
+            .iter()
+            .filter_map(|(call, per_sender)| per_sender.then_some(call))
+            .collect();
+        if !per_sender_calls.is_empty() {
+            info!(
+                "Running {} init calls for each of {} senders",
+                per_sender_calls.len(),
+                self.payloads.len()
+            );
+            let futures = self.payloads.iter_mut().map(|(gas, owned)| {
+                let proxy = proxy.clone();
+                let shared = &shared;
+                let per_sender_calls = &per_sender_calls;
+                async move {
+                    let mut created_shared = SharedObjects::new();
+                    for call in per_sender_calls {
+                        run_init_call(
+                            &proxy,
+                            call,
+                            gas,
+                            shared,
+                            &mut created_shared,
+                            owned,
+                            gas_price,
+                        )
+                        .await?;
+                    }
+                    Ok::<_, anyhow::Error>(created_shared)
+                }
+            });
+            for created_shared in join_all(futures).await {
+                merge_shared_objects(&mut shared, created_shared.unwrap());
+            }
+        }
+
+        for (_, call) in &calls.calls {
+            for arg in &call.arguments {
+                if let MoveCallArg::Shared(pattern, _) = arg {
+                    assert!(
+                        shared.keys().any(|tag| matches_type(pattern, tag)),
+                        "No shared object of type {pattern} was created for {}::{}",
+                        call.module,
+                        call.function
+                    );
+                }
+            }
+        }
+        self.shared_objects = Arc::new(shared);
+        self.calls = Some(Arc::new(calls));
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        self.payloads
+            .iter()
+            .map(|(gas, owned)| {
+                Box::<dyn Payload>::from(Box::new(MoveCallTestPayload {
+                    calls: self.calls.clone().unwrap(),
+                    shared_objects: self.shared_objects.clone(),
+                    owned_objects: owned.clone(),
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::transaction::{CallArg, Command};
+
+    /// The example of the module documentation
+    const WORKLOAD_FILE: &str = r#"
+package_path: ../my_package
+init:
+  - module: pool
+    function: create_shared_pool
+    arguments:
+      - owned: $package::pool::AdminCap
+  - module: pool
+    function: mint_position
+    per_sender: true
+    arguments:
+      - shared: { object_type: $package::pool::Pool }
+      - u64: 1000
+    transfer_results: 1
+calls:
+  - weight: 3
+    module: pool
+    function: swap
+    arguments:
+      - shared: { object_type: $package::pool::Pool }
+      - random_u64: { min: 1, max: 100 }
+  - weight: 1
+    module: pool
+    function: add_liquidity
+    arguments:
+      - shared: { object_type: $package::pool::Pool, mutable: false }
+      - owned: $package::pool::Position
+      - gas
+"#;
+
+    fn load(contents: &str) -> (tempfile::TempDir, Result<MoveCallWorkloadConfig>) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workload.yaml");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(contents.as_bytes())
+            .unwrap();
+        let config = MoveCallWorkloadConfig::load(&path);
+        (dir, config)
+    }
+
+    fn tag(package: ObjectID, name: &str) -> StructTag {
+        parse_sui_struct_tag(&format!("{package}::pool::{name}")).unwrap()
+    }
+
+    fn call(yaml: &str, package: ObjectID) -> MoveCall {
+        serde_yaml::from_str::<MoveCallConfig>(yaml)
+            .unwrap()
+            .resolve(package)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_load_workload_file() {
+        let (dir, config) = load(WORKLOAD_FILE);
+        let config = config.unwrap();
+        assert_eq!(config.package_path, dir.path().join("../my_package"));
+
+        let package = ObjectID::random();
+        let resolved = config.resolve(package).unwrap();
+        let per_sender: Vec<_> = resolved.init.iter().map(|(_, p)| *p).collect();
+        assert_eq!(per_sender, vec![false, true]);
+        let weights: Vec<_> = resolved.calls.iter().map(|(w, _)| *w).collect();
+        assert_eq!(weights, vec![3, 1]);
+
+        let (create_pool, _) = &resolved.init[0];
+        assert_eq!(create_pool.package, package);
+        assert_eq!(create_pool.module.as_str(), "pool");
+        assert_eq!(create_pool.function.as_str(), "create_shared_pool");
+        assert!(matches!(
+            &create_pool.arguments[..],
+            [MoveCallArg::Owned(t)] if *t == tag(package, "AdminCap")
+        ));
+        let (_, mint_position) = &resolved.init[1];
+        assert_eq!(mint_position.transfer_results, 1);
+        assert!(matches!(
+            &mint_position.arguments[..],
+            [MoveCallArg::Shared(t, true), MoveCallArg::Pure(bytes)]
+                if *t == tag(package, "Pool") && *bytes == bcs::to_bytes(&1000u64).unwrap()
+        ));
+
+        let (_, swap) = &resolved.calls[0];
+        assert_eq!(swap.transfer_results, 0);
+        assert!(matches!(
+            &swap.arguments[..],
+            [MoveCallArg::Shared(_, true), MoveCallArg::RandomU64(1, 100)]
+        ));
+        let (_, add_liquidity) = &resolved.calls[1];
+        assert!(matches!(
+            &add_liquidity.arguments[..],
+            [MoveCallArg::Shared(_, false), MoveCallArg::Owned(t), MoveCallArg::Gas]
+                if *t == tag(package, "Position")
+        ));
+    }
+
+    #[test]
+    fn test_resolve_pure_arguments() {
+        let address = SuiAddress::random_for_testing_only();
+        let cases = vec![
+            (ArgumentConfig::Bool(true), bcs::to_bytes(&true)),
+            (ArgumentConfig::U8(1), bcs::to_bytes(&1u8)),
+            (ArgumentConfig::U16(2), bcs::to_bytes(&2u16)),
+            (ArgumentConfig::U32(3), bcs::to_bytes(&3u32)),
+            (ArgumentConfig::U64(4), bcs::to_bytes(&4u64)),
+            (ArgumentConfig::Address(address), bcs::to_bytes(&address)),
+            (
+                ArgumentConfig::String("sui".to_string()),
+                bcs::to_bytes("sui"),
+            ),
+            (
+                ArgumentConfig::Bytes(vec![1, 2]),
+                bcs::to_bytes(&vec![1u8, 2]),
+            ),
+        ];
+        for (arg, expected) in cases {
+            let MoveCallArg::Pure(bytes) = arg.resolve("0x1").unwrap() else {
+                panic!("{arg:?} is not a pure value");
+            };
+            assert_eq!(bytes, expected.unwrap(), "{arg:?}");
+        }
+    }
+
+    #[test]
+    fn test_object_arguments() {
+        let package = ObjectID::random();
+        let pool_id = ObjectID::random();
+        // Patterns without type arguments match objects with any
+        let shared: SharedObjects = [(
+            parse_sui_struct_tag(&format!("{package}::pool::Pool<u64>")).unwrap(),
+            vec![(pool_id, SequenceNumber::from_u64(3))],
+        )]
+        .into();
+        let mut owned: OwnedObjects = [(
+            tag(package, "Position"),
+            vec![random_object_ref(), random_object_ref()],
+        )]
+        .into();
+        let add_liquidity = call(
+            r#"
+module: pool
+function: add_liquidity
+arguments:
+  - shared: { object_type: $package::pool::Pool, mutable: false }
+  - owned: $package::pool::Position
+  - owned: $package::pool::Position
+transfer_results: 2
+"#,
+            package,
+        );
+        assert!(add_liquidity.has_owned_objects(&owned));
+
+        let sender = SuiAddress::random_for_testing_only();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        add_liquidity
+            .add_to(
+                &mut builder,
+                sender,
+                &shared,
+                &owned,
+                &mut rand::thread_rng(),
+            )
+            .unwrap();
+        let pt = builder.finish();
+        let [shared_input, first, second, CallArg::Pure(_)] = &pt.inputs[..] else {
+            panic!("Unexpected inputs {:?}", pt.inputs);
+        };
+        assert_eq!(
+            *shared_input,
+            CallArg::Object(ObjectArg::SharedObject {
+                id: pool_id,
+                initial_shared_version: SequenceNumber::from_u64(3),
+                mutable: false,
+            })
+        );
+        assert!(matches!(
+            first,
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(_))
+        ));
+        assert!(matches!(
+            second,
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(_))
+        ));
+        // The same object is not passed twice
+        assert_ne!(first, second);
+        assert!(matches!(
+            &pt.commands[..],
+            [Command::MoveCall(call), Command::TransferObjects(results, _)]
+                if call.package == package && results.len() == 2
+        ));
+
+        // Calls are skipped, and can't be made, without enough owned objects of each type
+        owned.values_mut().next().unwrap().pop();
+        assert!(!add_liquidity.has_owned_objects(&owned));
+        let mut builder = ProgrammableTransactionBuilder::new();
+        assert!(add_liquidity
+            .add_to(
+                &mut builder,
+                sender,
+                &shared,
+                &owned,
+                &mut rand::thread_rng(),
+            )
+            .is_err());
+        let mut builder = ProgrammableTransactionBuilder::new();
+        assert!(add_liquidity
+            .add_to(
+                &mut builder,
+                sender,
+                &SharedObjects::new(),
+                &OwnedObjects::new(),
+                &mut rand::thread_rng(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_reject_malformed_workload_files() {
+        let call = |weight: u32, arguments: &str| {
+            format!(
+                "package_path: .\ncalls:\n  - weight: {weight}\n    module: pool\n    function: swap\n    arguments: {arguments}\n"
+            )
+        };
+        let files = vec![
+            // No call of non zero weight which takes no owned objects
+            "package_path: .\ncalls: []\n".to_string(),
+            call(0, "[]"),
+            call(1, "[{ owned: $package::pool::Position }]"),
+            // Missing fields
+            "calls: []\n".to_string(),
+            "package_path: .\n".to_string(),
+            // Unknown or out of range arguments
+            call(1, "[{ u128: 1 }]"),
+            call(1, "[{ u8: 256 }]"),
+            call(1, "[{ shared: { mutable: true } }]"),
+            // Empty random range
+            call(1, "[{ random_u64: { min: 2, max: 1 } }]"),
+            // Unparsable types
+            call(1, "[{ owned: $package::pool }]"),
+            call(1, "[{ shared: { object_type: pool::Pool } }]"),
+        ];
+        for file in files {
+            assert!(load(&file).1.is_err(), "{file}");
+        }
+
+        // Invalid identifiers and type arguments
+        let (_, config) =
+            load("package_path: .\ncalls:\n  - weight: 1\n    module: 1pool\n    function: swap\n");
+        assert!(config.is_err());
+        let (_, config) = load(
+            "package_path: .\ncalls:\n  - weight: 1\n    module: pool\n    function: swap\n    type_arguments: [\"u64<\"]\n",
+        );
+        assert!(config.is_err());
+        // Zero weight calls are fine alongside others
+        let (_, config) = load(&format!(
+            "{}  - weight: 0\n    module: pool\n    function: swap\n",
+            call(1, "[gas]")
+        ));
+        assert_eq!(config.unwrap().calls.len(), 2);
+    }
+}
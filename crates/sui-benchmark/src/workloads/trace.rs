@@ -0,0 +1,914 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A workload replaying the traffic recorded in a range of checkpoints. Recorded transactions are
+//! not re-executed as they are, since their inputs do not exist on the benchmark network. Instead,
+//! each one is reduced to its shape (the kinds of its commands, the shared objects it contends
+//! on, and the sizes of the objects it creates), and structurally equivalent transactions are
+//! built against objects created by the workload.
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::publish_basics_package;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_BUDGET, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ProgrammableTransactionBuilder, ValidatorProxy};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::{stream, StreamExt, TryStreamExt};
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_archival::{read_checkpoint_files, read_manifest, FileType};
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+use sui_types::crypto::get_key_pair;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::object::{Object, Owner};
+use sui_types::transaction::{
+    Argument, CallArg, Command, ObjectArg, Transaction, TransactionData, TransactionDataAPI,
+    TransactionKind,
+};
+use sui_types::utils::to_sender_signed_transaction;
+use tracing::{error, info};
+
+/// Number of checkpoints fetched concurrently from the REST API
+const REST_FETCH_CONCURRENCY: usize = 16;
+
+// Storage price and storage units per byte, used to estimate the size of the objects created by
+// recorded transactions when the trace does not include objects, as in a checkpoint archive
+const ESTIMATED_STORAGE_PRICE: u64 = 76;
+const STORAGE_UNITS_PER_BYTE: u64 = 100;
+
+// Bounds on the size of the objects created by replayed transactions. Objects are filled byte by
+// byte in Move, so large objects are capped to keep the computation cost of a transaction bounded.
+const MIN_OBJECT_SIZE: u64 = 34;
+const MAX_OBJECT_SIZE: u64 = 16 * 1024;
+
+/// Where to read the checkpoints of a trace from
+#[derive(Debug, Clone)]
+pub enum TraceSource {
+    /// A checkpoint archive in a local directory
+    Archive(PathBuf),
+    /// The REST API of a fullnode, which also serves the objects written by each transaction
+    Rest(String),
+}
+
+/// The structure of a recorded programmable transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionShape {
+    pub move_calls: usize,
+    pub transfer_objects: usize,
+    pub split_coins: usize,
+    pub merge_coins: usize,
+    pub make_move_vecs: usize,
+    /// Owned objects taken as input, other than gas
+    pub owned_inputs: usize,
+    /// Shared objects taken as input, by their contention rank in the trace (0 being the shared
+    /// object accessed by the most transactions), and whether they are taken mutably
+    pub shared_objects: Vec<(usize, bool)>,
+    /// Size in bytes of each created object
+    pub created_object_sizes: Vec<u64>,
+}
+
+/// The mix of transaction shapes in a range of checkpoints, and the rate at which they were
+/// executed
+#[derive(Debug)]
+pub struct TraceProfile {
+    /// One shape per recorded programmable transaction, so that sampling them uniformly
+    /// reproduces the mix of the trace
+    pub shapes: Vec<TransactionShape>,
+    /// Number of distinct shared objects accessed in the trace
+    pub num_shared_objects: usize,
+    pub transactions_per_second: f64,
+}
+
+struct TraceCheckpoint {
+    timestamp_ms: u64,
+    transactions: Vec<TraceTransaction>,
+}
+
+struct TraceTransaction {
+    transaction: Transaction,
+    effects: TransactionEffects,
+    /// Objects written by the transaction, if the source provides them
+    output_objects: Option<Vec<Object>>,
+}
+
+impl TraceProfile {
+    /// Derive the profile of checkpoints `start..=end` in `source`
+    pub async fn load(source: &TraceSource, start: u64, end: u64) -> Result<Self> {
+        if start > end {
+            bail!("Invalid checkpoint range {start}..={end}");
+        }
+        let checkpoints = match source {
+            TraceSource::Archive(path) => read_archive(path, start, end).await?,
+            TraceSource::Rest(url) => read_rest(url, start, end).await?,
+        };
+        Self::from_checkpoints(&checkpoints)
+    }
+
+    fn from_checkpoints(checkpoints: &[TraceCheckpoint]) -> Result<Self> {
+        let mut shapes = vec![];
+        let mut shared_inputs = vec![];
+        let mut num_publishes = 0;
+        for tx in checkpoints.iter().flat_map(|c| &c.transactions) {
+            let TransactionKind::ProgrammableTransaction(pt) =
+                tx.transaction.data().transaction_data().kind()
+            else {
+                // System transactions are not part of the traffic
+                continue;
+            };
+
+            let mut shape = TransactionShape::default();
+            for command in &pt.commands {
+                match command {
+                    Command::MoveCall(_) => shape.move_calls += 1,
+                    Command::TransferObjects(..) => shape.transfer_objects += 1,
+                    Command::SplitCoins(..) => shape.split_coins += 1,
+                    Command::MergeCoins(..) => shape.merge_coins += 1,
+                    Command::MakeMoveVec(..) => shape.make_move_vecs += 1,
+                    Command::Publish(..) | Command::Upgrade(..) => num_publishes += 1,
+                }
+            }
+
+            let mut shared = vec![];
+            for input in &pt.inputs {
+                match input {
+                    CallArg::Object(ObjectArg::ImmOrOwnedObject(_) | ObjectArg::Receiving(_)) => {
+                        shape.owned_inputs += 1
+                    }
+                    CallArg::Object(ObjectArg::SharedObject { id, mutable, .. }) => {
+                        shared.push((*id, *mutable))
+                    }
+                    CallArg::Pure(_) => (),
+                }
+            }
+
+            shape.created_object_sizes = created_object_sizes(tx);
+            shapes.push(shape);
+            shared_inputs.push(shared);
+        }
+
+        if shapes.is_empty() {
+            bail!("No programmable transactions in the trace");
+        }
+        if num_publishes > 0 {
+            info!(
+                "{} publish and upgrade commands in the trace are not replayed",
+                num_publishes
+            );
+        }
+
+        // Rank shared objects by the number of transactions accessing them
+        let mut accesses: HashMap<ObjectID, usize> = HashMap::new();
+        for (id, _) in shared_inputs.iter().flatten() {
+            *accesses.entry(*id).or_default() += 1;
+        }
+        let mut by_contention: Vec<_> = accesses.into_iter().collect();
+        by_contention.sort_by(|(id1, n1), (id2, n2)| n2.cmp(n1).then(id1.cmp(id2)));
+        let ranks: HashMap<ObjectID, usize> = by_contention
+            .iter()
+            .enumerate()
+            .map(|(rank, (id, _))| (*id, rank))
+            .collect();
+        for (shape, shared) in shapes.iter_mut().zip(shared_inputs) {
+            shape.shared_objects = shared
+                .into_iter()
+                .map(|(id, mutable)| (ranks[&id], mutable))
+                .collect();
+        }
+
+        // The first checkpoint marks the start of the trace, so a single checkpoint is taken to
+        // span a second
+        let first = checkpoints.first().map_or(0, |c| c.timestamp_ms);
+        let last = checkpoints.last().map_or(0, |c| c.timestamp_ms);
+        let duration_secs = (last.saturating_sub(first)).max(1_000) as f64 / 1_000.0;
+
+        Ok(Self {
+            transactions_per_second: shapes.len() as f64 / duration_secs,
+            num_shared_objects: ranks.len(),
+            shapes,
+        })
+    }
+}
+
+/// Sizes of the objects created by `tx`, read from its output objects if the trace has them, or
+/// else estimated from its storage cost, shared evenly by all the objects it wrote
+fn created_object_sizes(tx: &TraceTransaction) -> Vec<u64> {
+    let created = tx.effects.created();
+    match &tx.output_objects {
+        Some(objects) => {
+            let sizes: BTreeMap<ObjectID, u64> = objects
+                .iter()
+                .filter_map(|o| Some((o.id(), o.data.try_as_move()?.contents().len() as u64)))
+                .collect();
+            created
+                .iter()
+                .filter_map(|((id, _, _), _)| sizes.get(id).copied())
+                .collect()
+        }
+        None => {
+            let written = (created.len() + tx.effects.mutated().len()).max(1) as u64;
+            let bytes = tx.effects.gas_cost_summary().storage_cost
+                / (ESTIMATED_STORAGE_PRICE * STORAGE_UNITS_PER_BYTE);
+            vec![bytes / written; created.len()]
+        }
+    }
+}
+
+async fn read_archive(archive_path: &Path, start: u64, end: u64) -> Result<Vec<TraceCheckpoint>> {
+    let store = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(archive_path.to_path_buf()),
+        ..Default::default()
+    }
+    .make()?;
+    let manifest = read_manifest(store.clone()).await?;
+    let mut files = manifest.files();
+    files.retain(|f| f.checkpoint_seq_range.start <= end && f.checkpoint_seq_range.end > start);
+    files.sort_by_key(|f| f.checkpoint_seq_range.start);
+    let (summaries, contents): (Vec<_>, Vec<_>) = files
+        .into_iter()
+        .partition(|f| f.file_type == FileType::CheckpointSummary);
+
+    let mut checkpoints = vec![];
+    for (summary_file, content_file) in summaries.iter().zip(&contents) {
+        for (summary, contents) in
+            read_checkpoint_files(summary_file, content_file, store.clone()).await?
+        {
+            if !(start..=end).contains(&summary.sequence_number) {
+                continue;
+            }
+            checkpoints.push(TraceCheckpoint {
+                timestamp_ms: summary.timestamp_ms,
+                transactions: contents
+                    .iter()
+                    .map(|data| TraceTransaction {
+                        transaction: data.transaction.clone(),
+                        effects: data.effects.clone(),
+                        output_objects: None,
+                    })
+                    .collect(),
+            });
+        }
+    }
+    if checkpoints.len() as u64 != end - start + 1 {
+        return Err(anyhow!(
+            "Archive at {:?} does not contain all checkpoints in {start}..={end}",
+            archive_path
+        ));
+    }
+    Ok(checkpoints)
+}
+
+async fn read_rest(url: &str, start: u64, end: u64) -> Result<Vec<TraceCheckpoint>> {
+    let client = sui_rest_api::Client::new(url);
+    stream::iter(start..=end)
+        .map(|seq| {
+            let client = client.clone();
+            async move {
+                let checkpoint = client.get_full_checkpoint(seq).await?;
+                Ok::<_, anyhow::Error>(TraceCheckpoint {
+                    timestamp_ms: checkpoint.checkpoint_summary.timestamp_ms,
+                    transactions: checkpoint
+                        .transactions
+                        .into_iter()
+                        .map(|tx| TraceTransaction {
+                            transaction: tx.transaction,
+                            effects: tx.effects,
+                            output_objects: Some(tx.output_objects),
+                        })
+                        .collect(),
+                })
+            }
+        })
+        .buffered(REST_FETCH_CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+#[derive(Debug)]
+pub struct TraceTestPayload {
+    profile: Arc<TraceProfile>,
+    basics_package_id: ObjectID,
+    adversarial_package_id: ObjectID,
+    /// Shared counters standing in for the shared objects of the trace, by contention rank
+    counters: Arc<Vec<(ObjectID, SequenceNumber)>>,
+    gas: Gas,
+    /// Objects owned by the sender other than gas, which stand in for owned inputs
+    owned_objects: BTreeMap<ObjectID, ObjectRef>,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for TraceTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "trace")
+    }
+}
+
+impl TraceTestPayload {
+    fn do_nothing(&self, builder: &mut ProgrammableTransactionBuilder) {
+        builder.programmable_move_call(
+            self.adversarial_package_id,
+            Identifier::new("adversarial").unwrap(),
+            Identifier::new("do_nothing").unwrap(),
+            vec![],
+            vec![],
+        );
+    }
+}
+
+impl Payload for TraceTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Trace tx failed... Status: {:?}", effects.status());
+        }
+
+        self.gas.0 = effects.gas_object().0;
+        let sender = Owner::AddressOwner(self.gas.1);
+        for (obj_ref, owner) in effects.created().into_iter().chain(effects.mutated()) {
+            if obj_ref.0 != self.gas.0 .0 && owner == sender {
+                self.owned_objects.insert(obj_ref.0, obj_ref);
+            }
+        }
+        for obj_ref in effects.deleted() {
+            self.owned_objects.remove(&obj_ref.0);
+        }
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let mut rng = rand::thread_rng();
+        let shape = self
+            .profile
+            .shapes
+            .choose(&mut rng)
+            .expect("Trace profile has at least one transaction");
+        let rgp = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let sender = self.gas.1;
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let mut move_calls = 0;
+        let mut num_commands = 0;
+
+        // Shared objects map to counters of the same contention rank. Ranks beyond the number of
+        // counters are the least contended, and are spread over all counters.
+        if !self.counters.is_empty() {
+            for (rank, mutable) in &shape.shared_objects {
+                let index = if *rank < self.counters.len() {
+                    *rank
+                } else {
+                    rng.gen_range(0..self.counters.len())
+                };
+                let (id, initial_shared_version) = self.counters[index];
+                let counter = builder
+                    .obj(ObjectArg::SharedObject {
+                        id,
+                        initial_shared_version,
+                        mutable: *mutable,
+                    })
+                    .unwrap();
+                let function = if *mutable { "increment" } else { "value" };
+                builder.programmable_move_call(
+                    self.basics_package_id,
+                    Identifier::new("counter").unwrap(),
+                    Identifier::new(function).unwrap(),
+                    vec![],
+                    vec![counter],
+                );
+                move_calls += 1;
+            }
+        }
+
+        // Created objects, and owned inputs, are transferred back to the sender
+        let mut to_transfer = vec![];
+        for size in &shape.created_object_sizes {
+            let size = builder
+                .pure((*size).clamp(MIN_OBJECT_SIZE, MAX_OBJECT_SIZE))
+                .unwrap();
+            to_transfer.push(builder.programmable_move_call(
+                self.adversarial_package_id,
+                Identifier::new("adversarial").unwrap(),
+                Identifier::new("create_object_with_size").unwrap(),
+                vec![],
+                vec![size],
+            ));
+            move_calls += 1;
+        }
+        for _ in move_calls..shape.move_calls {
+            self.do_nothing(&mut builder);
+        }
+        num_commands += shape.move_calls.max(move_calls);
+
+        let mut coins: Vec<Argument> = (0..shape.split_coins)
+            .map(|_| {
+                let amount = builder.pure(1u64).unwrap();
+                builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]))
+            })
+            .collect();
+        for _ in 0..shape.merge_coins {
+            if let Some(coin) = coins.pop() {
+                builder.command(Command::MergeCoins(Argument::GasCoin, vec![coin]));
+                num_commands += 1;
+            }
+        }
+        num_commands += shape.split_coins;
+        for _ in 0..shape.make_move_vecs {
+            let element = builder.pure(0u64).unwrap();
+            builder.command(Command::MakeMoveVec(Some(TypeTag::U64), vec![element]));
+        }
+        num_commands += shape.make_move_vecs;
+
+        for obj_ref in self.owned_objects.values().take(shape.owned_inputs) {
+            to_transfer.push(builder.obj(ObjectArg::ImmOrOwnedObject(*obj_ref)).unwrap());
+        }
+        to_transfer.extend(coins);
+        if !to_transfer.is_empty() {
+            let num_transfers = shape.transfer_objects.clamp(1, to_transfer.len());
+            for objects in to_transfer.chunks(to_transfer.len().div_ceil(num_transfers)) {
+                builder.transfer_args(sender, objects.to_vec());
+                num_commands += 1;
+            }
+        }
+        // Transactions made only of commands that are not replayed still need a command
+        if num_commands == 0 {
+            self.do_nothing(&mut builder);
+        }
+
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![self.gas.0],
+            builder.finish(),
+            MAX_BUDGET,
+            rgp,
+        );
+        to_sender_signed_transaction(data, self.gas.2.as_ref())
+    }
+}
+
+#[derive(Debug)]
+pub struct TraceWorkloadBuilder {
+    profile: Arc<TraceProfile>,
+    num_counters: u64,
+    num_payloads: u64,
+}
+
+impl TraceWorkloadBuilder {
+    pub fn from(
+        profile: TraceProfile,
+        speedup_pct: u64,
+        max_shared_objects: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps =
+            (profile.transactions_per_second * speedup_pct as f64 / 100.0).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            info!(
+                "Replaying {} transactions over {} shared objects at {} tps",
+                profile.shapes.len(),
+                profile.num_shared_objects,
+                target_qps
+            );
+            let workload_params = WorkloadParams {
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+                group,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(TraceWorkloadBuilder {
+                    num_counters: (profile.num_shared_objects as u64).min(max_shared_objects),
+                    profile: Arc::new(profile),
+                    num_payloads: max_ops,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for TraceWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coins for publishing the basics and adversarial packages, and creating counters
+        (0..self.num_counters + 2)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        (0..self.num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(TraceWorkload {
+            profile: self.profile.clone(),
+            basics_package_id: None,
+            adversarial_package_id: None,
+            counters: Arc::new(vec![]),
+            init_gas,
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct TraceWorkload {
+    profile: Arc<TraceProfile>,
+    basics_package_id: Option<ObjectID>,
+    adversarial_package_id: Option<ObjectID>,
+    counters: Arc<Vec<(ObjectID, SequenceNumber)>>,
+    init_gas: Vec<Gas>,
+    payload_gas: Vec<Gas>,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for TraceWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.basics_package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let [basics_gas, adversarial_gas, counters_gas @ ..] = self.init_gas.as_slice() else {
+            panic!("Not enough gas to initialize trace workload");
+        };
+
+        info!("Publishing basics package");
+        self.basics_package_id = Some(
+            publish_basics_package(
+                basics_gas.0,
+                proxy.clone(),
+                basics_gas.1,
+                &basics_gas.2,
+                gas_price,
+            )
+            .await
+            .0,
+        );
+
+        info!("Publishing adversarial package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/workloads/data/adversarial");
+        let transaction =
+            TestTransactionBuilder::new(adversarial_gas.1, adversarial_gas.0, gas_price)
+                .publish(path)
+                .build_and_sign(adversarial_gas.2.as_ref());
+        let effects = proxy.execute_transaction_block(transaction).await.unwrap();
+        self.adversarial_package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|((id, _, _), _)| *id);
+
+        info!("Creating {} shared counters", counters_gas.len());
+        let mut futures = vec![];
+        for (gas, sender, keypair) in counters_gas {
+            let transaction = TestTransactionBuilder::new(*sender, *gas, gas_price)
+                .call_counter_create(self.basics_package_id.unwrap())
+                .build_and_sign(keypair.as_ref());
+            let proxy_ref = proxy.clone();
+            futures.push(async move {
+                let effects = proxy_ref
+                    .execute_transaction_block(transaction)
+                    .await
+                    .expect("Failed to create shared counter!");
+                let ((id, version, _), _) = effects.created()[0];
+                (id, version)
+            });
+        }
+        self.counters = Arc::new(join_all(futures).await);
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        self.payload_gas
+            .iter()
+            .map(|gas| {
+                Box::<dyn Payload>::from(Box::new(TraceTestPayload {
+                    profile: self.profile.clone(),
+                    basics_package_id: self.basics_package_id.unwrap(),
+                    adversarial_package_id: self.adversarial_package_id.unwrap(),
+                    counters: self.counters.clone(),
+                    gas: gas.clone(),
+                    owned_objects: BTreeMap::new(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{random_object_ref, ObjectDigest, SuiAddress, TransactionDigest};
+    use sui_types::crypto::AccountKeyPair;
+    use sui_types::execution_status::ExecutionStatus;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::transaction::VerifiedTransaction;
+
+    fn programmable(build: impl FnOnce(&mut ProgrammableTransactionBuilder)) -> Transaction {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        build(&mut builder);
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![random_object_ref()],
+            builder.finish(),
+            MAX_BUDGET,
+            1000,
+        );
+        to_sender_signed_transaction(data, &keypair)
+    }
+
+    fn shared(
+        builder: &mut ProgrammableTransactionBuilder,
+        id: ObjectID,
+        mutable: bool,
+    ) -> Argument {
+        builder
+            .obj(ObjectArg::SharedObject {
+                id,
+                initial_shared_version: SequenceNumber::from_u64(1),
+                mutable,
+            })
+            .unwrap()
+    }
+
+    fn owned(builder: &mut ProgrammableTransactionBuilder) -> Argument {
+        builder
+            .obj(ObjectArg::ImmOrOwnedObject(random_object_ref()))
+            .unwrap()
+    }
+
+    fn move_call(builder: &mut ProgrammableTransactionBuilder, arguments: Vec<Argument>) {
+        builder.programmable_move_call(
+            ObjectID::random(),
+            Identifier::new("module").unwrap(),
+            Identifier::new("function").unwrap(),
+            vec![],
+            arguments,
+        );
+    }
+
+    fn effects(created: &[ObjectID], num_mutated: usize, storage_cost: u64) -> TransactionEffects {
+        let owner = Owner::AddressOwner(SuiAddress::ZERO);
+        TransactionEffects::new_from_execution_v1(
+            ExecutionStatus::Success,
+            0,
+            GasCostSummary::new(0, storage_cost, 0, 0),
+            vec![],
+            vec![],
+            TransactionDigest::random(),
+            created
+                .iter()
+                .map(|id| {
+                    (
+                        (*id, SequenceNumber::from_u64(1), ObjectDigest::random()),
+                        owner,
+                    )
+                })
+                .collect(),
+            (0..num_mutated)
+                .map(|_| (random_object_ref(), owner))
+                .collect(),
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            (random_object_ref(), owner),
+            None,
+            vec![],
+        )
+    }
+
+    fn recorded(transaction: Transaction, effects: TransactionEffects) -> TraceTransaction {
+        TraceTransaction {
+            transaction,
+            effects,
+            output_objects: None,
+        }
+    }
+
+    fn consensus_commit_prologue() -> TraceTransaction {
+        recorded(
+            VerifiedTransaction::new_consensus_commit_prologue(0, 0, 0).into_inner(),
+            effects(&[], 0, 0),
+        )
+    }
+
+    #[test]
+    fn test_transaction_mix() {
+        let (x, y) = (ObjectID::random(), ObjectID::random());
+        // Each object written by the first transaction takes 100 bytes of storage
+        let storage_cost = 2 * 100 * ESTIMATED_STORAGE_PRICE * STORAGE_UNITS_PER_BYTE;
+        let split_and_transfer = programmable(|builder| {
+            let counter = shared(builder, x, true);
+            move_call(builder, vec![counter]);
+            let amount = builder.pure(1u64).unwrap();
+            let coin = builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+            builder.transfer_args(SuiAddress::ZERO, vec![coin]);
+        });
+        let call_and_make_vec = programmable(|builder| {
+            let read = shared(builder, x, false);
+            let write = shared(builder, y, true);
+            let object = owned(builder);
+            move_call(builder, vec![read, write, object]);
+            let element = builder.pure(0u64).unwrap();
+            builder.command(Command::MakeMoveVec(Some(TypeTag::U64), vec![element]));
+        });
+        let merge_and_publish = programmable(|builder| {
+            let coin = owned(builder);
+            builder.command(Command::MergeCoins(Argument::GasCoin, vec![coin]));
+            builder.publish_upgradeable(vec![], vec![]);
+        });
+        let checkpoints = vec![
+            TraceCheckpoint {
+                timestamp_ms: 0,
+                transactions: vec![
+                    consensus_commit_prologue(),
+                    recorded(
+                        split_and_transfer,
+                        effects(&[ObjectID::random()], 1, storage_cost),
+                    ),
+                    recorded(call_and_make_vec, effects(&[], 2, 0)),
+                ],
+            },
+            TraceCheckpoint {
+                timestamp_ms: 2_000,
+                transactions: vec![
+                    consensus_commit_prologue(),
+                    recorded(merge_and_publish, effects(&[], 1, 0)),
+                ],
+            },
+        ];
+
+        let profile = TraceProfile::from_checkpoints(&checkpoints).unwrap();
+        // System transactions are skipped, and publish commands are not replayed
+        assert_eq!(
+            profile.shapes,
+            vec![
+                TransactionShape {
+                    move_calls: 1,
+                    transfer_objects: 1,
+                    split_coins: 1,
+                    shared_objects: vec![(0, true)],
+                    created_object_sizes: vec![100],
+                    ..Default::default()
+                },
+                TransactionShape {
+                    move_calls: 1,
+                    make_move_vecs: 1,
+                    owned_inputs: 1,
+                    // `x` is accessed by two transactions, so it is the most contended
+                    shared_objects: vec![(0, false), (1, true)],
+                    ..Default::default()
+                },
+                TransactionShape {
+                    merge_coins: 1,
+                    owned_inputs: 1,
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(profile.num_shared_objects, 2);
+        assert_eq!(profile.transactions_per_second, 1.5);
+    }
+
+    #[test]
+    fn test_created_object_sizes_from_output_objects() {
+        let (created, unknown) = (ObjectID::random(), ObjectID::random());
+        let mut tx = recorded(
+            programmable(|builder| move_call(builder, vec![])),
+            effects(&[created, unknown], 0, 0),
+        );
+        // Objects missing from the outputs have no known size
+        tx.output_objects = Some(vec![Object::with_id_owner_gas_for_testing(
+            created,
+            SuiAddress::ZERO,
+            1,
+        )]);
+        // A gas coin is its id and balance
+        assert_eq!(created_object_sizes(&tx), vec![40]);
+    }
+
+    #[test]
+    fn test_single_checkpoint_spans_a_second() {
+        let checkpoints = vec![TraceCheckpoint {
+            timestamp_ms: 1_000,
+            transactions: (0..3)
+                .map(|_| {
+                    recorded(
+                        programmable(|builder| move_call(builder, vec![])),
+                        effects(&[], 0, 0),
+                    )
+                })
+                .collect(),
+        }];
+        let profile = TraceProfile::from_checkpoints(&checkpoints).unwrap();
+        assert_eq!(profile.transactions_per_second, 3.0);
+        assert_eq!(profile.num_shared_objects, 0);
+    }
+
+    #[tokio::test]
+    async fn test_empty_trace() {
+        assert!(TraceProfile::from_checkpoints(&[]).is_err());
+        // Only system transactions, which are not part of the traffic
+        let checkpoints = vec![TraceCheckpoint {
+            timestamp_ms: 0,
+            transactions: vec![consensus_commit_prologue()],
+        }];
+        assert!(TraceProfile::from_checkpoints(&checkpoints).is_err());
+        // The range is checked before the source is read
+        let source = TraceSource::Archive(PathBuf::from("/nonexistent"));
+        let err = TraceProfile::load(&source, 2, 1).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid checkpoint range"));
+    }
+
+    #[tokio::test]
+    async fn test_workload_builder_rate() {
+        let profile = || TraceProfile {
+            shapes: vec![TransactionShape::default()],
+            num_shared_objects: 10,
+            transactions_per_second: 0.5,
+        };
+        let duration = Interval::Count(100);
+
+        // Rates below a transaction per second are rounded up
+        let info = TraceWorkloadBuilder::from(profile(), 100, 4, 2, 5, duration, 0)
+            .expect("Workload with a non zero rate");
+        assert_eq!(info.workload_params.target_qps, 1);
+        assert_eq!(info.workload_params.max_ops, 5);
+        // Only the most contended shared objects get their own counter, besides the gas for the
+        // two packages
+        assert_eq!(
+            info.workload_builder
+                .generate_coin_config_for_init()
+                .await
+                .len(),
+            4 + 2
+        );
+
+        let info = TraceWorkloadBuilder::from(profile(), 1_000, 100, 2, 1, duration, 0).unwrap();
+        assert_eq!(info.workload_params.target_qps, 5);
+        assert_eq!(
+            info.workload_builder
+                .generate_coin_config_for_init()
+                .await
+                .len(),
+            10 + 2
+        );
+
+        // No workload without a rate or workers
+        assert!(TraceWorkloadBuilder::from(profile(), 0, 4, 2, 5, duration, 0).is_none());
+        assert!(TraceWorkloadBuilder::from(profile(), 100, 4, 0, 5, duration, 0).is_none());
+    }
+}
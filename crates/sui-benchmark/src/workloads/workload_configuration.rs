@@ -10,14 +10,16 @@ use crate::workloads::delegation::DelegationWorkloadBuilder;
 use crate::workloads::shared_counter::SharedCounterWorkloadBuilder;
 use crate::workloads::transfer_object::TransferObjectWorkloadBuilder;
 use crate::workloads::{GroupID, WorkloadBuilderInfo, WorkloadInfo};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::info;
 
 use super::adversarial::{AdversarialPayloadCfg, AdversarialWorkloadBuilder};
+use super::move_call::{MoveCallWorkloadBuilder, MoveCallWorkloadConfig};
 use super::shared_object_deletion::SharedCounterDeletionWorkloadBuilder;
+use super::trace::{TraceProfile, TraceSource, TraceWorkloadBuilder};
 
 pub struct WorkloadConfiguration;
 
@@ -39,11 +41,13 @@ impl WorkloadConfiguration {
                 delegation,
                 batch_payment,
                 adversarial,
+                move_call,
                 shared_counter_hotness_factor,
                 num_shared_counters,
                 shared_counter_max_tip,
                 batch_payment_size,
                 adversarial_cfg,
+                move_call_config,
                 target_qps,
                 num_workers,
                 in_flight_ratio,
@@ -53,6 +57,13 @@ impl WorkloadConfiguration {
                     "Number of benchmark groups to run: {}",
                     num_of_benchmark_groups
                 );
+                let move_call_config = match move_call_config {
+                    Some(path) => Some(MoveCallWorkloadConfig::load(&path)?),
+                    None if move_call.iter().any(|weight| *weight > 0) => {
+                        bail!("--move-call-config must be set to run the move call workload")
+                    }
+                    None => None,
+                };
 
                 // Creating the workload builders for each benchmark group. The workloads for each
                 // benchmark group will run in the same time for the same duration.
@@ -69,6 +80,8 @@ impl WorkloadConfiguration {
                         batch_payment[i],
                         adversarial[i],
                         AdversarialPayloadCfg::from_str(&adversarial_cfg[i]).unwrap(),
+                        move_call[i],
+                        move_call_config.clone(),
                         batch_payment_size[i],
                         shared_counter_hotness_factor[i],
                         num_shared_counters.as_ref().map(|n| n[i]),
@@ -90,6 +103,44 @@ impl WorkloadConfiguration {
                 )
                 .await
             }
+            RunSpec::Trace {
+                start_checkpoint,
+                end_checkpoint,
+                archive_path,
+                rest_url,
+                speedup_pct,
+                max_shared_objects,
+                num_workers,
+                in_flight_ratio,
+            } => {
+                let source = match (archive_path, rest_url) {
+                    (Some(path), None) => TraceSource::Archive(path),
+                    (None, Some(url)) => TraceSource::Rest(url),
+                    _ => bail!("Exactly one of --archive-path and --rest-url must be set"),
+                };
+                info!(
+                    "Reading checkpoints {}..={} from {:?}",
+                    start_checkpoint, end_checkpoint, source
+                );
+                let profile = TraceProfile::load(&source, start_checkpoint, end_checkpoint).await?;
+                let workload_builder = TraceWorkloadBuilder::from(
+                    profile,
+                    speedup_pct,
+                    max_shared_objects,
+                    num_workers,
+                    in_flight_ratio,
+                    Interval::from_str("unbounded").unwrap(),
+                    0,
+                );
+
+                Self::build(
+                    vec![workload_builder],
+                    bank,
+                    system_state_observer,
+                    opts.gas_request_chunk_size,
+                )
+                .await
+            }
         }
     }
 
@@ -146,6 +197,8 @@ impl WorkloadConfiguration {
         shared_deletion_weight: u32,
         adversarial_weight: u32,
         adversarial_cfg: AdversarialPayloadCfg,
+        move_call_weight: u32,
+        move_call_config: Option<MoveCallWorkloadConfig>,
         batch_payment_size: u32,
         shared_counter_hotness_factor: u32,
         num_shared_counters: Option<u64>,
@@ -159,7 +212,8 @@ impl WorkloadConfiguration {
             + transfer_object_weight
             + delegation_weight
             + batch_payment_weight
-            + adversarial_weight;
+            + adversarial_weight
+            + move_call_weight;
         let reference_gas_price = system_state_observer.state.borrow().reference_gas_price;
         let mut workload_builders = vec![];
         let shared_workload = SharedCounterWorkloadBuilder::from(
@@ -226,6 +280,18 @@ impl WorkloadConfiguration {
             workload_group,
         );
         workload_builders.push(adversarial_workload);
+        if let Some(config) = move_call_config {
+            let move_call_workload = MoveCallWorkloadBuilder::from(
+                move_call_weight as f32 / total_weight as f32,
+                target_qps,
+                num_workers,
+                in_flight_ratio,
+                config,
+                duration,
+                workload_group,
+            );
+            workload_builders.push(move_call_workload);
+        }
 
         workload_builders
     }
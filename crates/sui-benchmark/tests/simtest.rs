@@ -543,6 +543,8 @@ mod test {
             shared_object_deletion_weight,
             adversarial_weight,
             adversarial_cfg,
+            0,
+            None,
             batch_payment_size,
             shared_counter_hotness_factor,
             num_shared_counters,
@@ -7,6 +7,7 @@ use crate::p2p::P2pConfig;
 use crate::transaction_deny_config::TransactionDenyConfig;
 use crate::Config;
 use anyhow::Result;
+use move_core_types::language_storage::StructTag;
 use narwhal_config::Parameters as ConsensusParameters;
 use once_cell::sync::OnceCell;
 use rand::rngs::OsRng;
@@ -29,6 +30,8 @@ use sui_types::crypto::NetworkKeyPair;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::{get_key_pair_from_rng, AccountKeyPair, AuthorityKeyPair};
 use sui_types::multiaddr::Multiaddr;
+use sui_types::object::{Object, Owner};
+use sui_types::sui_serde::SuiStructTag;
 use tracing::info;
 
 // Default max number of concurrent requests served
@@ -516,7 +519,7 @@ impl Default for CheckpointExecutorConfig {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AuthorityStorePruningConfig {
     /// number of the latest epoch dbs to retain
@@ -543,6 +546,10 @@ pub struct AuthorityStorePruningConfig {
     /// number of epochs to keep the latest version of transactions and effects for
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num_epochs_to_retain_for_checkpoints: Option<u64>,
+    /// objects matching any of these rules keep all their versions, regardless of
+    /// `num_epochs_to_retain`. Only applies to the versions pruned after the rule was added.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub object_retention_rules: Vec<ObjectRetentionRule>,
 }
 
 impl Default for AuthorityStorePruningConfig {
@@ -559,6 +566,7 @@ impl Default for AuthorityStorePruningConfig {
             max_transactions_in_batch: 1000,
             periodic_compaction_threshold_days: None,
             num_epochs_to_retain_for_checkpoints: None,
+            object_retention_rules: vec![],
         }
     }
 }
@@ -578,6 +586,7 @@ impl AuthorityStorePruningConfig {
             max_transactions_in_batch: 1000,
             periodic_compaction_threshold_days: None,
             num_epochs_to_retain_for_checkpoints,
+            object_retention_rules: vec![],
         }
     }
     pub fn fullnode_config() -> Self {
@@ -594,6 +603,7 @@ impl AuthorityStorePruningConfig {
             max_transactions_in_batch: 1000,
             periodic_compaction_threshold_days: None,
             num_epochs_to_retain_for_checkpoints,
+            object_retention_rules: vec![],
         }
     }
 
@@ -615,6 +625,54 @@ impl AuthorityStorePruningConfig {
     }
 }
 
+/// Selects objects whose old versions are kept by the pruner.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ObjectRetentionRule {
+    /// Objects of the given Move type, e.g. `0x2::coin::Coin<0x2::sui::SUI>`. When no type
+    /// parameters are given, instances with any type parameters match.
+    Type(#[serde_as(as = "SuiStructTag")] StructTag),
+    /// The package itself and the objects of the types it defines. Types are identified by the
+    /// package that first defined them, so this is the original ID of upgraded packages.
+    Package(ObjectID),
+    /// Objects owned by the address, or by the object with this ID (e.g. dynamic fields).
+    Owner(SuiAddress),
+}
+
+impl ObjectRetentionRule {
+    pub fn matches(&self, object: &Object) -> bool {
+        match self {
+            Self::Type(tag) => object.type_().is_some_and(|type_| {
+                type_.address() == tag.address
+                    && type_.module() == tag.module.as_ident_str()
+                    && type_.name() == tag.name.as_ident_str()
+                    && (tag.type_params.is_empty() || type_.type_params() == tag.type_params)
+            }),
+            Self::Package(package) => {
+                object.id() == *package
+                    || object
+                        .type_()
+                        .is_some_and(|type_| ObjectID::from(type_.address()) == *package)
+            }
+            Self::Owner(owner) => match object.owner {
+                Owner::AddressOwner(address) | Owner::ObjectOwner(address) => address == *owner,
+                Owner::Shared { .. } | Owner::Immutable => false,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ObjectRetentionRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Type(tag) => write!(f, "type:{}", tag),
+            Self::Package(package) => write!(f, "package:{}", package),
+            Self::Owner(owner) => write!(f, "owner:{}", owner),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct MetricsConfig {
@@ -661,6 +719,10 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// When set, a full snapshot is only written for the epochs that are a multiple of this
+    /// interval, and an incremental snapshot against the previous epoch is written otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_snapshot_interval_epochs: Option<u64>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -893,8 +955,10 @@ mod tests {
     use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
     use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair};
 
-    use super::Genesis;
+    use super::{AuthorityStorePruningConfig, Genesis, ObjectRetentionRule};
     use crate::NodeConfig;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::object::Object;
 
     #[test]
     fn serialize_genesis_from_file() {
@@ -906,6 +970,39 @@ mod tests {
         assert_eq!(g, loaded_genesis);
     }
 
+    #[test]
+    fn object_retention_rules() {
+        let owner = SuiAddress::random_for_testing_only();
+        let config: AuthorityStorePruningConfig = serde_yaml::from_str(&format!(
+            r#"
+num-latest-epoch-dbs-to-retain: 3
+epoch-db-pruning-period-secs: 3600
+num-epochs-to-retain: 0
+max-checkpoints-in-batch: 10
+max-transactions-in-batch: 1000
+object-retention-rules:
+  - type: "0x2::coin::Coin"
+  - type: "0x2::coin::Coin<0x2::foo::FOO>"
+  - package: "0x2"
+  - owner: "{owner}"
+"#
+        ))
+        .unwrap();
+        let [any_coin, foo_coin, package, owned] = &config.object_retention_rules[..] else {
+            panic!("unexpected rules {:?}", config.object_retention_rules);
+        };
+        assert!(matches!(owned, ObjectRetentionRule::Owner(address) if *address == owner));
+
+        let gas = Object::with_id_owner_for_testing(ObjectID::random(), owner);
+        let other_gas = Object::immutable_with_id_for_testing(ObjectID::random());
+        assert!(any_coin.matches(&gas));
+        assert!(!foo_coin.matches(&gas));
+        assert!(package.matches(&gas));
+        assert!(owned.matches(&gas));
+        assert!(!owned.matches(&other_gas));
+        assert_eq!(foo_coin.to_string(), "type:0x2::coin::Coin<0x2::foo::FOO>");
+    }
+
     #[test]
     fn fullnode_template() {
         const TEMPLATE: &str = include_str!("../data/fullnode-template.yaml");
@@ -184,3 +184,42 @@ impl TransactionDenyConfigBuilder {
         self
     }
 }
+
+/// A deny rule added while the node is running, on top of the [TransactionDenyConfig].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DenyRule {
+    pub id: u64,
+    pub target: DenyTarget,
+    /// Unix timestamp in milliseconds from which the rule no longer applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_ms: Option<u64>,
+    /// Why the rule was added, for the operators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl DenyRule {
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        self.expires_at_ms
+            .is_some_and(|expires_at_ms| expires_at_ms <= now_ms)
+    }
+}
+
+/// What a [DenyRule] denies.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DenyTarget {
+    /// Transactions depending on the package, with the same semantics as `package_deny_list`.
+    Package(ObjectID),
+    /// Transactions calling the function directly.
+    MoveFunction {
+        package: ObjectID,
+        module: String,
+        function: String,
+    },
+    /// Transactions sent or sponsored by the address.
+    Address(SuiAddress),
+    /// Transactions using the object as input, be it owned, shared or received.
+    Object(ObjectID),
+}
@@ -132,6 +132,7 @@ use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::CheckpointStore;
 use crate::consensus_adapter::ConsensusAdapter;
+use crate::dynamic_deny_list::DynamicDenyList;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
@@ -636,7 +637,7 @@ pub struct AuthorityState {
     tx_execution_shutdown: Mutex<Option<oneshot::Sender<()>>>,
 
     pub metrics: Arc<AuthorityMetrics>,
-    _pruner: AuthorityStorePruner,
+    pruner: AuthorityStorePruner,
     _authority_per_epoch_pruner: AuthorityPerEpochStorePruner,
 
     /// Take db checkpoints af different dbs
@@ -645,15 +646,20 @@ pub struct AuthorityState {
     /// Config controlling what kind of expensive safety checks to perform.
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
 
-    transaction_deny_config: TransactionDenyConfig,
+    /// Reloadable at runtime, see `update_transaction_deny_config`.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
 
-    certificate_deny_config: CertificateDenyConfig,
+    /// Reloadable at runtime, see `update_certificate_deny_config`.
+    certificate_deny_config: ArcSwap<CertificateDenyConfig>,
+
+    /// Deny rules managed at runtime, on top of `transaction_deny_config`.
+    dynamic_deny_list: DynamicDenyList,
 
     /// Config for state dumping on forks
     debug_dump_config: StateDebugDumpConfig,
 
     /// Config for when we consider the node overloaded.
-    overload_threshold_config: OverloadThresholdConfig,
+    overload_threshold_config: ArcSwap<OverloadThresholdConfig>,
 }
 
 /// The authority state encapsulates all state, drives execution, and ensures safety.
@@ -680,7 +686,29 @@ impl AuthorityState {
     }
 
     pub fn max_txn_age_in_queue(&self) -> Duration {
-        self.overload_threshold_config.max_txn_age_in_queue
+        self.overload_threshold_config.load().max_txn_age_in_queue
+    }
+
+    /// Replace the transaction deny config, applied to the transactions handled from now on.
+    pub fn update_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        self.transaction_deny_config.store(Arc::new(config));
+    }
+
+    /// Replace the certificate deny config, applied to the certificates executed from now on.
+    pub fn update_certificate_deny_config(&self, config: CertificateDenyConfig) {
+        self.certificate_deny_config.store(Arc::new(config));
+    }
+
+    pub fn dynamic_deny_list(&self) -> &DynamicDenyList {
+        &self.dynamic_deny_list
+    }
+
+    pub fn update_overload_threshold_config(&self, config: OverloadThresholdConfig) {
+        self.overload_threshold_config.store(Arc::new(config));
+    }
+
+    pub fn update_pruning_config(&self, config: AuthorityStorePruningConfig) {
+        self.pruner.update_config(config);
     }
 
     pub fn get_epoch_state_commitments(
@@ -716,9 +744,11 @@ impl AuthorityState {
             epoch_store.epoch(),
             transaction.data().transaction_data(),
             transaction.tx_signatures(),
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             &self.metrics.bytecode_verifier_metrics,
         )?;
+        self.dynamic_deny_list
+            .check(transaction.data().transaction_data(), &self.database)?;
 
         let owned_objects = input_objects.filter_owned_objects();
 
@@ -1313,7 +1343,7 @@ impl AuthorityState {
                 // cyclic dependency w/ sui-adapter
                 self.expensive_safety_check_config
                     .enable_deep_per_tx_sui_conservation_check(),
-                self.certificate_deny_config.certificate_deny_set(),
+                self.certificate_deny_config.load().certificate_deny_set(),
                 &epoch_store.epoch_start_config().epoch_data().epoch_id(),
                 epoch_store
                     .epoch_start_config()
@@ -1390,7 +1420,7 @@ impl AuthorityState {
                     epoch_store.epoch(),
                     &transaction,
                     &[],
-                    &self.transaction_deny_config,
+                    &self.transaction_deny_config.load(),
                     &self.metrics.bytecode_verifier_metrics,
                 )?,
                 None,
@@ -1403,9 +1433,13 @@ impl AuthorityState {
         let silent = true;
         // don't bother with paranoid checks in dry run
         let enable_move_vm_paranoid_checks = false;
-        let executor =
-            sui_execution::executor(protocol_config, enable_move_vm_paranoid_checks, silent)
-                .expect("Creating an executor should not fail here");
+        let executor = sui_execution::executor(
+            protocol_config,
+            enable_move_vm_paranoid_checks,
+            silent,
+            None,
+        )
+        .expect("Creating an executor should not fail here");
 
         let expensive_checks = false;
         let (inner_temp_store, effects, _execution_error) = executor
@@ -1414,7 +1448,7 @@ impl AuthorityState {
                 protocol_config,
                 self.metrics.limits_metrics.clone(),
                 expensive_checks,
-                self.certificate_deny_config.certificate_deny_set(),
+                self.certificate_deny_config.load().certificate_deny_set(),
                 &epoch_store.epoch_start_config().epoch_data().epoch_id(),
                 epoch_store
                     .epoch_start_config()
@@ -1550,6 +1584,7 @@ impl AuthorityState {
             self.expensive_safety_check_config
                 .enable_move_vm_paranoid_checks(),
             silent,
+            None,
         )
         .expect("Creating an executor should not fail here");
         let expensive_checks = false;
@@ -1558,7 +1593,7 @@ impl AuthorityState {
             protocol_config,
             self.metrics.limits_metrics.clone(),
             expensive_checks,
-            self.certificate_deny_config.certificate_deny_set(),
+            self.certificate_deny_config.load().certificate_deny_set(),
             &epoch_store.epoch_start_config().epoch_data().epoch_id(),
             epoch_store
                 .epoch_start_config()
@@ -2054,6 +2089,7 @@ impl AuthorityState {
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
         transaction_deny_config: TransactionDenyConfig,
         certificate_deny_config: CertificateDenyConfig,
+        dynamic_deny_list: DynamicDenyList,
         indirect_objects_threshold: usize,
         debug_dump_config: StateDebugDumpConfig,
         overload_threshold_config: OverloadThresholdConfig,
@@ -2073,7 +2109,7 @@ impl AuthorityState {
 
         let _authority_per_epoch_pruner =
             AuthorityPerEpochStorePruner::new(epoch_store.get_parent_path(), &pruning_config);
-        let _pruner = AuthorityStorePruner::new(
+        let pruner = AuthorityStorePruner::new(
             store.perpetual_tables.clone(),
             checkpoint_store.clone(),
             store.objects_lock_table.clone(),
@@ -2095,14 +2131,15 @@ impl AuthorityState {
             transaction_manager,
             tx_execution_shutdown: Mutex::new(Some(tx_execution_shutdown)),
             metrics,
-            _pruner,
+            pruner,
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             expensive_safety_check_config,
-            transaction_deny_config,
-            certificate_deny_config,
+            transaction_deny_config: ArcSwap::from_pointee(transaction_deny_config),
+            certificate_deny_config: ArcSwap::from_pointee(certificate_deny_config),
+            dynamic_deny_list,
             debug_dump_config,
-            overload_threshold_config,
+            overload_threshold_config: ArcSwap::from_pointee(overload_threshold_config),
         });
 
         // Start a task to execute ready certificates.
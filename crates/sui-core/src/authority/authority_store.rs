@@ -941,6 +941,80 @@ impl AuthorityStore {
         Ok(())
     }
 
+    /// Like [Self::bulk_insert_live_objects], for objects superseding the versions already in
+    /// the store, when applying an incremental state snapshot.
+    pub fn bulk_update_live_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        live_objects: impl Iterator<Item = LiveObject>,
+        indirect_objects_threshold: usize,
+        expected_sha3_digest: &[u8; 32],
+    ) -> SuiResult<()> {
+        let live_objects: Vec<_> = live_objects.collect();
+        let mut batch = perpetual_db.objects.batch();
+        Self::remove_latest_object_locks(
+            perpetual_db,
+            &mut batch,
+            live_objects.iter().map(LiveObject::object_id),
+        )?;
+        batch.write()?;
+        Self::bulk_insert_live_objects(
+            perpetual_db,
+            live_objects.into_iter(),
+            indirect_objects_threshold,
+            expected_sha3_digest,
+        )
+    }
+
+    /// Remove objects from the live object set by writing the given deleted or wrapped object
+    /// references, when applying an incremental state snapshot.
+    pub fn bulk_insert_tombstones(
+        perpetual_db: &AuthorityPerpetualTables,
+        tombstones: impl Iterator<Item = ObjectRef>,
+    ) -> SuiResult<()> {
+        let tombstones: Vec<_> = tombstones.collect();
+        let mut batch = perpetual_db.objects.batch();
+        Self::remove_latest_object_locks(
+            perpetual_db,
+            &mut batch,
+            tombstones.iter().map(|obj_ref| obj_ref.0),
+        )?;
+        for (object_id, version, digest) in tombstones {
+            let store_object = if digest.is_deleted() {
+                StoreObject::Deleted
+            } else if digest.is_wrapped() {
+                StoreObject::Wrapped
+            } else {
+                return Err(SuiError::from(
+                    format!("Not a tombstone: {:?}", (object_id, version, digest)).as_str(),
+                ));
+            };
+            batch.insert_batch(
+                &perpetual_db.objects,
+                std::iter::once::<(ObjectKey, StoreObjectWrapper)>((
+                    ObjectKey(object_id, version),
+                    store_object.into(),
+                )),
+            )?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
+    fn remove_latest_object_locks(
+        perpetual_db: &AuthorityPerpetualTables,
+        batch: &mut DBBatch,
+        object_ids: impl Iterator<Item = ObjectID>,
+    ) -> SuiResult {
+        let mut latest_refs = vec![];
+        for object_id in object_ids {
+            if let Some(obj_ref) = perpetual_db.get_latest_object_ref_or_tombstone(object_id)? {
+                latest_refs.push(obj_ref);
+            }
+        }
+        batch.delete_batch(&perpetual_db.owned_object_transaction_locks, latest_refs)?;
+        Ok(())
+    }
+
     pub async fn set_epoch_start_configuration(
         &self,
         epoch_start_configuration: &EpochStartConfiguration,
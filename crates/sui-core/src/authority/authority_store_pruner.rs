@@ -7,8 +7,8 @@ use anyhow::anyhow;
 use mysten_metrics::{monitored_scope, spawn_monitored_task};
 use once_cell::sync::Lazy;
 use prometheus::{
-    register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
-    Registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_with_registry, IntCounter, IntCounterVec, IntGauge, Registry,
 };
 use rocksdb::LiveFile;
 use std::cmp::{max, min};
@@ -16,7 +16,7 @@ use std::collections::{HashMap, HashSet};
 use std::time::SystemTime;
 use std::{sync::Arc, time::Duration};
 use sui_archival::reader::ArchiveReaderBalancer;
-use sui_config::node::AuthorityStorePruningConfig;
+use sui_config::node::{AuthorityStorePruningConfig, ObjectRetentionRule};
 use sui_storage::mutex_table::RwLockTable;
 use sui_types::base_types::SequenceNumber;
 use sui_types::effects::TransactionEffects;
@@ -30,6 +30,7 @@ use sui_types::{
     storage::ObjectKey,
 };
 use tokio::sync::oneshot::{self, Sender};
+use tokio::sync::watch;
 use tokio::time::Instant;
 use tracing::{debug, error, info};
 use typed_store::{Map, TypedStoreError};
@@ -51,11 +52,13 @@ static PERIODIC_PRUNING_TABLES: Lazy<HashSet<String>> = Lazy::new(|| {
 });
 pub struct AuthorityStorePruner {
     _objects_pruner_cancel_handle: oneshot::Sender<()>,
+    config_sender: watch::Sender<AuthorityStorePruningConfig>,
 }
 
 pub struct AuthorityStorePruningMetrics {
     pub last_pruned_checkpoint: IntGauge,
     pub num_pruned_objects: IntCounter,
+    pub num_retained_objects: IntCounterVec,
     pub last_pruned_effects_checkpoint: IntGauge,
 }
 
@@ -74,6 +77,13 @@ impl AuthorityStorePruningMetrics {
                 registry
             )
             .unwrap(),
+            num_retained_objects: register_int_counter_vec_with_registry!(
+                "num_retained_objects",
+                "Number of object versions kept by each object retention rule",
+                &["rule"],
+                registry
+            )
+            .unwrap(),
             last_pruned_effects_checkpoint: register_int_gauge_with_registry!(
                 "last_pruned_effects_checkpoint",
                 "Last pruned effects checkpoint",
@@ -104,6 +114,7 @@ impl AuthorityStorePruner {
         checkpoint_number: CheckpointSequenceNumber,
         metrics: Arc<AuthorityStorePruningMetrics>,
         indirect_objects_threshold: usize,
+        retention_rules: &[ObjectRetentionRule],
     ) -> anyhow::Result<()> {
         let _scope = monitored_scope("ObjectsLivePruner");
         let mut wb = perpetual_db.objects.batch();
@@ -114,6 +125,20 @@ impl AuthorityStorePruner {
                 object_keys_to_prune.push(ObjectKey(object_id, seq_number));
             }
         }
+        let retained_objects =
+            Self::retained_objects(perpetual_db, &object_keys_to_prune, retention_rules)?;
+        if !retained_objects.is_empty() {
+            object_keys_to_prune.retain(|key| match retained_objects.get(&key.0) {
+                Some(rule) => {
+                    metrics
+                        .num_retained_objects
+                        .with_label_values(&[&rule.to_string()])
+                        .inc();
+                    false
+                }
+                None => true,
+            });
+        }
         metrics
             .num_pruned_objects
             .inc_by(object_keys_to_prune.len() as u64);
@@ -137,6 +162,9 @@ impl AuthorityStorePruner {
         let mut updates: HashMap<ObjectID, (VersionNumber, VersionNumber)> = HashMap::new();
         for effects in transaction_effects {
             for (object_id, seq_number) in effects.modified_at_versions() {
+                if retained_objects.contains_key(&object_id) {
+                    continue;
+                }
                 updates
                     .entry(object_id)
                     .and_modify(|range| {
@@ -171,6 +199,38 @@ impl AuthorityStorePruner {
         Ok(())
     }
 
+    /// The objects that have a version about to be pruned matching one of the retention rules,
+    /// along with the first rule they match. All their versions are kept.
+    fn retained_objects<'a>(
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        object_keys: &[ObjectKey],
+        retention_rules: &'a [ObjectRetentionRule],
+    ) -> anyhow::Result<HashMap<ObjectID, &'a ObjectRetentionRule>> {
+        let mut retained = HashMap::new();
+        if retention_rules.is_empty() {
+            return Ok(retained);
+        }
+        for (key, object) in object_keys
+            .iter()
+            .zip(perpetual_db.objects.multi_get(object_keys.iter())?)
+        {
+            if retained.contains_key(&key.0) {
+                continue;
+            }
+            let Some(object) = object
+                .map(|object| perpetual_db.object(key, object))
+                .transpose()?
+                .flatten()
+            else {
+                continue;
+            };
+            if let Some(rule) = retention_rules.iter().find(|rule| rule.matches(&object)) {
+                retained.insert(key.0, rule);
+            }
+        }
+        Ok(retained)
+    }
+
     fn prune_checkpoints(
         perpetual_db: &Arc<AuthorityPerpetualTables>,
         checkpoint_db: &Arc<CheckpointStore>,
@@ -385,6 +445,7 @@ impl AuthorityStorePruner {
                             checkpoint_number,
                             metrics.clone(),
                             indirect_objects_threshold,
+                            &config.object_retention_rules,
                         )
                         .await?
                     }
@@ -413,6 +474,7 @@ impl AuthorityStorePruner {
                         checkpoint_number,
                         metrics.clone(),
                         indirect_objects_threshold,
+                        &config.object_retention_rules,
                     )
                     .await?
                 }
@@ -471,8 +533,21 @@ impl AuthorityStorePruner {
         Ok(Some(sst_file))
     }
 
+    fn pruning_tick_duration(
+        config: &AuthorityStorePruningConfig,
+        epoch_duration_ms: u64,
+    ) -> Duration {
+        Duration::from_millis(config.pruning_run_delay_seconds.unwrap_or(
+            if config.num_epochs_to_retain > 0 {
+                min(epoch_duration_ms / 2, 60 * 60 * 1000)
+            } else {
+                min(epoch_duration_ms / 2, 60 * 1000)
+            },
+        ))
+    }
+
     fn setup_pruning(
-        config: AuthorityStorePruningConfig,
+        mut config_receiver: watch::Receiver<AuthorityStorePruningConfig>,
         epoch_duration_ms: u64,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
@@ -482,17 +557,12 @@ impl AuthorityStorePruner {
         archive_readers: ArchiveReaderBalancer,
     ) -> Sender<()> {
         let (sender, mut recv) = tokio::sync::oneshot::channel();
+        let mut config = config_receiver.borrow().clone();
         debug!(
             "Starting object pruning service with num_epochs_to_retain={}",
             config.num_epochs_to_retain
         );
-        let tick_duration = Duration::from_millis(config.pruning_run_delay_seconds.unwrap_or(
-            if config.num_epochs_to_retain > 0 {
-                min(epoch_duration_ms / 2, 60 * 60 * 1000)
-            } else {
-                min(epoch_duration_ms / 2, 60 * 1000)
-            },
-        ));
+        let tick_duration = Self::pruning_tick_duration(&config, epoch_duration_ms);
         let pruning_initial_delay = if cfg!(msim) {
             Duration::from_millis(1)
         } else {
@@ -530,15 +600,24 @@ impl AuthorityStorePruner {
             loop {
                 tokio::select! {
                     _ = objects_prune_interval.tick(), if config.num_epochs_to_retain != u64::MAX => {
-                        if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone(), indirect_objects_threshold).await {
+                        if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config.clone(), metrics.clone(), indirect_objects_threshold).await {
                             error!("Failed to prune objects: {:?}", err);
                         }
                     },
                     _ = checkpoints_prune_interval.tick(), if !matches!(config.num_epochs_to_retain_for_checkpoints(), None | Some(u64::MAX) | Some(0)) => {
-                        if let Err(err) = Self::prune_checkpoints_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config, metrics.clone(), indirect_objects_threshold, archive_readers.clone()).await {
+                        if let Err(err) = Self::prune_checkpoints_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config.clone(), metrics.clone(), indirect_objects_threshold, archive_readers.clone()).await {
                             error!("Failed to prune checkpoints: {:?}", err);
                         }
                     },
+                    Ok(()) = config_receiver.changed() => {
+                        config = config_receiver.borrow().clone();
+                        info!("Updated pruning config: {:?}", config);
+                        let tick_duration = Self::pruning_tick_duration(&config, epoch_duration_ms);
+                        objects_prune_interval =
+                            tokio::time::interval_at(Instant::now() + tick_duration, tick_duration);
+                        checkpoints_prune_interval =
+                            tokio::time::interval_at(Instant::now() + tick_duration, tick_duration);
+                    },
                     _ = &mut recv => break,
                 }
             }
@@ -556,9 +635,10 @@ impl AuthorityStorePruner {
         indirect_objects_threshold: usize,
         archive_readers: ArchiveReaderBalancer,
     ) -> Self {
+        let (config_sender, config_receiver) = watch::channel(pruning_config);
         AuthorityStorePruner {
             _objects_pruner_cancel_handle: Self::setup_pruning(
-                pruning_config,
+                config_receiver,
                 epoch_duration_ms,
                 perpetual_db,
                 checkpoint_store,
@@ -567,9 +647,16 @@ impl AuthorityStorePruner {
                 indirect_objects_threshold,
                 archive_readers,
             ),
+            config_sender,
         }
     }
 
+    /// Apply a new pruning config to the running pruner. The periodic compaction settings are
+    /// only read at startup.
+    pub fn update_config(&self, config: AuthorityStorePruningConfig) {
+        self.config_sender.send_replace(config);
+    }
+
     pub fn compact(perpetual_db: &Arc<AuthorityPerpetualTables>) -> Result<(), TypedStoreError> {
         perpetual_db.objects.compact_range(
             &ObjectKey(ObjectID::ZERO, SequenceNumber::MIN),
@@ -593,8 +680,9 @@ mod tests {
         StoreObjectWrapper,
     };
     use prometheus::Registry;
+    use sui_config::node::ObjectRetentionRule;
     use sui_storage::mutex_table::RwLockTable;
-    use sui_types::base_types::ObjectDigest;
+    use sui_types::base_types::{ObjectDigest, SuiAddress};
     use sui_types::effects::TransactionEffects;
     use sui_types::effects::TransactionEffectsAPI;
     use sui_types::{
@@ -713,9 +801,17 @@ mod tests {
                     ObjectDigest::MIN,
                 ));
             }
-            AuthorityStorePruner::prune_objects(vec![effects], &db, &lock_table(), 0, metrics, 1)
-                .await
-                .unwrap();
+            AuthorityStorePruner::prune_objects(
+                vec![effects],
+                &db,
+                &lock_table(),
+                0,
+                metrics,
+                1,
+                &[],
+            )
+            .await
+            .unwrap();
             to_keep
         };
         tokio::time::sleep(Duration::from_secs(3)).await;
@@ -762,6 +858,69 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_object_retention_rules() {
+        let path = tempfile::tempdir().unwrap().into_path();
+        let retained_owner = SuiAddress::random_for_testing_only();
+        let (retained_id, pruned_id) = (ObjectID::random(), ObjectID::random());
+        let rule = ObjectRetentionRule::Owner(retained_owner);
+        let registry = Registry::default();
+        let metrics = AuthorityStorePruningMetrics::new(&registry);
+        {
+            let db = Arc::new(AuthorityPerpetualTables::open(&path, None));
+            let mut effects = TransactionEffects::default();
+            for (id, owner) in [
+                (retained_id, retained_owner),
+                (pruned_id, SuiAddress::random_for_testing_only()),
+            ] {
+                for version in 0..3 {
+                    let obj =
+                        get_store_object_pair(Object::with_id_owner_for_testing(id, owner), 0).0;
+                    db.objects
+                        .insert(&ObjectKey(id, SequenceNumber::from(version)), &obj)
+                        .unwrap();
+                    if version < 2 {
+                        effects.unsafe_add_deleted_object_for_testing((
+                            id,
+                            SequenceNumber::from(version),
+                            ObjectDigest::MIN,
+                        ));
+                    }
+                }
+            }
+            AuthorityStorePruner::prune_objects(
+                vec![effects],
+                &db,
+                &lock_table(),
+                0,
+                metrics.clone(),
+                0,
+                &[rule.clone()],
+            )
+            .await
+            .unwrap();
+        }
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        assert_eq!(
+            metrics
+                .num_retained_objects
+                .with_label_values(&[&rule.to_string()])
+                .get(),
+            2
+        );
+        assert_eq!(metrics.num_pruned_objects.get(), 2);
+        assert_eq!(
+            get_keys_after_pruning(&path).unwrap(),
+            HashSet::from([
+                ObjectKey(retained_id, SequenceNumber::from(0)),
+                ObjectKey(retained_id, SequenceNumber::from(1)),
+                ObjectKey(retained_id, SequenceNumber::from(2)),
+                ObjectKey(pruned_id, SequenceNumber::from(2)),
+            ])
+        );
+    }
+
     #[cfg(not(target_env = "msvc"))]
     #[tokio::test]
     async fn test_db_size_after_compaction() -> Result<(), anyhow::Error> {
@@ -819,6 +978,7 @@ mod tests {
             0,
             metrics,
             0,
+            &[],
         )
         .await;
         info!("Total pruned keys = {:?}", total_pruned);
@@ -934,6 +1094,7 @@ mod pprof_tests {
             0,
             metrics,
             1,
+            &[],
         )
         .await?;
         let guard = pprof::ProfilerGuardBuilder::default()
@@ -969,6 +1130,7 @@ mod pprof_tests {
             0,
             metrics,
             1,
+            &[],
         )
         .await?;
         if let Ok(()) = perpetual_db.objects.flush() {
@@ -6,6 +6,7 @@ use crate::authority::authority_store_tables::AuthorityPerpetualTables;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use crate::authority::{AuthorityState, AuthorityStore};
 use crate::checkpoints::CheckpointStore;
+use crate::dynamic_deny_list::DynamicDenyList;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::epoch::epoch_metrics::EpochMetrics;
 use crate::module_cache_metrics::ResolverMetrics;
@@ -263,6 +264,7 @@ impl<'a> TestAuthorityBuilder<'a> {
             ExpensiveSafetyCheckConfig::new_enable_all(),
             transaction_deny_config,
             certificate_deny_config,
+            DynamicDenyList::load(None, &registry).unwrap(),
             usize::MAX,
             StateDebugDumpConfig {
                 dump_file_directory: Some(tempdir().unwrap().into_path()),
@@ -0,0 +1,187 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transaction deny rules managed while the node is running, on top of the static
+//! [sui_config::transaction_deny_config::TransactionDenyConfig].
+
+use crate::authority::AuthorityState;
+use anyhow::Context;
+use arc_swap::ArcSwap;
+use parking_lot::Mutex;
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_with_registry, IntCounterVec,
+    IntGauge, Registry,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_config::transaction_deny_config::{DenyRule, DenyTarget};
+use sui_transaction_checks::deny::find_matching_deny_rule;
+use sui_types::error::{SuiError, SuiResult, UserInputError};
+use sui_types::storage::BackingPackageStore;
+use sui_types::transaction::TransactionData;
+use tracing::info;
+
+/// What is persisted, so that rule ids are never reused across restarts.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DenyListFile {
+    next_id: u64,
+    rules: Vec<DenyRule>,
+}
+
+pub struct DynamicDenyList {
+    /// Where the rules are persisted, if anywhere.
+    path: Option<PathBuf>,
+    /// Read on every transaction, replaced on every change.
+    rules: ArcSwap<Vec<DenyRule>>,
+    /// Serializes changes, holding the id of the next rule.
+    next_id: Mutex<u64>,
+    num_rejected_transactions: IntCounterVec,
+    num_rules: IntGauge,
+}
+
+impl DynamicDenyList {
+    /// Load the rules persisted at `path`, dropping the ones that expired in the meantime.
+    pub fn load(path: Option<PathBuf>, registry: &Registry) -> anyhow::Result<Self> {
+        let mut file = match &path {
+            Some(path) if path.exists() => serde_json::from_slice(
+                &fs::read(path).with_context(|| format!("cannot read deny list {:?}", path))?,
+            )
+            .with_context(|| format!("cannot parse deny list {:?}", path))?,
+            _ => DenyListFile::default(),
+        };
+        let now_ms = AuthorityState::unixtime_now_ms();
+        file.rules.retain(|rule| !rule.is_expired(now_ms));
+
+        let deny_list = Self {
+            path,
+            rules: ArcSwap::from_pointee(vec![]),
+            next_id: Mutex::new(file.next_id),
+            num_rejected_transactions: register_int_counter_vec_with_registry!(
+                "dynamic_deny_list_rejected_transactions",
+                "Number of transactions rejected by each dynamic deny rule",
+                &["rule"],
+                registry,
+            )
+            .unwrap(),
+            num_rules: register_int_gauge_with_registry!(
+                "dynamic_deny_list_rules",
+                "Number of dynamic deny rules",
+                registry,
+            )
+            .unwrap(),
+        };
+        deny_list.store(file.next_id, file.rules)?;
+        Ok(deny_list)
+    }
+
+    /// The rules currently in place, including the ones that expired since the last change.
+    pub fn list(&self) -> Arc<Vec<DenyRule>> {
+        self.rules.load_full()
+    }
+
+    /// How many transactions the rule with the given id rejected since the node started.
+    pub fn num_rejected_transactions(&self, id: u64) -> u64 {
+        self.num_rejected_transactions
+            .with_label_values(&[&id.to_string()])
+            .get()
+    }
+
+    /// Add a rule denying `target` until `expires_at_ms`, or until it is removed.
+    pub fn add(
+        &self,
+        target: DenyTarget,
+        expires_at_ms: Option<u64>,
+        reason: Option<String>,
+    ) -> anyhow::Result<DenyRule> {
+        let mut next_id = self.next_id.lock();
+        let now_ms = AuthorityState::unixtime_now_ms();
+        let rule = DenyRule {
+            id: *next_id,
+            target,
+            expires_at_ms,
+            reason,
+        };
+        let mut rules: Vec<_> = self
+            .rules
+            .load()
+            .iter()
+            .filter(|rule| !rule.is_expired(now_ms))
+            .cloned()
+            .collect();
+        rules.push(rule.clone());
+        self.store(*next_id + 1, rules)?;
+        *next_id += 1;
+        info!("Added transaction deny rule {:?}", rule);
+        Ok(rule)
+    }
+
+    /// Remove the rule with the given id, returning it if it existed.
+    pub fn remove(&self, id: u64) -> anyhow::Result<Option<DenyRule>> {
+        let next_id = self.next_id.lock();
+        let mut rules = self.rules.load().to_vec();
+        let Some(index) = rules.iter().position(|rule| rule.id == id) else {
+            return Ok(None);
+        };
+        let rule = rules.remove(index);
+        self.store(*next_id, rules)?;
+        let _ = self
+            .num_rejected_transactions
+            .remove_label_values(&[&id.to_string()]);
+        info!("Removed transaction deny rule {:?}", rule);
+        Ok(Some(rule))
+    }
+
+    /// Persist the rules before putting them in place, so that a change that is acknowledged
+    /// survives a restart.
+    fn store(&self, next_id: u64, rules: Vec<DenyRule>) -> anyhow::Result<()> {
+        let file = DenyListFile { next_id, rules };
+        if let Some(path) = &self.path {
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, serde_json::to_vec_pretty(&file)?)
+                .with_context(|| format!("cannot write deny list {:?}", tmp))?;
+            fs::rename(&tmp, path).with_context(|| format!("cannot write deny list {:?}", path))?;
+        }
+        self.num_rules.set(file.rules.len() as i64);
+        self.rules.store(Arc::new(file.rules));
+        Ok(())
+    }
+
+    /// Check that the transaction is not denied by any of the rules in place.
+    pub fn check(
+        &self,
+        tx_data: &TransactionData,
+        package_store: &impl BackingPackageStore,
+    ) -> SuiResult {
+        let rules = self.rules.load();
+        if rules.is_empty() {
+            return Ok(());
+        }
+        let input_objects = tx_data.input_objects()?;
+        let receiving_objects = tx_data.receiving_objects();
+        let Some(rule) = find_matching_deny_rule(
+            &rules,
+            AuthorityState::unixtime_now_ms(),
+            tx_data,
+            &input_objects,
+            &receiving_objects,
+            package_store,
+        )?
+        else {
+            return Ok(());
+        };
+        self.num_rejected_transactions
+            .with_label_values(&[&rule.id.to_string()])
+            .inc();
+        Err(SuiError::UserInputError {
+            error: UserInputError::TransactionDenied {
+                error: format!(
+                    "Transaction is temporarily disabled by deny rule {}",
+                    rule.id
+                ),
+            },
+        })
+    }
+}
@@ -16,6 +16,7 @@ pub mod consensus_throughput_calculator;
 pub(crate) mod consensus_types;
 pub mod consensus_validator;
 pub mod db_checkpoint_handler;
+pub mod dynamic_deny_list;
 pub mod epoch;
 mod execution_driver;
 pub mod metrics;
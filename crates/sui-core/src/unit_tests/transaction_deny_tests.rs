@@ -6,14 +6,19 @@ use crate::authority::authority_test_utils::{
 };
 use crate::authority::test_authority_builder::TestAuthorityBuilder;
 use crate::authority::AuthorityState;
+use crate::dynamic_deny_list::DynamicDenyList;
 use crate::test_utils::make_transfer_sui_transaction;
 use fastcrypto::ed25519::Ed25519KeyPair;
 use fastcrypto::traits::KeyPair;
 use move_core_types::ident_str;
+use prometheus::Registry;
+use prometheus::Registry;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_config::certificate_deny_config::CertificateDenyConfigBuilder;
-use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder};
+use sui_config::transaction_deny_config::{
+    DenyTarget, TransactionDenyConfig, TransactionDenyConfigBuilder,
+};
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_test_transaction_builder::TestTransactionBuilder;
@@ -29,6 +34,7 @@ use sui_types::transaction::{
 use sui_types::utils::{
     make_zklogin_tx, to_sender_signed_transaction, to_sender_signed_transaction_with_multi_signers,
 };
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
 
 const ACCOUNT_NUM: usize = 5;
 const GAS_OBJECT_COUNT: usize = 15;
@@ -478,3 +484,125 @@ async fn test_certificate_deny() {
         }
     ));
 }
+
+#[tokio::test]
+async fn test_dynamic_deny_rules() {
+    let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let deny_list = state.dynamic_deny_list();
+
+    // Both the sender and the sponsor are checked.
+    let rule = deny_list
+        .add(DenyTarget::Address(accounts[0].0), None, None)
+        .unwrap();
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+    assert_denied(&transfer_with_account(&accounts[1], &accounts[0], &state).await);
+    assert_eq!(deny_list.num_rejected_transactions(rule.id), 2);
+
+    // Rules stop applying as soon as they are removed.
+    assert_eq!(deny_list.remove(rule.id).unwrap(), Some(rule.clone()));
+    assert_eq!(deny_list.remove(rule.id).unwrap(), None);
+    assert!(transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .is_ok());
+
+    // Expired rules no longer apply.
+    deny_list
+        .add(DenyTarget::Address(accounts[1].0), Some(0), None)
+        .unwrap();
+    assert!(transfer_with_account(&accounts[1], &accounts[1], &state)
+        .await
+        .is_ok());
+
+    let rule = deny_list
+        .add(DenyTarget::Object(accounts[2].2[0]), None, None)
+        .unwrap();
+    assert_denied(&transfer_with_account(&accounts[2], &accounts[2], &state).await);
+    deny_list.remove(rule.id).unwrap();
+
+    // Only the denied function is blocked, the rest of the package can still be called.
+    deny_list
+        .add(
+            DenyTarget::MoveFunction {
+                package: SUI_FRAMEWORK_PACKAGE_ID,
+                module: "coin".into(),
+                function: "zero".into(),
+            },
+            None,
+            Some("incident".into()),
+        )
+        .unwrap();
+    let result = handle_move_call_transaction(
+        &state,
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "coin",
+        "zero",
+        vec![],
+        &accounts[3],
+        0,
+    )
+    .await;
+    assert_denied(&result);
+    let result = handle_move_call_transaction(
+        &state,
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "coin",
+        "supply",
+        vec![],
+        &accounts[3],
+        1,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    deny_list
+        .add(DenyTarget::Package(SUI_FRAMEWORK_PACKAGE_ID), None, None)
+        .unwrap();
+    let result = handle_move_call_transaction(
+        &state,
+        SUI_FRAMEWORK_PACKAGE_ID,
+        "coin",
+        "supply",
+        vec![],
+        &accounts[4],
+        0,
+    )
+    .await;
+    assert_denied(&result);
+}
+
+#[test]
+fn test_dynamic_deny_list_persistence() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dynamic_deny_list.json");
+    let deny_list = DynamicDenyList::load(Some(path.clone()), &Registry::new()).unwrap();
+    assert!(deny_list.list().is_empty());
+
+    let expired = deny_list
+        .add(DenyTarget::Object(ObjectID::random()), Some(0), None)
+        .unwrap();
+    let removed = deny_list
+        .add(
+            DenyTarget::Address(SuiAddress::random_for_testing_only()),
+            None,
+            None,
+        )
+        .unwrap();
+    let kept = deny_list
+        .add(
+            DenyTarget::Package(ObjectID::random()),
+            Some(u64::MAX),
+            Some("incident".into()),
+        )
+        .unwrap();
+    deny_list.remove(removed.id).unwrap();
+    assert_eq!(*deny_list.list(), [expired, kept.clone()]);
+
+    // Expired rules are dropped on restart, and ids are never reused.
+    let deny_list = DynamicDenyList::load(Some(path), &Registry::new()).unwrap();
+    assert_eq!(*deny_list.list(), [kept.clone()]);
+    let added = deny_list
+        .add(DenyTarget::Object(ObjectID::random()), None, None)
+        .unwrap();
+    assert_eq!(added.id, kept.id + 1);
+}
@@ -9,8 +9,12 @@ use move_core_types::parser::parse_struct_tag;
 use move_core_types::value::MoveStructLayout;
 use rand::rngs::OsRng;
 use serde_json::json;
+use std::path::PathBuf;
 use std::sync::Arc;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+use sui_archival::read_manifest;
+use sui_config::node::{DBCheckpointConfig, StateArchiveConfig, StateSnapshotConfig};
+use sui_config::Config;
 use sui_core::authority::EffectsNotifyRead;
 use sui_json_rpc_types::{
     type_and_fields_from_move_struct, EventPage, SuiEvent, SuiExecutionStatus,
@@ -20,9 +24,11 @@ use sui_json_rpc_types::{EventFilter, TransactionFilter};
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
 use sui_node::SuiNodeHandle;
+use sui_replay::{execute_replay_command, ArchiveSource, ReplayToolCommand};
 use sui_sdk::wallet_context::WalletContext;
 use sui_storage::key_value_store::TransactionKeyValueStore;
 use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_test_transaction_builder::{
     batch_make_transfer_transactions, create_devnet_nft, delete_devnet_nft, increment_counter,
     publish_basics_package, publish_basics_package_and_make_counter, publish_nfts_package,
@@ -1212,6 +1218,141 @@ async fn test_full_node_bootstrap_from_snapshot() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+// Test for replaying an epoch offline, from the checkpoint archive and the formal snapshots
+// written by a full node
+#[sim_test]
+async fn test_replay_epoch_from_archive() -> Result<(), anyhow::Error> {
+    telemetry_subscribers::init_for_testing();
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let dir = tempfile::tempdir()?;
+    let archive_path = dir.path().join("archive");
+    let snapshot_path = dir.path().join("snapshot");
+    let file_store = |directory: PathBuf| ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(directory),
+        ..Default::default()
+    };
+    // Formal snapshots are written from the db checkpoints taken at the end of every epoch
+    let mut config = test_cluster
+        .fullnode_config_builder()
+        .with_db_checkpoint_config(DBCheckpointConfig {
+            perform_db_checkpoints_at_epoch_end: true,
+            checkpoint_path: None,
+            object_store_config: Some(file_store(dir.path().join("db_checkpoints"))),
+            perform_index_db_checkpoints_at_epoch_end: None,
+            prune_and_compact_before_upload: None,
+        })
+        .build(&mut OsRng, test_cluster.swarm.config());
+    config.state_archive_write_config = StateArchiveConfig {
+        object_store_config: Some(file_store(archive_path.clone())),
+        concurrency: 5,
+        use_for_pruning_watermark: false,
+    };
+    config.state_snapshot_write_config = StateSnapshotConfig {
+        object_store_config: Some(file_store(snapshot_path.clone())),
+        concurrency: 5,
+        full_snapshot_interval_epochs: None,
+    };
+    let node = test_cluster
+        .start_fullnode_from_config(config)
+        .await
+        .sui_node;
+
+    // Epoch 1 is replayed on top of the snapshot at the end of epoch 0
+    test_cluster.trigger_reconfiguration().await;
+    let (_transferred_object, _, _, digest, ..) = transfer_coin(&test_cluster.wallet).await?;
+    node.state()
+        .db()
+        .notify_read_executed_effects(vec![digest])
+        .await
+        .unwrap();
+    test_cluster.trigger_reconfiguration().await;
+    while node.with(|node| node.current_epoch_for_testing()) < 2 {
+        sleep(Duration::from_millis(500)).await;
+    }
+    let last_checkpoint = node
+        .with(|node| {
+            node.state()
+                .get_checkpoint_store()
+                .get_epoch_last_checkpoint(1)
+        })?
+        .expect("Epoch 1 has ended")
+        .sequence_number;
+
+    // The archive files of an epoch are uploaded once the next epoch starts
+    let archive_store = file_store(archive_path.clone()).make()?;
+    loop {
+        let archived = match read_manifest(archive_store.clone()).await {
+            Ok(manifest) => manifest.next_checkpoint_seq_num() > last_checkpoint,
+            Err(_) => false,
+        };
+        if archived && snapshot_path.join("epoch_0").join("_SUCCESS").exists() {
+            break;
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    let archive = ArchiveSource {
+        archive_path: Some(archive_path),
+        snapshot_path: Some(snapshot_path),
+        db_path: Some(dir.path().join("replay_db")),
+    };
+    let (succeeded, total) = execute_replay_command(
+        None,
+        false,
+        false,
+        None,
+        ReplayToolCommand::ReplayEpoch {
+            epoch: 1,
+            terminate_early: true,
+            max_tasks: 1,
+            archive: archive.clone(),
+        },
+    )
+    .await?
+    .expect("Epoch replay reports the transactions it executed");
+    // The transfer and the change epoch transactions, at least
+    assert!(total >= 2);
+    assert_eq!(succeeded, total);
+
+    // Replaying the last checkpoint alone first replays the ones before it in the epoch, from the
+    // base state restored above
+    let (succeeded, total) = execute_replay_command(
+        None,
+        false,
+        false,
+        None,
+        ReplayToolCommand::ReplayCheckpoints {
+            start: last_checkpoint,
+            end: last_checkpoint,
+            terminate_early: true,
+            max_tasks: 1,
+            archive: archive.clone(),
+        },
+    )
+    .await?
+    .expect("Checkpoint replay reports the transactions it executed");
+    assert_eq!(succeeded, total);
+
+    // Checkpoints past the end of the epoch can't be replayed from its base state
+    assert!(execute_replay_command(
+        None,
+        false,
+        false,
+        None,
+        ReplayToolCommand::ReplayCheckpoints {
+            start: last_checkpoint,
+            end: last_checkpoint + 1,
+            terminate_early: true,
+            max_tasks: 1,
+            archive,
+        },
+    )
+    .await
+    .is_err());
+    Ok(())
+}
+
 // Object fast path should be disabled and unused.
 #[sim_test]
 async fn test_pass_back_no_object() -> Result<(), anyhow::Error> {
@@ -1302,3 +1443,32 @@ async fn transfer_coin(
     let resp = context.execute_transaction_must_succeed(txn).await;
     Ok((object_to_send.0, sender, receiver, resp.digest, gas_object))
 }
+
+#[sim_test]
+async fn test_reload_config() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let fullnode = &test_cluster.fullnode_handle.sui_node;
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fullnode.yaml");
+
+    let mut config = fullnode.with_async(|node| node.live_config()).await;
+    assert_ne!(config.indexer_max_subscriptions, Some(7));
+    config.indexer_max_subscriptions = Some(7);
+    config.save(&path)?;
+    let changed = fullnode
+        .with_async(|node| node.reload_config(&path))
+        .await?;
+    assert_eq!(changed, vec!["indexer-max-subscriptions".to_owned()]);
+    fullnode.with(|node| assert_eq!(node.max_subscriptions_for_testing(), 7));
+
+    // Nothing is applied when an immutable field changed.
+    config.indexer_max_subscriptions = Some(3);
+    config.db_path = dir.path().join("db");
+    config.save(&path)?;
+    assert!(fullnode
+        .with_async(|node| node.reload_config(&path))
+        .await
+        .is_err());
+    fullnode.with(|node| assert_eq!(node.max_subscriptions_for_testing(), 7));
+    Ok(())
+}
@@ -40,6 +40,12 @@ pub enum FaucetError {
     #[error("Coin amounts sent are incorrect:`{0}`")]
     CoinAmountTransferredIncorrect(String),
 
+    #[error("Coin type `{0}` is not served by this faucet")]
+    UnsupportedCoinType(String),
+
+    #[error("Request quota for {key} exceeded, retry after {retry_after_secs} seconds")]
+    QuotaExceeded { key: String, retry_after_secs: u64 },
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -0,0 +1,131 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::{parse_sui_type_tag, TypeTag};
+use tokio::sync::{mpsc::Sender, Mutex};
+use uuid::Uuid;
+
+/// A coin type other than SUI handed out by the faucet.
+///
+/// Parsed from a comma separated list of `key=value` pairs, e.g.
+/// `type=0x123::usdc::USDC,amount=1000000,treasury-cap=0x456`. Coins are split from the `Coin<T>`
+/// objects owned by the faucet unless a `treasury-cap` is given, in which case they are minted.
+/// `max-amount-per-address` and `max-amount-per-ip` limit the amount of the coin sent within a
+/// quota window, like the faucet's limits on amounts of MIST.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomCoinConfig {
+    pub coin_type: TypeTag,
+    /// Value of each coin sent, in the coin's smallest unit.
+    pub amount: u64,
+    pub source: CustomCoinSource,
+    pub max_amount_per_address: Option<u64>,
+    pub max_amount_per_ip: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomCoinSource {
+    /// Split from the `Coin<T>` objects owned by the faucet.
+    CoinPool,
+    /// Minted with the `TreasuryCap<T>` owned by the faucet.
+    TreasuryCap(ObjectID),
+}
+
+impl FromStr for CustomCoinConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut coin_type = None;
+        let mut amount = None;
+        let mut source = CustomCoinSource::CoinPool;
+        let mut max_amount_per_address = None;
+        let mut max_amount_per_ip = None;
+        for pair in s.split(',') {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Expected `key=value`, got `{pair}`"))?;
+            match key.trim() {
+                "type" => coin_type = Some(parse_sui_type_tag(value.trim())?),
+                "amount" => amount = Some(value.trim().parse::<u64>()?),
+                "treasury-cap" => {
+                    source =
+                        CustomCoinSource::TreasuryCap(ObjectID::from_hex_literal(value.trim())?)
+                }
+                "max-amount-per-address" => {
+                    max_amount_per_address = Some(value.trim().parse::<u64>()?)
+                }
+                "max-amount-per-ip" => max_amount_per_ip = Some(value.trim().parse::<u64>()?),
+                key => bail!("Unknown custom coin option `{key}`"),
+            }
+        }
+        Ok(Self {
+            coin_type: coin_type.ok_or_else(|| anyhow!("Missing custom coin `type`"))?,
+            amount: amount.ok_or_else(|| anyhow!("Missing custom coin `amount`"))?,
+            source,
+            max_amount_per_address,
+            max_amount_per_ip,
+        })
+    }
+}
+
+pub(crate) struct CustomCoin {
+    pub config: CustomCoinConfig,
+    /// All the transactions sending a custom coin use the same source objects, so they are
+    /// executed one at a time to not equivocate them.
+    pub lock: Mutex<()>,
+    /// Queue of the batch requests for this coin type, which are sent together.
+    pub request_producer: Sender<(Uuid, SuiAddress, Vec<u64>)>,
+}
+
+impl CustomCoin {
+    pub fn new(
+        config: CustomCoinConfig,
+        request_producer: Sender<(Uuid, SuiAddress, Vec<u64>)>,
+    ) -> Self {
+        Self {
+            config,
+            lock: Mutex::new(()),
+            request_producer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_custom_coin_config() {
+        let coin_type = "0x123::usdc::USDC";
+        assert_eq!(
+            CustomCoinConfig::from_str(&format!("type={coin_type},amount=1000")).unwrap(),
+            CustomCoinConfig {
+                coin_type: parse_sui_type_tag(coin_type).unwrap(),
+                amount: 1000,
+                source: CustomCoinSource::CoinPool,
+                max_amount_per_address: None,
+                max_amount_per_ip: None,
+            }
+        );
+        assert_eq!(
+            CustomCoinConfig::from_str(&format!(
+                "type={coin_type}, amount=1000, treasury-cap=0x456"
+            ))
+            .unwrap()
+            .source,
+            CustomCoinSource::TreasuryCap(ObjectID::from_hex_literal("0x456").unwrap())
+        );
+        let config = CustomCoinConfig::from_str(&format!(
+            "type={coin_type},amount=1000,max-amount-per-address=5000,max-amount-per-ip=20000"
+        ))
+        .unwrap();
+        assert_eq!(config.max_amount_per_address, Some(5000));
+        assert_eq!(config.max_amount_per_ip, Some(20000));
+        assert!(CustomCoinConfig::from_str("amount=1000").is_err());
+        assert!(CustomCoinConfig::from_str(&format!("type={coin_type}")).is_err());
+        assert!(CustomCoinConfig::from_str(&format!("type={coin_type},amount=1,cap=0x1")).is_err());
+    }
+}
@@ -4,10 +4,15 @@ use crate::FaucetError;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::TypeTag;
 use uuid::Uuid;
 
+mod custom_coins;
+mod quotas;
 mod simple_faucet;
 mod write_ahead_log;
+pub use self::custom_coins::{CustomCoinConfig, CustomCoinSource};
+pub use self::quotas::{AllowListEntry, QuotaKey, QuotaReservation, RequestQuotas};
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
 use std::{net::Ipv4Addr, path::PathBuf};
@@ -62,6 +67,26 @@ pub trait Faucet {
         amounts: &[u64],
     ) -> Result<BatchFaucetReceipt, FaucetError>;
 
+    /// Send `Coin<T>` of one of the faucet's custom coin types, of the specified amount, to the
+    /// recipient
+    async fn send_custom_coins(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError>;
+
+    /// Send `Coin<T>` of one of the faucet's custom coin types, of the specified amount, to the
+    /// recipient in a batch request
+    async fn batch_send_custom_coins(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+    ) -> Result<BatchFaucetReceipt, FaucetError>;
+
     /// Get the status of a batch_send request
     async fn get_batch_send_status(&self, task_id: Uuid) -> Result<BatchSendStatus, FaucetError>;
 }
@@ -114,6 +139,42 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Maximum number of requests sending coins to the same address within a quota window.
+    #[clap(long)]
+    pub max_requests_per_address: Option<u64>,
+
+    /// Maximum amount of MIST sent to the same address within a quota window.
+    #[clap(long)]
+    pub max_amount_per_address: Option<u64>,
+
+    /// Maximum number of requests from the same IP address within a quota window.
+    #[clap(long)]
+    pub max_requests_per_ip: Option<u64>,
+
+    /// Maximum amount of MIST requested from the same IP address within a quota window.
+    #[clap(long)]
+    pub max_amount_per_ip: Option<u64>,
+
+    /// Length of the rolling window the quotas apply to.
+    #[clap(long, default_value_t = 86400)]
+    pub quota_window_secs: u64,
+
+    /// Comma separated addresses, IP addresses and CIDR ranges which aren't subject to quotas.
+    #[clap(long, value_delimiter = ',')]
+    pub quota_allow_list: Vec<AllowListEntry>,
+
+    /// Header carrying the IP address of the client, set by a trusted reverse proxy in front of
+    /// the faucet, e.g. `X-Forwarded-For` or `X-Real-IP`. The last address in the header, which is
+    /// the one added by the proxy, is subject to the IP quotas instead of the address of the peer.
+    #[clap(long)]
+    pub client_ip_header: Option<String>,
+
+    /// Coin types handed out in addition to SUI, each as
+    /// `type=<coin type>,amount=<amount>[,treasury-cap=<object id>]`, optionally limited with
+    /// `max-amount-per-address=<amount>` and `max-amount-per-ip=<amount>`. Can be repeated.
+    #[clap(long = "custom-coin")]
+    pub custom_coins: Vec<CustomCoinConfig>,
 }
 
 impl Default for FaucetConfig {
@@ -132,6 +193,14 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            max_requests_per_address: None,
+            max_amount_per_address: None,
+            max_requests_per_ip: None,
+            max_amount_per_ip: None,
+            quota_window_secs: 86400,
+            quota_allow_list: vec![],
+            client_ip_header: None,
+            custom_coins: vec![],
         }
     }
 }
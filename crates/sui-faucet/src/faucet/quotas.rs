@@ -0,0 +1,582 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use sui_types::TypeTag;
+use typed_store::rocks::DBMap;
+use typed_store::Map;
+
+use crate::{FaucetConfig, FaucetError};
+
+/// Who a quota applies to: the recipient of the coins, or the IP address the request came from.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum QuotaKey {
+    Address(SuiAddress),
+    Ip(IpAddr),
+}
+
+impl fmt::Display for QuotaKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QuotaKey::Address(address) => write!(f, "address {address}"),
+            QuotaKey::Ip(ip) => write!(f, "IP {ip}"),
+        }
+    }
+}
+
+/// The requests served for a quota key within the current window, oldest first.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct QuotaUsage {
+    pub requests: Vec<QuotaEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct QuotaEntry {
+    pub timestamp_ms: u64,
+    pub amount: u64,
+    /// The custom coin type `amount` is of, or `None` for MIST.
+    pub coin_type: Option<TypeTag>,
+}
+
+impl QuotaUsage {
+    /// Remove the requests which are no longer within the window ending at `now_ms`.
+    fn prune(&mut self, now_ms: u64, window_ms: u64) {
+        self.requests
+            .retain(|entry| entry.timestamp_ms.saturating_add(window_ms) > now_ms);
+    }
+
+    /// The total amount of `coin_type` sent.
+    fn amount(&self, coin_type: Option<&TypeTag>) -> u64 {
+        self.requests
+            .iter()
+            .filter(|entry| entry.coin_type.as_ref() == coin_type)
+            .fold(0, |total, entry| total.saturating_add(entry.amount))
+    }
+}
+
+/// Limits on the requests, on the total MIST sent, and on the total of each custom coin type sent,
+/// per key within a rolling window.
+#[derive(Debug, Clone, Default)]
+pub struct Quota {
+    pub max_requests: Option<u64>,
+    pub max_amount: Option<u64>,
+    pub max_custom_coin_amounts: HashMap<TypeTag, u64>,
+}
+
+impl Quota {
+    fn is_unlimited(&self) -> bool {
+        self.max_requests.is_none()
+            && self.max_amount.is_none()
+            && self.max_custom_coin_amounts.is_empty()
+    }
+
+    fn max_amount(&self, coin_type: Option<&TypeTag>) -> Option<u64> {
+        match coin_type {
+            Some(coin_type) => self.max_custom_coin_amounts.get(coin_type).copied(),
+            None => self.max_amount,
+        }
+    }
+}
+
+/// An address or a range of IP addresses which bypass the quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowListEntry {
+    Address(SuiAddress),
+    Network { ip: IpAddr, prefix_len: u8 },
+}
+
+impl AllowListEntry {
+    fn allows(&self, key: &QuotaKey) -> bool {
+        match (self, key) {
+            (AllowListEntry::Address(allowed), QuotaKey::Address(address)) => allowed == address,
+            (AllowListEntry::Network { ip, prefix_len }, QuotaKey::Ip(other)) => {
+                let (network, other, bits) = match (ip, other) {
+                    (IpAddr::V4(ip), IpAddr::V4(other)) => {
+                        (u32::from(*ip) as u128, u32::from(*other) as u128, 32)
+                    }
+                    (IpAddr::V6(ip), IpAddr::V6(other)) => {
+                        (u128::from(*ip), u128::from(*other), 128)
+                    }
+                    _ => return false,
+                };
+                // Compare the first `prefix_len` bits.
+                let shift = bits - *prefix_len as u32;
+                shift == 128 || network >> shift == other >> shift
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parses a Sui address, an IP address, or a range of IP addresses in CIDR notation.
+impl FromStr for AllowListEntry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(address) = SuiAddress::from_str(s) {
+            return Ok(AllowListEntry::Address(address));
+        }
+        let (ip, prefix_len) = match s.split_once('/') {
+            Some((ip, prefix_len)) => (IpAddr::from_str(ip)?, Some(prefix_len.parse::<u8>()?)),
+            None => (IpAddr::from_str(s)?, None),
+        };
+        let max_prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_prefix_len);
+        if prefix_len > max_prefix_len {
+            anyhow::bail!("Invalid prefix length {prefix_len} for {ip}");
+        }
+        Ok(AllowListEntry::Network { ip, prefix_len })
+    }
+}
+
+/// A request recorded against the quotas of its keys. It stays recorded unless it is released,
+/// which the faucet does when the request fails.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QuotaReservation {
+    keys: Vec<QuotaKey>,
+    entry: QuotaEntry,
+}
+
+/// Per recipient address and per source IP quotas, which are persisted in the faucet's write ahead
+/// log so that they survive restarts.
+pub struct RequestQuotas {
+    address_quota: Quota,
+    ip_quota: Quota,
+    window_ms: u64,
+    allow_list: Vec<AllowListEntry>,
+    usage: DBMap<QuotaKey, QuotaUsage>,
+    /// Serializes the checks and updates of the usage.
+    lock: Mutex<()>,
+}
+
+impl RequestQuotas {
+    pub(crate) fn new(config: &FaucetConfig, usage: DBMap<QuotaKey, QuotaUsage>) -> Self {
+        Self {
+            address_quota: Quota {
+                max_requests: config.max_requests_per_address,
+                max_amount: config.max_amount_per_address,
+                max_custom_coin_amounts: config
+                    .custom_coins
+                    .iter()
+                    .filter_map(|custom| {
+                        Some((custom.coin_type.clone(), custom.max_amount_per_address?))
+                    })
+                    .collect(),
+            },
+            ip_quota: Quota {
+                max_requests: config.max_requests_per_ip,
+                max_amount: config.max_amount_per_ip,
+                max_custom_coin_amounts: config
+                    .custom_coins
+                    .iter()
+                    .filter_map(|custom| {
+                        Some((custom.coin_type.clone(), custom.max_amount_per_ip?))
+                    })
+                    .collect(),
+            },
+            window_ms: config.quota_window_secs.saturating_mul(1000),
+            allow_list: config.quota_allow_list.clone(),
+            usage,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Record a request of `amount` of `coin_type` (or MIST if it is `None`) for `recipient` from
+    /// `ip`, or fail with the number of seconds to wait for if it exceeds a quota. Only requests
+    /// within quotas are recorded, until they are released.
+    pub fn reserve(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+        coin_type: Option<&TypeTag>,
+        amount: u64,
+    ) -> Result<QuotaReservation, FaucetError> {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(FaucetError::internal)?
+            .as_millis() as u64;
+        self.reserve_at(recipient, ip, coin_type, amount, now_ms)
+    }
+
+    fn reserve_at(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+        coin_type: Option<&TypeTag>,
+        amount: u64,
+        now_ms: u64,
+    ) -> Result<QuotaReservation, FaucetError> {
+        let entry = QuotaEntry {
+            timestamp_ms: now_ms,
+            amount,
+            coin_type: coin_type.cloned(),
+        };
+        let keys: Vec<_> = [
+            Some((QuotaKey::Address(recipient), &self.address_quota)),
+            ip.map(|ip| (QuotaKey::Ip(ip), &self.ip_quota)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|(key, quota)| {
+            !quota.is_unlimited() && !self.allow_list.iter().any(|entry| entry.allows(key))
+        })
+        .collect();
+        let mut reservation = QuotaReservation {
+            keys: vec![],
+            entry,
+        };
+        if keys.is_empty() {
+            return Ok(reservation);
+        }
+
+        let _guard = self.lock.lock();
+        let mut updates = vec![];
+        for (key, quota) in keys {
+            let mut usage = self
+                .usage
+                .get(&key)
+                .map_err(FaucetError::internal)?
+                .unwrap_or_default();
+            usage.prune(now_ms, self.window_ms);
+            let retry_after_ms = self.retry_after_ms(&usage, quota, coin_type, amount, now_ms);
+            if let Some(retry_after_ms) = retry_after_ms {
+                return Err(FaucetError::QuotaExceeded {
+                    key: key.to_string(),
+                    // Round up, so that retrying after that many seconds succeeds.
+                    retry_after_secs: retry_after_ms.div_ceil(1000),
+                });
+            }
+            usage.requests.push(reservation.entry.clone());
+            updates.push((key, usage));
+            reservation.keys.push(key);
+        }
+        self.usage
+            .multi_insert(updates)
+            .map_err(FaucetError::internal)?;
+        Ok(reservation)
+    }
+
+    /// Remove a reserved request from the quotas, when it couldn't be served.
+    pub fn release(&self, reservation: QuotaReservation) -> Result<(), FaucetError> {
+        let QuotaReservation { keys, entry } = reservation;
+        let _guard = self.lock.lock();
+        let mut updates = vec![];
+        for key in keys {
+            let Some(mut usage) = self.usage.get(&key).map_err(FaucetError::internal)? else {
+                continue;
+            };
+            if let Some(i) = usage.requests.iter().position(|e| *e == entry) {
+                usage.requests.remove(i);
+                updates.push((key, usage));
+            }
+        }
+        self.usage
+            .multi_insert(updates)
+            .map_err(FaucetError::internal)
+    }
+
+    /// How long until a request of `amount` of `coin_type` fits in `quota`, or `None` if it
+    /// already does.
+    fn retry_after_ms(
+        &self,
+        usage: &QuotaUsage,
+        quota: &Quota,
+        coin_type: Option<&TypeTag>,
+        amount: u64,
+        now_ms: u64,
+    ) -> Option<u64> {
+        // The number of the oldest requests which need to leave the window.
+        let mut expiring = 0;
+        if let Some(max_requests) = quota.max_requests {
+            let requests = usage.requests.len() as u64 + 1;
+            expiring = expiring.max(requests.saturating_sub(max_requests) as usize);
+        }
+        if let Some(max_amount) = quota.max_amount(coin_type) {
+            if amount > max_amount {
+                return Some(self.window_ms);
+            }
+            let mut total = usage.amount(coin_type).saturating_add(amount);
+            let mut released = 0;
+            for (i, entry) in usage.requests.iter().enumerate() {
+                if total <= max_amount {
+                    break;
+                }
+                if entry.coin_type.as_ref() == coin_type {
+                    total -= entry.amount;
+                    released = i + 1;
+                }
+            }
+            expiring = expiring.max(released);
+        }
+        if expiring == 0 {
+            return None;
+        }
+        // Requests beyond the quota on their own can never be served, so retrying in a full window
+        // is as good as any.
+        Some(match usage.requests.get(expiring - 1) {
+            Some(entry) => entry
+                .timestamp_ms
+                .saturating_add(self.window_ms)
+                .saturating_sub(now_ms),
+            None => self.window_ms,
+        })
+    }
+
+    /// Remove the usage of keys without requests in the current window.
+    pub fn prune(&self) -> Result<(), FaucetError> {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(FaucetError::internal)?
+            .as_millis() as u64;
+
+        let _guard = self.lock.lock();
+        let expired = self
+            .usage
+            .safe_iter()
+            .filter_map(|item| match item {
+                Ok((key, mut usage)) => {
+                    usage.prune(now_ms, self.window_ms);
+                    usage.requests.is_empty().then_some(Ok(key))
+                }
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(FaucetError::internal)?;
+        self.usage
+            .multi_remove(expired)
+            .map_err(FaucetError::internal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faucet::write_ahead_log::WriteAheadLog;
+    use crate::CustomCoinConfig;
+
+    const WINDOW_SECS: u64 = 60;
+
+    fn quotas(config: FaucetConfig, path: &std::path::Path) -> RequestQuotas {
+        let wal = WriteAheadLog::open(path);
+        RequestQuotas::new(
+            &FaucetConfig {
+                quota_window_secs: WINDOW_SECS,
+                ..config
+            },
+            wal.quotas,
+        )
+    }
+
+    #[test]
+    fn address_request_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = FaucetConfig {
+            max_requests_per_address: Some(2),
+            ..Default::default()
+        };
+        let quotas = quotas(config, &tmp.path().join("wal"));
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.reserve_at(recipient, None, None, 1, 1000).unwrap();
+        quotas.reserve_at(recipient, None, None, 1, 2000).unwrap();
+        assert_eq!(
+            quotas.reserve_at(recipient, None, None, 1, 3000),
+            Err(FaucetError::QuotaExceeded {
+                key: QuotaKey::Address(recipient).to_string(),
+                retry_after_secs: WINDOW_SECS - 2,
+            })
+        );
+
+        // Other recipients have their own quota.
+        quotas
+            .reserve_at(SuiAddress::random_for_testing_only(), None, None, 1, 3000)
+            .unwrap();
+
+        // The first request leaves the window.
+        quotas
+            .reserve_at(recipient, None, None, 1, 1000 + WINDOW_SECS * 1000 + 1)
+            .unwrap();
+    }
+
+    #[test]
+    fn ip_amount_quota_survives_restart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("wal");
+        let config = FaucetConfig {
+            max_amount_per_ip: Some(100),
+            ..Default::default()
+        };
+        let ip = IpAddr::from_str("10.0.0.1").unwrap();
+
+        let quotas = self::quotas(config.clone(), &path);
+        quotas
+            .reserve_at(
+                SuiAddress::random_for_testing_only(),
+                Some(ip),
+                None,
+                60,
+                1000,
+            )
+            .unwrap();
+        drop(quotas);
+
+        let quotas = self::quotas(config, &path);
+        assert!(matches!(
+            quotas.reserve_at(
+                SuiAddress::random_for_testing_only(),
+                Some(ip),
+                None,
+                60,
+                2000
+            ),
+            Err(FaucetError::QuotaExceeded { .. })
+        ));
+        quotas
+            .reserve_at(
+                SuiAddress::random_for_testing_only(),
+                Some(ip),
+                None,
+                40,
+                2000,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn custom_coin_amount_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let coin_type = sui_types::parse_sui_type_tag("0x123::usdc::USDC").unwrap();
+        let other_coin_type = sui_types::parse_sui_type_tag("0x123::usdt::USDT").unwrap();
+        let config = FaucetConfig {
+            max_amount_per_address: Some(100),
+            custom_coins: vec![CustomCoinConfig::from_str(&format!(
+                "type={coin_type},amount=1000,max-amount-per-address=3000"
+            ))
+            .unwrap()],
+            ..Default::default()
+        };
+        let quotas = quotas(config, &tmp.path().join("wal"));
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas
+            .reserve_at(recipient, None, Some(&coin_type), 2000, 1000)
+            .unwrap();
+        // MIST is counted separately from the custom coin.
+        quotas.reserve_at(recipient, None, None, 100, 2000).unwrap();
+        quotas
+            .reserve_at(recipient, None, Some(&coin_type), 1000, 3000)
+            .unwrap();
+        assert_eq!(
+            quotas.reserve_at(recipient, None, Some(&coin_type), 1000, 4000),
+            Err(FaucetError::QuotaExceeded {
+                key: QuotaKey::Address(recipient).to_string(),
+                // Only the custom coin requests count towards its amount.
+                retry_after_secs: WINDOW_SECS - 3,
+            })
+        );
+
+        // Coin types without a limit on their amount are only limited by the number of requests.
+        quotas
+            .reserve_at(recipient, None, Some(&other_coin_type), 1_000_000, 4000)
+            .unwrap();
+    }
+
+    #[test]
+    fn released_requests_leave_the_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = FaucetConfig {
+            max_requests_per_address: Some(1),
+            max_amount_per_ip: Some(100),
+            ..Default::default()
+        };
+        let quotas = quotas(config, &tmp.path().join("wal"));
+        let recipient = SuiAddress::random_for_testing_only();
+        let ip = IpAddr::from_str("10.0.0.1").unwrap();
+
+        let reservation = quotas
+            .reserve_at(recipient, Some(ip), None, 100, 1000)
+            .unwrap();
+        assert!(quotas
+            .reserve_at(recipient, Some(ip), None, 100, 2000)
+            .is_err());
+
+        // A failed request doesn't count towards the quotas of its recipient and IP.
+        quotas.release(reservation).unwrap();
+        quotas
+            .reserve_at(recipient, Some(ip), None, 100, 3000)
+            .unwrap();
+    }
+
+    #[test]
+    fn large_quota_window() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = FaucetConfig {
+            max_requests_per_address: Some(1),
+            quota_window_secs: u64::MAX,
+            ..Default::default()
+        };
+        let wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        let quotas = RequestQuotas::new(&config, wal.quotas);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.reserve_at(recipient, None, None, 1, 1000).unwrap();
+        assert!(matches!(
+            quotas.reserve_at(recipient, None, None, 1, 2000),
+            Err(FaucetError::QuotaExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn allow_list_bypasses_quotas() {
+        let tmp = tempfile::tempdir().unwrap();
+        let recipient = SuiAddress::random_for_testing_only();
+        let config = FaucetConfig {
+            max_requests_per_address: Some(1),
+            max_requests_per_ip: Some(1),
+            quota_allow_list: vec![
+                AllowListEntry::from_str(&recipient.to_string()).unwrap(),
+                AllowListEntry::from_str("192.168.0.0/16").unwrap(),
+            ],
+            ..Default::default()
+        };
+        let quotas = quotas(config, &tmp.path().join("wal"));
+        let allowed_ip = IpAddr::from_str("192.168.1.2").unwrap();
+
+        for timestamp_ms in 0..3 {
+            quotas
+                .reserve_at(recipient, Some(allowed_ip), None, 1, timestamp_ms)
+                .unwrap();
+        }
+        let ip = IpAddr::from_str("10.0.0.1").unwrap();
+        quotas.reserve_at(recipient, Some(ip), None, 1, 3).unwrap();
+        assert!(quotas.reserve_at(recipient, Some(ip), None, 1, 4).is_err());
+    }
+
+    #[test]
+    fn parse_allow_list_entries() {
+        assert_eq!(
+            AllowListEntry::from_str("10.1.0.0/16").unwrap(),
+            AllowListEntry::Network {
+                ip: IpAddr::from_str("10.1.0.0").unwrap(),
+                prefix_len: 16
+            }
+        );
+        assert_eq!(
+            AllowListEntry::from_str("::1").unwrap(),
+            AllowListEntry::Network {
+                ip: IpAddr::from_str("::1").unwrap(),
+                prefix_len: 128
+            }
+        );
+        assert!(AllowListEntry::from_str("10.1.0.0/33").is_err());
+        assert!(AllowListEntry::from_str("not an entry").is_err());
+    }
+}
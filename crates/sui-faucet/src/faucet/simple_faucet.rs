@@ -16,32 +16,36 @@ use std::path::Path;
 use std::sync::{Arc, Weak};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use tap::tap::TapFallible;
-use tokio::sync::oneshot;
 use ttl_cache::TtlCache;
 use typed_store::Map;
 
 use sui_json_rpc_types::{
-    OwnedObjectRef, SuiObjectDataOptions, SuiTransactionBlockEffectsAPI,
+    OwnedObjectRef, SuiObjectDataOptions, SuiParsedData, SuiTransactionBlockEffectsAPI,
     SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::coin::{COIN_MINT_AND_TRANSFER_FUNC_NAME, COIN_MODULE_NAME};
 use sui_types::object::Owner;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::{
-    base_types::{ObjectID, SuiAddress, TransactionDigest},
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
     gas_coin::GasCoin,
-    transaction::{Transaction, TransactionData},
+    transaction::{ObjectArg, Transaction, TransactionData},
+    TypeTag, SUI_FRAMEWORK_PACKAGE_ID,
 };
 use tokio::sync::{
     mpsc::{self, Receiver, Sender},
     Mutex,
 };
 use tokio::time::{timeout, Duration};
+use tokio_util::sync::{CancellationToken, DropGuard};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use super::custom_coins::CustomCoin;
 use super::write_ahead_log::WriteAheadLog;
+use super::{CustomCoinConfig, CustomCoinSource, RequestQuotas};
 use crate::{
     BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
     FaucetError, FaucetReceipt,
@@ -56,14 +60,18 @@ pub struct SimpleFaucet {
     batch_consumer: Mutex<Receiver<ObjectID>>,
     pub metrics: FaucetMetrics,
     pub wal: Mutex<WriteAheadLog>,
+    pub quotas: RequestQuotas,
+    custom_coins: HashMap<TypeTag, CustomCoin>,
     request_producer: Sender<(Uuid, SuiAddress, Vec<u64>)>,
     batch_request_size: u64,
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
     ttl_expiration: u64,
     coin_amount: u64,
-    /// Shuts down the batch transfer task. Used only in testing.
+    /// Shuts down the batch transfer tasks. Used only in testing.
     #[allow(unused)]
-    batch_transfer_shutdown: parking_lot::Mutex<Option<oneshot::Sender<()>>>,
+    batch_transfer_shutdown: CancellationToken,
+    /// Shuts down the batch transfer tasks when the faucet is dropped.
+    _batch_transfer_shutdown_guard: DropGuard,
 }
 
 /// We do not just derive(Debug) because WalletContext and the WriteAheadLog do not implement Debug / are also hard
@@ -119,6 +127,7 @@ impl SimpleFaucet {
         let metrics = FaucetMetrics::new(prometheus_registry);
 
         let wal = WriteAheadLog::open(wal_path);
+        let quotas = RequestQuotas::new(&config, wal.quotas.clone());
         let mut pending = vec![];
 
         let (producer, consumer) = mpsc::channel(coins.len());
@@ -170,7 +179,20 @@ impl SimpleFaucet {
                     .unwrap();
             }
         }
-        let (batch_transfer_shutdown, mut rx_batch_transfer_shutdown) = oneshot::channel();
+        let batch_transfer_shutdown = CancellationToken::new();
+
+        let mut custom_coins = HashMap::new();
+        let mut custom_coin_receivers = vec![];
+        for custom in &config.custom_coins {
+            let (sender, receiver) = mpsc::channel::<(Uuid, SuiAddress, Vec<u64>)>(
+                config.max_request_queue_length as usize,
+            );
+            custom_coins.insert(
+                custom.coin_type.clone(),
+                CustomCoin::new(custom.clone(), sender),
+            );
+            custom_coin_receivers.push((custom.coin_type.clone(), receiver));
+        }
 
         let faucet = Self {
             wallet,
@@ -181,6 +203,8 @@ impl SimpleFaucet {
             batch_consumer: Mutex::new(batch_consumer),
             metrics,
             wal: Mutex::new(wal),
+            quotas,
+            custom_coins,
             request_producer: sender,
             batch_request_size: config.batch_request_size,
             // Max faucet requests times 10 minutes worth of requests to hold onto at max.
@@ -188,30 +212,26 @@ impl SimpleFaucet {
             task_id_cache: TtlCache::new(config.max_request_per_second as usize * 60 * 10).into(),
             ttl_expiration: config.ttl_expiration,
             coin_amount: config.amount,
-            batch_transfer_shutdown: parking_lot::Mutex::new(Some(batch_transfer_shutdown)),
+            batch_transfer_shutdown: batch_transfer_shutdown.clone(),
+            _batch_transfer_shutdown_guard: batch_transfer_shutdown.clone().drop_guard(),
         };
 
         let arc_faucet = Arc::new(faucet);
         let batch_clone = Arc::downgrade(&arc_faucet);
+        let shutdown = batch_transfer_shutdown.clone();
         spawn_monitored_task!(async move {
             info!("Starting task to handle batch faucet requests.");
             loop {
-                match batch_transfer_gases(
-                    &batch_clone,
-                    &mut receiver,
-                    &mut rx_batch_transfer_shutdown,
-                )
-                .await
-                {
+                match batch_transfer_gases(&batch_clone, &mut receiver, &shutdown).await {
+                    Ok(response) if response == TransactionDigest::ZERO => {
+                        info!("Exiting batch faucet task.");
+                        break;
+                    }
                     Ok(response) => {
-                        if response == TransactionDigest::ZERO {
-                            info!("Batch transfer incomplete due to faucet shutting down.");
-                        } else {
-                            info!(
-                                "Batch transfer completed with transaction digest: {:?}",
-                                response
-                            );
-                        }
+                        info!(
+                            "Batch transfer completed with transaction digest: {:?}",
+                            response
+                        );
                     }
                     Err(err) => {
                         error!("{:?}", err);
@@ -219,6 +239,41 @@ impl SimpleFaucet {
                 }
             }
         });
+        for (coin_type, mut receiver) in custom_coin_receivers {
+            let weak_faucet = Arc::downgrade(&arc_faucet);
+            let shutdown = batch_transfer_shutdown.clone();
+            spawn_monitored_task!(async move {
+                info!(
+                    ?coin_type,
+                    "Starting task to handle batch custom coin requests."
+                );
+                loop {
+                    match batch_transfer_custom_coins(
+                        &weak_faucet,
+                        &coin_type,
+                        &mut receiver,
+                        &shutdown,
+                    )
+                    .await
+                    {
+                        Ok(response) if response == TransactionDigest::ZERO => {
+                            info!(?coin_type, "Exiting batch custom coin task.");
+                            break;
+                        }
+                        Ok(response) => {
+                            info!(
+                                ?coin_type,
+                                "Batch custom coin transfer completed with transaction digest: {:?}",
+                                response
+                            );
+                        }
+                        Err(err) => {
+                            error!(?coin_type, "{:?}", err);
+                        }
+                    }
+                }
+            });
+        }
         // Retrying all the pending transactions from the WAL, before continuing.  Ignore return
         // values -- if the executions failed, the pending coins will simply remain in the WAL, and
         // not recycled.
@@ -354,6 +409,22 @@ impl SimpleFaucet {
         }
     }
 
+    /// Read the value of a `Coin<T>` of any coin type.
+    async fn get_coin_value(&self, coin_id: ObjectID) -> anyhow::Result<Option<u64>> {
+        let client = self.wallet.get_client().await?;
+        let coin_obj = client
+            .read_api()
+            .get_object_with_options(coin_id, SuiObjectDataOptions::new().with_content())
+            .await?;
+        Ok(match coin_obj.data.and_then(|o| o.content) {
+            // All coins have the same layout as gas coins.
+            Some(SuiParsedData::MoveObject(o)) => {
+                GasCoin::try_from(&o.fields).ok().map(|coin| coin.value())
+            }
+            _ => None,
+        })
+    }
+
     /// Similar to get_coin but checks that the owner is the active
     /// faucet address. If the coin exists, but does not have the correct owner,
     /// returns None
@@ -547,6 +618,96 @@ impl SimpleFaucet {
         }
     }
 
+    /// Send coins of a custom coin type, paying for gas with a coin from the gas coin pool.
+    async fn transfer_custom_coins(
+        &self,
+        custom: &CustomCoin,
+        amounts: &[u64],
+        recipient: SuiAddress,
+        uuid: Uuid,
+    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+        let recipients = vec![recipient; amounts.len()];
+        let response = self
+            .execute_custom_coin_txn(custom, &recipients, amounts, recipient, uuid, false)
+            .await?;
+        self.metrics.total_coin_requests_succeeded.inc();
+        self.check_and_map_transfer_gas_result(response, amounts.len(), recipient)
+            .await
+    }
+
+    /// Build and execute a transaction sending `amounts[i]` of a custom coin type to
+    /// `recipients[i]`, paying for gas with a coin from the gas coin pool, or from the batch gas
+    /// coin pool if `for_batch`. `wal_recipient` is the recipient the transaction is logged with.
+    async fn execute_custom_coin_txn(
+        &self,
+        custom: &CustomCoin,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+        wal_recipient: SuiAddress,
+        uuid: Uuid,
+        for_batch: bool,
+    ) -> Result<SuiTransactionBlockResponse, FaucetError> {
+        let gas_cost = self.get_gas_cost().await?;
+        let coin_id = loop {
+            match self.prepare_gas_coin(gas_cost, uuid, for_batch).await {
+                GasCoinResponse::ValidGasCoin(coin_id) => break coin_id,
+
+                GasCoinResponse::UnknownGasCoin(coin_id) => {
+                    self.recycle_custom_coin_txn_gas_coin(coin_id, uuid, for_batch)
+                        .await;
+                    return Err(FaucetError::FullnodeReadingError(format!(
+                        "unknown gas coin {coin_id:?}"
+                    )));
+                }
+
+                GasCoinResponse::GasCoinWithInsufficientBalance(coin_id)
+                | GasCoinResponse::InvalidGasCoin(coin_id) => {
+                    warn!(?uuid, ?coin_id, "Unusable gas coin, removing from pool");
+                    self.metrics.total_discarded_coins.inc();
+                }
+
+                GasCoinResponse::NoGasCoinAvailable => return Err(FaucetError::NoGasCoinAvailable),
+            }
+        };
+
+        let _guard = custom.lock.lock().await;
+        let tx_data = match self
+            .build_custom_coin_txn(coin_id, &custom.config, recipients, amounts, gas_cost)
+            .await
+        {
+            Ok(tx_data) => tx_data,
+            Err(err) => {
+                self.recycle_custom_coin_txn_gas_coin(coin_id, uuid, for_batch)
+                    .await;
+                return Err(err);
+            }
+        };
+
+        {
+            // Register the intention to send this transaction before we send it, so that if
+            // faucet fails or we give up before we get a definite response, we have a
+            // chance to retry later.
+            let mut wal = self.wal.lock().await;
+            wal.reserve(uuid, coin_id, wal_recipient, tx_data.clone())
+                .map_err(FaucetError::internal)?;
+        }
+        self.sign_and_execute_txn(uuid, wal_recipient, coin_id, tx_data, for_batch)
+            .await
+    }
+
+    async fn recycle_custom_coin_txn_gas_coin(
+        &self,
+        coin_id: ObjectID,
+        uuid: Uuid,
+        for_batch: bool,
+    ) {
+        if for_batch {
+            self.recycle_gas_coin_for_batch(coin_id, uuid).await
+        } else {
+            self.recycle_gas_coin(coin_id, uuid).await
+        }
+    }
+
     async fn recycle_gas_coin(&self, coin_id: ObjectID, uuid: Uuid) {
         // Once transactions are done, in despite of success or failure,
         // we put back the coins. The producer should never wait indefinitely,
@@ -678,6 +839,107 @@ impl SimpleFaucet {
             })
     }
 
+    /// Build a transaction sending `amounts[i]` of a custom coin type to `recipients[i]`, either
+    /// split from the faucet's coins of that type or minted with its treasury cap.
+    async fn build_custom_coin_txn(
+        &self,
+        gas_coin_id: ObjectID,
+        custom: &CustomCoinConfig,
+        recipients: &[SuiAddress],
+        amounts: &[u64],
+        budget: u64,
+    ) -> Result<TransactionData, FaucetError> {
+        let gas_payment = self
+            .wallet
+            .get_object_ref(gas_coin_id)
+            .await
+            .map_err(FaucetError::internal)?;
+        let gas_price = self.get_gas_price().await?;
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        match custom.source {
+            CustomCoinSource::CoinPool => {
+                let coins = self.select_custom_coins(custom, amounts).await?;
+                builder
+                    .pay(coins, recipients.to_vec(), amounts.to_vec())
+                    .map_err(FaucetError::internal)?;
+            }
+            CustomCoinSource::TreasuryCap(treasury_cap_id) => {
+                let treasury_cap = self
+                    .wallet
+                    .get_object_ref(treasury_cap_id)
+                    .await
+                    .map_err(FaucetError::internal)?;
+                let treasury_cap = builder
+                    .obj(ObjectArg::ImmOrOwnedObject(treasury_cap))
+                    .map_err(FaucetError::internal)?;
+                for (recipient, amount) in recipients.iter().zip(amounts) {
+                    let recipient = builder.pure(*recipient).map_err(FaucetError::internal)?;
+                    let amount = builder.pure(*amount).map_err(FaucetError::internal)?;
+                    builder.programmable_move_call(
+                        SUI_FRAMEWORK_PACKAGE_ID,
+                        COIN_MODULE_NAME.to_owned(),
+                        COIN_MINT_AND_TRANSFER_FUNC_NAME.to_owned(),
+                        vec![custom.coin_type.clone()],
+                        vec![treasury_cap, amount, recipient],
+                    );
+                }
+            }
+        }
+
+        Ok(TransactionData::new_programmable(
+            self.active_address,
+            vec![gas_payment],
+            builder.finish(),
+            budget,
+            gas_price,
+        ))
+    }
+
+    /// Select enough of the faucet's coins of a custom coin type to pay `amounts`.
+    async fn select_custom_coins(
+        &self,
+        custom: &CustomCoinConfig,
+        amounts: &[u64],
+    ) -> Result<Vec<ObjectRef>, FaucetError> {
+        let client = self
+            .wallet
+            .get_client()
+            .await
+            .map_err(|e| FaucetError::Wallet(format!("Unable to get client: {e:?}")))?;
+        let total_amount: u64 = amounts.iter().sum();
+        let mut selected = vec![];
+        let mut balance = 0;
+        let mut cursor = None;
+        while balance < total_amount {
+            let page = client
+                .coin_read_api()
+                .get_coins(
+                    self.active_address,
+                    Some(custom.coin_type.to_string()),
+                    cursor,
+                    None,
+                )
+                .await
+                .map_err(|e| FaucetError::FullnodeReadingError(format!("{e:?}")))?;
+            for coin in page.data {
+                if balance >= total_amount {
+                    break;
+                }
+                balance += coin.balance;
+                selected.push(coin.object_ref());
+            }
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        if balance < total_amount {
+            return Err(FaucetError::InsuffientBalance);
+        }
+        Ok(selected)
+    }
+
     async fn check_and_map_transfer_gas_result(
         &self,
         res: SuiTransactionBlockResponse,
@@ -738,10 +1000,13 @@ impl SimpleFaucet {
         ))
     }
 
+    /// Store the receipts of the batched `requests`, each of whose coins has a value of
+    /// `coin_amount`, into the status map.
     async fn check_and_map_batch_transfer_gas_result(
         &self,
         res: SuiTransactionBlockResponse,
         requests: Vec<(Uuid, SuiAddress, Vec<u64>)>,
+        coin_amount: u64,
     ) -> Result<(), FaucetError> {
         // Grab the list of created coins and turn it into a map of destination SuiAddress to Vec<Coins>
         let created = res
@@ -797,7 +1062,7 @@ impl SimpleFaucet {
                 .map(|coin| CoinInfo {
                     id: coin.object_id(),
                     transfer_tx_digest: res.digest,
-                    amount: self.coin_amount,
+                    amount: coin_amount,
                 })
                 .collect();
 
@@ -817,14 +1082,55 @@ impl SimpleFaucet {
         Ok(())
     }
 
+    /// Look up the coins sent by a request, and store its receipt into the status map.
+    async fn record_receipt(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        digest: TransactionDigest,
+        coin_ids: Vec<ObjectID>,
+    ) -> FaucetReceipt {
+        let mut sent = Vec::with_capacity(coin_ids.len());
+        let coin_results =
+            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin_value(*coin_id)))
+                .await;
+        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
+            let amount = if let Ok(Some(value)) = res {
+                value
+            } else {
+                info!(
+                    ?recipient,
+                    ?coin_id,
+                    uuid = ?id,
+                    "Could not find coin after successful transaction, error: {:?}",
+                    &res,
+                );
+                0
+            };
+            sent.push(CoinInfo {
+                transfer_tx_digest: digest,
+                amount,
+                id: coin_id,
+            });
+        }
+
+        // Store into status map that the txn was successful for backwards compatibility
+        let faucet_receipt = FaucetReceipt { sent };
+        let mut task_map = self.task_id_cache.lock().await;
+        task_map.insert(
+            id,
+            BatchSendStatus {
+                status: BatchSendStatusType::SUCCEEDED,
+                transferred_gas_objects: Some(faucet_receipt.clone()),
+            },
+            Duration::from_secs(self.ttl_expiration),
+        );
+        faucet_receipt
+    }
+
     #[cfg(test)]
     pub(crate) fn shutdown_batch_send_task(&self) {
-        self.batch_transfer_shutdown
-            .lock()
-            .take()
-            .unwrap()
-            .send(())
-            .unwrap();
+        self.batch_transfer_shutdown.cancel();
     }
 
     #[cfg(test)]
@@ -871,52 +1177,71 @@ impl Faucet for SimpleFaucet {
         let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
 
         info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
-        let mut sent = Vec::with_capacity(coin_ids.len());
-        let coin_results =
-            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
-        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
-            let amount = if let Ok(Some((_, coin))) = res {
-                coin.value()
-            } else {
-                info!(
-                    ?recipient,
-                    ?coin_id,
-                    uuid = ?id,
-                    "Could not find coin after successful transaction, error: {:?}",
-                    &res,
-                );
-                0
-            };
-            sent.push(CoinInfo {
-                transfer_tx_digest: digest,
-                amount,
-                id: coin_id,
-            });
-        }
+        Ok(self.record_receipt(id, recipient, digest, coin_ids).await)
+    }
 
-        // Store into status map that the txn was successful for backwards compatibility
-        let faucet_receipt = FaucetReceipt { sent };
+    async fn send_custom_coins(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError> {
+        info!(?recipient, uuid = ?id, ?coin_type, ?amounts, "Getting custom coin faucet requests");
+        let custom = self
+            .custom_coins
+            .get(coin_type)
+            .ok_or_else(|| FaucetError::UnsupportedCoinType(coin_type.to_string()))?;
+
+        let (digest, coin_ids) = self
+            .transfer_custom_coins(custom, amounts, recipient, id)
+            .await?;
+
+        info!(uuid = ?id, ?recipient, ?digest, "Custom coin txn succeeded");
+        Ok(self.record_receipt(id, recipient, digest, coin_ids).await)
+    }
+
+    async fn batch_send(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        amounts: &[u64],
+    ) -> Result<BatchFaucetReceipt, FaucetError> {
+        info!(?recipient, uuid = ?id, "Getting faucet request");
+        if self
+            .request_producer
+            .try_send((id, recipient, amounts.to_vec()))
+            .is_err()
+        {
+            return Err(FaucetError::BatchSendQueueFull);
+        }
         let mut task_map = self.task_id_cache.lock().await;
         task_map.insert(
             id,
             BatchSendStatus {
-                status: BatchSendStatusType::SUCCEEDED,
-                transferred_gas_objects: Some(faucet_receipt.clone()),
+                status: BatchSendStatusType::INPROGRESS,
+                transferred_gas_objects: None,
             },
             Duration::from_secs(self.ttl_expiration),
         );
-
-        Ok(faucet_receipt)
+        Ok(BatchFaucetReceipt {
+            task: id.to_string(),
+        })
     }
 
-    async fn batch_send(
+    async fn batch_send_custom_coins(
         &self,
         id: Uuid,
         recipient: SuiAddress,
+        coin_type: &TypeTag,
         amounts: &[u64],
     ) -> Result<BatchFaucetReceipt, FaucetError> {
-        info!(?recipient, uuid = ?id, "Getting faucet request");
-        if self
+        info!(?recipient, uuid = ?id, ?coin_type, "Getting custom coin faucet request");
+        let custom = self
+            .custom_coins
+            .get(coin_type)
+            .ok_or_else(|| FaucetError::UnsupportedCoinType(coin_type.to_string()))?;
+        if custom
             .request_producer
             .try_send((id, recipient, amounts.to_vec()))
             .is_err()
@@ -968,7 +1293,7 @@ pub async fn batch_gather(
 pub async fn batch_transfer_gases(
     weak_faucet: &Weak<SimpleFaucet>,
     request_consumer: &mut Receiver<(Uuid, SuiAddress, Vec<u64>)>,
-    rx_batch_transfer_shutdown: &mut oneshot::Receiver<()>,
+    shutdown: &CancellationToken,
 ) -> Result<TransactionDigest, FaucetError> {
     let mut requests = Vec::new();
 
@@ -982,7 +1307,7 @@ pub async fn batch_transfer_gases(
                 return Ok(TransactionDigest::ZERO);
             };
         }
-        _ = rx_batch_transfer_shutdown => {
+        _ = shutdown.cancelled() => {
             info!("Shutdown signal received. Exiting faucet ...");
             return Ok(TransactionDigest::ZERO);
         }
@@ -1051,7 +1376,11 @@ pub async fn batch_transfer_gases(
                     .add(total_requests as i64);
 
                 faucet
-                    .check_and_map_batch_transfer_gas_result(response.clone(), requests)
+                    .check_and_map_batch_transfer_gas_result(
+                        response.clone(),
+                        requests,
+                        faucet.coin_amount,
+                    )
                     .await?;
 
                 return Ok(response.digest);
@@ -1084,11 +1413,91 @@ pub async fn batch_transfer_gases(
     }
 }
 
+// Function to process the batch send of the queue of a custom coin type
+pub async fn batch_transfer_custom_coins(
+    weak_faucet: &Weak<SimpleFaucet>,
+    coin_type: &TypeTag,
+    request_consumer: &mut Receiver<(Uuid, SuiAddress, Vec<u64>)>,
+    shutdown: &CancellationToken,
+) -> Result<TransactionDigest, FaucetError> {
+    let mut requests = Vec::new();
+
+    tokio::select! {
+        first_req = request_consumer.recv() => {
+            let Some(first_req) = first_req else {
+                // Should only happen after the Faucet has shut down
+                info!("No more faucet requests will be received. Exiting batch custom coin task ...");
+                return Ok(TransactionDigest::ZERO);
+            };
+            requests.push(first_req);
+        }
+        _ = shutdown.cancelled() => {
+            info!("Shutdown signal received. Exiting batch custom coin task ...");
+            return Ok(TransactionDigest::ZERO);
+        }
+    };
+
+    let Some(faucet) = weak_faucet.upgrade() else {
+        info!("Faucet has shut down already. Exiting ...");
+        return Ok(TransactionDigest::ZERO);
+    };
+    let custom = faucet
+        .custom_coins
+        .get(coin_type)
+        .ok_or_else(|| FaucetError::UnsupportedCoinType(coin_type.to_string()))?;
+
+    if timeout(
+        BATCH_TIMEOUT,
+        batch_gather(request_consumer, &mut requests, faucet.batch_request_size),
+    )
+    .await
+    .is_err()
+    {
+        info!("Batch timeout elapsed while waiting.");
+    };
+
+    let total_requests = requests.len();
+    // The UUID here is for the batched request
+    let uuid = Uuid::new_v4();
+    info!(
+        ?uuid,
+        ?coin_type,
+        "Batch custom coin transfer attempted of size: {:?}",
+        total_requests
+    );
+    let (recipients, amounts): (Vec<_>, Vec<_>) = requests
+        .iter()
+        .flat_map(|(_, recipient, amounts)| amounts.iter().map(|amount| (*recipient, *amount)))
+        .unzip();
+
+    // Because we are batching transactions to faucet, we will just not use a real recipient for
+    // sui address, and instead just fill it with the ZERO address.
+    let response = faucet
+        .execute_custom_coin_txn(custom, &recipients, &amounts, SuiAddress::ZERO, uuid, true)
+        .await?;
+
+    faucet
+        .metrics
+        .total_coin_requests_succeeded
+        .add(total_requests as i64);
+
+    faucet
+        .check_and_map_batch_transfer_gas_result(response.clone(), requests, custom.config.amount)
+        .await?;
+
+    Ok(response.digest)
+}
+
 #[cfg(test)]
 mod tests {
     use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
-    use sui_json_rpc_types::SuiExecutionStatus;
+    use sui_json_rpc_types::{ObjectChange, SuiExecutionStatus};
     use sui_sdk::wallet_context::WalletContext;
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use sui_types::coin::TreasuryCap;
+    use sui_types::gas_coin::GAS;
+    use sui_types::parse_sui_struct_tag;
+    use sui_types::transaction::{Command, TransactionDataAPI, TransactionKind};
     use test_cluster::TestClusterBuilder;
 
     use super::*;
@@ -1886,6 +2295,378 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_select_custom_coins() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let mut context = test_cluster.wallet;
+        let (coin_type, _) = publish_managed_coin(&mut context, &[100, 100, 100]).await;
+        let custom = CustomCoinConfig {
+            coin_type,
+            amount: 10,
+            source: CustomCoinSource::CoinPool,
+            max_amount_per_address: None,
+            max_amount_per_ip: None,
+        };
+
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &Registry::new(),
+            &tmp.path().join("faucet.wal"),
+            FaucetConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            faucet
+                .select_custom_coins(&custom, &[100])
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            faucet
+                .select_custom_coins(&custom, &[60, 60])
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            faucet
+                .select_custom_coins(&custom, &[100, 100, 100])
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+        assert_eq!(
+            faucet.select_custom_coins(&custom, &[301]).await,
+            Err(FaucetError::InsuffientBalance)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_custom_coin_txn() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let mut context = test_cluster.wallet;
+        let (coin_type, treasury_cap) = publish_managed_coin(&mut context, &[100, 100]).await;
+        let gas_coin_id = *get_current_gases(address, &mut context).await[0].id();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &Registry::new(),
+            &tmp.path().join("faucet.wal"),
+            FaucetConfig::default(),
+        )
+        .await
+        .unwrap();
+        let gas_cost = faucet.get_gas_cost().await.unwrap();
+        let recipients = [
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        ];
+        let amounts = [150, 10];
+
+        // Paying from the faucet's coins merges the coins needed, and splits the amounts off them.
+        let pool = CustomCoinConfig {
+            coin_type: coin_type.clone(),
+            amount: 10,
+            source: CustomCoinSource::CoinPool,
+            max_amount_per_address: None,
+            max_amount_per_ip: None,
+        };
+        let tx_data = faucet
+            .build_custom_coin_txn(gas_coin_id, &pool, &recipients, &amounts, gas_cost)
+            .await
+            .unwrap();
+        assert_eq!(tx_data.sender(), address);
+        let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+            panic!(
+                "Expected a programmable transaction, got {:?}",
+                tx_data.kind()
+            );
+        };
+        assert!(matches!(pt.commands[0], Command::MergeCoins(_, ref coins) if coins.len() == 1));
+        assert!(
+            matches!(pt.commands[1], Command::SplitCoins(_, ref amounts) if amounts.len() == 2)
+        );
+        assert!(pt.commands[2..]
+            .iter()
+            .all(|command| matches!(command, Command::TransferObjects(..))));
+        assert_eq!(pt.commands.len(), 4);
+
+        // Minting with the treasury cap mints and transfers each coin.
+        let mint = CustomCoinConfig {
+            source: CustomCoinSource::TreasuryCap(treasury_cap),
+            ..pool
+        };
+        let tx_data = faucet
+            .build_custom_coin_txn(gas_coin_id, &mint, &recipients, &amounts, gas_cost)
+            .await
+            .unwrap();
+        let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+            panic!(
+                "Expected a programmable transaction, got {:?}",
+                tx_data.kind()
+            );
+        };
+        assert_eq!(pt.commands.len(), amounts.len());
+        for command in &pt.commands {
+            let Command::MoveCall(call) = command else {
+                panic!("Expected a move call, got {command:?}");
+            };
+            assert_eq!(call.package, SUI_FRAMEWORK_PACKAGE_ID);
+            assert_eq!(call.module, COIN_MODULE_NAME.to_owned());
+            assert_eq!(call.function, COIN_MINT_AND_TRANSFER_FUNC_NAME.to_owned());
+            assert_eq!(call.type_arguments, vec![coin_type.clone()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_custom_coins() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let mut context = test_cluster.wallet;
+        let (minted_coin_type, treasury_cap) = publish_managed_coin(&mut context, &[]).await;
+        // A second package has a coin type of its own, whose coins are minted up front.
+        let (pooled_coin_type, _) = publish_managed_coin(&mut context, &[100, 100]).await;
+        let config = FaucetConfig {
+            custom_coins: vec![
+                CustomCoinConfig {
+                    coin_type: minted_coin_type.clone(),
+                    amount: 10,
+                    source: CustomCoinSource::TreasuryCap(treasury_cap),
+                    max_amount_per_address: None,
+                    max_amount_per_ip: None,
+                },
+                CustomCoinConfig {
+                    coin_type: pooled_coin_type.clone(),
+                    amount: 10,
+                    source: CustomCoinSource::CoinPool,
+                    max_amount_per_address: None,
+                    max_amount_per_ip: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &Registry::new(),
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        for coin_type in [&minted_coin_type, &pooled_coin_type] {
+            let recipient = SuiAddress::random_for_testing_only();
+            let amounts = vec![1, 2, 150];
+            let uuid = Uuid::new_v4();
+            let FaucetReceipt { sent } = faucet
+                .send_custom_coins(uuid, recipient, coin_type, &amounts)
+                .await
+                .unwrap();
+            let mut actual_amounts: Vec<u64> = sent.iter().map(|c| c.amount).collect();
+            actual_amounts.sort_unstable();
+            assert_eq!(actual_amounts, amounts);
+
+            let status = faucet.get_batch_send_status(uuid).await.unwrap();
+            assert_eq!(status.status, BatchSendStatusType::SUCCEEDED);
+
+            let client = faucet.wallet.get_client().await.unwrap();
+            let balance = client
+                .coin_read_api()
+                .get_balance(recipient, Some(coin_type.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(balance.total_balance, 153);
+        }
+
+        assert_eq!(
+            faucet
+                .send_custom_coins(Uuid::new_v4(), SuiAddress::ZERO, &GAS::type_tag(), &[1])
+                .await
+                .unwrap_err(),
+            FaucetError::UnsupportedCoinType(GAS::type_tag().to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_send_custom_coins() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let mut context = test_cluster.wallet;
+        let gases = get_current_gases(address, &mut context).await;
+        // Split some extra gas coins so that we can test batch queue
+        SuiClientCommands::SplitCoin {
+            coin_id: *gases[0].id(),
+            amounts: None,
+            gas_budget: 50000000,
+            gas: None,
+            count: Some(10),
+            serialize_unsigned_transaction: false,
+            serialize_signed_transaction: false,
+        }
+        .execute(&mut context)
+        .await
+        .expect("split failed");
+        let (coin_type, treasury_cap) = publish_managed_coin(&mut context, &[]).await;
+        let amount = 10;
+        let config = FaucetConfig {
+            custom_coins: vec![CustomCoinConfig {
+                coin_type: coin_type.clone(),
+                amount,
+                source: CustomCoinSource::TreasuryCap(treasury_cap),
+                max_amount_per_address: None,
+                max_amount_per_ip: None,
+            }],
+            ..Default::default()
+        };
+
+        let tmp = tempfile::tempdir().unwrap();
+        let faucet = SimpleFaucet::new(
+            context,
+            &Registry::new(),
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        let target_addresses: Vec<SuiAddress> = (0..2)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        let coins_sent = 2;
+        let amounts = &vec![amount; coins_sent];
+
+        let response = futures::future::join_all(target_addresses.iter().map(|address| {
+            faucet.batch_send_custom_coins(Uuid::new_v4(), *address, &coin_type, amounts)
+        }))
+        .await
+        .into_iter()
+        .map(|res| res.unwrap())
+        .collect::<Vec<BatchFaucetReceipt>>();
+
+        let mut status_results;
+        loop {
+            status_results =
+                futures::future::join_all(response.clone().iter().map(|task| {
+                    faucet.get_batch_send_status(Uuid::parse_str(&task.task).unwrap())
+                }))
+                .await
+                .into_iter()
+                .map(|res| res.unwrap())
+                .collect::<Vec<BatchSendStatus>>();
+
+            // All requests are submitted and picked up by the same batch, so one success in the test
+            // will guarantee all success.
+            if status_results[0].status == BatchSendStatusType::SUCCEEDED {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        for status in status_results {
+            assert_eq!(status.status, BatchSendStatusType::SUCCEEDED);
+            let sent = status.transferred_gas_objects.unwrap().sent;
+            assert_eq!(sent.len(), coins_sent);
+            for coin in sent {
+                assert_eq!(coin.amount, amount);
+            }
+        }
+        let client = faucet.wallet.get_client().await.unwrap();
+        for address in target_addresses {
+            let balance = client
+                .coin_read_api()
+                .get_balance(address, Some(coin_type.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(balance.total_balance, (amount * coins_sent as u64) as u128);
+        }
+    }
+
+    /// Publish the `fungible_tokens` example package, which sends the `TreasuryCap` of its
+    /// `MANAGED` coin to the active address, and mint coins of `amounts` to the active address
+    /// with it.
+    async fn publish_managed_coin(
+        context: &mut WalletContext,
+        amounts: &[u64],
+    ) -> (TypeTag, ObjectID) {
+        let sender = context.active_address().unwrap();
+        let gas_price = context.get_reference_gas_price().await.unwrap();
+        let gas_object = context
+            .get_one_gas_object_owned_by_address(sender)
+            .await
+            .unwrap()
+            .unwrap();
+        let txn = context.sign_transaction(
+            &TestTransactionBuilder::new(sender, gas_object, gas_price)
+                .publish_examples("fungible_tokens")
+                .build(),
+        );
+        let response = context.execute_transaction_must_succeed(txn).await;
+        let object_changes = response.object_changes.unwrap();
+        let package_id = object_changes
+            .iter()
+            .find_map(|change| match change {
+                ObjectChange::Published { package_id, .. } => Some(*package_id),
+                _ => None,
+            })
+            .unwrap();
+        let coin_struct = parse_sui_struct_tag(&format!("{package_id}::managed::MANAGED")).unwrap();
+        let treasury_cap_type = TreasuryCap::type_(coin_struct.clone());
+        let treasury_cap = object_changes
+            .iter()
+            .find_map(|change| match change {
+                ObjectChange::Created {
+                    object_type,
+                    object_id,
+                    ..
+                } if *object_type == treasury_cap_type => Some(*object_id),
+                _ => None,
+            })
+            .unwrap();
+        let coin_type = TypeTag::Struct(Box::new(coin_struct));
+
+        if !amounts.is_empty() {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let treasury_cap_ref = context.get_object_ref(treasury_cap).await.unwrap();
+            let treasury_cap_arg = builder
+                .obj(ObjectArg::ImmOrOwnedObject(treasury_cap_ref))
+                .unwrap();
+            let recipient = builder.pure(sender).unwrap();
+            for amount in amounts {
+                let amount = builder.pure(*amount).unwrap();
+                builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    COIN_MODULE_NAME.to_owned(),
+                    COIN_MINT_AND_TRANSFER_FUNC_NAME.to_owned(),
+                    vec![coin_type.clone()],
+                    vec![treasury_cap_arg, amount, recipient],
+                );
+            }
+            let gas_object = context
+                .get_one_gas_object_owned_by_address(sender)
+                .await
+                .unwrap()
+                .unwrap();
+            let txn = context.sign_transaction(
+                &TestTransactionBuilder::new(sender, gas_object, gas_price)
+                    .programmable(builder.finish())
+                    .build(),
+            );
+            context.execute_transaction_must_succeed(txn).await;
+        }
+
+        (coin_type, treasury_cap)
+    }
+
     async fn test_send_interface_has_success_status(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
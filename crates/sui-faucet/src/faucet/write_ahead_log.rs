@@ -14,6 +14,8 @@ use tracing::info;
 use typed_store_derive::DBMapUtils;
 use uuid::Uuid;
 
+use super::quotas::{QuotaKey, QuotaUsage};
+
 /// Persistent log of transactions paying out sui from the faucet, keyed by the coin serving the
 /// request.  Transactions are expected to be written to the log before they are sent to full-node,
 /// and removed after receiving a response back, before the coin becomes available for subsequent
@@ -21,9 +23,13 @@ use uuid::Uuid;
 ///
 /// This allows the faucet to go down and back up, and not forget which requests were in-flight that
 /// it needs to confirm succeeded or failed.
+///
+/// The usage of each request quota is stored alongside the log, so that quotas also survive the
+/// faucet restarting.
 #[derive(DBMapUtils, Clone)]
 pub struct WriteAheadLog {
     pub log: DBMap<ObjectID, Entry>,
+    pub quotas: DBMap<QuotaKey, QuotaUsage>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
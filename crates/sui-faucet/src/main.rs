@@ -3,15 +3,16 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::Path,
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{ConnectInfo, Path},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
 use clap::Parser;
 use http::Method;
 use mysten_metrics::spawn_monitored_task;
+use serde::Serialize;
 use std::env;
 use std::{
     borrow::Cow,
@@ -21,10 +22,13 @@ use std::{
 };
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
 use sui_faucet::{
-    BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet,
+    BatchFaucetReceipt, BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig,
+    FaucetError, FaucetRequest, FaucetResponse, QuotaReservation, RequestMetricsLayer,
+    SimpleFaucet,
 };
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
+use sui_types::{parse_sui_type_tag, TypeTag};
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
@@ -115,13 +119,16 @@ async fn main() -> Result<(), anyhow::Error> {
             // Every config.wal_retry_interval (Default: 300 seconds) we try to clear the wal coins
             tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
             app_state.faucet.retry_wal_coins().await.unwrap();
+            if let Err(err) = app_state.faucet.quotas.prune() {
+                warn!("Failed to prune request quotas: {:?}", err);
+            }
         }
     });
 
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -134,8 +141,10 @@ async fn health() -> &'static str {
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let id = Uuid::new_v4();
     // ID for traceability
     info!(uuid = ?id, "Got new gas request.");
@@ -146,11 +155,35 @@ async fn batch_request_gas(
             Json(BatchFaucetResponse::from(FaucetError::Internal(
                 "Input Error.".to_string(),
             ))),
-        );
+        )
+            .into_response();
     };
 
+    let ip = client_ip(&state.config, &headers, addr);
+    if let Some(coin_type) = &request.coin_type {
+        return request_custom_coins(state, id, request.recipient, coin_type, ip).await;
+    }
+
+    let amount = state
+        .config
+        .amount
+        .saturating_mul(state.config.num_coins as u64);
+    let reservation = match reserve_quotas::<BatchFaucetResponse>(
+        &state,
+        id,
+        request.recipient,
+        ip,
+        None,
+        amount,
+    ) {
+        Ok(reservation) => reservation,
+        Err(response) => return response,
+    };
+
+    let task_state = state.clone();
     if state.config.batch_enabled {
         let result = spawn_monitored_task!(async move {
+            let state = task_state;
             state
                 .faucet
                 .batch_send(
@@ -166,20 +199,23 @@ async fn batch_request_gas(
         match result {
             Ok(v) => {
                 info!(uuid =?id, "Request is successfully served");
-                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v)))
+                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v))).into_response()
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                release_quotas(&state, id, reservation);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
                 )
+                    .into_response()
             }
         }
     } else {
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
         let result = spawn_monitored_task!(async move {
+            let state = task_state;
             state
                 .faucet
                 .send(
@@ -195,14 +231,16 @@ async fn batch_request_gas(
         match result {
             Ok(_) => {
                 info!(uuid =?id, "Request is successfully served");
-                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id)))
+                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id))).into_response()
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                release_quotas(&state, id, reservation);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
                 )
+                    .into_response()
             }
         }
     }
@@ -239,16 +277,35 @@ async fn request_status(
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
-    let result = match payload {
-        FaucetRequest::FixedAmountRequest(requests) => {
+    let (result, reservation) = match payload {
+        FaucetRequest::FixedAmountRequest(requests) if requests.coin_type.is_none() => {
+            let amount = state
+                .config
+                .amount
+                .saturating_mul(state.config.num_coins as u64);
+            let reservation = match reserve_quotas::<FaucetResponse>(
+                &state,
+                id,
+                requests.recipient,
+                client_ip(&state.config, &headers, addr),
+                None,
+                amount,
+            ) {
+                Ok(reservation) => reservation,
+                Err(response) => return response,
+            };
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
-            spawn_monitored_task!(async move {
+            let task_state = state.clone();
+            let result = spawn_monitored_task!(async move {
+                let state = task_state;
                 state
                     .faucet
                     .send(
@@ -259,7 +316,8 @@ async fn request_gas(
                     .await
             })
             .await
-            .unwrap()
+            .unwrap();
+            (result, reservation)
         }
         _ => {
             return (
@@ -268,23 +326,162 @@ async fn request_gas(
                     "Input Error.".to_string(),
                 ))),
             )
+                .into_response()
         }
     };
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
-            (StatusCode::CREATED, Json(FaucetResponse::from(v)))
+            (StatusCode::CREATED, Json(FaucetResponse::from(v))).into_response()
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            release_quotas(&state, id, reservation);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
             )
+                .into_response()
+        }
+    }
+}
+
+/// Serve a /v1/gas request for one of the faucet's custom coin types. The response carries the task
+/// id to look their status up with.
+async fn request_custom_coins(
+    state: Arc<AppState>,
+    id: Uuid,
+    recipient: SuiAddress,
+    coin_type: &str,
+    ip: IpAddr,
+) -> Response {
+    let custom = parse_sui_type_tag(coin_type).ok().and_then(|coin_type| {
+        state
+            .config
+            .custom_coins
+            .iter()
+            .find(|custom| custom.coin_type == coin_type)
+            .cloned()
+    });
+    let Some(custom) = custom else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(BatchFaucetResponse::from(FaucetError::UnsupportedCoinType(
+                coin_type.to_string(),
+            ))),
+        )
+            .into_response();
+    };
+
+    let amount = custom.amount.saturating_mul(state.config.num_coins as u64);
+    let reservation = match reserve_quotas::<BatchFaucetResponse>(
+        &state,
+        id,
+        recipient,
+        ip,
+        Some(&custom.coin_type),
+        amount,
+    ) {
+        Ok(reservation) => reservation,
+        Err(response) => return response,
+    };
+
+    let task_state = state.clone();
+    let result = spawn_monitored_task!(async move {
+        let state = task_state;
+        let amounts = vec![custom.amount; state.config.num_coins];
+        if state.config.batch_enabled {
+            state
+                .faucet
+                .batch_send_custom_coins(id, recipient, &custom.coin_type, &amounts)
+                .await
+        } else {
+            state
+                .faucet
+                .send_custom_coins(id, recipient, &custom.coin_type, &amounts)
+                .await
+                .map(|_| BatchFaucetReceipt {
+                    task: id.to_string(),
+                })
+        }
+    })
+    .await
+    .unwrap();
+
+    match result {
+        Ok(v) => {
+            info!(uuid =?id, "Request is successfully served");
+            (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v))).into_response()
+        }
+        Err(v) => {
+            warn!(uuid =?id, "Failed to request custom coins: {:?}", v);
+            release_quotas(&state, id, reservation);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(BatchFaucetResponse::from(v)),
+            )
+                .into_response()
         }
     }
 }
 
+/// The IP address of the client, from the header set by the reverse proxy in front of the faucet if
+/// one is configured, or else the address of the peer.
+fn client_ip(config: &FaucetConfig, headers: &HeaderMap, peer: SocketAddr) -> IpAddr {
+    let Some(header) = &config.client_ip_header else {
+        return peer.ip();
+    };
+    let ip = headers
+        .get(header.as_str())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok());
+    ip.unwrap_or_else(|| {
+        warn!(?peer, "Missing or invalid {header} header");
+        peer.ip()
+    })
+}
+
+/// Reserve the request of `amount` of `coin_type` (or MIST if it is `None`) against the quotas of
+/// its recipient and source IP, or return the response rejecting it if it exceeds one of them.
+fn reserve_quotas<R: From<FaucetError> + Serialize>(
+    state: &AppState,
+    id: Uuid,
+    recipient: SuiAddress,
+    ip: IpAddr,
+    coin_type: Option<&TypeTag>,
+    amount: u64,
+) -> Result<QuotaReservation, Response> {
+    let err = match state
+        .faucet
+        .quotas
+        .reserve(recipient, Some(ip), coin_type, amount)
+    {
+        Ok(reservation) => return Ok(reservation),
+        Err(err) => err,
+    };
+    warn!(uuid = ?id, ?ip, "Rejected gas request: {:?}", err);
+    let response = match err {
+        FaucetError::QuotaExceeded {
+            retry_after_secs, ..
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after_secs.to_string())],
+            Json(R::from(err)),
+        )
+            .into_response(),
+        err => (StatusCode::INTERNAL_SERVER_ERROR, Json(R::from(err))).into_response(),
+    };
+    Err(response)
+}
+
+/// Give the quota reserved by a request which failed back to its recipient and source IP.
+fn release_quotas(state: &AppState, id: Uuid, reservation: QuotaReservation) {
+    if let Err(err) = state.faucet.quotas.release(reservation) {
+        warn!(uuid = ?id, "Failed to release request quotas: {:?}", err);
+    }
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
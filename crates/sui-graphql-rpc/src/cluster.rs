@@ -4,6 +4,7 @@
 use crate::client::simple_client::SimpleClient;
 use crate::config::ConnectionConfig;
 use crate::config::ServerConfig;
+use crate::config::TxExecFullNodeConfig;
 use crate::server::simple_server::start_example_server;
 use mysten_metrics::init_metrics;
 use rand::rngs::StdRng;
@@ -58,7 +59,11 @@ pub async fn start_cluster(
         start_test_indexer(Some(db_url), val_fn.rpc_url().to_string()).await;
 
     // Starts graphql server
-    let graphql_server_handle = start_graphql_server(graphql_connection_config.clone()).await;
+    let graphql_server_handle = start_graphql_server(
+        graphql_connection_config.clone(),
+        Some(val_fn.rpc_url().to_string()),
+    )
+    .await;
     tokio::time::sleep(std::time::Duration::from_secs(10)).await;
 
     let server_url = format!(
@@ -98,7 +103,7 @@ pub async fn serve_simulator(
         start_test_indexer(Some(db_url), format!("http://{}", sim_server_url)).await;
 
     // Starts graphql server
-    let graphql_server_handle = start_graphql_server(graphql_connection_config.clone()).await;
+    let graphql_server_handle = start_graphql_server(graphql_connection_config.clone(), None).await;
     tokio::time::sleep(std::time::Duration::from_secs(10)).await;
 
     let server_url = format!(
@@ -118,9 +123,13 @@ pub async fn serve_simulator(
     }
 }
 
-async fn start_graphql_server(graphql_connection_config: ConnectionConfig) -> JoinHandle<()> {
+async fn start_graphql_server(
+    graphql_connection_config: ConnectionConfig,
+    node_rpc_url: Option<String>,
+) -> JoinHandle<()> {
     let server_config = ServerConfig {
         connection: graphql_connection_config,
+        tx_exec_full_node: TxExecFullNodeConfig::new(node_rpc_url),
         ..ServerConfig::default()
     };
 
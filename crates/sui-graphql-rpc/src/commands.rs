@@ -49,6 +49,9 @@ pub enum Command {
         /// Host to bind the prom server to
         #[clap(long)]
         prom_host: Option<String>,
+        /// RPC URL of the fullnode that transactions are executed and simulated against
+        #[clap(long)]
+        node_rpc_url: Option<String>,
 
         /// Path to TOML file containing configuration for service.
         #[clap(short, long)]
@@ -208,6 +208,22 @@ impl Default for InternalFeatureConfig {
     }
 }
 
+/// Configuration for the fullnode that transactions are forwarded to, for execution and
+/// simulation.
+#[derive(Serialize, Clone, Deserialize, Debug, Eq, PartialEq, Default)]
+pub struct TxExecFullNodeConfig {
+    /// RPC URL of the fullnode. If not provided, the service will not be able to execute or
+    /// simulate transactions.
+    #[serde(default)]
+    pub(crate) node_rpc_url: Option<String>,
+}
+
+impl TxExecFullNodeConfig {
+    pub fn new(node_rpc_url: Option<String>) -> Self {
+        Self { node_rpc_url }
+    }
+}
+
 #[derive(Serialize, Clone, Deserialize, Debug, Default)]
 pub struct ServerConfig {
     #[serde(default)]
@@ -218,6 +234,8 @@ pub struct ServerConfig {
     pub internal_features: InternalFeatureConfig,
     #[serde(default)]
     pub name_service: NameServiceConfig,
+    #[serde(default)]
+    pub tx_exec_full_node: TxExecFullNodeConfig,
 }
 
 #[allow(dead_code)]
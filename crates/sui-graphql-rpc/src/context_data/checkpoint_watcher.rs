@@ -0,0 +1,167 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use futures::{stream, Stream};
+use tokio::sync::watch;
+use tracing::warn;
+
+use super::db_data_provider::PgManager;
+
+/// How often the database is polled for newly indexed checkpoints.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tracks the latest checkpoint that the indexer has committed to the database, and notifies
+/// subscribers about every checkpoint that becomes available after they subscribe.
+#[derive(Clone)]
+pub(crate) struct CheckpointWatcher {
+    latest: watch::Receiver<Option<u64>>,
+}
+
+impl CheckpointWatcher {
+    /// Start polling the database behind `pg_manager` for new checkpoints, in a background task.
+    pub(crate) fn spawn(pg_manager: PgManager) -> Self {
+        let (sender, latest) = watch::channel(None);
+        tokio::spawn(Self::poll(pg_manager, sender, DEFAULT_POLL_INTERVAL));
+        Self { latest }
+    }
+
+    async fn poll(pg_manager: PgManager, sender: watch::Sender<Option<u64>>, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match pg_manager.fetch_latest_checkpoint().await {
+                Ok(checkpoint) => {
+                    sender.send_if_modified(|latest| {
+                        let next = Some(checkpoint.sequence_number);
+                        let modified = *latest < next;
+                        if modified {
+                            *latest = next;
+                        }
+                        modified
+                    });
+                }
+                Err(e) => warn!("Failed to fetch latest checkpoint: {e}"),
+            }
+
+            if sender.is_closed() {
+                break;
+            }
+        }
+    }
+
+    /// A stream of the sequence numbers of all checkpoints indexed after this call, in order,
+    /// without gaps.  Subscribers that fall behind will still see every checkpoint, because they
+    /// only track the latest available checkpoint, and catch up on the rest in order.
+    ///
+    /// Subscribers that arrive before the watcher has heard from the database for the first time
+    /// wait for that first poll, and start from the checkpoint after it, rather than replaying
+    /// the chain from genesis.
+    pub(crate) fn subscribe(&self) -> impl Stream<Item = u64> + Send + 'static {
+        let mut latest = self.latest.clone();
+        let next = latest.borrow_and_update().map(|seq| seq + 1);
+
+        stream::unfold((latest, next), |(mut latest, mut next)| async move {
+            loop {
+                let current = *latest.borrow_and_update();
+                match (next, current) {
+                    (Some(n), Some(seq)) if seq >= n => {
+                        return Some((n, (latest, Some(n + 1))));
+                    }
+                    (None, Some(seq)) => next = Some(seq + 1),
+                    _ => {}
+                }
+
+                // The sender has been dropped, so there will be no more checkpoints.
+                latest.changed().await.ok()?;
+            }
+        })
+    }
+
+    /// A watcher whose notion of the latest checkpoint is driven by the returned sender rather
+    /// than by polling the database.
+    pub(crate) fn new_for_testing() -> (watch::Sender<Option<u64>>, Self) {
+        let (sender, latest) = watch::channel(None);
+        (sender, Self { latest })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    /// Wait (a bounded amount of time) for the next element of `stream`.
+    async fn next<S: Stream<Item = u64> + Unpin>(stream: &mut S) -> Option<u64> {
+        tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for checkpoint")
+    }
+
+    /// `true` if `stream` has nothing to offer right now.
+    async fn is_pending<S: Stream<Item = u64> + Unpin>(stream: &mut S) -> bool {
+        tokio::time::timeout(Duration::from_millis(50), stream.next())
+            .await
+            .is_err()
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_before_first_poll() {
+        let (sender, watcher) = CheckpointWatcher::new_for_testing();
+        let mut checkpoints = Box::pin(watcher.subscribe());
+
+        // Nothing is known about the database yet, so nothing is streamed.
+        assert!(is_pending(&mut checkpoints).await);
+
+        // The first poll determines where the subscription starts, rather than genesis.
+        sender.send(Some(41)).unwrap();
+        assert!(is_pending(&mut checkpoints).await);
+
+        sender.send(Some(42)).unwrap();
+        assert_eq!(next(&mut checkpoints).await, Some(42));
+        assert!(is_pending(&mut checkpoints).await);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_after_first_poll() {
+        let (sender, watcher) = CheckpointWatcher::new_for_testing();
+        sender.send(Some(10)).unwrap();
+
+        let mut checkpoints = Box::pin(watcher.subscribe());
+        assert!(is_pending(&mut checkpoints).await);
+
+        sender.send(Some(11)).unwrap();
+        assert_eq!(next(&mut checkpoints).await, Some(11));
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_without_gaps() {
+        let (sender, watcher) = CheckpointWatcher::new_for_testing();
+        sender.send(Some(0)).unwrap();
+
+        let mut checkpoints = Box::pin(watcher.subscribe());
+
+        // The watcher skips ahead, but the subscriber still sees every checkpoint in between.
+        sender.send(Some(4)).unwrap();
+        for expect in 1..=4 {
+            assert_eq!(next(&mut checkpoints).await, Some(expect));
+        }
+        assert!(is_pending(&mut checkpoints).await);
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_with_watcher() {
+        let (sender, watcher) = CheckpointWatcher::new_for_testing();
+        let mut checkpoints = Box::pin(watcher.subscribe());
+        drop(watcher);
+
+        sender.send(Some(0)).unwrap();
+        sender.send(Some(1)).unwrap();
+        assert_eq!(next(&mut checkpoints).await, Some(1));
+
+        drop(sender);
+        assert_eq!(next(&mut checkpoints).await, None);
+    }
+}
@@ -17,7 +17,6 @@ use crate::{
         epoch::Epoch,
         event::{Event, EventFilter},
         gas::{GasCostSummary, GasInput},
-        move_module::MoveModuleId,
         move_object::MoveObject,
         move_package::MovePackage,
         move_type::MoveType,
@@ -46,7 +45,7 @@ use diesel::{
     query_builder::{AstPass, BoxedSelectStatement, FromClause, QueryFragment, QueryId},
     sql_types::Text,
     BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl,
-    QueryResult, RunQueryDsl,
+    QueryResult, RunQueryDsl, SqliteConnection,
 };
 use move_core_types::language_storage::StructTag;
 use std::str::FromStr;
@@ -54,7 +53,10 @@ use sui_indexer::{
     apis::GovernanceReadApiV2,
     indexer_reader::IndexerReader,
     models_v2::{
-        checkpoints::StoredCheckpoint, epoch::StoredEpochInfo, objects::StoredObject,
+        checkpoints::StoredCheckpoint,
+        epoch::StoredEpochInfo,
+        objects::StoredObject,
+        sqlite::{SqliteCheckpoint, SqliteEpochInfo, SqliteTransaction},
         transactions::StoredTransaction,
     },
     schema_v2::{
@@ -66,7 +68,7 @@ use sui_indexer::{
 };
 use sui_json_rpc::name_service::{Domain, NameRecord, NameServiceConfig};
 use sui_json_rpc_types::{
-    EventFilter as RpcEventFilter, ProtocolConfigResponse, Stake as SuiStake,
+    EventFilter as RpcEventFilter, ProtocolConfigResponse, Stake as SuiStake, SuiEvent,
     SuiTransactionBlockEffects,
 };
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
@@ -121,7 +123,7 @@ pub enum DbValidationError {
     QueryCostExceeded(u64, u64),
 }
 
-type BalanceQuery<'a> = BoxedSelectStatement<
+type BalanceQuery<'a, DB> = BoxedSelectStatement<
     'a,
     (
         diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
@@ -129,7 +131,7 @@ type BalanceQuery<'a> = BoxedSelectStatement<
         diesel::sql_types::Nullable<diesel::sql_types::Text>,
     ),
     FromClause<objects::table>,
-    Pg,
+    DB,
     objects::dsl::coin_type,
 >;
 
@@ -192,349 +194,418 @@ pub fn extract_cost(explain_result: &str) -> Result<f64, Error> {
     }
 }
 
-pub struct QueryBuilder;
-impl QueryBuilder {
-    fn get_tx_by_digest<'a>(digest: Vec<u8>) -> transactions::BoxedQuery<'a, Pg> {
-        transactions::dsl::transactions
-            .filter(transactions::dsl::transaction_digest.eq(digest))
-            .into_boxed()
-    }
-
-    fn get_obj<'a>(address: Vec<u8>, version: Option<i64>) -> objects::BoxedQuery<'a, Pg> {
-        let mut query = objects::dsl::objects.into_boxed();
-        query = query.filter(objects::dsl::object_id.eq(address));
-
-        if let Some(version) = version {
-            query = query.filter(objects::dsl::object_version.eq(version));
-        }
-        query
-    }
-
-    fn get_epoch<'a>(epoch_id: i64) -> epochs::BoxedQuery<'a, Pg> {
-        epochs::dsl::epochs
-            .filter(epochs::dsl::epoch.eq(epoch_id))
-            .into_boxed()
-    }
-
-    fn get_latest_epoch<'a>() -> epochs::BoxedQuery<'a, Pg> {
-        epochs::dsl::epochs
-            .order_by(epochs::dsl::epoch.desc())
-            .limit(1)
-            .into_boxed()
-    }
-
-    fn get_checkpoint_by_digest<'a>(digest: Vec<u8>) -> checkpoints::BoxedQuery<'a, Pg> {
-        checkpoints::dsl::checkpoints
-            .filter(checkpoints::dsl::checkpoint_digest.eq(digest))
-            .into_boxed()
-    }
-
-    fn get_checkpoint_by_sequence_number<'a>(
-        sequence_number: i64,
-    ) -> checkpoints::BoxedQuery<'a, Pg> {
-        checkpoints::dsl::checkpoints
-            .filter(checkpoints::dsl::sequence_number.eq(sequence_number))
-            .into_boxed()
-    }
-
-    fn get_latest_checkpoint<'a>() -> checkpoints::BoxedQuery<'a, Pg> {
-        checkpoints::dsl::checkpoints
-            .order_by(checkpoints::dsl::sequence_number.desc())
-            .limit(1)
-            .into_boxed()
-    }
-
-    fn multi_get_txs<'a>(
-        cursor: Option<i64>,
-        descending_order: bool,
-        limit: i64,
-        filter: Option<TransactionBlockFilter>,
-        after_tx_seq_num: Option<i64>,
-        before_tx_seq_num: Option<i64>,
-    ) -> Result<transactions::BoxedQuery<'a, Pg>, Error> {
-        let mut query = transactions::dsl::transactions.into_boxed();
-
-        if let Some(cursor_val) = cursor {
-            if descending_order {
-                let filter_value =
-                    before_tx_seq_num.map_or(cursor_val, |b| std::cmp::min(b, cursor_val));
-                query = query.filter(transactions::dsl::tx_sequence_number.lt(filter_value));
-            } else {
-                let filter_value =
-                    after_tx_seq_num.map_or(cursor_val, |a| std::cmp::max(a, cursor_val));
-                query = query.filter(transactions::dsl::tx_sequence_number.gt(filter_value));
+/// Defines the `QueryBuilder` of the `$db` backend. Its `checkpoints`, `epochs` and `transactions`
+/// tables are resolved where the macro is invoked, as they differ between `schema_v2` and
+/// `schema_sqlite`.
+macro_rules! query_builder {
+    ($db:ty) => {
+        pub(super) struct QueryBuilder;
+        impl QueryBuilder {
+            pub(super) fn get_tx_by_digest<'a>(
+                digest: Vec<u8>,
+            ) -> transactions::BoxedQuery<'a, $db> {
+                transactions::dsl::transactions
+                    .filter(transactions::dsl::transaction_digest.eq(digest))
+                    .into_boxed()
             }
-        } else {
-            if let Some(av) = after_tx_seq_num {
-                query = query.filter(transactions::dsl::tx_sequence_number.gt(av));
-            }
-            if let Some(bv) = before_tx_seq_num {
-                query = query.filter(transactions::dsl::tx_sequence_number.lt(bv));
-            }
-        }
 
-        if descending_order {
-            query = query.order(transactions::dsl::tx_sequence_number.desc());
-        } else {
-            query = query.order(transactions::dsl::tx_sequence_number.asc());
-        }
+            pub(super) fn get_obj<'a>(
+                address: Vec<u8>,
+                version: Option<i64>,
+            ) -> objects::BoxedQuery<'a, $db> {
+                let mut query = objects::dsl::objects.into_boxed();
+                query = query.filter(objects::dsl::object_id.eq(address));
 
-        query = query.limit(limit + 1);
+                if let Some(version) = version {
+                    query = query.filter(objects::dsl::object_version.eq(version));
+                }
+                query
+            }
 
-        if let Some(filter) = filter {
-            // Filters for transaction table
-            // at_checkpoint mutually exclusive with before_ and after_checkpoint
-            if let Some(checkpoint) = filter.at_checkpoint {
-                query = query
-                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64));
+            pub(super) fn get_epoch<'a>(epoch_id: i64) -> epochs::BoxedQuery<'a, $db> {
+                epochs::dsl::epochs
+                    .filter(epochs::dsl::epoch.eq(epoch_id))
+                    .into_boxed()
             }
-            if let Some(transaction_ids) = filter.transaction_ids {
-                let digests = transaction_ids
-                    .into_iter()
-                    .map(|id| Ok::<Vec<u8>, Error>(Digest::from_str(&id)?.into_vec()))
-                    .collect::<Result<Vec<_>, _>>()?;
-                query = query.filter(transactions::dsl::transaction_digest.eq_any(digests));
+
+            pub(super) fn get_latest_epoch<'a>() -> epochs::BoxedQuery<'a, $db> {
+                epochs::dsl::epochs
+                    .order_by(epochs::dsl::epoch.desc())
+                    .limit(1)
+                    .into_boxed()
             }
 
-            // Queries on foreign tables
-            match (filter.package, filter.module, filter.function) {
-                (Some(p), None, None) => {
-                    let subquery = tx_calls::dsl::tx_calls
-                        .filter(tx_calls::dsl::package.eq(p.into_vec()))
-                        .select(tx_calls::dsl::tx_sequence_number);
+            pub(super) fn get_checkpoint_by_digest<'a>(
+                digest: Vec<u8>,
+            ) -> checkpoints::BoxedQuery<'a, $db> {
+                checkpoints::dsl::checkpoints
+                    .filter(checkpoints::dsl::checkpoint_digest.eq(digest))
+                    .into_boxed()
+            }
 
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-                (Some(p), Some(m), None) => {
-                    let subquery = tx_calls::dsl::tx_calls
-                        .filter(tx_calls::dsl::package.eq(p.into_vec()))
-                        .filter(tx_calls::dsl::module.eq(m))
-                        .select(tx_calls::dsl::tx_sequence_number);
+            pub(super) fn get_checkpoint_by_sequence_number<'a>(
+                sequence_number: i64,
+            ) -> checkpoints::BoxedQuery<'a, $db> {
+                checkpoints::dsl::checkpoints
+                    .filter(checkpoints::dsl::sequence_number.eq(sequence_number))
+                    .into_boxed()
+            }
 
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-                (Some(p), Some(m), Some(f)) => {
-                    let subquery = tx_calls::dsl::tx_calls
-                        .filter(tx_calls::dsl::package.eq(p.into_vec()))
-                        .filter(tx_calls::dsl::module.eq(m))
-                        .filter(tx_calls::dsl::func.eq(f))
-                        .select(tx_calls::dsl::tx_sequence_number);
-
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-                _ => {}
+            pub(super) fn get_latest_checkpoint<'a>() -> checkpoints::BoxedQuery<'a, $db> {
+                checkpoints::dsl::checkpoints
+                    .order_by(checkpoints::dsl::sequence_number.desc())
+                    .limit(1)
+                    .into_boxed()
             }
 
-            if let Some(signer) = filter.sign_address {
-                if let Some(sender) = filter.sent_address {
-                    let subquery = tx_senders::dsl::tx_senders
-                        .filter(
-                            tx_senders::dsl::sender
-                                .eq(signer.into_vec())
-                                .or(tx_senders::dsl::sender.eq(sender.into_vec())),
-                        )
-                        .select(tx_senders::dsl::tx_sequence_number);
-
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+            pub(super) fn multi_get_txs<'a>(
+                cursor: Option<i64>,
+                descending_order: bool,
+                limit: i64,
+                filter: Option<TransactionBlockFilter>,
+                after_tx_seq_num: Option<i64>,
+                before_tx_seq_num: Option<i64>,
+            ) -> Result<transactions::BoxedQuery<'a, $db>, Error> {
+                let mut query = transactions::dsl::transactions.into_boxed();
+
+                if let Some(cursor_val) = cursor {
+                    if descending_order {
+                        let filter_value =
+                            before_tx_seq_num.map_or(cursor_val, |b| std::cmp::min(b, cursor_val));
+                        query =
+                            query.filter(transactions::dsl::tx_sequence_number.lt(filter_value));
+                    } else {
+                        let filter_value =
+                            after_tx_seq_num.map_or(cursor_val, |a| std::cmp::max(a, cursor_val));
+                        query =
+                            query.filter(transactions::dsl::tx_sequence_number.gt(filter_value));
+                    }
                 } else {
-                    let subquery = tx_senders::dsl::tx_senders
-                        .filter(tx_senders::dsl::sender.eq(signer.into_vec()))
-                        .select(tx_senders::dsl::tx_sequence_number);
+                    if let Some(av) = after_tx_seq_num {
+                        query = query.filter(transactions::dsl::tx_sequence_number.gt(av));
+                    }
+                    if let Some(bv) = before_tx_seq_num {
+                        query = query.filter(transactions::dsl::tx_sequence_number.lt(bv));
+                    }
+                }
 
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                if descending_order {
+                    query = query.order(transactions::dsl::tx_sequence_number.desc());
+                } else {
+                    query = query.order(transactions::dsl::tx_sequence_number.asc());
                 }
-            } else if let Some(sender) = filter.sent_address {
-                let subquery = tx_senders::dsl::tx_senders
-                    .filter(tx_senders::dsl::sender.eq(sender.into_vec()))
-                    .select(tx_senders::dsl::tx_sequence_number);
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-            if let Some(recipient) = filter.recv_address {
-                let subquery = tx_recipients::dsl::tx_recipients
-                    .filter(tx_recipients::dsl::recipient.eq(recipient.into_vec()))
-                    .select(tx_recipients::dsl::tx_sequence_number);
+                query = query.limit(limit + 1);
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-            if filter.paid_address.is_some() {
-                return Err(Error::Internal(
-                    "Paid address filter not supported".to_string(),
-                ));
-            }
+                if let Some(filter) = filter {
+                    // Filters for transaction table
+                    // at_checkpoint mutually exclusive with before_ and after_checkpoint
+                    if let Some(checkpoint) = filter.at_checkpoint {
+                        query = query.filter(
+                            transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64),
+                        );
+                    }
+                    if let Some(transaction_ids) = filter.transaction_ids {
+                        let digests = transaction_ids
+                            .into_iter()
+                            .map(|id| Ok::<Vec<u8>, Error>(Digest::from_str(&id)?.into_vec()))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        query = query.filter(transactions::dsl::transaction_digest.eq_any(digests));
+                    }
 
-            if let Some(input_object) = filter.input_object {
-                let subquery = tx_input_objects::dsl::tx_input_objects
-                    .filter(tx_input_objects::dsl::object_id.eq(input_object.into_vec()))
-                    .select(tx_input_objects::dsl::tx_sequence_number);
+                    // Queries on foreign tables
+                    match (filter.package, filter.module, filter.function) {
+                        (Some(p), None, None) => {
+                            let subquery = tx_calls::dsl::tx_calls
+                                .filter(tx_calls::dsl::package.eq(p.into_vec()))
+                                .select(tx_calls::dsl::tx_sequence_number);
+
+                            query = query
+                                .filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                        }
+                        (Some(p), Some(m), None) => {
+                            let subquery = tx_calls::dsl::tx_calls
+                                .filter(tx_calls::dsl::package.eq(p.into_vec()))
+                                .filter(tx_calls::dsl::module.eq(m))
+                                .select(tx_calls::dsl::tx_sequence_number);
+
+                            query = query
+                                .filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                        }
+                        (Some(p), Some(m), Some(f)) => {
+                            let subquery = tx_calls::dsl::tx_calls
+                                .filter(tx_calls::dsl::package.eq(p.into_vec()))
+                                .filter(tx_calls::dsl::module.eq(m))
+                                .filter(tx_calls::dsl::func.eq(f))
+                                .select(tx_calls::dsl::tx_sequence_number);
+
+                            query = query
+                                .filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                        }
+                        _ => {}
+                    }
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-            if let Some(changed_object) = filter.changed_object {
-                let subquery = tx_changed_objects::dsl::tx_changed_objects
-                    .filter(tx_changed_objects::dsl::object_id.eq(changed_object.into_vec()))
-                    .select(tx_changed_objects::dsl::tx_sequence_number);
+                    if let Some(signer) = filter.sign_address {
+                        if let Some(sender) = filter.sent_address {
+                            let subquery = tx_senders::dsl::tx_senders
+                                .filter(
+                                    tx_senders::dsl::sender
+                                        .eq(signer.into_vec())
+                                        .or(tx_senders::dsl::sender.eq(sender.into_vec())),
+                                )
+                                .select(tx_senders::dsl::tx_sequence_number);
+
+                            query = query
+                                .filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                        } else {
+                            let subquery = tx_senders::dsl::tx_senders
+                                .filter(tx_senders::dsl::sender.eq(signer.into_vec()))
+                                .select(tx_senders::dsl::tx_sequence_number);
+
+                            query = query
+                                .filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                        }
+                    } else if let Some(sender) = filter.sent_address {
+                        let subquery = tx_senders::dsl::tx_senders
+                            .filter(tx_senders::dsl::sender.eq(sender.into_vec()))
+                            .select(tx_senders::dsl::tx_sequence_number);
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-        };
+                        query =
+                            query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                    }
+                    if let Some(recipient) = filter.recv_address {
+                        let subquery = tx_recipients::dsl::tx_recipients
+                            .filter(tx_recipients::dsl::recipient.eq(recipient.into_vec()))
+                            .select(tx_recipients::dsl::tx_sequence_number);
 
-        Ok(query)
-    }
+                        query =
+                            query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                    }
+                    if filter.paid_address.is_some() {
+                        return Err(Error::Internal(
+                            "Paid address filter not supported".to_string(),
+                        ));
+                    }
 
-    fn multi_get_coins<'a>(
-        cursor: Option<Vec<u8>>,
-        descending_order: bool,
-        limit: i64,
-        address: Vec<u8>,
-        coin_type: Option<String>,
-    ) -> objects::BoxedQuery<'a, Pg> {
-        let mut query = objects::dsl::objects.into_boxed();
-        if let Some(cursor) = cursor {
-            if descending_order {
-                query = query.filter(objects::dsl::object_id.lt(cursor));
-            } else {
-                query = query.filter(objects::dsl::object_id.gt(cursor));
-            }
-        }
-        if descending_order {
-            query = query.order(objects::dsl::object_id.desc());
-        } else {
-            query = query.order(objects::dsl::object_id.asc());
-        }
-        query = query.limit(limit + 1);
+                    if let Some(input_object) = filter.input_object {
+                        let subquery = tx_input_objects::dsl::tx_input_objects
+                            .filter(tx_input_objects::dsl::object_id.eq(input_object.into_vec()))
+                            .select(tx_input_objects::dsl::tx_sequence_number);
 
-        query = query
-            .filter(objects::dsl::owner_id.eq(address))
-            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16)); // Leverage index on objects table
+                        query =
+                            query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                    }
+                    if let Some(changed_object) = filter.changed_object {
+                        let subquery = tx_changed_objects::dsl::tx_changed_objects
+                            .filter(
+                                tx_changed_objects::dsl::object_id.eq(changed_object.into_vec()),
+                            )
+                            .select(tx_changed_objects::dsl::tx_sequence_number);
 
-        if let Some(coin_type) = coin_type {
-            query = query.filter(objects::dsl::coin_type.eq(coin_type));
-        }
-        query
-    }
+                        query =
+                            query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+                    }
+                };
 
-    fn multi_get_objs<'a>(
-        cursor: Option<Vec<u8>>,
-        descending_order: bool,
-        limit: i64,
-        filter: Option<ObjectFilter>,
-        owner_type: Option<OwnerType>,
-    ) -> Result<objects::BoxedQuery<'a, Pg>, Error> {
-        let mut query = objects::dsl::objects.into_boxed();
+                Ok(query)
+            }
 
-        if let Some(cursor) = cursor {
-            if descending_order {
-                query = query.filter(objects::dsl::object_id.lt(cursor));
-            } else {
-                query = query.filter(objects::dsl::object_id.gt(cursor));
+            pub(super) fn get_tx_seq_num_at_checkpoint<'a>(
+                checkpoint: i64,
+                descending_order: bool,
+            ) -> transactions::BoxedQuery<'a, $db, diesel::sql_types::BigInt> {
+                let query = transactions::dsl::transactions
+                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint))
+                    .select(transactions::dsl::tx_sequence_number)
+                    .limit(1)
+                    .into_boxed();
+
+                if descending_order {
+                    query.order(transactions::dsl::tx_sequence_number.desc())
+                } else {
+                    query.order(transactions::dsl::tx_sequence_number.asc())
+                }
             }
-        }
 
-        if descending_order {
-            query = query.order(objects::dsl::object_id.desc());
-        } else {
-            query = query.order(objects::dsl::object_id.asc());
-        }
+            pub(super) fn multi_get_coins<'a>(
+                cursor: Option<Vec<u8>>,
+                descending_order: bool,
+                limit: i64,
+                address: Vec<u8>,
+                coin_type: Option<String>,
+            ) -> objects::BoxedQuery<'a, $db> {
+                let mut query = objects::dsl::objects.into_boxed();
+                if let Some(cursor) = cursor {
+                    if descending_order {
+                        query = query.filter(objects::dsl::object_id.lt(cursor));
+                    } else {
+                        query = query.filter(objects::dsl::object_id.gt(cursor));
+                    }
+                }
+                if descending_order {
+                    query = query.order(objects::dsl::object_id.desc());
+                } else {
+                    query = query.order(objects::dsl::object_id.asc());
+                }
+                query = query.limit(limit + 1);
 
-        query = query.limit(limit + 1);
+                query = query
+                    .filter(objects::dsl::owner_id.eq(address))
+                    .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16)); // Leverage index on objects table
 
-        if let Some(filter) = filter {
-            if let Some(object_ids) = filter.object_ids {
-                query = query.filter(
-                    objects::dsl::object_id.eq_any(
-                        object_ids
-                            .into_iter()
-                            .map(|id| id.into_vec())
-                            .collect::<Vec<_>>(),
-                    ),
-                );
+                if let Some(coin_type) = coin_type {
+                    query = query.filter(objects::dsl::coin_type.eq(coin_type));
+                }
+                query
             }
 
-            if let Some(owner) = filter.owner {
-                query = query.filter(objects::dsl::owner_id.eq(owner.into_vec()));
-
-                match owner_type {
-                    Some(OwnerType::Address) => {
-                        query =
-                            query.filter(objects::dsl::owner_type.eq(OwnerType::Address as i16));
+            pub(super) fn multi_get_objs<'a>(
+                cursor: Option<Vec<u8>>,
+                descending_order: bool,
+                limit: i64,
+                filter: Option<ObjectFilter>,
+                owner_type: Option<OwnerType>,
+            ) -> Result<objects::BoxedQuery<'a, $db>, Error> {
+                let mut query = objects::dsl::objects.into_boxed();
+
+                if let Some(cursor) = cursor {
+                    if descending_order {
+                        query = query.filter(objects::dsl::object_id.lt(cursor));
+                    } else {
+                        query = query.filter(objects::dsl::object_id.gt(cursor));
                     }
-                    Some(OwnerType::Object) => {
-                        query = query.filter(objects::dsl::owner_type.eq(OwnerType::Object as i16));
-                    }
-                    None => {
+                }
+
+                if descending_order {
+                    query = query.order(objects::dsl::object_id.desc());
+                } else {
+                    query = query.order(objects::dsl::object_id.asc());
+                }
+
+                query = query.limit(limit + 1);
+
+                if let Some(filter) = filter {
+                    if let Some(object_ids) = filter.object_ids {
                         query = query.filter(
-                            objects::dsl::owner_type
-                                .eq(OwnerType::Address as i16)
-                                .or(objects::dsl::owner_type.eq(OwnerType::Object as i16)),
+                            objects::dsl::object_id.eq_any(
+                                object_ids
+                                    .into_iter()
+                                    .map(|id| id.into_vec())
+                                    .collect::<Vec<_>>(),
+                            ),
                         );
                     }
-                    _ => Err(DbValidationError::InvalidOwnerType)?,
+
+                    if let Some(owner) = filter.owner {
+                        query = query.filter(objects::dsl::owner_id.eq(owner.into_vec()));
+
+                        match owner_type {
+                            Some(OwnerType::Address) => {
+                                query = query
+                                    .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16));
+                            }
+                            Some(OwnerType::Object) => {
+                                query = query
+                                    .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16));
+                            }
+                            None => {
+                                query = query.filter(
+                                    objects::dsl::owner_type
+                                        .eq(OwnerType::Address as i16)
+                                        .or(objects::dsl::owner_type.eq(OwnerType::Object as i16)),
+                                );
+                            }
+                            _ => Err(DbValidationError::InvalidOwnerType)?,
+                        }
+                    }
+
+                    if let Some(object_type) = filter.ty {
+                        query = query.filter(objects::dsl::object_type.eq(object_type));
+                    }
                 }
-            }
 
-            if let Some(object_type) = filter.ty {
-                query = query.filter(objects::dsl::object_type.eq(object_type));
+                Ok(query)
             }
-        }
 
-        Ok(query)
-    }
+            pub(super) fn multi_get_balances<'a>(address: Vec<u8>) -> BalanceQuery<'a, $db> {
+                let query = objects::dsl::objects
+                    .group_by(objects::dsl::coin_type)
+                    .select((
+                        diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                            "CAST(SUM(coin_balance) AS BIGINT)",
+                        ),
+                        diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                            "COUNT(*)",
+                        ),
+                        objects::dsl::coin_type,
+                    ))
+                    .filter(objects::dsl::owner_id.eq(address))
+                    .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+                    .filter(objects::dsl::coin_type.is_not_null())
+                    .into_boxed();
 
-    fn multi_get_balances<'a>(address: Vec<u8>) -> BalanceQuery<'a> {
-        let query = objects::dsl::objects
-            .group_by(objects::dsl::coin_type)
-            .select((
-                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
-                    "CAST(SUM(coin_balance) AS BIGINT)",
-                ),
-                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
-                    "COUNT(*)",
-                ),
-                objects::dsl::coin_type,
-            ))
-            .filter(objects::dsl::owner_id.eq(address))
-            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
-            .filter(objects::dsl::coin_type.is_not_null())
-            .into_boxed();
+                query
+            }
 
-        query
-    }
+            pub(super) fn get_balance<'a>(
+                address: Vec<u8>,
+                coin_type: String,
+            ) -> BalanceQuery<'a, $db> {
+                let query = QueryBuilder::multi_get_balances(address);
+                query.filter(objects::dsl::coin_type.eq(coin_type))
+            }
 
-    fn get_balance<'a>(address: Vec<u8>, coin_type: String) -> BalanceQuery<'a> {
-        let query = QueryBuilder::multi_get_balances(address);
-        query.filter(objects::dsl::coin_type.eq(coin_type))
-    }
+            pub(super) fn multi_get_checkpoints<'a>(
+                cursor: Option<i64>,
+                descending_order: bool,
+                limit: i64,
+                epoch: Option<i64>,
+            ) -> checkpoints::BoxedQuery<'a, $db> {
+                let mut query = checkpoints::dsl::checkpoints.into_boxed();
+
+                if let Some(cursor) = cursor {
+                    if descending_order {
+                        query = query.filter(checkpoints::dsl::sequence_number.lt(cursor));
+                    } else {
+                        query = query.filter(checkpoints::dsl::sequence_number.gt(cursor));
+                    }
+                }
+                if descending_order {
+                    query = query.order(checkpoints::dsl::sequence_number.desc());
+                } else {
+                    query = query.order(checkpoints::dsl::sequence_number.asc());
+                }
+                if let Some(epoch) = epoch {
+                    query = query.filter(checkpoints::dsl::epoch.eq(epoch));
+                }
+                query = query.limit(limit + 1);
 
-    fn multi_get_checkpoints<'a>(
-        cursor: Option<i64>,
-        descending_order: bool,
-        limit: i64,
-        epoch: Option<i64>,
-    ) -> checkpoints::BoxedQuery<'a, Pg> {
-        let mut query = checkpoints::dsl::checkpoints.into_boxed();
-
-        if let Some(cursor) = cursor {
-            if descending_order {
-                query = query.filter(checkpoints::dsl::sequence_number.lt(cursor));
-            } else {
-                query = query.filter(checkpoints::dsl::sequence_number.gt(cursor));
+                query
             }
         }
-        if descending_order {
-            query = query.order(checkpoints::dsl::sequence_number.desc());
-        } else {
-            query = query.order(checkpoints::dsl::sequence_number.asc());
-        }
-        if let Some(epoch) = epoch {
-            query = query.filter(checkpoints::dsl::epoch.eq(epoch));
-        }
-        query = query.limit(limit + 1);
+    };
+}
 
-        query
-    }
+/// Queries of [PgManager] against Postgres.
+mod pg {
+    use super::*;
+
+    query_builder!(Pg);
+}
+
+/// Queries of [PgManager] against SQLite, which stores the array columns of these tables as bcs
+/// serialized blobs (see `schema_sqlite`).
+mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+    use sui_indexer::schema_sqlite::{checkpoints, epochs, transactions};
+
+    query_builder!(Sqlite);
 }
 
+use pg::QueryBuilder;
+
+#[derive(Clone)]
 pub(crate) struct PgManager {
     pub inner: IndexerReader,
     pub limits: Limits,
@@ -565,6 +636,21 @@ impl PgManager {
             .map_err(|e| Error::Internal(e.to_string()))
     }
 
+    /// Runs a query against the SQLite backend. SQLite has no counterpart to the cost estimate of
+    /// Postgres' `EXPLAIN`, so unlike [Self::run_query_async_with_cost], this does not enforce the
+    /// DB query cost limit.
+    async fn run_sqlite_query_async<T, E, F>(&self, query: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut SqliteConnection) -> Result<T, E> + Send + 'static,
+        E: From<diesel::result::Error> + std::error::Error + Send + 'static,
+        T: Send + 'static,
+    {
+        self.inner
+            .run_sqlite_query_async(query)
+            .await
+            .map_err(|e| Error::Internal(e.to_string()))
+    }
+
     /// Takes a query_builder_fn that returns Result<QueryFragment> and a lambda to execute the query
     /// Spawns a blocking task that determines the cost of the query fragment
     /// And if within limits, then executes the query
@@ -615,6 +701,18 @@ impl PgManager {
 /// Implement methods to query db and return StoredData
 impl PgManager {
     async fn get_tx(&self, digest: Vec<u8>) -> Result<Option<StoredTransaction>, Error> {
+        if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::get_tx_by_digest(digest);
+            return self
+                .run_sqlite_query_async(move |conn| {
+                    query.get_result::<SqliteTransaction>(conn).optional()
+                })
+                .await?
+                .map(StoredTransaction::try_from)
+                .transpose()
+                .map_err(Error::from);
+        }
+
         self.run_query_async_with_cost(
             move || Ok(QueryBuilder::get_tx_by_digest(digest.clone())),
             |query| move |conn| query.get_result::<StoredTransaction>(conn).optional(),
@@ -627,6 +725,15 @@ impl PgManager {
         address: Vec<u8>,
         version: Option<i64>,
     ) -> Result<Option<StoredObject>, Error> {
+        if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::get_obj(address, version);
+            return self
+                .run_sqlite_query_async(move |conn| {
+                    query.get_result::<StoredObject>(conn).optional()
+                })
+                .await;
+        }
+
         self.run_query_async_with_cost(
             move || Ok(QueryBuilder::get_obj(address.clone(), version)),
             |query| move |conn| query.get_result::<StoredObject>(conn).optional(),
@@ -635,6 +742,21 @@ impl PgManager {
     }
 
     pub async fn get_epoch(&self, epoch_id: Option<i64>) -> Result<Option<StoredEpochInfo>, Error> {
+        if self.inner.is_sqlite() {
+            let query = match epoch_id {
+                Some(epoch_id) => sqlite::QueryBuilder::get_epoch(epoch_id),
+                None => sqlite::QueryBuilder::get_latest_epoch(),
+            };
+            return self
+                .run_sqlite_query_async(move |conn| {
+                    query.get_result::<SqliteEpochInfo>(conn).optional()
+                })
+                .await?
+                .map(StoredEpochInfo::try_from)
+                .transpose()
+                .map_err(Error::from);
+        }
+
         let query_fn = move || {
             Ok(match epoch_id {
                 Some(epoch_id) => QueryBuilder::get_epoch(epoch_id),
@@ -653,6 +775,27 @@ impl PgManager {
         digest: Option<Vec<u8>>,
         sequence_number: Option<i64>,
     ) -> Result<Option<StoredCheckpoint>, Error> {
+        if self.inner.is_sqlite() {
+            let query = match (digest, sequence_number) {
+                (Some(digest), None) => sqlite::QueryBuilder::get_checkpoint_by_digest(digest),
+                (None, Some(sequence_number)) => {
+                    sqlite::QueryBuilder::get_checkpoint_by_sequence_number(sequence_number)
+                }
+                (Some(_), Some(_)) => {
+                    return Err(Error::InvalidCheckpointQuery);
+                }
+                _ => sqlite::QueryBuilder::get_latest_checkpoint(),
+            };
+            return self
+                .run_sqlite_query_async(move |conn| {
+                    query.get_result::<SqliteCheckpoint>(conn).optional()
+                })
+                .await?
+                .map(StoredCheckpoint::try_from)
+                .transpose()
+                .map_err(Error::from);
+        }
+
         let query = move || {
             Ok(match (digest.clone(), sequence_number) {
                 (Some(digest), None) => QueryBuilder::get_checkpoint_by_digest(digest),
@@ -702,8 +845,18 @@ impl PgManager {
             .transpose()?;
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
 
-        let result: Option<Vec<StoredObject>> = self
-            .run_query_async_with_cost(
+        let result: Option<Vec<StoredObject>> = if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::multi_get_coins(
+                cursor,
+                descending_order,
+                limit,
+                address,
+                coin_type,
+            );
+            self.run_sqlite_query_async(move |conn| query.load(conn).optional())
+                .await?
+        } else {
+            self.run_query_async_with_cost(
                 move || {
                     Ok(QueryBuilder::multi_get_coins(
                         cursor.clone(),
@@ -715,7 +868,8 @@ impl PgManager {
                 },
                 |query| move |conn| query.load(conn).optional(),
             )
-            .await?;
+            .await?
+        };
 
         result
             .map(|mut stored_objs| {
@@ -734,6 +888,13 @@ impl PgManager {
         address: Vec<u8>,
         coin_type: String,
     ) -> Result<Option<(Option<i64>, Option<i64>, Option<String>)>, Error> {
+        if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::get_balance(address, coin_type);
+            return self
+                .run_sqlite_query_async(move |conn| query.get_result(conn).optional())
+                .await;
+        }
+
         self.run_query_async_with_cost(
             move || {
                 Ok(QueryBuilder::get_balance(
@@ -760,6 +921,13 @@ impl PgManager {
             return Err(DbValidationError::PaginationDisabledOnBalances.into());
         }
 
+        if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::multi_get_balances(address);
+            return self
+                .run_sqlite_query_async(move |conn| query.load(conn).optional())
+                .await;
+        }
+
         self.run_query_async_with_cost(
             move || Ok(QueryBuilder::multi_get_balances(address.clone())),
             |query| move |conn| query.load(conn).optional(),
@@ -767,6 +935,26 @@ impl PgManager {
         .await
     }
 
+    /// The sequence number of the first transaction in `checkpoint`, or of the last one if
+    /// `descending_order` is set.
+    async fn get_tx_seq_num_at_checkpoint(
+        &self,
+        checkpoint: i64,
+        descending_order: bool,
+    ) -> Result<Option<i64>, Error> {
+        if self.inner.is_sqlite() {
+            let query =
+                sqlite::QueryBuilder::get_tx_seq_num_at_checkpoint(checkpoint, descending_order);
+            return self
+                .run_sqlite_query_async(move |conn| query.get_result::<i64>(conn).optional())
+                .await;
+        }
+
+        let query = QueryBuilder::get_tx_seq_num_at_checkpoint(checkpoint, descending_order);
+        self.run_query_async(move |conn| query.get_result::<i64>(conn).optional())
+            .await
+    }
+
     async fn multi_get_txs(
         &self,
         first: Option<u64>,
@@ -785,15 +973,11 @@ impl PgManager {
         let mut before_tx_seq_num: Option<i64> = None;
         if let Some(filter) = &filter {
             if let Some(checkpoint) = filter.after_checkpoint {
-                let subquery = transactions::dsl::transactions
-                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64))
-                    .order(transactions::dsl::tx_sequence_number.asc())
-                    .select(transactions::dsl::tx_sequence_number)
-                    .limit(1)
-                    .into_boxed();
-
                 after_tx_seq_num = self
-                    .run_query_async(|conn| subquery.get_result::<i64>(conn).optional())
+                    .get_tx_seq_num_at_checkpoint(
+                        checkpoint as i64,
+                        /* descending_order */ false,
+                    )
                     .await?;
 
                 // Return early if we cannot find txs after the specified checkpoint
@@ -803,14 +987,11 @@ impl PgManager {
             }
 
             if let Some(checkpoint) = filter.before_checkpoint {
-                let subquery = transactions::dsl::transactions
-                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64))
-                    .order(transactions::dsl::tx_sequence_number.desc())
-                    .select(transactions::dsl::tx_sequence_number)
-                    .into_boxed();
-
                 before_tx_seq_num = self
-                    .run_query_async(|conn| subquery.get_result::<i64>(conn).optional())
+                    .get_tx_seq_num_at_checkpoint(
+                        checkpoint as i64,
+                        /* descending_order */ true,
+                    )
                     .await?;
 
                 // Return early if we cannot find tx before the specified checkpoint
@@ -820,20 +1001,40 @@ impl PgManager {
             }
         }
 
-        let query = move || {
-            QueryBuilder::multi_get_txs(
+        let result: Option<Vec<StoredTransaction>> = if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::multi_get_txs(
                 cursor,
                 descending_order,
                 limit,
-                filter.clone(),
+                filter,
                 after_tx_seq_num,
                 before_tx_seq_num,
-            )
-        };
+            )?;
+            self.run_sqlite_query_async(move |conn| {
+                query.load::<SqliteTransaction>(conn).optional()
+            })
+            .await?
+            .map(|txs| {
+                txs.into_iter()
+                    .map(StoredTransaction::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+        } else {
+            let query = move || {
+                QueryBuilder::multi_get_txs(
+                    cursor,
+                    descending_order,
+                    limit,
+                    filter.clone(),
+                    after_tx_seq_num,
+                    before_tx_seq_num,
+                )
+            };
 
-        let result: Option<Vec<StoredTransaction>> = self
-            .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
-            .await?;
+            self.run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
+                .await?
+        };
 
         result
             .map(|mut stored_txs| {
@@ -862,8 +1063,24 @@ impl PgManager {
             .transpose()?;
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
 
-        let result: Option<Vec<StoredCheckpoint>> = self
-            .run_query_async_with_cost(
+        let result: Option<Vec<StoredCheckpoint>> = if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::multi_get_checkpoints(
+                cursor,
+                descending_order,
+                limit,
+                epoch.map(|e| e as i64),
+            );
+            self.run_sqlite_query_async(move |conn| query.load::<SqliteCheckpoint>(conn).optional())
+                .await?
+                .map(|checkpoints| {
+                    checkpoints
+                        .into_iter()
+                        .map(StoredCheckpoint::try_from)
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?
+        } else {
+            self.run_query_async_with_cost(
                 move || {
                     Ok(QueryBuilder::multi_get_checkpoints(
                         cursor,
@@ -874,7 +1091,8 @@ impl PgManager {
                 },
                 |query| move |conn| query.load(conn).optional(),
             )
-            .await?;
+            .await?
+        };
 
         result
             .map(|mut stored_checkpoints| {
@@ -904,19 +1122,30 @@ impl PgManager {
             .transpose()?;
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
 
-        let query = move || {
-            QueryBuilder::multi_get_objs(
-                cursor.clone(),
+        let result: Option<Vec<StoredObject>> = if self.inner.is_sqlite() {
+            let query = sqlite::QueryBuilder::multi_get_objs(
+                cursor,
                 descending_order,
                 limit,
-                filter.clone(),
+                filter,
                 owner_type,
-            )
-        };
+            )?;
+            self.run_sqlite_query_async(move |conn| query.load(conn).optional())
+                .await?
+        } else {
+            let query = move || {
+                QueryBuilder::multi_get_objs(
+                    cursor.clone(),
+                    descending_order,
+                    limit,
+                    filter.clone(),
+                    owner_type,
+                )
+            };
 
-        let result: Option<Vec<StoredObject>> = self
-            .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
-            .await?;
+            self.run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
+                .await?
+        };
         result
             .map(|mut stored_objs| {
                 let has_next_page = stored_objs.len() as i64 > limit;
@@ -956,6 +1185,44 @@ impl PgManager {
         EventID::try_from(cursor).map_err(|_| Error::InvalidCursor("event".to_string()))
     }
 
+    pub(crate) fn parse_event_filter(&self, filter: EventFilter) -> Result<RpcEventFilter, Error> {
+        if let Some(sender) = filter.sender {
+            let sender = NativeSuiAddress::from_bytes(sender.into_array())
+                .map_err(|_| Error::InvalidFilter)?;
+            Ok(RpcEventFilter::Sender(sender))
+        } else if let Some(digest) = filter.transaction_digest {
+            let digest = TransactionDigest::from_str(&digest).map_err(|_| Error::InvalidFilter)?;
+            Ok(RpcEventFilter::Transaction(digest))
+        } else if let Some(package) = filter.emitting_package {
+            if let Some(module) = filter.emitting_module {
+                let package =
+                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
+                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
+                Ok(RpcEventFilter::MoveModule { package, module })
+            } else {
+                let package =
+                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
+                Ok(RpcEventFilter::Package(package))
+            }
+        } else if let Some(event_type) = filter.event_type {
+            let event_type = StructTag::from_str(&event_type).map_err(|_| Error::InvalidFilter)?;
+            Ok(RpcEventFilter::MoveEventType(event_type))
+        } else if let Some(package) = filter.event_package {
+            if let Some(module) = filter.event_module {
+                let package =
+                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
+                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
+                Ok(RpcEventFilter::MoveModule { package, module })
+            } else {
+                let package =
+                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
+                Ok(RpcEventFilter::Package(package))
+            }
+        } else {
+            Err(Error::InvalidFilter)
+        }
+    }
+
     pub(crate) fn validate_package_dependencies(
         &self,
         package: Option<&SuiAddress>,
@@ -1570,41 +1837,7 @@ impl PgManager {
         before: Option<String>,
         filter: EventFilter,
     ) -> Result<Option<Connection<String, Event>>, Error> {
-        let event_filter: Result<RpcEventFilter, Error> = if let Some(sender) = filter.sender {
-            let sender = NativeSuiAddress::from_bytes(sender.into_array())
-                .map_err(|_| Error::InvalidFilter)?;
-            Ok(RpcEventFilter::Sender(sender))
-        } else if let Some(digest) = filter.transaction_digest {
-            let digest = TransactionDigest::from_str(&digest).map_err(|_| Error::InvalidFilter)?;
-            Ok(RpcEventFilter::Transaction(digest))
-        } else if let Some(package) = filter.emitting_package {
-            if let Some(module) = filter.emitting_module {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::MoveModule { package, module })
-            } else {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::Package(package))
-            }
-        } else if let Some(event_type) = filter.event_type {
-            let event_type = StructTag::from_str(&event_type).map_err(|_| Error::InvalidFilter)?;
-            Ok(RpcEventFilter::MoveEventType(event_type))
-        } else if let Some(package) = filter.event_package {
-            if let Some(module) = filter.event_module {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::MoveModule { package, module })
-            } else {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::Package(package))
-            }
-        } else {
-            return Err(Error::InvalidFilter);
-        };
+        let event_filter = self.parse_event_filter(filter);
 
         let descending_order = before.is_some();
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as usize;
@@ -1623,27 +1856,40 @@ impl PgManager {
             let mut connection = Connection::new(false, has_next_page);
             connection.edges.extend(results.into_iter().map(|e| {
                 let cursor = String::from(e.id);
-                let event = Event {
-                    sending_module_id: Some(MoveModuleId {
-                        package: SuiAddress::from_array(**e.package_id),
-                        name: e.transaction_module.to_string(),
-                    }),
-                    event_type: Some(MoveType::new(e.type_.to_string())),
-                    senders: Some(vec![Address {
-                        address: SuiAddress::from_array(e.sender.to_inner()),
-                    }]),
-                    timestamp: e.timestamp_ms.and_then(|t| DateTime::from_ms(t as i64)),
-                    json: Some(e.parsed_json.to_string()),
-                    bcs: Some(Base64::from(e.bcs)),
-                };
-
-                Edge::new(cursor, event)
+                Edge::new(cursor, Event::from(e))
             }));
             Ok(Some(connection))
         } else {
             Err(Error::InvalidFilter)
         }
     }
+
+    /// Fetch up to `limit` events matching `filter` that were emitted after the event identified
+    /// by `cursor` (or from the beginning of the chain, if there is no cursor), in ascending
+    /// order.
+    pub(crate) async fn fetch_events_after(
+        &self,
+        filter: RpcEventFilter,
+        cursor: Option<EventID>,
+        limit: usize,
+    ) -> Result<Vec<SuiEvent>, Error> {
+        Ok(self
+            .inner
+            .query_events_in_blocking_task(filter, cursor, limit, /* descending_order */ false)
+            .await?)
+    }
+
+    /// Fetch the ID of the most recent event matching `filter`, if there is one.
+    pub(crate) async fn fetch_latest_event_id(
+        &self,
+        filter: RpcEventFilter,
+    ) -> Result<Option<EventID>, Error> {
+        let events = self
+            .inner
+            .query_events_in_blocking_task(filter, None, 1, /* descending_order */ true)
+            .await?;
+        Ok(events.into_iter().next().map(|e| e.id))
+    }
 }
 
 impl TryFrom<StoredCheckpoint> for Checkpoint {
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod checkpoint_watcher;
 pub(crate) mod db_data_provider;
 pub mod db_query_cost;
 pub(crate) mod package_cache;
@@ -671,11 +671,18 @@ impl PackageStore for DbPackageStore {
             .select(objects::dsl::object_version)
             .filter(objects::dsl::object_id.eq(id.to_vec()));
 
-        let Some(version) = self
-            .0
-            .run_query_async(move |conn| query.get_result::<i64>(conn).optional())
-            .await?
-        else {
+        // The query only uses the `objects` table, which is the same in both backends.
+        let version = if self.0.is_sqlite() {
+            self.0
+                .run_sqlite_query_async(move |conn| query.get_result::<i64>(conn).optional())
+                .await?
+        } else {
+            self.0
+                .run_query_async(move |conn| query.get_result::<i64>(conn).optional())
+                .await?
+        };
+
+        let Some(version) = version else {
             return Err(Error::PackageNotFound(id));
         };
 
@@ -690,11 +697,19 @@ impl PackageStore for DbPackageStore {
             ))
             .filter(objects::dsl::object_id.eq(id.to_vec()));
 
-        let Some((version, bcs)) = self
-            .0
-            .run_query_async(move |conn| query.get_result::<(i64, Vec<u8>)>(conn).optional())
-            .await?
-        else {
+        let row = if self.0.is_sqlite() {
+            self.0
+                .run_sqlite_query_async(move |conn| {
+                    query.get_result::<(i64, Vec<u8>)>(conn).optional()
+                })
+                .await?
+        } else {
+            self.0
+                .run_query_async(move |conn| query.get_result::<(i64, Vec<u8>)>(conn).optional())
+                .await?
+        };
+
+        let Some((version, bcs)) = row else {
             return Err(Error::PackageNotFound(id));
         };
 
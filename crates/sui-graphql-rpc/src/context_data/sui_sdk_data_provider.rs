@@ -3,6 +3,7 @@
 
 // For testing, use existing RPC as data source
 
+use crate::error::{code, graphql_error};
 use crate::types::address::Address;
 use crate::types::base64::Base64;
 use crate::types::big_int::BigInt;
@@ -10,11 +11,13 @@ use crate::types::sui_address::SuiAddress;
 use crate::types::validator::Validator;
 use crate::types::validator_credentials::ValidatorCredentials;
 
+use async_graphql::Context;
 use sui_sdk::types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 use sui_sdk::types::{
     base_types::SuiAddress as NativeSuiAddress,
     sui_system_state::sui_system_state_summary::SuiValidatorSummary,
 };
+use sui_sdk::SuiClient;
 
 pub(crate) fn convert_to_validators(
     validators: Vec<SuiValidatorSummary>,
@@ -123,3 +126,15 @@ impl From<&SuiAddress> for NativeSuiAddress {
         NativeSuiAddress::try_from(a.as_slice()).unwrap()
     }
 }
+
+/// Fetch the client for the fullnode that transactions are executed and simulated on, which is
+/// only available if the service was configured with a fullnode RPC URL.
+pub(crate) fn sui_sdk_client<'ctx>(ctx: &Context<'ctx>) -> async_graphql::Result<&'ctx SuiClient> {
+    ctx.data_opt::<SuiClient>().ok_or_else(|| {
+        graphql_error(
+            code::INTERNAL_SERVER_ERROR,
+            "Transaction execution is not configured on this service",
+        )
+        .into()
+    })
+}
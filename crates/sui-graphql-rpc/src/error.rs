@@ -89,6 +89,8 @@ pub enum Error {
     _CursorConnectionFetchFailed(String),
     #[error("Error received in multi-get query: {0}")]
     MultiGet(String),
+    #[error("Invalid transaction input: {0}")]
+    InvalidTransactionInput(String),
     #[error("Internal error occurred while processing request: {0}")]
     Internal(String),
 }
@@ -107,6 +109,7 @@ impl ErrorExtensions for Error {
             | Error::InvalidCursor(_)
             | Error::_CursorConnectionFetchFailed(_)
             | Error::MultiGet(_)
+            | Error::InvalidTransactionInput(_)
             | Error::InvalidBase58(_)
             | Error::InvalidDigestLength { .. } => {
                 e.set("code", code::BAD_USER_INPUT);
@@ -26,12 +26,16 @@ pub(crate) enum FunctionalGroup {
     /// SuiNS name and reverse name look-up.
     NameService,
 
-    /// Transaction and Event subscriptions.
+    /// Checkpoint, Transaction and Event subscriptions.
     Subscriptions,
 
     /// Information about the system that changes from epoch to epoch (protocol config, committee,
     /// reference gas price).
     SystemState,
+
+    /// Executing transactions on chain, and simulating their execution (dry-run and dev-inspect)
+    /// without committing their effects.
+    TransactionExecution,
 }
 
 impl FunctionalGroup {
@@ -51,6 +55,7 @@ impl FunctionalGroup {
             G::NameService,
             G::Subscriptions,
             G::SystemState,
+            G::TransactionExecution,
         ];
         ALL
     }
@@ -75,6 +80,10 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Epoch", "protocolConfigs"), G::SystemState),
             (("Epoch", "referenceGasPrice"), G::SystemState),
             (("Epoch", "validatorSet"), G::SystemState),
+            (
+                ("Mutation", "executeTransactionBlock"),
+                G::TransactionExecution,
+            ),
             (("SuiSystemStateSummary", "protocolConfigs"), G::SystemState),
             (
                 ("SuiSystemStateSummary", "referenceGasPrice"),
@@ -104,12 +113,14 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Owner", "defaultNameServiceName"), G::NameService),
             // (("Owner", "nameServiceConnection"), G::NameService),
             (("Query", "coinMetadata"), G::Coins),
+            (("Query", "dryRunTransactionBlock"), G::TransactionExecution),
             (("Query", "moveCallMetrics"), G::Analytics),
             (("Query", "networkMetrics"), G::Analytics),
             (("Query", "protocolConfig"), G::SystemState),
             (("Query", "resolveNameServiceAddress"), G::NameService),
+            (("Subscription", "checkpoints"), G::Subscriptions),
             (("Subscription", "events"), G::Subscriptions),
-            (("Subscription", "transactions"), G::Subscriptions),
+            (("Subscription", "transactionBlocks"), G::Subscriptions),
         ])
     });
 
@@ -129,7 +140,7 @@ mod tests {
     use async_graphql::registry::Registry;
     use async_graphql::OutputType;
 
-    use crate::types::query::Query;
+    use crate::types::{mutation::Mutation, query::Query, subscription::Subscription};
 
     use super::*;
 
@@ -140,6 +151,8 @@ mod tests {
     fn test_groups_match_schema() {
         let mut registry = Registry::default();
         Query::create_type_info(&mut registry);
+        Mutation::create_type_info(&mut registry);
+        Subscription::create_type_info(&mut registry);
 
         let unimplemented = BTreeSet::from_iter([
             ("Checkpoint", "addressMetrics"),
@@ -149,8 +162,6 @@ mod tests {
             ("Query", "coinMetadata"),
             ("Query", "moveCallMetrics"),
             ("Query", "networkMetrics"),
-            ("Subscription", "events"),
-            ("Subscription", "transactions"),
         ]);
 
         for (type_, field) in &unimplemented {
@@ -6,7 +6,9 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use sui_graphql_rpc::commands::Command;
-use sui_graphql_rpc::config::{ConnectionConfig, ServerConfig, ServiceConfig};
+use sui_graphql_rpc::config::{
+    ConnectionConfig, ServerConfig, ServiceConfig, TxExecFullNodeConfig,
+};
 use sui_graphql_rpc::schema_sdl_export;
 use sui_graphql_rpc::server::builder::Server;
 use sui_graphql_rpc::server::simple_server::start_example_server;
@@ -57,6 +59,7 @@ async fn main() {
             config,
             prom_host,
             prom_port,
+            node_rpc_url,
         } => {
             let connection = ConnectionConfig::new(port, host, db_url, prom_host, prom_port);
             let service_config = service_config(config);
@@ -68,6 +71,7 @@ async fn main() {
             let server_config = ServerConfig {
                 connection,
                 service: service_config,
+                tx_exec_full_node: TxExecFullNodeConfig::new(node_rpc_url),
                 ..ServerConfig::default()
             };
 
@@ -3,7 +3,10 @@
 
 use crate::{
     config::ServerConfig,
-    context_data::{db_data_provider::PgManager, package_cache::PackageCache},
+    context_data::{
+        checkpoint_watcher::CheckpointWatcher, db_data_provider::PgManager,
+        package_cache::PackageCache,
+    },
     error::Error,
     extensions::{
         feature_gate::FeatureGate,
@@ -11,13 +14,17 @@ use crate::{
         query_limits_checker::{QueryLimitsChecker, ShowUsage},
         timeout::Timeout,
     },
+    functional_group::FunctionalGroup,
     metrics::RequestMetrics,
     server::version::{check_version_middleware, set_version_middleware},
-    types::query::{Query, SuiGraphQLSchema},
+    types::{
+        mutation::Mutation,
+        query::{Query, SuiGraphQLSchema},
+        subscription::Subscription,
+    },
 };
 use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql::{EmptyMutation, EmptySubscription};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use axum::http::HeaderMap;
 use axum::{
     extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
@@ -27,6 +34,7 @@ use axum::{headers::Header, Router};
 use hyper::server::conn::AddrIncoming as HyperAddrIncoming;
 use hyper::Server as HyperServer;
 use std::{any::Any, net::SocketAddr, sync::Arc, time::Instant};
+use sui_sdk::SuiClientBuilder;
 use tokio::sync::OnceCell;
 
 pub struct Server {
@@ -55,6 +63,7 @@ impl Server {
         let reader = PgManager::reader(config.connection.db_url.clone())
             .map_err(|e| Error::Internal(format!("Failed to create pg connection pool: {}", e)))?;
         let pg_conn_pool = PgManager::new(reader.clone(), config.service.limits);
+        let checkpoint_pg_manager = pg_conn_pool.clone();
         let package_cache = PackageCache::new(reader);
 
         let prom_addr: SocketAddr = format!(
@@ -84,6 +93,22 @@ impl Server {
             .context_data(Arc::new(metrics))
             .context_data(config.clone());
 
+        if !config
+            .service
+            .disabled_features
+            .contains(&FunctionalGroup::Subscriptions)
+        {
+            builder = builder.context_data(CheckpointWatcher::spawn(checkpoint_pg_manager));
+        }
+
+        if let Some(node_rpc_url) = &config.tx_exec_full_node.node_rpc_url {
+            let sui_sdk_client = SuiClientBuilder::default()
+                .build(node_rpc_url)
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to create SuiClient: {}", e)))?;
+            builder = builder.context_data(sui_sdk_client);
+        }
+
         if config.internal_features.feature_gate {
             builder = builder.extension(FeatureGate);
         }
@@ -105,7 +130,7 @@ pub(crate) struct ServerBuilder {
     port: u16,
     host: String,
 
-    schema: SchemaBuilder<Query, EmptyMutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
 }
 
 impl ServerBuilder {
@@ -113,7 +138,7 @@ impl ServerBuilder {
         Self {
             port,
             host,
-            schema: async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription),
+            schema: async_graphql::Schema::build(Query, Mutation, Subscription),
         }
     }
 
@@ -141,7 +166,7 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, EmptyMutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
@@ -153,6 +178,7 @@ impl ServerBuilder {
             .route("/", axum::routing::get(graphiql).post(graphql_handler))
             .route("/schema", axum::routing::get(get_schema))
             .route("/health", axum::routing::get(health_checks))
+            .route_service("/subscriptions", GraphQLSubscription::new(schema.clone()))
             .layer(axum::extract::Extension(schema))
             .layer(middleware::from_fn(check_version_middleware))
             .layer(middleware::from_fn(set_version_middleware));
@@ -240,7 +266,7 @@ pub mod tests {
     use crate::{
         cluster::SimulatorCluster,
         config::{ConnectionConfig, Limits, ServiceConfig},
-        context_data::db_data_provider::PgManager,
+        context_data::{checkpoint_watcher::CheckpointWatcher, db_data_provider::PgManager},
         extensions::query_limits_checker::QueryLimitsChecker,
         extensions::timeout::Timeout,
         metrics::RequestMetrics,
@@ -249,6 +275,7 @@ pub mod tests {
         extensions::{Extension, ExtensionContext, NextExecute},
         Response,
     };
+    use futures::StreamExt;
     use rand::{rngs::StdRng, SeedableRng};
     use simulacrum::Simulacrum;
     use std::sync::Arc;
@@ -482,4 +509,64 @@ pub mod tests {
         assert_eq!(metrics2.num_nodes.get_sample_sum(), 2. + 4.);
         assert_eq!(metrics2.query_depth.get_sample_sum(), 1. + 3.);
     }
+
+    pub async fn test_subscriptions_impl() {
+        let (connection_config, _cluster) = prep_cluster().await;
+
+        let db_url: String = connection_config.db_url.clone();
+        let reader = PgManager::reader(db_url).expect("Failed to create pg connection pool");
+        let pg_conn_pool = PgManager::new(reader, Limits::default());
+
+        // Subscriptions are disabled if there is no checkpoint watcher.
+        let schema = ServerBuilder::new(8000, "127.0.0.1".to_string())
+            .context_data(pg_conn_pool.clone())
+            .build_schema();
+        let resp = schema
+            .execute_stream("subscription { checkpoints { sequenceNumber } }")
+            .next()
+            .await
+            .unwrap();
+        let errs: Vec<_> = resp.errors.into_iter().map(|e| e.message).collect();
+        assert_eq!(
+            errs,
+            vec!["Feature \"subscriptions\" is disabled.".to_string()]
+        );
+
+        let (latest, watcher) = CheckpointWatcher::new_for_testing();
+        let schema = ServerBuilder::new(8000, "127.0.0.1".to_string())
+            .context_data(pg_conn_pool)
+            .context_data(watcher)
+            .build_schema();
+
+        // Checkpoint bounds are rejected in transaction block subscriptions.
+        let resp = schema
+            .execute_stream(
+                "subscription { transactionBlocks(filter: { atCheckpoint: 0 }) { digest } }",
+            )
+            .next()
+            .await
+            .unwrap();
+        let errs: Vec<_> = resp.errors.into_iter().map(|e| e.message).collect();
+        assert_eq!(
+            errs,
+            vec!["Invalid filter option or value provided".to_string()]
+        );
+
+        // A subscriber that arrives before the watcher's first poll starts after the checkpoint
+        // that poll finds, rather than from genesis.
+        let mut checkpoints =
+            schema.execute_stream("subscription { checkpoints { sequenceNumber } }");
+        latest.send(Some(0)).unwrap();
+        latest.send(Some(1)).unwrap();
+
+        let resp = tokio::time::timeout(Duration::from_secs(10), checkpoints.next())
+            .await
+            .expect("Timed out waiting for checkpoint")
+            .unwrap();
+        assert!(resp.is_ok(), "Unexpected errors: {:?}", resp.errors);
+        assert_eq!(
+            resp.data.into_json().unwrap(),
+            serde_json::json!({ "checkpoints": { "sequenceNumber": 1 } }),
+        );
+    }
 }
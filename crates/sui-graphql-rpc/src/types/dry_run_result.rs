@@ -0,0 +1,170 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiExecutionResult, SuiExecutionStatus,
+    SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI, SuiTypeTag,
+};
+use sui_types::TypeTag;
+
+use super::{
+    address::Address,
+    base64::Base64,
+    digest::Digest,
+    event::Event,
+    gas::GasInput,
+    move_value::MoveValue,
+    sui_address::SuiAddress,
+    transaction_block::{TransactionBlock, TransactionBlockEffects},
+};
+use crate::error::Error;
+
+/// Data that is missing from a `TransactionKind` to make it a `TransactionData`, for the purposes
+/// of dev-inspecting it.
+#[derive(InputObject, Debug, Default, Clone)]
+pub(crate) struct TransactionMetadata {
+    /// The address that the transaction is simulated as being sent from. Defaults to `0x0`.
+    pub sender: Option<SuiAddress>,
+    /// The gas price to simulate the transaction with. Defaults to the reference gas price.
+    pub gas_price: Option<u64>,
+}
+
+/// The result of simulating a transaction, without committing its effects on chain.
+#[derive(SimpleObject)]
+pub(crate) struct DryRunResult {
+    /// The error that occurred during simulation, if any.
+    pub error: Option<String>,
+    /// The transaction block that was simulated, including its (simulated) effects.
+    pub transaction: Option<TransactionBlock>,
+    /// Events that would be emitted if the transaction were executed.
+    pub events: Option<Vec<Event>>,
+    /// The intermediate results for each command of the transaction block, including mutated
+    /// references and return values. Only available when dev-inspecting a transaction.
+    pub results: Option<Vec<DryRunEffect>>,
+}
+
+/// Effects of a single command in a dry run transaction block.
+#[derive(SimpleObject)]
+pub(crate) struct DryRunEffect {
+    /// Changes made to arguments that were mutably borrowed by this command.
+    pub mutated_references: Option<Vec<DryRunMutation>>,
+    /// Return values of this command.
+    pub return_values: Option<Vec<MoveValue>>,
+}
+
+/// A value that was mutably borrowed by a command, and its value after the command executed.
+#[derive(SimpleObject)]
+pub(crate) struct DryRunMutation {
+    /// The argument that was borrowed: `GasCoin`, `Input(i)`, `Result(i)` or
+    /// `NestedResult(i,j)`.
+    pub argument: String,
+    /// The value of the argument after the command was executed.
+    pub value: MoveValue,
+}
+
+impl DryRunResult {
+    /// Build the result of dry-running a full transaction, whose BCS representation is
+    /// `tx_bytes`.
+    pub(crate) fn from_dry_run(
+        response: DryRunTransactionBlockResponse,
+        tx_bytes: Vec<u8>,
+    ) -> async_graphql::Result<Self> {
+        let error = match response.effects.status() {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => Some(error.clone()),
+        };
+        let effects = TransactionBlockEffects::from_fullnode_response(
+            &response.effects,
+            response.object_changes,
+            response.balance_changes,
+        )?;
+
+        let transaction = TransactionBlock {
+            digest: Digest::from_array(response.effects.transaction_digest().into_inner()),
+            effects: Some(effects),
+            sender: Some(Address {
+                address: SuiAddress::from_array(response.input.sender().to_inner()),
+            }),
+            bcs: Some(Base64::from(tx_bytes)),
+            gas_input: Some(GasInput::from(response.input.gas_data())),
+            epoch_id: None,
+            kind: None,
+            signatures: None,
+        };
+
+        Ok(Self {
+            error,
+            transaction: Some(transaction),
+            events: Some(response.events.data.into_iter().map(Event::from).collect()),
+            results: None,
+        })
+    }
+
+    /// Build the result of dev-inspecting a transaction kind, on behalf of `sender`.
+    pub(crate) fn from_dev_inspect(
+        results: DevInspectResults,
+        sender: SuiAddress,
+    ) -> async_graphql::Result<Self> {
+        let effects =
+            TransactionBlockEffects::from_fullnode_response(&results.effects, vec![], vec![])?;
+
+        let transaction = TransactionBlock {
+            digest: Digest::from_array(results.effects.transaction_digest().into_inner()),
+            effects: Some(effects),
+            sender: Some(Address { address: sender }),
+            bcs: None,
+            gas_input: None,
+            epoch_id: None,
+            kind: None,
+            signatures: None,
+        };
+
+        let command_results = results
+            .results
+            .map(|results| results.into_iter().map(DryRunEffect::try_from).collect())
+            .transpose()?;
+
+        Ok(Self {
+            error: results.error,
+            transaction: Some(transaction),
+            events: Some(results.events.data.into_iter().map(Event::from).collect()),
+            results: command_results,
+        })
+    }
+}
+
+impl TryFrom<SuiExecutionResult> for DryRunEffect {
+    type Error = Error;
+
+    fn try_from(result: SuiExecutionResult) -> Result<Self, Self::Error> {
+        let mutated_references = result
+            .mutable_reference_outputs
+            .into_iter()
+            .map(|(argument, bcs, type_)| {
+                Ok(DryRunMutation {
+                    argument: argument.to_string(),
+                    value: move_value(type_, bcs)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let return_values = result
+            .return_values
+            .into_iter()
+            .map(|(bcs, type_)| move_value(type_, bcs))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            mutated_references: Some(mutated_references),
+            return_values: Some(return_values),
+        })
+    }
+}
+
+fn move_value(type_: SuiTypeTag, bcs: Vec<u8>) -> Result<MoveValue, Error> {
+    let tag: TypeTag = type_
+        .try_into()
+        .map_err(|e| Error::Internal(format!("Cannot parse type of dry run value: {e}")))?;
+    Ok(MoveValue::new(tag.to_string(), Base64::from(bcs)))
+}
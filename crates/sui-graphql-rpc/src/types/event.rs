@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_graphql::*;
+use sui_json_rpc_types::SuiEvent;
 
 use super::{
     address::Address, base64::Base64, date_time::DateTime, move_module::MoveModuleId,
@@ -23,6 +24,24 @@ pub(crate) struct Event {
     pub bcs: Option<Base64>,
 }
 
+impl From<SuiEvent> for Event {
+    fn from(e: SuiEvent) -> Self {
+        Self {
+            sending_module_id: Some(MoveModuleId {
+                package: SuiAddress::from_array(**e.package_id),
+                name: e.transaction_module.to_string(),
+            }),
+            event_type: Some(MoveType::new(e.type_.to_string())),
+            senders: Some(vec![Address {
+                address: SuiAddress::from_array(e.sender.to_inner()),
+            }]),
+            timestamp: e.timestamp_ms.and_then(|t| DateTime::from_ms(t as i64)),
+            json: Some(e.parsed_json.to_string()),
+            bcs: Some(Base64::from(e.bcs)),
+        }
+    }
+}
+
 #[derive(InputObject)]
 pub(crate) struct EventFilter {
     pub sender: Option<SuiAddress>,
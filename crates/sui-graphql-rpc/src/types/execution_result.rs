@@ -0,0 +1,18 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+
+use super::transaction_block::TransactionBlockEffects;
+
+/// The result of an execution, including errors that occurred during said execution.
+#[derive(SimpleObject)]
+pub(crate) struct ExecutionResult {
+    /// The errors field captures any errors that occurred during execution
+    pub errors: Option<Vec<String>>,
+
+    /// The effects of the executed transaction. Since the transaction was just executed, it may not
+    /// have been indexed yet, in which case fields that are resolved from the index (such as its
+    /// `checkpoint`) are not available.
+    pub effects: Option<TransactionBlockEffects>,
+}
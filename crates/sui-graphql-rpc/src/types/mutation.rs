@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use fastcrypto::traits::ToFromBytes;
+use shared_crypto::intent::Intent;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_types::{
+    signature::GenericSignature,
+    transaction::{Transaction, TransactionData},
+};
+
+use super::{
+    base64::Base64, execution_result::ExecutionResult, transaction_block::TransactionBlockEffects,
+};
+use crate::{context_data::sui_sdk_data_provider::sui_sdk_client, error::Error};
+
+pub(crate) struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Execute a transaction, committing its effects on chain.
+    ///
+    /// `txBytes` is a `TransactionData` struct that has been BCS-encoded and then Base64-encoded.
+    /// `signatures` are a list of `flag || signature || pubkey` bytes, Base64-encoded.
+    ///
+    /// Waits until the transaction has been finalized on chain to return its effects. If the
+    /// transaction could not be finalized, returns the errors that prevented it, instead.
+    async fn execute_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> Result<ExecutionResult> {
+        let sui_sdk_client = sui_sdk_client(ctx)?;
+
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| {
+                Error::InvalidTransactionInput(format!(
+                    "Unable to deserialize TransactionData from txBytes: {e}"
+                ))
+            })
+            .extend()?;
+
+        let mut sigs = Vec::with_capacity(signatures.len());
+        for sig in signatures {
+            sigs.push(
+                GenericSignature::from_bytes(&sig.0)
+                    .map_err(|e| {
+                        Error::InvalidTransactionInput(format!("Unable to parse signature: {e}"))
+                    })
+                    .extend()?,
+            );
+        }
+
+        let transaction =
+            Transaction::from_generic_sig_data(tx_data, Intent::sui_transaction(), sigs);
+        let options = SuiTransactionBlockResponseOptions::new()
+            .with_effects()
+            .with_balance_changes()
+            .with_object_changes();
+
+        let response = match sui_sdk_client
+            .quorum_driver_api()
+            .execute_transaction_block(transaction, options, None)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                return Ok(ExecutionResult {
+                    errors: Some(vec![e.to_string()]),
+                    effects: None,
+                })
+            }
+        };
+
+        let errors = (!response.errors.is_empty()).then_some(response.errors);
+        let effects = response
+            .effects
+            .as_ref()
+            .map(|effects| {
+                TransactionBlockEffects::from_fullnode_response(
+                    effects,
+                    response.object_changes.unwrap_or_default(),
+                    response.balance_changes.unwrap_or_default(),
+                )
+            })
+            .transpose()?;
+
+        Ok(ExecutionResult { errors, effects })
+    }
+}
@@ -3,10 +3,16 @@
 
 use async_graphql::{connection::Connection, *};
 use sui_json_rpc::name_service::NameServiceConfig;
+use sui_types::{
+    sui_serde::BigInt as NativeBigInt,
+    transaction::{TransactionData, TransactionKind},
+};
 
 use super::{
     address::Address,
+    base64::Base64,
     checkpoint::{Checkpoint, CheckpointId},
+    dry_run_result::{DryRunResult, TransactionMetadata},
     epoch::Epoch,
     event::{Event, EventFilter},
     object::{Object, ObjectFilter},
@@ -18,12 +24,13 @@ use super::{
 };
 use crate::{
     config::ServiceConfig,
-    context_data::db_data_provider::PgManager,
+    context_data::{db_data_provider::PgManager, sui_sdk_data_provider::sui_sdk_client},
     error::{code, graphql_error, Error},
+    types::{mutation::Mutation, subscription::Subscription},
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[allow(unreachable_code)]
 #[allow(unused_variables)]
@@ -51,8 +58,73 @@ impl Query {
             .cloned()?)
     }
 
+    /// Simulate running a transaction to inspect its effects without committing to them on-chain.
+    ///
+    /// `txBytes` either a `TransactionData` struct or a `TransactionKind` struct, BCS-encoded and
+    /// then Base64-encoded.  The expected type is controlled by the presence or absence of
+    /// `txMeta`: If present, `txBytes` is assumed to be a `TransactionKind` and the transaction is
+    /// dev-inspected, if absent, then `TransactionData` and the transaction is dry-run.
+    ///
+    /// `txMeta` the data that is missing from a `TransactionKind` to make a `TransactionData`.
+    ///
+    /// `epoch` the epoch to simulate executing the transaction in (only applicable when
+    /// dev-inspecting).  Defaults to the current epoch.
+    async fn dry_run_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        tx_meta: Option<TransactionMetadata>,
+        epoch: Option<u64>,
+    ) -> Result<DryRunResult> {
+        let sui_sdk_client = sui_sdk_client(ctx)?;
+
+        let Some(tx_meta) = tx_meta else {
+            let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+                .map_err(|e| {
+                    Error::InvalidTransactionInput(format!(
+                        "Unable to deserialize TransactionData from txBytes: {e}"
+                    ))
+                })
+                .extend()?;
+
+            let response = sui_sdk_client
+                .read_api()
+                .dry_run_transaction_block(tx_data)
+                .await
+                .map_err(|e| Error::Internal(format!("Unable to dry run transaction: {e}")))
+                .extend()?;
+
+            return DryRunResult::from_dry_run(response, tx_bytes.0);
+        };
+
+        let tx_kind: TransactionKind = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| {
+                Error::InvalidTransactionInput(format!(
+                    "Unable to deserialize TransactionKind from txBytes: {e}"
+                ))
+            })
+            .extend()?;
+
+        let sender = tx_meta
+            .sender
+            .unwrap_or_else(|| SuiAddress::from_array([0; 32]));
+
+        let results = sui_sdk_client
+            .read_api()
+            .dev_inspect_transaction_block(
+                sender.into(),
+                tx_kind,
+                tx_meta.gas_price.map(NativeBigInt::from),
+                epoch.map(NativeBigInt::from),
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Unable to dev inspect transaction: {e}")))
+            .extend()?;
+
+        DryRunResult::from_dev_inspect(results, sender)
+    }
+
     // availableRange - pending impl. on IndexerV2
-    // dryRunTransactionBlock
     // coinMetadata
 
     async fn owner(&self, ctx: &Context<'_>, address: SuiAddress) -> Option<ObjectOwner> {
@@ -0,0 +1,198 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use futures::{stream, Stream, StreamExt};
+use sui_json_rpc_types::EventFilter as RpcEventFilter;
+use sui_types::event::EventID;
+
+use super::{
+    checkpoint::Checkpoint,
+    event::{Event, EventFilter},
+    transaction_block::{TransactionBlock, TransactionBlockFilter},
+};
+use crate::{
+    context_data::{checkpoint_watcher::CheckpointWatcher, db_data_provider::PgManager},
+    error::{code, graphql_error, Error},
+    functional_group::FunctionalGroup,
+};
+
+/// Number of transactions or events fetched from the database at a time, while catching a
+/// subscription up with a new checkpoint.
+const SUBSCRIPTION_PAGE_SIZE: u64 = 50;
+
+pub(crate) struct Subscription;
+
+#[Subscription]
+impl Subscription {
+    /// Checkpoints, as they are indexed, starting from the first checkpoint indexed after the
+    /// subscription was created.
+    async fn checkpoints(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = Result<Checkpoint>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        let checkpoints = checkpoint_watcher(ctx)?.subscribe();
+
+        Ok(checkpoints.then(move |sequence_number| {
+            let pg_manager = pg_manager.clone();
+            async move {
+                pg_manager
+                    .fetch_checkpoint(None, Some(sequence_number))
+                    .await
+                    .extend()?
+                    .ok_or_else(|| {
+                        Error::Internal(format!("Checkpoint {sequence_number} not found")).extend()
+                    })
+            }
+        }))
+    }
+
+    /// Transaction blocks matching `filter`, in the order they were indexed, starting from the
+    /// first checkpoint indexed after the subscription was created.  Checkpoint bounds in the
+    /// filter are not supported.
+    async fn transaction_blocks(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<TransactionBlock>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        let filter = filter.unwrap_or_default();
+        if filter.at_checkpoint.is_some()
+            || filter.after_checkpoint.is_some()
+            || filter.before_checkpoint.is_some()
+        {
+            return Err(Error::InvalidFilter.extend());
+        }
+        pg_manager.validate_tx_block_filter(&filter).extend()?;
+
+        let checkpoints = checkpoint_watcher(ctx)?.subscribe();
+        Ok(checkpoints
+            .then(move |sequence_number| {
+                let pg_manager = pg_manager.clone();
+                let filter = filter.clone();
+                async move { checkpoint_transactions(&pg_manager, sequence_number, filter).await }
+            })
+            .flat_map(flatten))
+    }
+
+    /// Events matching `filter`, in the order they were indexed, starting from the first event
+    /// indexed after the subscription was created.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: EventFilter,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        let filter = pg_manager.parse_event_filter(filter).extend()?;
+        let cursor = pg_manager
+            .fetch_latest_event_id(filter.clone())
+            .await
+            .extend()?;
+
+        let checkpoints = Box::pin(checkpoint_watcher(ctx)?.subscribe());
+        let events = stream::unfold(
+            (checkpoints, pg_manager, filter, cursor),
+            |(mut checkpoints, pg_manager, filter, mut cursor)| async move {
+                checkpoints.next().await?;
+                let events = new_events(&pg_manager, &filter, &mut cursor).await;
+                Some((events, (checkpoints, pg_manager, filter, cursor)))
+            },
+        );
+
+        Ok(events.flat_map(flatten))
+    }
+}
+
+/// The checkpoint watcher is only available if subscriptions are enabled on this service.
+fn checkpoint_watcher<'ctx>(ctx: &Context<'ctx>) -> Result<&'ctx CheckpointWatcher> {
+    ctx.data_opt().ok_or_else(|| {
+        graphql_error(
+            code::BAD_REQUEST,
+            format!(
+                "Feature {} is disabled.",
+                FunctionalGroup::Subscriptions.name()
+            ),
+        )
+        .into()
+    })
+}
+
+/// All the transactions in checkpoint `sequence_number` that match `filter`.
+async fn checkpoint_transactions(
+    pg_manager: &PgManager,
+    sequence_number: u64,
+    filter: TransactionBlockFilter,
+) -> Result<Vec<TransactionBlock>, Error> {
+    let filter = TransactionBlockFilter {
+        at_checkpoint: Some(sequence_number),
+        ..filter
+    };
+
+    let mut transactions = vec![];
+    let mut after = None;
+    loop {
+        let Some(page) = pg_manager
+            .fetch_txs(
+                Some(SUBSCRIPTION_PAGE_SIZE),
+                after.take(),
+                None,
+                None,
+                Some(filter.clone()),
+            )
+            .await?
+        else {
+            break;
+        };
+
+        let has_next_page = page.has_next_page;
+        after = page.edges.last().map(|edge| edge.cursor.clone());
+        transactions.extend(page.edges.into_iter().map(|edge| edge.node));
+
+        if !has_next_page || after.is_none() {
+            break;
+        }
+    }
+
+    Ok(transactions)
+}
+
+/// All the events matching `filter` that were indexed after `cursor`.  Updates `cursor` to point
+/// to the last event returned.
+async fn new_events(
+    pg_manager: &PgManager,
+    filter: &RpcEventFilter,
+    cursor: &mut Option<EventID>,
+) -> Result<Vec<Event>, Error> {
+    let mut events = vec![];
+    loop {
+        let page = pg_manager
+            .fetch_events_after(
+                filter.clone(),
+                cursor.clone(),
+                SUBSCRIPTION_PAGE_SIZE as usize,
+            )
+            .await?;
+
+        let is_last_page = page.len() < SUBSCRIPTION_PAGE_SIZE as usize;
+        if let Some(last) = page.last() {
+            *cursor = Some(last.id.clone());
+        }
+        events.extend(page.into_iter().map(Event::from));
+
+        if is_last_page {
+            break;
+        }
+    }
+
+    Ok(events)
+}
+
+/// Turn a batch of results for one checkpoint into a stream of individual results.
+fn flatten<T>(batch: Result<Vec<T>, Error>) -> impl Stream<Item = Result<T>> {
+    let results: Vec<Result<T>> = match batch {
+        Ok(items) => items.into_iter().map(Ok).collect(),
+        Err(e) => vec![Err(e.extend())],
+    };
+    stream::iter(results)
+}
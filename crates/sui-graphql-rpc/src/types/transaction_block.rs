@@ -23,8 +23,9 @@ use async_graphql::*;
 
 use sui_indexer::types_v2::IndexedObjectChange;
 use sui_json_rpc_types::{
-    BalanceChange as NativeBalanceChange, SuiExecutionStatus, SuiTransactionBlockDataAPI,
-    SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    BalanceChange as NativeBalanceChange, ObjectChange as NativeObjectChange, SuiExecutionStatus,
+    SuiTransactionBlockDataAPI, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse,
 };
 use sui_types::digests::TransactionDigest;
 
@@ -189,6 +190,39 @@ impl TransactionBlockEffects {
     }
 }
 
+impl TransactionBlockEffects {
+    /// Effects of a transaction that was executed or simulated by a fullnode, which has not
+    /// necessarily been indexed yet. Object and balance changes are converted into the same
+    /// representation that the indexer stores them in.
+    pub(crate) fn from_fullnode_response(
+        tx_effects: &SuiTransactionBlockEffects,
+        object_changes: Vec<NativeObjectChange>,
+        balance_changes: Vec<NativeBalanceChange>,
+    ) -> Result<Self> {
+        let object_changes = object_changes
+            .into_iter()
+            .map(|change| bcs::to_bytes(&IndexedObjectChange::from(change)).map(Some))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                error::Error::Internal(format!("Cannot serialize object change to bcs: {e}"))
+            })?;
+
+        let balance_changes = balance_changes
+            .iter()
+            .map(|change| bcs::to_bytes(change).map(Some))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                error::Error::Internal(format!("Cannot serialize balance change to bcs: {e}"))
+            })?;
+
+        let tx_block_digest = Digest::from_array(tx_effects.transaction_digest().into_inner());
+        Self::from_stored_transaction(balance_changes, object_changes, tx_effects, tx_block_digest)?
+            .ok_or_else(|| {
+                error::Error::Internal("Cannot build transaction block effects".to_string()).into()
+            })
+    }
+}
+
 #[ComplexObject]
 impl TransactionBlockEffects {
     // the lamport version is the sequence number?
@@ -63,6 +63,58 @@ mod tests {
         assert_eq!(&format!("{}", res), &exp);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_transaction_execution() {
+        let _guard = telemetry_subscribers::TelemetryConfig::new()
+            .with_env()
+            .init();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+
+        let cluster = sui_graphql_rpc::cluster::start_cluster(connection_config, None).await;
+
+        // Wait for servers to start and catchup
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+
+        let test_cluster = &cluster.validator_fullnode_handle;
+        let recipient = test_cluster.get_addresses()[1];
+        let tx = test_cluster
+            .test_transaction_builder()
+            .await
+            .transfer_sui(Some(1_000), recipient)
+            .build();
+        let signed_tx = test_cluster.sign_transaction(&tx);
+        let (tx_bytes, signatures) = signed_tx.to_tx_bytes_and_signatures();
+
+        let signatures = signatures
+            .iter()
+            .map(|s| format!("\"{}\"", s.encoded()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let query = format!(
+            r#"
+            mutation {{
+                executeTransactionBlock(txBytes: "{}", signatures: [{}]) {{
+                    errors
+                    effects {{
+                        status
+                    }}
+                }}
+            }}
+            "#,
+            tx_bytes.encoded(),
+            signatures,
+        );
+
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+
+        let result = &res["data"]["executeTransactionBlock"];
+        assert!(result["errors"].is_null(), "Unexpected errors: {}", res);
+        assert_eq!(result["effects"]["status"], "SUCCESS");
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_simple_client_simulator_cluster() {
@@ -156,4 +208,10 @@ mod tests {
     async fn test_query_complexity_metrics() {
         test_query_complexity_metrics_impl().await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_subscriptions() {
+        test_subscriptions_impl().await;
+    }
 }
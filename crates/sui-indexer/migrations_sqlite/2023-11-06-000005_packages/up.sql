@@ -0,0 +1,6 @@
+CREATE TABLE packages
+(
+    package_id                   BLOB           PRIMARY KEY,
+    -- bcs serialized MovePackage
+    move_package                 BLOB           NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE tx_changed_objects (
+    tx_sequence_number          BIGINT       NOT NULL,
+    -- Object Id in bytes.
+    object_id                   BLOB         NOT NULL,
+    PRIMARY KEY(object_id, tx_sequence_number)
+);
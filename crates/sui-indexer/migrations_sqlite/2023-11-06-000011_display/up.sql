@@ -0,0 +1,7 @@
+CREATE TABLE display
+(
+    object_type     TEXT        PRIMARY KEY,
+    id              BLOB        NOT NULL,
+    version         SMALLINT    NOT NULL,
+    bcs             BLOB        NOT NULL
+);
@@ -3,6 +3,7 @@
 
 use crate::{
     errors::IndexerError,
+    get_sqlite_pool_connection,
     models_v2::{
         address_metrics::StoredAddressMetrics,
         checkpoints::StoredCheckpoint,
@@ -13,23 +14,26 @@ use crate::{
         network_metrics::StoredNetworkMetrics,
         objects::{CoinBalance, ObjectRefColumn, StoredObject},
         packages::StoredPackage,
+        sqlite::{SqliteCheckpoint, SqliteEpochInfo, SqliteEvent, SqliteTransaction},
         transactions::StoredTransaction,
         tx_count_metrics::StoredTxCountMetrics,
         tx_indices::TxSequenceNumber,
     },
+    new_sqlite_connection_pool,
     schema_v2::{
         address_metrics, checkpoints, display, epochs, events, move_call_metrics, network_metrics,
         objects, packages, transactions, tx_count_metrics,
     },
     types_v2::{IndexerResult, OwnerType},
-    PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection,
+    PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection, SqliteConnectionPool,
+    SQLITE_URL_PREFIX,
 };
 use anyhow::{anyhow, Result};
 use cached::proc_macro::cached;
 use cached::SizedCache;
 use diesel::{
-    r2d2::ConnectionManager, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl,
-    RunQueryDsl,
+    r2d2::ConnectionManager, Connection, ExpressionMethods, OptionalExtension, PgConnection,
+    QueryDsl, RunQueryDsl, SqliteConnection,
 };
 use fastcrypto::encoding::Encoding;
 use fastcrypto::encoding::Hex;
@@ -66,10 +70,19 @@ pub const EVENT_SEQUENCE_NUMBER_STR: &str = "event_sequence_number";
 
 #[derive(Clone)]
 pub struct IndexerReader {
-    pool: crate::PgConnectionPool,
+    pool: ReaderPool,
     package_cache: PackageCache,
 }
 
+/// The DB an [IndexerReader] reads from. Tables with array columns are laid out differently in
+/// SQLite (see `schema_sqlite`), so queries on them are written once per backend, while queries
+/// on the other tables go through `run_shared_query!`.
+#[derive(Clone)]
+enum ReaderPool {
+    Postgres(crate::PgConnectionPool),
+    Sqlite(SqliteConnectionPool),
+}
+
 // Impl for common initialization and utilities
 impl IndexerReader {
     pub fn new<T: Into<String>>(db_url: T) -> Result<Self> {
@@ -77,10 +90,17 @@ impl IndexerReader {
         Self::new_with_config(db_url, config)
     }
 
+    /// `db_url`s starting with `sqlite://` are read with [Self::new_sqlite], in which case
+    /// `config` is ignored.
     pub fn new_with_config<T: Into<String>>(
         db_url: T,
         config: PgConnectionPoolConfig,
     ) -> Result<Self> {
+        let db_url = db_url.into();
+        if let Some(db_path) = db_url.strip_prefix(SQLITE_URL_PREFIX) {
+            return Self::new_sqlite(db_path);
+        }
+
         let manager = ConnectionManager::<PgConnection>::new(db_url);
 
         let connection_config = PgConnectionConfig {
@@ -96,13 +116,31 @@ impl IndexerReader {
             .map_err(|e| anyhow!("Failed to initialize connection pool. Error: {:?}. If Error is None, please check whether the configured pool size (currently {}) exceeds the maximum number of connections allowed by the database.", e, config.pool_size))?;
 
         Ok(Self {
-            pool,
+            pool: ReaderPool::Postgres(pool),
+            package_cache: Default::default(),
+        })
+    }
+
+    /// Reads from the SQLite DB file at `db_path`, as set up by the v2 writer.
+    pub fn new_sqlite(db_path: &str) -> Result<Self> {
+        let pool = new_sqlite_connection_pool(db_path)?;
+        Ok(Self {
+            pool: ReaderPool::Sqlite(pool),
             package_cache: Default::default(),
         })
     }
 
+    pub fn is_sqlite(&self) -> bool {
+        matches!(self.pool, ReaderPool::Sqlite(_))
+    }
+
     fn get_connection(&self) -> Result<PgPoolConnection, IndexerError> {
-        self.pool.get().map_err(|e| {
+        let ReaderPool::Postgres(pool) = &self.pool else {
+            return Err(IndexerError::NotSupportedError(
+                "This query is not supported by the SQLite backend".to_string(),
+            ));
+        };
+        pool.get().map_err(|e| {
             IndexerError::PgPoolConnectionError(format!(
                 "Failed to get connection from PG connection pool with error: {:?}",
                 e
@@ -151,6 +189,54 @@ impl IndexerReader {
     {
         self.spawn_blocking(move |this| this.run_query(query)).await
     }
+
+    pub fn run_sqlite_query<T, E, F>(&self, query: F) -> Result<T, IndexerError>
+    where
+        F: FnOnce(&mut SqliteConnection) -> Result<T, E>,
+        E: From<diesel::result::Error> + std::error::Error,
+    {
+        blocking_call_is_ok_or_panic();
+
+        let ReaderPool::Sqlite(pool) = &self.pool else {
+            return Err(IndexerError::NotSupportedError(
+                "SQLite queries cannot run against a Postgres DB".to_string(),
+            ));
+        };
+        let mut connection = get_sqlite_pool_connection(pool)?;
+        SqliteConnection::transaction(&mut connection, query)
+            .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+    }
+
+    pub async fn run_sqlite_query_async<T, E, F>(&self, query: F) -> Result<T, IndexerError>
+    where
+        F: FnOnce(&mut SqliteConnection) -> Result<T, E> + Send + 'static,
+        E: From<diesel::result::Error> + std::error::Error + Send + 'static,
+        T: Send + 'static,
+    {
+        self.spawn_blocking(move |this| this.run_sqlite_query(query))
+            .await
+    }
+
+    /// Renders `bytes` as a literal of the binary column type of the DB, for raw SQL queries.
+    fn bytea_literal(&self, bytes: &[u8]) -> String {
+        if self.is_sqlite() {
+            format!("X'{}'", Hex::encode(bytes))
+        } else {
+            format!("'\\x{}'::bytea", Hex::encode(bytes))
+        }
+    }
+}
+
+/// Runs `|conn| query` against whichever backend `reader` reads from. The query is compiled once
+/// per connection type, so it may only use tables that `schema_sqlite` does not redefine.
+macro_rules! run_shared_query {
+    ($reader:expr, |$conn:ident| $query:expr) => {
+        if $reader.is_sqlite() {
+            $reader.run_sqlite_query(|$conn| $query)
+        } else {
+            $reader.run_query(|$conn| $query)
+        }
+    };
 }
 
 thread_local! {
@@ -185,7 +271,7 @@ impl IndexerReader {
     ) -> Result<Option<StoredObject>, IndexerError> {
         let object_id = object_id.to_vec();
 
-        let stored_object = self.run_query(|conn| {
+        let stored_object = run_shared_query!(self, |conn| {
             if let Some(version) = version {
                 objects::dsl::objects
                     .filter(objects::dsl::object_id.eq(object_id))
@@ -235,7 +321,7 @@ impl IndexerReader {
     fn get_object_read(&self, object_id: &ObjectID) -> Result<ObjectRead, IndexerError> {
         let id = object_id.to_vec();
 
-        let stored_object = self.run_query(|conn| {
+        let stored_object = run_shared_query!(self, |conn| {
             objects::dsl::objects
                 .filter(objects::dsl::object_id.eq(id))
                 .first::<StoredObject>(conn)
@@ -254,7 +340,7 @@ impl IndexerReader {
         package_id: &ObjectID,
     ) -> Result<Option<MovePackage>, IndexerError> {
         let package_id = package_id.to_vec();
-        let stored_package = self.run_query(|conn| {
+        let stored_package = run_shared_query!(self, |conn| {
             packages::dsl::packages
                 .filter(packages::dsl::package_id.eq(package_id))
                 .first::<StoredPackage>(conn)
@@ -304,6 +390,27 @@ impl IndexerReader {
         &self,
         epoch: Option<EpochId>,
     ) -> Result<Option<StoredEpochInfo>, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::epochs;
+
+            return self
+                .run_sqlite_query(|conn| {
+                    if let Some(epoch) = epoch {
+                        epochs::dsl::epochs
+                            .filter(epochs::epoch.eq(epoch as i64))
+                            .first::<SqliteEpochInfo>(conn)
+                            .optional()
+                    } else {
+                        epochs::dsl::epochs
+                            .order_by(epochs::epoch.desc())
+                            .first::<SqliteEpochInfo>(conn)
+                            .optional()
+                    }
+                })?
+                .map(StoredEpochInfo::try_from)
+                .transpose();
+        }
+
         let stored_epoch = self.run_query(|conn| {
             if let Some(epoch) = epoch {
                 epochs::dsl::epochs
@@ -322,6 +429,18 @@ impl IndexerReader {
     }
 
     pub fn get_latest_epoch_info_from_db(&self) -> Result<StoredEpochInfo, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::epochs;
+
+            return self
+                .run_sqlite_query(|conn| {
+                    epochs::dsl::epochs
+                        .order_by(epochs::epoch.desc())
+                        .first::<SqliteEpochInfo>(conn)
+                })?
+                .try_into();
+        }
+
         let stored_epoch = self.run_query(|conn| {
             epochs::dsl::epochs
                 .order_by(epochs::epoch.desc())
@@ -352,6 +471,34 @@ impl IndexerReader {
         limit: usize,
         descending_order: bool,
     ) -> Result<Vec<StoredEpochInfo>, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::epochs;
+
+            return self
+                .run_sqlite_query(|conn| {
+                    let mut boxed_query = epochs::table.into_boxed();
+                    if let Some(cursor) = cursor {
+                        if descending_order {
+                            boxed_query = boxed_query.filter(epochs::epoch.lt(cursor as i64));
+                        } else {
+                            boxed_query = boxed_query.filter(epochs::epoch.gt(cursor as i64));
+                        }
+                    }
+                    if descending_order {
+                        boxed_query = boxed_query.order_by(epochs::epoch.desc());
+                    } else {
+                        boxed_query = boxed_query.order_by(epochs::epoch.asc());
+                    }
+
+                    boxed_query
+                        .limit(limit as i64)
+                        .load::<SqliteEpochInfo>(conn)
+                })?
+                .into_iter()
+                .map(StoredEpochInfo::try_from)
+                .collect();
+        }
+
         self.run_query(|conn| {
             let mut boxed_query = epochs::table.into_boxed();
             if let Some(cursor) = cursor {
@@ -395,6 +542,24 @@ impl IndexerReader {
         &self,
         checkpoint_id: CheckpointId,
     ) -> Result<Option<StoredCheckpoint>, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::checkpoints;
+
+            return self
+                .run_sqlite_query(|conn| match checkpoint_id {
+                    CheckpointId::SequenceNumber(seq) => checkpoints::dsl::checkpoints
+                        .filter(checkpoints::sequence_number.eq(seq as i64))
+                        .first::<SqliteCheckpoint>(conn)
+                        .optional(),
+                    CheckpointId::Digest(digest) => checkpoints::dsl::checkpoints
+                        .filter(checkpoints::checkpoint_digest.eq(digest.into_inner().to_vec()))
+                        .first::<SqliteCheckpoint>(conn)
+                        .optional(),
+                })?
+                .map(StoredCheckpoint::try_from)
+                .transpose();
+        }
+
         let stored_checkpoint = self.run_query(|conn| match checkpoint_id {
             CheckpointId::SequenceNumber(seq) => checkpoints::dsl::checkpoints
                 .filter(checkpoints::sequence_number.eq(seq as i64))
@@ -410,6 +575,18 @@ impl IndexerReader {
     }
 
     pub fn get_latest_checkpoint_from_db(&self) -> Result<StoredCheckpoint, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::checkpoints;
+
+            return self
+                .run_sqlite_query(|conn| {
+                    checkpoints::dsl::checkpoints
+                        .order_by(checkpoints::sequence_number.desc())
+                        .first::<SqliteCheckpoint>(conn)
+                })?
+                .try_into();
+        }
+
         let stored_checkpoint = self.run_query(|conn| {
             checkpoints::dsl::checkpoints
                 .order_by(checkpoints::sequence_number.desc())
@@ -444,6 +621,36 @@ impl IndexerReader {
         limit: usize,
         descending_order: bool,
     ) -> Result<Vec<StoredCheckpoint>, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::checkpoints;
+
+            return self
+                .run_sqlite_query(|conn| {
+                    let mut boxed_query = checkpoints::table.into_boxed();
+                    if let Some(cursor) = cursor {
+                        if descending_order {
+                            boxed_query =
+                                boxed_query.filter(checkpoints::sequence_number.lt(cursor as i64));
+                        } else {
+                            boxed_query =
+                                boxed_query.filter(checkpoints::sequence_number.gt(cursor as i64));
+                        }
+                    }
+                    if descending_order {
+                        boxed_query = boxed_query.order_by(checkpoints::sequence_number.desc());
+                    } else {
+                        boxed_query = boxed_query.order_by(checkpoints::sequence_number.asc());
+                    }
+
+                    boxed_query
+                        .limit(limit as i64)
+                        .load::<SqliteCheckpoint>(conn)
+                })?
+                .into_iter()
+                .map(StoredCheckpoint::try_from)
+                .collect();
+        }
+
         self.run_query(|conn| {
             let mut boxed_query = checkpoints::table.into_boxed();
             if let Some(cursor) = cursor {
@@ -483,11 +690,22 @@ impl IndexerReader {
         &self,
         digest: TransactionDigest,
     ) -> Result<SuiTransactionBlockEffects, IndexerError> {
-        let stored_txn: StoredTransaction = self.run_query(|conn| {
-            transactions::table
-                .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
-                .first::<StoredTransaction>(conn)
-        })?;
+        let stored_txn: StoredTransaction = if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            self.run_sqlite_query(|conn| {
+                transactions::table
+                    .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
+                    .first::<SqliteTransaction>(conn)
+            })?
+            .try_into()?
+        } else {
+            self.run_query(|conn| {
+                transactions::table
+                    .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
+                    .first::<StoredTransaction>(conn)
+            })?
+        };
 
         stored_txn.try_into_sui_transaction_effects()
     }
@@ -496,11 +714,22 @@ impl IndexerReader {
         &self,
         sequence_number: i64,
     ) -> Result<SuiTransactionBlockEffects, IndexerError> {
-        let stored_txn: StoredTransaction = self.run_query(|conn| {
-            transactions::table
-                .filter(transactions::tx_sequence_number.eq(sequence_number))
-                .first::<StoredTransaction>(conn)
-        })?;
+        let stored_txn: StoredTransaction = if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            self.run_sqlite_query(|conn| {
+                transactions::table
+                    .filter(transactions::tx_sequence_number.eq(sequence_number))
+                    .first::<SqliteTransaction>(conn)
+            })?
+            .try_into()?
+        } else {
+            self.run_query(|conn| {
+                transactions::table
+                    .filter(transactions::tx_sequence_number.eq(sequence_number))
+                    .first::<StoredTransaction>(conn)
+            })?
+        };
 
         stored_txn.try_into_sui_transaction_effects()
     }
@@ -513,6 +742,19 @@ impl IndexerReader {
             .iter()
             .map(|digest| digest.inner().to_vec())
             .collect::<Vec<_>>();
+        if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            return self
+                .run_sqlite_query(|conn| {
+                    transactions::table
+                        .filter(transactions::transaction_digest.eq_any(digests))
+                        .load::<SqliteTransaction>(conn)
+                })?
+                .into_iter()
+                .map(StoredTransaction::try_from)
+                .collect();
+        }
         self.run_query(|conn| {
             transactions::table
                 .filter(transactions::transaction_digest.eq_any(digests))
@@ -531,12 +773,54 @@ impl IndexerReader {
             .collect::<IndexerResult<Vec<_>>>()
     }
 
+    fn get_tx_sequence_number(&self, digest: TransactionDigest) -> Result<i64, IndexerError> {
+        let digest = digest.into_inner().to_vec();
+        if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            return self.run_sqlite_query(|conn| {
+                transactions::dsl::transactions
+                    .select(transactions::tx_sequence_number)
+                    .filter(transactions::dsl::transaction_digest.eq(digest))
+                    .first::<i64>(conn)
+            });
+        }
+        self.run_query(|conn| {
+            transactions::dsl::transactions
+                .select(transactions::tx_sequence_number)
+                .filter(transactions::dsl::transaction_digest.eq(digest))
+                .first::<i64>(conn)
+        })
+    }
+
     fn multi_get_transactions_with_sequence_numbers(
         &self,
         tx_sequence_numbers: Vec<i64>,
         // Some(true) for desc, Some(false) for asc, None for undefined order
         is_descending: Option<bool>,
     ) -> Result<Vec<StoredTransaction>, IndexerError> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            let mut query = transactions::table
+                .filter(transactions::tx_sequence_number.eq_any(tx_sequence_numbers))
+                .into_boxed();
+            match is_descending {
+                Some(true) => {
+                    query = query.order(transactions::dsl::tx_sequence_number.desc());
+                }
+                Some(false) => {
+                    query = query.order(transactions::dsl::tx_sequence_number.asc());
+                }
+                None => (),
+            }
+            return self
+                .run_sqlite_query(|conn| query.load::<SqliteTransaction>(conn))?
+                .into_iter()
+                .map(StoredTransaction::try_from)
+                .collect();
+        }
+
         let mut query = transactions::table
             .filter(transactions::tx_sequence_number.eq_any(tx_sequence_numbers))
             .into_boxed();
@@ -597,7 +881,7 @@ impl IndexerReader {
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
-        self.run_query(|conn| {
+        run_shared_query!(self, |conn| {
             let mut query = objects::dsl::objects
                 .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
                 .filter(objects::dsl::owner_id.eq(address.to_vec()))
@@ -633,7 +917,7 @@ impl IndexerReader {
         object_type: String,
     ) -> Result<Vec<ObjectID>, IndexerError> {
         let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
-        let filtered_ids = self.run_query(|conn| {
+        let filtered_ids = run_shared_query!(self, |conn| {
             objects::dsl::objects
                 .filter(objects::object_id.eq_any(object_ids))
                 .filter(objects::object_type.eq(object_type))
@@ -667,7 +951,7 @@ impl IndexerReader {
     ) -> Result<Vec<StoredObject>, IndexerError> {
         let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
 
-        self.run_query(|conn| {
+        run_shared_query!(self, |conn| {
             objects::dsl::objects
                 .filter(objects::object_id.eq_any(object_ids))
                 .load::<StoredObject>(conn)
@@ -682,6 +966,36 @@ impl IndexerReader {
         limit: usize,
         is_descending: bool,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
+        if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            let mut query = transactions::dsl::transactions
+                .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint_seq as i64))
+                .into_boxed();
+            if let Some(cursor_tx_seq) = cursor_tx_seq {
+                if is_descending {
+                    query = query.filter(transactions::dsl::tx_sequence_number.le(cursor_tx_seq));
+                } else {
+                    query = query.filter(transactions::dsl::tx_sequence_number.ge(cursor_tx_seq));
+                }
+            }
+            if is_descending {
+                query = query.order(transactions::dsl::tx_sequence_number.desc());
+            } else {
+                query = query.order(transactions::dsl::tx_sequence_number.asc());
+            }
+
+            let stored_txes = self
+                .run_sqlite_query(|conn| {
+                    query.limit((limit) as i64).load::<SqliteTransaction>(conn)
+                })?
+                .into_iter()
+                .map(StoredTransaction::try_from)
+                .collect::<IndexerResult<Vec<_>>>()?;
+
+            return self.stored_transaction_to_transaction_block(stored_txes, options);
+        }
+
         let mut query = transactions::dsl::transactions
             .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint_seq as i64))
             .into_boxed();
@@ -729,15 +1043,38 @@ impl IndexerReader {
         is_descending: bool,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
         let cursor_tx_seq = if let Some(cursor) = cursor {
-            Some(self.run_query(|conn| {
-                transactions::dsl::transactions
-                    .select(transactions::tx_sequence_number)
-                    .filter(transactions::dsl::transaction_digest.eq(cursor.into_inner().to_vec()))
-                    .first::<i64>(conn)
-            })?)
+            Some(self.get_tx_sequence_number(cursor)?)
         } else {
             None
         };
+        if let Some(TransactionFilter::Checkpoint(seq)) = filter {
+            return self.query_transaction_blocks_by_checkpoint_impl(
+                seq,
+                options,
+                cursor_tx_seq,
+                limit,
+                is_descending,
+            );
+        }
+
+        let tx_sequence_numbers =
+            self.query_tx_sequence_numbers(filter, cursor_tx_seq, limit, is_descending)?;
+        self.multi_get_transaction_block_response_by_sequence_numbers(
+            tx_sequence_numbers,
+            options,
+            Some(is_descending),
+        )
+    }
+
+    /// The sequence numbers of the transactions matching `filter`, for any filter but
+    /// [TransactionFilter::Checkpoint], which is served from the transactions table directly.
+    fn query_tx_sequence_numbers(
+        &self,
+        filter: Option<TransactionFilter>,
+        cursor_tx_seq: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> IndexerResult<Vec<i64>> {
         let cursor_clause = if let Some(cursor_tx_seq) = cursor_tx_seq {
             if is_descending {
                 format!("AND {TX_SEQUENCE_NUMBER_STR} < {}", cursor_tx_seq)
@@ -749,15 +1086,10 @@ impl IndexerReader {
         };
         let order_str = if is_descending { "DESC" } else { "ASC" };
         let (table_name, main_where_clause) = match filter {
-            // Processed above
-            Some(TransactionFilter::Checkpoint(seq)) => {
-                return self.query_transaction_blocks_by_checkpoint_impl(
-                    seq,
-                    options,
-                    cursor_tx_seq,
-                    limit,
-                    is_descending,
-                )
+            Some(TransactionFilter::Checkpoint(_)) => {
+                return Err(IndexerError::InvalidArgumentError(
+                    "Transactions of a checkpoint are queried by checkpoint.".into(),
+                ));
             }
             // FIXME: sanitize module & function
             Some(TransactionFilter::MoveFunction {
@@ -765,64 +1097,55 @@ impl IndexerReader {
                 module,
                 function,
             }) => {
-                let package = Hex::encode(package.to_vec());
+                let package = self.bytea_literal(&package.to_vec());
                 match (module, function) {
                     (Some(module), Some(function)) => (
                         "tx_calls".into(),
                         format!(
-                            "package = '\\x{}'::bytea AND module = '{}' AND func = '{}'",
+                            "package = {} AND module = '{}' AND func = '{}'",
                             package, module, function
                         ),
                     ),
                     (Some(module), None) => (
                         "tx_calls".into(),
-                        format!(
-                            "package = '\\x{}'::bytea AND module = '{}'",
-                            package, module
-                        ),
+                        format!("package = {} AND module = '{}'", package, module),
                     ),
                     (None, Some(_)) => {
                         return Err(IndexerError::InvalidArgumentError(
                             "Function cannot be present wihtout Module.".into(),
                         ));
                     }
-                    (None, None) => (
-                        "tx_calls".into(),
-                        format!("package = '\\x{}'::bytea", package),
-                    ),
+                    (None, None) => ("tx_calls".into(), format!("package = {}", package)),
                 }
             }
             Some(TransactionFilter::InputObject(object_id)) => {
-                let object_id = Hex::encode(object_id.to_vec());
+                let object_id = self.bytea_literal(&object_id.to_vec());
                 (
                     "tx_input_objects".into(),
-                    format!("object_id = '\\x{}'::bytea", object_id),
+                    format!("object_id = {}", object_id),
                 )
             }
             Some(TransactionFilter::ChangedObject(object_id)) => {
-                let object_id = Hex::encode(object_id.to_vec());
+                let object_id = self.bytea_literal(&object_id.to_vec());
                 (
                     "tx_changed_objects".into(),
-                    format!("object_id = '\\x{}'::bytea", object_id),
+                    format!("object_id = {}", object_id),
                 )
             }
             Some(TransactionFilter::FromAddress(from_address)) => {
-                let from_address = Hex::encode(from_address.to_vec());
-                (
-                    "tx_senders".into(),
-                    format!("sender = '\\x{}'::bytea", from_address),
-                )
+                let from_address = self.bytea_literal(&from_address.to_vec());
+                ("tx_senders".into(), format!("sender = {}", from_address))
             }
             Some(TransactionFilter::ToAddress(to_address)) => {
-                let to_address = Hex::encode(to_address.to_vec());
+                let to_address = self.bytea_literal(&to_address.to_vec());
                 (
                     "tx_recipients".into(),
-                    format!("recipient = '\\x{}'::bytea", to_address),
+                    format!("recipient = {}", to_address),
                 )
             }
             Some(TransactionFilter::FromAndToAddress { from, to }) => {
-                let from_address = Hex::encode(from.to_vec());
-                let to_address = Hex::encode(to.to_vec());
+                let from_address = self.bytea_literal(&from.to_vec());
+                let to_address = self.bytea_literal(&to.to_vec());
                 // Need to remove ambiguities for tx_sequence_number column
                 let cursor_clause = if let Some(cursor_tx_seq) = cursor_tx_seq {
                     if is_descending {
@@ -844,10 +1167,10 @@ impl IndexerReader {
                     FROM tx_senders \
                     JOIN tx_recipients \
                     ON tx_senders.{TX_SEQUENCE_NUMBER_STR} = tx_recipients.{TX_SEQUENCE_NUMBER_STR} \
-                    WHERE tx_senders.sender = '\\x{}'::BYTEA \
-                    AND tx_recipients.recipient = '\\x{}'::BYTEA \
+                    WHERE tx_senders.sender = {} \
+                    AND tx_recipients.recipient = {} \
                     {} \
-                    ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
+                    ORDER BY tx_senders.{TX_SEQUENCE_NUMBER_STR} {} \
                     LIMIT {}) AS inner_query
                     ",
                     from_address,
@@ -859,22 +1182,24 @@ impl IndexerReader {
                 (inner_query, "1 = 1".into())
             }
             Some(TransactionFilter::FromOrToAddress { addr }) => {
-                let address = Hex::encode(addr.to_vec());
+                let address = self.bytea_literal(&addr.to_vec());
+                // SQLite does not allow parenthesized UNION operands, so each side is wrapped in
+                // its own sub-select instead.
                 let inner_query = format!(
                     "( \
-                        ( \
+                        SELECT {TX_SEQUENCE_NUMBER_STR} FROM ( \
                             SELECT {TX_SEQUENCE_NUMBER_STR} FROM tx_senders \
-                            WHERE sender = '\\x{}'::BYTEA {} \
+                            WHERE sender = {} {} \
                             ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
                             LIMIT {} \
-                        ) \
+                        ) AS senders \
                         UNION \
-                        ( \
+                        SELECT {TX_SEQUENCE_NUMBER_STR} FROM ( \
                             SELECT {TX_SEQUENCE_NUMBER_STR} FROM tx_recipients \
-                            WHERE recipient = '\\x{}'::BYTEA {} \
+                            WHERE recipient = {} {} \
                             ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
                             LIMIT {} \
-                        ) \
+                        ) AS recipients \
                     ) AS combined",
                     address,
                     cursor_clause,
@@ -911,17 +1236,12 @@ impl IndexerReader {
 
         tracing::debug!("query transaction blocks: {}", query);
 
-        let tx_sequence_numbers = self
-            .run_query(|conn| diesel::sql_query(query.clone()).load::<TxSequenceNumber>(conn))?
-            .into_iter()
-            .map(|tsn| tsn.tx_sequence_number)
-            .collect::<Vec<_>>();
-
-        self.multi_get_transaction_block_response_by_sequence_numbers(
-            tx_sequence_numbers,
-            options,
-            Some(is_descending),
-        )
+        Ok(run_shared_query!(self, |conn| {
+            diesel::sql_query(query.clone()).load::<TxSequenceNumber>(conn)
+        })?
+        .into_iter()
+        .map(|tsn| tsn.tx_sequence_number)
+        .collect())
     }
 
     fn multi_get_transaction_block_response_impl(
@@ -960,12 +1280,25 @@ impl IndexerReader {
         &self,
         digest: TransactionDigest,
     ) -> Result<Vec<sui_json_rpc_types::SuiEvent>, IndexerError> {
-        let (timestamp_ms, serialized_events) = self.run_query(|conn| {
-            transactions::table
-                .filter(transactions::transaction_digest.eq(digest.into_inner().to_vec()))
-                .select((transactions::timestamp_ms, transactions::events))
-                .first::<(i64, Vec<Option<Vec<u8>>>)>(conn)
-        })?;
+        let (timestamp_ms, serialized_events) = if self.is_sqlite() {
+            use crate::schema_sqlite::transactions;
+
+            let stored_tx: StoredTransaction = self
+                .run_sqlite_query(|conn| {
+                    transactions::table
+                        .filter(transactions::transaction_digest.eq(digest.into_inner().to_vec()))
+                        .first::<SqliteTransaction>(conn)
+                })?
+                .try_into()?;
+            (stored_tx.timestamp_ms, stored_tx.events)
+        } else {
+            self.run_query(|conn| {
+                transactions::table
+                    .filter(transactions::transaction_digest.eq(digest.into_inner().to_vec()))
+                    .select((transactions::timestamp_ms, transactions::events))
+                    .first::<(i64, Vec<Option<Vec<u8>>>)>(conn)
+            })?
+        };
 
         let events = serialized_events
             .into_iter()
@@ -1015,16 +1348,16 @@ impl IndexerReader {
 
         let order_clause = if descending_order { "DESC" } else { "ASC" };
         Ok(format!(
-            "SELECT * \
+            "SELECT e.* \
             FROM EVENTS e \
             JOIN TRANSACTIONS t \
             ON t.tx_sequence_number = e.tx_sequence_number \
-            AND t.transaction_digest = '\\x{}'::bytea \
+            AND t.transaction_digest = {} \
             WHERE {cursor} \
             ORDER BY e.{EVENT_SEQUENCE_NUMBER_STR} {order_clause} \
             LIMIT {limit}
             ",
-            Hex::encode(tx_digest.into_inner()),
+            self.bytea_literal(&tx_digest.into_inner()),
         ))
     }
 
@@ -1040,25 +1373,25 @@ impl IndexerReader {
                 tx_digest,
                 event_seq,
             } = cursor;
-            (
+            (self.get_tx_sequence_number(tx_digest)?, event_seq)
+        } else if descending_order {
+            let max_tx_seq: i64 = if self.is_sqlite() {
+                use crate::schema_sqlite::events;
+
+                self.run_sqlite_query(|conn| {
+                    events::dsl::events
+                        .select(events::tx_sequence_number)
+                        .order(events::dsl::tx_sequence_number.desc())
+                        .first::<i64>(conn)
+                })?
+            } else {
                 self.run_query(|conn| {
-                    transactions::dsl::transactions
-                        .select(transactions::tx_sequence_number)
-                        .filter(
-                            transactions::dsl::transaction_digest
-                                .eq(tx_digest.into_inner().to_vec()),
-                        )
+                    events::dsl::events
+                        .select(events::tx_sequence_number)
+                        .order(events::dsl::tx_sequence_number.desc())
                         .first::<i64>(conn)
-                })?,
-                event_seq,
-            )
-        } else if descending_order {
-            let max_tx_seq: i64 = self.run_query(|conn| {
-                events::dsl::events
-                    .select(events::tx_sequence_number)
-                    .order(events::dsl::tx_sequence_number.desc())
-                    .first::<i64>(conn)
-            })?;
+                })?
+            };
             (max_tx_seq + 1, 0)
         } else {
             (-1, 0)
@@ -1077,17 +1410,15 @@ impl IndexerReader {
                 format!("e.{TX_SEQUENCE_NUMBER_STR} ASC, e.{EVENT_SEQUENCE_NUMBER_STR} ASC")
             };
             format!(
-                "( \
-                    SELECT *
-                    FROM tx_senders s
-                    JOIN events e
-                    ON e.tx_sequence_number = s.tx_sequence_number
-                    AND s.sender = '\\x{}'::bytea
-                    WHERE {} \
-                    ORDER BY {} \
-                    LIMIT {}
-                )",
-                Hex::encode(sender.to_vec()),
+                "SELECT e.* \
+                FROM tx_senders s \
+                JOIN events e \
+                ON e.tx_sequence_number = s.tx_sequence_number \
+                AND s.sender = {} \
+                WHERE {} \
+                ORDER BY {} \
+                LIMIT {}",
+                self.bytea_literal(&sender.to_vec()),
                 cursor_clause,
                 order_clause,
                 limit,
@@ -1097,12 +1428,12 @@ impl IndexerReader {
         } else {
             let main_where_clause = match filter {
                 EventFilter::Package(package_id) => {
-                    format!("package = '\\x{}'::bytea", package_id.to_hex())
+                    format!("package = {}", self.bytea_literal(&package_id.to_vec()))
                 }
                 EventFilter::MoveModule { package, module } => {
                     format!(
-                        "package = '\\x{}'::bytea AND module = '{}'",
-                        package.to_hex(),
+                        "package = {} AND module = '{}'",
+                        self.bytea_literal(&package.to_vec()),
                         module,
                     )
                 }
@@ -1155,8 +1486,14 @@ impl IndexerReader {
             )
         };
         tracing::debug!("query events: {}", query);
-        let stored_events =
-            self.run_query(|conn| diesel::sql_query(query).load::<StoredEvent>(conn))?;
+        let stored_events = if self.is_sqlite() {
+            self.run_sqlite_query(|conn| diesel::sql_query(query).load::<SqliteEvent>(conn))?
+                .into_iter()
+                .map(StoredEvent::try_from)
+                .collect::<IndexerResult<Vec<_>>>()?
+        } else {
+            self.run_query(|conn| diesel::sql_query(query).load::<StoredEvent>(conn))?
+        };
         stored_events
             .into_iter()
             .map(|se| se.try_into_sui_event(self))
@@ -1247,7 +1584,7 @@ impl IndexerReader {
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
-        let objects: Vec<StoredObject> = self.run_query(|conn| {
+        let objects: Vec<StoredObject> = run_shared_query!(self, |conn| {
             let mut query = objects::dsl::objects
                 .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16))
                 .filter(objects::dsl::owner_id.eq(parent_object_id.to_vec()))
@@ -1278,7 +1615,7 @@ impl IndexerReader {
         &self,
         object_ids: Vec<Vec<u8>>,
     ) -> IndexerResult<HashMap<ObjectID, ObjectRef>> {
-        self.run_query(|conn| {
+        run_shared_query!(self, |conn| {
             let query = objects::dsl::objects
                 .select((
                     objects::dsl::object_id,
@@ -1323,7 +1660,7 @@ impl IndexerReader {
         &self,
         object_type: String,
     ) -> Result<Option<sui_types::display::DisplayVersionUpdatedEvent>, IndexerError> {
-        let stored_display = self.run_query(|conn| {
+        let stored_display = run_shared_query!(self, |conn| {
             display::table
                 .filter(display::object_type.eq(object_type))
                 .first::<StoredDisplay>(conn)
@@ -1359,21 +1696,22 @@ impl IndexerReader {
         cursor: ObjectID,
         limit: usize,
     ) -> Result<Vec<SuiCoin>, IndexerError> {
-        let mut query = objects::dsl::objects
-            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
-            .filter(objects::dsl::owner_id.eq(owner.to_vec()))
-            .filter(objects::dsl::object_id.gt(cursor.to_vec()))
-            .into_boxed();
-        if let Some(coin_type) = coin_type {
-            query = query.filter(objects::dsl::coin_type.eq(Some(coin_type)));
-        } else {
-            query = query.filter(objects::dsl::coin_type.is_not_null());
-        }
-        query = query
-            .order((objects::dsl::coin_type.asc(), objects::dsl::object_id.asc()))
-            .limit(limit as i64);
-
-        let stored_objects = self.run_query(|conn| query.load::<StoredObject>(conn))?;
+        let stored_objects = run_shared_query!(self, |conn| {
+            let mut query = objects::dsl::objects
+                .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+                .filter(objects::dsl::owner_id.eq(owner.to_vec()))
+                .filter(objects::dsl::object_id.gt(cursor.to_vec()))
+                .into_boxed();
+            if let Some(coin_type) = coin_type {
+                query = query.filter(objects::dsl::coin_type.eq(Some(coin_type)));
+            } else {
+                query = query.filter(objects::dsl::coin_type.is_not_null());
+            }
+            query
+                .order((objects::dsl::coin_type.asc(), objects::dsl::object_id.asc()))
+                .limit(limit as i64)
+                .load::<StoredObject>(conn)
+        })?;
 
         stored_objects
             .into_iter()
@@ -1410,19 +1748,19 @@ impl IndexerReader {
             CAST(SUM(coin_balance) AS BIGINT) AS coin_balance \
             FROM objects \
             WHERE owner_type = {} \
-            AND owner_id = '\\x{}'::BYTEA \
+            AND owner_id = {} \
             AND coin_type {} \
             GROUP BY coin_type \
             ORDER BY coin_type ASC
         ",
             OwnerType::Address as i16,
-            Hex::encode(owner.to_vec()),
+            self.bytea_literal(&owner.to_vec()),
             coin_type_filter,
         );
 
         tracing::debug!("get coin balances query: {query}");
-        let coin_balances =
-            self.run_query(|conn| diesel::sql_query(query).load::<CoinBalance>(conn))?;
+        let coin_balances = run_shared_query!(self, |conn| diesel::sql_query(query)
+            .load::<CoinBalance>(conn))?;
         Ok(coin_balances.into_iter().map(|cb| cb.into()).collect())
     }
 
@@ -1725,3 +2063,68 @@ fn get_single_obj_id_from_package_publish(
         Ok(None)
     }
 }
+
+#[cfg(all(test, feature = "pg_integration"))]
+mod pg_integration_tests {
+    use super::*;
+    use crate::models_v2::tx_indices::{StoredTxRecipients, StoredTxSenders};
+    use crate::schema_v2::{tx_recipients, tx_senders};
+    use crate::utils::reset_database;
+    use crate::{get_pg_pool_connection, new_pg_connection_pool};
+    use std::env;
+
+    #[test]
+    fn test_from_or_to_address_pagination() {
+        let pg_host = env::var("POSTGRES_HOST").unwrap_or_else(|_| "localhost".into());
+        let pg_port = env::var("POSTGRES_PORT").unwrap_or_else(|_| "32770".into());
+        let pw = env::var("POSTGRES_PASSWORD").unwrap_or_else(|_| "postgrespw".into());
+        let db_url = format!("postgres://postgres:{pw}@{pg_host}:{pg_port}");
+        let pool = new_pg_connection_pool(&db_url).unwrap();
+        let mut conn = get_pg_pool_connection(&pool).unwrap();
+        reset_database(&mut conn, /* drop_all */ true, /* use_v2 */ true).unwrap();
+
+        let addr = SuiAddress::random_for_testing_only();
+        let other = SuiAddress::random_for_testing_only();
+        let senders =
+            [(1, addr), (3, other), (4, addr), (6, addr)].map(|(tx_sequence_number, sender)| {
+                StoredTxSenders {
+                    tx_sequence_number,
+                    sender: sender.to_vec(),
+                }
+            });
+        let recipients = [(2, addr), (3, other), (4, addr), (5, addr), (7, addr)].map(
+            |(tx_sequence_number, recipient)| StoredTxRecipients {
+                tx_sequence_number,
+                recipient: recipient.to_vec(),
+            },
+        );
+        diesel::insert_into(tx_senders::table)
+            .values(&senders[..])
+            .execute(&mut conn)
+            .unwrap();
+        diesel::insert_into(tx_recipients::table)
+            .values(&recipients[..])
+            .execute(&mut conn)
+            .unwrap();
+
+        let reader = IndexerReader::new(db_url).unwrap();
+        let query = |cursor, is_descending| {
+            reader
+                .query_tx_sequence_numbers(
+                    Some(TransactionFilter::FromOrToAddress { addr }),
+                    cursor,
+                    3,
+                    is_descending,
+                )
+                .unwrap()
+        };
+        // pages interleave both sides of the UNION, and a transaction the address both sent
+        // and received is only returned once
+        assert_eq!(query(None, false), vec![1, 2, 4]);
+        assert_eq!(query(Some(4), false), vec![5, 6, 7]);
+        assert_eq!(query(Some(7), false), Vec::<i64>::new());
+        assert_eq!(query(None, true), vec![7, 6, 5]);
+        assert_eq!(query(Some(5), true), vec![4, 2, 1]);
+        assert_eq!(query(Some(1), true), Vec::<i64>::new());
+    }
+}
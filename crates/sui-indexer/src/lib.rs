@@ -11,6 +11,7 @@ use axum::{extract::Extension, http::StatusCode, routing::get, Router};
 use clap::Parser;
 use diesel::pg::PgConnection;
 use diesel::r2d2::ConnectionManager;
+use diesel::sqlite::SqliteConnection;
 use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
 use metrics::IndexerMetrics;
 use prometheus::{Registry, TextEncoder};
@@ -46,6 +47,7 @@ pub mod models_v2;
 pub mod processors;
 pub mod processors_v2;
 pub mod schema;
+pub mod schema_sqlite;
 pub mod schema_v2;
 pub mod store;
 pub mod test_utils;
@@ -55,6 +57,12 @@ pub mod utils;
 
 pub type PgConnectionPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type PgPoolConnection = diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>;
+pub type SqliteConnectionPool = diesel::r2d2::Pool<ConnectionManager<SqliteConnection>>;
+pub type SqlitePoolConnection =
+    diesel::r2d2::PooledConnection<ConnectionManager<SqliteConnection>>;
+
+/// Prefix of `--db-url`s that select the SQLite backend, followed by the path of the DB file.
+pub const SQLITE_URL_PREFIX: &str = "sqlite://";
 
 const METRICS_ROUTE: &str = "/metrics";
 /// Returns all endpoints for which we have implemented on the indexer,
@@ -135,6 +143,12 @@ impl IndexerConfig {
         ))
     }
 
+    /// Path of the SQLite DB file, if the indexer is configured to use the SQLite backend with a
+    /// `sqlite://<path>` db url.
+    pub fn sqlite_path(&self) -> Option<&str> {
+        self.db_url.as_deref()?.strip_prefix(SQLITE_URL_PREFIX)
+    }
+
     pub fn all_implemented_methods() -> Vec<String> {
         IMPLEMENTED_METHODS.iter().map(|&s| s.to_string()).collect()
     }
@@ -381,6 +395,94 @@ pub fn get_pg_pool_connection(pool: &PgConnectionPool) -> Result<PgPoolConnectio
     })
 }
 
+pub fn new_sqlite_connection_pool(db_path: &str) -> Result<SqliteConnectionPool, IndexerError> {
+    let pool_config = SqliteConnectionPoolConfig::default();
+    let manager = ConnectionManager::<SqliteConnection>::new(db_path);
+
+    diesel::r2d2::Pool::builder()
+        .max_size(pool_config.pool_size)
+        .connection_timeout(pool_config.connection_timeout)
+        .connection_customizer(Box::new(SqliteConnectionConfig {
+            busy_timeout: pool_config.busy_timeout,
+        }))
+        .build(manager)
+        .map_err(|e| {
+            IndexerError::SqliteConnectionPoolInitError(format!(
+                "Failed to initialize connection pool with error: {:?}",
+                e
+            ))
+        })
+}
+
+/// SQLite serializes writers, so the pool only needs a few connections, to let readers proceed
+/// concurrently with the writer.
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteConnectionPoolConfig {
+    pool_size: u32,
+    connection_timeout: Duration,
+    busy_timeout: Duration,
+}
+
+impl SqliteConnectionPoolConfig {
+    const DEFAULT_POOL_SIZE: u32 = 4;
+    const DEFAULT_CONNECTION_TIMEOUT: u64 = 30;
+    const DEFAULT_BUSY_TIMEOUT: u64 = 30;
+}
+
+impl Default for SqliteConnectionPoolConfig {
+    fn default() -> Self {
+        let db_pool_size = std::env::var("DB_POOL_SIZE")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(Self::DEFAULT_POOL_SIZE);
+        let conn_timeout_secs = std::env::var("DB_CONNECTION_TIMEOUT")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(Self::DEFAULT_CONNECTION_TIMEOUT);
+
+        Self {
+            pool_size: db_pool_size,
+            connection_timeout: Duration::from_secs(conn_timeout_secs),
+            busy_timeout: Duration::from_secs(Self::DEFAULT_BUSY_TIMEOUT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SqliteConnectionConfig {
+    busy_timeout: Duration,
+}
+
+impl diesel::r2d2::CustomizeConnection<SqliteConnection, diesel::r2d2::Error>
+    for SqliteConnectionConfig
+{
+    fn on_acquire(
+        &self,
+        conn: &mut SqliteConnection,
+    ) -> std::result::Result<(), diesel::r2d2::Error> {
+        use diesel::connection::SimpleConnection;
+
+        // WAL lets readers proceed while a write is in progress, and the busy timeout makes
+        // connections wait for the write lock instead of failing immediately.
+        conn.batch_execute(&format!(
+            "PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; PRAGMA busy_timeout = {};",
+            self.busy_timeout.as_millis(),
+        ))
+        .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+pub fn get_sqlite_pool_connection(
+    pool: &SqliteConnectionPool,
+) -> Result<SqlitePoolConnection, IndexerError> {
+    pool.get().map_err(|e| {
+        IndexerError::SqlitePoolConnectionError(format!(
+            "Failed to get connection from SQLite connection pool with error: {:?}",
+            e
+        ))
+    })
+}
+
 pub async fn build_json_rpc_server<S: IndexerStore + Sync + Send + 'static + Clone>(
     prometheus_registry: &Registry,
     state: S,
@@ -11,8 +11,12 @@ use sui_indexer::start_prometheus_server;
 use sui_indexer::store::PgIndexerAnalyticalStore;
 use sui_indexer::store::PgIndexerStore;
 use sui_indexer::store::PgIndexerStoreV2;
-use sui_indexer::utils::reset_database;
-use sui_indexer::{get_pg_pool_connection, new_pg_connection_pool, Indexer, IndexerConfig};
+use sui_indexer::store::SqliteIndexerStoreV2;
+use sui_indexer::utils::{reset_database, setup_sqlite_database};
+use sui_indexer::{
+    get_pg_pool_connection, get_sqlite_pool_connection, new_pg_connection_pool,
+    new_sqlite_connection_pool, Indexer, IndexerConfig, SQLITE_URL_PREFIX,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), IndexerError> {
@@ -38,6 +42,39 @@ async fn main() -> Result<(), IndexerError> {
 
     mysten_metrics::init_metrics(&registry);
 
+    if let Some(db_path) = indexer_config.sqlite_path() {
+        // The SQLite backend implements the v2 writer store and reader, the analytical worker
+        // still requires Postgres.
+        if !indexer_config.use_v2
+            || !(indexer_config.fullnode_sync_worker || indexer_config.rpc_server_worker)
+        {
+            return Err(IndexerError::NotSupportedError(
+                "SQLite DB is only supported by the v2 fullnode sync and RPC server workers"
+                    .to_string(),
+            ));
+        }
+        let blocking_cp = new_sqlite_connection_pool(db_path).map_err(|e| {
+            error!("Failed creating SQLite connection pool with error {:?}", e);
+            e
+        })?;
+        let mut conn = get_sqlite_pool_connection(&blocking_cp)?;
+        setup_sqlite_database(&mut conn, indexer_config.reset_db).map_err(|e| {
+            let db_err_msg = format!(
+                "Failed setting up SQLite database at: {:?} with error: {:?}",
+                db_path, e
+            );
+            error!("{}", db_err_msg);
+            IndexerError::SqliteResetError(db_err_msg)
+        })?;
+        drop(conn);
+        if indexer_config.fullnode_sync_worker {
+            let store = SqliteIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
+            return IndexerV2::start_writer(&indexer_config, store, indexer_metrics).await;
+        }
+        let db_url = format!("{SQLITE_URL_PREFIX}{db_path}");
+        return IndexerV2::start_reader(&indexer_config, &registry, db_url).await;
+    }
+
     let db_url = indexer_config.get_db_url().map_err(|e| {
         IndexerError::PgPoolConnectionError(format!(
             "Failed parsing database url with error {:?}",
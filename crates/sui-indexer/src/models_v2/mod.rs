@@ -10,6 +10,7 @@ pub mod move_call_metrics;
 pub mod network_metrics;
 pub mod objects;
 pub mod packages;
+pub mod sqlite;
 pub mod transactions;
 pub mod tx_count_metrics;
 pub mod tx_indices;
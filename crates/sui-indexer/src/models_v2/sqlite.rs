@@ -0,0 +1,286 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rows of the SQLite tables in `schema_sqlite`, which store the array columns of their Postgres
+//! counterparts as bcs serialized blobs. They convert from and to the Postgres models, so that the
+//! rest of the indexer only deals with the latter.
+
+use diesel::prelude::*;
+
+use crate::errors::IndexerError;
+use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::models_v2::epoch::StoredEpochInfo;
+use crate::models_v2::events::StoredEvent;
+use crate::models_v2::transactions::StoredTransaction;
+use crate::schema_sqlite::{checkpoints, epochs, events, transactions};
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = checkpoints)]
+pub struct SqliteCheckpoint {
+    pub sequence_number: i64,
+    pub checkpoint_digest: Vec<u8>,
+    pub epoch: i64,
+    pub network_total_transactions: i64,
+    pub previous_checkpoint_digest: Option<Vec<u8>>,
+    pub end_of_epoch: bool,
+    pub tx_digests: Vec<u8>,
+    pub timestamp_ms: i64,
+    pub total_gas_cost: i64,
+    pub computation_cost: i64,
+    pub storage_cost: i64,
+    pub storage_rebate: i64,
+    pub non_refundable_storage_fee: i64,
+    pub checkpoint_commitments: Vec<u8>,
+    pub validator_signature: Vec<u8>,
+    pub end_of_epoch_data: Option<Vec<u8>>,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = epochs)]
+pub struct SqliteEpochInfo {
+    pub epoch: i64,
+    pub validators: Vec<u8>,
+    pub first_checkpoint_id: i64,
+    pub epoch_start_timestamp: i64,
+    pub reference_gas_price: i64,
+    pub protocol_version: i64,
+    pub epoch_total_transactions: Option<i64>,
+    pub last_checkpoint_id: Option<i64>,
+    pub epoch_end_timestamp: Option<i64>,
+    pub storage_fund_reinvestment: Option<i64>,
+    pub storage_charge: Option<i64>,
+    pub storage_rebate: Option<i64>,
+    pub storage_fund_balance: Option<i64>,
+    pub stake_subsidy_amount: Option<i64>,
+    pub total_gas_fees: Option<i64>,
+    pub total_stake_rewards_distributed: Option<i64>,
+    pub leftover_storage_fund_inflow: Option<i64>,
+    pub new_total_stake: Option<i64>,
+    pub epoch_commitments: Option<Vec<u8>>,
+    pub next_epoch_reference_gas_price: Option<i64>,
+    pub next_epoch_protocol_version: Option<i64>,
+}
+
+#[derive(Queryable, QueryableByName, Insertable, Debug, Clone)]
+#[diesel(table_name = events)]
+pub struct SqliteEvent {
+    pub tx_sequence_number: i64,
+    pub event_sequence_number: i64,
+    pub transaction_digest: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub senders: Vec<u8>,
+    pub package: Vec<u8>,
+    pub module: String,
+    pub event_type: String,
+    pub timestamp_ms: i64,
+    pub bcs: Vec<u8>,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = transactions)]
+pub struct SqliteTransaction {
+    pub tx_sequence_number: i64,
+    pub transaction_digest: Vec<u8>,
+    pub raw_transaction: Vec<u8>,
+    pub raw_effects: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub timestamp_ms: i64,
+    pub object_changes: Vec<u8>,
+    pub balance_changes: Vec<u8>,
+    pub events: Vec<u8>,
+    pub transaction_kind: i16,
+    pub success_command_count: i16,
+}
+
+fn encode_array(array: &[Option<Vec<u8>>]) -> Vec<u8> {
+    // Serializing a vector of byte vectors cannot fail.
+    bcs::to_bytes(array).unwrap()
+}
+
+fn decode_array(column: &str, bytes: &[u8]) -> Result<Vec<Option<Vec<u8>>>, IndexerError> {
+    bcs::from_bytes(bytes).map_err(|e| {
+        IndexerError::PersistentStorageDataCorruptionError(format!(
+            "Failed to decode {column} array column with error: {e}"
+        ))
+    })
+}
+
+impl From<&StoredCheckpoint> for SqliteCheckpoint {
+    fn from(c: &StoredCheckpoint) -> Self {
+        Self {
+            sequence_number: c.sequence_number,
+            checkpoint_digest: c.checkpoint_digest.clone(),
+            epoch: c.epoch,
+            network_total_transactions: c.network_total_transactions,
+            previous_checkpoint_digest: c.previous_checkpoint_digest.clone(),
+            end_of_epoch: c.end_of_epoch,
+            tx_digests: encode_array(&c.tx_digests),
+            timestamp_ms: c.timestamp_ms,
+            total_gas_cost: c.total_gas_cost,
+            computation_cost: c.computation_cost,
+            storage_cost: c.storage_cost,
+            storage_rebate: c.storage_rebate,
+            non_refundable_storage_fee: c.non_refundable_storage_fee,
+            checkpoint_commitments: c.checkpoint_commitments.clone(),
+            validator_signature: c.validator_signature.clone(),
+            end_of_epoch_data: c.end_of_epoch_data.clone(),
+        }
+    }
+}
+
+impl TryFrom<SqliteCheckpoint> for StoredCheckpoint {
+    type Error = IndexerError;
+
+    fn try_from(c: SqliteCheckpoint) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence_number: c.sequence_number,
+            checkpoint_digest: c.checkpoint_digest,
+            epoch: c.epoch,
+            network_total_transactions: c.network_total_transactions,
+            previous_checkpoint_digest: c.previous_checkpoint_digest,
+            end_of_epoch: c.end_of_epoch,
+            tx_digests: decode_array("tx_digests", &c.tx_digests)?,
+            timestamp_ms: c.timestamp_ms,
+            total_gas_cost: c.total_gas_cost,
+            computation_cost: c.computation_cost,
+            storage_cost: c.storage_cost,
+            storage_rebate: c.storage_rebate,
+            non_refundable_storage_fee: c.non_refundable_storage_fee,
+            checkpoint_commitments: c.checkpoint_commitments,
+            validator_signature: c.validator_signature,
+            end_of_epoch_data: c.end_of_epoch_data,
+        })
+    }
+}
+
+impl From<&StoredEpochInfo> for SqliteEpochInfo {
+    fn from(e: &StoredEpochInfo) -> Self {
+        Self {
+            epoch: e.epoch,
+            validators: encode_array(&e.validators),
+            first_checkpoint_id: e.first_checkpoint_id,
+            epoch_start_timestamp: e.epoch_start_timestamp,
+            reference_gas_price: e.reference_gas_price,
+            protocol_version: e.protocol_version,
+            epoch_total_transactions: e.epoch_total_transactions,
+            last_checkpoint_id: e.last_checkpoint_id,
+            epoch_end_timestamp: e.epoch_end_timestamp,
+            storage_fund_reinvestment: e.storage_fund_reinvestment,
+            storage_charge: e.storage_charge,
+            storage_rebate: e.storage_rebate,
+            storage_fund_balance: e.storage_fund_balance,
+            stake_subsidy_amount: e.stake_subsidy_amount,
+            total_gas_fees: e.total_gas_fees,
+            total_stake_rewards_distributed: e.total_stake_rewards_distributed,
+            leftover_storage_fund_inflow: e.leftover_storage_fund_inflow,
+            new_total_stake: e.new_total_stake,
+            epoch_commitments: e.epoch_commitments.clone(),
+            next_epoch_reference_gas_price: e.next_epoch_reference_gas_price,
+            next_epoch_protocol_version: e.next_epoch_protocol_version,
+        }
+    }
+}
+
+impl TryFrom<SqliteEpochInfo> for StoredEpochInfo {
+    type Error = IndexerError;
+
+    fn try_from(e: SqliteEpochInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            epoch: e.epoch,
+            validators: decode_array("validators", &e.validators)?,
+            first_checkpoint_id: e.first_checkpoint_id,
+            epoch_start_timestamp: e.epoch_start_timestamp,
+            reference_gas_price: e.reference_gas_price,
+            protocol_version: e.protocol_version,
+            epoch_total_transactions: e.epoch_total_transactions,
+            last_checkpoint_id: e.last_checkpoint_id,
+            epoch_end_timestamp: e.epoch_end_timestamp,
+            storage_fund_reinvestment: e.storage_fund_reinvestment,
+            storage_charge: e.storage_charge,
+            storage_rebate: e.storage_rebate,
+            storage_fund_balance: e.storage_fund_balance,
+            stake_subsidy_amount: e.stake_subsidy_amount,
+            total_gas_fees: e.total_gas_fees,
+            total_stake_rewards_distributed: e.total_stake_rewards_distributed,
+            leftover_storage_fund_inflow: e.leftover_storage_fund_inflow,
+            new_total_stake: e.new_total_stake,
+            epoch_commitments: e.epoch_commitments,
+            next_epoch_reference_gas_price: e.next_epoch_reference_gas_price,
+            next_epoch_protocol_version: e.next_epoch_protocol_version,
+        })
+    }
+}
+
+impl From<&StoredEvent> for SqliteEvent {
+    fn from(e: &StoredEvent) -> Self {
+        Self {
+            tx_sequence_number: e.tx_sequence_number,
+            event_sequence_number: e.event_sequence_number,
+            transaction_digest: e.transaction_digest.clone(),
+            checkpoint_sequence_number: e.checkpoint_sequence_number,
+            senders: encode_array(&e.senders),
+            package: e.package.clone(),
+            module: e.module.clone(),
+            event_type: e.event_type.clone(),
+            timestamp_ms: e.timestamp_ms,
+            bcs: e.bcs.clone(),
+        }
+    }
+}
+
+impl TryFrom<SqliteEvent> for StoredEvent {
+    type Error = IndexerError;
+
+    fn try_from(e: SqliteEvent) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_sequence_number: e.tx_sequence_number,
+            event_sequence_number: e.event_sequence_number,
+            transaction_digest: e.transaction_digest,
+            checkpoint_sequence_number: e.checkpoint_sequence_number,
+            senders: decode_array("senders", &e.senders)?,
+            package: e.package,
+            module: e.module,
+            event_type: e.event_type,
+            timestamp_ms: e.timestamp_ms,
+            bcs: e.bcs,
+        })
+    }
+}
+
+impl From<&StoredTransaction> for SqliteTransaction {
+    fn from(tx: &StoredTransaction) -> Self {
+        Self {
+            tx_sequence_number: tx.tx_sequence_number,
+            transaction_digest: tx.transaction_digest.clone(),
+            raw_transaction: tx.raw_transaction.clone(),
+            raw_effects: tx.raw_effects.clone(),
+            checkpoint_sequence_number: tx.checkpoint_sequence_number,
+            timestamp_ms: tx.timestamp_ms,
+            object_changes: encode_array(&tx.object_changes),
+            balance_changes: encode_array(&tx.balance_changes),
+            events: encode_array(&tx.events),
+            transaction_kind: tx.transaction_kind,
+            success_command_count: tx.success_command_count,
+        }
+    }
+}
+
+impl TryFrom<SqliteTransaction> for StoredTransaction {
+    type Error = IndexerError;
+
+    fn try_from(tx: SqliteTransaction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_sequence_number: tx.tx_sequence_number,
+            transaction_digest: tx.transaction_digest,
+            raw_transaction: tx.raw_transaction,
+            raw_effects: tx.raw_effects,
+            checkpoint_sequence_number: tx.checkpoint_sequence_number,
+            timestamp_ms: tx.timestamp_ms,
+            object_changes: decode_array("object_changes", &tx.object_changes)?,
+            balance_changes: decode_array("balance_changes", &tx.balance_changes)?,
+            events: decode_array("events", &tx.events)?,
+            transaction_kind: tx.transaction_kind,
+            success_command_count: tx.success_command_count,
+        })
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tables of the SQLite backend (see `migrations_sqlite`) that differ from `schema_v2`. SQLite has
+//! no array type, so array columns are stored as bcs serialized `Vec<Option<Vec<u8>>>` blobs.
+//! All other tables have the same columns in both backends, and are shared through `schema_v2`.
+
+use crate::schema_v2::{tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders};
+
+diesel::table! {
+    checkpoints (sequence_number) {
+        sequence_number -> Int8,
+        checkpoint_digest -> Bytea,
+        epoch -> Int8,
+        network_total_transactions -> Int8,
+        previous_checkpoint_digest -> Nullable<Bytea>,
+        end_of_epoch -> Bool,
+        tx_digests -> Bytea,
+        timestamp_ms -> Int8,
+        total_gas_cost -> Int8,
+        computation_cost -> Int8,
+        storage_cost -> Int8,
+        storage_rebate -> Int8,
+        non_refundable_storage_fee -> Int8,
+        checkpoint_commitments -> Bytea,
+        validator_signature -> Bytea,
+        end_of_epoch_data -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    epochs (epoch) {
+        epoch -> Int8,
+        validators -> Bytea,
+        first_checkpoint_id -> Int8,
+        epoch_start_timestamp -> Int8,
+        reference_gas_price -> Int8,
+        protocol_version -> Int8,
+        epoch_total_transactions -> Nullable<Int8>,
+        last_checkpoint_id -> Nullable<Int8>,
+        epoch_end_timestamp -> Nullable<Int8>,
+        storage_fund_reinvestment -> Nullable<Int8>,
+        storage_charge -> Nullable<Int8>,
+        storage_rebate -> Nullable<Int8>,
+        storage_fund_balance -> Nullable<Int8>,
+        stake_subsidy_amount -> Nullable<Int8>,
+        total_gas_fees -> Nullable<Int8>,
+        total_stake_rewards_distributed -> Nullable<Int8>,
+        leftover_storage_fund_inflow -> Nullable<Int8>,
+        new_total_stake -> Nullable<Int8>,
+        epoch_commitments -> Nullable<Bytea>,
+        next_epoch_reference_gas_price -> Nullable<Int8>,
+        next_epoch_protocol_version -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    events (tx_sequence_number, event_sequence_number) {
+        tx_sequence_number -> Int8,
+        event_sequence_number -> Int8,
+        transaction_digest -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        senders -> Bytea,
+        package -> Bytea,
+        module -> Text,
+        event_type -> Text,
+        timestamp_ms -> Int8,
+        bcs -> Bytea,
+    }
+}
+
+diesel::table! {
+    transactions (tx_sequence_number) {
+        tx_sequence_number -> Int8,
+        transaction_digest -> Bytea,
+        raw_transaction -> Bytea,
+        raw_effects -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        timestamp_ms -> Int8,
+        object_changes -> Bytea,
+        balance_changes -> Bytea,
+        events -> Bytea,
+        transaction_kind -> Int2,
+        success_command_count -> Int2,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(checkpoints, epochs, events, transactions,);
+
+// Readers filter transactions by subqueries on the shared index tables. Each pair is declared on
+// its own, as the `schema_v2` tables are already allowed to appear in a query with each other.
+diesel::allow_tables_to_appear_in_same_query!(transactions, tx_calls);
+diesel::allow_tables_to_appear_in_same_query!(transactions, tx_changed_objects);
+diesel::allow_tables_to_appear_in_same_query!(transactions, tx_input_objects);
+diesel::allow_tables_to_appear_in_same_query!(transactions, tx_recipients);
+diesel::allow_tables_to_appear_in_same_query!(transactions, tx_senders);
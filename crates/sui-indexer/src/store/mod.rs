@@ -7,6 +7,7 @@ pub(crate) use indexer_store_v2::*;
 pub use pg_indexer_analytical_store::PgIndexerAnalyticalStore;
 pub use pg_indexer_store::PgIndexerStore;
 pub use pg_indexer_store_v2::PgIndexerStoreV2;
+pub use sqlite_indexer_store_v2::SqliteIndexerStoreV2;
 
 mod indexer_analytical_store;
 mod indexer_store;
@@ -17,6 +18,7 @@ mod pg_indexer_analytical_store;
 mod pg_indexer_store;
 mod pg_indexer_store_v2;
 mod query;
+mod sqlite_indexer_store_v2;
 
 pub(crate) mod diesel_macro {
     macro_rules! read_only_blocking {
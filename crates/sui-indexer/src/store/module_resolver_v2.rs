@@ -19,7 +19,7 @@ use sui_types::move_package::MovePackage;
 use crate::errors::{Context, IndexerError};
 use crate::models_v2::packages::StoredPackage;
 use crate::store::diesel_macro::read_only_blocking;
-use crate::PgConnectionPool;
+use crate::{get_sqlite_pool_connection, PgConnectionPool, SqliteConnectionPool};
 
 /// A package resolver that reads packages from the database.
 pub struct IndexerStoreModuleResolver {
@@ -48,21 +48,52 @@ impl ModuleResolver for IndexerStoreModuleResolver {
         })
         .context("Error reading module.")?;
 
-        let move_package =
-            bcs::from_bytes::<MovePackage>(&stored_package.move_package).map_err(|e| {
-                IndexerError::PersistentStorageDataCorruptionError(format!(
-                    "Error deserializing move package. Error: {}",
-                    e
-                ))
-            })?;
-
-        Ok(move_package
-            .serialized_module_map()
-            .get(&module_name)
-            .cloned())
+        module_from_stored_package(&stored_package, &module_name)
     }
 }
 
+/// A package resolver that reads packages from a SQLite database.
+pub struct SqliteStoreModuleResolver {
+    cp: SqliteConnectionPool,
+}
+
+impl SqliteStoreModuleResolver {
+    pub fn new(cp: SqliteConnectionPool) -> Self {
+        Self { cp }
+    }
+}
+
+impl ModuleResolver for SqliteStoreModuleResolver {
+    type Error = IndexerError;
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        let package_id = ObjectID::from(*id.address()).to_vec();
+        let module_name = id.name().to_string();
+
+        let stored_package: StoredPackage = packages::dsl::packages
+            .filter(packages::dsl::package_id.eq(package_id))
+            .first::<StoredPackage>(&mut get_sqlite_pool_connection(&self.cp)?)
+            .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+            .context("Error reading module.")?;
+
+        module_from_stored_package(&stored_package, &module_name)
+    }
+}
+
+fn module_from_stored_package(
+    stored_package: &StoredPackage,
+    module_name: &str,
+) -> Result<Option<Vec<u8>>, IndexerError> {
+    let move_package = bcs::from_bytes::<MovePackage>(&stored_package.move_package).map_err(|e| {
+        IndexerError::PersistentStorageDataCorruptionError(format!(
+            "Error deserializing move package. Error: {}",
+            e
+        ))
+    })?;
+
+    Ok(move_package.serialized_module_map().get(module_name).cloned())
+}
+
 /// InterimModuleResolver consists of a backup ModuleResolver
 /// (e.g. IndexerStoreModuleResolver) and an in-mem package cache.
 pub struct InterimModuleResolver<GM> {
@@ -889,7 +889,7 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
 /// Construct deleted objects and mutated objects to commit.
 /// In particular, filter mutated objects updates that would
 /// be override immediately.
-fn make_final_list_of_objects_to_commit(
+pub(super) fn make_final_list_of_objects_to_commit(
     tx_object_changes: Vec<TransactionObjectChangesToCommit>,
 ) -> Vec<ObjectChangeToCommit> {
     let deleted_objects = tx_object_changes
@@ -930,7 +930,7 @@ fn make_final_list_of_objects_to_commit(
 }
 
 #[allow(clippy::large_enum_variant)]
-enum ObjectChangeToCommit {
+pub(super) enum ObjectChangeToCommit {
     MutatedObject(StoredObject),
     DeletedObject(ObjectID),
 }
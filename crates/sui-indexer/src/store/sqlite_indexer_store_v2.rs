@@ -0,0 +1,649 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tap::Tap;
+
+use async_trait::async_trait;
+use diesel::dsl::max;
+use diesel::upsert::excluded;
+use diesel::Connection;
+use diesel::ExpressionMethods;
+use diesel::OptionalExtension;
+use diesel::SqliteConnection;
+use diesel::{QueryDsl, RunQueryDsl};
+use move_bytecode_utils::module_cache::SyncModuleCache;
+use tracing::info;
+
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::object::ObjectRead;
+
+use crate::errors::{Context, IndexerError};
+use crate::handlers::EpochToCommit;
+use crate::handlers::TransactionObjectChangesToCommit;
+use crate::metrics::IndexerMetrics;
+
+use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::models_v2::display::StoredDisplay;
+use crate::models_v2::epoch::StoredEpochInfo;
+use crate::models_v2::events::StoredEvent;
+use crate::models_v2::objects::StoredObject;
+use crate::models_v2::packages::StoredPackage;
+use crate::models_v2::sqlite::{SqliteCheckpoint, SqliteEpochInfo, SqliteEvent, SqliteTransaction};
+use crate::models_v2::transactions::StoredTransaction;
+use crate::schema_sqlite::{checkpoints, epochs, events, transactions};
+use crate::schema_v2::{
+    display, objects, packages, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients,
+    tx_senders,
+};
+use crate::store::module_resolver_v2::SqliteStoreModuleResolver;
+use crate::types_v2::{
+    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
+};
+use crate::{get_sqlite_pool_connection, SqliteConnectionPool};
+
+use super::pg_indexer_store_v2::{make_final_list_of_objects_to_commit, ObjectChangeToCommit};
+use super::IndexerStoreV2;
+
+/// An [IndexerStoreV2] backed by a single SQLite file, for local development and tests that
+/// should not depend on a running Postgres. Its tables are created by `migrations_sqlite`.
+///
+/// SQLite only allows one writer at a time, so unlike [super::PgIndexerStoreV2], each call
+/// commits all of its rows in a single DB transaction, rather than in parallel chunks.
+#[derive(Clone)]
+pub struct SqliteIndexerStoreV2 {
+    blocking_cp: SqliteConnectionPool,
+    module_cache: Arc<SyncModuleCache<SqliteStoreModuleResolver>>,
+    metrics: IndexerMetrics,
+}
+
+impl SqliteIndexerStoreV2 {
+    pub fn new(blocking_cp: SqliteConnectionPool, metrics: IndexerMetrics) -> Self {
+        let module_cache = Arc::new(SyncModuleCache::new(SqliteStoreModuleResolver::new(
+            blocking_cp.clone(),
+        )));
+        Self {
+            blocking_cp,
+            module_cache,
+            metrics,
+        }
+    }
+
+    fn read<T, F>(&self, query: F) -> Result<T, IndexerError>
+    where
+        F: FnOnce(&mut SqliteConnection) -> Result<T, IndexerError>,
+    {
+        let mut connection = get_sqlite_pool_connection(&self.blocking_cp)?;
+        SqliteConnection::transaction(&mut connection, query)
+            .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+    }
+
+    fn write<T, F>(&self, query: F) -> Result<T, IndexerError>
+    where
+        F: FnOnce(&mut SqliteConnection) -> Result<T, IndexerError>,
+    {
+        // Take the write lock upfront, so that concurrent writers wait for each other (up to the
+        // busy timeout) instead of failing when upgrading from a read lock.
+        get_sqlite_pool_connection(&self.blocking_cp)?
+            .immediate_transaction(query)
+            .map_err(|e| IndexerError::SqliteWriteError(e.to_string()))
+    }
+
+    fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
+        self.read(|conn| {
+            checkpoints::table
+                .select(max(checkpoints::sequence_number))
+                .first::<Option<i64>>(conn)
+                .map(|v| v.map(|v| v as u64))
+                .map_err(IndexerError::from)
+        })
+        .context("Failed reading latest checkpoint sequence number from SQLite DB")
+    }
+
+    // Note: here we treat Deleted as NotExists too
+    fn get_object_read(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        let stored_object = self
+            .read(|conn| {
+                let query = objects::table.filter(objects::object_id.eq(object_id.to_vec()));
+                let boxed_query = if let Some(version) = version {
+                    query
+                        .filter(objects::object_version.eq(version.value() as i64))
+                        .into_boxed()
+                } else {
+                    query.into_boxed()
+                };
+                boxed_query
+                    .first::<StoredObject>(conn)
+                    .optional()
+                    .map_err(IndexerError::from)
+            })
+            .context("Failed to read object from SQLite DB")?;
+
+        // Resolve the object's layout outside of the transaction above, as the module cache may
+        // need a connection of its own.
+        match stored_object {
+            None => Ok(ObjectRead::NotExists(object_id)),
+            Some(obj) => obj.try_into_object_read(self.module_cache.as_ref()),
+        }
+    }
+
+    fn persist_objects(
+        &self,
+        object_changes: Vec<TransactionObjectChangesToCommit>,
+    ) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_objects
+            .start_timer();
+        let objects = make_final_list_of_objects_to_commit(object_changes);
+        let len = objects.len();
+
+        self.write(|conn| {
+            for object in &objects {
+                match object {
+                    ObjectChangeToCommit::MutatedObject(o) => {
+                        diesel::replace_into(objects::table)
+                            .values(o)
+                            .execute(conn)?;
+                    }
+                    ObjectChangeToCommit::DeletedObject(id) => {
+                        diesel::delete(objects::table.filter(objects::object_id.eq(id.to_vec())))
+                            .execute(conn)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+        .context("Failed to write objects to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} objects", len)
+        })
+    }
+
+    fn persist_checkpoints(&self, checkpoints: Vec<IndexedCheckpoint>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_checkpoints
+            .start_timer();
+        let checkpoints = checkpoints
+            .iter()
+            .map(|c| SqliteCheckpoint::from(&StoredCheckpoint::from(c)))
+            .collect::<Vec<_>>();
+
+        self.write(|conn| {
+            for checkpoint in &checkpoints {
+                diesel::insert_or_ignore_into(checkpoints::table)
+                    .values(checkpoint)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write checkpoints to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} checkpoints", checkpoints.len())
+        })
+    }
+
+    fn persist_transactions(
+        &self,
+        transactions: Vec<IndexedTransaction>,
+    ) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_transactions
+            .start_timer();
+        let transactions = transactions
+            .iter()
+            .map(|tx| SqliteTransaction::from(&StoredTransaction::from(tx)))
+            .collect::<Vec<_>>();
+
+        self.write(|conn| {
+            for transaction in &transactions {
+                diesel::insert_or_ignore_into(transactions::table)
+                    .values(transaction)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write transactions to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} transactions", transactions.len())
+        })
+    }
+
+    fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_tx_indices
+            .start_timer();
+        let len = indices.len();
+
+        self.write(|conn| {
+            for index in indices {
+                let (senders, recipients, input_objects, changed_objects, calls) = index.split();
+                for sender in &senders {
+                    diesel::insert_or_ignore_into(tx_senders::table)
+                        .values(sender)
+                        .execute(conn)?;
+                }
+                for recipient in &recipients {
+                    diesel::insert_or_ignore_into(tx_recipients::table)
+                        .values(recipient)
+                        .execute(conn)?;
+                }
+                for input_object in &input_objects {
+                    diesel::insert_or_ignore_into(tx_input_objects::table)
+                        .values(input_object)
+                        .execute(conn)?;
+                }
+                for changed_object in &changed_objects {
+                    diesel::insert_or_ignore_into(tx_changed_objects::table)
+                        .values(changed_object)
+                        .execute(conn)?;
+                }
+                for call in &calls {
+                    diesel::insert_or_ignore_into(tx_calls::table)
+                        .values(call)
+                        .execute(conn)?;
+                }
+            }
+            Ok(())
+        })
+        .context("Failed to write tx_indices to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} tx_indices", len)
+        })
+    }
+
+    fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_events
+            .start_timer();
+        let events = events
+            .into_iter()
+            .map(|e| SqliteEvent::from(&StoredEvent::from(e)))
+            .collect::<Vec<_>>();
+
+        self.write(|conn| {
+            for event in &events {
+                diesel::insert_or_ignore_into(events::table)
+                    .values(event)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write events to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} events", events.len())
+        })
+    }
+
+    fn persist_display_updates(
+        &self,
+        display_updates: BTreeMap<String, StoredDisplay>,
+    ) -> Result<(), IndexerError> {
+        self.write(|conn| {
+            for display in display_updates.values() {
+                diesel::replace_into(display::table)
+                    .values(display)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write display updates to SQLite DB")
+    }
+
+    fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_packages
+            .start_timer();
+        let packages = packages
+            .into_iter()
+            .map(StoredPackage::from)
+            .collect::<Vec<_>>();
+
+        self.write(|conn| {
+            for package in &packages {
+                // System packages keep their package_id across upgrades, so the modules of an
+                // existing package are overridden.
+                diesel::replace_into(packages::table)
+                    .values(package)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write packages to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} packages", packages.len())
+        })
+    }
+
+    fn persist_epoch(&self, data: &[EpochToCommit]) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_epochs
+            .start_timer();
+
+        self.write(|conn| {
+            for epoch_data in data {
+                if let Some(last_epoch) = &epoch_data.last_epoch {
+                    let last_epoch_id = last_epoch.epoch;
+                    let last_epoch = StoredEpochInfo::from_epoch_end_info(last_epoch);
+                    info!(last_epoch_id, "Persisting epoch end data: {:?}", last_epoch);
+                    diesel::insert_into(epochs::table)
+                        .values(SqliteEpochInfo::from(&last_epoch))
+                        .on_conflict(epochs::epoch)
+                        .do_update()
+                        .set((
+                            // Note: as in PgIndexerStoreV2, epoch beginning info must not be
+                            // overridden here.
+                            epochs::epoch_total_transactions
+                                .eq(excluded(epochs::epoch_total_transactions)),
+                            epochs::last_checkpoint_id.eq(excluded(epochs::last_checkpoint_id)),
+                            epochs::epoch_end_timestamp.eq(excluded(epochs::epoch_end_timestamp)),
+                            epochs::storage_fund_reinvestment
+                                .eq(excluded(epochs::storage_fund_reinvestment)),
+                            epochs::storage_charge.eq(excluded(epochs::storage_charge)),
+                            epochs::storage_rebate.eq(excluded(epochs::storage_rebate)),
+                            epochs::storage_fund_balance.eq(excluded(epochs::storage_fund_balance)),
+                            epochs::stake_subsidy_amount.eq(excluded(epochs::stake_subsidy_amount)),
+                            epochs::total_gas_fees.eq(excluded(epochs::total_gas_fees)),
+                            epochs::total_stake_rewards_distributed
+                                .eq(excluded(epochs::total_stake_rewards_distributed)),
+                            epochs::leftover_storage_fund_inflow
+                                .eq(excluded(epochs::leftover_storage_fund_inflow)),
+                            epochs::new_total_stake.eq(excluded(epochs::new_total_stake)),
+                            epochs::epoch_commitments.eq(excluded(epochs::epoch_commitments)),
+                            epochs::next_epoch_reference_gas_price
+                                .eq(excluded(epochs::next_epoch_reference_gas_price)),
+                            epochs::next_epoch_protocol_version
+                                .eq(excluded(epochs::next_epoch_protocol_version)),
+                        ))
+                        .execute(conn)?;
+                }
+                let epoch_id = epoch_data.new_epoch.epoch;
+                info!(epoch_id, "Persisting initial epoch state");
+                let new_epoch = StoredEpochInfo::from_epoch_beginning_info(&epoch_data.new_epoch);
+                diesel::insert_or_ignore_into(epochs::table)
+                    .values(SqliteEpochInfo::from(&new_epoch))
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write epochs to SQLite DB")
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} epochs", data.len())
+        })
+    }
+
+    fn get_network_total_transactions_by_end_of_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, IndexerError> {
+        self.read(|conn| {
+            checkpoints::table
+                .filter(checkpoints::epoch.eq(epoch as i64))
+                .select(max(checkpoints::network_total_transactions))
+                .first::<Option<i64>>(conn)
+                .map(|o| o.unwrap_or(0))
+                .map_err(IndexerError::from)
+        })
+        .context("Failed to get network total transactions in epoch")
+        .map(|v| v as u64)
+    }
+
+    async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
+    where
+        F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let this = self.clone();
+        let current_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _guard = current_span.enter();
+            f(this)
+        })
+        .await
+        .map_err(Into::into)
+        .and_then(std::convert::identity)
+    }
+}
+
+#[async_trait]
+impl IndexerStoreV2 for SqliteIndexerStoreV2 {
+    type ModuleCache = SyncModuleCache<SqliteStoreModuleResolver>;
+
+    async fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_latest_tx_checkpoint_sequence_number())
+            .await
+    }
+
+    async fn get_object_read(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_object_read(object_id, version))
+            .await
+    }
+
+    async fn persist_objects(
+        &self,
+        object_changes: Vec<TransactionObjectChangesToCommit>,
+    ) -> Result<(), IndexerError> {
+        if object_changes.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_objects(object_changes))
+            .await
+    }
+
+    async fn persist_checkpoints(
+        &self,
+        checkpoints: Vec<IndexedCheckpoint>,
+    ) -> Result<(), IndexerError> {
+        if checkpoints.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_checkpoints(checkpoints))
+            .await
+    }
+
+    async fn persist_transactions(
+        &self,
+        transactions: Vec<IndexedTransaction>,
+    ) -> Result<(), IndexerError> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_transactions(transactions))
+            .await
+    }
+
+    async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        if indices.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_tx_indices(indices))
+            .await
+    }
+
+    async fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_events(events))
+            .await
+    }
+
+    async fn persist_displays(
+        &self,
+        display_updates: BTreeMap<String, StoredDisplay>,
+    ) -> Result<(), IndexerError> {
+        if display_updates.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_display_updates(display_updates))
+            .await
+    }
+
+    async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
+        if packages.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_packages(packages))
+            .await
+    }
+
+    async fn persist_epoch(&self, data: Vec<EpochToCommit>) -> Result<(), IndexerError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_epoch(&data))
+            .await
+    }
+
+    async fn get_network_total_transactions_by_end_of_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.get_network_total_transactions_by_end_of_epoch(epoch)
+        })
+        .await
+    }
+
+    fn module_cache(&self) -> Arc<Self::ModuleCache> {
+        self.module_cache.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use diesel::{QueryDsl, RunQueryDsl};
+    use prometheus::Registry;
+
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::gas_coin::GAS;
+    use sui_types::object::{Object, ObjectRead};
+
+    use crate::handlers::TransactionObjectChangesToCommit;
+    use crate::indexer_reader::IndexerReader;
+    use crate::metrics::IndexerMetrics;
+    use crate::schema_v2::objects;
+    use crate::store::IndexerStoreV2;
+    use crate::types_v2::IndexedObject;
+    use crate::utils::setup_sqlite_database;
+    use crate::{get_sqlite_pool_connection, new_sqlite_connection_pool};
+
+    use super::SqliteIndexerStoreV2;
+
+    #[tokio::test]
+    async fn test_persist_and_delete_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("indexer.db");
+        let cp = new_sqlite_connection_pool(db_path.to_str().unwrap()).unwrap();
+        setup_sqlite_database(&mut get_sqlite_pool_connection(&cp).unwrap(), false).unwrap();
+        let store =
+            SqliteIndexerStoreV2::new(cp.clone(), IndexerMetrics::new(&Registry::default()));
+
+        assert_eq!(
+            store
+                .get_latest_tx_checkpoint_sequence_number()
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            store
+                .get_network_total_transactions_by_end_of_epoch(0)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let object = Object::new_gas_for_testing();
+        let object_ref = object.compute_object_reference();
+        store
+            .persist_objects(vec![TransactionObjectChangesToCommit {
+                changed_objects: vec![IndexedObject::from_object(1, object, None)],
+                deleted_objects: vec![],
+            }])
+            .await
+            .unwrap();
+        let count: i64 = objects::table
+            .count()
+            .get_result(&mut get_sqlite_pool_connection(&cp).unwrap())
+            .unwrap();
+        assert_eq!(count, 1);
+
+        store
+            .persist_objects(vec![TransactionObjectChangesToCommit {
+                changed_objects: vec![],
+                deleted_objects: vec![object_ref],
+            }])
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.get_object_read(object_ref.0, None).await.unwrap(),
+            ObjectRead::NotExists(id) if id == object_ref.0
+        ));
+    }
+    #[tokio::test]
+    async fn test_read_objects_with_indexer_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("indexer.db");
+        let cp = new_sqlite_connection_pool(db_path.to_str().unwrap()).unwrap();
+        setup_sqlite_database(&mut get_sqlite_pool_connection(&cp).unwrap(), false).unwrap();
+        let store = SqliteIndexerStoreV2::new(cp, IndexerMetrics::new(&Registry::default()));
+
+        let owner = SuiAddress::random_for_testing_only();
+        let object = Object::new_gas_with_balance_and_owner_for_testing(1_000, owner);
+        let object_id = object.id();
+        store
+            .persist_objects(vec![TransactionObjectChangesToCommit {
+                changed_objects: vec![IndexedObject::from_object(1, object, None)],
+                deleted_objects: vec![],
+            }])
+            .await
+            .unwrap();
+
+        let reader = IndexerReader::new_sqlite(db_path.to_str().unwrap()).unwrap();
+        assert!(reader.is_sqlite());
+        assert!(matches!(
+            reader.get_object_read_in_blocking_task(object_id).await.unwrap(),
+            ObjectRead::Exists(object_ref, _, _) if object_ref.0 == object_id
+        ));
+
+        let coins = reader
+            .get_owned_coins_in_blocking_task(owner, None, ObjectID::ZERO, 10)
+            .await
+            .unwrap();
+        assert_eq!(coins.len(), 1);
+        assert_eq!(coins[0].coin_object_id, object_id);
+        assert_eq!(coins[0].balance, 1_000);
+
+        let balances = reader
+            .get_coin_balances_in_blocking_task(owner, None)
+            .await
+            .unwrap();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].coin_type, GAS::type_tag().to_string());
+        assert_eq!(balances[0].coin_object_count, 1);
+        assert_eq!(balances[0].total_balance, 1_000);
+    }
+}
@@ -4,12 +4,13 @@
 use crate::PgPoolConnection;
 use anyhow::anyhow;
 use diesel::migration::MigrationSource;
-use diesel::{PgConnection, RunQueryDsl};
+use diesel::{PgConnection, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use tracing::info;
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 const MIGRATIONS_V2: EmbeddedMigrations = embed_migrations!("migrations_v2");
+const MIGRATIONS_SQLITE: EmbeddedMigrations = embed_migrations!("migrations_sqlite");
 
 /// Resets the database by reverting all migrations and reapplying them.
 ///
@@ -37,6 +38,19 @@ pub fn reset_database(
     Ok(())
 }
 
+/// Brings a SQLite database up to date by applying any pending migrations. If `reset` is set to
+/// `true`, all migrations are reverted first, which drops all indexed data.
+pub fn setup_sqlite_database(conn: &mut SqliteConnection, reset: bool) -> Result<(), anyhow::Error> {
+    if reset {
+        info!("Resetting SQLite database ...");
+        conn.revert_all_migrations(MIGRATIONS_SQLITE)
+            .map_err(|e| anyhow!("Error reverting all migrations {e}"))?;
+    }
+    conn.run_pending_migrations(MIGRATIONS_SQLITE)
+        .map_err(|e| anyhow!("Failed to run migrations {e}"))?;
+    Ok(())
+}
+
 pub fn drop_all_tables(conn: &mut PgConnection) -> Result<(), diesel::result::Error> {
     info!("Dropping all tables in the database");
     let table_names: Vec<String> = diesel::dsl::sql::<diesel::sql_types::Text>(
@@ -14,7 +14,7 @@ use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
 use serde::Serialize;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use sui_core::authority::AuthorityState;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
@@ -73,13 +73,80 @@ pub fn spawn_subscription<S, T>(
 }
 const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
 
+/// Caps the number of concurrent subscriptions served by the [IndexerApi]. The cap can be changed
+/// at runtime, when it is lowered the excess permits are retired as the open subscriptions close.
+#[derive(Clone)]
+pub struct SubscriptionLimit {
+    semaphore: Arc<Semaphore>,
+    /// Permits are only acquired with the state locked, so that they can't be taken in between
+    /// counting the available permits and retiring them.
+    state: Arc<Mutex<SubscriptionLimitState>>,
+}
+
+struct SubscriptionLimitState {
+    max_subscriptions: usize,
+    /// The number of permits held by open subscriptions to retire once they are released.
+    excess_permits: usize,
+}
+
+impl SubscriptionLimit {
+    pub fn new(max_subscriptions: Option<usize>) -> Self {
+        let max_subscriptions = max_subscriptions.unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_subscriptions)),
+            state: Arc::new(Mutex::new(SubscriptionLimitState {
+                max_subscriptions,
+                excess_permits: 0,
+            })),
+        }
+    }
+
+    pub fn max_subscriptions(&self) -> usize {
+        self.state.lock().unwrap().max_subscriptions
+    }
+
+    pub fn set_max_subscriptions(&self, max_subscriptions: Option<usize>) {
+        let max_subscriptions = max_subscriptions.unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS);
+        let mut state = self.state.lock().unwrap();
+        if max_subscriptions > state.max_subscriptions {
+            // Keep the permits which haven't been retired yet, before adding new ones.
+            let increase = max_subscriptions - state.max_subscriptions;
+            let kept = increase.min(state.excess_permits);
+            state.excess_permits -= kept;
+            self.semaphore.add_permits(increase - kept);
+        } else {
+            state.excess_permits += state.max_subscriptions - max_subscriptions;
+        }
+        state.max_subscriptions = max_subscriptions;
+        self.retire_excess_permits(&mut state);
+    }
+
+    fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let mut state = self.state.lock().unwrap();
+        self.retire_excess_permits(&mut state);
+        self.semaphore.clone().try_acquire_owned().ok()
+    }
+
+    /// Retire as many of the excess permits as have been released.
+    fn retire_excess_permits(&self, state: &mut SubscriptionLimitState) {
+        let retired = state.excess_permits.min(self.semaphore.available_permits());
+        if retired == 0 {
+            return;
+        }
+        if let Ok(permits) = self.semaphore.try_acquire_many(retired as u32) {
+            permits.forget();
+            state.excess_permits -= retired;
+        }
+    }
+}
+
 pub struct IndexerApi<R> {
     state: Arc<dyn StateRead>,
     read_api: R,
     transaction_kv_store: Arc<TransactionKeyValueStore>,
     name_service_config: NameServiceConfig,
     pub metrics: Arc<JsonRpcMetrics>,
-    subscription_semaphore: Arc<Semaphore>,
+    subscription_limit: SubscriptionLimit,
 }
 
 impl<R: ReadApiServer> IndexerApi<R> {
@@ -89,16 +156,15 @@ impl<R: ReadApiServer> IndexerApi<R> {
         transaction_kv_store: Arc<TransactionKeyValueStore>,
         name_service_config: NameServiceConfig,
         metrics: Arc<JsonRpcMetrics>,
-        max_subscriptions: Option<usize>,
+        subscription_limit: SubscriptionLimit,
     ) -> Self {
-        let max_subscriptions = max_subscriptions.unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS);
         Self {
             state,
             transaction_kv_store,
             read_api,
             name_service_config,
             metrics,
-            subscription_semaphore: Arc::new(Semaphore::new(max_subscriptions)),
+            subscription_limit,
         }
     }
 
@@ -118,9 +184,9 @@ impl<R: ReadApiServer> IndexerApi<R> {
     }
 
     fn acquire_subscribe_permit(&self) -> anyhow::Result<OwnedSemaphorePermit> {
-        match self.subscription_semaphore.clone().try_acquire_owned() {
-            Ok(p) => Ok(p),
-            Err(_) => bail!("Resources exhausted"),
+        match self.subscription_limit.try_acquire() {
+            Some(p) => Ok(p),
+            None => bail!("Resources exhausted"),
         }
     }
 }
@@ -441,3 +507,36 @@ impl<R: ReadApiServer> SuiRpcModule for IndexerApi<R> {
         crate::api::IndexerApiOpenRpc::module_doc()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_limit() {
+        let limit = SubscriptionLimit::new(Some(2));
+        let first = limit.try_acquire().unwrap();
+        let second = limit.try_acquire().unwrap();
+        assert!(limit.try_acquire().is_none());
+
+        // Lowering the limit retires the permits as they are released.
+        limit.set_max_subscriptions(Some(1));
+        assert_eq!(limit.max_subscriptions(), 1);
+        drop(first);
+        assert!(limit.try_acquire().is_none());
+        drop(second);
+        let first = limit.try_acquire().unwrap();
+        assert!(limit.try_acquire().is_none());
+        drop(first);
+
+        // Raising the limit while permits are still to be retired doesn't leak any permits.
+        let first = limit.try_acquire().unwrap();
+        limit.set_max_subscriptions(Some(0));
+        limit.set_max_subscriptions(Some(2));
+        let second = limit.try_acquire().unwrap();
+        assert!(limit.try_acquire().is_none());
+        drop((first, second));
+        let permits: Vec<_> = std::iter::from_fn(|| limit.try_acquire()).collect();
+        assert_eq!(permits.len(), 2);
+    }
+}
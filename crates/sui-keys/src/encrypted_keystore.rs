@@ -0,0 +1,631 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::AccountKeystore;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, ensure};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the passphrase of an encrypted keystore.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+/// Environment variable holding the path of a file containing the passphrase of an encrypted
+/// keystore.
+pub const SUI_KEYSTORE_PASSPHRASE_FILE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE_FILE";
+
+/// Environment variable holding the token of a session started by
+/// [EncryptedFileKeystore::start_session].
+pub const SUI_KEYSTORE_SESSION_ENV: &str = "SUI_KEYSTORE_SESSION";
+
+const FORMAT_VERSION: u64 = 1;
+const KDF_ALGORITHM: &str = "argon2id";
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Associated data for the ciphertext that is used to check the passphrase, even when the
+/// keystore holds no keys.
+const CHECK_AAD: &[u8] = b"sui-keystore-check";
+
+/// A keystore that keeps private keys encrypted at rest, under a key derived from a passphrase
+/// (argon2id), using AES-256-GCM. Public keys are stored in the clear, so that addresses can be
+/// listed while the keystore is locked, and each ciphertext is bound to its public key.
+///
+/// The keystore starts locked, and is unlocked on first use of a private key, by reading the
+/// passphrase from [SUI_KEYSTORE_PASSPHRASE_ENV], from the file named by
+/// [SUI_KEYSTORE_PASSPHRASE_FILE_ENV], or by prompting for it on the terminal. An unlocked
+/// keystore can start a session (see [EncryptedFileKeystore::start_session]), which allows other
+/// processes that hold the session's token in [SUI_KEYSTORE_SESSION_ENV] to unlock it without the
+/// passphrase, until the session expires or is ended by [EncryptedFileKeystore::lock].
+pub struct EncryptedFileKeystore {
+    path: PathBuf,
+    contents: KeystoreFile,
+    public_keys: BTreeMap<SuiAddress, PublicKey>,
+    unlocked: OnceLock<Unlocked>,
+}
+
+/// The decryption key, and the decrypted private keys of an unlocked keystore.
+struct Unlocked {
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+}
+
+/// The on-disk representation of an encrypted keystore.
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u64,
+    kdf: KdfParams,
+    check: Ciphertext,
+    keys: Vec<EncryptedKey>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Ciphertext {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedKey {
+    /// Base64 encoded `flag || pk`, also used as associated data for the ciphertext.
+    public_key: String,
+    #[serde(flatten)]
+    private_key: Ciphertext,
+}
+
+/// The keystore's decryption key, encrypted under a session token that is never written to disk,
+/// so that processes holding the token can unlock the keystore until the session expires.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    salt: String,
+    expires_at_ms: u64,
+    key: Ciphertext,
+}
+
+impl Serialize for EncryptedFileKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(msg, self.signing_key(address)?))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.signing_key(address)?,
+        ))
+    }
+
+    fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        self.unlocked()?;
+        let unlocked = self
+            .unlocked
+            .get_mut()
+            .expect("Keystore was unlocked above");
+
+        let public_key = keypair.public();
+        let address: SuiAddress = (&public_key).into();
+        let encrypted = EncryptedKey::new(&unlocked.key, &keypair)?;
+
+        self.contents
+            .keys
+            .retain(|k| k.public_key != encrypted.public_key);
+        self.contents.keys.push(encrypted);
+        self.public_keys.insert(address, public_key);
+        unlocked.keys.insert(address, keypair);
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.public_keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        match self.unlocked()?.keys.get(address) {
+            Some(key) => Ok(key),
+            None => Err(anyhow!("Cannot find key for address: [{address}]")),
+        }
+    }
+}
+
+impl EncryptedFileKeystore {
+    /// Open the encrypted keystore at `path`. The keystore starts locked.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let bytes = fs::read(path)
+            .map_err(|e| anyhow!("Can't open EncryptedFileKeystore from {:?}: {e}", path))?;
+        let contents: KeystoreFile = serde_json::from_slice(&bytes).map_err(|e| {
+            anyhow!(
+                "Can't deserialize EncryptedFileKeystore from {:?}: {e}",
+                path
+            )
+        })?;
+
+        ensure!(
+            contents.version == FORMAT_VERSION,
+            "Unsupported encrypted keystore version {} in {:?}",
+            contents.version,
+            path
+        );
+
+        let public_keys = contents
+            .keys
+            .iter()
+            .map(|k| {
+                let pk = PublicKey::decode_base64(&k.public_key)
+                    .map_err(|e| anyhow!("Invalid public key in {:?}: {e}", path))?;
+                Ok(((&pk).into(), pk))
+            })
+            .collect::<Result<_, anyhow::Error>>()?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            contents,
+            public_keys,
+            unlocked: OnceLock::new(),
+        })
+    }
+
+    /// Create an encrypted keystore at `path` holding `keys`, encrypted under `passphrase`,
+    /// replacing any keystore already at that path. The new keystore starts unlocked.
+    pub fn create<'k>(
+        path: &Path,
+        passphrase: &str,
+        keys: impl IntoIterator<Item = &'k SuiKeyPair>,
+    ) -> Result<Self, anyhow::Error> {
+        let kdf = KdfParams::new();
+        let key = kdf.derive_key(passphrase)?;
+
+        let mut contents = KeystoreFile {
+            version: FORMAT_VERSION,
+            check: Ciphertext::encrypt(&key, &[], CHECK_AAD)?,
+            kdf,
+            keys: vec![],
+        };
+
+        let mut public_keys = BTreeMap::new();
+        let mut decrypted = BTreeMap::new();
+        for keypair in keys {
+            let encrypted = EncryptedKey::new(&key, keypair)?;
+            let keypair = encrypted.decrypt(&key)?;
+            let public_key = keypair.public();
+            let address: SuiAddress = (&public_key).into();
+            contents.keys.push(encrypted);
+            public_keys.insert(address, public_key);
+            decrypted.insert(address, keypair);
+        }
+
+        let keystore = Self {
+            path: path.to_path_buf(),
+            contents,
+            public_keys,
+            unlocked: OnceLock::from(Unlocked {
+                key,
+                keys: decrypted,
+            }),
+        };
+
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Whether the file at `path` holds an encrypted keystore (as opposed to a plaintext one, or
+    /// nothing at all).
+    pub fn is_encrypted(path: &Path) -> bool {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+            .is_some_and(|value| value.get("kdf").is_some())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.get().is_none()
+    }
+
+    /// Unlock the keystore with `passphrase`, decrypting its private keys into memory.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), anyhow::Error> {
+        if self.unlocked.get().is_some() {
+            return Ok(());
+        }
+
+        let key = self.contents.kdf.derive_key(passphrase)?;
+        self.unlock_with_key(key)
+    }
+
+    /// Forget the decrypted private keys, and end any session started by
+    /// [EncryptedFileKeystore::start_session].
+    pub fn lock(&mut self) -> Result<(), anyhow::Error> {
+        self.unlocked.take();
+        match fs::remove_file(self.session_path()) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(anyhow!("Failed to end keystore session: {e}")),
+        }
+    }
+
+    /// Allow other processes to unlock this keystore without its passphrase, for `duration`, by
+    /// presenting the returned session token (see [SUI_KEYSTORE_SESSION_ENV]). Only the keystore's
+    /// decryption key, encrypted under the token, is written next to the keystore, until the
+    /// session expires, or the keystore is locked. The token itself is not stored anywhere.
+    pub fn start_session(&self, duration: Duration) -> Result<String, anyhow::Error> {
+        let unlocked = self.unlocked()?;
+        let expires_at = SystemTime::now() + duration;
+        let expires_at_ms = expires_at.duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        let salt = self.contents.kdf.salt.clone();
+
+        let token = Zeroizing::new(rand::random::<[u8; KEY_LENGTH]>());
+        let session = Session {
+            key: Ciphertext::encrypt(
+                &token,
+                unlocked.key.as_slice(),
+                &session_aad(&salt, expires_at_ms),
+            )?,
+            salt,
+            expires_at_ms,
+        };
+
+        write_private(&self.session_path(), &serde_json::to_vec(&session)?)?;
+        Ok(Base64::encode(token.as_slice()))
+    }
+
+    /// Unlock the keystore with the token of a session started by
+    /// [EncryptedFileKeystore::start_session], if that session is still active.
+    pub fn unlock_with_session(&self, token: &str) -> Result<(), anyhow::Error> {
+        if self.unlocked.get().is_some() {
+            return Ok(());
+        }
+
+        let key = self
+            .session_key(token)?
+            .ok_or_else(|| anyhow!("No active session for keystore {:?}", self.path))?;
+        self.unlock_with_key(key)
+    }
+
+    /// Re-encrypt all the keys in the keystore under a key derived from `new_passphrase` with a
+    /// fresh salt. Any existing session is ended, because its key no longer decrypts the keystore.
+    pub fn change_passphrase(
+        &mut self,
+        old_passphrase: &str,
+        new_passphrase: &str,
+    ) -> Result<(), anyhow::Error> {
+        // Always check the old passphrase, even if the keystore is already unlocked.
+        let old_key = self.contents.kdf.derive_key(old_passphrase)?;
+        self.contents.check.decrypt(&old_key, CHECK_AAD)?;
+        self.lock()?;
+        self.unlock_with_key(old_key)?;
+
+        let kdf = KdfParams::new();
+        let key = kdf.derive_key(new_passphrase)?;
+        let unlocked = self
+            .unlocked
+            .get_mut()
+            .expect("Keystore was unlocked above");
+
+        self.contents.check = Ciphertext::encrypt(&key, &[], CHECK_AAD)?;
+        self.contents.kdf = kdf;
+        self.contents.keys = unlocked
+            .keys
+            .values()
+            .map(|keypair| EncryptedKey::new(&key, keypair))
+            .collect::<Result<_, _>>()?;
+        unlocked.key = key;
+
+        self.save()
+    }
+
+    pub fn key_pairs(&self) -> Result<Vec<&SuiKeyPair>, anyhow::Error> {
+        Ok(self.unlocked()?.keys.values().collect())
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        write_private(&self.path, &serde_json::to_vec_pretty(&self.contents)?)
+    }
+
+    fn signing_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, signature::Error> {
+        self.unlocked()
+            .map_err(|e| signature::Error::from_source(e.to_string()))?
+            .keys
+            .get(address)
+            .ok_or_else(|| {
+                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+            })
+    }
+
+    /// The unlocked keystore, unlocking it first if necessary, using a session if one is active,
+    /// or the passphrase otherwise.
+    fn unlocked(&self) -> Result<&Unlocked, anyhow::Error> {
+        if let Some(unlocked) = self.unlocked.get() {
+            return Ok(unlocked);
+        }
+
+        let session_key = match std::env::var(SUI_KEYSTORE_SESSION_ENV) {
+            Ok(token) => self.session_key(&token).ok().flatten(),
+            Err(_) => None,
+        };
+
+        match session_key {
+            Some(key) => self.unlock_with_key(key)?,
+            None => self.unlock(&read_passphrase(&format!(
+                "Enter passphrase for keystore {:?}: ",
+                self.path
+            ))?)?,
+        }
+
+        Ok(self.unlocked.get().expect("Keystore was unlocked above"))
+    }
+
+    fn unlock_with_key(&self, key: Zeroizing<[u8; KEY_LENGTH]>) -> Result<(), anyhow::Error> {
+        self.contents.check.decrypt(&key, CHECK_AAD)?;
+
+        let mut keys = BTreeMap::new();
+        for encrypted in &self.contents.keys {
+            let keypair = encrypted.decrypt(&key)?;
+            keys.insert((&keypair.public()).into(), keypair);
+        }
+
+        // Another thread may have unlocked the keystore concurrently, with the same result.
+        let _ = self.unlocked.set(Unlocked { key, keys });
+        Ok(())
+    }
+
+    fn session_path(&self) -> PathBuf {
+        let mut path = OsString::from(self.path.as_os_str());
+        path.push(".session");
+        PathBuf::from(path)
+    }
+
+    /// The key from an unexpired session for this keystore, decrypted with the session's `token`,
+    /// if there is such a session. Fails if the token does not belong to the session.
+    fn session_key(
+        &self,
+        token: &str,
+    ) -> Result<Option<Zeroizing<[u8; KEY_LENGTH]>>, anyhow::Error> {
+        let bytes = match fs::read(self.session_path()) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => bail!("Failed to read keystore session: {e}"),
+        };
+        let session: Session =
+            serde_json::from_slice(&bytes).map_err(|e| anyhow!("Invalid keystore session: {e}"))?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        if session.expires_at_ms <= now || session.salt != self.contents.kdf.salt {
+            return Ok(None);
+        }
+
+        let token = Zeroizing::new(
+            Base64::decode(token).map_err(|e| anyhow!("Invalid session token: {e}"))?,
+        );
+        ensure!(token.len() == KEY_LENGTH, "Invalid session token length");
+        let mut token_key = Zeroizing::new([0u8; KEY_LENGTH]);
+        token_key.copy_from_slice(&token);
+
+        let bytes = session
+            .key
+            .decrypt(
+                &token_key,
+                &session_aad(&session.salt, session.expires_at_ms),
+            )
+            .map_err(|_| anyhow!("Session token does not match keystore session"))?;
+        ensure!(bytes.len() == KEY_LENGTH, "Invalid keystore session");
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        key.copy_from_slice(&bytes);
+        Ok(Some(key))
+    }
+}
+
+/// Associated data binding a session's encrypted key to the keystore it unlocks and its expiry, so
+/// that neither can be changed without the session token.
+fn session_aad(salt: &str, expires_at_ms: u64) -> Vec<u8> {
+    format!("sui-keystore-session:{salt}:{expires_at_ms}").into_bytes()
+}
+
+impl KdfParams {
+    /// Parameters for deriving a new key, with a fresh salt.
+    fn new() -> Self {
+        Self {
+            algorithm: KDF_ALGORITHM.to_string(),
+            salt: Base64::encode(rand::random::<[u8; SALT_LENGTH]>()),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, anyhow::Error> {
+        ensure!(
+            self.algorithm == KDF_ALGORITHM,
+            "Unsupported key derivation function: {}",
+            self.algorithm
+        );
+
+        let salt = Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid salt: {e}"))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LENGTH))
+            .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| anyhow!("Failed to derive key from passphrase: {e}"))?;
+        Ok(key)
+    }
+}
+
+impl Ciphertext {
+    fn encrypt(key: &[u8; KEY_LENGTH], msg: &[u8], aad: &[u8]) -> Result<Self, anyhow::Error> {
+        let nonce = rand::random::<[u8; NONCE_LENGTH]>();
+        let ciphertext = Aes256Gcm::new(key.into())
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg, aad })
+            .map_err(|_| anyhow!("Failed to encrypt keystore"))?;
+
+        Ok(Self {
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8; KEY_LENGTH],
+        aad: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, anyhow::Error> {
+        let nonce = Base64::decode(&self.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        ensure!(nonce.len() == NONCE_LENGTH, "Invalid nonce length");
+        let msg =
+            Base64::decode(&self.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+
+        let plaintext = Aes256Gcm::new(key.into())
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &msg, aad })
+            .map_err(|_| anyhow!("Incorrect passphrase, or corrupted keystore"))?;
+        Ok(Zeroizing::new(plaintext))
+    }
+}
+
+impl EncryptedKey {
+    fn new(key: &[u8; KEY_LENGTH], keypair: &SuiKeyPair) -> Result<Self, anyhow::Error> {
+        let public_key = keypair.public().encode_base64();
+        let private_key = Zeroizing::new(keypair.encode_base64());
+        Ok(Self {
+            private_key: Ciphertext::encrypt(key, private_key.as_bytes(), public_key.as_bytes())?,
+            public_key,
+        })
+    }
+
+    fn decrypt(&self, key: &[u8; KEY_LENGTH]) -> Result<SuiKeyPair, anyhow::Error> {
+        let private_key = self.private_key.decrypt(key, self.public_key.as_bytes())?;
+        let private_key = std::str::from_utf8(&private_key)?;
+        SuiKeyPair::decode_base64(private_key).map_err(|e| anyhow!("Invalid keypair: {e}"))
+    }
+}
+
+/// Read the passphrase of an encrypted keystore from [SUI_KEYSTORE_PASSPHRASE_ENV], the file named
+/// by [SUI_KEYSTORE_PASSPHRASE_FILE_ENV], or by prompting for it on the terminal, in that order.
+pub fn read_passphrase(prompt: &str) -> Result<String, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    if let Ok(path) = std::env::var(SUI_KEYSTORE_PASSPHRASE_FILE_ENV) {
+        return read_passphrase_file(Path::new(&path));
+    }
+
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "Keystore is locked. Provide its passphrase in {SUI_KEYSTORE_PASSPHRASE_ENV} or \
+             {SUI_KEYSTORE_PASSPHRASE_FILE_ENV}, or unlock it with `sui keytool unlock`"
+        );
+    }
+
+    Ok(rpassword::prompt_password(prompt)?)
+}
+
+/// Read a new passphrase from `file` if one is given, or otherwise by prompting for it (twice) on
+/// the terminal.
+pub fn read_new_passphrase(file: Option<&Path>) -> Result<String, anyhow::Error> {
+    let passphrase = match file {
+        Some(file) => read_passphrase_file(file)?,
+        None => {
+            ensure!(
+                std::io::stdin().is_terminal(),
+                "Cannot prompt for a new passphrase, provide it in a file instead"
+            );
+            let passphrase = rpassword::prompt_password("Enter new passphrase: ")?;
+            let confirmation = rpassword::prompt_password("Confirm new passphrase: ")?;
+            ensure!(passphrase == confirmation, "Passphrases do not match");
+            passphrase
+        }
+    };
+
+    ensure!(!passphrase.is_empty(), "Passphrase must not be empty");
+    Ok(passphrase)
+}
+
+fn read_passphrase_file(path: &Path) -> Result<String, anyhow::Error> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Can't read passphrase from {:?}: {e}", path))?;
+    Ok(contents.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Atomically replace the contents of the file at `path` with `bytes`, making it readable only by
+/// its owner. The file is created with those permissions, so its contents are never readable by
+/// anyone else, even briefly.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), anyhow::Error> {
+    let mut tmp = OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    // Permissions only apply to newly created files, so clear out any left-over temporary file.
+    match fs::remove_file(&tmp) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
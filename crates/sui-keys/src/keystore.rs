@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::encrypted_keystore::EncryptedFileKeystore;
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use anyhow::anyhow;
 use bip32::DerivationPath;
@@ -26,6 +27,19 @@ use sui_types::crypto::{
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileKeystore),
+}
+
+impl Keystore {
+    /// Open the keystore at `path`, as an encrypted keystore if the file holds one, or as a
+    /// plaintext one otherwise.
+    pub fn from_path(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        Ok(if EncryptedFileKeystore::is_encrypted(path) {
+            Keystore::Encrypted(EncryptedFileKeystore::new(path)?)
+        } else {
+            Keystore::File(FileBasedKeystore::new(path)?)
+        })
+    }
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -91,6 +105,11 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                write!(writer, "Keystore Path : {:?}", file.path())?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -175,6 +194,12 @@ impl AccountKeystore for FileBasedKeystore {
 impl FileBasedKeystore {
     pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
         let keys = if path.exists() {
+            if EncryptedFileKeystore::is_encrypted(path) {
+                return Err(anyhow!(
+                    "Keystore at {:?} is encrypted, open it as an EncryptedFileKeystore",
+                    path
+                ));
+            }
             let reader = BufReader::new(
                 File::open(path)
                     .map_err(|e| anyhow!("Can't open FileBasedKeystore from {:?}: {e}", path))?,
@@ -199,6 +224,10 @@ impl FileBasedKeystore {
         })
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn set_path(&mut self, path: &Path) {
         self.path = Some(path.to_path_buf());
     }
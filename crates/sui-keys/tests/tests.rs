@@ -1,12 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use std::str::FromStr;
+use std::time::Duration;
 
 use fastcrypto::hash::HashFunction;
+use shared_crypto::intent::Intent;
 use tempfile::TempDir;
 
+use sui_keys::encrypted_keystore::EncryptedFileKeystore;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, SignatureScheme, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +72,143 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn encrypted_keystore_round_trip_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut plaintext = FileBasedKeystore::new(&keystore_path)?;
+    let (address, _, _) =
+        plaintext.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    plaintext.generate_and_add_new_key(SignatureScheme::Secp256k1, None, None)?;
+
+    // Migrate the plaintext keystore in place.
+    EncryptedFileKeystore::create(&keystore_path, "hunter2", plaintext.key_pairs())?;
+    assert!(EncryptedFileKeystore::is_encrypted(&keystore_path));
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+
+    // Public keys are available without the passphrase.
+    let keystore = Keystore::from_path(&keystore_path)?;
+    assert!(keystore.to_string().contains("Encrypted File"));
+    assert_eq!(keystore.keys(), plaintext.keys());
+
+    let Keystore::Encrypted(encrypted) = keystore else {
+        panic!("Expected an encrypted keystore");
+    };
+    assert!(encrypted.is_locked());
+    assert!(encrypted.unlock("hunter3").is_err());
+    assert!(encrypted.is_locked());
+
+    encrypted.unlock("hunter2")?;
+    assert!(!encrypted.is_locked());
+    assert_eq!(
+        encrypted.get_key(&address)?.encode_base64(),
+        plaintext.get_key(&address)?.encode_base64()
+    );
+
+    let msg = b"hello";
+    assert_eq!(
+        encrypted.sign_secure(&address, msg, Intent::sui_transaction())?,
+        plaintext.sign_secure(&address, msg, Intent::sui_transaction())?,
+    );
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_change_passphrase_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = EncryptedFileKeystore::create(&keystore_path, "old", [])?;
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+
+    assert!(keystore.change_passphrase("wrong", "new").is_err());
+    keystore.change_passphrase("old", "new")?;
+
+    let reopened = EncryptedFileKeystore::new(&keystore_path)?;
+    assert_eq!(reopened.addresses(), vec![address]);
+    assert!(reopened.unlock("old").is_err());
+    reopened.unlock("new")?;
+    assert_eq!(
+        reopened.get_key(&address)?.encode_base64(),
+        keystore.get_key(&address)?.encode_base64()
+    );
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_session_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = EncryptedFileKeystore::create(&keystore_path, "hunter2", [])?;
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    let token = keystore.start_session(Duration::from_secs(60))?;
+
+    // The decryption key is not written to disk in the clear.
+    let mut session_path = keystore_path.clone().into_os_string();
+    session_path.push(".session");
+    let session = std::fs::read_to_string(&session_path)?;
+    let key = keystore.get_key(&address)?.encode_base64();
+    assert!(!session.contains(&token));
+    assert!(!session.contains(&key));
+
+    // Another instance cannot unlock through the session without its token.
+    let other = EncryptedFileKeystore::new(&keystore_path)?;
+    let unrelated = EncryptedFileKeystore::create(&temp_dir.path().join("other"), "x", [])?;
+    let wrong_token = unrelated.start_session(Duration::from_secs(60))?;
+    assert!(other.unlock_with_session(&wrong_token).is_err());
+    assert!(other.is_locked());
+
+    // ...but unlocks with it, without the passphrase.
+    other.unlock_with_session(&token)?;
+    assert!(!other.is_locked());
+    assert_eq!(other.get_key(&address)?.encode_base64(), key);
+
+    // Changing the passphrase ends the session.
+    keystore.change_passphrase("hunter2", "hunter3")?;
+    let other = EncryptedFileKeystore::new(&keystore_path)?;
+    assert!(other.unlock_with_session(&token).is_err());
+    assert!(other.unlock("hunter2").is_err());
+    other.unlock("hunter3")?;
+
+    // Locking removes the session.
+    let token = keystore.start_session(Duration::from_secs(60))?;
+    keystore.lock()?;
+    assert!(keystore.is_locked());
+    let other = EncryptedFileKeystore::new(&keystore_path)?;
+    assert!(other.unlock_with_session(&token).is_err());
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_expired_session_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let keystore = EncryptedFileKeystore::create(&keystore_path, "hunter2", [])?;
+    let token = keystore.start_session(Duration::ZERO)?;
+
+    let other = EncryptedFileKeystore::new(&keystore_path)?;
+    assert!(other.unlock_with_session(&token).is_err());
+    assert!(other.is_locked());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn encrypted_keystore_permissions_test() -> Result<(), anyhow::Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let keystore = EncryptedFileKeystore::create(&keystore_path, "hunter2", [])?;
+    keystore.start_session(Duration::from_secs(60))?;
+
+    let mut session_path = keystore_path.clone().into_os_string();
+    session_path.push(".session");
+    for path in [keystore_path.into_os_string(), session_path] {
+        let mode = std::fs::metadata(&path)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600, "{:?} has mode {:o}", path, mode);
+    }
+    Ok(())
+}
@@ -11,7 +11,12 @@ use axum::{
 use humantime::parse_duration;
 use serde::Deserialize;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
+use sui_config::transaction_deny_config::DenyTarget;
+use sui_core::authority::AuthorityState;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::error::SuiError;
 use telemetry_subscribers::TracingHandle;
 use tracing::info;
@@ -39,6 +44,26 @@ use tracing::info;
 //
 //   $ curl 'http://127.0.0.1:1337/node-config'
 //
+// Re-read the node config file and apply the changes to the fields that can be changed at
+// runtime (transaction-deny-config, certificate-deny-config, overload-threshold-config,
+// authority-store-pruning-config, indexer-max-subscriptions). The node also does this on SIGHUP.
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/reload-config'
+//
+// Deny transactions depending on a package, calling a Move function, sent or sponsored by an
+// address, or using an object, on top of the transaction-deny-config. Rules are persisted in the
+// db directory, and optionally expire after the given duration:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/add-deny-rule?package=0x1234&duration=1h&reason=incident-42'
+//   $ curl -X POST 'http://127.0.0.1:1337/add-deny-rule?function=0x1234::pool::swap'
+//   $ curl -X POST 'http://127.0.0.1:1337/add-deny-rule?address=0x5678'
+//   $ curl -X POST 'http://127.0.0.1:1337/add-deny-rule?object=0x9abc'
+//
+// View and remove the deny rules:
+//
+//   $ curl 'http://127.0.0.1:1337/deny-rules'
+//   $ curl -X POST 'http://127.0.0.1:1337/remove-deny-rule?id=0'
+//
 // Set a time-limited tracing config. After the duration expires, tracing will be disabled
 // automatically.
 //
@@ -56,24 +81,36 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const RELOAD_CONFIG: &str = "/reload-config";
+const DENY_RULES: &str = "/deny-rules";
+const ADD_DENY_RULE: &str = "/add-deny-rule";
+const REMOVE_DENY_RULE: &str = "/remove-deny-rule";
 
 struct AppState {
     node: Arc<SuiNode>,
     tracing_handle: TracingHandle,
+    config_path: PathBuf,
 }
 
-pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: TracingHandle) {
+pub async fn run_admin_server(
+    node: Arc<SuiNode>,
+    port: u16,
+    tracing_handle: TracingHandle,
+    config_path: PathBuf,
+) {
     let filter = tracing_handle.get_log().unwrap();
 
     let app_state = AppState {
         node,
         tracing_handle,
+        config_path,
     };
 
     let app = Router::new()
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(DENY_RULES, get(deny_rules))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -86,6 +123,9 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
+        .route(RELOAD_CONFIG, post(reload_config))
+        .route(ADD_DENY_RULE, post(add_deny_rule))
+        .route(REMOVE_DENY_RULE, post(remove_deny_rule))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -210,12 +250,137 @@ async fn capabilities(State(state): State<Arc<AppState>>) -> (StatusCode, String
 }
 
 async fn node_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
-    let node_config = &state.node.config;
+    let node_config = state.node.live_config().await;
 
     // Note private keys will be masked
     (StatusCode::OK, format!("{:#?}\n", node_config))
 }
 
+async fn reload_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match state.node.reload_config(&state.config_path).await {
+        Ok(changed) if changed.is_empty() => (StatusCode::OK, "config unchanged\n".to_string()),
+        Ok(changed) => (
+            StatusCode::OK,
+            format!("config reloaded, changed: {}\n", changed.join(", ")),
+        ),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{:#}\n", err)),
+    }
+}
+
+async fn deny_rules(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let deny_list = state.node.state().dynamic_deny_list();
+
+    let mut output = String::new();
+    for rule in deny_list.list().iter() {
+        output.push_str(&format!(
+            "{:?} rejected={}\n",
+            rule,
+            deny_list.num_rejected_transactions(rule.id)
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct AddDenyRule {
+    // Exactly one of the targets must be set.
+    package: Option<String>,
+    // In the form `<package>::<module>::<function>`.
+    function: Option<String>,
+    address: Option<String>,
+    object: Option<String>,
+
+    // The rule is removed after the duration expires, if set.
+    duration: Option<String>,
+    reason: Option<String>,
+}
+
+fn parse_deny_target(query: &AddDenyRule) -> Result<DenyTarget, String> {
+    let target = match (
+        &query.package,
+        &query.function,
+        &query.address,
+        &query.object,
+    ) {
+        (Some(package), None, None, None) => DenyTarget::Package(
+            ObjectID::from_str(package).map_err(|e| format!("invalid package: {e}"))?,
+        ),
+        (None, Some(function), None, None) => {
+            let [package, module, function] = function.split("::").collect::<Vec<_>>()[..] else {
+                return Err("invalid function: expected <package>::<module>::<function>".into());
+            };
+            DenyTarget::MoveFunction {
+                package: ObjectID::from_str(package)
+                    .map_err(|e| format!("invalid function package: {e}"))?,
+                module: module.to_owned(),
+                function: function.to_owned(),
+            }
+        }
+        (None, None, Some(address), None) => DenyTarget::Address(
+            SuiAddress::from_str(address).map_err(|e| format!("invalid address: {e}"))?,
+        ),
+        (None, None, None, Some(object)) => DenyTarget::Object(
+            ObjectID::from_str(object).map_err(|e| format!("invalid object: {e}"))?,
+        ),
+        _ => return Err("exactly one of package, function, address or object is required".into()),
+    };
+    Ok(target)
+}
+
+async fn add_deny_rule(
+    State(state): State<Arc<AppState>>,
+    query: Query<AddDenyRule>,
+) -> (StatusCode, String) {
+    let Query(query) = query;
+
+    let target = match parse_deny_target(&query) {
+        Ok(target) => target,
+        Err(err) => return (StatusCode::BAD_REQUEST, format!("{}\n", err)),
+    };
+    let expires_at_ms = match query.duration.as_deref().map(parse_duration) {
+        None => None,
+        Some(Ok(duration)) => {
+            let expires_at_ms = u64::try_from(duration.as_millis())
+                .ok()
+                .and_then(|duration_ms| AuthorityState::unixtime_now_ms().checked_add(duration_ms));
+            match expires_at_ms {
+                Some(expires_at_ms) => Some(expires_at_ms),
+                None => return (StatusCode::BAD_REQUEST, "duration too long\n".into()),
+            }
+        }
+        Some(Err(_)) => return (StatusCode::BAD_REQUEST, "invalid duration\n".into()),
+    };
+
+    match state
+        .node
+        .state()
+        .dynamic_deny_list()
+        .add(target, expires_at_ms, query.reason)
+    {
+        Ok(rule) => (StatusCode::OK, format!("added deny rule {:?}\n", rule)),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}\n", err)),
+    }
+}
+
+#[derive(Deserialize)]
+struct RuleId {
+    id: u64,
+}
+
+async fn remove_deny_rule(
+    State(state): State<Arc<AppState>>,
+    id: Query<RuleId>,
+) -> (StatusCode, String) {
+    let Query(RuleId { id }) = id;
+
+    match state.node.state().dynamic_deny_list().remove(id) {
+        Ok(Some(rule)) => (StatusCode::OK, format!("removed deny rule {:?}\n", rule)),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("no deny rule {}\n", id)),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}\n", err)),
+    }
+}
+
 #[derive(Deserialize)]
 struct Epoch {
     epoch: u64,
@@ -0,0 +1,183 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for reloading parts of the [NodeConfig] of a running node.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_yaml::Value;
+use std::path::Path;
+use sui_config::{Config, NodeConfig};
+
+/// Top level fields of the [NodeConfig] that can be changed without restarting the node.
+const RELOADABLE_FIELDS: &[&str] = &[
+    "transaction-deny-config",
+    "certificate-deny-config",
+    "overload-threshold-config",
+    "authority-store-pruning-config",
+    "indexer-max-subscriptions",
+];
+
+/// Fields nested in [RELOADABLE_FIELDS] that are only read at startup.
+const STARTUP_ONLY_FIELDS: &[&str] = &[
+    "authority-store-pruning-config.num-latest-epoch-dbs-to-retain",
+    "authority-store-pruning-config.epoch-db-pruning-period-secs",
+    "authority-store-pruning-config.periodic-compaction-threshold-days",
+];
+
+/// Load the config at `path` to replace `current`. Values that are never read from the config
+/// file, or that can be overridden from the command line, are carried over from `current`.
+pub fn load_config(path: &Path, current: &NodeConfig) -> Result<NodeConfig> {
+    let mut config = NodeConfig::load(path)?;
+    config.supported_protocol_versions = current.supported_protocol_versions;
+    config.network_address = current.network_address.clone();
+    Ok(config)
+}
+
+/// The dotted paths of the fields that differ between `old` and `new`, in the order they are
+/// serialized.
+pub fn changed_fields<T: Serialize>(old: &T, new: &T) -> Result<Vec<String>> {
+    let mut changed = vec![];
+    diff_values(
+        "",
+        &serde_yaml::to_value(old)?,
+        &serde_yaml::to_value(new)?,
+        &mut changed,
+    );
+    Ok(changed)
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, changed: &mut Vec<String>) {
+    let (Value::Mapping(old), Value::Mapping(new)) = (old, new) else {
+        if old != new {
+            changed.push(path.to_owned());
+        }
+        return;
+    };
+    let missing = Value::Null;
+    let keys = old.iter().map(|(key, _)| key).chain(
+        new.iter()
+            .map(|(key, _)| key)
+            .filter(|key| !old.contains_key(key)),
+    );
+    for key in keys {
+        let name = match key {
+            Value::String(name) => name.clone(),
+            key => serde_yaml::to_string(key)
+                .map(|name| name.trim_start_matches("---").trim().to_owned())
+                .unwrap_or_default(),
+        };
+        let path = if path.is_empty() {
+            name
+        } else {
+            format!("{path}.{name}")
+        };
+        diff_values(
+            &path,
+            old.get(key).unwrap_or(&missing),
+            new.get(key).unwrap_or(&missing),
+            changed,
+        );
+    }
+}
+
+fn is_reloadable(field: &str) -> bool {
+    let under = |parent: &str| {
+        field == parent
+            || field
+                .strip_prefix(parent)
+                .is_some_and(|rest| rest.starts_with('.'))
+    };
+    RELOADABLE_FIELDS.iter().any(|parent| under(parent))
+        && !STARTUP_ONLY_FIELDS.iter().any(|parent| under(parent))
+}
+
+/// Fail if any of the changed fields can't be applied without restarting the node.
+pub fn check_reloadable(changed: &[String]) -> Result<()> {
+    let immutable: Vec<_> = changed
+        .iter()
+        .filter(|field| !is_reloadable(field))
+        .map(String::as_str)
+        .collect();
+    if immutable.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "changes to {} require a restart, no change was applied",
+            immutable.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(s: &str) -> Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = yaml(
+            r#"
+db-path: /opt/sui/db
+authority-store-pruning-config:
+  num-epochs-to-retain: 2
+  max-checkpoints-in-batch: 10
+transaction-deny-config:
+  package-publish-disabled: false
+"#,
+        );
+        let new = yaml(
+            r#"
+db-path: /opt/sui/db
+authority-store-pruning-config:
+  num-epochs-to-retain: 1
+  max-checkpoints-in-batch: 10
+transaction-deny-config:
+  package-publish-disabled: true
+  object-deny-list: [0x1]
+indexer-max-subscriptions: 10
+"#,
+        );
+        let changed = changed_fields(&old, &new).unwrap();
+        assert_eq!(
+            changed,
+            [
+                "authority-store-pruning-config.num-epochs-to-retain",
+                "transaction-deny-config.package-publish-disabled",
+                "transaction-deny-config.object-deny-list",
+                "indexer-max-subscriptions",
+            ]
+        );
+        check_reloadable(&changed).unwrap();
+        assert!(changed_fields(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_reloadable() {
+        let changed = |fields: &[&str]| fields.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        check_reloadable(&changed(&[
+            "certificate-deny-config",
+            "overload-threshold-config.max_txn_age_in_queue.secs",
+        ]))
+        .unwrap();
+
+        let err = check_reloadable(&changed(&[
+            "transaction-deny-config.shared-object-disabled",
+            "db-path",
+            "protocol-key-pair.path",
+            "authority-store-pruning-config.num-latest-epoch-dbs-to-retain",
+            "transaction-deny-config-extra",
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "changes to db-path, protocol-key-pair.path, \
+             authority-store-pruning-config.num-latest-epoch-dbs-to-retain, \
+             transaction-deny-config-extra require a restart, no change was applied"
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 #[cfg(msim)]
 use std::sync::atomic::Ordering;
@@ -71,6 +71,7 @@ use sui_core::consensus_throughput_calculator::{
 };
 use sui_core::consensus_validator::{SuiTxValidator, SuiTxValidatorMetrics};
 use sui_core::db_checkpoint_handler::DBCheckpointHandler;
+use sui_core::dynamic_deny_list::DynamicDenyList;
 use sui_core::epoch::committee_store::CommitteeStore;
 use sui_core::epoch::data_removal::EpochDataRemover;
 use sui_core::epoch::epoch_metrics::EpochMetrics;
@@ -86,7 +87,7 @@ use sui_core::{
 };
 use sui_json_rpc::coin_api::CoinReadApi;
 use sui_json_rpc::governance_api::GovernanceReadApi;
-use sui_json_rpc::indexer_api::IndexerApi;
+use sui_json_rpc::indexer_api::{IndexerApi, SubscriptionLimit};
 use sui_json_rpc::move_utils::MoveUtils;
 use sui_json_rpc::read_api::ReadApi;
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi;
@@ -124,6 +125,7 @@ use typed_store::DBMetrics;
 use crate::metrics::{GrpcMetrics, SuiNodeMetrics};
 
 pub mod admin;
+pub mod config_reload;
 mod handle;
 pub mod metrics;
 
@@ -200,6 +202,10 @@ use sui_core::consensus_handler::ConsensusHandlerInitializer;
 
 pub struct SuiNode {
     config: NodeConfig,
+    /// The config as of the last reload, only its reloadable fields may differ from `config`.
+    live_config: Mutex<NodeConfig>,
+    node_config_metrics: Arc<NodeConfigMetrics>,
+    subscription_limit: SubscriptionLimit,
     validator_components: Mutex<Option<ValidatorComponents>>,
     /// The http server responsible for serving JSON-RPC as well as the experimental rest service
     _http_server: Option<tokio::task::JoinHandle<()>>,
@@ -390,7 +396,8 @@ impl SuiNode {
         registry_service: RegistryService,
         custom_rpc_runtime: Option<Handle>,
     ) -> Result<Arc<SuiNode>> {
-        NodeConfigMetrics::new(&registry_service.default_registry()).record_metrics(config);
+        let node_config_metrics = NodeConfigMetrics::new(&registry_service.default_registry());
+        node_config_metrics.record_metrics(config);
         let mut config = config.clone();
         if config.supported_protocol_versions.is_none() {
             info!(
@@ -572,12 +579,16 @@ impl SuiNode {
             index_store.clone(),
             checkpoint_store.clone(),
             &prometheus_registry,
-            config.authority_store_pruning_config,
+            config.authority_store_pruning_config.clone(),
             genesis.objects(),
             &db_checkpoint_config,
             config.expensive_safety_check_config.clone(),
             config.transaction_deny_config.clone(),
             config.certificate_deny_config.clone(),
+            DynamicDenyList::load(
+                Some(config.db_path().join("dynamic_deny_list.json")),
+                &prometheus_registry,
+            )?,
             config.indirect_objects_threshold,
             config.state_debug_dump_config.clone(),
             config.overload_threshold_config.clone(),
@@ -628,12 +639,14 @@ impl SuiNode {
             None
         };
 
+        let subscription_limit = SubscriptionLimit::new(config.indexer_max_subscriptions);
         let http_server = build_http_server(
             state.clone(),
             &transaction_orchestrator.clone(),
             &config,
             &prometheus_registry,
             custom_rpc_runtime,
+            subscription_limit.clone(),
         )?;
 
         let accumulator = Arc::new(StateAccumulator::new(store));
@@ -686,7 +699,10 @@ impl SuiNode {
         };
 
         let node = Self {
+            live_config: Mutex::new(config.clone()),
             config,
+            node_config_metrics,
+            subscription_limit,
             validator_components: Mutex::new(validator_components),
             _http_server: http_server,
             state,
@@ -728,10 +744,47 @@ impl SuiNode {
         self.state.current_epoch_for_testing()
     }
 
+    pub fn max_subscriptions_for_testing(&self) -> usize {
+        self.subscription_limit.max_subscriptions()
+    }
+
     pub fn db_checkpoint_path(&self) -> PathBuf {
         self.config.db_checkpoint_path()
     }
 
+    /// The node config, including the changes applied by `reload_config`.
+    pub async fn live_config(&self) -> NodeConfig {
+        self.live_config.lock().await.clone()
+    }
+
+    /// Re-read the config file at `path` and apply the changes to its reloadable fields.
+    /// Nothing is applied if any other field changed. Returns the changed fields.
+    pub async fn reload_config(&self, path: &Path) -> Result<Vec<String>> {
+        let mut live_config = self.live_config.lock().await;
+        let new_config = config_reload::load_config(path, &live_config)?;
+        let changed = config_reload::changed_fields(&*live_config, &new_config)?;
+        config_reload::check_reloadable(&changed)?;
+        if changed.is_empty() {
+            return Ok(changed);
+        }
+
+        self.state
+            .update_transaction_deny_config(new_config.transaction_deny_config.clone());
+        self.state
+            .update_certificate_deny_config(new_config.certificate_deny_config.clone());
+        self.state
+            .update_overload_threshold_config(new_config.overload_threshold_config.clone());
+        self.state
+            .update_pruning_config(new_config.authority_store_pruning_config.clone());
+        self.subscription_limit
+            .set_max_subscriptions(new_config.indexer_max_subscriptions);
+        self.node_config_metrics.record_metrics(&new_config);
+
+        info!(?changed, "Reloaded node config from {}", path.display());
+        *live_config = new_config;
+        Ok(changed)
+    }
+
     // Init reconfig process by starting to reject user certs
     pub async fn close_epoch(&self, epoch_store: &Arc<AuthorityPerEpochStore>) -> SuiResult {
         info!("close_epoch (current epoch = {})", epoch_store.epoch());
@@ -803,6 +856,9 @@ impl SuiNode {
                 &config.snapshot_path(),
                 remote_store_config.clone(),
                 60,
+                config
+                    .state_snapshot_write_config
+                    .full_snapshot_interval_epochs,
                 prometheus_registry,
             )?;
             Ok(Some(snapshot_uploader.start()))
@@ -842,7 +898,7 @@ impl SuiNode {
                         .prune_and_compact_before_upload
                         .unwrap_or(true),
                     config.indirect_objects_threshold,
-                    config.authority_store_pruning_config,
+                    config.authority_store_pruning_config.clone(),
                     prometheus_registry,
                     state_snapshot_enabled,
                 )?;
@@ -1646,6 +1702,7 @@ pub fn build_http_server(
     config: &NodeConfig,
     prometheus_registry: &Registry,
     _custom_runtime: Option<Handle>,
+    subscription_limit: SubscriptionLimit,
 ) -> Result<Option<tokio::task::JoinHandle<()>>> {
     // Validators do not expose these APIs
     if config.consensus_config().is_some() {
@@ -1702,7 +1759,7 @@ pub fn build_http_server(
             kv_store,
             name_service_config,
             metrics,
-            config.indexer_max_subscriptions,
+            subscription_limit,
         ))?;
         server.register_module(MoveUtils::new(state.clone()))?;
 
@@ -119,6 +119,7 @@ fn main() {
     });
 
     let node_once_cell_clone = node_once_cell.clone();
+    let config_path = args.config_path.clone();
     runtimes.metrics.spawn(async move {
         let node = node_once_cell_clone.get().await;
         let chain_identifier = match node.state().get_chain_identifier() {
@@ -134,9 +135,20 @@ fn main() {
             ))
             .unwrap();
 
-        sui_node::admin::run_admin_server(node, admin_interface_port, filter_handle).await
+        sui_node::admin::run_admin_server(node, admin_interface_port, filter_handle, config_path)
+            .await
     });
 
+    #[cfg(unix)]
+    {
+        let node_once_cell_clone = node_once_cell.clone();
+        let config_path = args.config_path.clone();
+        runtimes.metrics.spawn(async move {
+            let node = node_once_cell_clone.get().await;
+            reload_config_on_sighup(node, config_path).await
+        });
+    }
+
     runtimes.metrics.spawn(async move {
         let node = node_once_cell.get().await;
         let state = node.state();
@@ -157,6 +169,20 @@ fn main() {
     drop(runtimes);
 }
 
+#[cfg(unix)]
+// Re-read the config file on SIGHUP, like the admin interface's /reload-config
+async fn reload_config_on_sighup(node: Arc<sui_node::SuiNode>, config_path: PathBuf) {
+    use tokio::signal::unix::*;
+
+    let mut sighup = signal(SignalKind::hangup()).unwrap();
+    while sighup.recv().await.is_some() {
+        match node.reload_config(&config_path).await {
+            Ok(changed) => info!(?changed, "Reloaded config on SIGHUP"),
+            Err(e) => error!("Failed to reload config on SIGHUP: {e:#}"),
+        }
+    }
+}
+
 #[cfg(not(unix))]
 // On windows we wait for whatever "ctrl_c" means there
 async fn wait_termination() {
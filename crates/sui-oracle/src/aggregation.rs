@@ -0,0 +1,137 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::AggregationMethod;
+
+/// A value retrieved from one of a feed's data sources.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SourceValue {
+    pub value: f64,
+    pub weight: f64,
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+/// The positions of the values deviating from the median of all the values by more than
+/// `max_deviation_pct` percent.
+pub(crate) fn outliers(values: &[SourceValue], max_deviation_pct: f64) -> Vec<usize> {
+    let raw: Vec<_> = values.iter().map(|v| v.value).collect();
+    let Some(median) = median(&raw) else {
+        return vec![];
+    };
+    let max_deviation = median.abs() * max_deviation_pct / 100.0;
+    raw.iter()
+        .enumerate()
+        .filter(|(_, value)| (*value - median).abs() > max_deviation)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Combine the values of a feed's data sources into a single one.
+pub(crate) fn aggregate(values: &[SourceValue], method: &AggregationMethod) -> anyhow::Result<f64> {
+    if values.is_empty() {
+        anyhow::bail!("No values to aggregate");
+    }
+    let raw: Vec<_> = values.iter().map(|v| v.value).collect();
+    Ok(match method {
+        AggregationMethod::Median => median(&raw).unwrap(),
+        AggregationMethod::TrimmedMean { trim_fraction } => {
+            if !(0.0..0.5).contains(trim_fraction) {
+                anyhow::bail!("Trim fraction {trim_fraction} is not in [0, 0.5)");
+            }
+            let mut sorted = raw;
+            sorted.sort_by(f64::total_cmp);
+            let trimmed = (sorted.len() as f64 * trim_fraction).floor() as usize;
+            let kept = &sorted[trimmed..sorted.len() - trimmed];
+            kept.iter().sum::<f64>() / kept.len() as f64
+        }
+        AggregationMethod::Weighted => {
+            let total_weight: f64 = values.iter().map(|v| v.weight).sum();
+            if total_weight <= 0.0 {
+                anyhow::bail!("Total weight of the values is {total_weight}");
+            }
+            values.iter().map(|v| v.value * v.weight).sum::<f64>() / total_weight
+        }
+    })
+}
+
+/// Whether `value` differs from `last_value` by at least `min_change_pct` percent.
+pub(crate) fn is_significant_change(last_value: f64, value: f64, min_change_pct: f64) -> bool {
+    (value - last_value).abs() >= last_value.abs() * min_change_pct / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(values: &[f64]) -> Vec<SourceValue> {
+        values
+            .iter()
+            .map(|value| SourceValue {
+                value: *value,
+                weight: 1.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_median() {
+        let method = AggregationMethod::Median;
+        assert_eq!(aggregate(&values(&[3.0, 1.0, 2.0]), &method).unwrap(), 2.0);
+        assert_eq!(
+            aggregate(&values(&[4.0, 1.0, 2.0, 3.0]), &method).unwrap(),
+            2.5
+        );
+        assert!(aggregate(&[], &method).is_err());
+    }
+
+    #[test]
+    fn test_trimmed_mean() {
+        let method = AggregationMethod::TrimmedMean { trim_fraction: 0.2 };
+        assert_eq!(
+            aggregate(&values(&[100.0, 1.0, 2.0, 3.0, 0.0]), &method).unwrap(),
+            2.0
+        );
+        let method = AggregationMethod::TrimmedMean { trim_fraction: 0.5 };
+        assert!(aggregate(&values(&[1.0, 2.0]), &method).is_err());
+    }
+
+    #[test]
+    fn test_weighted() {
+        let values = [
+            SourceValue {
+                value: 1.0,
+                weight: 3.0,
+            },
+            SourceValue {
+                value: 5.0,
+                weight: 1.0,
+            },
+        ];
+        assert_eq!(
+            aggregate(&values, &AggregationMethod::Weighted).unwrap(),
+            2.0
+        );
+    }
+
+    #[test]
+    fn test_outliers() {
+        assert_eq!(outliers(&values(&[100.0, 101.0, 150.0, 99.0]), 10.0), [2]);
+        assert!(outliers(&values(&[100.0, 101.0]), 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_significant_change() {
+        assert!(!is_significant_change(100.0, 100.5, 1.0));
+        assert!(is_significant_change(100.0, 99.0, 1.0));
+    }
+}
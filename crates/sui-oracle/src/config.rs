@@ -13,16 +13,98 @@ use sui_types::base_types::ObjectID;
 pub struct DataSourceConfig {
     pub url: String,
     pub json_path: String,
+    /// Name of the source in metrics. Defaults to the name of the upload source, suffixed with
+    /// the position of the data source when the feed has several.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Weight of the source's values with the weighted aggregation method.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UploadFeedConfig {
     pub submission_interval: Duration,
-    pub data_source_config: DataSourceConfig,
+    /// The data source of feeds with a single one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source_config: Option<DataSourceConfig>,
+    /// The data sources of feeds aggregating several ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data_source_configs: Vec<DataSourceConfig>,
+    #[serde(default)]
+    pub aggregation: AggregationConfig,
     pub upload_parameters: UploadParameters,
 }
 
+impl UploadFeedConfig {
+    pub fn data_sources(&self) -> impl Iterator<Item = &DataSourceConfig> {
+        self.data_source_config
+            .iter()
+            .chain(self.data_source_configs.iter())
+    }
+}
+
+/// How the values of a feed's data sources are combined into the uploaded value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AggregationConfig {
+    #[serde(default)]
+    pub method: AggregationMethod,
+    /// How long the last value of a data source is still aggregated for after the source fails to
+    /// respond. Failing sources are left out right away by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_staleness: Option<Duration>,
+    /// Values deviating from the median of all the sources by more than this percentage are
+    /// rejected as outliers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_deviation_pct: Option<f64>,
+    /// The minimum number of values to aggregate, after leaving out stale values and outliers.
+    #[serde(default = "default_min_sources")]
+    pub min_sources: usize,
+    /// Uploads changing the value by less than this percentage from the last uploaded value are
+    /// skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_change_pct: Option<f64>,
+    /// The longest time between two uploads, even when the value doesn't change enough.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<Duration>,
+}
+
+fn default_min_sources() -> usize {
+    1
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            method: AggregationMethod::default(),
+            max_staleness: None,
+            max_deviation_pct: None,
+            min_sources: default_min_sources(),
+            min_change_pct: None,
+            heartbeat: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AggregationMethod {
+    #[default]
+    Median,
+    /// The mean of the values left after dropping `trim_fraction` of the lowest and of the highest
+    /// ones.
+    #[serde(rename_all = "kebab-case")]
+    TrimmedMean { trim_fraction: f64 },
+    /// The mean of the values, weighted by the weights of their sources.
+    Weighted,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UploadParameters {
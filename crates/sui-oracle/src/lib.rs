@@ -1,13 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use aggregation::SourceValue;
 use chrono::{DateTime, Utc};
-use config::{DownloadFeedConfigs, UploadFeedConfig, UploadParameters};
+use config::{DataSourceConfig, DownloadFeedConfigs, UploadFeedConfig, UploadParameters};
 use metrics::OracleMetrics;
 use mysten_metrics::monitored_scope;
 use once_cell::sync::OnceCell;
 use prometheus::Registry;
-use std::ops::Add;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -25,18 +25,18 @@ use sui_types::object::{Object, Owner};
 use sui_types::parse_sui_type_tag;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::quorum_driver_types::NON_RECOVERABLE_ERROR_MSG;
-use sui_types::transaction::{Argument, Transaction};
 use sui_types::transaction::{Command, ObjectArg};
+use sui_types::transaction::{ProgrammableTransaction, Transaction};
 use sui_types::Identifier;
 use sui_types::{
     base_types::SuiAddress,
     transaction::{CallArg, TransactionData},
 };
-use tap::tap::TapFallible;
 
 use sui_sdk::wallet_context::WalletContext;
 use sui_types::base_types::{random_object_ref, ObjectID, ObjectRef};
 use tracing::{debug, error, info, warn};
+mod aggregation;
 pub mod config;
 mod metrics;
 
@@ -117,7 +117,7 @@ impl OracleNode {
 }
 
 struct DataProviderRunner {
-    providers: Vec<Arc<DataProvider>>,
+    providers: Vec<DataProvider>,
     uploader: OnChainDataUploader,
 }
 
@@ -131,24 +131,18 @@ impl DataProviderRunner {
         metrics: Arc<OracleMetrics>,
     ) -> Self {
         let mut providers = vec![];
-        let mut staleness_tolerance = HashMap::new();
         let mut oracle_object_args = HashMap::new();
-        let (sender, receiver) = tokio::sync::mpsc::channel(10000);
+        let http_client = reqwest::Client::new();
         for (feed_name, upload_feed) in upload_feeds {
             for (source_name, data_feed) in upload_feed {
-                staleness_tolerance.insert(
-                    make_onchain_feed_name(&feed_name, &source_name),
-                    data_feed.submission_interval,
-                );
                 let oracle_obj_id = data_feed.upload_parameters.write_data_provider_object_id;
-                let data_provider = DataProvider {
-                    feed_name: feed_name.clone(),
-                    source_name: source_name.clone(),
-                    upload_feed: Arc::new(data_feed),
-                    sender: sender.clone(),
-                    metrics: metrics.clone(),
-                };
-                providers.push(Arc::new(data_provider));
+                providers.push(DataProvider::new(
+                    feed_name.clone(),
+                    source_name.clone(),
+                    Arc::new(data_feed),
+                    http_client.clone(),
+                    metrics.clone(),
+                ));
                 if let std::collections::hash_map::Entry::Vacant(e) =
                     oracle_object_args.entry(oracle_obj_id)
                 {
@@ -160,7 +154,6 @@ impl DataProviderRunner {
                 }
             }
         }
-        info!("Staleness tolerance: {:?}", staleness_tolerance);
 
         let gas_obj_ref = get_gas_obj_ref(client.read_api(), gas_coin_id, signer_address).await;
         info!("Gas object: {:?}", gas_obj_ref);
@@ -168,10 +161,8 @@ impl DataProviderRunner {
         let uploader = OnChainDataUploader {
             wallet_ctx: wallet_ctx.clone(),
             client: client.clone(),
-            receiver,
             signer_address,
             gas_obj_ref,
-            staleness_tolerance,
             oracle_object_args,
             metrics: metrics.clone(),
         };
@@ -181,15 +172,70 @@ impl DataProviderRunner {
         }
     }
 
-    pub fn spawn(mut self) {
-        for data_provider in self.providers {
-            tokio::spawn(async move {
-                data_provider.run().await;
-            });
+    pub fn spawn(self) {
+        tokio::spawn(self.run());
+    }
+
+    /// Every tick, retrieve the values of all the feeds due for a submission, and upload them in a
+    /// single transaction.
+    async fn run(mut self) {
+        info!("Starting DataProviderRunner");
+        // The minimal latency is 1 second so we check for due feeds every 0.5 second
+        let mut interval = tokio::time::interval(Duration::from_millis(500));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let data_points: Vec<_> = futures::future::join_all(
+                self.providers
+                    .iter_mut()
+                    .filter(|provider| provider.is_due(now))
+                    .map(|provider| provider.run_once(now)),
+            )
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+            if data_points.is_empty() {
+                continue;
+            }
+
+            let uploaded: Vec<_> = data_points
+                .iter()
+                .map(|data_point| (data_point.feed_name.clone(), data_point.value))
+                .collect();
+            match self.uploader.upload(data_points).await {
+                // Feeds only count as uploaded once the transaction succeeded.
+                Ok(effects) if effects.status().is_ok() => {
+                    for (feed_name, value) in uploaded {
+                        if let Some(provider) = self
+                            .providers
+                            .iter_mut()
+                            .find(|provider| provider.onchain_feed_name == feed_name)
+                        {
+                            provider.record_upload(value, now);
+                        }
+                    }
+                }
+                Ok(effects) => {
+                    error!("Upload failure: {:?}", effects.status());
+                }
+                Err(err) => {
+                    error!("Upload failure: {err}. About to resting for {UPLOAD_FAILURE_RECOVER_SEC} sec.");
+                    tokio::time::sleep(Duration::from_secs(UPLOAD_FAILURE_RECOVER_SEC)).await;
+                    self.uploader.gas_obj_ref = get_gas_obj_ref(
+                        self.uploader.client.read_api(),
+                        self.uploader.gas_obj_ref.0,
+                        self.uploader.signer_address,
+                    )
+                    .await;
+                    error!(
+                        "Updated gas object reference: {:?}",
+                        self.uploader.gas_obj_ref
+                    );
+                }
+            }
         }
-        tokio::spawn(async move {
-            self.uploader.run().await;
-        });
     }
 }
 
@@ -225,108 +271,262 @@ async fn get_gas_obj_ref(
 struct DataProvider {
     pub feed_name: String,
     pub source_name: String,
+    pub onchain_feed_name: String,
     pub upload_feed: Arc<UploadFeedConfig>,
-    pub sender: tokio::sync::mpsc::Sender<DataPoint>,
+    http_client: reqwest::Client,
     metrics: Arc<OracleMetrics>,
+    /// When the feed is next due for a submission.
+    next_run: Instant,
+    /// The last value retrieved from each data source, and when.
+    last_values: Vec<Option<(f64, Instant)>>,
+    /// The last value uploaded, and when.
+    last_upload: Option<(u64, Instant)>,
 }
 
 impl DataProvider {
-    pub async fn run(&self) {
-        info!(
-            feed_name = self.feed_name,
-            source_name = self.source_name,
-            "Starting DataProvider"
-        );
-        let mut interval = tokio::time::interval(self.upload_feed.submission_interval);
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    fn new(
+        feed_name: String,
+        source_name: String,
+        upload_feed: Arc<UploadFeedConfig>,
+        http_client: reqwest::Client,
+        metrics: Arc<OracleMetrics>,
+    ) -> Self {
+        info!(feed_name, source_name, "Starting DataProvider");
+        Self {
+            onchain_feed_name: make_onchain_feed_name(&feed_name, &source_name),
+            last_values: vec![None; upload_feed.data_sources().count()],
+            feed_name,
+            source_name,
+            upload_feed,
+            http_client,
+            metrics,
+            next_run: Instant::now(),
+            last_upload: None,
+        }
+    }
 
-        loop {
-            interval.tick().await;
-            self.run_once().await;
+    fn is_due(&self, now: Instant) -> bool {
+        self.next_run <= now
+    }
+
+    /// The name of the `index`th data source in metrics.
+    fn data_source_name(&self, index: usize) -> String {
+        let data_source = self.upload_feed.data_sources().nth(index);
+        match data_source.and_then(|data_source| data_source.name.clone()) {
+            Some(name) => name,
+            None if self.last_values.len() == 1 => self.source_name.clone(),
+            None => format!("{}-{}", self.source_name, index),
         }
     }
 
-    async fn run_once(&self) {
+    /// Retrieve the values of the feed's data sources and aggregate them, returning the data point
+    /// to upload if the feed is due an update.
+    async fn run_once(&mut self, now: Instant) -> Option<DataPoint> {
         debug!(
             feed_name = self.feed_name,
             source_name = self.source_name,
             "Running data provider once."
         );
-        let value = self.retrieve_from_data_source().await;
-        if value.is_err() {
+        let submission_interval = self.upload_feed.submission_interval;
+        self.next_run = now + submission_interval;
+        let aggregation = &self.upload_feed.aggregation;
+
+        // A data source which doesn't respond must not hold up the next submission of this feed,
+        // nor the uploads of the other feeds, so each retrieval is bounded by the feed's interval.
+        let http_client = &self.http_client;
+        let results = futures::future::join_all(self.upload_feed.data_sources().map(
+            |data_source| async move {
+                tokio::time::timeout(
+                    submission_interval,
+                    retrieve_from_data_source(http_client, data_source),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(anyhow::anyhow!(
+                        "Timed out after {submission_interval:?} retrieving data"
+                    ))
+                })
+            },
+        ))
+        .await;
+
+        let mut values = vec![];
+        let mut names = vec![];
+        for (index, (result, data_source)) in results
+            .into_iter()
+            .zip(self.upload_feed.data_sources())
+            .enumerate()
+        {
+            let name = self.data_source_name(index);
+            match result {
+                Ok(value) => {
+                    self.metrics
+                        .data_source_successes
+                        .with_label_values(&[&self.feed_name, &name])
+                        .inc();
+                    self.last_values[index] = Some((value, now));
+                }
+                Err(err) => {
+                    error!(
+                        feed_name = self.feed_name,
+                        source_name = name,
+                        "Failed to retrieve data from data source: {:?}",
+                        err
+                    );
+                    self.metrics
+                        .data_source_errors
+                        .with_label_values(&[&self.feed_name, &name])
+                        .inc();
+                }
+            }
+            let Some((value, retrieved_at)) = self.last_values[index] else {
+                continue;
+            };
+            let max_staleness = aggregation.max_staleness.unwrap_or_default();
+            if now.duration_since(retrieved_at) > max_staleness {
+                warn!(
+                    feed_name = self.feed_name,
+                    source_name = name,
+                    value,
+                    ?max_staleness,
+                    "Data is too stale, skipping."
+                );
+                self.metrics
+                    .data_staleness
+                    .with_label_values(&[&self.onchain_feed_name])
+                    .inc();
+                continue;
+            }
+            values.push(SourceValue {
+                value,
+                weight: data_source.weight,
+            });
+            names.push(name);
+        }
+
+        if let Some(max_deviation_pct) = aggregation.max_deviation_pct {
+            let outliers = aggregation::outliers(&values, max_deviation_pct);
+            for index in outliers.iter().rev() {
+                let value = values.remove(*index);
+                let name = names.remove(*index);
+                warn!(
+                    feed_name = self.feed_name,
+                    source_name = name,
+                    value = value.value,
+                    "Value deviates too much from the other sources, skipping."
+                );
+                self.metrics
+                    .data_source_outliers
+                    .with_label_values(&[&self.feed_name, &name])
+                    .inc();
+            }
+        }
+
+        if values.len() < aggregation.min_sources {
             error!(
                 feed_name = self.feed_name,
                 source_name = self.source_name,
-                "Failed to retrieve data from data source: {:?}",
-                value
+                "Only {} of the required {} data sources have usable values.",
+                values.len(),
+                aggregation.min_sources,
             );
-            self.metrics
-                .data_source_errors
-                .with_label_values(&[&self.feed_name, &self.source_name])
-                .inc();
-            return;
+            return None;
         }
-
-        self.metrics
-            .data_source_successes
-            .with_label_values(&[&self.feed_name, &self.source_name])
-            .inc();
+        let value = match aggregation::aggregate(&values, &aggregation.method) {
+            Ok(value) => value,
+            Err(err) => {
+                error!(
+                    feed_name = self.feed_name,
+                    source_name = self.source_name,
+                    "Failed to aggregate data: {:?}",
+                    err
+                );
+                return None;
+            }
+        };
 
         // TODO: allow more flexible multiplers and data types
-        let value = (value.unwrap() * METRICS_MULTIPLIER) as u64;
-        self.send_to_uploader(value).await;
+        let value = (value * METRICS_MULTIPLIER) as u64;
+        if let (Some(min_change_pct), Some((last_value, last_upload))) =
+            (aggregation.min_change_pct, self.last_upload)
+        {
+            let heartbeat_due = aggregation.heartbeat.map_or(false, |heartbeat| {
+                now.duration_since(last_upload) >= heartbeat
+            });
+            if !heartbeat_due
+                && !aggregation::is_significant_change(
+                    last_value as f64,
+                    value as f64,
+                    min_change_pct,
+                )
+            {
+                debug!(
+                    feed_name = self.feed_name,
+                    source_name = self.source_name,
+                    value,
+                    last_value,
+                    "Value didn't change enough since the last upload, skipping."
+                );
+                self.metrics
+                    .skipped_uploads
+                    .with_label_values(&[&self.onchain_feed_name])
+                    .inc();
+                return None;
+            }
+        }
+
+        Some(DataPoint {
+            feed_name: self.onchain_feed_name.clone(),
+            upload_parameters: self.upload_feed.upload_parameters.clone(),
+            value,
+            retrieval_timestamp: SystemTime::now(),
+            retrieval_instant: Instant::now(),
+        })
     }
 
-    async fn retrieve_from_data_source(&self) -> anyhow::Result<f64> {
-        // TODO: support websocket
-        let url = &self.upload_feed.data_source_config.url;
-        let json_path = &self.upload_feed.data_source_config.json_path;
-        let response = reqwest::Client::new().get(url).send().await?;
+    fn record_upload(&mut self, value: u64, now: Instant) {
+        self.last_upload = Some((value, now));
+    }
+}
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to fetch data: {:?}", response);
-        }
+async fn retrieve_from_data_source(
+    http_client: &reqwest::Client,
+    data_source: &DataSourceConfig,
+) -> anyhow::Result<f64> {
+    // TODO: support websocket
+    let url = &data_source.url;
+    let json_path = &data_source.json_path;
+    let response = http_client.get(url).send().await?;
 
-        let json_blob: serde_json::Value = response.json().await.unwrap();
-        let data = jsonpath_lib::select(&json_blob, json_path)?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch data: {:?}", response);
+    }
 
-        if data.is_empty() {
-            anyhow::bail!(
-                "Failed to find data from json blob: {:?} with json path: {:?}",
-                json_blob,
-                json_path
-            );
-        }
-        // Assume there is one single value per request
-        match data[0].as_str() {
-            Some(value_str) => match value_str.parse::<f64>() {
-                Ok(value) => Ok(value),
-                Err(_) => anyhow::bail!(
-                    "Failed to parse data {:?} as f64 from json blob: {:?}",
-                    data[0],
-                    json_blob
-                ),
-            },
-            None => anyhow::bail!(
-                "Failed to parse data {:?} as string from json blob: {:?}",
+    let json_blob: serde_json::Value = response.json().await?;
+    let data = jsonpath_lib::select(&json_blob, json_path)?;
+
+    if data.is_empty() {
+        anyhow::bail!(
+            "Failed to find data from json blob: {:?} with json path: {:?}",
+            json_blob,
+            json_path
+        );
+    }
+    // Assume there is one single value per request
+    match data[0].as_str() {
+        Some(value_str) => match value_str.parse::<f64>() {
+            Ok(value) => Ok(value),
+            Err(_) => anyhow::bail!(
+                "Failed to parse data {:?} as f64 from json blob: {:?}",
                 data[0],
                 json_blob
             ),
-        }
-    }
-
-    async fn send_to_uploader(&self, value: u64) {
-        let _ = self
-            .sender
-            .send(DataPoint {
-                feed_name: make_onchain_feed_name(&self.feed_name, &self.source_name),
-                upload_parameters: self.upload_feed.upload_parameters.clone(),
-                value,
-                retrieval_timestamp: SystemTime::now(),
-                retrieval_instant: Instant::now(),
-            })
-            .await
-            .tap_err(|err| error!("Failed to send data point to uploader: {:?}", err));
+        },
+        None => anyhow::bail!(
+            "Failed to parse data {:?} as string from json blob: {:?}",
+            data[0],
+            json_blob
+        ),
     }
 }
 
@@ -338,153 +538,83 @@ fn make_onchain_feed_name(feed_name: &str, source_name: &str) -> String {
     )
 }
 
+/// Build the transaction writing `data_points` to their oracle objects.
+fn make_upload_transaction(
+    data_points: &[DataPoint],
+    oracle_object_args: &HashMap<ObjectID, ObjectArg>,
+) -> anyhow::Result<ProgrammableTransaction> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for data_point in data_points {
+        let package_id = data_point.upload_parameters.write_package_id;
+        let feed_name = &data_point.feed_name;
+        let oracle_obj_arg = *oracle_object_args
+            .get(&data_point.upload_parameters.write_data_provider_object_id)
+            .unwrap_or_else(|| panic!("Bug, missing oracle object arg for feed: {}", feed_name));
+        let duration_since_start = data_point.retrieval_instant.elapsed();
+        let data_point_ts: DateTime<Utc> =
+            DateTime::from(data_point.retrieval_timestamp + duration_since_start);
+
+        // The builder adds each object as an input once, and returns that input for the feeds
+        // of the same oracle object.
+        let mut arguments = vec![
+            builder.input(CallArg::Object(oracle_obj_arg))?,
+            builder.input(CallArg::CLOCK_IMM)?,
+        ];
+
+        let decimal = builder
+            .input(CallArg::Pure(bcs::to_bytes(&DECIMAL).unwrap()))
+            .unwrap();
+        let value = builder
+            .input(CallArg::Pure(bcs::to_bytes(&data_point.value).unwrap()))
+            .unwrap();
+
+        arguments.extend_from_slice(&[
+            builder
+                .input(CallArg::Pure(bcs::to_bytes(&feed_name)?))
+                .unwrap(),
+            builder.programmable_move_call(
+                package_id,
+                Identifier::from_str("decimal_value").unwrap(),
+                Identifier::from_str("new").unwrap(),
+                vec![],
+                vec![value, decimal],
+            ),
+            builder
+                .input(CallArg::Pure(bcs::to_bytes(&format!("{}", data_point_ts))?))
+                .unwrap(),
+        ]);
+
+        builder.command(Command::move_call(
+            package_id,
+            Identifier::new(data_point.upload_parameters.write_module_name.clone()).unwrap(),
+            Identifier::new(data_point.upload_parameters.write_function_name.clone()).unwrap(),
+            // TODO: allow more generic data types
+            vec![
+                parse_sui_type_tag(&format!("{package_id}::decimal_value::DecimalValue")).unwrap(),
+            ],
+            arguments,
+        ));
+    }
+    Ok(builder.finish())
+}
+
 struct OnChainDataUploader {
     wallet_ctx: Arc<WalletContext>,
     client: Arc<SuiClient>,
-    receiver: tokio::sync::mpsc::Receiver<DataPoint>,
     signer_address: SuiAddress,
     gas_obj_ref: ObjectRef,
-    staleness_tolerance: HashMap<String, Duration>,
     oracle_object_args: HashMap<ObjectID, ObjectArg>,
     metrics: Arc<OracleMetrics>,
 }
 
 impl OnChainDataUploader {
-    async fn run(&mut self) {
-        info!("Starting OnChainDataUploader");
-        // The minimal latency is 1 second so we collect data every 1 second
-        let mut read_interval = tokio::time::interval(Duration::from_millis(500));
-        read_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-        loop {
-            read_interval.tick().await;
-            let data_points = self.collect().await;
-            if !data_points.is_empty() {
-                if let Err(err) = self.upload(data_points).await {
-                    error!("Upload failure: {err}. About to resting for {UPLOAD_FAILURE_RECOVER_SEC} sec.");
-                    tokio::time::sleep(Duration::from_secs(UPLOAD_FAILURE_RECOVER_SEC)).await;
-                    self.gas_obj_ref = get_gas_obj_ref(
-                        self.client.read_api(),
-                        self.gas_obj_ref.0,
-                        self.signer_address,
-                    )
-                    .await;
-                    error!("Updated gas object reference: {:?}", self.gas_obj_ref);
-                }
-            }
-        }
-    }
-
-    async fn collect(&mut self) -> Vec<DataPoint> {
-        let start = Instant::now();
-        let mut data_points = vec![];
-        while let Ok(Some(data_point)) =
-            tokio::time::timeout(Duration::from_millis(100), self.receiver.recv()).await
-        {
-            let feed_name = &data_point.feed_name;
-            debug!(
-                feed_name = data_point.feed_name,
-                value = data_point.value,
-                "Received data from data provider."
-            );
-            // TODO: for each source, at most take one value in each submission
-            let staleness_tolerance =
-                self.staleness_tolerance.get(feed_name).unwrap_or_else(|| {
-                    panic!("Bug, missing staleness tolerance for feed: {}", feed_name)
-                });
-            let duration_since = data_point.retrieval_instant.elapsed();
-            if duration_since > staleness_tolerance.add(Duration::from_secs(1)) {
-                warn!(
-                    feed_name,
-                    value = data_point.value,
-                    ?duration_since,
-                    ?staleness_tolerance,
-                    "Data is too stale, skipping."
-                );
-                self.metrics
-                    .data_staleness
-                    .with_label_values(&[feed_name])
-                    .inc();
-            } else {
-                data_points.push(data_point);
-            }
-
-            // One run only waits for 1 second
-            // But if we don't have any valid data points, we wait until we do.
-            if data_points.is_empty() && start.elapsed() >= Duration::from_millis(500) {
-                break;
-            }
-        }
-        debug!("Collected {} data points", data_points.len());
-        data_points
-    }
-
     async fn upload(
         &mut self,
         data_points: Vec<DataPoint>,
     ) -> anyhow::Result<SuiTransactionBlockEffects> {
         let _scope = monitored_scope("Oracle::OnChainDataUploader::upload");
         // TODO add more error handling & polling perhaps
-        let mut builder = ProgrammableTransactionBuilder::new();
-        let mut is_first = true;
-        for data_point in &data_points {
-            let package_id = data_point.upload_parameters.write_package_id;
-            let feed_name = &data_point.feed_name;
-            let oracle_obj_arg = *self
-                .oracle_object_args
-                .get(&data_point.upload_parameters.write_data_provider_object_id)
-                .unwrap_or_else(|| {
-                    panic!("Bug, missing oracle object arg for feed: {}", feed_name)
-                });
-            let duration_since_start = data_point.retrieval_instant.elapsed();
-            let data_point_ts: DateTime<Utc> =
-                DateTime::from(data_point.retrieval_timestamp + duration_since_start);
-
-            let mut arguments = if is_first {
-                vec![
-                    builder.input(CallArg::Object(oracle_obj_arg)).unwrap(),
-                    builder.input(CallArg::CLOCK_IMM).unwrap(),
-                ]
-            } else {
-                vec![Argument::Input(0), Argument::Input(1)]
-            };
-
-            let decimal = builder
-                .input(CallArg::Pure(bcs::to_bytes(&DECIMAL).unwrap()))
-                .unwrap();
-            let value = builder
-                .input(CallArg::Pure(bcs::to_bytes(&data_point.value).unwrap()))
-                .unwrap();
-
-            arguments.extend_from_slice(&[
-                builder
-                    .input(CallArg::Pure(bcs::to_bytes(&feed_name)?))
-                    .unwrap(),
-                builder.programmable_move_call(
-                    package_id,
-                    Identifier::from_str("decimal_value").unwrap(),
-                    Identifier::from_str("new").unwrap(),
-                    vec![],
-                    vec![value, decimal],
-                ),
-                builder
-                    .input(CallArg::Pure(bcs::to_bytes(&format!("{}", data_point_ts))?))
-                    .unwrap(),
-            ]);
-
-            builder.command(Command::move_call(
-                package_id,
-                Identifier::new(data_point.upload_parameters.write_module_name.clone()).unwrap(),
-                Identifier::new(data_point.upload_parameters.write_function_name.clone()).unwrap(),
-                // TODO: allow more generic data types
-                vec![
-                    parse_sui_type_tag(&format!("{package_id}::decimal_value::DecimalValue"))
-                        .unwrap(),
-                ],
-                arguments,
-            ));
-            is_first = false;
-        }
-        let pt = builder.finish();
+        let pt = make_upload_transaction(&data_points, &self.oracle_object_args)?;
         let rgp = self
             .client
             .governance_api()
@@ -709,3 +839,216 @@ async fn get_object_arg(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AggregationConfig, AggregationMethod};
+    use axum::{routing::get, Json, Router};
+    use sui_types::transaction::Argument;
+
+    /// Serve each price at `/<index>` from a local HTTP stub, returning the URLs of the prices.
+    fn serve_prices(prices: &[&'static str]) -> Vec<String> {
+        let mut app = Router::new();
+        for (index, price) in prices.iter().copied().enumerate() {
+            app = app.route(
+                &format!("/{index}"),
+                get(move || async move { Json(serde_json::json!({ "data": { "price": price } })) }),
+            );
+        }
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+        (0..prices.len())
+            .map(|index| format!("http://{addr}/{index}"))
+            .collect()
+    }
+
+    fn data_provider(urls: Vec<String>, aggregation: AggregationConfig) -> DataProvider {
+        let upload_feed = UploadFeedConfig {
+            submission_interval: Duration::from_secs(1),
+            data_source_config: None,
+            data_source_configs: urls
+                .into_iter()
+                .map(|url| DataSourceConfig {
+                    url,
+                    json_path: "$.data.price".into(),
+                    name: None,
+                    weight: 1.0,
+                })
+                .collect(),
+            aggregation,
+            upload_parameters: UploadParameters {
+                write_package_id: ObjectID::ZERO,
+                write_module_name: "simple_oracle".into(),
+                write_function_name: "submit_data".into(),
+                write_data_provider_object_id: ObjectID::ZERO,
+            },
+        };
+        DataProvider::new(
+            "SUIUSDT".into(),
+            "test".into(),
+            Arc::new(upload_feed),
+            reqwest::Client::new(),
+            Arc::new(OracleMetrics::new(&Registry::new())),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_sources_without_outliers() {
+        let urls = serve_prices(&["100", "101", "150"]);
+        let mut provider = data_provider(
+            urls,
+            AggregationConfig {
+                max_deviation_pct: Some(10.0),
+                ..Default::default()
+            },
+        );
+
+        let data_point = provider.run_once(Instant::now()).await.unwrap();
+        assert_eq!(data_point.feed_name, "suiusdt-test");
+        assert_eq!(data_point.value, 100_500_000);
+    }
+
+    #[tokio::test]
+    async fn test_min_sources() {
+        let mut urls = serve_prices(&["1", "2"]);
+        urls.push(format!("{}-missing", urls[0]));
+        let mut provider = data_provider(
+            urls,
+            AggregationConfig {
+                method: AggregationMethod::Weighted,
+                min_sources: 3,
+                ..Default::default()
+            },
+        );
+        assert!(provider.run_once(Instant::now()).await.is_none());
+
+        provider.upload_feed = Arc::new(UploadFeedConfig {
+            aggregation: AggregationConfig {
+                method: AggregationMethod::Weighted,
+                min_sources: 2,
+                ..Default::default()
+            },
+            ..(*provider.upload_feed).clone()
+        });
+        let data_point = provider.run_once(Instant::now()).await.unwrap();
+        assert_eq!(data_point.value, 1_500_000);
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_source_times_out() {
+        let mut urls = serve_prices(&["3"]);
+        // Connections to a listener which never accepts them are never responded to.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        urls.push(format!("http://{}/", listener.local_addr().unwrap()));
+        let mut provider = data_provider(urls, Default::default());
+
+        let data_point =
+            tokio::time::timeout(Duration::from_secs(10), provider.run_once(Instant::now()))
+                .await
+                .expect("retrieving from an unresponsive data source should time out")
+                .unwrap();
+        assert_eq!(data_point.value, 3_000_000);
+        assert_eq!(
+            provider
+                .metrics
+                .data_source_errors
+                .with_label_values(&[&provider.feed_name, &provider.data_source_name(1)])
+                .get(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skip_small_changes_until_heartbeat() {
+        let urls = serve_prices(&["2"]);
+        let mut provider = data_provider(
+            urls,
+            AggregationConfig {
+                min_change_pct: Some(1.0),
+                heartbeat: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+        );
+
+        let start = Instant::now();
+        let data_point = provider.run_once(start).await.unwrap();
+        provider.record_upload(data_point.value, start);
+
+        // The value didn't change since the last upload.
+        assert!(provider
+            .run_once(start + Duration::from_secs(1))
+            .await
+            .is_none());
+        // The heartbeat is due.
+        assert!(provider
+            .run_once(start + Duration::from_secs(60))
+            .await
+            .is_some());
+    }
+
+    #[test]
+    fn test_upload_to_each_oracle_object() {
+        let oracles: Vec<_> = (0..2).map(|_| ObjectID::random()).collect();
+        let oracle_object_args: HashMap<_, _> = oracles
+            .iter()
+            .map(|id| {
+                let arg = ObjectArg::SharedObject {
+                    id: *id,
+                    initial_shared_version: 1.into(),
+                    mutable: true,
+                };
+                (*id, arg)
+            })
+            .collect();
+        let data_points: Vec<_> = [oracles[0], oracles[1], oracles[0]]
+            .into_iter()
+            .enumerate()
+            .map(|(index, oracle)| DataPoint {
+                feed_name: format!("feed-{index}"),
+                upload_parameters: UploadParameters {
+                    write_package_id: ObjectID::ZERO,
+                    write_module_name: "simple_oracle".into(),
+                    write_function_name: "submit_data".into(),
+                    write_data_provider_object_id: oracle,
+                },
+                value: index as u64,
+                retrieval_timestamp: SystemTime::now(),
+                retrieval_instant: Instant::now(),
+            })
+            .collect();
+
+        let pt = make_upload_transaction(&data_points, &oracle_object_args).unwrap();
+        let input = |oracle: &ObjectID| {
+            let arg = CallArg::Object(oracle_object_args[oracle]);
+            let index = pt.inputs.iter().position(|input| *input == arg).unwrap();
+            Argument::Input(index as u16)
+        };
+        let oracle_arguments: Vec<_> = pt
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::MoveCall(call) if call.function.as_str() == "submit_data" => {
+                    Some(call.arguments[0])
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            oracle_arguments,
+            vec![input(&oracles[0]), input(&oracles[1]), input(&oracles[0])]
+        );
+        // Both oracle objects and the clock.
+        let objects = pt
+            .inputs
+            .iter()
+            .filter(|input| matches!(input, CallArg::Object(_)))
+            .count();
+        assert_eq!(objects, 3);
+    }
+}
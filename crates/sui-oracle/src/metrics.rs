@@ -13,6 +13,8 @@ pub struct OracleMetrics {
     pub(crate) data_source_successes: IntCounterVec,
     pub(crate) data_source_errors: IntCounterVec,
     pub(crate) data_staleness: IntCounterVec,
+    pub(crate) data_source_outliers: IntCounterVec,
+    pub(crate) skipped_uploads: IntCounterVec,
     pub(crate) upload_successes: IntCounterVec,
     pub(crate) upload_data_errors: IntCounterVec,
     pub(crate) download_successes: IntCounterVec,
@@ -50,6 +52,20 @@ impl OracleMetrics {
                 registry,
             )
             .unwrap(),
+            data_source_outliers: register_int_counter_vec_with_registry!(
+                "oracle_data_source_outliers",
+                "Total number of data source values rejected as outliers",
+                &["feed", "source"],
+                registry,
+            )
+            .unwrap(),
+            skipped_uploads: register_int_counter_vec_with_registry!(
+                "oracle_skipped_uploads",
+                "Total number of uploads skipped as the value didn't change enough",
+                &["feed"],
+                registry,
+            )
+            .unwrap(),
             upload_successes: register_int_counter_vec_with_registry!(
                 "oracle_upload_successes",
                 "Total number of successful data upload",
@@ -1,5 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::buffer::WriteAheadBuffer;
 use crate::config::{PeerValidationConfig, RemoteWriteConfig};
 use crate::handlers::publish_metrics;
 use crate::histogram_relay::HistogramRelay;
@@ -7,6 +8,7 @@ use crate::middleware::{
     expect_content_length, expect_mysten_proxy_header, expect_valid_public_key,
 };
 use crate::peers::SuiNodeProvider;
+use crate::relabel::Relabeler;
 use crate::var;
 use anyhow::Result;
 use axum::{extract::DefaultBodyLimit, middleware, routing::post, Extension, Router};
@@ -79,6 +81,38 @@ pub fn make_reqwest_client(settings: RemoteWriteConfig, user_agent: &str) -> Req
     }
 }
 
+/// RemoteWriteTarget is a remote_write api we relay metrics to, along with the relabel rules
+/// applied to the metrics sent to it and the buffer holding what it failed to receive
+#[derive(Clone)]
+pub struct RemoteWriteTarget {
+    pub client: ReqwestClient,
+    pub relabeler: Arc<Relabeler>,
+    pub buffer: Option<Arc<WriteAheadBuffer>>,
+}
+
+impl RemoteWriteTarget {
+    pub fn name(&self) -> &str {
+        &self.client.settings.name
+    }
+}
+
+pub fn make_remote_write_target(
+    settings: RemoteWriteConfig,
+    user_agent: &str,
+) -> Result<RemoteWriteTarget> {
+    let relabeler = Arc::new(Relabeler::new(&settings.relabel)?);
+    let buffer = settings
+        .buffer
+        .as_ref()
+        .map(|buffer| WriteAheadBuffer::open(&settings.name, buffer).map(Arc::new))
+        .transpose()?;
+    Ok(RemoteWriteTarget {
+        client: make_reqwest_client(settings, user_agent),
+        relabeler,
+        buffer,
+    })
+}
+
 // Labels are adhoc labels we will inject per our config
 #[derive(Clone)]
 pub struct Labels {
@@ -89,7 +123,7 @@ pub struct Labels {
 /// App will configure our routes. This fn is also used to instrument our tests
 pub fn app(
     labels: Labels,
-    client: ReqwestClient,
+    targets: Vec<RemoteWriteTarget>,
     relay: HistogramRelay,
     allower: Option<SuiNodeProvider>,
 ) -> Router {
@@ -111,7 +145,7 @@ pub fn app(
     router
         .layer(Extension(relay))
         .layer(Extension(labels))
-        .layer(Extension(client))
+        .layer(Extension(Arc::new(targets)))
         .layer(
             ServiceBuilder::new().layer(
                 TraceLayer::new_for_http().on_response(
@@ -0,0 +1,271 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::config::BufferConfig;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use prometheus::{register_counter_vec, register_int_gauge_vec, CounterVec, IntGaugeVec};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::{error, warn};
+
+static BUFFER_OPS: Lazy<CounterVec> = Lazy::new(|| {
+    register_counter_vec!(
+        "remote_write_buffer_operations",
+        "Number of batches buffered, replayed, evicted or dropped per remote_write target.",
+        &["target", "operation"]
+    )
+    .unwrap()
+});
+static BUFFER_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "remote_write_buffer_bytes",
+        "The size of the batches waiting to be replayed per remote_write target.",
+        &["target"]
+    )
+    .unwrap()
+});
+
+const BATCH_EXTENSION: &str = "batch";
+
+#[derive(Default)]
+struct BufferState {
+    /// sequence number and size of the buffered batches, oldest first
+    batches: VecDeque<(u64, u64)>,
+    next_seq: u64,
+    bytes: u64,
+    /// whether a replay is in flight
+    replaying: bool,
+}
+
+/// WriteAheadBuffer keeps the compressed remote_write batches that could not be delivered to a
+/// target on disk, one file per batch, so they can be replayed in order once the target recovers.
+/// It is bounded in size, the oldest batches are evicted to make room for new ones.
+pub struct WriteAheadBuffer {
+    target: String,
+    dir: PathBuf,
+    max_bytes: u64,
+    state: Mutex<BufferState>,
+}
+
+impl WriteAheadBuffer {
+    /// open the buffer in the configured directory, picking up the batches left by a previous run
+    pub fn open(target: &str, config: &BufferConfig) -> Result<Self> {
+        let dir = config.path.clone();
+        fs::create_dir_all(&dir).context(format!("cannot create buffer directory {:?}", dir))?;
+
+        let mut state = BufferState::default();
+        let mut batches = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let seq = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            match (seq, path.extension().and_then(|ext| ext.to_str())) {
+                (Some(seq), Some(BATCH_EXTENSION)) => {
+                    batches.push((seq, fs::metadata(&path)?.len()))
+                }
+                // leftovers of a write interrupted by a crash
+                (_, Some("tmp")) => fs::remove_file(&path)?,
+                _ => warn!("ignoring unexpected file {:?} in remote_write buffer", path),
+            }
+        }
+        batches.sort_unstable();
+        state.next_seq = batches.last().map_or(0, |(seq, _)| seq + 1);
+        state.bytes = batches.iter().map(|(_, len)| len).sum();
+        state.batches = batches.into();
+        BUFFER_BYTES
+            .with_label_values(&[target])
+            .set(state.bytes as i64);
+
+        Ok(Self {
+            target: target.to_owned(),
+            dir,
+            max_bytes: config.max_bytes,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn batch_path(&self, seq: u64) -> PathBuf {
+        self.dir.join(format!("{seq:020}.{BATCH_EXTENSION}"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.lock().unwrap().batches.is_empty()
+    }
+
+    /// whether new batches must queue up in the buffer to keep their order, as it has batches
+    /// waiting to be replayed or a replay is in flight
+    pub fn has_backlog(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.replaying || !state.batches.is_empty()
+    }
+
+    /// mark a replay as in flight until the returned guard is dropped
+    pub fn start_replay(&self) -> ReplayGuard<'_> {
+        self.state.lock().unwrap().replaying = true;
+        ReplayGuard(self)
+    }
+
+    /// append a batch to the buffer, evicting the oldest batches if we run out of room
+    pub fn push(&self, batch: &[u8]) -> Result<()> {
+        let len = batch.len() as u64;
+        if len > self.max_bytes {
+            BUFFER_OPS
+                .with_label_values(&[&self.target, "dropped"])
+                .inc();
+            anyhow::bail!("batch of {len} bytes does not fit in the remote_write buffer");
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let seq = state.next_seq;
+        let path = self.batch_path(seq);
+        // write then rename so a crash never leaves a truncated batch behind
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, batch)?;
+        fs::rename(&tmp, &path)?;
+        state.next_seq += 1;
+        state.batches.push_back((seq, len));
+        state.bytes += len;
+        BUFFER_OPS
+            .with_label_values(&[&self.target, "buffered"])
+            .inc();
+
+        while state.bytes > self.max_bytes {
+            let Some((oldest, oldest_len)) = state.batches.pop_front() else {
+                break;
+            };
+            state.bytes -= oldest_len;
+            if let Err(error) = fs::remove_file(self.batch_path(oldest)) {
+                error!("unable to evict remote_write buffer batch {oldest}; {error}");
+            }
+            BUFFER_OPS
+                .with_label_values(&[&self.target, "evicted"])
+                .inc();
+        }
+        BUFFER_BYTES
+            .with_label_values(&[&self.target])
+            .set(state.bytes as i64);
+        Ok(())
+    }
+
+    /// the sequence number and content of the oldest batch, if any
+    pub fn oldest(&self) -> Option<(u64, Vec<u8>)> {
+        let mut state = self.state.lock().unwrap();
+        while let Some((seq, len)) = state.batches.front().copied() {
+            match fs::read(self.batch_path(seq)) {
+                Ok(batch) => return Some((seq, batch)),
+                Err(error) => {
+                    // an unreadable batch would block the replay forever
+                    error!("dropping unreadable remote_write buffer batch {seq}; {error}");
+                    state.batches.pop_front();
+                    state.bytes -= len;
+                    BUFFER_OPS
+                        .with_label_values(&[&self.target, "dropped"])
+                        .inc();
+                    BUFFER_BYTES
+                        .with_label_values(&[&self.target])
+                        .set(state.bytes as i64);
+                    let _ = fs::remove_file(self.batch_path(seq));
+                }
+            }
+        }
+        None
+    }
+
+    /// remove the oldest batch once it has been replayed, or can never be
+    pub fn remove(&self, seq: u64, operation: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        // the batch may have been evicted while it was being replayed
+        if !matches!(state.batches.front(), Some((oldest, _)) if *oldest == seq) {
+            return Ok(());
+        }
+        let (_, len) = state.batches.pop_front().unwrap();
+        state.bytes -= len;
+        BUFFER_OPS
+            .with_label_values(&[&self.target, operation])
+            .inc();
+        BUFFER_BYTES
+            .with_label_values(&[&self.target])
+            .set(state.bytes as i64);
+        fs::remove_file(self.batch_path(seq))?;
+        Ok(())
+    }
+}
+
+/// ReplayGuard marks the replay of a buffer as over when dropped
+pub struct ReplayGuard<'a>(&'a WriteAheadBuffer);
+
+impl Drop for ReplayGuard<'_> {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().replaying = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn config(path: PathBuf, max_bytes: u64) -> BufferConfig {
+        BufferConfig {
+            path,
+            max_bytes,
+            replay_interval: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn buffer_replays_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = WriteAheadBuffer::open("test", &config(dir.path().into(), 1024)).unwrap();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.oldest(), None);
+
+        assert!(!buffer.has_backlog());
+        // new batches queue up behind a replay in flight, even once it emptied the buffer
+        let replay = buffer.start_replay();
+        assert!(buffer.has_backlog());
+        drop(replay);
+        assert!(!buffer.has_backlog());
+
+        buffer.push(b"first").unwrap();
+        buffer.push(b"second").unwrap();
+        assert!(buffer.has_backlog());
+        let (seq, batch) = buffer.oldest().unwrap();
+        assert_eq!(batch, b"first");
+        buffer.remove(seq, "replayed").unwrap();
+        // removing a batch twice is a no-op
+        buffer.remove(seq, "replayed").unwrap();
+        assert_eq!(buffer.oldest().unwrap().1, b"second");
+
+        // batches survive a restart
+        drop(buffer);
+        let buffer = WriteAheadBuffer::open("test", &config(dir.path().into(), 1024)).unwrap();
+        let (seq, batch) = buffer.oldest().unwrap();
+        assert_eq!(batch, b"second");
+        buffer.push(b"third").unwrap();
+        buffer.remove(seq, "replayed").unwrap();
+        assert_eq!(buffer.oldest().unwrap().1, b"third");
+    }
+
+    #[test]
+    fn buffer_is_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = WriteAheadBuffer::open("test", &config(dir.path().into(), 10)).unwrap();
+
+        buffer.push(b"1234").unwrap();
+        buffer.push(b"5678").unwrap();
+        // evicts the oldest batch
+        buffer.push(b"90").unwrap();
+        buffer.push(b"ab").unwrap();
+        assert_eq!(buffer.oldest().unwrap().1, b"5678");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+
+        // never fits
+        assert!(buffer.push(b"0123456789a").is_err());
+        assert_eq!(buffer.oldest().unwrap().1, b"5678");
+    }
+}
@@ -1,10 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use core::time::Duration;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::{serde_as, DurationSeconds};
+use std::collections::HashSet;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::debug;
 
 #[serde_as]
@@ -15,6 +17,9 @@ pub struct ProxyConfig {
     pub inventory_hostname: String,
     pub listen_address: SocketAddr,
     pub remote_write: RemoteWriteConfig,
+    /// remote_write targets receiving the same data as `remote_write`
+    #[serde(default)]
+    pub additional_remote_writes: Vec<RemoteWriteConfig>,
     pub json_rpc: PeerValidationConfig,
     pub metrics_address: String,
     pub histogram_address: String,
@@ -24,6 +29,9 @@ pub struct ProxyConfig {
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct RemoteWriteConfig {
+    /// name of the target, used in logs and metrics
+    #[serde(default = "remote_write_name_default")]
+    pub name: String,
     // TODO upgrade to https
     /// the remote_write url to post data to
     #[serde(default = "remote_write_url")]
@@ -36,6 +44,71 @@ pub struct RemoteWriteConfig {
     /// <https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.pool_max_idle_per_host>
     #[serde(default = "pool_max_idle_per_host_default")]
     pub pool_max_idle_per_host: usize,
+
+    /// rules applied, in order, to the metrics before they are sent to this target
+    #[serde(default)]
+    pub relabel: Vec<RelabelConfig>,
+
+    /// if set, batches that cannot be delivered because the target is unavailable are kept on
+    /// disk and replayed once it recovers, instead of being dropped
+    pub buffer: Option<BufferConfig>,
+}
+
+/// RelabelConfig rewrites the metrics sent to a remote_write target
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", tag = "action")]
+pub enum RelabelConfig {
+    /// set `label` to `value` on every metric, adding it if missing
+    Replace { label: String, value: String },
+    /// rename the label `from` to `to` on every metric
+    Rename { from: String, to: String },
+    /// remove `label` from every metric
+    LabelDrop { label: String },
+    /// only keep the metric families whose name fully matches `regex`
+    Keep { regex: String },
+    /// drop the metric families whose name fully matches `regex`
+    Drop { regex: String },
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BufferConfig {
+    /// directory holding the undelivered batches, it must not be shared between targets
+    /// please use an absolute path
+    pub path: PathBuf,
+    /// once the buffer grows past this size, the oldest batches are dropped
+    #[serde(default = "buffer_max_bytes_default")]
+    pub max_bytes: u64,
+    /// how often we try to replay the buffered batches
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "buffer_replay_interval_default")]
+    pub replay_interval: Duration,
+}
+
+impl ProxyConfig {
+    /// all the remote_write targets we send data to
+    pub fn remote_writes(&self) -> impl Iterator<Item = &RemoteWriteConfig> {
+        std::iter::once(&self.remote_write).chain(self.additional_remote_writes.iter())
+    }
+
+    /// check the constraints between settings that serde can't, i.e. that no two remote_write
+    /// targets share a buffer directory
+    pub fn validate(&self) -> Result<()> {
+        let mut buffer_paths = HashSet::new();
+        for remote_write in self.remote_writes() {
+            if let Some(buffer) = &remote_write.buffer {
+                if !buffer_paths.insert(&buffer.path) {
+                    bail!(
+                        "remote_write target {} shares its buffer path {:?} with another target",
+                        remote_write.name,
+                        buffer.path
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[serde_as]
@@ -64,6 +137,21 @@ fn pool_max_idle_per_host_default() -> usize {
     8
 }
 
+/// the default name of a remote_write target
+fn remote_write_name_default() -> String {
+    "default".to_string()
+}
+
+/// the default size limit of a remote_write buffer, 1 GiB
+fn buffer_max_bytes_default() -> u64 {
+    1 << 30
+}
+
+/// the default interval between two replays of a remote_write buffer
+fn buffer_replay_interval_default() -> Duration {
+    Duration::from_secs(30)
+}
+
 /// the default hostname we will use if not provided
 fn hostname_default() -> Option<String> {
     Some("localhost".to_string())
@@ -90,6 +178,42 @@ mod tests {
     fn config_load() {
         const TEMPLATE: &str = include_str!("./data/config.yaml");
 
-        let _template: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        let template: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        assert_eq!(template.remote_writes().count(), 2);
+        assert_eq!(template.remote_write.name, "default");
+
+        let backup = &template.additional_remote_writes[0];
+        assert_eq!(backup.name, "backup");
+        assert_eq!(
+            backup.relabel,
+            vec![
+                RelabelConfig::Rename {
+                    from: "host".into(),
+                    to: "instance".into(),
+                },
+                RelabelConfig::Drop {
+                    regex: "go_.*".into(),
+                },
+            ]
+        );
+        let buffer = backup.buffer.as_ref().unwrap();
+        assert_eq!(buffer.max_bytes, 1 << 30);
+        assert_eq!(buffer.replay_interval, Duration::from_secs(30));
+        template.validate().unwrap();
+    }
+
+    #[test]
+    fn config_rejects_shared_buffer_paths() {
+        const TEMPLATE: &str = include_str!("./data/config.yaml");
+
+        let mut template: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        let buffer = template.additional_remote_writes[0].buffer.clone();
+        template.remote_write.buffer = buffer.clone();
+        assert!(template.validate().is_err());
+
+        let mut buffer = buffer.unwrap();
+        buffer.path = buffer.path.join("default");
+        template.remote_write.buffer = Some(buffer);
+        template.validate().unwrap();
     }
 }
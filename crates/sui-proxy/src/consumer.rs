@@ -1,7 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::admin::ReqwestClient;
+use crate::admin::{RemoteWriteTarget, ReqwestClient};
+use crate::buffer::WriteAheadBuffer;
 use crate::prom_to_mimir::Mimir;
 use crate::remote_write::WriteRequest;
 use anyhow::Result;
@@ -267,76 +268,226 @@ async fn convert(
     Ok(result)
 }
 
-/// convert_to_remote_write is an expensive method due to the time it takes to submit to mimir.
-/// other operations here are optimized for async, within reason.  The post process uses a single
-/// connection to mimir and thus incurs the seriliaztion delay for each metric family sent. Possible
-/// future optimizations would be to use multiple tcp connections to mimir, within reason. Nevertheless
-/// we await on each post of each metric family so it shouldn't block any other async work in a
-/// significant way.
-pub async fn convert_to_remote_write(
-    rc: ReqwestClient,
-    node_metric: NodeMetric,
-) -> (StatusCode, &'static str) {
-    let timer = CONSUMER_OPERATION_DURATION
-        .with_label_values(&["convert_to_remote_write"])
-        .start_timer();
+/// retriable responses are the ones we expect to succeed later, when the target recovers
+fn is_retriable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
 
-    let remote_write_protos = match convert(node_metric.data).await {
-        Ok(v) => v,
-        Err(err) => {
-            timer.stop_and_discard();
-            return err;
-        }
+async fn post(rc: &ReqwestClient, body: Bytes) -> reqwest::Result<reqwest::Response> {
+    rc.client
+        .post(rc.settings.url.to_owned())
+        .header(reqwest::header::CONTENT_ENCODING, "snappy")
+        .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .basic_auth(
+            rc.settings.username.to_owned(),
+            Some(rc.settings.password.to_owned()),
+        )
+        .body(body)
+        .send()
+        .await
+}
+
+/// keep a batch the target could not receive so we can replay it later
+fn buffer_batch(
+    target: &RemoteWriteTarget,
+    buffer: &WriteAheadBuffer,
+    compressed: &[u8],
+) -> Result<(), (StatusCode, &'static str)> {
+    buffer.push(compressed).map_err(|error| {
+        CONSUMER_OPS.with_label_values(&["buffer", "failed"]).inc();
+        error!(
+            "DROPPING METRICS for {}, unable to buffer them; {error}",
+            target.name()
+        );
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DROPPING METRICS; unable to buffer them",
+        )
+    })
+}
+
+/// send the metrics to a single target, buffering them if it is unavailable and has a buffer
+async fn send_to_target(
+    target: RemoteWriteTarget,
+    data: Vec<MetricFamily>,
+) -> Result<usize, (StatusCode, &'static str)> {
+    let data = if target.relabeler.is_empty() {
+        data
+    } else {
+        target.relabeler.apply(data)
     };
+    let remote_write_protos = convert(data).await?;
 
     // a counter so we don't iterate the node data 2x
     let mut mf_cnt = 0;
     for request in remote_write_protos {
         mf_cnt += 1;
-        let compressed = match encode_compress(&request) {
-            Ok(compressed) => compressed,
-            Err(error) => return error,
-        };
+        let compressed = Bytes::from(encode_compress(&request)?);
 
-        let response = match rc
-            .client
-            .post(rc.settings.url.to_owned())
-            .header(reqwest::header::CONTENT_ENCODING, "snappy")
-            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .basic_auth(
-                rc.settings.username.to_owned(),
-                Some(rc.settings.password.to_owned()),
-            )
-            .body(compressed)
-            .send()
-            .await
-        {
+        // batches must reach the target in order, so new ones queue up behind the buffered ones,
+        // including the ones being replayed
+        if let Some(buffer) = &target.buffer {
+            if buffer.has_backlog() {
+                buffer_batch(&target, buffer, &compressed)?;
+                continue;
+            }
+        }
+
+        let response = match post(&target.client, compressed.clone()).await {
             Ok(response) => response,
             Err(error) => {
                 CONSUMER_OPS
                     .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
                     .inc();
-                error!("DROPPING METRICS due to post error: {error}");
-                timer.stop_and_discard();
-                return (
+                if let Some(buffer) = &target.buffer {
+                    error!(
+                        "BUFFERING METRICS for {} due to post error: {error}",
+                        target.name()
+                    );
+                    buffer_batch(&target, buffer, &compressed)?;
+                    continue;
+                }
+                error!(
+                    "DROPPING METRICS for {} due to post error: {error}",
+                    target.name()
+                );
+                return Err((
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "DROPPING METRICS due to post error",
-                );
+                ));
             }
         };
 
-        match check_response(request, response).await {
-            Ok(_) => (),
+        if let Some(buffer) = &target.buffer {
+            if is_retriable(response.status()) {
+                CONSUMER_OPS
+                    .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
+                    .inc();
+                error!(
+                    "BUFFERING METRICS for {} due to response status {}",
+                    target.name(),
+                    response.status()
+                );
+                buffer_batch(&target, buffer, &compressed)?;
+                continue;
+            }
+        }
+
+        check_response(request, response).await?;
+    }
+    Ok(mf_cnt)
+}
+
+/// convert_to_remote_write is an expensive method due to the time it takes to submit to mimir.
+/// other operations here are optimized for async, within reason.  The post process uses a single
+/// connection to mimir and thus incurs the seriliaztion delay for each metric family sent. Possible
+/// future optimizations would be to use multiple tcp connections to mimir, within reason. Nevertheless
+/// we await on each post of each metric family so it shouldn't block any other async work in a
+/// significant way.
+///
+/// Each target is sent its own copy of the data concurrently, so a slow or unavailable target
+/// doesn't hold back the others.
+pub async fn convert_to_remote_write(
+    targets: &[RemoteWriteTarget],
+    node_metric: NodeMetric,
+) -> (StatusCode, &'static str) {
+    let timer = CONSUMER_OPERATION_DURATION
+        .with_label_values(&["convert_to_remote_write"])
+        .start_timer();
+
+    let sends: Vec<_> = targets
+        .iter()
+        .map(|target| tokio::spawn(send_to_target(target.clone(), node_metric.data.clone())))
+        .collect();
+
+    let mut result = Ok(0);
+    for send in sends {
+        match send.await {
+            Ok(Ok(mf_cnt)) => {
+                result = result.map(|total| total + mf_cnt);
+            }
+            Ok(Err(err)) => result = Err(err),
             Err(err) => {
-                timer.stop_and_discard();
-                return err;
+                error!("unable to send to remote_write; {err}");
+                result = Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "DROPPING METRICS; unable to send to remote_write",
+                ));
             }
         }
     }
-    CONSUMER_OPS_SUBMITTED.inc_by(mf_cnt as f64);
-    timer.observe_duration();
-    (StatusCode::CREATED, "created")
+
+    match result {
+        Ok(mf_cnt) => {
+            CONSUMER_OPS_SUBMITTED.inc_by(mf_cnt as f64);
+            timer.observe_duration();
+            (StatusCode::CREATED, "created")
+        }
+        Err(err) => {
+            timer.stop_and_discard();
+            err
+        }
+    }
+}
+
+/// replay the buffered batches of a target, oldest first, until there are none left or the
+/// target is still unavailable
+async fn replay(target: &RemoteWriteTarget, buffer: &WriteAheadBuffer) -> Result<()> {
+    let _replay = buffer.start_replay();
+    while let Some((seq, batch)) = buffer.oldest() {
+        match post(&target.client, batch.into()).await {
+            Ok(response) if response.status().is_success() => buffer.remove(seq, "replayed")?,
+            Ok(response) if is_retriable(response.status()) => {
+                debug!(
+                    "{} is still unavailable, response status {}",
+                    target.name(),
+                    response.status()
+                );
+                return Ok(());
+            }
+            Ok(response) => {
+                // it will never be accepted, most likely err-mimir-sample-out-of-order
+                let status = response.status();
+                let body = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "response body cannot be decoded".into());
+                error!(
+                    "DROPPING BUFFERED METRICS for {}, ({}) ERROR: {:?}",
+                    target.name(),
+                    status,
+                    body
+                );
+                buffer.remove(seq, "dropped")?
+            }
+            Err(error) => {
+                debug!("{} is still unavailable; {error}", target.name());
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// replay_buffered periodically replays the batches a target failed to receive. It returns
+/// straight away if the target has no buffer.
+pub async fn replay_buffered(target: RemoteWriteTarget) {
+    let (Some(buffer), Some(config)) =
+        (target.buffer.clone(), target.client.settings.buffer.clone())
+    else {
+        return;
+    };
+    let mut interval = tokio::time::interval(config.replay_interval);
+    loop {
+        interval.tick().await;
+        if let Err(error) = replay(&target, &buffer).await {
+            error!(
+                "unable to replay buffered metrics for {}; {error}",
+                target.name()
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::admin::{Labels, ReqwestClient};
+use crate::admin::{Labels, RemoteWriteTarget};
 use crate::consumer::{convert_to_remote_write, populate_labels, NodeMetric};
 use crate::histogram_relay::HistogramRelay;
 use crate::middleware::LenDelimProtobuf;
@@ -14,6 +14,7 @@ use once_cell::sync::Lazy;
 use prometheus::{register_counter_vec, register_histogram_vec};
 use prometheus::{CounterVec, HistogramVec};
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 static HANDLER_HITS: Lazy<CounterVec> = Lazy::new(|| {
     register_counter_vec!(
@@ -38,12 +39,12 @@ static HTTP_HANDLER_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
 });
 
 /// Publish handler which receives metrics from nodes.  Nodes will call us at this endpoint
-/// and we relay them to the upstream tsdb
+/// and we relay them to the upstream tsdbs
 ///
 /// Clients will receive a response after successfully relaying the metrics upstream
 pub async fn publish_metrics(
     Extension(labels): Extension<Labels>,
-    Extension(client): Extension<ReqwestClient>,
+    Extension(targets): Extension<Arc<Vec<RemoteWriteTarget>>>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Extension(SuiPeer {
         name, public_key, ..
@@ -60,7 +61,7 @@ pub async fn publish_metrics(
     let data = populate_labels(name, labels.network, labels.inventory_hostname, data);
     relay.submit(data.clone());
     let response = convert_to_remote_write(
-        client.clone(),
+        &targets,
         NodeMetric {
             data,
             peer_addr: Multiaddr::from(addr.ip()),
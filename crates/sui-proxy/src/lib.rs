@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 pub mod admin;
+pub mod buffer;
 pub mod config;
 pub mod consumer;
 pub mod handlers;
@@ -9,6 +10,7 @@ pub mod metrics;
 pub mod middleware;
 pub mod peers;
 pub mod prom_to_mimir;
+pub mod relabel;
 pub mod remote_write;
 
 /// var extracts environment variables at runtime with a default fallback value
@@ -97,7 +99,7 @@ mod tests {
             )
             .unwrap();
 
-        let client = admin::make_reqwest_client(
+        let target = admin::make_remote_write_target(
             RemoteWriteConfig {
                 url: dummy_remote_write_url.to_owned(),
                 username: "bar".into(),
@@ -105,14 +107,15 @@ mod tests {
                 ..Default::default()
             },
             "dummy user agent",
-        );
+        )
+        .unwrap();
 
         let app = admin::app(
             Labels {
                 network: "unittest-network".into(),
                 inventory_hostname: "ansible_inventory_name".into(),
             },
-            client,
+            vec![target],
             HistogramRelay::new(),
             Some(allower.clone()),
         );
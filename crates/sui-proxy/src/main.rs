@@ -7,10 +7,10 @@ use sui_proxy::config::ProxyConfig;
 use sui_proxy::{
     admin::{
         app, create_server_cert_default_allow, create_server_cert_enforce_peer,
-        make_reqwest_client, server, Labels,
+        make_remote_write_target, server, Labels,
     },
     config::load,
-    histogram_relay, metrics,
+    consumer, histogram_relay, metrics,
 };
 use sui_tls::TlsAcceptor;
 use telemetry_subscribers::TelemetryConfig;
@@ -64,10 +64,15 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     let config: ProxyConfig = load(args.config)?;
+    config.validate()?;
 
     info!(
         "listen on {:?} send to {:?}",
-        config.listen_address, config.remote_write.url
+        config.listen_address,
+        config
+            .remote_writes()
+            .map(|remote_write| &remote_write.url)
+            .collect::<Vec<_>>()
     );
 
     let listener = std::net::TcpListener::bind(config.listen_address).unwrap();
@@ -86,7 +91,13 @@ async fn main() -> Result<()> {
     let histogram_listener = std::net::TcpListener::bind(config.histogram_address).unwrap();
     let metrics_listener = std::net::TcpListener::bind(config.metrics_address).unwrap();
     let acceptor = TlsAcceptor::new(tls_config);
-    let client = make_reqwest_client(config.remote_write, APP_USER_AGENT);
+    let targets = config
+        .remote_writes()
+        .map(|remote_write| make_remote_write_target(remote_write.clone(), APP_USER_AGENT))
+        .collect::<Result<Vec<_>>>()?;
+    for target in &targets {
+        tokio::spawn(consumer::replay_buffered(target.clone()));
+    }
     let histogram_relay = histogram_relay::start_prometheus_server(histogram_listener);
     let registry_service = metrics::start_prometheus_server(metrics_listener);
     let prometheus_registry = registry_service.default_registry();
@@ -98,7 +109,7 @@ async fn main() -> Result<()> {
             network: config.network,
             inventory_hostname: config.inventory_hostname,
         },
-        client,
+        targets,
         histogram_relay,
         allower,
     );
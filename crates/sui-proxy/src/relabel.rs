@@ -0,0 +1,193 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::config::RelabelConfig;
+use anyhow::{Context, Result};
+use prometheus::proto::{self, MetricFamily};
+use protobuf::RepeatedField;
+use regex::Regex;
+
+/// A compiled RelabelConfig
+#[derive(Debug)]
+enum Rule {
+    Replace { label: String, value: String },
+    Rename { from: String, to: String },
+    LabelDrop { label: String },
+    Keep(Regex),
+    Drop(Regex),
+}
+
+/// Relabeler applies the relabel rules of a remote_write target to the metrics sent to it
+#[derive(Debug, Default)]
+pub struct Relabeler {
+    rules: Vec<Rule>,
+}
+
+/// like prometheus, regexes have to match the whole metric name
+fn anchored(regex: &str) -> Result<Regex> {
+    Regex::new(&format!("^(?:{regex})$")).context(format!("invalid relabel regex {regex:?}"))
+}
+
+impl Relabeler {
+    pub fn new(configs: &[RelabelConfig]) -> Result<Self> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                Ok(match config {
+                    RelabelConfig::Replace { label, value } => Rule::Replace {
+                        label: label.to_owned(),
+                        value: value.to_owned(),
+                    },
+                    RelabelConfig::Rename { from, to } => Rule::Rename {
+                        from: from.to_owned(),
+                        to: to.to_owned(),
+                    },
+                    RelabelConfig::LabelDrop { label } => Rule::LabelDrop {
+                        label: label.to_owned(),
+                    },
+                    RelabelConfig::Keep { regex } => Rule::Keep(anchored(regex)?),
+                    RelabelConfig::Drop { regex } => Rule::Drop(anchored(regex)?),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// apply our rules, in order, to the given metric families
+    pub fn apply(&self, mut data: Vec<MetricFamily>) -> Vec<MetricFamily> {
+        for rule in &self.rules {
+            match rule {
+                Rule::Keep(regex) => data.retain(|mf| regex.is_match(mf.get_name())),
+                Rule::Drop(regex) => data.retain(|mf| !regex.is_match(mf.get_name())),
+                Rule::Replace { label, value } => relabel(&mut data, |labels| {
+                    match labels.iter_mut().find(|l| l.get_name() == label) {
+                        Some(existing) => existing.set_value(value.to_owned()),
+                        None => {
+                            let mut lp = proto::LabelPair::default();
+                            lp.set_name(label.to_owned());
+                            lp.set_value(value.to_owned());
+                            labels.push(lp);
+                        }
+                    }
+                }),
+                Rule::Rename { from, to } => relabel(&mut data, |labels| {
+                    if labels.iter().any(|l| l.get_name() == from) {
+                        labels.retain(|l| l.get_name() != to);
+                        for l in labels.iter_mut().filter(|l| l.get_name() == from) {
+                            l.set_name(to.to_owned());
+                        }
+                    }
+                }),
+                Rule::LabelDrop { label } => {
+                    relabel(&mut data, |labels| labels.retain(|l| l.get_name() != label))
+                }
+            }
+        }
+        data
+    }
+}
+
+/// run f on the labels of every metric
+fn relabel(data: &mut [MetricFamily], f: impl Fn(&mut Vec<proto::LabelPair>)) {
+    for mf in data.iter_mut() {
+        for m in mf.mut_metric() {
+            // proto::Metric labels are a RepeatedField, which can't remove elements in place
+            let mut labels = m.take_label().into_vec();
+            f(&mut labels);
+            m.set_label(RepeatedField::from_vec(labels));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prom_to_mimir::tests::{
+        create_counter, create_labels, create_metric_counter, create_metric_family,
+    };
+
+    fn metric_family(name: &str, labels: Vec<(&str, &str)>) -> MetricFamily {
+        create_metric_family(
+            name,
+            "some help this is",
+            None,
+            RepeatedField::from_vec(vec![create_metric_counter(
+                RepeatedField::from_vec(create_labels(labels)),
+                create_counter(2046.0),
+            )]),
+        )
+    }
+
+    #[test]
+    fn relabel_labels() {
+        let relabeler = Relabeler::new(&[
+            RelabelConfig::Rename {
+                from: "host".into(),
+                to: "instance".into(),
+            },
+            RelabelConfig::Replace {
+                label: "network".into(),
+                value: "mainnet".into(),
+            },
+            RelabelConfig::Replace {
+                label: "cluster".into(),
+                value: "eu".into(),
+            },
+            RelabelConfig::LabelDrop {
+                label: "some".into(),
+            },
+        ])
+        .unwrap();
+
+        let data = relabeler.apply(vec![metric_family(
+            "foo_metric",
+            vec![
+                ("some", "label"),
+                ("network", "testnet"),
+                ("instance", "stale"),
+                ("host", "validator-0"),
+            ],
+        )]);
+        assert_eq!(
+            data[0].get_metric()[0].get_label(),
+            &create_labels(vec![
+                ("network", "mainnet"),
+                ("instance", "validator-0"),
+                ("cluster", "eu"),
+            ])
+        );
+    }
+
+    #[test]
+    fn relabel_metric_families() {
+        let data = || {
+            vec![
+                metric_family("go_goroutines", vec![]),
+                metric_family("sui_tps", vec![]),
+                metric_family("sui_go_version", vec![]),
+            ]
+        };
+        let names = |data: Vec<MetricFamily>| {
+            data.iter()
+                .map(|mf| mf.get_name().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let drop = Relabeler::new(&[RelabelConfig::Drop {
+            regex: "go_.*".into(),
+        }])
+        .unwrap();
+        assert_eq!(names(drop.apply(data())), ["sui_tps", "sui_go_version"]);
+
+        let keep = Relabeler::new(&[RelabelConfig::Keep {
+            regex: "sui_tps|go_.*".into(),
+        }])
+        .unwrap();
+        assert_eq!(names(keep.apply(data())), ["go_goroutines", "sui_tps"]);
+
+        assert!(Relabeler::new(&[RelabelConfig::Keep { regex: "(".into() }]).is_err());
+    }
+}
@@ -5,13 +5,21 @@ use crate::types::ReplayEngineError;
 use crate::types::EPOCH_CHANGE_STRUCT_TAG;
 use async_trait::async_trait;
 use futures::future::join_all;
+use futures::future::AbortHandle;
+use indicatif::MultiProgress;
 use lru::LruCache;
 use move_core_types::parser::parse_struct_tag;
+use object_store::DynObjectStore;
 use parking_lot::RwLock;
 use rand::Rng;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
+use sui_archival::{read_checkpoint_files, read_manifest, FileMetadata, FileType, Manifest};
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::NodeStateDump;
 use sui_json_rpc::api::QUERY_MAX_RESULT_LIMIT;
 use sui_json_rpc_types::EventFilter;
@@ -21,16 +29,23 @@ use sui_json_rpc_types::SuiObjectData;
 use sui_json_rpc_types::SuiObjectDataOptions;
 use sui_json_rpc_types::SuiObjectResponse;
 use sui_json_rpc_types::SuiPastObjectResponse;
+use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::SuiClient;
+use sui_snapshot::reader::read_snapshot_chain;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_types::base_types::{ObjectID, SequenceNumber, VersionNumber};
 use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, FullCheckpointContents};
 use sui_types::object::Object;
+use sui_types::storage::ObjectStore;
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
 use sui_types::transaction::SenderSignedData;
 use sui_types::transaction::TransactionDataAPI;
 use sui_types::transaction::{EndOfEpochTransactionKind, TransactionKind};
-use tracing::error;
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+use tracing::{error, info};
 
 /// This trait defines the interfaces for fetching data from some local or remote store
 #[async_trait]
@@ -89,29 +104,37 @@ pub(crate) trait DataFetcher {
 pub enum Fetchers {
     Remote(RemoteFetcher),
     NodeStateDump(NodeStateDumpFetcher),
+    Archive(ArchiveFetcher),
 }
 
 impl Fetchers {
     pub fn as_remote(&self) -> &RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::Archive(_) => panic!("not a remote fetcher"),
         }
     }
 
     pub fn into_remote(self) -> RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::Archive(_) => panic!("not a remote fetcher"),
         }
     }
 
     pub fn as_node_state_dump(&self) -> &NodeStateDumpFetcher {
         match self {
-            Fetchers::Remote(_) => panic!("not a node state dump fetcher"),
+            Fetchers::Remote(_) | Fetchers::Archive(_) => panic!("not a node state dump fetcher"),
             Fetchers::NodeStateDump(q) => q,
         }
     }
+
+    pub fn as_archive(&self) -> &ArchiveFetcher {
+        match self {
+            Fetchers::Remote(_) | Fetchers::NodeStateDump(_) => panic!("not an archive fetcher"),
+            Fetchers::Archive(q) => q,
+        }
+    }
 }
 
 #[async_trait]
@@ -124,6 +147,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_versioned(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_versioned(objects).await,
+            Fetchers::Archive(q) => q.multi_get_versioned(objects).await,
         }
     }
 
@@ -134,6 +158,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_latest(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_latest(objects).await,
+            Fetchers::Archive(q) => q.multi_get_latest(objects).await,
         }
     }
 
@@ -144,6 +169,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_checkpoint_txs(id).await,
             Fetchers::NodeStateDump(q) => q.get_checkpoint_txs(id).await,
+            Fetchers::Archive(q) => q.get_checkpoint_txs(id).await,
         }
     }
 
@@ -154,6 +180,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_transaction(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_transaction(tx_digest).await,
+            Fetchers::Archive(q) => q.get_transaction(tx_digest).await,
         }
     }
 
@@ -164,6 +191,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_loaded_child_objects(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_loaded_child_objects(tx_digest).await,
+            Fetchers::Archive(q) => q.get_loaded_child_objects(tx_digest).await,
         }
     }
 
@@ -171,6 +199,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_latest_checkpoint_sequence_number().await,
             Fetchers::NodeStateDump(q) => q.get_latest_checkpoint_sequence_number().await,
+            Fetchers::Archive(q) => q.get_latest_checkpoint_sequence_number().await,
         }
     }
 
@@ -188,6 +217,10 @@ impl DataFetcher for Fetchers {
                 q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
                     .await
             }
+            Fetchers::Archive(q) => {
+                q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
+                    .await
+            }
         }
     }
 
@@ -198,6 +231,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
+            Fetchers::Archive(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
         }
     }
 
@@ -208,6 +242,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_change_events(reverse).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_change_events(reverse).await,
+            Fetchers::Archive(q) => q.get_epoch_change_events(reverse).await,
         }
     }
 }
@@ -774,3 +809,417 @@ impl DataFetcher for NodeStateDumpFetcher {
         unimplemented!("get_epoch_change_events for state dump is not implemented")
     }
 }
+
+const ARCHIVE_FILE_CACHE_CAPACITY: Option<NonZeroUsize> = NonZeroUsize::new(16);
+
+/// Created in the base state directory once the snapshot is fully restored into it
+const BASE_STATE_RESTORED_MARKER: &str = "RESTORED";
+
+type ArchivedCheckpoint = (CertifiedCheckpointSummary, FullCheckpointContents);
+
+/// Fetches checkpoints, transactions and effects from a checkpoint archive on disk, and objects
+/// from the live object set at the end of the previous epoch, restored from a formal snapshot.
+/// Objects written during the replayed epoch are in neither, so they are recorded from the
+/// outputs of replayed transactions whose effects match (see `record_outputs`). The epoch must
+/// therefore be replayed in checkpoint order from its first checkpoint, and only the epoch right
+/// after the snapshot can be replayed.
+#[derive(Clone)]
+pub struct ArchiveFetcher {
+    archive_store: Arc<DynObjectStore>,
+    /// Pairs of summary and content files of the epoch, sorted by checkpoint
+    files: Arc<Vec<(FileMetadata, FileMetadata)>>,
+    /// Live object set at the end of the previous epoch
+    base_state: Arc<AuthorityPerpetualTables>,
+    epoch: u64,
+    protocol_version: u64,
+    epoch_start_timestamp_ms: u64,
+    reference_gas_price: u64,
+    /// The change epoch transaction which started the epoch
+    epoch_change_tx: TransactionDigest,
+    /// Recently read archive files, keyed by their first checkpoint
+    file_cache: Arc<RwLock<LruCache<u64, Arc<Vec<ArchivedCheckpoint>>>>>,
+    /// Checkpoint of every transaction read from the archive so far
+    tx_checkpoints: Arc<RwLock<HashMap<TransactionDigest, u64>>>,
+    /// Objects written by the transactions replayed so far
+    written_objects: Arc<RwLock<BTreeMap<(ObjectID, SequenceNumber), Object>>>,
+    /// Latest version of the objects written by the transactions replayed so far, or `None` if
+    /// they were deleted or wrapped since
+    latest_objects: Arc<RwLock<BTreeMap<ObjectID, Option<Object>>>>,
+}
+
+impl ArchiveFetcher {
+    /// Opens the archive in `archive_path` to replay `epoch`. The formal snapshot of the previous
+    /// epoch in `snapshot_path` is restored into `db_path`, unless it was restored there before.
+    pub async fn new(
+        archive_path: PathBuf,
+        snapshot_path: PathBuf,
+        db_path: PathBuf,
+        epoch: u64,
+        download_concurrency: NonZeroUsize,
+    ) -> Result<Self, ReplayEngineError> {
+        let base_epoch = epoch
+            .checked_sub(1)
+            .ok_or(ReplayEngineError::EpochNotSupported { epoch })?;
+        let archive_store = local_store_config(archive_path).make()?;
+        let manifest = read_manifest(archive_store.clone()).await?;
+        let files = epoch_files(&manifest, epoch);
+        if files.is_empty() {
+            return Err(ReplayEngineError::UnableToDetermineCheckpoint { epoch });
+        }
+        let base_state =
+            restore_base_state(base_epoch, snapshot_path, &db_path, download_concurrency).await?;
+        let system_state = get_sui_system_state(&*base_state)?;
+        if system_state.epoch() != epoch {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "State restored in {} from the snapshot of epoch {} is for epoch {}, expected {}",
+                    db_path.display(),
+                    base_epoch,
+                    system_state.epoch(),
+                    epoch
+                ),
+            });
+        }
+        // The system state object is last written by the change epoch transaction
+        let epoch_change_tx = base_state
+            .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)?
+            .ok_or(ReplayEngineError::ObjectNotExist {
+                id: SUI_SYSTEM_STATE_OBJECT_ID,
+            })?
+            .previous_transaction;
+        Ok(Self {
+            archive_store,
+            files: Arc::new(files),
+            base_state,
+            epoch,
+            protocol_version: system_state.protocol_version(),
+            epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+            reference_gas_price: system_state.reference_gas_price(),
+            epoch_change_tx,
+            file_cache: Arc::new(RwLock::new(LruCache::new(
+                ARCHIVE_FILE_CACHE_CAPACITY.expect("Cache size must be non zero"),
+            ))),
+            tx_checkpoints: Arc::new(RwLock::new(HashMap::new())),
+            written_objects: Arc::new(RwLock::new(BTreeMap::new())),
+            latest_objects: Arc::new(RwLock::new(BTreeMap::new())),
+        })
+    }
+
+    /// The epoch of checkpoint `id` in the archive in `archive_path`
+    pub async fn epoch_of_checkpoint(
+        archive_path: &Path,
+        id: u64,
+    ) -> Result<u64, ReplayEngineError> {
+        let manifest =
+            read_manifest(local_store_config(archive_path.to_path_buf()).make()?).await?;
+        manifest
+            .files()
+            .iter()
+            .find(|f| f.checkpoint_seq_range.contains(&id))
+            .map(|f| f.epoch_num)
+            .ok_or(ReplayEngineError::GeneralError {
+                err: format!("Checkpoint {} is not in the archive", id),
+            })
+    }
+
+    /// The first and last checkpoints of `epoch` in the archive in `archive_path`
+    pub async fn checkpoints_for_epoch_in(
+        archive_path: &Path,
+        epoch: u64,
+    ) -> Result<(u64, u64), ReplayEngineError> {
+        let manifest =
+            read_manifest(local_store_config(archive_path.to_path_buf()).make()?).await?;
+        checkpoint_range(&epoch_files(&manifest, epoch))
+            .ok_or(ReplayEngineError::UnableToDetermineCheckpoint { epoch })
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn protocol_version(&self) -> u64 {
+        self.protocol_version
+    }
+
+    pub fn epoch_change_tx(&self) -> TransactionDigest {
+        self.epoch_change_tx
+    }
+
+    /// The first and last checkpoints of the epoch
+    pub fn checkpoints_for_epoch(&self) -> (u64, u64) {
+        checkpoint_range(&self.files).expect("Archive has files for the epoch")
+    }
+
+    /// Records the objects written, and the objects deleted or wrapped, by a replayed transaction
+    /// whose effects match
+    pub fn record_outputs(&self, written: &BTreeMap<ObjectID, Object>, removed: &[ObjectID]) {
+        let mut written_objects = self.written_objects.write();
+        let mut latest_objects = self.latest_objects.write();
+        for (id, object) in written {
+            written_objects.insert((*id, object.version()), object.clone());
+            latest_objects.insert(*id, Some(object.clone()));
+        }
+        for id in removed {
+            latest_objects.insert(*id, None);
+        }
+    }
+
+    async fn get_checkpoint(&self, id: u64) -> Result<ArchivedCheckpoint, ReplayEngineError> {
+        let index = self
+            .files
+            .partition_point(|(summary, _)| summary.checkpoint_seq_range.end <= id);
+        let (summary_metadata, content_metadata) = self
+            .files
+            .get(index)
+            .filter(|(summary, _)| summary.checkpoint_seq_range.contains(&id))
+            .ok_or(ReplayEngineError::GeneralError {
+                err: format!(
+                    "Checkpoint {} is not in the archive for epoch {}",
+                    id, self.epoch
+                ),
+            })?;
+        let start = summary_metadata.checkpoint_seq_range.start;
+        let cached = self.file_cache.write().get(&start).cloned();
+        let file = match cached {
+            Some(file) => file,
+            None => {
+                let checkpoints = read_checkpoint_files(
+                    summary_metadata,
+                    content_metadata,
+                    self.archive_store.clone(),
+                )
+                .await?;
+                {
+                    let mut tx_checkpoints = self.tx_checkpoints.write();
+                    for (summary, contents) in checkpoints.iter() {
+                        for tx in contents.iter() {
+                            tx_checkpoints
+                                .insert(*tx.transaction.digest(), summary.sequence_number);
+                        }
+                    }
+                }
+                let file = Arc::new(checkpoints);
+                self.file_cache.write().put(start, file.clone());
+                file
+            }
+        };
+        file.iter()
+            .find(|(summary, _)| summary.sequence_number == id)
+            .cloned()
+            .ok_or_else(|| ReplayEngineError::GeneralError {
+                err: format!(
+                    "Archive file for checkpoints {:?} is missing checkpoint {}",
+                    summary_metadata.checkpoint_seq_range, id
+                ),
+            })
+    }
+}
+
+#[async_trait]
+impl DataFetcher for ArchiveFetcher {
+    #![allow(implied_bounds_entailment)]
+    async fn multi_get_versioned(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|(id, version)| {
+                if let Some(object) = self.written_objects.read().get(&(*id, *version)) {
+                    return Ok(object.clone());
+                }
+                self.base_state.get_object_by_key(id, *version)?.ok_or(
+                    ReplayEngineError::ObjectVersionNotFound {
+                        id: *id,
+                        version: *version,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    async fn multi_get_latest(
+        &self,
+        objects: &[ObjectID],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|id| match self.latest_objects.read().get(id) {
+                Some(Some(object)) => Ok(object.clone()),
+                Some(None) => Err(ReplayEngineError::ObjectNotExist { id: *id }),
+                None => self
+                    .base_state
+                    .get_object(id)?
+                    .ok_or(ReplayEngineError::ObjectNotExist { id: *id }),
+            })
+            .collect()
+    }
+
+    async fn get_checkpoint_txs(
+        &self,
+        id: u64,
+    ) -> Result<Vec<TransactionDigest>, ReplayEngineError> {
+        let (_, contents) = self.get_checkpoint(id).await?;
+        Ok(contents.iter().map(|tx| *tx.transaction.digest()).collect())
+    }
+
+    async fn get_transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<SuiTransactionBlockResponse, ReplayEngineError> {
+        let checkpoint = self.tx_checkpoints.read().get(tx_digest).copied().ok_or(
+            ReplayEngineError::GeneralError {
+                err: format!(
+                    "Transaction {} is not in the archived checkpoints read so far",
+                    tx_digest
+                ),
+            },
+        )?;
+        let (summary, contents) = self.get_checkpoint(checkpoint).await?;
+        let tx = contents
+            .iter()
+            .find(|tx| tx.transaction.digest() == tx_digest)
+            .ok_or_else(|| ReplayEngineError::GeneralError {
+                err: format!(
+                    "Transaction {} is missing from its archived checkpoint {}",
+                    tx_digest, checkpoint
+                ),
+            })?;
+        Ok(SuiTransactionBlockResponse {
+            raw_transaction: bcs::to_bytes(tx.transaction.data())
+                .map_err(|e| ReplayEngineError::GeneralError { err: e.to_string() })?,
+            effects: Some(SuiTransactionBlockEffects::try_from(tx.effects.clone())?),
+            timestamp_ms: Some(summary.timestamp_ms),
+            checkpoint: Some(checkpoint),
+            ..SuiTransactionBlockResponse::new(*tx_digest)
+        })
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        _tx_digest: &TransactionDigest,
+    ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
+        // Not archived. Objects are replayed in order, so child objects are read at their latest
+        // version when the transaction loads them instead
+        Ok(vec![])
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, ReplayEngineError> {
+        Ok(self.checkpoints_for_epoch().1)
+    }
+
+    async fn fetch_random_transaction(
+        &self,
+        checkpoint_id_start: Option<u64>,
+        checkpoint_id_end: Option<u64>,
+    ) -> Result<TransactionDigest, ReplayEngineError> {
+        let (first, last) = self.checkpoints_for_epoch();
+        let start = checkpoint_id_start.unwrap_or(first).max(first);
+        let end = checkpoint_id_end.unwrap_or(last).min(last);
+        if start > end {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "No checkpoints of epoch {} (checkpoints {} to {}) between {:?} and {:?}",
+                    self.epoch, first, last, checkpoint_id_start, checkpoint_id_end
+                ),
+            });
+        }
+        let checkpoint = rand::thread_rng().gen_range(start..=end);
+        let txs = self.get_checkpoint_txs(checkpoint).await?;
+        Ok(txs[rand::thread_rng().gen_range(0..txs.len())])
+    }
+
+    async fn get_epoch_start_timestamp_and_rgp(
+        &self,
+        epoch_id: u64,
+    ) -> Result<(u64, u64), ReplayEngineError> {
+        if epoch_id != self.epoch {
+            return Err(ReplayEngineError::EpochNotSupported { epoch: epoch_id });
+        }
+        Ok((self.epoch_start_timestamp_ms, self.reference_gas_price))
+    }
+
+    async fn get_epoch_change_events(
+        &self,
+        _reverse: bool,
+    ) -> Result<Vec<SuiEvent>, ReplayEngineError> {
+        // The epoch is known from the base state instead, see `protocol_version` and
+        // `epoch_change_tx`
+        Err(ReplayEngineError::GeneralError {
+            err: "Epoch change events are not archived".to_string(),
+        })
+    }
+}
+
+fn local_store_config(directory: PathBuf) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(directory),
+        ..Default::default()
+    }
+}
+
+fn epoch_files(manifest: &Manifest, epoch: u64) -> Vec<(FileMetadata, FileMetadata)> {
+    let mut files = manifest.files();
+    files.retain(|f| f.epoch_num == epoch);
+    files.sort_by_key(|f| f.checkpoint_seq_range.start);
+    let (summaries, contents): (Vec<_>, Vec<_>) = files
+        .into_iter()
+        .partition(|f| f.file_type == FileType::CheckpointSummary);
+    summaries.into_iter().zip(contents).collect()
+}
+
+fn checkpoint_range(files: &[(FileMetadata, FileMetadata)]) -> Option<(u64, u64)> {
+    let first = files.first()?.0.checkpoint_seq_range.start;
+    let last = files.last()?.0.checkpoint_seq_range.end.checked_sub(1)?;
+    Some((first, last))
+}
+
+/// Restores the live object set at the end of `epoch` from the formal snapshots in
+/// `snapshot_path`, which takes a while, so a fully restored state is reused on later runs.
+async fn restore_base_state(
+    epoch: u64,
+    snapshot_path: PathBuf,
+    db_path: &Path,
+    download_concurrency: NonZeroUsize,
+) -> anyhow::Result<Arc<AuthorityPerpetualTables>> {
+    let path = db_path.join(format!("epoch_{}", epoch));
+    let marker = path.join(BASE_STATE_RESTORED_MARKER);
+    if marker.exists() {
+        info!(
+            "Using the state at the end of epoch {} in {}",
+            epoch,
+            path.display()
+        );
+        return Ok(Arc::new(AuthorityPerpetualTables::open(
+            &path.join("store"),
+            None,
+        )));
+    }
+    if path.exists() {
+        fs::remove_dir_all(&path)?;
+    }
+    info!(
+        "Restoring the state at the end of epoch {} into {}",
+        epoch,
+        path.display()
+    );
+    let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
+    let staging_path = path.join("snapshot");
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    read_snapshot_chain(
+        epoch,
+        &local_store_config(snapshot_path),
+        &local_store_config(staging_path.clone()),
+        usize::MAX,
+        download_concurrency,
+        MultiProgress::new(),
+        &perpetual_db,
+        abort_registration,
+        None,
+    )
+    .await?;
+    fs::remove_dir_all(staging_path)?;
+    fs::File::create(marker)?;
+    Ok(Arc::new(perpetual_db))
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_recursion::async_recursion;
-use clap::Parser;
+use clap::{Args, Parser};
 use config::ReplayableNetworkConfigSet;
 use fuzz::ReplayFuzzer;
 use fuzz::ReplayFuzzerConfig;
@@ -11,15 +11,19 @@ use sui_types::message_envelope::Message;
 use tracing::warn;
 use transaction_provider::{FuzzStartPoint, TransactionSource};
 
-use crate::replay::ExecutionSandboxState;
-use crate::replay::LocalExec;
+use crate::data_fetcher::ArchiveFetcher;
 use crate::replay::ProtocolVersionSummary;
+use crate::replay::{build_package_override, LocalExec};
+use std::collections::BTreeMap;
 use std::env;
 use std::io::BufRead;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use sui_config::node::ExpensiveSafetyCheckConfig;
+use sui_json_rpc_types::SuiTransactionBlockEffects;
+use sui_types::base_types::ObjectID;
 use sui_types::digests::TransactionDigest;
+use sui_types::transaction::TransactionData;
 use tracing::{error, info};
 pub mod config;
 mod data_fetcher;
@@ -29,12 +33,31 @@ mod replay;
 pub mod transaction_provider;
 pub mod types;
 
+pub use replay::ExecutionSandboxState;
+
+/// Whether transactions can be profiled, which is always the case in debug builds
+pub const GAS_PROFILER_ENABLED: bool = cfg!(any(debug_assertions, feature = "gas-profiler"));
+
 static DEFAULT_SANDBOX_BASE_PATH: &str =
     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/sandbox_snapshots");
 
 #[cfg(test)]
 mod tests;
 
+/// Replay from files on disk instead of a fullnode
+#[derive(Args, Clone, Debug, Default)]
+pub struct ArchiveSource {
+    /// Directory of a checkpoint archive to read checkpoints, transactions and effects from
+    #[arg(long, requires_all = ["snapshot_path", "db_path"])]
+    pub archive_path: Option<PathBuf>,
+    /// Directory of formal snapshots, the one at the end of the previous epoch is the base state
+    #[arg(long, requires = "archive_path")]
+    pub snapshot_path: Option<PathBuf>,
+    /// Directory the base state is restored into, and reused from on later runs
+    #[arg(long, requires = "archive_path")]
+    pub db_path: Option<PathBuf>,
+}
+
 #[derive(Parser, Clone)]
 #[command(rename_all = "kebab-case")]
 pub enum ReplayToolCommand {
@@ -72,6 +95,13 @@ pub enum ReplayToolCommand {
         executor_version_override: Option<i64>,
         #[arg(long, short, allow_hyphen_values = true)]
         protocol_version_override: Option<i64>,
+        /// Write a gas profile of the transaction to this file, in speedscope format
+        #[arg(long)]
+        profile_output: Option<PathBuf>,
+        /// Replace the on-chain package with this ID by the Move package built from this path,
+        /// and compare the local execution with the original one. Can be repeated.
+        #[arg(long, value_name = "PACKAGE_ID=PATH", value_parser = parse_package_override)]
+        package_override: Vec<(ObjectID, PathBuf)>,
     },
 
     /// Replay transactions listed in a file
@@ -95,6 +125,7 @@ pub enum ReplayToolCommand {
     },
 
     /// Replay all transactions in a range of checkpoints
+    /// From an archive, the epoch is replayed by a single task up to the end of the range
     #[command(name = "ch")]
     ReplayCheckpoints {
         #[arg(long, short)]
@@ -105,6 +136,8 @@ pub enum ReplayToolCommand {
         terminate_early: bool,
         #[arg(long, short, default_value = "16")]
         max_tasks: u64,
+        #[command(flatten)]
+        archive: ArchiveSource,
     },
 
     /// Replay all transactions in an epoch
@@ -116,6 +149,8 @@ pub enum ReplayToolCommand {
         terminate_early: bool,
         #[arg(long, short, default_value = "16")]
         max_tasks: u64,
+        #[command(flatten)]
+        archive: ArchiveSource,
     },
 
     /// Run the replay based fuzzer
@@ -175,6 +210,8 @@ pub async fn execute_replay_command(
                 use_authority,
                 None,
                 None,
+                None,
+                BTreeMap::new(),
             )
             .await?;
 
@@ -257,6 +294,8 @@ pub async fn execute_replay_command(
                             use_authority,
                             None,
                             None,
+                            None,
+                            BTreeMap::new(),
                         )
                         .await?;
 
@@ -346,8 +385,22 @@ pub async fn execute_replay_command(
             diag,
             executor_version_override,
             protocol_version_override,
+            profile_output,
+            package_override,
         } => {
             let tx_digest = TransactionDigest::from_str(&tx_digest)?;
+            if let Some(profile_output) = &profile_output {
+                create_profile_output(profile_output)?;
+            }
+            let mut package_overrides = BTreeMap::new();
+            for (package_id, path) in package_override {
+                info!(
+                    "Building override for package {} from {:?}",
+                    package_id, path
+                );
+                package_overrides.insert(package_id, build_package_override(path)?);
+            }
+            let compare_executions = !package_overrides.is_empty();
             info!("Executing tx: {}", tx_digest);
             let sandbox_state = LocalExec::replay_with_network_config(
                 rpc_url,
@@ -357,6 +410,8 @@ pub async fn execute_replay_command(
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                profile_output,
+                package_overrides,
             )
             .await?;
 
@@ -367,9 +422,14 @@ pub async fn execute_replay_command(
                 println!("{}", sandbox_state.local_exec_effects);
             }
 
-            sandbox_state.check_effects()?;
-
-            println!("Execution finished successfully. Local and on-chain effects match.");
+            // Overridden packages are expected to change the effects, so report the differences
+            // instead of failing on them
+            if compare_executions {
+                println!("{}", sandbox_state.compare_executions());
+            } else {
+                sandbox_state.check_effects()?;
+                println!("Execution finished successfully. Local and on-chain effects match.");
+            }
             Some((1u64, 1u64))
         }
 
@@ -422,8 +482,21 @@ pub async fn execute_replay_command(
             end,
             terminate_early,
             max_tasks,
+            archive,
         } => {
             assert!(start <= end, "Start checkpoint must be <= end checkpoint");
+            if archive.archive_path.is_some() {
+                return replay_checkpoints_from_archive(
+                    archive,
+                    start,
+                    end,
+                    safety,
+                    terminate_early,
+                    use_authority,
+                )
+                .await
+                .map(Some);
+            }
             assert!(max_tasks > 0, "Max tasks must be > 0");
             let checkpoints_per_task = ((end - start + max_tasks) / max_tasks) as usize;
             let mut handles = vec![];
@@ -488,11 +561,19 @@ pub async fn execute_replay_command(
             epoch,
             terminate_early,
             max_tasks,
+            archive,
         } => {
-            let lx =
-                LocalExec::new_from_fn_url(&rpc_url.clone().expect("Url must be provided")).await?;
-
-            let (start, end) = lx.checkpoints_for_epoch(epoch).await?;
+            let (start, end) = match &archive.archive_path {
+                Some(archive_path) => {
+                    ArchiveFetcher::checkpoints_for_epoch_in(archive_path, epoch).await?
+                }
+                None => {
+                    LocalExec::new_from_fn_url(&rpc_url.clone().expect("Url must be provided"))
+                        .await?
+                        .checkpoints_for_epoch(epoch)
+                        .await?
+                }
+            };
 
             info!(
                 "Executing epoch {} (checkpoint range {}-{}) with at most {} tasks",
@@ -508,6 +589,7 @@ pub async fn execute_replay_command(
                     end,
                     terminate_early,
                     max_tasks,
+                    archive,
                 },
             )
             .await;
@@ -531,3 +613,108 @@ pub async fn execute_replay_command(
         }
     })
 }
+
+/// Re-execute a transaction that was dry run on the network locally, against the objects its
+/// dry run read, and write a gas profile of it to `profile_output`
+pub async fn profile_dry_run(
+    rpc_url: String,
+    tx_data: TransactionData,
+    dry_run_effects: SuiTransactionBlockEffects,
+    profile_output: PathBuf,
+) -> anyhow::Result<()> {
+    create_profile_output(&profile_output)?;
+    let sandbox_state = LocalExec::new_from_fn_url(&rpc_url)
+        .await?
+        .init_for_execution()
+        .await?
+        .execute_dry_run(
+            tx_data,
+            dry_run_effects,
+            ExpensiveSafetyCheckConfig::default(),
+            Some(profile_output),
+        )
+        .await?;
+    sandbox_state.check_effects()?;
+    Ok(())
+}
+
+/// Check that a gas profile can be written to `profile_output` before executing anything. The
+/// profiler only writes it once execution is over, when failing to do so can only be logged.
+fn create_profile_output(profile_output: &Path) -> anyhow::Result<()> {
+    if !GAS_PROFILER_ENABLED {
+        anyhow::bail!("Gas profiling requires a debug build or the `gas-profiler` feature");
+    }
+    std::fs::File::create(profile_output).map_err(|e| {
+        anyhow::anyhow!(
+            "Unable to create gas profile file {}: {e}",
+            profile_output.display()
+        )
+    })?;
+    Ok(())
+}
+
+fn parse_package_override(s: &str) -> anyhow::Result<(ObjectID, PathBuf)> {
+    let (package_id, path) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("Expected <PACKAGE_ID>=<PATH>, got {s}"))?;
+    Ok((ObjectID::from_str(package_id)?, PathBuf::from(path)))
+}
+
+/// Objects written during an epoch are only known from replaying the transactions which wrote
+/// them, so replaying from an archive starts at the first checkpoint of the epoch, and only the
+/// transactions in `start..=end` are counted.
+async fn replay_checkpoints_from_archive(
+    archive: ArchiveSource,
+    start: u64,
+    end: u64,
+    safety: ExpensiveSafetyCheckConfig,
+    terminate_early: bool,
+    use_authority: bool,
+) -> anyhow::Result<(u64, u64)> {
+    let (Some(archive_path), Some(snapshot_path), Some(db_path)) =
+        (archive.archive_path, archive.snapshot_path, archive.db_path)
+    else {
+        anyhow::bail!("Replaying from an archive requires an archive, snapshot and db path");
+    };
+    let epoch = ArchiveFetcher::epoch_of_checkpoint(&archive_path, start).await?;
+    let mut lx = LocalExec::new_for_archive(archive_path, snapshot_path, db_path, epoch)
+        .await?
+        .init_for_execution()
+        .await?;
+    let (first, last) = lx.checkpoints_for_epoch(epoch).await?;
+    if end > last {
+        anyhow::bail!(
+            "Checkpoints {} to {} are not all in epoch {}, which ends at checkpoint {}",
+            start,
+            end,
+            epoch,
+            last
+        );
+    }
+    if start > first {
+        info!(
+            "Replaying checkpoints {} to {} of epoch {} to build up the state at checkpoint {}",
+            first,
+            start - 1,
+            epoch,
+            start
+        );
+        let checkpoints: Vec<_> = (first..start).collect();
+        lx.execute_all_in_checkpoints(&checkpoints, &safety, false, use_authority)
+            .await?;
+    }
+
+    let time = std::time::Instant::now();
+    let checkpoints: Vec<_> = (start..=end).collect();
+    let (succeeded, total) = lx
+        .execute_all_in_checkpoints(&checkpoints, &safety, terminate_early, use_authority)
+        .await?;
+    info!(
+        "Executed {} checkpoints @ {}/{} total TXs succeeded in {} ms",
+        end - start + 1,
+        succeeded,
+        total,
+        time.elapsed().as_millis()
+    );
+    Ok((succeeded, total))
+}
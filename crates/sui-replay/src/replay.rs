@@ -4,12 +4,13 @@
 use crate::{
     config::ReplayableNetworkConfigSet,
     data_fetcher::{
-        extract_epoch_and_version, DataFetcher, Fetchers, NodeStateDumpFetcher, RemoteFetcher,
+        extract_epoch_and_version, ArchiveFetcher, DataFetcher, Fetchers, NodeStateDumpFetcher,
+        RemoteFetcher,
     },
     types::*,
 };
 use futures::executor::block_on;
-use move_binary_format::CompiledModule;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{
     account_address::AccountAddress,
@@ -18,9 +19,11 @@ use move_core_types::{
 };
 use prometheus::Registry;
 use serde::{Deserialize, Serialize};
+use shared_crypto::intent::Intent;
 use similar::{ChangeTag, TextDiff};
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
+    num::NonZeroUsize,
     path::PathBuf,
     sync::Arc,
     sync::Mutex,
@@ -38,7 +41,11 @@ use sui_core::{
 };
 use sui_execution::Executor;
 use sui_framework::BuiltInFramework;
-use sui_json_rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI};
+use sui_json_rpc_types::{
+    OwnedObjectRef, SuiEvent, SuiObjectRef, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI,
+};
+use sui_move_build::{BuildConfig, SuiPackageHooks};
 use sui_protocol_config::{Chain, ProtocolConfig};
 use sui_sdk::{SuiClient, SuiClientBuilder};
 use sui_types::storage::{get_module, PackageObjectArc};
@@ -52,6 +59,7 @@ use sui_types::{
     gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
     metrics::LimitsMetrics,
+    move_package::{MovePackage, TypeOrigin},
     object::{Data, Object, Owner},
     storage::get_module_by_id,
     storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
@@ -120,6 +128,145 @@ impl ExecutionSandboxState {
 
         res.join("")
     }
+
+    /// Side-by-side comparison of the original and local execution, for replays whose effects
+    /// are expected to differ, such as those against locally modified packages
+    pub fn compare_executions(&self) -> String {
+        let original = &self.transaction_info.effects;
+        let local = &self.local_exec_effects;
+        let mut rows = vec![(
+            "status".to_string(),
+            format!("{:?}", original.status()),
+            format!("{:?}", local.status()),
+        )];
+
+        let (original_gas, local_gas) = (original.gas_cost_summary(), local.gas_cost_summary());
+        for (label, original, local) in [
+            (
+                "computation cost",
+                original_gas.computation_cost as i64,
+                local_gas.computation_cost as i64,
+            ),
+            (
+                "storage cost",
+                original_gas.storage_cost as i64,
+                local_gas.storage_cost as i64,
+            ),
+            (
+                "storage rebate",
+                original_gas.storage_rebate as i64,
+                local_gas.storage_rebate as i64,
+            ),
+            (
+                "non-refundable fee",
+                original_gas.non_refundable_storage_fee as i64,
+                local_gas.non_refundable_storage_fee as i64,
+            ),
+            (
+                "net gas usage",
+                original_gas.net_gas_usage(),
+                local_gas.net_gas_usage(),
+            ),
+        ] {
+            rows.push((label.to_string(), original.to_string(), local.to_string()));
+        }
+
+        let owned_ids = |refs: &[OwnedObjectRef]| -> BTreeSet<ObjectID> {
+            refs.iter().map(|r| r.object_id()).collect()
+        };
+        let ids = |refs: &[SuiObjectRef]| -> BTreeSet<ObjectID> {
+            refs.iter().map(|r| r.object_id).collect()
+        };
+        for (label, original, local) in [
+            (
+                "created",
+                owned_ids(original.created()),
+                owned_ids(local.created()),
+            ),
+            (
+                "mutated",
+                owned_ids(original.mutated()),
+                owned_ids(local.mutated()),
+            ),
+            (
+                "unwrapped",
+                owned_ids(original.unwrapped()),
+                owned_ids(local.unwrapped()),
+            ),
+            ("deleted", ids(original.deleted()), ids(local.deleted())),
+            ("wrapped", ids(original.wrapped()), ids(local.wrapped())),
+        ] {
+            rows.push((
+                format!("{label} objects"),
+                original.len().to_string(),
+                local.len().to_string(),
+            ));
+            for id in original.union(&local) {
+                let presence = |ids: &BTreeSet<ObjectID>| {
+                    if ids.contains(id) { "yes" } else { "no" }.to_string()
+                };
+                rows.push((format!("  {id}"), presence(&original), presence(&local)));
+            }
+        }
+
+        let original_events: Option<Vec<(String, &[u8])>> =
+            self.transaction_info.events.as_ref().map(|events| {
+                events
+                    .iter()
+                    .map(|e| (e.type_.to_string(), e.bcs.as_slice()))
+                    .collect()
+            });
+        let local_events: Option<Vec<(String, &[u8])>> =
+            self.local_exec_temporary_store.as_ref().map(|store| {
+                store
+                    .events
+                    .data
+                    .iter()
+                    .map(|e| (e.type_.to_string(), e.contents.as_slice()))
+                    .collect()
+            });
+        let count = |events: &Option<Vec<_>>| {
+            events
+                .as_ref()
+                .map_or("unavailable".to_string(), |e| e.len().to_string())
+        };
+        rows.push((
+            "events".to_string(),
+            count(&original_events),
+            count(&local_events),
+        ));
+        let original_events = original_events.unwrap_or_default();
+        let local_events = local_events.unwrap_or_default();
+        for i in 0..original_events.len().max(local_events.len()) {
+            let describe = |event: Option<&(String, &[u8])>| {
+                event.map_or("-".to_string(), |(type_, contents)| {
+                    format!("{type_} ({} bytes)", contents.len())
+                })
+            };
+            let (original, local) = (original_events.get(i), local_events.get(i));
+            // Events of the same type and size may still differ in their contents
+            let (mut original_desc, local_desc) = (describe(original), describe(local));
+            if original != local && original_desc == local_desc {
+                original_desc.push_str(" [contents differ]");
+            }
+            rows.push((format!("  event {i}"), original_desc, local_desc));
+        }
+
+        let label_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+        let mut res = vec![format!(
+            "  {:label_width$}  {:value_width$}  {}",
+            "", "original", "local"
+        )];
+        for (label, original, local) in rows {
+            // Mark every row where the executions differ
+            let marker = if original == local { ' ' } else { '*' };
+            res.push(format!(
+                "{marker} {label:label_width$}  {original:value_width$}  {local}"
+            ));
+        }
+        res.join("\n")
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -234,6 +381,10 @@ pub struct LocalExec {
     // -1 implies use latest version
     // None implies use the protocol version at the time of execution
     pub protocol_version_override: Option<i64>,
+    // One can optionally write a gas profile of the executed transaction to this file
+    pub enable_profiler: Option<PathBuf>,
+    // Modules built locally, which are substituted for those of the on-chain package with this ID
+    pub package_overrides: BTreeMap<ObjectID, Vec<CompiledModule>>,
     // Retry policies due to RPC errors
     pub num_retries_for_timeout: u32,
     pub sleep_period_for_timeout: std::time::Duration,
@@ -249,7 +400,7 @@ impl LocalExec {
         let mut num_retries_for_timeout = self.num_retries_for_timeout as i64;
         while num_retries_for_timeout >= 0 {
             match self.fetcher.multi_get_versioned(objs).await {
-                Ok(objs) => return Ok(objs),
+                Ok(objs) => return self.apply_package_overrides(objs),
                 Err(ReplayEngineError::SuiRpcRequestTimeout) => {
                     warn!(
                         "RPC request timed out. Retries left {}. Sleeping for {}s",
@@ -273,7 +424,7 @@ impl LocalExec {
         let mut num_retries_for_timeout = self.num_retries_for_timeout as i64;
         while num_retries_for_timeout >= 0 {
             match self.fetcher.multi_get_latest(objs).await {
-                Ok(objs) => return Ok(objs),
+                Ok(objs) => return self.apply_package_overrides(objs),
                 Err(ReplayEngineError::SuiRpcRequestTimeout) => {
                     warn!(
                         "RPC request timed out. Retries left {}. Sleeping for {}s",
@@ -289,6 +440,15 @@ impl LocalExec {
         Err(ReplayEngineError::SuiRpcRequestTimeout)
     }
 
+    fn apply_package_overrides(&self, objs: Vec<Object>) -> Result<Vec<Object>, ReplayEngineError> {
+        objs.into_iter()
+            .map(|obj| match self.package_overrides.get(&obj.id()) {
+                Some(modules) => override_package(&obj, modules),
+                None => Ok(obj),
+            })
+            .collect()
+    }
+
     pub async fn fetch_loaded_child_refs(
         &self,
         tx_digest: &TransactionDigest,
@@ -309,6 +469,7 @@ impl LocalExec {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn replay_with_network_config(
         rpc_url: Option<String>,
         path: Option<String>,
@@ -317,7 +478,10 @@ impl LocalExec {
         use_authority: bool,
         executor_version_override: Option<i64>,
         protocol_version_override: Option<i64>,
+        enable_profiler: Option<PathBuf>,
+        package_overrides: BTreeMap<ObjectID, Vec<CompiledModule>>,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
+        #[allow(clippy::too_many_arguments)]
         async fn inner_exec(
             rpc_url: String,
             tx_digest: TransactionDigest,
@@ -325,10 +489,12 @@ impl LocalExec {
             use_authority: bool,
             executor_version_override: Option<i64>,
             protocol_version_override: Option<i64>,
+            enable_profiler: Option<PathBuf>,
+            package_overrides: BTreeMap<ObjectID, Vec<CompiledModule>>,
         ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-            LocalExec::new_from_fn_url(&rpc_url)
-                .await?
-                .init_for_execution()
+            let mut exec = LocalExec::new_from_fn_url(&rpc_url).await?;
+            exec.package_overrides = package_overrides;
+            exec.init_for_execution()
                 .await?
                 .execute_transaction(
                     &tx_digest,
@@ -336,6 +502,7 @@ impl LocalExec {
                     use_authority,
                     executor_version_override,
                     protocol_version_override,
+                    enable_profiler,
                 )
                 .await
         }
@@ -349,6 +516,8 @@ impl LocalExec {
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                enable_profiler.clone(),
+                package_overrides.clone(),
             )
             .await
             {
@@ -375,6 +544,8 @@ impl LocalExec {
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                enable_profiler.clone(),
+                package_overrides.clone(),
             )
             .await
             {
@@ -434,6 +605,8 @@ impl LocalExec {
             diag: Default::default(),
             executor_version_override: None,
             protocol_version_override: None,
+            enable_profiler: None,
+            package_overrides: BTreeMap::new(),
         })
     }
 
@@ -476,6 +649,49 @@ impl LocalExec {
             diag: Default::default(),
             executor_version_override: None,
             protocol_version_override: None,
+            enable_profiler: None,
+            package_overrides: BTreeMap::new(),
+        })
+    }
+
+    /// Replay `epoch` offline, from the checkpoint archive in `archive_path` and the formal
+    /// snapshot of the previous epoch in `snapshot_path`, which is restored into `db_path`
+    pub async fn new_for_archive(
+        archive_path: PathBuf,
+        snapshot_path: PathBuf,
+        db_path: PathBuf,
+        epoch: u64,
+    ) -> Result<Self, ReplayEngineError> {
+        // Use a throwaway metrics registry for local execution.
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(LimitsMetrics::new(&registry));
+
+        let fetcher = ArchiveFetcher::new(
+            archive_path,
+            snapshot_path,
+            db_path,
+            epoch,
+            NonZeroUsize::new(SNAPSHOT_RESTORE_CONCURRENCY).unwrap(),
+        )
+        .await?;
+
+        Ok(Self {
+            client: None,
+            protocol_version_epoch_table: BTreeMap::new(),
+            protocol_version_system_package_table: BTreeMap::new(),
+            current_protocol_version: fetcher.protocol_version(),
+            exec_store_events: Arc::new(Mutex::new(Vec::new())),
+            metrics,
+            storage: Storage::default(),
+            fetcher: Fetchers::Archive(fetcher),
+            // TODO: make these configurable
+            num_retries_for_timeout: RPC_TIMEOUT_ERR_NUM_RETRIES,
+            sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+            diag: Default::default(),
+            executor_version_override: None,
+            protocol_version_override: None,
+            enable_profiler: None,
+            package_overrides: BTreeMap::new(),
         })
     }
 
@@ -658,9 +874,10 @@ impl LocalExec {
                     use_authority,
                     None,
                     None,
+                    None,
                 )
                 .await
-                .map(|q| q.check_effects())
+                .map(|q| q.check_effects().map(|_| q))
             {
                 Err(e) | Ok(Err(e)) => {
                     if terminate_early {
@@ -669,7 +886,7 @@ impl LocalExec {
                     error!("Error executing tx: {},  {:#?}", tx, e);
                     continue;
                 }
-                _ => (),
+                Ok(Ok(sandbox_state)) => self.record_outputs(&sandbox_state),
             }
 
             succeeded += 1;
@@ -677,6 +894,34 @@ impl LocalExec {
         Ok((succeeded, num as u64))
     }
 
+    /// When replaying from an archive, the objects written after the snapshot are only known
+    /// from local execution, so the outputs of a transaction whose effects match are recorded for
+    /// the transactions that follow
+    fn record_outputs(&mut self, sandbox_state: &ExecutionSandboxState) {
+        let Fetchers::Archive(fetcher) = &self.fetcher else {
+            return;
+        };
+        // Genesis is not executed
+        let Some(temporary_store) = &sandbox_state.local_exec_temporary_store else {
+            return;
+        };
+        let effects = &sandbox_state.local_exec_effects;
+        let removed: Vec<ObjectID> = effects
+            .deleted()
+            .iter()
+            .chain(effects.wrapped())
+            .chain(effects.unwrapped_then_deleted())
+            .map(|o| o.object_id)
+            .collect();
+        fetcher.record_outputs(&temporary_store.written, &removed);
+        for (id, object) in temporary_store.written.iter() {
+            self.storage.live_objects_store.insert(*id, object.clone());
+        }
+        for id in removed.iter() {
+            self.storage.live_objects_store.remove(id);
+        }
+    }
+
     pub async fn execution_engine_execute_with_tx_info_impl(
         &mut self,
         tx_info: &OnChainTransactionInfo,
@@ -685,7 +930,11 @@ impl LocalExec {
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         let tx_digest = &tx_info.tx_digest;
         // A lot of the logic here isnt designed for genesis
-        if *tx_digest == TransactionDigest::genesis() || tx_info.sender == SuiAddress::ZERO {
+        // System transactions are executed when replaying from an archive, as nothing else
+        // provides the objects they write
+        if *tx_digest == TransactionDigest::genesis()
+            || (tx_info.sender == SuiAddress::ZERO && !self.is_archive_replay())
+        {
             // Genesis.
             warn!(
                 "Genesis/system TX replay not supported: {}, skipping transaction",
@@ -730,14 +979,22 @@ impl LocalExec {
         let ov = self.executor_version_override;
 
         // We could probably cache the executor per protocol config
-        let executor = get_executor(ov, protocol_config, expensive_safety_check_config);
+        let executor = get_executor(
+            ov,
+            protocol_config,
+            expensive_safety_check_config,
+            self.enable_profiler.clone(),
+        );
 
         // All prep done
         let expensive_checks = true;
         let certificate_deny_set = HashSet::new();
-        let res = if let Ok(gas_status) =
+        let gas_status = if tx_info.kind.is_system_tx() {
+            Ok(SuiGasStatus::new_unmetered())
+        } else {
             SuiGasStatus::new(tx_info.gas_budget, tx_info.gas_price, rgp, protocol_config)
-        {
+        };
+        let res = if let Ok(gas_status) = gas_status {
             executor.execute_transaction_to_effects(
                 &self,
                 protocol_config,
@@ -777,7 +1034,7 @@ impl LocalExec {
         tx_digest: &TransactionDigest,
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-        if self.is_remote_replay() {
+        if self.is_remote_replay() || self.is_archive_replay() {
             assert!(
             !self.protocol_version_system_package_table.is_empty()
                 || !self.protocol_version_epoch_table.is_empty(),
@@ -785,7 +1042,7 @@ impl LocalExec {
         );
         }
 
-        let tx_info = if self.is_remote_replay() {
+        let tx_info = if self.is_remote_replay() || self.is_archive_replay() {
             self.resolve_tx_components(tx_digest).await?
         } else {
             self.resolve_tx_components_from_dump(tx_digest).await?
@@ -955,6 +1212,31 @@ impl LocalExec {
         Ok(sandbox_state)
     }
 
+    /// Must be called after `init_for_execution`
+    /// Executes a transaction that was dry run on the network, against the objects its dry run
+    /// read. This is how a transaction that has not been submitted yet is profiled.
+    pub async fn execute_dry_run(
+        &mut self,
+        tx_data: TransactionData,
+        dry_run_effects: SuiTransactionBlockEffects,
+        expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+        enable_profiler: Option<PathBuf>,
+    ) -> Result<ExecutionSandboxState, ReplayEngineError> {
+        self.enable_profiler = enable_profiler;
+        let tx_digest = *dry_run_effects.transaction_digest();
+        // The transaction is not signed, and signatures are not checked by the execution engine
+        let orig_tx = SenderSignedData::new(tx_data, Intent::sui_transaction(), vec![]);
+        let tx_info = self
+            .resolve_tx_components_from_effects(orig_tx, dry_run_effects, None, &tx_digest)
+            .await?;
+        self.execution_engine_execute_with_tx_info_impl(
+            &tx_info,
+            None,
+            expensive_safety_check_config,
+        )
+        .await
+    }
+
     pub async fn execute_state_dump(
         &mut self,
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
@@ -980,9 +1262,11 @@ impl LocalExec {
         use_authority: bool,
         executor_version_override: Option<i64>,
         protocol_version_override: Option<i64>,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         self.executor_version_override = executor_version_override;
         self.protocol_version_override = protocol_version_override;
+        self.enable_profiler = enable_profiler;
         if use_authority {
             self.certificate_execute(tx_digest, expensive_safety_check_config.clone())
                 .await
@@ -1055,13 +1339,17 @@ impl LocalExec {
         matches!(self.fetcher, Fetchers::Remote(_))
     }
 
+    pub fn is_archive_replay(&self) -> bool {
+        matches!(self.fetcher, Fetchers::Archive(_))
+    }
+
     /// Must be called after `populate_protocol_version_tables`
     pub fn system_package_versions_for_protocol_version(
         &self,
         protocol_version: u64,
     ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
         match &self.fetcher {
-            Fetchers::Remote(_) => Ok(self
+            Fetchers::Remote(_) | Fetchers::Archive(_) => Ok(self
                 .protocol_version_system_package_table
                 .get(&protocol_version)
                 .ok_or(ReplayEngineError::FrameworkObjectVersionTableNotPopulated {
@@ -1084,6 +1372,9 @@ impl LocalExec {
     pub async fn protocol_ver_to_epoch_map(
         &self,
     ) -> Result<BTreeMap<u64, ProtocolVersionSummary>, ReplayEngineError> {
+        if let Fetchers::Archive(fetcher) = &self.fetcher {
+            return Ok(archive_protocol_version_table(fetcher));
+        }
         let mut range_map = BTreeMap::new();
         let epoch_change_events = self.fetcher.get_epoch_change_events(false).await?;
 
@@ -1180,6 +1471,9 @@ impl LocalExec {
     }
 
     pub async fn populate_protocol_version_tables(&mut self) -> Result<(), ReplayEngineError> {
+        if self.is_archive_replay() {
+            return self.populate_protocol_version_tables_from_archive().await;
+        }
         self.protocol_version_epoch_table = self.protocol_ver_to_epoch_map().await?;
 
         let system_package_revisions = self.system_package_versions().await?;
@@ -1223,6 +1517,27 @@ impl LocalExec {
         Ok(())
     }
 
+    /// Epoch change events are not archived, but only the epoch following the snapshot can be
+    /// replayed from an archive, and its system packages are the ones in the snapshot
+    async fn populate_protocol_version_tables_from_archive(
+        &mut self,
+    ) -> Result<(), ReplayEngineError> {
+        let fetcher = self.fetcher.as_archive();
+        let protocol_version = fetcher.protocol_version();
+        self.protocol_version_epoch_table = archive_protocol_version_table(fetcher);
+        let system_packages = self
+            .multi_download_latest(&Self::system_package_ids(protocol_version))
+            .await?;
+        self.protocol_version_system_package_table.insert(
+            protocol_version,
+            system_packages
+                .iter()
+                .map(|package| (package.id(), package.version()))
+                .collect(),
+        );
+        Ok(())
+    }
+
     pub async fn system_package_versions(
         &self,
     ) -> Result<BTreeMap<ObjectID, Vec<(SequenceNumber, TransactionDigest)>>, ReplayEngineError>
@@ -1331,6 +1646,12 @@ impl LocalExec {
         &self,
         epoch_id: u64,
     ) -> Result<(u64, u64), ReplayEngineError> {
+        if let Fetchers::Archive(fetcher) = &self.fetcher {
+            if epoch_id != fetcher.epoch() {
+                return Err(ReplayEngineError::EpochNotSupported { epoch: epoch_id });
+            }
+            return Ok(fetcher.checkpoints_for_epoch());
+        }
         let epoch_change_events = self
             .fetcher
             .get_epoch_change_events(true)
@@ -1399,16 +1720,30 @@ impl LocalExec {
         &self,
         tx_digest: &TransactionDigest,
     ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
-        assert!(self.is_remote_replay());
+        assert!(self.is_remote_replay() || self.is_archive_replay());
         // Fetch full transaction content
         let tx_info = self.fetcher.get_transaction(tx_digest).await?;
-        let sender = match tx_info.clone().transaction.unwrap().data {
-            sui_json_rpc_types::SuiTransactionBlockData::V1(tx) => tx.sender,
-        };
-        let SuiTransactionBlockEffects::V1(effects) = tx_info.clone().effects.unwrap();
-
         let raw_tx_bytes = tx_info.clone().raw_transaction;
         let orig_tx: SenderSignedData = bcs::from_bytes(&raw_tx_bytes).unwrap();
+        let events = tx_info.events.map(|events| events.data);
+        self.resolve_tx_components_from_effects(
+            orig_tx,
+            tx_info.effects.unwrap(),
+            events,
+            tx_digest,
+        )
+        .await
+    }
+
+    async fn resolve_tx_components_from_effects(
+        &self,
+        orig_tx: SenderSignedData,
+        effects: SuiTransactionBlockEffects,
+        events: Option<Vec<SuiEvent>>,
+        tx_digest: &TransactionDigest,
+    ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
+        let SuiTransactionBlockEffects::V1(effects) = effects;
+        let sender = orig_tx.transaction_data().sender();
         let input_objs = orig_tx
             .transaction_data()
             .input_objects()
@@ -1431,14 +1766,9 @@ impl LocalExec {
                 }
             })
             .collect();
-        let gas_data = match tx_info.clone().transaction.unwrap().data {
-            sui_json_rpc_types::SuiTransactionBlockData::V1(tx) => tx.gas_data,
-        };
-        let gas_object_refs: Vec<_> = gas_data
-            .payment
-            .iter()
-            .map(|obj_ref| obj_ref.to_object_ref())
-            .collect();
+        // The archive has no json rendering of transactions, so both read the raw transaction
+        let gas_data = orig_tx.transaction_data().gas_data();
+        let gas_object_refs: Vec<_> = gas_data.clone().payment.into_iter().collect();
 
         let epoch_id = effects.executed_epoch;
 
@@ -1458,6 +1788,7 @@ impl LocalExec {
             executed_epoch: epoch_id,
             dependencies: effects.dependencies().to_vec(),
             effects: SuiTransactionBlockEffects::V1(effects),
+            events,
             // Find the protocol version for this epoch
             // This assumes we already initialized the protocol version table `protocol_version_epoch_table`
             protocol_version: self.get_protocol_config(epoch_id).await?.version,
@@ -1535,6 +1866,7 @@ impl LocalExec {
             executed_epoch: epoch_id,
             dependencies: effects.dependencies().to_vec(),
             effects,
+            events: None,
             protocol_version: protocol_config.version,
             tx_digest: *tx_digest,
             epoch_start_timestamp,
@@ -1703,8 +2035,11 @@ impl LocalExec {
         self.multi_download_and_store(&shared_refs).await?;
 
         // Download gas (although this should already be in cache from modified at versions?)
-        let gas_refs: Vec<_> = tx_info.gas.iter().map(|w| (w.0, w.1)).collect();
-        self.multi_download_and_store(&gas_refs).await?;
+        // System transactions have a placeholder gas object, which does not exist
+        if !tx_info.kind.is_system_tx() {
+            let gas_refs: Vec<_> = tx_info.gas.iter().map(|w| (w.0, w.1)).collect();
+            self.multi_download_and_store(&gas_refs).await?;
+        }
 
         // Fetch the input objects we know from the raw transaction
         let input_objs = self
@@ -1956,7 +2291,15 @@ impl ObjectStore for LocalExec {
     /// The object must be present in store by normal process we used to backfill store in init
     /// We dont download if not present
     fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
-        let res = Ok(self.storage.live_objects_store.get(object_id).cloned());
+        let res = match self.storage.live_objects_store.get(object_id) {
+            Some(obj) => Ok(Some(obj.clone())),
+            // The dynamic fields loaded by a transaction are not archived, but the archive fetcher
+            // has the latest version of every object as of the transaction being replayed
+            None if self.is_archive_replay() => self
+                .download_latest_object(object_id)
+                .map_err(SuiError::from),
+            None => Ok(None),
+        };
         self.exec_store_events
             .lock()
             .expect("Unable to lock events list")
@@ -2035,10 +2378,96 @@ impl GetModule for LocalExec {
 
 // <--------------------- Util functions ----------------------->
 
+/// Build the Move package at `path`, whose modules are substituted for those of an on-chain
+/// package during replay
+pub fn build_package_override(path: PathBuf) -> Result<Vec<CompiledModule>, ReplayEngineError> {
+    move_package::package_hooks::register_package_hooks(Box::new(SuiPackageHooks));
+    Ok(BuildConfig::default().build(path)?.into_modules())
+}
+
+/// Replace the modules of the package in `obj` with `modules`. The package keeps its ID, version
+/// and linkage table, so the locally built modules are loaded wherever the on-chain ones were.
+pub(crate) fn override_package(
+    obj: &Object,
+    modules: &[CompiledModule],
+) -> Result<Object, ReplayEngineError> {
+    let invalid = |err: String| ReplayEngineError::InvalidPackageOverride { id: obj.id(), err };
+    let Some(package) = obj.data.try_as_package() else {
+        return Err(invalid("object is not a package".to_string()));
+    };
+
+    let original_id = package.original_package_id();
+    let mut type_origins = package.type_origin_map();
+    let mut type_origin_table = vec![];
+    let mut module_map = BTreeMap::new();
+    for module in modules {
+        let module_name = module.name().to_string();
+        let self_id = ObjectID::from(*module.self_id().address());
+        if self_id != original_id {
+            return Err(invalid(format!(
+                "module {module_name} has address {self_id}, expected {original_id}"
+            )));
+        }
+
+        for struct_def in module.struct_defs() {
+            let struct_handle = module.struct_handle_at(struct_def.struct_handle);
+            let struct_name = module.identifier_at(struct_handle.name).to_string();
+            // Types that are new in the override are defined by the overridden package
+            let package = type_origins
+                .remove(&(module_name.clone(), struct_name.clone()))
+                .unwrap_or(package.id());
+            type_origin_table.push(TypeOrigin {
+                module_name: module_name.clone(),
+                struct_name,
+                package,
+            });
+        }
+
+        let mut bytes = vec![];
+        module
+            .serialize(&mut bytes)
+            .map_err(|e| invalid(format!("cannot serialize module {module_name}: {e}")))?;
+        module_map.insert(module_name, bytes);
+    }
+
+    let package = MovePackage::new(
+        package.id(),
+        package.version(),
+        module_map,
+        u64::MAX,
+        type_origin_table,
+        package.linkage_table().clone(),
+    )
+    .map_err(|e| invalid(format!("{e:?}")))?;
+    Ok(Object::new_from_package(package, obj.previous_transaction))
+}
+
+/// An archive is replayed one epoch at a time, so its table only has the protocol version of
+/// that epoch. Epoch change events are not archived, the epoch is read from the base state.
+fn archive_protocol_version_table(
+    fetcher: &ArchiveFetcher,
+) -> BTreeMap<u64, ProtocolVersionSummary> {
+    let protocol_version = fetcher.protocol_version();
+    let epoch = fetcher.epoch();
+    let (checkpoint_start, checkpoint_end) = fetcher.checkpoints_for_epoch();
+    BTreeMap::from([(
+        protocol_version,
+        ProtocolVersionSummary {
+            protocol_version,
+            epoch_start: epoch,
+            epoch_end: epoch,
+            checkpoint_start: Some(checkpoint_start),
+            checkpoint_end: Some(checkpoint_end),
+            epoch_change_tx: fetcher.epoch_change_tx(),
+        },
+    )])
+}
+
 pub fn get_executor(
     executor_version_override: Option<i64>,
     protocol_config: &ProtocolConfig,
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    enable_profiler: Option<PathBuf>,
 ) -> Arc<dyn Executor + Send + Sync> {
     let protocol_config = executor_version_override
         .map(|q| {
@@ -2059,6 +2488,7 @@ pub fn get_executor(
         &protocol_config,
         expensive_safety_check_config.enable_move_vm_paranoid_checks(),
         silent,
+        enable_profiler,
     )
     .expect("Creating an executor should not fail here")
 }
@@ -2,15 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::ReplayableNetworkConfigSet;
-use crate::types::ReplayEngineError;
+use crate::replay::{override_package, ExecutionSandboxState};
+use crate::types::{DiagInfo, OnChainTransactionInfo, ReplayEngineError};
 use crate::types::{MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD};
 use crate::LocalExec;
+use shared_crypto::intent::Intent;
 use sui_config::node::ExpensiveSafetyCheckConfig;
+use sui_framework::BuiltInFramework;
 use sui_json_rpc::api::QUERY_MAX_RESULT_LIMIT;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_protocol_config::ProtocolVersion;
 use sui_sdk::{SuiClient, SuiClientBuilder};
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{random_object_ref, SuiAddress};
 use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::execution_status::{ExecutionFailureStatus, ExecutionStatus};
+use sui_types::transaction::{SenderSignedData, TransactionData, TransactionDataAPI};
+use sui_types::{MOVE_STDLIB_PACKAGE_ID, SUI_FRAMEWORK_PACKAGE_ID};
 
 /// Keep searching for non-system TXs in the checkppints for this long
 /// Very unlikely to take this long, but we want to be sure we find one
@@ -141,7 +149,14 @@ async fn execute_replay(url: &str, tx: &TransactionDigest) -> Result<(), ReplayE
         .await?
         .init_for_execution()
         .await?
-        .execute_transaction(tx, ExpensiveSafetyCheckConfig::default(), true, None, None)
+        .execute_transaction(
+            tx,
+            ExpensiveSafetyCheckConfig::default(),
+            true,
+            None,
+            None,
+            None,
+        )
         .await?
         .check_effects()?;
     tokio::task::yield_now().await;
@@ -149,10 +164,106 @@ async fn execute_replay(url: &str, tx: &TransactionDigest) -> Result<(), ReplayE
         .await?
         .init_for_execution()
         .await?
-        .execute_transaction(tx, ExpensiveSafetyCheckConfig::default(), false, None, None)
+        .execute_transaction(
+            tx,
+            ExpensiveSafetyCheckConfig::default(),
+            false,
+            None,
+            None,
+            None,
+        )
         .await?
         .check_effects()?;
     tokio::task::yield_now().await;
 
     Ok(())
 }
+
+/// Overriding a package with the modules it was built from leaves it unchanged, while modules
+/// built for a different address are rejected
+#[test]
+fn verify_package_override() {
+    let framework = BuiltInFramework::genesis_objects()
+        .find(|o| o.id() == SUI_FRAMEWORK_PACKAGE_ID)
+        .unwrap();
+
+    let modules = BuiltInFramework::get_package_by_id(&SUI_FRAMEWORK_PACKAGE_ID).modules();
+    let overridden = override_package(&framework, &modules).unwrap();
+    assert_eq!(overridden.data, framework.data);
+
+    let stdlib = BuiltInFramework::get_package_by_id(&MOVE_STDLIB_PACKAGE_ID).modules();
+    assert!(matches!(
+        override_package(&framework, &stdlib),
+        Err(ReplayEngineError::InvalidPackageOverride { .. })
+    ));
+}
+
+fn sandbox_state(original: TransactionEffects, local: TransactionEffects) -> ExecutionSandboxState {
+    let sender = SuiAddress::random_for_testing_only();
+    let gas = random_object_ref();
+    let tx_data = TransactionData::new_transfer_sui(sender, sender, None, gas, 1_000_000, 1_000);
+    ExecutionSandboxState {
+        transaction_info: OnChainTransactionInfo {
+            tx_digest: *original.transaction_digest(),
+            kind: tx_data.kind().clone(),
+            sender_signed_data: SenderSignedData::new(tx_data, Intent::sui_transaction(), vec![]),
+            sender,
+            input_objects: vec![],
+            modified_at_versions: vec![],
+            shared_object_refs: vec![],
+            gas: vec![gas],
+            gas_budget: 1_000_000,
+            gas_price: 1_000,
+            executed_epoch: 0,
+            dependencies: vec![],
+            effects: original.try_into().unwrap(),
+            events: None,
+            protocol_version: ProtocolVersion::MAX,
+            epoch_start_timestamp: 0,
+            reference_gas_price: 1_000,
+        },
+        required_objects: vec![],
+        local_exec_temporary_store: None,
+        local_exec_effects: local.try_into().unwrap(),
+        local_exec_status: None,
+        pre_exec_diag: DiagInfo::default(),
+    }
+}
+
+/// Only the rows where the original and local executions differ are marked
+#[test]
+fn verify_compare_executions() {
+    let mut original = TransactionEffects::default();
+    original.gas_cost_summary_mut_for_testing().computation_cost = 1_000;
+    original.gas_cost_summary_mut_for_testing().storage_cost = 2_000;
+
+    let comparison = sandbox_state(original.clone(), original.clone()).compare_executions();
+    assert!(comparison.lines().all(|line| !line.starts_with('*')));
+
+    let mut local = original.clone();
+    *local.status_mut_for_testing() =
+        ExecutionStatus::new_failure(ExecutionFailureStatus::InsufficientGas, None);
+    local.gas_cost_summary_mut_for_testing().computation_cost = 3_000;
+    let deleted = random_object_ref();
+    local.unsafe_add_deleted_object_for_testing(deleted);
+
+    let comparison = sandbox_state(original, local).compare_executions();
+    let differing: Vec<_> = comparison
+        .lines()
+        .filter(|line| line.starts_with('*'))
+        .collect();
+    let deleted_id = deleted.0.to_string();
+    for label in [
+        "status",
+        "computation cost",
+        "net gas usage",
+        "deleted objects",
+        deleted_id.as_str(),
+    ] {
+        assert!(
+            differing.iter().any(|line| line.contains(label)),
+            "{label} is not marked as differing in\n{comparison}"
+        );
+    }
+    assert_eq!(differing.len(), 5, "{comparison}");
+}
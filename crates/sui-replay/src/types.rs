@@ -27,6 +27,7 @@ use crate::config::ReplayableNetworkConfigSet;
 pub(crate) const RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD: Duration = Duration::from_millis(10_000);
 pub(crate) const RPC_TIMEOUT_ERR_NUM_RETRIES: u32 = 2;
 pub(crate) const MAX_CONCURRENT_REQUESTS: usize = 1_000;
+pub(crate) const SNAPSHOT_RESTORE_CONCURRENCY: usize = 32;
 
 // Struct tag used in system epoch change events
 pub(crate) const EPOCH_CHANGE_STRUCT_TAG: &str =
@@ -49,6 +50,9 @@ pub struct OnChainTransactionInfo {
     pub executed_epoch: u64,
     pub dependencies: Vec<TransactionDigest>,
     pub effects: SuiTransactionBlockEffects,
+    /// Events emitted on-chain, if the source of the transaction provides them
+    #[serde(default)]
+    pub events: Option<Vec<SuiEvent>>,
     pub protocol_version: ProtocolVersion,
     pub epoch_start_timestamp: u64,
     pub reference_gas_price: u64,
@@ -125,6 +129,9 @@ pub enum ReplayEngineError {
         local: Box<SuiTransactionBlockEffects>,
     },
 
+    #[error("Invalid override for package {id}: {err}")]
+    InvalidPackageOverride { id: ObjectID, err: String },
+
     #[error("Genesis replay not supported digest {:#?}", digest)]
     GenesisReplayNotSupported { digest: TransactionDigest },
 
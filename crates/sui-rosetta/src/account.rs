@@ -9,14 +9,15 @@ use futures::StreamExt;
 use sui_sdk::rpc_types::StakeStatus;
 use sui_sdk::{SuiClient, SUI_COIN_TYPE};
 use sui_types::base_types::SuiAddress;
+use sui_types::parse_sui_type_tag;
 use tracing::info;
 
 use crate::errors::Error;
 use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountCoinsRequest, AccountCoinsResponse,
-    Amount, Coin, SubAccount, SubAccountType, SubBalance,
+    Amount, Coin, Currency, SubAccount, SubAccountType, SubBalance,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{OnlineServerContext, SuiEnv, SUI};
 use std::time::Duration;
 
 /// Get an array of all AccountBalances for an AccountIdentifier and the BlockIdentifier
@@ -70,12 +71,7 @@ pub async fn balance(
     } else {
         // Get current live balance
         while retry_attempts > 0 {
-            let balances_first = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_first = get_balances(&ctx, address, &request.currencies).await?;
 
             // Get current latest checkpoint
             let checkpoint1 = ctx
@@ -101,12 +97,7 @@ pub async fn balance(
             }
 
             // Get live balance again
-            let balances_second = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_second = get_balances(&ctx, address, &request.currencies).await?;
 
             // if those two live balances are equal then that is the current balance for checkpoint2
             if balances_first.eq(&balances_second) {
@@ -116,7 +107,7 @@ pub async fn balance(
                 );
                 return Ok(AccountBalanceResponse {
                     block_identifier: ctx.blocks().create_block_identifier(checkpoint2).await?,
-                    balances: vec![Amount::new(balances_first)],
+                    balances: balances_first,
                 });
             } else {
                 // balances are different so we need to try again.
@@ -131,6 +122,52 @@ pub async fn balance(
     }
 }
 
+/// The balances of `address` in `currencies`, or in every currency it holds, SUI included, when no
+/// currency is requested.
+async fn get_balances(
+    ctx: &OnlineServerContext,
+    address: SuiAddress,
+    currencies: &[Currency],
+) -> Result<Vec<Amount>, Error> {
+    let coin_read_api = ctx.client.coin_read_api();
+    if !currencies.is_empty() {
+        let mut amounts = vec![];
+        for currency in currencies {
+            let coin_type = currency.coin_type()?;
+            let balance = coin_read_api
+                .get_balance(address, Some(coin_type.to_string()))
+                .await?;
+            amounts.push(Amount::new_with_currency(
+                balance.total_balance as i128,
+                currency.clone(),
+            ));
+        }
+        return Ok(amounts);
+    }
+
+    let mut amounts = vec![];
+    for balance in coin_read_api.get_all_balances(address).await? {
+        let coin_type = parse_sui_type_tag(&balance.coin_type)?;
+        let currency = ctx.coin_metadata_cache.get_currency(&coin_type).await?;
+        amounts.push(Amount::new_with_currency(
+            balance.total_balance as i128,
+            currency,
+        ));
+    }
+    if !amounts.iter().any(|amount| amount.currency == *SUI) {
+        amounts.push(Amount::new(0));
+    }
+    // SUI first, then the other currencies by coin type.
+    amounts.sort_by_key(|amount| {
+        amount
+            .currency
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.coin_type.clone())
+    });
+    Ok(amounts)
+}
+
 async fn get_sub_account_balances(
     account_type: SubAccountType,
     client: &SuiClient,
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
 use axum::extract::State;
 use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
@@ -21,6 +23,7 @@ use sui_types::signature::{GenericSignature, VerifyParams};
 use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
 
 use crate::errors::Error;
+use crate::operations::Operations;
 use crate::types::{
     Amount, ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
     ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadata,
@@ -264,6 +267,35 @@ pub async fn metadata(
 
             (Some(0), stake_refs)
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            let amount = amounts.iter().sum::<u64>();
+            let coin_type = currency.coin_type()?;
+            // The declared currency is reported by `/construction/parse`, so it must match the
+            // metadata of its coin type.
+            let resolved = context.coin_metadata_cache.get_currency(&coin_type).await?;
+            if (&resolved.symbol, resolved.decimals) != (&currency.symbol, currency.decimals) {
+                return Err(Error::InvalidInput(format!(
+                    "Currency {} with {} decimals doesn't match the metadata of {coin_type}: {} \
+                     with {} decimals",
+                    currency.symbol, currency.decimals, resolved.symbol, resolved.decimals
+                )));
+            }
+            let coin_refs = context
+                .client
+                .coin_read_api()
+                .select_coins(*sender, Some(coin_type.to_string()), amount.into(), vec![])
+                .await?
+                .into_iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+            // Gas coins only need to cover the budget.
+            (Some(0), coin_refs)
+        }
     };
 
     // Dry run the transaction to get the gas used, amount doesn't really matter here when using mock coins.
@@ -363,7 +395,12 @@ pub async fn parse(
     } else {
         vec![]
     };
-    let operations = data.try_into()?;
+    let currencies: HashMap<_, _> = request
+        .currencies
+        .into_iter()
+        .map(|currency| Ok((currency.coin_type()?, currency)))
+        .collect::<Result<_, Error>>()?;
+    let operations = Operations::try_from_data(data, &currencies)?;
     Ok(ConstructionParseResponse {
         operations,
         account_identifier_signers,
@@ -14,6 +14,7 @@ use serde_json::{json, Value};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+use sui_types::base_types::TransactionDigest;
 use sui_types::error::SuiError;
 
 use crate::types::{BlockHash, OperationType, PublicKey, SuiEnv};
@@ -83,6 +84,9 @@ pub enum Error {
     #[error("Retries exhausted while getting balance. try again.")]
     #[strum(props(retriable = "true"))]
     RetryExhausted(String),
+
+    #[error("Transaction not in mempool: {0}")]
+    TransactionNotInMempool(TransactionDigest),
 }
 
 impl Serialize for ErrorType {
@@ -17,19 +17,24 @@ use crate::errors::Error;
 use crate::state::{CheckpointBlockProvider, OnlineServerContext};
 use crate::types::{Currency, SuiEnv};
 
+pub use crate::state::CoinMetadataCache;
+
 /// This lib implements the Rosetta online and offline server defined by the [Rosetta API Spec](https://www.rosetta-api.org/docs/Reference.html)
 mod account;
 mod block;
 mod construction;
 mod errors;
+mod mempool;
 mod network;
 pub mod operations;
+mod search;
 mod state;
 pub mod types;
 
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: None,
 });
 
 pub struct RosettaOnlineServer {
@@ -39,10 +44,14 @@ pub struct RosettaOnlineServer {
 
 impl RosettaOnlineServer {
     pub fn new(env: SuiEnv, client: SuiClient) -> Self {
-        let blocks = Arc::new(CheckpointBlockProvider::new(client.clone()));
+        let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+        let blocks = Arc::new(CheckpointBlockProvider::new(
+            client.clone(),
+            coin_metadata_cache.clone(),
+        ));
         Self {
             env,
-            context: OnlineServerContext::new(client, blocks),
+            context: OnlineServerContext::new(client, blocks, coin_metadata_cache),
         }
     }
 
@@ -55,9 +64,12 @@ impl RosettaOnlineServer {
             .route("/block/transaction", post(block::transaction))
             .route("/construction/submit", post(construction::submit))
             .route("/construction/metadata", post(construction::metadata))
+            .route("/mempool", post(mempool::mempool))
+            .route("/mempool/transaction", post(mempool::transaction))
             .route("/network/status", post(network::status))
             .route("/network/list", post(network::list))
             .route("/network/options", post(network::options))
+            .route("/search/transactions", post(search::transactions))
             .layer(Extension(self.env))
             .with_state(self.context);
         let server = axum::Server::bind(&addr).serve(app.into_make_service());
@@ -0,0 +1,96 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use sui_json_rpc_types::{SuiTransactionBlockResponseOptions, SuiTransactionBlockResponseQuery};
+
+use crate::operations::Operations;
+use crate::types::{
+    MempoolResponse, MempoolTransactionRequest, MempoolTransactionResponse, NetworkRequest,
+    Transaction, TransactionIdentifier,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+/// This module implements the [Rosetta Mempool API](https://www.rosetta-api.org/docs/MempoolApi.html)
+///
+/// Sui doesn't have a mempool: transactions are executed as soon as they are certified, and
+/// submitted transactions have already been executed when `/construction/submit` returns. The
+/// closest equivalent is the set of transactions which the fullnode has executed but which aren't
+/// part of a checkpoint, i.e. a block, yet.
+
+/// The number of most recently executed transactions looked at for ones without a checkpoint.
+const MEMPOOL_SCAN_SIZE: usize = 50;
+
+/// Get all Transaction Identifiers in the mempool.
+///
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempool)
+pub async fn mempool(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<NetworkRequest>, Error>,
+) -> Result<MempoolResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    let page = context
+        .client
+        .read_api()
+        .query_transaction_blocks(
+            SuiTransactionBlockResponseQuery::default(),
+            None,
+            Some(MEMPOOL_SCAN_SIZE),
+            true,
+        )
+        .await?;
+    let transaction_identifiers = page
+        .data
+        .into_iter()
+        .filter(|response| response.checkpoint.is_none())
+        .map(|response| TransactionIdentifier {
+            hash: response.digest,
+        })
+        .collect();
+    Ok(MempoolResponse {
+        transaction_identifiers,
+    })
+}
+
+/// Get a transaction in the mempool by its Transaction Identifier.
+///
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempooltransaction)
+pub async fn transaction(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<MempoolTransactionRequest>, Error>,
+) -> Result<MempoolTransactionResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    let digest = request.transaction_identifier.hash;
+    let response = context
+        .client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_events()
+                .with_effects()
+                .with_balance_changes(),
+        )
+        .await?;
+    if response.checkpoint.is_some() {
+        return Err(Error::TransactionNotInMempool(digest));
+    }
+
+    let hash = response.digest;
+    Ok(MempoolTransactionResponse {
+        transaction: Transaction {
+            transaction_identifier: TransactionIdentifier { hash },
+            operations: Operations::try_from_response(response, &context.coin_metadata_cache)
+                .await?,
+            related_transactions: vec![],
+            metadata: None,
+        },
+        metadata: None,
+    })
+}
@@ -8,8 +8,9 @@ use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -22,18 +23,22 @@ use sui_sdk::rpc_types::{
     SuiTransactionBlockKind, SuiTransactionBlockResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::coin::{PAY_MODULE_NAME, PAY_SPLIT_AND_TRANSFER_FUNC_NAME};
 use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::TransactionData;
-use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
+use sui_types::{
+    parse_sui_type_tag, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID,
+};
 
+use crate::state::CoinMetadataCache;
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -94,6 +99,10 @@ impl Operations {
         self.0.first().map(|op| op.type_)
     }
 
+    pub fn iter(&self) -> std::slice::Iter<'_, Operation> {
+        self.0.iter()
+    }
+
     /// Parse operation input from rosetta operation to intermediate internal operation;
     pub fn into_internal(self) -> Result<InternalOperation, Error> {
         let type_ = self
@@ -101,6 +110,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -135,6 +145,49 @@ impl Operations {
         })
     }
 
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut recipients = vec![];
+        let mut amounts = vec![];
+        let mut sender = None;
+        let mut currency: Option<Currency> = None;
+        for op in self {
+            if let (Some(amount), Some(account)) = (op.amount, op.account) {
+                if *currency.get_or_insert_with(|| amount.currency.clone()) != amount.currency {
+                    return Err(Error::MalformedOperationError(
+                        "PayCoin operations should all have the same currency.".into(),
+                    ));
+                }
+                if amount.value.is_negative() {
+                    sender = Some(account.address)
+                } else {
+                    recipients.push(account.address);
+                    let amount = amount.value.abs();
+                    if amount > u64::MAX as i128 {
+                        return Err(Error::InvalidInput(
+                            "Input amount exceed u64::MAX".to_string(),
+                        ));
+                    }
+                    amounts.push(amount as u64)
+                }
+            }
+        }
+        let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        // Validates the coin type of the currency.
+        currency.coin_type()?;
+        if currency == *SUI {
+            return Err(Error::InvalidInput(
+                "SUI should be paid with PaySui operations".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
         let mut ops = self
             .0
@@ -213,14 +266,31 @@ impl Operations {
         Ok(InternalOperation::WithdrawStake { sender, stake_ids })
     }
 
+    /// Parse the operations of `data`. `currencies` holds the currencies of the coin types other
+    /// than SUI paid by the transaction, payments of any other coin type are reported as generic
+    /// operations.
+    fn from_transaction_data(
+        data: SuiTransactionBlockData,
+        currencies: &HashMap<TypeTag, Currency>,
+    ) -> Result<Self, Error> {
+        let sender = *data.sender();
+        Ok(Self::new(Self::from_transaction(
+            data.transaction().clone(),
+            sender,
+            None,
+            currencies,
+        )?))
+    }
+
     fn from_transaction(
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
         status: Option<OperationStatus>,
+        currencies: &HashMap<TypeTag, Currency>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionBlockKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, status, pt, currencies)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
@@ -230,6 +300,7 @@ impl Operations {
         sender: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransactionBlock,
+        currencies: &HashMap<TypeTag, Currency>,
     ) -> Result<Vec<Operation>, Error> {
         #[derive(Debug)]
         enum KnownValue {
@@ -360,12 +431,72 @@ impl Operations {
             };
             Ok(id.cloned())
         }
+
+        fn merge_coins(target: SuiArgument, coins: &[SuiArgument]) -> Option<Vec<KnownValue>> {
+            // Only the merge of the input coins of a coin payment is known.
+            let is_input = |coin: &SuiArgument| matches!(coin, SuiArgument::Input(_));
+            (is_input(&target) && coins.iter().all(is_input)).then(Vec::new)
+        }
+
+        // The arguments of a Move call are only typed when its module is known, offline they are
+        // the BCS bytes of the values.
+        fn raw_pure_value<T: DeserializeOwned>(arg: &SuiCallArg) -> Option<T> {
+            let bytes: Vec<u8> = serde_json::from_value(arg.pure()?.to_json_value()).ok()?;
+            bcs::from_bytes(&bytes).ok()
+        }
+
+        fn pay_coin_call(
+            pay_coin: &mut Option<(Currency, HashMap<SuiAddress, u64>)>,
+            inputs: &[SuiCallArg],
+            call: &SuiProgrammableMoveCall,
+            currencies: &HashMap<TypeTag, Currency>,
+        ) -> Option<Vec<KnownValue>> {
+            let SuiProgrammableMoveCall {
+                type_arguments,
+                arguments,
+                ..
+            } = call;
+            let [coin_type] = &type_arguments[..] else {
+                return None;
+            };
+            let coin_type = parse_sui_type_tag(coin_type).ok()?;
+            let currency = currencies.get(&coin_type).cloned()?;
+            let [SuiArgument::Input(_), SuiArgument::Input(amount), SuiArgument::Input(recipient)] =
+                arguments[..]
+            else {
+                return None;
+            };
+            let amount = &inputs[amount as usize];
+            let amount = amount
+                .pure()?
+                .to_json_value()
+                .as_str()
+                .and_then(|amount| u64::from_str(amount).ok())
+                .or_else(|| raw_pure_value(amount))?;
+            let recipient = &inputs[recipient as usize];
+            let recipient = recipient
+                .pure()?
+                .to_sui_address()
+                .ok()
+                .or_else(|| raw_pure_value(recipient))?;
+
+            let (paid_currency, recipients) =
+                pay_coin.get_or_insert_with(|| (currency.clone(), HashMap::new()));
+            // A payment of several currencies can't be represented by PayCoin operations.
+            if *paid_currency != currency {
+                return None;
+            }
+            *recipients.entry(recipient).or_default() += amount;
+            Some(vec![])
+        }
+
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
+        let mut pay_coin = None;
         for command in commands {
             let result = match command {
                 SuiCommand::SplitCoins(coin, amounts) => {
@@ -398,6 +529,10 @@ impl Operations {
                     stake_ids.push(stake_id);
                     Some(vec![])
                 }
+                SuiCommand::MergeCoins(target, coins) => merge_coins(*target, coins),
+                SuiCommand::MoveCall(m) if Self::is_split_and_transfer_call(m) => {
+                    pay_coin_call(&mut pay_coin, inputs, m, currencies)
+                }
                 _ => None,
             };
             if let Some(result) = result {
@@ -408,6 +543,17 @@ impl Operations {
             }
         }
 
+        // Merged coins are only known to be paid by coin payments, and payments of both SUI and
+        // another coin are reported as generic operations.
+        let merges_coins = commands
+            .iter()
+            .any(|command| matches!(command, SuiCommand::MergeCoins(..)));
+        match &pay_coin {
+            Some(_) if !aggregated_recipients.is_empty() => needs_generic = true,
+            None if merges_coins => needs_generic = true,
+            _ => {}
+        }
+
         if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
             operations.extend(
@@ -418,6 +564,17 @@ impl Operations {
                     }),
             );
             operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+        } else if let (false, Some((currency, recipients))) = (needs_generic, pay_coin) {
+            let total_paid: u64 = recipients.values().copied().sum();
+            operations.extend(recipients.into_iter().map(|(recipient, amount)| {
+                Operation::pay_coin(status, recipient, amount.into(), currency.clone())
+            }));
+            operations.push(Operation::pay_coin(
+                status,
+                sender,
+                -(total_paid as i128),
+                currency,
+            ));
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -455,31 +612,44 @@ impl Operations {
             && tx.function == WITHDRAW_STAKE_FUN_NAME.as_str()
     }
 
+    fn is_split_and_transfer_call(tx: &SuiProgrammableMoveCall) -> bool {
+        tx.package == SUI_FRAMEWORK_PACKAGE_ID
+            && tx.module == PAY_MODULE_NAME.as_str()
+            && tx.function == PAY_SPLIT_AND_TRANSFER_FUNC_NAME.as_str()
+    }
+
     fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
-    ) -> impl Iterator<Item = Operation> {
-        let mut balances = balance_changes
-            .iter()
-            .fold(balances, |mut balances, balance_change| {
-                // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
-                    }
-                }
-                balances
-            });
+        mut balances: HashMap<(SuiAddress, Currency), i128>,
+        currencies: &HashMap<TypeTag, Currency>,
+    ) -> Result<impl Iterator<Item = Operation>, Error> {
+        for balance_change in balance_changes {
+            // Rosetta only care about address owner
+            if let Owner::AddressOwner(owner) = balance_change.owner {
+                let currency = if balance_change.coin_type == GAS::type_tag() {
+                    SUI.clone()
+                } else {
+                    currencies
+                        .get(&balance_change.coin_type)
+                        .cloned()
+                        .ok_or_else(|| {
+                            anyhow!("Missing currency of coin {}", balance_change.coin_type)
+                        })?
+                };
+                *balances.entry((owner, currency)).or_default() += balance_change.amount;
+            }
+        }
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                Operation::balance_change(status, addr, Amount::new_with_currency(amount, currency))
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -487,28 +657,48 @@ impl Operations {
             // Gas can be 0 for system tx
             vec![]
         };
-        balance_change.chain(gas)
-    }
-}
-
-impl TryFrom<SuiTransactionBlockData> for Operations {
-    type Error = Error;
-    fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
-        let sender = *data.sender();
-        Ok(Self::new(Self::from_transaction(
-            data.transaction().clone(),
-            sender,
-            None,
-        )?))
+        Ok(balance_change.chain(gas))
     }
-}
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+    /// Parse the operations of an executed transaction, including the balance changes of every
+    /// coin type. The currencies of coins other than SUI are resolved from their `CoinMetadata`.
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
+        let balance_changes = response
+            .balance_changes
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?;
+
+        // The coin types paid by the transaction, or whose balances it changes.
+        let mut coin_types: Vec<TypeTag> = balance_changes
+            .iter()
+            .map(|balance_change| balance_change.coin_type.clone())
+            .collect();
+        if let SuiTransactionBlockKind::ProgrammableTransaction(pt) = tx.data.transaction() {
+            for command in &pt.commands {
+                if let SuiCommand::MoveCall(m) = command {
+                    if Self::is_split_and_transfer_call(m) {
+                        coin_types.extend(
+                            m.type_arguments
+                                .iter()
+                                .filter_map(|coin_type| parse_sui_type_tag(coin_type).ok()),
+                        );
+                    }
+                }
+            }
+        }
+        let mut currencies = HashMap::new();
+        for coin_type in coin_types {
+            if coin_type != GAS::type_tag() && !currencies.contains_key(&coin_type) {
+                let currency = coin_metadata_cache.get_currency(&coin_type).await?;
+                currencies.insert(coin_type, currency);
+            }
+        }
+
         let sender = *tx.data.sender();
         let effect = response
             .effects
@@ -520,7 +710,7 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             - gas_summary.computation_cost as i128;
 
         let status = Some(effect.into_status().into());
-        let ops: Operations = tx.data.try_into()?;
+        let ops = Self::from_transaction_data(tx.data, &currencies)?;
         let ops = ops.set_status(status).into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
@@ -532,7 +722,9 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
                     if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
                         (&op.account, &op.amount, &op.status)
                     {
-                        *balances.entry(acc.address).or_default() -= amount.value;
+                        *balances
+                            .entry((acc.address, amount.currency.clone()))
+                            .or_default() -= amount.value;
                     }
                     balances
                 });
@@ -564,8 +756,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -578,12 +770,11 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            &balance_changes,
             status,
             accounted_balances,
-        );
+            &currencies,
+        )?;
 
         Ok(ops
             .into_iter()
@@ -593,15 +784,28 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
     }
 }
 
+/// Payments of coins other than SUI are parsed as generic operations, as their currencies are not
+/// known offline.
+impl TryFrom<SuiTransactionBlockData> for Operations {
+    type Error = Error;
+    fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
+        Self::from_transaction_data(data, &HashMap::new())
+    }
+}
+
 fn is_unstake_event(tag: &StructTag) -> bool {
     tag.address == SUI_SYSTEM_ADDRESS
         && tag.module.as_ident_str() == ident_str!("validator")
         && tag.name.as_ident_str() == ident_str!("UnstakingRequestEvent")
 }
 
-impl TryFrom<TransactionData> for Operations {
-    type Error = Error;
-    fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
+impl Operations {
+    /// Parse the operations of `data` offline, with the currencies of the coins other than SUI it
+    /// pays given by `currencies`.
+    pub fn try_from_data(
+        data: TransactionData,
+        currencies: &HashMap<TypeTag, Currency>,
+    ) -> Result<Self, Error> {
         struct NoOpsModuleResolver;
         impl ModuleResolver for NoOpsModuleResolver {
             type Error = Error;
@@ -610,7 +814,15 @@ impl TryFrom<TransactionData> for Operations {
             }
         }
         // Rosetta don't need the call args to be parsed into readable format
-        SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?.try_into()
+        let data = SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?;
+        Self::from_transaction_data(data, currencies)
+    }
+}
+
+impl TryFrom<TransactionData> for Operations {
+    type Error = Error;
+    fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
+        Self::try_from_data(data, &HashMap::new())
     }
 }
 
@@ -698,13 +910,35 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: Amount) -> Self {
+        let type_ = if amount.currency == *SUI {
+            OperationType::SuiBalanceChange
+        } else {
+            OperationType::CoinBalanceChange
+        };
         Self {
             operation_identifier: Default::default(),
-            type_: OperationType::SuiBalanceChange,
+            type_,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(amount),
             coin_change: None,
             metadata: None,
         }
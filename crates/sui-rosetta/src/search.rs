@@ -0,0 +1,300 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use tracing::debug;
+
+use sui_json_rpc_types::{
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionFilter,
+};
+use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::operations::Operations;
+use crate::types::{
+    BlockTransaction, CoinID, Operator, SearchTransactionsRequest, SearchTransactionsResponse,
+    Transaction, TransactionIdentifier,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+/// This module implements the [Rosetta Search API](https://www.rosetta-api.org/docs/SearchApi.html)
+
+/// The default, and maximum, number of transactions returned by a search.
+const MAX_LIMIT: u64 = 100;
+/// The number of transactions requested from the fullnode at a time.
+const QUERY_PAGE_SIZE: usize = 50;
+/// The number of transactions scanned per source before a search gives up looking for more
+/// matches. Only searches on conditions that the fullnode doesn't index, e.g. the operation type,
+/// are expected to reach it.
+const MAX_SCANNED_TRANSACTIONS: usize = 10_000;
+
+/// Search for transactions matching a set of conditions, from the newest to the oldest.
+///
+/// Transactions are looked up from the fullnode's transaction indexes: by sender and recipient
+/// for accounts, and by input and changed objects for coins. Conditions without an index are
+/// checked against each transaction's operations. As matches can't be counted without scanning
+/// them all, `total_count` only counts the matches found so far.
+///
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/SearchApi.html#searchtransactions)
+pub async fn transactions(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<SearchTransactionsRequest>, Error>,
+) -> Result<SearchTransactionsResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    let offset = request.offset.unwrap_or(0);
+    let limit = request.limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT);
+    if limit == 0 {
+        return Err(Error::InvalidInput("Limit must be greater than 0".into()));
+    }
+    let out_of_range = || Error::InvalidInput(format!("Offset {offset} is out of range"));
+    let end = offset.checked_add(limit).ok_or_else(out_of_range)?;
+    // One more match than requested tells whether there is a next page.
+    let wanted = usize::try_from(end)
+        .ok()
+        .and_then(|end| end.checked_add(1))
+        .ok_or_else(out_of_range)?;
+
+    let mut matches = vec![];
+    for source in sources(&request) {
+        debug!("Searching transactions from {source:?}");
+        matches.extend(search(&context, &request, source, wanted).await?);
+    }
+    // Sources can overlap, and are each ordered from newest to oldest.
+    let mut seen = HashSet::new();
+    matches.retain(|(_, transaction)| seen.insert(transaction.transaction_identifier.hash));
+    matches.sort_by_key(|(checkpoint, _)| Reverse(*checkpoint));
+
+    let total_count = matches.len() as u64;
+    let next_offset = (total_count > end).then_some(end);
+
+    let mut block_identifiers = HashMap::new();
+    let mut transactions = vec![];
+    for (checkpoint, transaction) in matches
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+    {
+        let block_identifier = match block_identifiers.get(&checkpoint) {
+            Some(block_identifier) => *block_identifier,
+            None => {
+                let block_identifier = context.blocks().create_block_identifier(checkpoint).await?;
+                block_identifiers.insert(checkpoint, block_identifier);
+                block_identifier
+            }
+        };
+        transactions.push(BlockTransaction {
+            block_identifier,
+            transaction,
+        });
+    }
+
+    Ok(SearchTransactionsResponse {
+        transactions,
+        total_count,
+        next_offset,
+    })
+}
+
+/// Where the candidate transactions of a search are read from.
+#[derive(Debug)]
+enum Source {
+    Transaction(TransactionDigest),
+    /// The fullnode's transaction indexes, or all transactions when there is no filter.
+    Index(Option<TransactionFilter>),
+}
+
+fn addresses(request: &SearchTransactionsRequest) -> Vec<SuiAddress> {
+    let mut addresses: Vec<_> = request
+        .account_identifier
+        .iter()
+        .map(|account| account.address)
+        .chain(request.address)
+        .collect();
+    addresses.dedup();
+    addresses
+}
+
+/// The sources of the transactions matching each indexed condition of the request, from the most
+/// to the least selective condition.
+fn indexed_sources(request: &SearchTransactionsRequest) -> Vec<Vec<Source>> {
+    let mut sources = vec![];
+    if let Some(transaction) = &request.transaction_identifier {
+        sources.push(vec![Source::Transaction(transaction.hash)]);
+    }
+    if let Some(coin) = &request.coin_identifier {
+        let id = coin.identifier.id;
+        sources.push(vec![
+            Source::Index(Some(TransactionFilter::InputObject(id))),
+            Source::Index(Some(TransactionFilter::ChangedObject(id))),
+        ]);
+    }
+    for address in addresses(request) {
+        sources.push(vec![
+            Source::Index(Some(TransactionFilter::FromAddress(address))),
+            Source::Index(Some(TransactionFilter::ToAddress(address))),
+        ]);
+    }
+    sources
+}
+
+fn sources(request: &SearchTransactionsRequest) -> Vec<Source> {
+    let mut indexed = indexed_sources(request);
+    let has_unindexed_conditions = request.currency.is_some()
+        || request.status.is_some()
+        || request.type_.is_some()
+        || request.success.is_some();
+    match request.operator {
+        // Every match satisfies the most selective condition.
+        Operator::And if !indexed.is_empty() => indexed.remove(0),
+        Operator::Or if !indexed.is_empty() && !has_unindexed_conditions => {
+            indexed.into_iter().flatten().collect()
+        }
+        _ => vec![Source::Index(None)],
+    }
+}
+
+/// Returns up to around `wanted` of the transactions from `source` that match the request, along
+/// with the checkpoints they are included in.
+async fn search(
+    context: &OnlineServerContext,
+    request: &SearchTransactionsRequest,
+    source: Source,
+    wanted: usize,
+) -> Result<Vec<(CheckpointSequenceNumber, Transaction)>, Error> {
+    let options = SuiTransactionBlockResponseOptions::new()
+        .with_input()
+        .with_effects()
+        .with_balance_changes()
+        .with_events();
+    let mut matches = vec![];
+    match source {
+        Source::Transaction(digest) => {
+            // Unlike a single get, a multi get doesn't fail on unknown transactions.
+            let responses = context
+                .client
+                .read_api()
+                .multi_get_transactions_with_options(vec![digest], options)
+                .await?;
+            for response in responses {
+                matches.extend(to_match(context, request, response).await?);
+            }
+        }
+        Source::Index(filter) => {
+            let query = SuiTransactionBlockResponseQuery::new(filter, Some(options));
+            let mut cursor = None;
+            let mut scanned = 0;
+            while matches.len() < wanted && scanned < MAX_SCANNED_TRANSACTIONS {
+                let page = context
+                    .client
+                    .read_api()
+                    .query_transaction_blocks(query.clone(), cursor, Some(QUERY_PAGE_SIZE), true)
+                    .await?;
+                scanned += page.data.len();
+                for response in page.data {
+                    matches.extend(to_match(context, request, response).await?);
+                }
+                if !page.has_next_page {
+                    break;
+                }
+                cursor = page.next_cursor;
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Convert `response` into a Rosetta transaction if it matches the request. Transactions which
+/// aren't included in a checkpoint yet are left to the mempool endpoints.
+async fn to_match(
+    context: &OnlineServerContext,
+    request: &SearchTransactionsRequest,
+    response: SuiTransactionBlockResponse,
+) -> Result<Option<(CheckpointSequenceNumber, Transaction)>, Error> {
+    let Some(checkpoint) = response.checkpoint else {
+        return Ok(None);
+    };
+    if response.transaction.is_none()
+        || request
+            .max_block
+            .map_or(false, |max_block| checkpoint > max_block)
+    {
+        return Ok(None);
+    }
+
+    let hash = response.digest;
+    let effects = response
+        .effects
+        .as_ref()
+        .ok_or_else(|| Error::DataError(format!("Missing effects for transaction {hash}")))?;
+    let success = matches!(effects.status(), SuiExecutionStatus::Success);
+    let coin_matches = request.coin_identifier.as_ref().map(|coin| {
+        let CoinID { id, version } = coin.identifier.clone();
+        // A coin version is either spent or created by a transaction.
+        effects.modified_at_versions().contains(&(id, version))
+            || effects.all_changed_objects().iter().any(|(object, _)| {
+                object.reference.object_id == id && object.reference.version == version
+            })
+    });
+
+    let operations = Operations::try_from_response(response, &context.coin_metadata_cache).await?;
+    // Currencies are compared by coin type, as it may be written in different forms.
+    let coin_type = request
+        .currency
+        .as_ref()
+        .map(|currency| currency.coin_type())
+        .transpose()?;
+    let addresses = addresses(request);
+    let conditions: Vec<bool> = [
+        request
+            .transaction_identifier
+            .as_ref()
+            .map(|transaction| transaction.hash == hash),
+        coin_matches,
+        coin_type.map(|coin_type| {
+            operations.iter().any(|op| {
+                op.amount.as_ref().map_or(false, |amount| {
+                    amount.currency.coin_type().ok().as_ref() == Some(&coin_type)
+                })
+            })
+        }),
+        request
+            .status
+            .map(|status| operations.iter().any(|op| op.status == Some(status))),
+        request
+            .type_
+            .map(|type_| operations.iter().any(|op| op.type_ == type_)),
+        request.success.map(|expected| success == expected),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(addresses.iter().map(|address| {
+        operations
+            .iter()
+            .any(|op| op.account.as_ref().map(|account| &account.address) == Some(address))
+    }))
+    .collect();
+
+    let is_match = match request.operator {
+        Operator::And => conditions.iter().all(|matched| *matched),
+        Operator::Or => conditions.is_empty() || conditions.iter().any(|matched| *matched),
+    };
+    Ok(is_match.then(|| {
+        (
+            checkpoint,
+            Transaction {
+                transaction_identifier: TransactionIdentifier { hash },
+                operations,
+                related_transactions: vec![],
+                metadata: None,
+            },
+        )
+    }))
+}
@@ -3,14 +3,18 @@
 
 use crate::operations::Operations;
 use crate::types::{
-    Block, BlockHash, BlockIdentifier, BlockResponse, Transaction, TransactionIdentifier,
+    Block, BlockHash, BlockIdentifier, BlockResponse, Currency, CurrencyMetadata, Transaction,
+    TransactionIdentifier,
 };
-use crate::Error;
+use crate::{Error, SUI};
 use async_trait::async_trait;
-use std::sync::Arc;
+use move_core_types::language_storage::TypeTag;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::rpc_types::Checkpoint;
 use sui_sdk::SuiClient;
+use sui_types::gas_coin::GAS;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 #[cfg(test)]
@@ -20,13 +24,19 @@ mod balance_changing_tx_tests;
 #[derive(Clone)]
 pub struct OnlineServerContext {
     pub client: SuiClient,
+    pub coin_metadata_cache: CoinMetadataCache,
     block_provider: Arc<dyn BlockProvider + Send + Sync>,
 }
 
 impl OnlineServerContext {
-    pub fn new(client: SuiClient, block_provider: Arc<dyn BlockProvider + Send + Sync>) -> Self {
+    pub fn new(
+        client: SuiClient,
+        block_provider: Arc<dyn BlockProvider + Send + Sync>,
+        coin_metadata_cache: CoinMetadataCache,
+    ) -> Self {
         Self {
             client,
+            coin_metadata_cache,
             block_provider,
         }
     }
@@ -36,6 +46,55 @@ impl OnlineServerContext {
     }
 }
 
+/// Rosetta currencies of the coin types seen so far, built from their `CoinMetadata`.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    client: SuiClient,
+    currencies: Arc<Mutex<HashMap<TypeTag, Currency>>>,
+}
+
+impl CoinMetadataCache {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            currencies: Default::default(),
+        }
+    }
+
+    /// The currency of coins of type `coin_type`. Coins without a `CoinMetadata` are reported in
+    /// their base unit, using their type as symbol.
+    pub async fn get_currency(&self, coin_type: &TypeTag) -> Result<Currency, Error> {
+        if coin_type == &GAS::type_tag() {
+            return Ok(SUI.clone());
+        }
+        if let Some(currency) = self.currencies.lock().unwrap().get(coin_type) {
+            return Ok(currency.clone());
+        }
+
+        let metadata = self
+            .client
+            .coin_read_api()
+            .get_coin_metadata(coin_type.to_string())
+            .await?;
+        let (symbol, decimals) = match metadata {
+            Some(metadata) => (metadata.symbol, metadata.decimals as u64),
+            None => (coin_type.to_string(), 0),
+        };
+        let currency = Currency {
+            symbol,
+            decimals,
+            metadata: Some(CurrencyMetadata {
+                coin_type: coin_type.to_string(),
+            }),
+        };
+        self.currencies
+            .lock()
+            .unwrap()
+            .insert(coin_type.clone(), currency.clone());
+        Ok(currency)
+    }
+}
+
 #[async_trait]
 pub trait BlockProvider {
     async fn get_block_by_index(&self, index: u64) -> Result<BlockResponse, Error>;
@@ -53,6 +112,7 @@ pub trait BlockProvider {
 #[derive(Clone)]
 pub struct CheckpointBlockProvider {
     client: SuiClient,
+    coin_metadata_cache: CoinMetadataCache,
 }
 
 #[async_trait]
@@ -103,8 +163,11 @@ impl BlockProvider for CheckpointBlockProvider {
 }
 
 impl CheckpointBlockProvider {
-    pub fn new(client: SuiClient) -> Self {
-        Self { client }
+    pub fn new(client: SuiClient, coin_metadata_cache: CoinMetadataCache) -> Self {
+        Self {
+            client,
+            coin_metadata_cache,
+        }
     }
 
     async fn create_block_response(&self, checkpoint: Checkpoint) -> Result<BlockResponse, Error> {
@@ -127,7 +190,8 @@ impl CheckpointBlockProvider {
             for tx in transaction_responses.into_iter() {
                 transactions.push(Transaction {
                     transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                    operations: Operations::try_from(tx)?,
+                    operations: Operations::try_from_response(tx, &self.coin_metadata_cache)
+                        .await?,
                     related_transactions: vec![],
                     metadata: None,
                 })
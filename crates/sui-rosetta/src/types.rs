@@ -14,16 +14,19 @@ use serde_json::Value;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
+use move_core_types::language_storage::TypeTag;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockKind};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::coin::{PAY_MODULE_NAME, PAY_SPLIT_AND_TRANSFER_FUNC_NAME};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::gas_coin::GAS;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, TransactionData};
-use sui_types::SUI_SYSTEM_PACKAGE_ID;
+use sui_types::{parse_sui_type_tag, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::errors::{Error, ErrorType};
 use crate::operations::Operations;
@@ -95,10 +98,33 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CurrencyMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
+}
+
+impl Currency {
+    /// The type of the coins of this currency. Currencies other than SUI are identified by the
+    /// coin type in their metadata.
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        match &self.metadata {
+            Some(CurrencyMetadata { coin_type }) => parse_sui_type_tag(coin_type)
+                .map_err(|e| Error::InvalidInput(format!("Invalid coin type {coin_type}: {e}"))),
+            None if self == &*SUI => Ok(GAS::type_tag()),
+            None => Err(Error::InvalidInput(format!(
+                "Missing coin type metadata for currency {}",
+                self.symbol
+            ))),
+        }
+    }
 }
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
@@ -153,9 +179,12 @@ pub struct SubBalance {
 
 impl Amount {
     pub fn new(value: i128) -> Self {
+        Self::new_with_currency(value, SUI.clone())
+    }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
         Self {
             value,
-            currency: SUI.clone(),
+            currency,
             metadata: None,
         }
     }
@@ -397,8 +426,11 @@ pub enum OperationType {
     SuiBalanceChange,
     StakeReward,
     StakePrinciple,
+    // Balance changes of coins other than SUI
+    CoinBalanceChange,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -612,6 +644,10 @@ pub struct ConstructionParseRequest {
     pub network_identifier: NetworkIdentifier,
     pub signed: bool,
     pub transaction: Hex,
+    /// The currencies of the coins other than SUI paid by the transaction, as declared by the
+    /// caller, since coin metadata isn't available offline.
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
 }
 
 #[derive(Serialize)]
@@ -834,6 +870,93 @@ impl IntoResponse for BlockTransactionResponse {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BlockTransaction {
+    pub block_identifier: BlockIdentifier,
+    pub transaction: Transaction,
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Operator {
+    #[default]
+    And,
+    Or,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchTransactionsRequest {
+    pub network_identifier: NetworkIdentifier,
+    #[serde(default)]
+    pub operator: Operator,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<BlockHeight>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coin_identifier: Option<CoinIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<OperationStatus>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<OperationType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<SuiAddress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchTransactionsResponse {
+    pub transactions: Vec<BlockTransaction>,
+    pub total_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
+impl IntoResponse for SearchTransactionsResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MempoolResponse {
+    pub transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+impl IntoResponse for MempoolResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MempoolTransactionRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MempoolTransactionResponse {
+    pub transaction: Transaction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+impl IntoResponse for MempoolTransactionResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
 #[derive(Serialize, Clone)]
 pub struct PrefundedAccount {
     pub privkey: String,
@@ -859,6 +982,12 @@ pub enum InternalOperation {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         stake_ids: Vec<ObjectID>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
 }
 
 impl InternalOperation {
@@ -866,7 +995,8 @@ impl InternalOperation {
         match self {
             InternalOperation::PaySui { sender, .. }
             | InternalOperation::Stake { sender, .. }
-            | InternalOperation::WithdrawStake { sender, .. } => *sender,
+            | InternalOperation::WithdrawStake { sender, .. }
+            | InternalOperation::PayCoin { sender, .. } => *sender,
         }
     }
     /// Combine with ConstructionMetadata to form the TransactionData
@@ -939,6 +1069,43 @@ impl InternalOperation {
                 }
                 builder.finish()
             }
+            InternalOperation::PayCoin {
+                recipients,
+                amounts,
+                currency,
+                ..
+            } => {
+                let coin_type = currency.coin_type()?;
+                let mut builder = ProgrammableTransactionBuilder::new();
+                // The coins of the paid currency are in the metadata objects, merge them into the
+                // first one to pay from it.
+                let (coin, others) = metadata
+                    .objects
+                    .split_first()
+                    .ok_or_else(|| Error::MissingInput(format!("{} coins", currency.symbol)))?;
+                let coin = builder.obj(ObjectArg::ImmOrOwnedObject(*coin))?;
+                let others = others
+                    .iter()
+                    .map(|other| builder.obj(ObjectArg::ImmOrOwnedObject(*other)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !others.is_empty() {
+                    builder.command(Command::MergeCoins(coin, others));
+                }
+                // Unlike split and transfer commands, the `split_and_transfer` call records the
+                // coin type in the transaction, so that the currency can be recovered from it.
+                for (recipient, amount) in recipients.into_iter().zip(amounts) {
+                    let amount = builder.pure(amount)?;
+                    let recipient = builder.pure(recipient)?;
+                    builder.command(Command::move_call(
+                        SUI_FRAMEWORK_PACKAGE_ID,
+                        PAY_MODULE_NAME.to_owned(),
+                        PAY_SPLIT_AND_TRANSFER_FUNC_NAME.to_owned(),
+                        vec![coin_type.clone()],
+                        vec![coin, amount, recipient],
+                    ));
+                }
+                builder.finish()
+            }
         };
 
         Ok(TransactionData::new_programmable(
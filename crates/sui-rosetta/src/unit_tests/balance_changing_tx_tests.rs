@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::operations::Operations;
-use crate::types::{ConstructionMetadata, OperationStatus, OperationType};
+use crate::state::CoinMetadataCache;
+use crate::types::{
+    Amount, ConstructionMetadata, InternalOperation, OperationStatus, OperationType,
+};
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
 use rand::seq::{IteratorRandom, SliceRandom};
@@ -203,7 +206,7 @@ async fn test_publish_and_move_call() {
         builder.finish()
     };
 
-    test_transaction(
+    let response = test_transaction(
         &client,
         keystore,
         vec![],
@@ -215,6 +218,64 @@ async fn test_publish_and_move_call() {
         false,
     )
     .await;
+
+    // The minted coins are reported in their own currency.
+    let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+    let ops = Operations::try_from_response(response.clone(), &coin_metadata_cache)
+        .await
+        .unwrap();
+    let minted = ops
+        .iter()
+        .find(|op| op.type_ == OperationType::CoinBalanceChange)
+        .unwrap();
+    assert_eq!(minted.account.as_ref().unwrap().address, recipient);
+    let amount = minted.amount.clone().unwrap();
+    assert_eq!(amount.value, 10000);
+    assert_eq!(amount.currency.symbol, "MANAGED");
+    assert_eq!(amount.currency.decimals, 2);
+
+    // Test paying the minted coins
+    let coin = find_module_object(&response.object_changes.unwrap(), "::Coin<");
+    let metadata = ConstructionMetadata {
+        sender: recipient,
+        coins: vec![],
+        objects: vec![coin.reference.to_object_ref()],
+        total_coin_value: 0,
+        gas_price: rgp,
+        budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+    };
+    let data = InternalOperation::PayCoin {
+        sender: recipient,
+        recipients: vec![sender],
+        amounts: vec![1000],
+        currency: amount.currency.clone(),
+    }
+    .try_into_data(metadata)
+    .unwrap();
+    let TransactionKind::ProgrammableTransaction(pt) = data.into_kind() else {
+        panic!("Expecting a programmable transaction");
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![sender],
+        recipient,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        rgp,
+        false,
+    )
+    .await;
+    let ops = Operations::try_from_response(response, &coin_metadata_cache)
+        .await
+        .unwrap();
+    assert!(ops.iter().any(|op| op.type_ == OperationType::PayCoin
+        && op.account.as_ref().unwrap().address == sender
+        && op.amount == Some(Amount::new_with_currency(1000, amount.currency.clone()))));
+    assert!(ops
+        .iter()
+        .all(|op| op.type_ != OperationType::CoinBalanceChange));
 }
 
 #[tokio::test]
@@ -740,7 +801,10 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
+    let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+    let ops = Operations::try_from_response(response.clone(), &coin_metadata_cache)
+        .await
+        .unwrap();
     let balances_from_ops = extract_balance_changes_from_ops(ops);
 
     // get actual balance changed after transaction
@@ -1,14 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
 use move_core_types::value::MoveTypeLayout;
 use sui_json_rpc_types::SuiCallArg;
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
-use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
+use sui_types::transaction::{
+    CallArg, TransactionData, TransactionDataAPI, TransactionKind, TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+};
 
-use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::operations::{Operation, Operations};
+use crate::types::{ConstructionMetadata, Currency, CurrencyMetadata, OperationType};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -58,3 +62,51 @@ async fn test_sui_json() {
     let json2 = SuiCallArg::try_from(arg2, Some(&MoveTypeLayout::U64)).unwrap();
     println!("{:?}, {:?}", json1, json2);
 }
+
+#[tokio::test]
+async fn test_pay_coin_offline_parsing() -> Result<(), anyhow::Error> {
+    let coin = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let sender = SuiAddress::random_for_testing_only();
+    let currency = Currency {
+        symbol: "MANAGED".to_string(),
+        decimals: 2,
+        metadata: Some(CurrencyMetadata {
+            coin_type: format!("{}::managed::MANAGED", ObjectID::random()),
+        }),
+    };
+    let recipient = SuiAddress::random_for_testing_only();
+    let ops = Operations::new(vec![
+        Operation::pay_coin(None, recipient, 10000, currency.clone()),
+        Operation::pay_coin(None, sender, -10000, currency.clone()),
+    ]);
+
+    let gas_price = 10;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![],
+        objects: vec![coin],
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+    };
+    let data = ops.clone().into_internal()?.try_into_data(metadata)?;
+    // Only the paid coin, and the amount and recipient of the payment are inputs.
+    let TransactionKind::ProgrammableTransaction(pt) = data.kind() else {
+        panic!("Expected a programmable transaction");
+    };
+    assert_eq!(pt.inputs.len(), 3);
+
+    // The currency of the paid coins isn't known offline, unless it is declared.
+    let parsed: Operations = data.clone().try_into()?;
+    assert_eq!(parsed.type_(), Some(OperationType::ProgrammableTransaction));
+    let currencies = HashMap::from([(currency.coin_type()?, currency)]);
+    let parsed = Operations::try_from_data(data, &currencies)?;
+    assert_eq!(parsed.type_(), Some(OperationType::PayCoin));
+    assert_eq!(ops, parsed);
+
+    Ok(())
+}
@@ -3,16 +3,17 @@
 
 use std::time::Duration;
 
-use serde_json::json;
+use serde_json::{json, Value};
 
 use rosetta_client::start_rosetta_test_server;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_keys::keystore::AccountKeystore;
 use sui_rosetta::operations::Operations;
 use sui_rosetta::types::{
-    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, NetworkIdentifier,
-    SubAccount, SubAccountType, SuiEnv,
+    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, MempoolResponse,
+    NetworkIdentifier, SearchTransactionsResponse, SubAccount, SubAccountType, SuiEnv,
 };
+use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
@@ -174,7 +175,9 @@ async fn test_stake() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -234,7 +237,9 @@ async fn test_stake_all() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -348,7 +353,9 @@ async fn test_withdraw_stake() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -417,7 +424,9 @@ async fn test_pay_sui() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -475,7 +484,9 @@ async fn test_pay_sui_multiple_times() {
             tx.effects.as_ref().unwrap().status()
         );
 
-        let ops2 = Operations::try_from(tx).unwrap();
+        let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
         assert!(
             ops2.contains(&ops),
             "Operation mismatch. expecting:{}, got:{}",
@@ -484,3 +495,136 @@ async fn test_pay_sui_multiple_times() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_search_transactions() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PaySui",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "1000000000" , "currency": { "symbol": "SUI", "decimals": 9}}
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PaySui",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-1000000000" , "currency": { "symbol": "SUI", "decimals": 9}}
+        }]
+    ))
+    .unwrap();
+    let digest = rosetta_client
+        .rosetta_flow(&ops, keystore)
+        .await
+        .transaction_identifier
+        .hash;
+
+    let network_identifier = NetworkIdentifier {
+        blockchain: "sui".to_string(),
+        network: SuiEnv::LocalNet,
+    };
+
+    // The transaction is only searchable once it is included in a checkpoint.
+    let response: SearchTransactionsResponse =
+        tokio::time::timeout(Duration::from_secs(30), async {
+            loop {
+                let response: SearchTransactionsResponse = rosetta_client
+                    .call(
+                        RosettaEndpoint::SearchTransactions,
+                        &json!({
+                            "network_identifier": network_identifier,
+                            "account_identifier": { "address": recipient.to_string() },
+                            "type": "PaySui",
+                        }),
+                    )
+                    .await;
+                if !response.transactions.is_empty() {
+                    break response;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("Transaction was not included in a checkpoint in time");
+    assert_eq!(1, response.total_count);
+    assert_eq!(None, response.next_offset);
+    let transaction = &response.transactions[0].transaction;
+    assert_eq!(digest, transaction.transaction_identifier.hash);
+    assert!(transaction.operations.contains(&ops));
+
+    // Checkpointed transactions are no longer in the mempool.
+    let mempool: MempoolResponse = rosetta_client
+        .call(
+            RosettaEndpoint::Mempool,
+            &json!({ "network_identifier": network_identifier }),
+        )
+        .await;
+    assert!(mempool
+        .transaction_identifiers
+        .iter()
+        .all(|transaction| transaction.hash != digest));
+    let error: Value = rosetta_client
+        .call(
+            RosettaEndpoint::MempoolTransaction,
+            &json!({
+                "network_identifier": network_identifier,
+                "transaction_identifier": { "hash": digest },
+            }),
+        )
+        .await;
+    assert_eq!(
+        json!(format!("Transaction not in mempool: {digest}")),
+        error["details"]["error"]
+    );
+
+    // Offsets past the end of the results can't be paged to.
+    let error: Value = rosetta_client
+        .call(
+            RosettaEndpoint::SearchTransactions,
+            &json!({
+                "network_identifier": network_identifier,
+                "offset": u64::MAX,
+            }),
+        )
+        .await;
+    assert_eq!(
+        json!(format!(
+            "Invalid input: Offset {} is out of range",
+            u64::MAX
+        )),
+        error["details"]["error"]
+    );
+
+    // Both conditions have to match with the `and` operator, either with `or`.
+    let request = json!({
+        "network_identifier": network_identifier,
+        "transaction_identifier": { "hash": digest },
+        "type": "Stake",
+    });
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &request)
+        .await;
+    assert!(response.transactions.is_empty());
+
+    let mut request = request;
+    request["operator"] = json!("or");
+    request["limit"] = json!(1);
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &request)
+        .await;
+    assert_eq!(1, response.transactions.len());
+    assert_eq!(
+        digest,
+        response.transactions[0]
+            .transaction
+            .transaction_identifier
+            .hash
+    );
+}
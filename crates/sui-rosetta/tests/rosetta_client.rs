@@ -209,6 +209,9 @@ pub enum RosettaEndpoint {
     Submit,
     Metadata,
     Status,
+    SearchTransactions,
+    Mempool,
+    MempoolTransaction,
 }
 
 impl RosettaEndpoint {
@@ -229,6 +232,9 @@ impl RosettaEndpoint {
             RosettaEndpoint::Submit => "construction/submit",
             RosettaEndpoint::Metadata => "construction/metadata",
             RosettaEndpoint::Status => "network/status",
+            RosettaEndpoint::SearchTransactions => "search/transactions",
+            RosettaEndpoint::Mempool => "mempool",
+            RosettaEndpoint::MempoolTransaction => "mempool/transaction",
         }
     }
 
@@ -248,7 +254,10 @@ impl RosettaEndpoint {
             | RosettaEndpoint::Transaction
             | RosettaEndpoint::Submit
             | RosettaEndpoint::Metadata
-            | RosettaEndpoint::Status => true,
+            | RosettaEndpoint::Status
+            | RosettaEndpoint::SearchTransactions
+            | RosettaEndpoint::Mempool
+            | RosettaEndpoint::MempoolTransaction => true,
         }
     }
 }
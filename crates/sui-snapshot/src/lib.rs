@@ -9,7 +9,8 @@ pub mod reader;
 pub mod uploader;
 mod writer;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use fastcrypto::hash::MultisetHash;
 use num_enum::IntoPrimitive;
 use num_enum::TryFromPrimitive;
 use object_store::path::Path;
@@ -25,6 +26,9 @@ use sui_storage::{compute_sha3_checksum, FileCompression, SHA3_BYTES};
 use sui_types::accumulator::Accumulator;
 use sui_types::authenticator_state::get_authenticator_state_obj_initial_shared_version;
 use sui_types::base_types::ObjectID;
+use sui_types::messages_checkpoint::{
+    CheckpointCommitment, CheckpointSummary, ECMHLiveObjectSetDigest,
+};
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::sui_system_state::SuiSystemStateTrait;
@@ -102,9 +106,31 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 ///├──────────────────────────────┤
 ///│      sha3 <32 bytes>         │
 ///└──────────────────────────────┘
+///
+/// Incremental Snapshots
+/// A snapshot with a V2 MANIFEST may be a delta against the snapshot of a base epoch instead of a
+/// full live object set. Its *.obj and REFERENCE files only hold the objects created, mutated or
+/// unwrapped since the end of the base epoch, and its TOMBSTONE files (<bucket_number>_<partition_number>.del)
+/// hold the references of the objects deleted or wrapped since then, in the same format as
+/// REFERENCE files. The live object set at the end of an epoch is restored by reading the closest
+/// full snapshot and applying the chain of deltas on top of it in epoch order.
+///
+/// TOMBSTONE File Disk Format
+///┌──────────────────────────────┐
+///│  magic(0x0DE1E7ED) <4 byte>  │
+///├──────────────────────────────┤
+///│ ┌──────────────────────────┐ │
+///│ │         ObjectRef 1      │ │
+///│ ├──────────────────────────┤ │
+///│ │          ...             │ │
+///│ ├──────────────────────────┤ │
+///│ │         ObjectRef N      │ │
+///│ └──────────────────────────┘ │
+///└──────────────────────────────┘
 const OBJECT_FILE_MAGIC: u32 = 0x00B7EC75;
 const REFERENCE_FILE_MAGIC: u32 = 0xDEADBEEF;
 const MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEE;
+const TOMBSTONE_FILE_MAGIC: u32 = 0x0DE1E7ED;
 const MAGIC_BYTES: usize = 4;
 const SNAPSHOT_VERSION_BYTES: usize = 1;
 const ADDRESS_LENGTH_BYTES: usize = 8;
@@ -130,6 +156,7 @@ const FILE_METADATA_BYTES: usize =
 pub enum FileType {
     Object = 0,
     Reference,
+    Tombstone,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -150,6 +177,9 @@ impl FileMetadata {
             FileType::Reference => {
                 dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
             }
+            FileType::Tombstone => {
+                dir_path.child(&*format!("{}_{}.del", self.bucket_num, self.part_num))
+            }
         }
     }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
@@ -165,30 +195,59 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ManifestV2 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    /// The epoch this snapshot is a delta against, or `None` for a full snapshot.
+    pub base_epoch: Option<u64>,
+    /// Whether wrapped objects are part of the live object set, or tombstones.
+    pub include_wrapped_tombstone: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    V2(ManifestV2),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::V2(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::V2(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::V2(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::V2(manifest) => manifest.epoch,
+        }
+    }
+    pub fn base_epoch(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(manifest) => manifest.base_epoch,
+        }
+    }
+    pub fn include_wrapped_tombstone(&self) -> Option<bool> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(manifest) => Some(manifest.include_wrapped_tombstone),
         }
     }
 }
@@ -212,6 +271,46 @@ pub fn create_file_metadata(
     Ok(file_metadata)
 }
 
+/// Check the root state hash of a restored live object set against the commitment in the last
+/// checkpoint of the epoch it was restored to.
+pub fn verify_root_state_hash(
+    accumulator: &Accumulator,
+    last_checkpoint: &CheckpointSummary,
+) -> Result<()> {
+    let epoch = last_checkpoint.epoch;
+    let commitment = last_checkpoint
+        .end_of_epoch_data
+        .as_ref()
+        .ok_or(anyhow!(
+            "Checkpoint {} is not the last checkpoint of epoch {}",
+            last_checkpoint.sequence_number,
+            epoch
+        ))?
+        .epoch_commitments
+        .last()
+        .ok_or(anyhow!(
+            "End of epoch has no commitments. This likely means that the epoch \
+            you are attempting to restore from does not support end of epoch state \
+            digest commitment. If restoring from mainnet, `--epoch` must be > 20, \
+            and for testnet, `--epoch` must be > 12."
+        ))?;
+    match commitment {
+        CheckpointCommitment::ECMHLiveObjectSetDigest(consensus_digest) => {
+            let local_digest: ECMHLiveObjectSetDigest = accumulator.digest().into();
+            if *consensus_digest != local_digest {
+                return Err(anyhow!(
+                    "End of epoch {} root state digest {} does not match \
+                    local root state hash {} after restoring from formal snapshot",
+                    epoch,
+                    consensus_digest.digest,
+                    local_digest.digest,
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 pub async fn setup_db_state(
     epoch: u64,
     accumulator: Accumulator,
@@ -2,15 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
+    compute_sha3_checksum, FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC,
+    OBJECT_FILE_MAGIC, OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    SHA3_BYTES, TOMBSTONE_FILE_MAGIC,
 };
 use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
 use fastcrypto::hash::MultisetHash;
 use fastcrypto::hash::{HashFunction, Sha3_256};
-use futures::future::{AbortRegistration, Abortable};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
 use futures::{StreamExt, TryStreamExt};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use integer_encoding::VarIntReader;
@@ -27,7 +28,9 @@ use std::sync::Arc;
 use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::authority::AuthorityStore;
 use sui_storage::blob::{Blob, BlobEncoding};
-use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem};
+use sui_storage::object_store::util::{
+    copy_file, download_file_resumable, get_verified, path_to_filesystem,
+};
 use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
@@ -35,7 +38,7 @@ use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use tokio::time::Instant;
-use tracing::{error, info};
+use tracing::info;
 
 pub type SnapshotChecksums = (DigestByBucketAndPartition, Accumulator);
 pub type DigestByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, [u8; 32]>>;
@@ -46,6 +49,9 @@ pub struct StateSnapshotReaderV1 {
     local_object_store: Arc<DynObjectStore>,
     ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
     object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    tombstone_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    base_epoch: Option<u64>,
+    include_wrapped_tombstone: Option<bool>,
     indirect_objects_threshold: usize,
     m: MultiProgress,
     concurrency: usize,
@@ -88,7 +94,7 @@ impl StateSnapshotReaderV1 {
             &manifest_file_path,
         )?)?;
         let snapshot_version = manifest.snapshot_version();
-        if snapshot_version != 1u8 {
+        if snapshot_version != 1u8 && snapshot_version != 2u8 {
             return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
         }
         if manifest.address_length() as usize > ObjectID::LENGTH {
@@ -100,8 +106,17 @@ impl StateSnapshotReaderV1 {
         if manifest.epoch() != epoch {
             return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
         }
+        let base_epoch = manifest.base_epoch();
+        if base_epoch.is_some_and(|base_epoch| base_epoch >= epoch) {
+            return Err(anyhow!(
+                "Snapshot for epoch: {} has an invalid base epoch: {:?}",
+                epoch,
+                base_epoch
+            ));
+        }
         let mut object_files = BTreeMap::new();
         let mut ref_files = BTreeMap::new();
+        let mut tombstone_files = BTreeMap::new();
         for file_metadata in manifest.file_metadata() {
             match file_metadata.file_type {
                 FileType::Object => {
@@ -116,37 +131,55 @@ impl StateSnapshotReaderV1 {
                         .or_insert_with(BTreeMap::new);
                     entry.insert(file_metadata.part_num, file_metadata.clone());
                 }
+                FileType::Tombstone => {
+                    let entry = tombstone_files
+                        .entry(file_metadata.bucket_num)
+                        .or_insert_with(BTreeMap::new);
+                    entry.insert(file_metadata.part_num, file_metadata.clone());
+                }
             }
         }
         let epoch_dir_path = Path::from(epoch_dir);
-        let files: Vec<Path> = ref_files
+        let files: Vec<FileMetadata> = ref_files
             .values()
-            .flat_map(|entry| {
-                let files: Vec<_> = entry
-                    .values()
-                    .map(|file_metadata| file_metadata.file_path(&epoch_dir_path))
-                    .collect();
-                files
-            })
+            .chain(tombstone_files.values())
+            .flat_map(|entry| entry.values().cloned().collect::<Vec<_>>())
             .collect();
 
         let progress_bar = m.add(
             ProgressBar::new(files.len() as u64).with_style(
                 ProgressStyle::with_template(
-                    "[{elapsed_precise}] {wide_bar} {pos} out of {len} .ref files done\n({msg})",
+                    "[{elapsed_precise}] {wide_bar} {pos} out of {len} .ref and .del files done\n({msg})",
                 )
                 .unwrap(),
             ),
         );
-        copy_files(
-            &files,
-            &files,
-            remote_object_store.clone(),
-            local_object_store.clone(),
-            download_concurrency,
-            Some(progress_bar.clone()),
-        )
-        .await?;
+        // Downloads are checked against the manifest and resumed from where they stopped
+        futures::stream::iter(files.iter())
+            .map(|file_metadata| {
+                let remote_object_store = remote_object_store.clone();
+                let file_path = file_metadata.file_path(&epoch_dir_path);
+                let local_path =
+                    file_metadata.local_file_path(&local_staging_dir_root, &epoch_dir_path);
+                async move {
+                    download_file_resumable(
+                        &file_path,
+                        remote_object_store,
+                        &local_path?,
+                        &file_metadata.sha3_digest,
+                    )
+                    .await?;
+                    Ok::<Path, anyhow::Error>(file_path)
+                }
+            })
+            .boxed()
+            .buffer_unordered(download_concurrency.get())
+            .try_for_each(|file_path| {
+                progress_bar.inc(1);
+                progress_bar.set_message(format!("file: {}", file_path));
+                futures::future::ready(Ok(()))
+            })
+            .await?;
         progress_bar.finish_with_message("ref files download complete");
         Ok(StateSnapshotReaderV1 {
             epoch,
@@ -155,18 +188,34 @@ impl StateSnapshotReaderV1 {
             local_object_store,
             ref_files,
             object_files,
+            tombstone_files,
+            base_epoch,
+            include_wrapped_tombstone: manifest.include_wrapped_tombstone(),
             indirect_objects_threshold,
             m,
             concurrency: download_concurrency.get(),
         })
     }
 
+    /// The epoch this snapshot is a delta against, or `None` for a full snapshot.
+    pub fn base_epoch(&self) -> Option<u64> {
+        self.base_epoch
+    }
+
+    /// Read the snapshot into `perpetual_db`. An incremental snapshot must be read on top of the
+    /// live object set at the end of its base epoch, and can't be accumulated on its own.
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
         abort_registration: AbortRegistration,
         sender: Option<tokio::sync::mpsc::Sender<Accumulator>>,
     ) -> Result<()> {
+        if self.base_epoch.is_some() && sender.is_some() {
+            return Err(anyhow!(
+                "Cannot accumulate incremental snapshot for epoch: {} on its own",
+                self.epoch
+            ));
+        }
         // This computes and stores the sha3 digest of object references in REFERENCE file for each
         // bucket partition. When downloading objects, we will match sha3 digest of object references
         // per *.obj file against this. We do this so during restore we can pre fetch object
@@ -224,6 +273,7 @@ impl StateSnapshotReaderV1 {
 
         self.sync_live_objects(perpetual_db, abort_registration, sha3_digests)
             .await?;
+        self.apply_tombstones(perpetual_db)?;
 
         if let Some(handle) = accum_handle {
             handle.await?;
@@ -231,6 +281,27 @@ impl StateSnapshotReaderV1 {
         Ok(())
     }
 
+    fn apply_tombstones(&self, perpetual_db: &AuthorityPerpetualTables) -> Result<()> {
+        for (bucket, part_files) in self.tombstone_files.iter() {
+            for (part, file_metadata) in part_files.iter() {
+                let file_path = file_metadata
+                    .local_file_path(&self.local_staging_dir_root, &self.epoch_dir())?;
+                if compute_sha3_checksum(&file_path)? != file_metadata.sha3_digest {
+                    return Err(anyhow!(
+                        "Checksum of tombstone file for bucket: {bucket}, part: {part} doesn't match"
+                    ));
+                }
+                let tombstones = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    self.epoch_dir(),
+                )?;
+                AuthorityStore::bulk_insert_tombstones(perpetual_db, tombstones)?;
+            }
+        }
+        Ok(())
+    }
+
     fn spawn_accumulation_tasks(
         &self,
         sender: tokio::sync::mpsc::Sender<Accumulator>,
@@ -329,6 +400,7 @@ impl StateSnapshotReaderV1 {
         let epoch_dir = self.epoch_dir();
         let concurrency = self.concurrency;
         let threshold = self.indirect_objects_threshold;
+        let is_delta = self.base_epoch.is_some();
         let remote_object_store = self.remote_object_store.clone();
         let input_files: Vec<_> = self
             .object_files
@@ -357,66 +429,16 @@ impl StateSnapshotReaderV1 {
             async move {
                 futures::stream::iter(input_files.iter())
                     .map(|(bucket, (part_num, file_metadata))| {
-                        let epoch_dir = epoch_dir.clone();
                         let file_path = file_metadata.file_path(&epoch_dir);
                         let remote_object_store = remote_object_store.clone();
                         let sha3_digests_cloned = sha3_digests.clone();
                         async move {
-                            // Download object file with retries
-                            let max_timeout = Duration::from_secs(30);
-                            let mut timeout = Duration::from_secs(2);
-                            timeout += timeout / 2;
-                            timeout = std::cmp::min(max_timeout, timeout);
-                            let mut attempts = 0usize;
-                            let bytes = loop {
-                                let get_result = match remote_object_store.get(&file_path).await {
-                                    Ok(get_result) => {
-                                        timeout = Duration::from_secs(2);
-                                        attempts = 0usize;
-                                        get_result
-                                    }
-                                    Err(err) => {
-                                        error!(
-                                            "Obj {} .get failed (attempt {}): {}",
-                                            file_metadata.file_path(&epoch_dir),
-                                            attempts,
-                                            err,
-                                        );
-                                        if timeout > max_timeout {
-                                            panic!(
-                                                "Failed to get obj file after {} attempts",
-                                                attempts
-                                            );
-                                        } else {
-                                            attempts += 1;
-                                            tokio::time::sleep(timeout).await;
-                                            timeout += timeout / 2;
-                                            continue;
-                                        }
-                                    }
-                                };
-                                match get_result.bytes().await {
-                                    Ok(bytes) => {
-                                        break bytes;
-                                    }
-                                    Err(err) => {
-                                        error!(
-                                            "Obj {} bytes conversion (attempt {}) failed: {}",
-                                            file_metadata.file_path(&epoch_dir),
-                                            attempts,
-                                            err,
-                                        );
-                                        if timeout > max_timeout {
-                                            panic!("Failed bytes() after {} attempts", attempts);
-                                        } else {
-                                            attempts += 1;
-                                            tokio::time::sleep(timeout).await;
-                                            timeout += timeout / 2;
-                                            continue;
-                                        }
-                                    }
-                                }
-                            };
+                            let bytes = get_verified(
+                                &file_path,
+                                remote_object_store,
+                                &file_metadata.sha3_digest,
+                            )
+                            .await?;
 
                             let sha3_digest = sha3_digests_cloned.lock().await;
                             let bucket_map = sha3_digest
@@ -439,12 +461,21 @@ impl StateSnapshotReaderV1 {
                         let bytes_len = bytes.len();
                         let result: Result<(), anyhow::Error> =
                             LiveObjectIter::new(&file_metadata, bytes).map(|obj_iter| {
-                                AuthorityStore::bulk_insert_live_objects(
-                                    perpetual_db,
-                                    obj_iter,
-                                    threshold,
-                                    &sha3_digest,
-                                )
+                                if is_delta {
+                                    AuthorityStore::bulk_update_live_objects(
+                                        perpetual_db,
+                                        obj_iter,
+                                        threshold,
+                                        &sha3_digest,
+                                    )
+                                } else {
+                                    AuthorityStore::bulk_insert_live_objects(
+                                        perpetual_db,
+                                        obj_iter,
+                                        threshold,
+                                        &sha3_digest,
+                                    )
+                                }
                                 .expect("Failed to insert live objects");
                             });
                         downloaded_bytes.fetch_add(bytes_len, Ordering::Relaxed);
@@ -522,7 +553,82 @@ impl StateSnapshotReaderV1 {
     }
 }
 
-/// An iterator over all object refs in a .ref file.
+/// Restore the live object set at the end of `epoch` into `perpetual_db`. If the snapshot of the
+/// epoch is incremental, the full snapshot it is based on is read first and the chain of
+/// incremental snapshots is applied on top of it in epoch order. The accumulator of the restored
+/// live object set is sent to `sender`, if any.
+#[allow(clippy::too_many_arguments)]
+pub async fn read_snapshot_chain(
+    epoch: u64,
+    remote_store_config: &ObjectStoreConfig,
+    local_store_config: &ObjectStoreConfig,
+    indirect_objects_threshold: usize,
+    download_concurrency: NonZeroUsize,
+    m: MultiProgress,
+    perpetual_db: &AuthorityPerpetualTables,
+    abort_registration: AbortRegistration,
+    sender: Option<tokio::sync::mpsc::Sender<Accumulator>>,
+) -> Result<()> {
+    let mut readers = vec![];
+    let mut next_epoch = Some(epoch);
+    while let Some(epoch) = next_epoch {
+        let reader = StateSnapshotReaderV1::new(
+            epoch,
+            remote_store_config,
+            local_store_config,
+            indirect_objects_threshold,
+            download_concurrency,
+            m.clone(),
+        )
+        .await?;
+        next_epoch = reader.base_epoch();
+        readers.push(reader);
+    }
+    readers.reverse();
+    info!(
+        "Restoring epoch {} from the snapshots of epochs: {:?}",
+        epoch,
+        readers
+            .iter()
+            .map(|reader| reader.epoch)
+            .collect::<Vec<_>>()
+    );
+    let include_wrapped_tombstone = readers
+        .last()
+        .and_then(|reader| reader.include_wrapped_tombstone);
+
+    if readers.len() == 1 {
+        return readers[0]
+            .read(perpetual_db, abort_registration, sender)
+            .await;
+    }
+    Abortable::new(
+        async {
+            for reader in readers.iter_mut() {
+                let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+                reader.read(perpetual_db, abort_registration, None).await?;
+            }
+            Ok::<(), anyhow::Error>(())
+        },
+        abort_registration,
+    )
+    .await??;
+
+    // The references of the objects superseded by the incremental snapshots are not part of them,
+    // so the accumulator is computed over the restored live object set instead.
+    if let Some(sender) = sender {
+        let include_wrapped_tombstone =
+            include_wrapped_tombstone.context("Incremental snapshot without a V2 manifest")?;
+        let mut accumulator = Accumulator::default();
+        for live_object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
+            accumulator.insert(live_object.object_reference().2);
+        }
+        sender.send(accumulator).await?;
+    }
+    Ok(())
+}
+
+/// An iterator over all object refs in a .ref or .del file.
 pub struct ObjectRefIter {
     reader: Box<dyn Read>,
 }
@@ -532,9 +638,14 @@ impl ObjectRefIter {
         let file_path = file_metadata.local_file_path(&root_path, &dir_path)?;
         let mut reader = file_metadata.file_compression.decompress(&file_path)?;
         let magic = reader.read_u32::<BigEndian>()?;
-        if magic != REFERENCE_FILE_MAGIC {
+        let expected_magic = match file_metadata.file_type {
+            FileType::Tombstone => TOMBSTONE_FILE_MAGIC,
+            _ => REFERENCE_FILE_MAGIC,
+        };
+        if magic != expected_magic {
             Err(anyhow!(
-                "Unexpected magic string in REFERENCE file: {:?}",
+                "Unexpected magic string in {:?} file: {:?}",
+                file_metadata.file_type,
                 magic
             ))
         } else {
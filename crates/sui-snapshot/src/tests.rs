@@ -1,18 +1,21 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::reader::StateSnapshotReaderV1;
+use crate::reader::{read_snapshot_chain, StateSnapshotReaderV1};
 use crate::writer::StateSnapshotWriterV1;
 use crate::FileCompression;
+use fastcrypto::hash::MultisetHash;
 use futures::future::AbortHandle;
 use indicatif::MultiProgress;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::AuthorityStore;
 use sui_protocol_config::ProtocolConfig;
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
-use sui_types::base_types::ObjectID;
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::object::Object;
 use tempfile::tempdir;
 
@@ -160,3 +163,132 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_snapshot_delta() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+    let snapshot_writer = || {
+        StateSnapshotWriterV1::new(
+            &local_store_config,
+            &remote_store_config,
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+    };
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    snapshot_writer()
+        .await?
+        .write_internal(0, false, perpetual_db.clone())
+        .await?;
+
+    // Epoch 1 mutates, deletes and creates objects
+    let ids = ObjectID::in_range(ObjectID::ZERO, 1100)?;
+    let mut changed_objects = BTreeMap::new();
+    for id in &ids[0..100] {
+        let object = Object::with_id_owner_version_for_testing(
+            *id,
+            SequenceNumber::from_u64(2),
+            SuiAddress::ZERO,
+        );
+        changed_objects.insert(*id, object.compute_object_reference());
+        perpetual_db.insert_object_test_only(object)?;
+    }
+    let deleted: Vec<ObjectRef> = ids[100..150]
+        .iter()
+        .map(|id| {
+            (
+                *id,
+                SequenceNumber::from_u64(2),
+                ObjectDigest::OBJECT_DIGEST_DELETED,
+            )
+        })
+        .collect();
+    AuthorityStore::bulk_insert_tombstones(&perpetual_db, deleted.iter().copied())?;
+    changed_objects.extend(deleted.into_iter().map(|obj_ref| (obj_ref.0, obj_ref)));
+    for id in &ids[1000..1100] {
+        let object = Object::immutable_with_id_for_testing(*id);
+        changed_objects.insert(*id, object.compute_object_reference());
+        perpetual_db.insert_object_test_only(object)?;
+    }
+    snapshot_writer()
+        .await?
+        .write_delta_internal(1, 0, false, perpetual_db.clone(), changed_objects)
+        .await?;
+
+    // Epoch 2 mutates objects again and deletes some created in epoch 1
+    let mut changed_objects = BTreeMap::new();
+    for id in &ids[0..10] {
+        let object = Object::with_id_owner_version_for_testing(
+            *id,
+            SequenceNumber::from_u64(3),
+            SuiAddress::ZERO,
+        );
+        changed_objects.insert(*id, object.compute_object_reference());
+        perpetual_db.insert_object_test_only(object)?;
+    }
+    let wrapped: Vec<ObjectRef> = ids[1000..1010]
+        .iter()
+        .map(|id| {
+            (
+                *id,
+                SequenceNumber::from_u64(2),
+                ObjectDigest::OBJECT_DIGEST_WRAPPED,
+            )
+        })
+        .collect();
+    AuthorityStore::bulk_insert_tombstones(&perpetual_db, wrapped.iter().copied())?;
+    changed_objects.extend(wrapped.into_iter().map(|obj_ref| (obj_ref.0, obj_ref)));
+    snapshot_writer()
+        .await?
+        .write_delta_internal(2, 1, false, perpetual_db.clone(), changed_objects)
+        .await?;
+
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+    read_snapshot_chain(
+        2,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+        &restored_perpetual_db,
+        abort_registration,
+        Some(sender),
+    )
+    .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, false)?;
+    assert_eq!(
+        restored_perpetual_db.iter_live_object_set(false).count(),
+        1040
+    );
+
+    let mut expected_accumulator = Accumulator::default();
+    for live_object in perpetual_db.iter_live_object_set(false) {
+        expected_accumulator.insert(live_object.object_reference().2);
+    }
+    let accumulator = receiver.recv().await.expect("No accumulator received");
+    assert_eq!(accumulator.digest(), expected_accumulator.digest());
+    Ok(())
+}
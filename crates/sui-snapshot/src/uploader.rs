@@ -12,6 +12,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
 use sui_core::db_checkpoint_handler::{
     STATE_SNAPSHOT_COMPLETED_MARKER, SUCCESS_MARKER, UPLOAD_COMPLETED_MARKER,
 };
@@ -22,7 +23,8 @@ use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_storage::FileCompression;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+use typed_store::rocks::MetricConf;
 
 pub struct StateSnapshotUploaderMetrics {
     pub first_missing_state_snapshot_epoch: IntGauge,
@@ -55,6 +57,8 @@ pub struct StateSnapshotUploader {
     snapshot_store: Arc<DynObjectStore>,
     /// Time interval to check for presence of new db checkpoint
     interval: Duration,
+    /// Number of epochs between full snapshots, with incremental snapshots in between, if set
+    full_snapshot_interval_epochs: Option<u64>,
     metrics: Arc<StateSnapshotUploaderMetrics>,
 }
 
@@ -64,6 +68,7 @@ impl StateSnapshotUploader {
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
         interval_s: u64,
+        full_snapshot_interval_epochs: Option<u64>,
         registry: &Registry,
     ) -> Result<Self> {
         let db_checkpoint_store_config = ObjectStoreConfig {
@@ -83,6 +88,7 @@ impl StateSnapshotUploader {
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
             interval: Duration::from_secs(interval_s),
+            full_snapshot_interval_epochs,
             metrics: StateSnapshotUploaderMetrics::new(registry),
         })
     }
@@ -121,6 +127,7 @@ impl StateSnapshotUploader {
             find_all_dirs_with_epoch_prefix(&self.db_checkpoint_store, None).await?;
         let mut dirs: Vec<_> = local_checkpoints_by_epoch.iter().collect();
         dirs.sort_by_key(|(epoch_num, _path)| *epoch_num);
+        let mut uploaded_epochs = vec![];
         for (epoch, db_path) in dirs {
             if missing_epochs.contains(epoch) || *epoch >= last_missing_epoch {
                 let dir_path = path_to_filesystem(self.db_checkpoint_path.clone(), db_path)?;
@@ -130,19 +137,50 @@ impl StateSnapshotUploader {
                     continue;
                 }
                 info!("Starting state snapshot creation for epoch: {}", *epoch);
-                let state_snapshot_writer = StateSnapshotWriterV1::new_from_store(
-                    &self.staging_path,
-                    &self.staging_store,
-                    &self.snapshot_store,
-                    FileCompression::Zstd,
-                    NonZeroUsize::new(20).unwrap(),
-                )
-                .await?;
                 let db = Arc::new(AuthorityPerpetualTables::open(
                     &path_to_filesystem(self.db_checkpoint_path.clone(), &db_path.child("store"))?,
                     None,
                 ));
-                state_snapshot_writer.write(*epoch, db).await?;
+                // An incremental snapshot is only written on top of the snapshot of the
+                // previous epoch, when there is one
+                let base_epoch = self
+                    .full_snapshot_interval_epochs
+                    .filter(|interval| *interval > 0 && *epoch % *interval != 0)
+                    .and_then(|_| epoch.checked_sub(1))
+                    .filter(|base_epoch| {
+                        !missing_epochs.contains(base_epoch) || uploaded_epochs.contains(base_epoch)
+                    });
+                let mut written = false;
+                if let Some(base_epoch) = base_epoch {
+                    let checkpoint_store = Arc::new(CheckpointStore::open_tables_read_write(
+                        path_to_filesystem(
+                            self.db_checkpoint_path.clone(),
+                            &db_path.child("checkpoints"),
+                        )?,
+                        MetricConf::default(),
+                        None,
+                        None,
+                    ));
+                    match self
+                        .state_snapshot_writer()
+                        .await?
+                        .write_delta(*epoch, base_epoch, db.clone(), checkpoint_store)
+                        .await
+                    {
+                        Ok(()) => written = true,
+                        Err(err) => warn!(
+                            "Failed to write incremental state snapshot for epoch: {} against epoch: {}, writing a full one instead: {:?}",
+                            *epoch, base_epoch, err
+                        ),
+                    }
+                }
+                if !written {
+                    self.state_snapshot_writer()
+                        .await?
+                        .write(*epoch, db)
+                        .await?;
+                }
+                uploaded_epochs.push(*epoch);
                 info!("State snapshot creation successful for epoch: {}", *epoch);
                 // Drop marker in the output directory that upload completed successfully
                 let bytes = Bytes::from_static(b"success");
@@ -163,6 +201,17 @@ impl StateSnapshotUploader {
         Ok(())
     }
 
+    async fn state_snapshot_writer(&self) -> Result<StateSnapshotWriterV1> {
+        StateSnapshotWriterV1::new_from_store(
+            &self.staging_path,
+            &self.staging_store,
+            &self.snapshot_store,
+            FileCompression::Zstd,
+            NonZeroUsize::new(20).unwrap(),
+        )
+        .await
+    }
+
     async fn get_missing_epochs(&self) -> Result<Vec<u64>> {
         let missing_epochs = find_missing_epochs_dirs(&self.snapshot_store, SUCCESS_MARKER).await?;
         Ok(missing_epochs.to_vec())
@@ -4,8 +4,8 @@
 
 use crate::{
     compute_sha3_checksum, create_file_metadata, FileCompression, FileMetadata, FileType, Manifest,
-    ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    ManifestV1, ManifestV2, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
+    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, TOMBSTONE_FILE_MAGIC,
 };
 use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ByteOrder};
@@ -14,7 +14,7 @@ use integer_encoding::VarInt;
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use std::collections::hash_map::Entry::Vacant;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Seek, SeekFrom, Write};
@@ -23,11 +23,14 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::authority::CHAIN_IDENTIFIER;
+use sui_core::checkpoints::CheckpointStore;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_storage::blob::{Blob, BlobEncoding, BLOB_ENCODING_BYTES};
 use sui_storage::object_store::util::{copy_file, delete_recursively, path_to_filesystem};
 use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::storage::ObjectKey;
 use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::sui_system_state::SuiSystemStateTrait;
 use tokio::sync::mpsc;
@@ -189,15 +192,110 @@ impl LiveObjectSetWriterV1 {
         Ok(())
     }
     fn write_object_ref(&mut self, object_ref: &ObjectRef) -> Result<()> {
-        let mut buf = [0u8; OBJECT_REF_BYTES];
-        buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
-        BigEndian::write_u64(
-            &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
-            object_ref.1.value(),
-        );
-        buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
-            .copy_from_slice(object_ref.2.as_ref());
-        self.ref_wbuf.write_all(&buf)?;
+        self.ref_wbuf.write_all(&encode_object_ref(object_ref))?;
+        Ok(())
+    }
+}
+
+fn encode_object_ref(object_ref: &ObjectRef) -> [u8; OBJECT_REF_BYTES] {
+    let mut buf = [0u8; OBJECT_REF_BYTES];
+    buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
+    BigEndian::write_u64(
+        &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
+        object_ref.1.value(),
+    );
+    buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
+        .copy_from_slice(object_ref.2.as_ref());
+    buf
+}
+
+/// TombstoneWriterV2 writes the references of the objects removed from the live object set in an
+/// incremental snapshot. It creates multiple *.del files
+struct TombstoneWriterV2 {
+    dir_path: PathBuf,
+    bucket_num: u32,
+    current_part_num: u32,
+    wbuf: BufWriter<File>,
+    n: usize,
+    files: Vec<FileMetadata>,
+    sender: Option<Sender<FileMetadata>>,
+    file_compression: FileCompression,
+}
+
+impl TombstoneWriterV2 {
+    fn new(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        file_compression: FileCompression,
+        sender: Sender<FileMetadata>,
+    ) -> Result<Self> {
+        let part_num = 1;
+        let (n, f) = Self::tombstone_file(dir_path.clone(), bucket_num, part_num)?;
+        Ok(TombstoneWriterV2 {
+            dir_path,
+            bucket_num,
+            current_part_num: part_num,
+            wbuf: BufWriter::new(f),
+            n,
+            files: vec![],
+            sender: Some(sender),
+            file_compression,
+        })
+    }
+    pub fn write(&mut self, tombstone: &ObjectRef) -> Result<()> {
+        if self.n + OBJECT_REF_BYTES > FILE_MAX_BYTES {
+            self.finalize()?;
+            let (n, f) = Self::tombstone_file(
+                self.dir_path.clone(),
+                self.bucket_num,
+                self.current_part_num + 1,
+            )?;
+            self.n = n;
+            self.wbuf = BufWriter::new(f);
+            self.current_part_num += 1;
+        }
+        self.wbuf.write_all(&encode_object_ref(tombstone))?;
+        self.n += OBJECT_REF_BYTES;
+        Ok(())
+    }
+    pub fn done(mut self) -> Result<Vec<FileMetadata>> {
+        self.finalize()?;
+        self.sender = None;
+        Ok(self.files.clone())
+    }
+    fn tombstone_file(dir_path: PathBuf, bucket_num: u32, part_num: u32) -> Result<(usize, File)> {
+        let path = dir_path.join(format!("{bucket_num}_{part_num}.del"));
+        let tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.del.tmp"));
+        let mut f = File::create(tmp_path.clone())?;
+        f.rewind()?;
+        let mut metab = [0u8; MAGIC_BYTES];
+        BigEndian::write_u32(&mut metab, TOMBSTONE_FILE_MAGIC);
+        let n = f.write(&metab)?;
+        drop(f);
+        fs::rename(tmp_path, path.clone())?;
+        let mut f = OpenOptions::new().append(true).open(path)?;
+        f.seek(SeekFrom::Start(n as u64))?;
+        Ok((n, f))
+    }
+    fn finalize(&mut self) -> Result<()> {
+        self.wbuf.flush()?;
+        self.wbuf.get_ref().sync_data()?;
+        let off = self.wbuf.get_ref().stream_position()?;
+        self.wbuf.get_ref().set_len(off)?;
+        let file_path = self
+            .dir_path
+            .join(format!("{}_{}.del", self.bucket_num, self.current_part_num));
+        let file_metadata = create_file_metadata(
+            &file_path,
+            self.file_compression,
+            FileType::Tombstone,
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.files.push(file_metadata.clone());
+        if let Some(sender) = &self.sender {
+            sender.blocking_send(file_metadata)?;
+        }
         Ok(())
     }
 }
@@ -256,7 +354,35 @@ impl StateSnapshotWriterV1 {
         epoch: u64,
         perpetual_db: Arc<AuthorityPerpetualTables>,
     ) -> Result<()> {
-        let system_state_object = get_sui_system_state(&perpetual_db)?;
+        let include_wrapped_tombstone = Self::include_wrapped_tombstone(&perpetual_db)?;
+        self.write_internal(epoch, include_wrapped_tombstone, perpetual_db)
+            .await
+    }
+
+    /// Write an incremental snapshot of the changes to the live object set between the end of
+    /// `base_epoch` and the end of `epoch`, found from the effects of the checkpoints in between.
+    pub async fn write_delta(
+        self,
+        epoch: u64,
+        base_epoch: u64,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        checkpoint_store: Arc<CheckpointStore>,
+    ) -> Result<()> {
+        let include_wrapped_tombstone = Self::include_wrapped_tombstone(&perpetual_db)?;
+        let changed_objects =
+            Self::changed_objects(epoch, base_epoch, &perpetual_db, &checkpoint_store)?;
+        self.write_delta_internal(
+            epoch,
+            base_epoch,
+            include_wrapped_tombstone,
+            perpetual_db,
+            changed_objects,
+        )
+        .await
+    }
+
+    fn include_wrapped_tombstone(perpetual_db: &AuthorityPerpetualTables) -> Result<bool> {
+        let system_state_object = get_sui_system_state(perpetual_db)?;
 
         let protocol_version = system_state_object.protocol_version();
         let chain_identifier = CHAIN_IDENTIFIER
@@ -266,17 +392,110 @@ impl StateSnapshotWriterV1 {
             ProtocolVersion::new(protocol_version),
             chain_identifier.chain(),
         );
-        let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
-        self.write_internal(epoch, include_wrapped_tombstone, perpetual_db)
-            .await
+        Ok(!protocol_config.simplified_unwrap_then_delete())
+    }
+
+    /// The objects created, mutated, unwrapped, deleted or wrapped by the transactions of the
+    /// checkpoints after the end of `base_epoch` up to the end of `epoch`, with their last
+    /// reference in the effects.
+    fn changed_objects(
+        epoch: u64,
+        base_epoch: u64,
+        perpetual_db: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+    ) -> Result<BTreeMap<ObjectID, ObjectRef>> {
+        if base_epoch >= epoch {
+            return Err(anyhow!(
+                "Base epoch: {base_epoch} must be before epoch: {epoch}"
+            ));
+        }
+        let first_checkpoint = checkpoint_store
+            .get_epoch_last_checkpoint(base_epoch)?
+            .context(format!("No last checkpoint found for epoch: {base_epoch}"))?
+            .sequence_number
+            + 1;
+        let last_checkpoint = checkpoint_store
+            .get_epoch_last_checkpoint(epoch)?
+            .context(format!("No last checkpoint found for epoch: {epoch}"))?
+            .sequence_number;
+        let mut changed_objects = BTreeMap::new();
+        for sequence_number in first_checkpoint..=last_checkpoint {
+            let checkpoint = checkpoint_store
+                .get_checkpoint_by_sequence_number(sequence_number)?
+                .context(format!("Missing checkpoint: {sequence_number}"))?;
+            let contents = checkpoint_store
+                .get_checkpoint_contents(&checkpoint.content_digest)?
+                .context(format!("Missing contents of checkpoint: {sequence_number}"))?;
+            for digests in contents.iter() {
+                let effects = perpetual_db
+                    .get_effects(&digests.transaction)?
+                    .context(format!(
+                        "Missing effects of transaction: {}, only a full snapshot can be written",
+                        digests.transaction
+                    ))?;
+                let object_refs = effects
+                    .all_changed_objects()
+                    .into_iter()
+                    .map(|(object_ref, _, _)| object_ref)
+                    .chain(
+                        effects
+                            .all_removed_objects()
+                            .into_iter()
+                            .map(|(object_ref, _)| object_ref),
+                    )
+                    .chain(effects.unwrapped_then_deleted());
+                for object_ref in object_refs {
+                    changed_objects.insert(object_ref.0, object_ref);
+                }
+            }
+        }
+        Ok(changed_objects)
     }
 
     pub(crate) async fn write_internal(
-        mut self,
+        self,
+        epoch: u64,
+        include_wrapped_tombstone: bool,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+    ) -> Result<()> {
+        self.write_files(epoch, move |writer, sender| {
+            writer.write_live_object_set(
+                epoch,
+                perpetual_db,
+                sender,
+                Self::bucket_func,
+                include_wrapped_tombstone,
+            )
+        })
+        .await
+    }
+
+    pub(crate) async fn write_delta_internal(
+        self,
         epoch: u64,
+        base_epoch: u64,
         include_wrapped_tombstone: bool,
         perpetual_db: Arc<AuthorityPerpetualTables>,
+        changed_objects: BTreeMap<ObjectID, ObjectRef>,
     ) -> Result<()> {
+        self.write_files(epoch, move |writer, sender| {
+            writer.write_object_changes(
+                epoch,
+                base_epoch,
+                perpetual_db,
+                sender,
+                Self::bucket_func,
+                include_wrapped_tombstone,
+                changed_objects,
+            )
+        })
+        .await
+    }
+
+    async fn write_files<W>(mut self, epoch: u64, write: W) -> Result<()>
+    where
+        W: FnOnce(&mut Self, Sender<FileMetadata>) -> Result<()> + Send + 'static,
+    {
         self.setup_epoch_dir(epoch).await?;
 
         let manifest_file_path = self.epoch_dir(epoch).child("MANIFEST");
@@ -286,15 +505,7 @@ impl StateSnapshotWriterV1 {
 
         let (sender, receiver) = mpsc::channel::<FileMetadata>(1000);
         let upload_handle = self.start_upload(epoch, receiver)?;
-        let write_handler = tokio::task::spawn_blocking(move || {
-            self.write_live_object_set(
-                epoch,
-                perpetual_db,
-                sender,
-                Self::bucket_func,
-                include_wrapped_tombstone,
-            )
-        });
+        let write_handler = tokio::task::spawn_blocking(move || write(&mut self, sender));
         write_handler.await?.context(format!(
             "Failed to write state snapshot for epoch: {}",
             &epoch
@@ -369,37 +580,141 @@ impl StateSnapshotWriterV1 {
         let local_staging_dir_path =
             path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
         for object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
-            let bucket_num = bucket_func(&object);
-            if let Vacant(entry) = object_writers.entry(bucket_num) {
-                entry.insert(LiveObjectSetWriterV1::new(
+            self.write_to_bucket(
+                &mut object_writers,
+                &local_staging_dir_path,
+                &sender,
+                bucket_func(&object),
+                &object,
+            )?;
+        }
+        let mut files = vec![];
+        for (_, writer) in object_writers.into_iter() {
+            files.extend(writer.done()?);
+        }
+        self.write_manifest(
+            epoch,
+            Manifest::V1(ManifestV1 {
+                snapshot_version: 1,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata: files,
+                epoch,
+            }),
+        )?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_object_changes<F>(
+        &mut self,
+        epoch: u64,
+        base_epoch: u64,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        sender: Sender<FileMetadata>,
+        bucket_func: F,
+        include_wrapped_tombstone: bool,
+        changed_objects: BTreeMap<ObjectID, ObjectRef>,
+    ) -> Result<()>
+    where
+        F: Fn(&LiveObject) -> u32,
+    {
+        let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
+        let mut tombstone_writer: Option<TombstoneWriterV2> = None;
+        let local_staging_dir_path =
+            path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
+        for (object_id, last_change) in changed_objects {
+            let tombstone = match perpetual_db.get_latest_object_or_tombstone(object_id)? {
+                Some((object_key, store_object)) => {
+                    if let Some(object) = perpetual_db.object(&object_key, store_object.clone())? {
+                        let object = LiveObject::Normal(object);
+                        self.write_to_bucket(
+                            &mut object_writers,
+                            &local_staging_dir_path,
+                            &sender,
+                            bucket_func(&object),
+                            &object,
+                        )?;
+                        continue;
+                    }
+                    perpetual_db.tombstone_reference(&object_key, &store_object)?
+                }
+                None => None,
+            };
+            // The tombstone itself may have been pruned, in which case the last change in the
+            // effects is the removal of the object
+            let tombstone = tombstone
+                .or(Some(last_change).filter(|object_ref| !object_ref.2.is_alive()))
+                .context(format!("Object: {object_id} is missing from the store"))?;
+            if include_wrapped_tombstone && tombstone.2.is_wrapped() {
+                let object = LiveObject::Wrapped(ObjectKey(tombstone.0, tombstone.1));
+                self.write_to_bucket(
+                    &mut object_writers,
+                    &local_staging_dir_path,
+                    &sender,
+                    bucket_func(&object),
+                    &object,
+                )?;
+                continue;
+            }
+            if tombstone_writer.is_none() {
+                tombstone_writer = Some(TombstoneWriterV2::new(
                     local_staging_dir_path.clone(),
-                    bucket_num,
+                    1,
                     self.file_compression,
                     sender.clone(),
                 )?);
             }
-            let writer = object_writers
-                .get_mut(&bucket_num)
-                .context("Unexpected missing bucket writer")?;
-            writer.write(&object)?;
+            tombstone_writer
+                .as_mut()
+                .context("Unexpected missing tombstone writer")?
+                .write(&tombstone)?;
         }
         let mut files = vec![];
         for (_, writer) in object_writers.into_iter() {
             files.extend(writer.done()?);
         }
-        self.write_manifest(epoch, files)?;
+        if let Some(writer) = tombstone_writer {
+            files.extend(writer.done()?);
+        }
+        self.write_manifest(
+            epoch,
+            Manifest::V2(ManifestV2 {
+                snapshot_version: 2,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata: files,
+                epoch,
+                base_epoch: Some(base_epoch),
+                include_wrapped_tombstone,
+            }),
+        )?;
         Ok(())
     }
 
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
+    fn write_to_bucket(
+        &self,
+        object_writers: &mut HashMap<u32, LiveObjectSetWriterV1>,
+        dir_path: &std::path::Path,
+        sender: &Sender<FileMetadata>,
+        bucket_num: u32,
+        object: &LiveObject,
+    ) -> Result<()> {
+        if let Vacant(entry) = object_writers.entry(bucket_num) {
+            entry.insert(LiveObjectSetWriterV1::new(
+                dir_path.to_path_buf(),
+                bucket_num,
+                self.file_compression,
+                sender.clone(),
+            )?);
+        }
+        let writer = object_writers
+            .get_mut(&bucket_num)
+            .context("Unexpected missing bucket writer")?;
+        writer.write(object)
+    }
+
+    fn write_manifest(&mut self, epoch: u64, manifest: Manifest) -> Result<()> {
         let (f, manifest_file_path) = self.manifest_file(epoch)?;
         let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
-            snapshot_version: 1,
-            address_length: ObjectID::LENGTH as u64,
-            file_metadata,
-            epoch,
-        });
         let serialized_manifest = bcs::to_bytes(&manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
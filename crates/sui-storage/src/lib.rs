@@ -40,6 +40,7 @@ pub mod sharded_lru;
 pub mod write_path_pending_tx_log;
 
 pub const SHA3_BYTES: usize = 32;
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: i32 = 1;
 
 #[derive(
     Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, TryFromPrimitive, IntoPrimitive,
@@ -60,8 +61,14 @@ pub enum FileCompression {
 
 impl FileCompression {
     pub fn zstd_compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
-        // TODO: Add zstd compression level as function argument
-        let mut encoder = zstd::Encoder::new(writer, 1)?;
+        Self::zstd_compress_with_level(reader, writer, DEFAULT_ZSTD_COMPRESSION_LEVEL)
+    }
+    pub fn zstd_compress_with_level<R: Read, W: Write>(
+        reader: &mut R,
+        writer: &mut W,
+        level: i32,
+    ) -> io::Result<()> {
+        let mut encoder = zstd::Encoder::new(writer, level)?;
         io::copy(reader, &mut encoder)?;
         encoder.finish()?;
         Ok(())
@@ -114,6 +121,14 @@ pub fn compute_sha3_checksum(source: &std::path::Path) -> Result<[u8; 32]> {
 }
 
 pub fn compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> Result<()> {
+    compress_with_level(reader, writer, DEFAULT_ZSTD_COMPRESSION_LEVEL)
+}
+
+pub fn compress_with_level<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    level: i32,
+) -> Result<()> {
     let magic = reader.read_u32::<BigEndian>()?;
     writer.write_u32::<BigEndian>(magic)?;
     let storage_format = reader.read_u8()?;
@@ -122,7 +137,7 @@ pub fn compress<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> Result<()>
     writer.write_u8(file_compression.into())?;
     match file_compression {
         FileCompression::Zstd => {
-            FileCompression::zstd_compress(reader, writer)?;
+            FileCompression::zstd_compress_with_level(reader, writer, level)?;
         }
         FileCompression::None => {}
     }
@@ -0,0 +1,33 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::object_store::downloader::Downloader;
+use crate::object_store::http::HttpStore;
+use anyhow::Result;
+use async_trait::async_trait;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::ObjectStore;
+
+/// Interface for files served by a static HTTP file server.
+#[derive(Debug)]
+pub struct HttpStorage {
+    store: HttpStore,
+}
+
+impl HttpStorage {
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(HttpStorage {
+            store: HttpStore::new(url)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Downloader for HttpStorage {
+    async fn get(&self, location: &Path) -> Result<Bytes> {
+        let result = self.store.get(location).await?;
+        let bytes = result.bytes().await?;
+        Ok(bytes)
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod gcs;
+mod http;
 mod local;
 mod s3;
 
@@ -9,6 +10,7 @@ use async_trait::async_trait;
 use std::sync::Arc;
 
 use crate::object_store::downloader::gcs::GoogleCloudStorage;
+use crate::object_store::downloader::http::HttpStorage;
 use crate::object_store::downloader::local::LocalStorage;
 use crate::object_store::downloader::s3::AmazonS3;
 use crate::object_store::{ObjectStoreConfig, ObjectStoreType};
@@ -30,8 +32,10 @@ pub(crate) const STRICT_ENCODE_SET: percent_encoding::AsciiSet = percent_encodin
     .remove(b'.')
     .remove(b'_')
     .remove(b'~');
-const STRICT_PATH_ENCODE_SET: percent_encoding::AsciiSet = STRICT_ENCODE_SET.remove(b'/');
-static DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+pub(crate) const STRICT_PATH_ENCODE_SET: percent_encoding::AsciiSet =
+    STRICT_ENCODE_SET.remove(b'/');
+pub(crate) static DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 #[async_trait]
 pub trait Downloader {
@@ -57,6 +61,13 @@ impl DownloaderBuilder for ObjectStoreConfig {
             Some(ObjectStoreType::GCS) => {
                 Ok(GoogleCloudStorage::new(&self.bucket.unwrap()).map(Arc::new)?)
             }
+            Some(ObjectStoreType::Http) => {
+                let url = self
+                    .http_url
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("No url provided for http storage"))?;
+                Ok(HttpStorage::new(url).map(Arc::new)?)
+            }
             _ => Err(anyhow!("At least one storage backend should be provided")),
         }
     }
@@ -0,0 +1,250 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::object_store::downloader::{DEFAULT_USER_AGENT, STRICT_PATH_ENCODE_SET};
+use anyhow::Context;
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    Error, GetOptions, GetResult, GetResultPayload, ListResult, MultipartId, ObjectMeta,
+    ObjectStore, Result,
+};
+use percent_encoding::utf8_percent_encode;
+use reqwest::header::{
+    HeaderMap, HeaderName, CONTENT_LENGTH, CONTENT_RANGE, ETAG, LAST_MODIFIED, RANGE,
+};
+use reqwest::{Client, ClientBuilder, Method, Response, StatusCode};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use tokio::io::AsyncWrite;
+use url::Url;
+
+const STORE: &str = "HTTP";
+
+/// Read-only object store for files served by a plain HTTP server (e.g. nginx serving a
+/// directory), with object paths resolved relative to a base url. Range reads are sent as
+/// http range requests, which is what allows downloads to be resumed part way through, see
+/// [`crate::object_store::util::get_resumable`]. Writing, copying and listing are not supported.
+#[derive(Debug)]
+pub struct HttpStore {
+    url: Url,
+    client: Client,
+}
+
+impl HttpStore {
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        let mut url = Url::parse(url).context("Invalid http url")?;
+        // Paths are joined to the base url, which only keeps its last segment with a trailing '/'
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        let client = ClientBuilder::new()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .context("Failed to build http client")?;
+        Ok(HttpStore { url, client })
+    }
+
+    fn object_url(&self, location: &Path) -> Result<Url> {
+        let encoded = utf8_percent_encode(location.as_ref(), &STRICT_PATH_ENCODE_SET).to_string();
+        self.url.join(&encoded).map_err(generic_error)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        location: &Path,
+        range: Option<&Range<usize>>,
+    ) -> Result<Response> {
+        let mut request = self.client.request(method, self.object_url(location)?);
+        if let Some(range) = range {
+            request = request.header(
+                RANGE,
+                format!("bytes={}-{}", range.start, range.end.saturating_sub(1)),
+            );
+        }
+        let response = request.send().await.map_err(generic_error)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(Error::NotFound {
+                path: location.to_string(),
+                source: format!("{} not found", response.url()).into(),
+            });
+        }
+        response.error_for_status().map_err(generic_error)
+    }
+}
+
+impl Display for HttpStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HttpStore({})", self.url)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for HttpStore {
+    async fn put(&self, _location: &Path, _bytes: Bytes) -> Result<()> {
+        Err(read_only())
+    }
+
+    async fn put_multipart(
+        &self,
+        _location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        Err(read_only())
+    }
+
+    async fn abort_multipart(&self, _location: &Path, _multipart_id: &MultipartId) -> Result<()> {
+        Err(read_only())
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let response = self
+            .send(Method::GET, location, options.range.as_ref())
+            .await?;
+        let (meta, content_range) = response_meta(location, response.headers())?;
+        let range = match (options.range, response.status(), content_range) {
+            (None, _, _) => 0..meta.size,
+            (Some(_), StatusCode::PARTIAL_CONTENT, Some(content_range)) => content_range,
+            // A server which doesn't do ranges replies with the whole file instead, which we
+            // don't want to stream to a caller asking for part of it
+            (Some(_), status, _) => {
+                return Err(Error::NotSupported {
+                    source: format!(
+                        "Expected partial content from {} for range request, got: {}",
+                        self.url, status
+                    )
+                    .into(),
+                })
+            }
+        };
+        let stream = response.bytes_stream().map_err(generic_error).boxed();
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream),
+            meta,
+            range,
+        })
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let response = self.send(Method::HEAD, location, None).await?;
+        let (meta, _) = response_meta(location, response.headers())?;
+        Ok(meta)
+    }
+
+    async fn delete(&self, _location: &Path) -> Result<()> {
+        Err(read_only())
+    }
+
+    async fn list(&self, _prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        Err(Error::NotSupported {
+            source: "Listing is not supported by the http object store".into(),
+        })
+    }
+
+    async fn list_with_delimiter(&self, _prefix: Option<&Path>) -> Result<ListResult> {
+        Err(Error::NotSupported {
+            source: "Listing is not supported by the http object store".into(),
+        })
+    }
+
+    async fn copy(&self, _from: &Path, _to: &Path) -> Result<()> {
+        Err(read_only())
+    }
+
+    async fn copy_if_not_exists(&self, _from: &Path, _to: &Path) -> Result<()> {
+        Err(read_only())
+    }
+}
+
+fn read_only() -> Error {
+    Error::NotSupported {
+        source: "The http object store is read only".into(),
+    }
+}
+
+fn generic_error<E: std::error::Error + Send + Sync + 'static>(source: E) -> Error {
+    Error::Generic {
+        store: STORE,
+        source: Box::new(source),
+    }
+}
+
+/// Object metadata from the response headers, along with the range of the object in the body
+/// for a partial content response. Static file servers don't always send an etag, and some
+/// don't send a last modified date either, so neither is required.
+fn response_meta(
+    location: &Path,
+    headers: &HeaderMap,
+) -> Result<(ObjectMeta, Option<Range<usize>>)> {
+    let header = |name| header_str(headers, name);
+    let content_range = header(CONTENT_RANGE)?
+        .map(|value| {
+            parse_content_range(value).ok_or_else(|| Error::Generic {
+                store: STORE,
+                source: format!("Invalid content range: {}", value).into(),
+            })
+        })
+        .transpose()?;
+    let size = match &content_range {
+        Some((_, size)) => *size,
+        None => header(CONTENT_LENGTH)?
+            .ok_or_else(|| Error::Generic {
+                store: STORE,
+                source: "Missing content length".into(),
+            })?
+            .parse()
+            .map_err(generic_error)?,
+    };
+    let last_modified = match header(LAST_MODIFIED)? {
+        Some(value) => DateTime::parse_from_rfc2822(value)
+            .map_err(generic_error)?
+            .with_timezone(&Utc),
+        None => Utc.timestamp_opt(0, 0).unwrap(),
+    };
+    let e_tag = header(ETAG)?.map(|value| value.to_string());
+    let meta = ObjectMeta {
+        location: location.clone(),
+        last_modified,
+        size,
+        e_tag,
+    };
+    Ok((meta, content_range.map(|(range, _)| range)))
+}
+
+fn header_str(headers: &HeaderMap, name: HeaderName) -> Result<Option<&str>> {
+    headers
+        .get(name)
+        .map(|value| value.to_str().map_err(generic_error))
+        .transpose()
+}
+
+/// Parses a `Content-Range: bytes <start>-<end>/<size>` header value into the (end exclusive)
+/// range and the size of the whole object.
+fn parse_content_range(value: &str) -> Option<(Range<usize>, usize)> {
+    let (range, size) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    let start: usize = start.parse().ok()?;
+    let end: usize = end.parse().ok()?;
+    Some((start..end.checked_add(1)?, size.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_content_range;
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(parse_content_range("bytes 0-9/20"), Some((0..10, 20)));
+        assert_eq!(parse_content_range("bytes 10-19/20"), Some((10..20, 20)));
+        assert_eq!(parse_content_range("bytes 0-9/*"), None);
+        assert_eq!(parse_content_range("0-9/20"), None);
+        assert_eq!(
+            parse_content_range(&format!("bytes 0-{}/20", usize::MAX)),
+            None
+        );
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::object_store::http::HttpStore;
 use anyhow::{anyhow, Context};
 use clap::*;
 use object_store::aws::AmazonS3Builder;
@@ -12,6 +13,7 @@ use std::sync::Arc;
 use tracing::info;
 
 pub mod downloader;
+pub mod http;
 pub mod util;
 
 /// Object-store type.
@@ -25,6 +27,8 @@ pub enum ObjectStoreType {
     GCS,
     /// Azure Blob Store
     Azure,
+    /// Static HTTP file server (read only)
+    Http,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Args)]
@@ -84,6 +88,11 @@ pub struct ObjectStoreConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[arg(long)]
     pub azure_storage_access_key: Option<String>,
+    /// When using a static HTTP file server as the object store, set this to
+    /// the url the files are served under. Only reads are supported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub http_url: Option<String>,
     #[serde(default = "default_object_store_connection_limit")]
     #[arg(long, default_value_t = 20)]
     pub object_store_connection_limit: usize,
@@ -187,12 +196,27 @@ impl ObjectStoreConfig {
             self.object_store_connection_limit,
         )))
     }
+    fn new_http(&self) -> Result<Arc<DynObjectStore>, anyhow::Error> {
+        use object_store::limit::LimitStore;
+
+        info!(url=?self.http_url, object_store_type="Http", "Object Store");
+
+        if let Some(url) = &self.http_url {
+            Ok(Arc::new(LimitStore::new(
+                HttpStore::new(url)?,
+                self.object_store_connection_limit,
+            )))
+        } else {
+            Err(anyhow!("No url provided for http storage"))
+        }
+    }
     pub fn make(&self) -> Result<Arc<DynObjectStore>, anyhow::Error> {
         match &self.object_store {
             Some(ObjectStoreType::File) => self.new_local_fs(),
             Some(ObjectStoreType::S3) => self.new_s3(),
             Some(ObjectStoreType::GCS) => self.new_gcs(),
             Some(ObjectStoreType::Azure) => self.new_azure(),
+            Some(ObjectStoreType::Http) => self.new_http(),
             _ => Err(anyhow!("At least one storage backend should be provided")),
         }
     }
@@ -1,15 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{compute_sha3_checksum, compute_sha3_checksum_for_bytes};
 use anyhow::{anyhow, Context};
+use backoff::backoff::Backoff;
 use backoff::future::retry;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures::StreamExt;
 use futures::TryStreamExt;
 use indicatif::ProgressBar;
 use object_store::path::Path;
-use object_store::{DynObjectStore, Error};
+use object_store::{DynObjectStore, Error, GetOptions};
 use std::collections::BTreeMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
 use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::path::PathBuf;
@@ -32,6 +37,158 @@ pub async fn get(location: &Path, from: Arc<DynObjectStore>) -> Result<Bytes, ob
     Ok(bytes)
 }
 
+/// Fetches the whole object at `location`, resuming from the last received byte with a range
+/// read when the download fails part way through instead of starting over.
+pub async fn get_resumable(location: &Path, from: Arc<DynObjectStore>) -> anyhow::Result<Bytes> {
+    let mut buf = BytesMut::new();
+    get_resumable_from(location, from, 0, |chunk| {
+        buf.extend_from_slice(chunk);
+        Ok(())
+    })
+    .await?;
+    Ok(buf.freeze())
+}
+
+/// Like [`get_resumable`] but fails if the sha3 digest of the downloaded object doesn't match
+/// `sha3_digest`, as recorded in the MANIFEST of archives and snapshots.
+pub async fn get_verified(
+    location: &Path,
+    from: Arc<DynObjectStore>,
+    sha3_digest: &[u8; 32],
+) -> anyhow::Result<Bytes> {
+    let bytes = get_resumable(location, from).await?;
+    if compute_sha3_checksum_for_bytes(bytes.clone())? != *sha3_digest {
+        return Err(anyhow!(
+            "Checksum mismatch for downloaded file: {}",
+            location
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Downloads the object at `location` to `local_path`, checking its sha3 digest against
+/// `sha3_digest`. The object is first written to a `.part` file next to `local_path` which is
+/// only renamed once complete and verified, so an interrupted download (even across restarts)
+/// picks up where it left off. A file already at `local_path` with the right digest is kept.
+pub async fn download_file_resumable(
+    location: &Path,
+    from: Arc<DynObjectStore>,
+    local_path: &std::path::Path,
+    sha3_digest: &[u8; 32],
+) -> anyhow::Result<()> {
+    if local_path.exists() && compute_sha3_checksum(local_path)? == *sha3_digest {
+        return Ok(());
+    }
+    if let Some(parent) = local_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut part_path = local_path.as_os_str().to_owned();
+    part_path.push(".part");
+    let part_path = PathBuf::from(part_path);
+    let mut part_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&part_path)
+        .context(format!("Failed to open: {}", part_path.display()))?;
+    let offset = part_file.metadata()?.len() as usize;
+    get_resumable_from(location, from, offset, |chunk| part_file.write_all(chunk)).await?;
+    part_file.sync_all()?;
+    drop(part_file);
+    if compute_sha3_checksum(&part_path)? != *sha3_digest {
+        // Start from scratch next time, the partial file may be stale or corrupted
+        fs::remove_file(&part_path)?;
+        return Err(anyhow!(
+            "Checksum mismatch for downloaded file: {}",
+            location
+        ));
+    }
+    fs::rename(&part_path, local_path)?;
+    Ok(())
+}
+
+/// Streams the object at `location` into `sink` starting at byte `offset`, retrying with
+/// exponential backoff on failures and resuming each retry from the last byte received.
+/// Retries stop once the backoff gives up without any progress being made.
+async fn get_resumable_from<F>(
+    location: &Path,
+    from: Arc<DynObjectStore>,
+    mut offset: usize,
+    mut sink: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(&[u8]) -> std::io::Result<()>,
+{
+    let size = retry(backoff::ExponentialBackoff::default(), || async {
+        from.head(location).await.map_err(|e| {
+            error!(
+                "Failed to get file metadata from object store with error: {:?}",
+                &e
+            );
+            permanent_if_unrecoverable(e)
+        })
+    })
+    .await?
+    .size;
+    let mut backoff = backoff::ExponentialBackoff::default();
+    while offset < size {
+        let start = offset;
+        let result: Result<(), backoff::Error<anyhow::Error>> = async {
+            let options = GetOptions {
+                range: Some(offset..size),
+                ..Default::default()
+            };
+            let mut stream = from
+                .get_opts(location, options)
+                .await
+                .map_err(permanent_if_unrecoverable)?
+                .into_stream();
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(permanent_if_unrecoverable)?;
+                sink(&chunk[..]).map_err(|e| backoff::Error::permanent(e.into()))?;
+                offset += chunk.len();
+            }
+            Ok(())
+        }
+        .await;
+        match result {
+            Ok(()) if offset < size => {
+                warn!(
+                    "Download of {} ended early at byte {} of {}",
+                    location, offset, size
+                );
+            }
+            Ok(()) => break,
+            Err(backoff::Error::Permanent(e)) => return Err(e),
+            Err(backoff::Error::Transient { err, .. }) => {
+                error!(
+                    "Failed to download {} at byte {} of {} with error: {:?}",
+                    location, offset, size, &err
+                );
+            }
+        }
+        if offset > start {
+            backoff.reset();
+        }
+        let Some(delay) = backoff.next_backoff() else {
+            return Err(anyhow!(
+                "Giving up downloading {} at byte {} of {}",
+                location,
+                offset,
+                size
+            ));
+        };
+        tokio::time::sleep(delay).await;
+    }
+    Ok(())
+}
+
+fn permanent_if_unrecoverable(e: Error) -> backoff::Error<anyhow::Error> {
+    match e {
+        Error::NotFound { .. } | Error::NotSupported { .. } => backoff::Error::permanent(e.into()),
+        e => backoff::Error::transient(e.into()),
+    }
+}
+
 pub async fn put(
     location: &Path,
     bytes: Bytes,
@@ -282,12 +439,119 @@ pub fn get_path(prefix: &str) -> Path {
 
 #[cfg(test)]
 mod tests {
-    use crate::object_store::util::{copy_recursively, delete_recursively};
+    use crate::compute_sha3_checksum_for_bytes;
+    use crate::object_store::util::{
+        copy_recursively, delete_recursively, download_file_resumable, get_resumable, get_verified,
+    };
     use crate::object_store::{ObjectStoreConfig, ObjectStoreType};
+    use bytes::Bytes;
     use object_store::path::Path;
     use std::fs;
     use std::num::NonZeroUsize;
     use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves `data` at `/dir/file` over http with range request support. Responses for ranges
+    /// starting at byte 0 are cut off half way through, as if the connection dropped.
+    async fn serve_with_interruptions(data: Bytes) -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/", listener.local_addr()?);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let data = data.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap();
+                    let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                    let mut lines = request.lines();
+                    let mut request_line = lines.next().unwrap().split_whitespace();
+                    let (method, path) = (request_line.next().unwrap(), request_line.next());
+                    let range = lines.find_map(|line| line.strip_prefix("range: bytes="));
+                    let (status, headers, body) = match (path, range) {
+                        (Some("/dir/file"), _) if method == "head" => (
+                            "200 OK",
+                            format!("content-length: {}", data.len()),
+                            Bytes::new(),
+                        ),
+                        (Some("/dir/file"), Some(range)) => {
+                            let (start, end) = range.split_once('-').unwrap();
+                            let (start, end): (usize, usize) =
+                                (start.parse().unwrap(), end.parse().unwrap());
+                            let body = data.slice(start..end + 1);
+                            let headers = format!(
+                                "content-length: {}\r\ncontent-range: bytes {}-{}/{}",
+                                body.len(),
+                                start,
+                                end,
+                                data.len()
+                            );
+                            let body = if start == 0 {
+                                body.slice(..body.len() / 2)
+                            } else {
+                                body
+                            };
+                            ("206 Partial Content", headers, body)
+                        }
+                        (Some("/dir/file"), None) => {
+                            ("200 OK", format!("content-length: {}", data.len()), data)
+                        }
+                        _ => (
+                            "404 Not Found",
+                            "content-length: 0".to_string(),
+                            Bytes::new(),
+                        ),
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {}\r\n{}\r\nconnection: close\r\n\r\n",
+                        status, headers
+                    );
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    let _ = socket.write_all(&body).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        Ok(url)
+    }
+
+    #[tokio::test]
+    pub async fn test_http_resumable_download() -> anyhow::Result<()> {
+        let data: Bytes = (0..100_000u32)
+            .flat_map(|i| i.to_le_bytes())
+            .collect::<Vec<u8>>()
+            .into();
+        let sha3_digest = compute_sha3_checksum_for_bytes(data.clone())?;
+        let store = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::Http),
+            http_url: Some(serve_with_interruptions(data.clone()).await?),
+            ..Default::default()
+        }
+        .make()?;
+        let location = Path::from("dir/file");
+
+        let downloaded = get_verified(&location, store.clone(), &sha3_digest).await?;
+        assert_eq!(downloaded, data);
+        assert!(get_verified(&location, store.clone(), &[0u8; 32])
+            .await
+            .is_err());
+        assert!(get_resumable(&Path::from("dir/missing"), store.clone())
+            .await
+            .is_err());
+
+        let output = TempDir::new()?;
+        let local_path = output.path().join("dir").join("file");
+        download_file_resumable(&location, store.clone(), &local_path, &sha3_digest).await?;
+        assert_eq!(fs::read(&local_path)?, data.to_vec());
+        assert!(!output.path().join("dir").join("file.part").exists());
+
+        // A partially downloaded file is completed rather than downloaded again
+        fs::remove_file(&local_path)?;
+        fs::write(output.path().join("dir").join("file.part"), &data[..1000])?;
+        download_file_resumable(&location, store, &local_path, &sha3_digest).await?;
+        assert_eq!(fs::read(&local_path)?, data.to_vec());
+        Ok(())
+    }
 
     #[tokio::test]
     pub async fn test_copy_recursively() -> anyhow::Result<()> {
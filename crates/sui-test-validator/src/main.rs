@@ -169,8 +169,21 @@ async fn faucet_request(
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let result = match payload {
-        FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient }) => {
-            state.faucet.request_sui_coins(recipient).await
+        FaucetRequest::FixedAmountRequest(FixedAmountRequest {
+            recipient,
+            coin_type: None,
+        }) => state.faucet.request_sui_coins(recipient).await,
+        // The faucet of the local network only dispenses SUI
+        FaucetRequest::FixedAmountRequest(FixedAmountRequest {
+            coin_type: Some(coin_type),
+            ..
+        }) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(FaucetResponse::from(FaucetError::UnsupportedCoinType(
+                    coin_type,
+                ))),
+            )
         }
         _ => {
             return (
@@ -194,8 +207,21 @@ async fn faucet_batch_request(
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let result = match payload {
-        FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient }) => {
-            state.faucet.batch_request_sui_coins(recipient).await
+        FaucetRequest::FixedAmountRequest(FixedAmountRequest {
+            recipient,
+            coin_type: None,
+        }) => state.faucet.batch_request_sui_coins(recipient).await,
+        // The faucet of the local network only dispenses SUI
+        FaucetRequest::FixedAmountRequest(FixedAmountRequest {
+            coin_type: Some(coin_type),
+            ..
+        }) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(BatchFaucetResponse::from(FaucetError::UnsupportedCoinType(
+                    coin_type,
+                ))),
+            )
         }
         _ => {
             return (
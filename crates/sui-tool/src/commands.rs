@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    compact_archive,
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, get_object, get_transaction_block,
     make_clients, restore_from_db_checkpoint, state_sync_from_archive, verify_archive,
@@ -23,6 +24,7 @@ use fastcrypto::encoding::Encoding;
 use sui_config::Config;
 use sui_core::authority_aggregator::AuthorityAggregatorBuilder;
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_storage::FileCompression;
 use sui_types::messages_checkpoint::{
     CheckpointRequest, CheckpointResponse, CheckpointSequenceNumber,
 };
@@ -154,6 +156,39 @@ pub enum ToolCommand {
         download_concurrency: usize,
     },
 
+    /// Tool to rewrite the files of past epochs in the archive store into files of a
+    /// target size and compression level. No archive writer should be running against
+    /// the archive store while it is compacted.
+    #[command(name = "compact-archive")]
+    CompactArchive {
+        #[command(flatten)]
+        object_store_config: ObjectStoreConfig,
+        /// Local directory to stage the rewritten files in
+        #[arg(long = "staging-dir")]
+        staging_dir: PathBuf,
+        /// First epoch to compact
+        #[arg(long = "start-epoch")]
+        start_epoch: u64,
+        /// Epoch to stop compacting at (exclusive). Defaults to the epoch currently
+        /// being archived
+        #[arg(long = "end-epoch")]
+        end_epoch: Option<u64>,
+        /// Target size of the rewritten checkpoint files
+        #[arg(long = "target-file-size-mb", default_value_t = 256)]
+        target_file_size_mb: usize,
+        /// Zstd compression level of the rewritten files
+        #[arg(long = "compression-level", default_value_t = 1)]
+        compression_level: i32,
+        /// Write the files uncompressed
+        #[arg(long = "no-compression")]
+        no_compression: bool,
+        /// Don't delete the replaced files from the archive store
+        #[arg(long = "keep-old-files")]
+        keep_old_files: bool,
+        #[arg(long = "download-concurrency", default_value_t = 5)]
+        download_concurrency: usize,
+    },
+
     #[command(name = "dump-validators")]
     DumpValidators {
         #[arg(long = "genesis")]
@@ -238,6 +273,7 @@ pub enum ToolCommand {
         network: Chain,
         /// Snapshot bucket name. If not specified, defaults are
         /// based on value of `--network` and `--formal` flags.
+        /// For `--snapshot-bucket-type` "http", the url snapshots are served under.
         #[clap(long = "snapshot-bucket")]
         snapshot_bucket: Option<String>,
         /// Snapshot bucket type. Defaults to "gcs" if `--formal`
@@ -250,6 +286,7 @@ pub enum ToolCommand {
         snapshot_path: Option<PathBuf>,
         /// Archival bucket name. If not specified, defaults are
         /// based on value of `--network` and `--formal` flags.
+        /// For `--archive-bucket-type` "http", the url archives are served under.
         #[clap(long = "archive-bucket")]
         archive_bucket: Option<String>,
         #[clap(long = "archive-bucket-type", default_value = "s3")]
@@ -546,6 +583,14 @@ impl ToolCommand {
                             ..Default::default()
                         }
                     },
+                    ObjectStoreType::Http => {
+                        ObjectStoreConfig {
+                            object_store: Some(ObjectStoreType::Http),
+                            http_url: Some(snapshot_bucket),
+                            object_store_connection_limit: 200,
+                            ..Default::default()
+                        }
+                    },
                     ObjectStoreType::File => {
                         if snapshot_path.is_some() {
                             ObjectStoreConfig {
@@ -602,6 +647,14 @@ impl ToolCommand {
                             ..Default::default()
                         }
                     },
+                    ObjectStoreType::Http => {
+                        ObjectStoreConfig {
+                            object_store: Some(ObjectStoreType::Http),
+                            http_url: Some(archive_bucket),
+                            object_store_connection_limit: 200,
+                            ..Default::default()
+                        }
+                    },
                     ObjectStoreType::File => panic!("Download from local filesystem is not supported")
                 };
 
@@ -669,6 +722,35 @@ impl ToolCommand {
             } => {
                 verify_archive_by_checksum(object_store_config, download_concurrency).await?;
             }
+            ToolCommand::CompactArchive {
+                object_store_config,
+                staging_dir,
+                start_epoch,
+                end_epoch,
+                target_file_size_mb,
+                compression_level,
+                no_compression,
+                keep_old_files,
+                download_concurrency,
+            } => {
+                let file_compression = if no_compression {
+                    FileCompression::None
+                } else {
+                    FileCompression::Zstd
+                };
+                compact_archive(
+                    object_store_config,
+                    staging_dir,
+                    start_epoch,
+                    end_epoch,
+                    target_file_size_mb * 1024 * 1024,
+                    file_compression,
+                    compression_level,
+                    keep_old_files,
+                    download_concurrency,
+                )
+                .await?;
+            }
             ToolCommand::SignTransaction {
                 genesis,
                 sender_signed_data,
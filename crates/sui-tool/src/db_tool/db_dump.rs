@@ -12,7 +12,7 @@ use std::str;
 use std::sync::Arc;
 use strum_macros::EnumString;
 use sui_archival::reader::ArchiveReaderBalancer;
-use sui_config::node::AuthorityStorePruningConfig;
+use sui_config::node::{AuthorityStorePruningConfig, ObjectRetentionRule};
 use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
 use sui_core::authority::authority_store_pruner::{
     AuthorityStorePruner, AuthorityStorePruningMetrics,
@@ -192,7 +192,10 @@ pub fn compact(db_path: PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub async fn prune_objects(db_path: PathBuf) -> anyhow::Result<()> {
+pub async fn prune_objects(
+    db_path: PathBuf,
+    object_retention_rules: Vec<ObjectRetentionRule>,
+) -> anyhow::Result<()> {
     let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path.join("store"), None));
     let checkpoint_store = Arc::new(CheckpointStore::open_tables_read_write(
         db_path.join("checkpoints"),
@@ -212,6 +215,7 @@ pub async fn prune_objects(db_path: PathBuf) -> anyhow::Result<()> {
     info!("Pruning setup for db at path: {:?}", db_path.display());
     let pruning_config = AuthorityStorePruningConfig {
         num_epochs_to_retain: 0,
+        object_retention_rules,
         ..Default::default()
     };
     info!("Starting object pruning");
@@ -8,6 +8,8 @@ use anyhow::{anyhow, bail};
 use clap::Parser;
 use narwhal_storage::NodeStorage;
 use std::path::{Path, PathBuf};
+use sui_config::node::AuthorityStorePruningConfig;
+use sui_config::{Config, NodeConfig};
 use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::checkpoints::CheckpointStore;
@@ -41,8 +43,9 @@ pub enum DbToolCommand {
     ResetDB,
     RewindCheckpointExecution(RewindCheckpointExecutionOptions),
     Compact,
-    PruneObjects,
+    PruneObjects(PruningOptions),
     PruneCheckpoints,
+    PrintPruningPolicy(PruningOptions),
 }
 
 #[derive(Parser)]
@@ -150,6 +153,15 @@ pub struct RemoveObjectLockOptions {
     confirm: bool,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct PruningOptions {
+    /// The node config to read the pruning config from, including the object retention rules.
+    /// The default pruning config is used if not set.
+    #[arg(long = "config")]
+    config: Option<PathBuf>,
+}
+
 #[derive(Parser)]
 #[command(rename_all = "kebab-case")]
 pub struct RewindCheckpointExecutionOptions {
@@ -190,8 +202,12 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             rewind_checkpoint_execution(&db_path, d.epoch, d.checkpoint_sequence_number)
         }
         DbToolCommand::Compact => compact(db_path),
-        DbToolCommand::PruneObjects => prune_objects(db_path).await,
+        DbToolCommand::PruneObjects(d) => {
+            let pruning_config = load_pruning_config(&d)?;
+            prune_objects(db_path, pruning_config.object_retention_rules).await
+        }
         DbToolCommand::PruneCheckpoints => prune_checkpoints(db_path).await,
+        DbToolCommand::PrintPruningPolicy(d) => print_pruning_policy(&db_path, d),
         DbToolCommand::IndexSearchKeyRange(rg) => {
             let res = search_index(
                 db_path,
@@ -234,6 +250,41 @@ pub fn print_db_duplicates_summary(db_path: PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn load_pruning_config(opt: &PruningOptions) -> anyhow::Result<AuthorityStorePruningConfig> {
+    Ok(match &opt.config {
+        Some(path) => NodeConfig::load(path)?.authority_store_pruning_config,
+        None => AuthorityStorePruningConfig::default(),
+    })
+}
+
+/// Print the pruning config along with how far the objects and checkpoints have been pruned.
+pub fn print_pruning_policy(path: &Path, opt: PruningOptions) -> anyhow::Result<()> {
+    let pruning_config = load_pruning_config(&opt)?;
+    println!("Pruning config: {:#?}", pruning_config);
+    if pruning_config.object_retention_rules.is_empty() {
+        println!("No object retention rules, old versions of all objects are pruned");
+    }
+    for rule in &pruning_config.object_retention_rules {
+        println!("Keeping all versions of objects matching {}", rule);
+    }
+
+    let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
+    let checkpoint_store = CheckpointStore::new(&path.join("checkpoints"));
+    println!(
+        "Highest executed checkpoint: {:?}",
+        checkpoint_store.get_highest_executed_checkpoint_seq_number()?
+    );
+    println!(
+        "Objects pruned up to checkpoint: {}",
+        perpetual_db.get_highest_pruned_checkpoint()?
+    );
+    println!(
+        "Transactions and effects pruned up to checkpoint: {}",
+        checkpoint_store.get_highest_pruned_checkpoint_seq_number()?
+    );
+    Ok(())
+}
+
 pub fn print_last_consensus_index(path: &Path) -> anyhow::Result<()> {
     let epoch_tables = AuthorityEpochTables::open_tables_read_write(
         path.to_path_buf(),
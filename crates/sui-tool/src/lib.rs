@@ -36,8 +36,11 @@ use fastcrypto::hash::MultisetHash;
 use futures::{StreamExt, TryStreamExt};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use prometheus::Registry;
+use sui_archival::compaction::ArchiveCompactor;
 use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
-use sui_archival::{verify_archive_with_checksums, verify_archive_with_genesis_config};
+use sui_archival::{
+    read_manifest, verify_archive_with_checksums, verify_archive_with_genesis_config,
+};
 use sui_config::node::ArchiveReaderConfig;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::AuthorityStore;
@@ -45,12 +48,11 @@ use sui_core::checkpoints::CheckpointStore;
 use sui_core::db_checkpoint_handler::SUCCESS_MARKER;
 use sui_core::epoch::committee_store::CommitteeStore;
 use sui_core::storage::RocksDbStore;
-use sui_snapshot::reader::StateSnapshotReaderV1;
-use sui_snapshot::setup_db_state;
+use sui_snapshot::reader::read_snapshot_chain;
+use sui_snapshot::{setup_db_state, verify_root_state_hash};
 use sui_storage::object_store::util::{copy_file, get_path};
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
-use sui_storage::verify_checkpoint_range;
-use sui_types::messages_checkpoint::{CheckpointCommitment, ECMHLiveObjectSetDigest};
+use sui_storage::{verify_checkpoint_range, FileCompression};
 use sui_types::messages_grpc::{
     ObjectInfoRequest, ObjectInfoRequestKind, ObjectInfoResponse, TransactionInfoRequest,
     TransactionStatus,
@@ -854,20 +856,19 @@ pub async fn download_formal_snapshot(
             directory: Some(snapshot_dir_clone.to_path_buf()),
             ..Default::default()
         };
-        let mut reader = StateSnapshotReaderV1::new(
+        read_snapshot_chain(
             epoch,
             &snapshot_store_config,
             &local_store_config,
             usize::MAX,
             NonZeroUsize::new(num_parallel_downloads).unwrap(),
             m,
+            &perpetual_db_clone,
+            abort_registration,
+            Some(sender),
         )
         .await
-        .unwrap_or_else(|err| panic!("Failed to create reader: {}", err));
-        reader
-            .read(&perpetual_db_clone, abort_registration, Some(sender))
-            .await
-            .unwrap_or_else(|err| panic!("Failed during read: {}", err));
+        .unwrap_or_else(|err| panic!("Failed during read: {}", err));
         Ok::<(), anyhow::Error>(())
     });
     let mut root_accumulator = Accumulator::default();
@@ -893,30 +894,8 @@ pub async fn download_formal_snapshot(
             epoch,
             last_checkpoint.epoch()
         );
-        let commitment = last_checkpoint
-            .end_of_epoch_data
-            .as_ref()
-            .expect("Expected highest verified checkpoint to have end of epoch data")
-            .epoch_commitments
-            .last()
-            .expect(
-                "End of epoch has no commitments. This likely means that the epoch \
-                you are attempting to restore from does not support end of epoch state \
-                digest commitment. If restoring from mainnet, `--epoch` must be > 20, \
-                and for testnet, `--epoch` must be > 12.",
-            );
-        match commitment {
-            CheckpointCommitment::ECMHLiveObjectSetDigest(consensus_digest) => {
-                let local_digest: ECMHLiveObjectSetDigest = root_accumulator.digest().into();
-                assert_eq!(
-                    *consensus_digest, local_digest,
-                    "End of epoch {} root state digest {} does not match \
-                    local root state hash {} after restoring from formal snapshot",
-                    epoch, consensus_digest.digest, local_digest.digest,
-                );
-                eprintln!("Formal snapshot state verification completed successfully!");
-            }
-        };
+        verify_root_state_hash(&root_accumulator, &last_checkpoint)?;
+        eprintln!("Formal snapshot state verification completed successfully!");
     } else {
         eprintln!(
             "WARNING: Skipping snapshot verification! \
@@ -1220,6 +1199,43 @@ pub async fn verify_archive_by_checksum(
     verify_archive_with_checksums(remote_store_config, concurrency).await
 }
 
+#[allow(clippy::too_many_arguments)]
+pub async fn compact_archive(
+    remote_store_config: ObjectStoreConfig,
+    staging_dir: PathBuf,
+    start_epoch: u64,
+    end_epoch: Option<u64>,
+    target_file_size: usize,
+    file_compression: FileCompression,
+    compression_level: i32,
+    keep_old_files: bool,
+    concurrency: usize,
+) -> Result<()> {
+    let end_epoch = match end_epoch {
+        Some(end_epoch) => end_epoch,
+        None => read_manifest(remote_store_config.make()?)
+            .await?
+            .epoch_num(),
+    };
+    let compactor = ArchiveCompactor::new(
+        remote_store_config,
+        staging_dir,
+        file_compression,
+        compression_level,
+        target_file_size,
+        NonZeroUsize::new(concurrency).unwrap(),
+        keep_old_files,
+    )?;
+    let manifest = compactor.compact(start_epoch..end_epoch).await?;
+    info!(
+        "Compacted epochs {}..{}, archive now has {} files",
+        start_epoch,
+        end_epoch,
+        manifest.files().len()
+    );
+    Ok(())
+}
+
 pub async fn state_sync_from_archive(
     path: &Path,
     genesis: &Path,
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
-use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_config::transaction_deny_config::{DenyRule, DenyTarget, TransactionDenyConfig};
 use sui_types::{
-    base_types::ObjectRef,
+    base_types::{ObjectID, ObjectRef},
     error::{SuiError, SuiResult, UserInputError},
     signature::GenericSignature,
     storage::BackingPackageStore,
@@ -157,6 +157,21 @@ fn check_package_dependencies(
     if deny_map.is_empty() {
         return Ok(());
     }
+    for dep in package_dependencies(tx_data, package_store)? {
+        deny_if_true!(
+            deny_map.contains(&dep),
+            format!("Access to package {:?} is temporarily disabled", dep)
+        );
+    }
+    Ok(())
+}
+
+/// The packages the transaction publishes against, upgrades or calls into, directly or through
+/// their dependencies.
+fn package_dependencies(
+    tx_data: &TransactionData,
+    package_store: &impl BackingPackageStore,
+) -> SuiResult<Vec<ObjectID>> {
     let mut dependencies = vec![];
     for command in tx_data.kind().iter_commands() {
         match command {
@@ -201,11 +216,48 @@ fn check_package_dependencies(
             | &Command::MakeMoveVec(..) => {}
         }
     }
-    for dep in dependencies {
-        deny_if_true!(
-            deny_map.contains(&dep),
-            format!("Access to package {:?} is temporarily disabled", dep)
-        );
+    Ok(dependencies)
+}
+
+/// The first of the `rules` denying the transaction, if any. Expired rules are skipped.
+pub fn find_matching_deny_rule<'a>(
+    rules: &'a [DenyRule],
+    now_ms: u64,
+    tx_data: &TransactionData,
+    input_objects: &[InputObjectKind],
+    receiving_objects: &[ObjectRef],
+    package_store: &impl BackingPackageStore,
+) -> SuiResult<Option<&'a DenyRule>> {
+    let rules: Vec<_> = rules.iter().filter(|r| !r.is_expired(now_ms)).collect();
+    if rules.is_empty() {
+        return Ok(None);
     }
-    Ok(())
+    let dependencies = if rules
+        .iter()
+        .any(|r| matches!(r.target, DenyTarget::Package(_)))
+    {
+        package_dependencies(tx_data, package_store)?
+    } else {
+        vec![]
+    };
+    let signers = tx_data.signers();
+    let matches = |target: &DenyTarget| match target {
+        DenyTarget::Package(package) => dependencies.contains(package),
+        DenyTarget::MoveFunction {
+            package,
+            module,
+            function,
+        } => tx_data.kind().iter_commands().any(|command| {
+            matches!(command, Command::MoveCall(call)
+                if call.package == *package
+                    && call.module.as_str() == module
+                    && call.function.as_str() == function)
+        }),
+        DenyTarget::Address(address) => signers.contains(address),
+        DenyTarget::Object(object) => {
+            input_objects.iter().any(|o| o.object_id() == *object)
+                || receiving_objects.iter().any(|(id, _, _)| id == object)
+        }
+    };
+    Ok(rules.into_iter().find(|r| matches(&r.target)))
 }
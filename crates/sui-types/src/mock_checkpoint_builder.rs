@@ -152,7 +152,7 @@ impl MockCheckpointBuilder {
         (checkpoint, contents, full_contents)
     }
 
-    fn create_certified_checkpoint(
+    pub fn create_certified_checkpoint(
         validator_keys: &impl ValidatorKeypairProvider,
         checkpoint: CheckpointSummary,
     ) -> VerifiedCheckpoint {
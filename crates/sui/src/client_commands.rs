@@ -27,13 +27,14 @@ use sui_move::build::resolve_lock_file_path;
 use sui_protocol_config::ProtocolConfig;
 use sui_source_validation::{BytecodeSourceVerifier, SourceMode};
 
+use crate::client_ptb::{build_ptb, estimate_gas_budget, Ptb};
 use shared_crypto::intent::Intent;
 use sui_execution::verifier::VerifierOverrides;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DryRunTransactionBlockResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
 use sui_keys::keystore::AccountKeystore;
@@ -57,7 +58,10 @@ use sui_types::{
     object::Owner,
     parse_sui_type_tag,
     signature::GenericSignature,
-    transaction::{SenderSignedData, Transaction, TransactionData, TransactionDataAPI},
+    transaction::{
+        InputObjectKind, SenderSignedData, Transaction, TransactionData, TransactionDataAPI,
+        TransactionKind,
+    },
 };
 
 use tabled::{
@@ -163,6 +167,22 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Instead of executing the transaction, dry run it on the fullnode and print the effects.
+        #[clap(
+            long,
+            required = false,
+            conflicts_with_all = ["serialize_unsigned_transaction", "serialize_signed_transaction"]
+        )]
+        dry_run: bool,
+
+        /// Dry run the transaction, re-execute it locally and write a gas profile of the Move
+        /// functions it calls to this file, in speedscope format.
+        #[clap(
+            long,
+            conflicts_with_all = ["serialize_unsigned_transaction", "serialize_signed_transaction"]
+        )]
+        profile_output: Option<PathBuf>,
     },
 
     /// Query the chain identifier from the rpc endpoint.
@@ -366,6 +386,32 @@ pub enum SuiClientCommands {
         serialize_signed_transaction: bool,
     },
 
+    /// Build, sign and execute a programmable transaction block, made up of a sequence of
+    /// commands. Each command starts with `--`, and may refer to the results of earlier commands:
+    ///
+    ///   --split-coins <coin> [<amount>, ...]
+    ///   --merge-coins <coin> [<coin>, ...]
+    ///   --transfer-objects [<object>, ...] <address>
+    ///   --make-move-vec <type> [<value>, ...]
+    ///   --move-call <package>::<module>::<function> <type args>? <arg> ...
+    ///   --publish <package path>
+    ///   --assign <name> <value>?
+    ///
+    /// `--assign` binds a name to a value, or to the result of the preceding command if no value
+    /// is given. Names can then be used as arguments, as can `name.<i>` to refer to the i-th
+    /// result of a command. Addresses and object IDs are prefixed with `@`, and `gas` refers to
+    /// the gas coin.
+    ///
+    /// The transaction is configured by the options `--gas <id>`, `--gas-budget <amount>`,
+    /// `--dry-run`, `--serialize-unsigned-transaction` and `--serialize-signed-transaction`.
+    /// If no gas budget is provided, it is estimated by dev-inspecting the transaction.
+    #[clap(name = "ptb")]
+    Ptb {
+        /// The commands and options making up the transaction.
+        #[clap(trailing_var_arg = true, allow_hyphen_values = true, num_args(1..))]
+        args: Vec<String>,
+    },
+
     /// Publish Move modules
     #[clap(name = "publish")]
     Publish {
@@ -616,6 +662,10 @@ pub enum SuiClientCommands {
         /// The digest of the transaction to replay
         #[arg(long, short)]
         tx_digest: String,
+
+        /// Write a gas profile of the transaction to this file, in speedscope format
+        #[arg(long)]
+        profile_output: Option<PathBuf>,
     },
 
     /// Replay transactions listed in a file.
@@ -653,13 +703,18 @@ impl SuiClientCommands {
         context: &mut WalletContext,
     ) -> Result<SuiClientCommandResult, anyhow::Error> {
         let ret = Ok(match self {
-            SuiClientCommands::ReplayTransaction { tx_digest } => {
+            SuiClientCommands::ReplayTransaction {
+                tx_digest,
+                profile_output,
+            } => {
                 let cmd = ReplayToolCommand::ReplayTransaction {
                     tx_digest,
                     show_effects: true,
                     diag: false,
                     executor_version_override: None,
                     protocol_version_override: None,
+                    profile_output,
+                    package_override: vec![],
                 };
                 let rpc = context.config.get_active_env()?.rpc.clone();
                 let _command_result =
@@ -690,6 +745,7 @@ impl SuiClientCommands {
                     end,
                     terminate_early,
                     max_tasks: 16,
+                    archive: Default::default(),
                 };
                 let rpc = context.config.get_active_env()?.rpc.clone();
                 let _command_result =
@@ -941,18 +997,99 @@ impl SuiClientCommands {
                 args,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
+                profile_output,
             } => {
                 let tx_data = construct_move_call_transaction(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
                 .await?;
-                serialize_or_execute!(
-                    tx_data,
+                if dry_run || profile_output.is_some() {
+                    let client = context.get_client().await?;
+                    let response = client
+                        .read_api()
+                        .dry_run_transaction_block(tx_data.clone())
+                        .await?;
+                    if let Some(profile_output) = profile_output {
+                        let rpc = context.config.get_active_env()?.rpc.clone();
+                        sui_replay::profile_dry_run(
+                            rpc,
+                            tx_data,
+                            response.effects.clone(),
+                            profile_output,
+                        )
+                        .await?;
+                    }
+                    SuiClientCommandResult::DryRun(response)
+                } else {
+                    serialize_or_execute!(
+                        tx_data,
+                        serialize_unsigned_transaction,
+                        serialize_signed_transaction,
+                        context,
+                        Call
+                    )
+                }
+            }
+
+            SuiClientCommands::Ptb { args } => {
+                let Ptb {
+                    commands,
+                    gas,
+                    gas_budget,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
-                    context,
-                    Call
-                )
+                } = Ptb::parse(&args)?;
+
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+
+                let client = context.get_client().await?;
+                let pt = build_ptb(&client, commands).await?;
+                let gas_price = client.read_api().get_reference_gas_price().await?;
+                let gas_budget = match gas_budget {
+                    Some(gas_budget) => gas_budget,
+                    None => {
+                        let kind = TransactionKind::programmable(pt.clone());
+                        estimate_gas_budget(&client, sender, kind, gas_price).await?
+                    }
+                };
+
+                let gas = match gas {
+                    Some(gas) => context.get_object_ref(gas).await?,
+                    None => {
+                        let input_objects = pt
+                            .input_objects()?
+                            .iter()
+                            .flat_map(|obj| match obj {
+                                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+                                _ => None,
+                            })
+                            .collect();
+                        context
+                            .gas_for_owner_budget(sender, gas_budget, input_objects)
+                            .await?
+                            .1
+                            .object_ref()
+                    }
+                };
+
+                let data =
+                    TransactionData::new_programmable(sender, vec![gas], pt, gas_budget, gas_price);
+
+                if dry_run {
+                    let response = client.read_api().dry_run_transaction_block(data).await?;
+                    SuiClientCommandResult::DryRun(response)
+                } else {
+                    serialize_or_execute!(
+                        data,
+                        serialize_unsigned_transaction,
+                        serialize_signed_transaction,
+                        context,
+                        Ptb
+                    )
+                }
             }
 
             SuiClientCommands::Transfer {
@@ -1376,7 +1513,7 @@ fn compile_package_simple(
     )?)
 }
 
-async fn compile_package(
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1609,6 +1746,12 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Call(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::DryRun(response) => {
+                write!(writer, "{}", write_dry_run_response(response)?)?;
+            }
             SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) => {
                 writeln!(
                     writer,
@@ -1804,6 +1947,27 @@ pub fn write_transaction_response(
     Ok(writer)
 }
 
+pub fn write_dry_run_response(
+    response: &DryRunTransactionBlockResponse,
+) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "{}", "----- Transaction Data ----".bold())?;
+    writeln!(writer, "{}", response.input)?;
+
+    writeln!(writer, "{}", "----- Transaction Effects ----".bold())?;
+    writeln!(writer, "{}", response.effects)?;
+
+    writeln!(writer, "{}", "----- Events ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.events))?;
+
+    writeln!(writer, "{}", "----- Object changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.object_changes))?;
+
+    writeln!(writer, "{}", "----- Balance changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.balance_changes))?;
+    Ok(writer)
+}
+
 impl Debug for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s = unwrap_err_to_string(|| match self {
@@ -1864,7 +2028,7 @@ impl SuiClientCommandResult {
         match self {
             Upgrade(b) | Publish(b) | TransactionBlock(b) | Call(b) | Transfer(b)
             | TransferSui(b) | Pay(b) | PaySui(b) | PayAllSui(b) | SplitCoin(b) | MergeCoin(b)
-            | ExecuteSignedTx(b) => Some(b),
+            | ExecuteSignedTx(b) | Ptb(b) => Some(b),
             _ => None,
         }
     }
@@ -2002,6 +2166,7 @@ pub enum SuiClientCommandResult {
     Addresses(AddressesOutput),
     Call(SuiTransactionBlockResponse),
     ChainIdentifier(String),
+    DryRun(DryRunTransactionBlockResponse),
     DynamicFieldQuery(DynamicFieldPage),
     Envs(Vec<SuiEnv>, Option<String>),
     ExecuteSignedTx(SuiTransactionBlockResponse),
@@ -2014,6 +2179,7 @@ pub enum SuiClientCommandResult {
     Pay(SuiTransactionBlockResponse),
     PayAllSui(SuiTransactionBlockResponse),
     PaySui(SuiTransactionBlockResponse),
+    Ptb(SuiTransactionBlockResponse),
     Publish(SuiTransactionBlockResponse),
     RawObject(SuiObjectResponse),
     SerializedSignedTransaction(SenderSignedData),
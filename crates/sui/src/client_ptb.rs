@@ -0,0 +1,1050 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for `sui client ptb`: parsing a sequence of programmable transaction block commands from
+//! the command line, and resolving them against the chain to build a single
+//! [`ProgrammableTransaction`].
+
+use std::{collections::BTreeMap, iter::Peekable, path::PathBuf, str::FromStr, vec};
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::{StructTag, TypeTag},
+    u256::U256,
+    value::MoveValue,
+};
+use move_package::BuildConfig as MoveBuildConfig;
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiMoveNormalizedType, SuiObjectDataOptions, SuiTransactionBlockEffectsAPI,
+};
+use sui_sdk::SuiClient;
+use sui_types::{
+    base_types::{
+        ObjectID, SuiAddress, RESOLVED_ASCII_STR, RESOLVED_STD_OPTION, RESOLVED_UTF8_STR,
+        TX_CONTEXT_MODULE_NAME, TX_CONTEXT_STRUCT_NAME,
+    },
+    id::RESOLVED_SUI_ID,
+    object::Owner,
+    parse_sui_type_tag,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    sui_serde::BigInt,
+    transaction::{Argument, Command, ObjectArg, ProgrammableTransaction, TransactionKind},
+    transfer::RESOLVED_RECEIVING_STRUCT,
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+use crate::client_commands::compile_package;
+
+#[cfg(test)]
+#[path = "unit_tests/client_ptb_tests.rs"]
+mod client_ptb_tests;
+
+/// Gas units added on top of the estimated computation cost when no gas budget is specified, to
+/// absorb small differences between estimation and execution.
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+/// A programmable transaction block described on the command line: the commands that make it up,
+/// and the options controlling how it is executed.
+#[derive(Debug, Default, PartialEq)]
+pub struct Ptb {
+    pub commands: Vec<PtbCommand>,
+    pub gas: Option<ObjectID>,
+    pub gas_budget: Option<u64>,
+    pub dry_run: bool,
+    pub serialize_unsigned_transaction: bool,
+    pub serialize_signed_transaction: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PtbCommand {
+    /// Bind `name` to `value`, or to the result of the preceding command if there is no value.
+    Assign {
+        name: String,
+        value: Option<PtbValue>,
+    },
+    SplitCoins {
+        coin: PtbValue,
+        amounts: Vec<PtbValue>,
+    },
+    MergeCoins {
+        into: PtbValue,
+        coins: Vec<PtbValue>,
+    },
+    TransferObjects {
+        objects: Vec<PtbValue>,
+        to: PtbValue,
+    },
+    MakeMoveVec {
+        type_: TypeTag,
+        elements: Vec<PtbValue>,
+    },
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbValue>,
+    },
+    Publish {
+        package_path: PathBuf,
+    },
+}
+
+/// An argument to a command, as written on the command line. Literals are only given a type when
+/// they are used, based on the command (or Move function parameter) they are passed to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PtbValue {
+    Gas,
+    Bool(bool),
+    /// The digits of an integer literal, and its type, if it was given with a suffix (e.g. `10u8`).
+    Number(String, Option<TypeTag>),
+    /// An address, or an object ID, prefixed with `@`.
+    Address(SuiAddress),
+    String(String),
+    Vector(Vec<PtbValue>),
+    Option(Option<Box<PtbValue>>),
+    /// A name bound by an earlier `--assign`.
+    Var(String),
+    /// An element of the result of a command bound by an earlier `--assign` (e.g. `coins.0`).
+    NestedVar(String, u16),
+}
+
+impl Ptb {
+    /// Parse the arguments passed to `sui client ptb`. Every command or option starts with `--`,
+    /// and is followed by its operands, up until the next `--`.
+    pub fn parse(args: &[String]) -> Result<Self, anyhow::Error> {
+        let mut ptb = Ptb::default();
+        let mut args = args.iter().peekable();
+
+        while let Some(flag) = args.next() {
+            let Some(name) = flag.strip_prefix("--") else {
+                bail!("Expected a command or option starting with '--', but found '{flag}'");
+            };
+
+            let mut operands = vec![];
+            while let Some(operand) = args.next_if(|arg| !arg.starts_with("--")) {
+                operands.push(operand.as_str());
+            }
+
+            match name {
+                "gas" => ptb.gas = Some(parse_object_id(single_operand(flag, &operands)?)?),
+                "gas-budget" => {
+                    let budget = single_operand(flag, &operands)?;
+                    ptb.gas_budget = Some(
+                        budget
+                            .replace('_', "")
+                            .parse()
+                            .map_err(|_| anyhow!("Invalid gas budget '{budget}'"))?,
+                    );
+                }
+                "dry-run" => {
+                    no_operands(flag, &operands)?;
+                    ptb.dry_run = true;
+                }
+                "serialize-unsigned-transaction" => {
+                    no_operands(flag, &operands)?;
+                    ptb.serialize_unsigned_transaction = true;
+                }
+                "serialize-signed-transaction" => {
+                    no_operands(flag, &operands)?;
+                    ptb.serialize_signed_transaction = true;
+                }
+                "publish" => ptb.commands.push(PtbCommand::Publish {
+                    package_path: PathBuf::from(single_operand(flag, &operands)?),
+                }),
+                _ => {
+                    let mut parser = Parser::new(&operands)?;
+                    ptb.commands.push(parser.command(name)?);
+                    parser.finish(flag)?;
+                }
+            }
+        }
+
+        ensure!(
+            !ptb.commands.is_empty(),
+            "A programmable transaction block needs at least one command"
+        );
+        ensure!(
+            !ptb.serialize_unsigned_transaction || !ptb.serialize_signed_transaction,
+            "Cannot specify both --serialize-unsigned-transaction and --serialize-signed-transaction"
+        );
+        ensure!(
+            !ptb.dry_run
+                || !(ptb.serialize_unsigned_transaction || ptb.serialize_signed_transaction),
+            "Cannot specify --dry-run together with --serialize-unsigned-transaction or \
+             --serialize-signed-transaction"
+        );
+
+        Ok(ptb)
+    }
+}
+
+/// Resolve `commands` against the chain (fetching objects, function signatures, and compiling
+/// packages as necessary) to build a programmable transaction.
+pub async fn build_ptb(
+    client: &SuiClient,
+    commands: Vec<PtbCommand>,
+) -> Result<ProgrammableTransaction, anyhow::Error> {
+    let mut builder = PtbBuilder {
+        client,
+        builder: ProgrammableTransactionBuilder::new(),
+        bindings: BTreeMap::new(),
+        last_result: None,
+    };
+
+    for command in commands {
+        builder.command(command).await?;
+    }
+
+    Ok(builder.builder.finish())
+}
+
+/// Estimate the gas budget for running `kind` as `sender`, by dev-inspecting it, and adding a
+/// safety margin to its computation cost.
+pub async fn estimate_gas_budget(
+    client: &SuiClient,
+    sender: SuiAddress,
+    kind: TransactionKind,
+    gas_price: u64,
+) -> Result<u64, anyhow::Error> {
+    let results = client
+        .read_api()
+        .dev_inspect_transaction_block(sender, kind, Some(BigInt::from(gas_price)), None)
+        .await?;
+
+    if let SuiExecutionStatus::Failure { error } = results.effects.status() {
+        bail!("Failed to estimate the gas budget, because the transaction would fail: {error}");
+    }
+
+    let summary = results.effects.gas_cost_summary();
+    let computation = summary.computation_cost + GAS_SAFE_OVERHEAD * gas_price;
+    Ok(
+        computation
+            .max((computation + summary.storage_cost).saturating_sub(summary.storage_rebate)),
+    )
+}
+
+struct PtbBuilder<'a> {
+    client: &'a SuiClient,
+    builder: ProgrammableTransactionBuilder,
+    bindings: BTreeMap<String, Binding>,
+    last_result: Option<Argument>,
+}
+
+/// What a name bound by `--assign` refers to.
+#[derive(Debug, Clone)]
+enum Binding {
+    Argument(Argument),
+    /// A literal, which is only turned into an input once its type is known, at each use.
+    Value(PtbValue),
+}
+
+/// How a command, or Move function, expects to use one of its arguments.
+enum ArgKind {
+    Object { mutable: bool },
+    ObjectVector,
+    Receiving,
+    Pure(TypeTag),
+}
+
+impl<'a> PtbBuilder<'a> {
+    async fn command(&mut self, command: PtbCommand) -> Result<(), anyhow::Error> {
+        use PtbCommand as C;
+
+        let result = match command {
+            C::Assign { name, value } => {
+                let binding = match value {
+                    Some(value) => self.lookup(&value)?,
+                    None => Binding::Argument(self.last_result.ok_or_else(|| {
+                        anyhow!("Cannot assign '{name}', no command precedes it")
+                    })?),
+                };
+                self.bindings.insert(name, binding);
+                return Ok(());
+            }
+
+            C::SplitCoins { coin, amounts } => {
+                let coin = self
+                    .resolve(&coin, &ArgKind::Object { mutable: true })
+                    .await?;
+                let mut args = vec![];
+                for amount in &amounts {
+                    args.push(self.resolve(amount, &ArgKind::Pure(TypeTag::U64)).await?);
+                }
+                self.builder.command(Command::SplitCoins(coin, args))
+            }
+
+            C::MergeCoins { into, coins } => {
+                let into = self
+                    .resolve(&into, &ArgKind::Object { mutable: true })
+                    .await?;
+                let mut args = vec![];
+                for coin in &coins {
+                    args.push(
+                        self.resolve(coin, &ArgKind::Object { mutable: true })
+                            .await?,
+                    );
+                }
+                self.builder.command(Command::MergeCoins(into, args))
+            }
+
+            C::TransferObjects { objects, to } => {
+                let mut args = vec![];
+                for object in &objects {
+                    args.push(
+                        self.resolve(object, &ArgKind::Object { mutable: true })
+                            .await?,
+                    );
+                }
+                let to = self.resolve(&to, &ArgKind::Pure(TypeTag::Address)).await?;
+                self.builder.command(Command::TransferObjects(args, to))
+            }
+
+            C::MakeMoveVec { type_, elements } => {
+                let kind = if is_pure_type(&type_) {
+                    ArgKind::Pure(type_.clone())
+                } else {
+                    ArgKind::Object { mutable: true }
+                };
+                let mut args = vec![];
+                for element in &elements {
+                    args.push(self.resolve(element, &kind).await?);
+                }
+                self.builder
+                    .command(Command::MakeMoveVec(Some(type_), args))
+            }
+
+            C::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            } => {
+                self.move_call(package, module, function, type_args, args)
+                    .await?
+            }
+
+            C::Publish { package_path } => {
+                let (dependencies, compiled_modules, _, _) = compile_package(
+                    self.client,
+                    MoveBuildConfig::default(),
+                    package_path,
+                    /* with_unpublished_dependencies */ false,
+                    /* skip_dependency_verification */ false,
+                    /* lint */ false,
+                )
+                .await?;
+                self.builder.publish_upgradeable(
+                    compiled_modules,
+                    dependencies.published.into_values().collect(),
+                )
+            }
+        };
+
+        self.last_result = Some(result);
+        Ok(())
+    }
+
+    async fn move_call(
+        &mut self,
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbValue>,
+    ) -> Result<Argument, anyhow::Error> {
+        let modules = self
+            .client
+            .read_api()
+            .get_normalized_move_modules_by_package(package)
+            .await?;
+
+        let signature = modules
+            .get(&module)
+            .and_then(|m| m.exposed_functions.get(&function))
+            .ok_or_else(|| anyhow!("Could not find function {package}::{module}::{function}"))?;
+
+        ensure!(
+            signature.type_parameters.len() == type_args.len(),
+            "Expected {} type argument(s) for {package}::{module}::{function}, but found {}",
+            signature.type_parameters.len(),
+            type_args.len(),
+        );
+
+        // The `TxContext` is supplied by the runtime, rather than passed explicitly.
+        let params: Vec<_> = signature
+            .parameters
+            .iter()
+            .filter(|param| !is_tx_context(param))
+            .collect();
+
+        ensure!(
+            params.len() == args.len(),
+            "Expected {} argument(s) for {package}::{module}::{function}, but found {}",
+            params.len(),
+            args.len(),
+        );
+
+        let mut arguments = vec![];
+        for (arg, param) in args.iter().zip(params) {
+            let kind = arg_kind(param, &type_args)?;
+            arguments.push(self.resolve(arg, &kind).await?);
+        }
+
+        Ok(self.builder.programmable_move_call(
+            package,
+            Identifier::new(module)?,
+            Identifier::new(function)?,
+            type_args,
+            arguments,
+        ))
+    }
+
+    /// Turn `value` into an argument to a command that uses it as described by `kind`, adding
+    /// inputs to the transaction as necessary.
+    async fn resolve(
+        &mut self,
+        value: &PtbValue,
+        kind: &ArgKind,
+    ) -> Result<Argument, anyhow::Error> {
+        let value = match self.lookup(value)? {
+            Binding::Argument(arg) => return Ok(arg),
+            Binding::Value(value) => value,
+        };
+
+        match kind {
+            ArgKind::Pure(type_) => {
+                let value = self.pure_value(&value, type_)?;
+                Ok(self.builder.pure_bytes(bcs::to_bytes(&value)?, false))
+            }
+
+            ArgKind::Object { mutable } => self.resolve_object(&value, *mutable, false).await,
+
+            ArgKind::Receiving => self.resolve_object(&value, false, true).await,
+
+            ArgKind::ObjectVector => {
+                let PtbValue::Vector(elements) = value else {
+                    bail!("Expected a vector of objects, but found {value:?}");
+                };
+
+                let mut args = vec![];
+                for element in &elements {
+                    let element = match self.lookup(element)? {
+                        Binding::Argument(arg) => {
+                            args.push(arg);
+                            continue;
+                        }
+                        Binding::Value(element) => element,
+                    };
+                    args.push(self.resolve_object(&element, true, false).await?);
+                }
+                Ok(self.builder.command(Command::MakeMoveVec(None, args)))
+            }
+        }
+    }
+
+    async fn resolve_object(
+        &mut self,
+        value: &PtbValue,
+        mutable: bool,
+        receiving: bool,
+    ) -> Result<Argument, anyhow::Error> {
+        let PtbValue::Address(address) = value else {
+            bail!("Expected an object ID (starting with '@'), but found {value:?}");
+        };
+
+        let id = ObjectID::from(*address);
+        let object = self
+            .client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::new().with_owner())
+            .await?
+            .into_object()?;
+
+        let object_ref = object.object_ref();
+        let object_arg = if receiving {
+            ObjectArg::Receiving(object_ref)
+        } else {
+            match object.owner {
+                Some(Owner::Shared {
+                    initial_shared_version,
+                }) => ObjectArg::SharedObject {
+                    id,
+                    initial_shared_version,
+                    mutable,
+                },
+                Some(_) => ObjectArg::ImmOrOwnedObject(object_ref),
+                None => bail!("Owner of object {id} is unknown"),
+            }
+        };
+
+        self.builder.obj(object_arg)
+    }
+
+    /// Follow `value` to what it refers to, if it is a name bound by `--assign`, or the gas coin.
+    fn lookup(&self, value: &PtbValue) -> Result<Binding, anyhow::Error> {
+        Ok(match value {
+            PtbValue::Gas => Binding::Argument(Argument::GasCoin),
+            PtbValue::Var(name) => self
+                .bindings
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("Unbound name '{name}'"))?,
+            PtbValue::NestedVar(name, ix) => match self.bindings.get(name) {
+                Some(Binding::Argument(Argument::Result(cmd))) => {
+                    Binding::Argument(Argument::NestedResult(*cmd, *ix))
+                }
+                Some(_) => {
+                    bail!("'{name}' is not the result of a command, so '{name}.{ix}' is invalid")
+                }
+                None => bail!("Unbound name '{name}'"),
+            },
+            value => Binding::Value(value.clone()),
+        })
+    }
+
+    /// Interpret `value` as a pure value of type `type_`.
+    fn pure_value(&self, value: &PtbValue, type_: &TypeTag) -> Result<MoveValue, anyhow::Error> {
+        let value = match self.lookup(value)? {
+            Binding::Value(value) => value,
+            Binding::Argument(_) => {
+                bail!("Results of commands cannot be used within literals, found {value:?}")
+            }
+        };
+
+        Ok(match (&value, type_) {
+            (PtbValue::Bool(b), TypeTag::Bool) => MoveValue::Bool(*b),
+
+            (PtbValue::Number(digits, suffix), _) => {
+                if let Some(suffix) = suffix {
+                    ensure!(
+                        suffix == type_,
+                        "Expected a value of type {type_}, but found {digits}{suffix}"
+                    );
+                }
+                number_value(digits, type_)?
+            }
+
+            (PtbValue::Address(address), TypeTag::Address) => {
+                MoveValue::Address(AccountAddress::from(*address))
+            }
+
+            (PtbValue::Address(address), TypeTag::Struct(tag))
+                if resolved(tag) == RESOLVED_SUI_ID =>
+            {
+                MoveValue::Address(AccountAddress::from(*address))
+            }
+
+            (PtbValue::String(s), TypeTag::Struct(tag))
+                if [RESOLVED_ASCII_STR, RESOLVED_UTF8_STR].contains(&resolved(tag)) =>
+            {
+                MoveValue::Vector(s.bytes().map(MoveValue::U8).collect())
+            }
+
+            (PtbValue::String(s), TypeTag::Vector(inner)) if **inner == TypeTag::U8 => {
+                MoveValue::Vector(s.bytes().map(MoveValue::U8).collect())
+            }
+
+            (PtbValue::Vector(elements), TypeTag::Vector(inner)) => MoveValue::Vector(
+                elements
+                    .iter()
+                    .map(|element| self.pure_value(element, inner))
+                    .collect::<Result<_, _>>()?,
+            ),
+
+            (PtbValue::Option(inner), TypeTag::Struct(tag))
+                if resolved(tag) == RESOLVED_STD_OPTION && tag.type_params.len() == 1 =>
+            {
+                MoveValue::Vector(
+                    inner
+                        .iter()
+                        .map(|inner| self.pure_value(inner, &tag.type_params[0]))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+
+            _ => bail!("Expected a value of type {type_}, but found {value:?}"),
+        })
+    }
+}
+
+/// Tokens of a single command's operands.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    /// The contents of a balanced `<...>`.
+    TypeArgs(String),
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Comma,
+}
+
+struct Parser {
+    tokens: Peekable<vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    /// Tokenize each operand on its own, so that tokens never span operands that the shell has
+    /// already split.
+    fn new(operands: &[&str]) -> Result<Self, anyhow::Error> {
+        let mut tokens = vec![];
+        for operand in operands {
+            tokens.extend(tokenize_operand(operand)?);
+        }
+        Ok(Self {
+            tokens: tokens.into_iter().peekable(),
+        })
+    }
+
+    fn command(&mut self, name: &str) -> Result<PtbCommand, anyhow::Error> {
+        Ok(match name {
+            "assign" => {
+                let name = self.word()?;
+                ensure!(is_identifier(&name), "Invalid name for --assign: '{name}'");
+                let value = if self.tokens.peek().is_some() {
+                    Some(self.value()?)
+                } else {
+                    None
+                };
+                PtbCommand::Assign { name, value }
+            }
+
+            "split-coins" => PtbCommand::SplitCoins {
+                coin: self.value()?,
+                amounts: self.vector()?,
+            },
+
+            "merge-coins" => PtbCommand::MergeCoins {
+                into: self.value()?,
+                coins: self.vector()?,
+            },
+
+            "transfer-objects" => PtbCommand::TransferObjects {
+                objects: self.vector()?,
+                to: self.value()?,
+            },
+
+            "make-move-vec" => {
+                let Some(Token::TypeArgs(type_)) = self.tokens.next() else {
+                    bail!("--make-move-vec expects the type of its elements, e.g. <u64>");
+                };
+                PtbCommand::MakeMoveVec {
+                    type_: parse_sui_type_tag(&type_)?,
+                    elements: self.vector()?,
+                }
+            }
+
+            "move-call" => {
+                let target = self.word()?;
+                let mut parts = target.split("::");
+                let (Some(package), Some(module), Some(function), None) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                else {
+                    bail!("Expected a function of the form <package>::<module>::<function>, found '{target}'");
+                };
+
+                let type_args = if let Some(Token::TypeArgs(_)) = self.tokens.peek() {
+                    let Some(Token::TypeArgs(type_args)) = self.tokens.next() else {
+                        unreachable!();
+                    };
+                    parse_type_args(&type_args)?
+                } else {
+                    vec![]
+                };
+
+                let mut args = vec![];
+                while self.tokens.peek().is_some() {
+                    args.push(self.value()?);
+                }
+
+                PtbCommand::MoveCall {
+                    package: parse_object_id(package)?,
+                    module: module.to_string(),
+                    function: function.to_string(),
+                    type_args,
+                    args,
+                }
+            }
+
+            _ => bail!("Unknown command or option '--{name}'"),
+        })
+    }
+
+    fn finish(mut self, flag: &str) -> Result<(), anyhow::Error> {
+        match self.tokens.next() {
+            None => Ok(()),
+            Some(token) => bail!("Unexpected {token:?} at the end of {flag}"),
+        }
+    }
+
+    fn word(&mut self) -> Result<String, anyhow::Error> {
+        match self.tokens.next() {
+            Some(Token::Word(word)) => Ok(word),
+            Some(token) => bail!("Expected a name, but found {token:?}"),
+            None => bail!("Expected a name, but reached the end of the command"),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), anyhow::Error> {
+        match self.tokens.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => bail!("Expected {expected:?}, but found {token:?}"),
+            None => bail!("Expected {expected:?}, but reached the end of the command"),
+        }
+    }
+
+    fn value(&mut self) -> Result<PtbValue, anyhow::Error> {
+        match self.tokens.next() {
+            Some(Token::LBracket) => Ok(PtbValue::Vector(self.elements()?)),
+            Some(Token::Str(s)) => Ok(PtbValue::String(s)),
+            Some(Token::Word(word)) => self.word_value(word),
+            Some(token) => bail!("Expected a value, but found {token:?}"),
+            None => bail!("Expected a value, but reached the end of the command"),
+        }
+    }
+
+    fn vector(&mut self) -> Result<Vec<PtbValue>, anyhow::Error> {
+        self.expect(Token::LBracket)?;
+        self.elements()
+    }
+
+    /// Comma-separated values up to and including the closing `]`, which may be preceded by a
+    /// trailing comma.
+    fn elements(&mut self) -> Result<Vec<PtbValue>, anyhow::Error> {
+        let mut elements = vec![];
+        loop {
+            if self.tokens.next_if_eq(&Token::RBracket).is_some() {
+                return Ok(elements);
+            }
+
+            elements.push(self.value()?);
+            match self.tokens.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RBracket) => return Ok(elements),
+                Some(token) => bail!("Expected ',' or ']', but found {token:?}"),
+                None => bail!("Unterminated vector, expected ']'"),
+            }
+        }
+    }
+
+    fn word_value(&mut self, word: String) -> Result<PtbValue, anyhow::Error> {
+        Ok(match word.as_str() {
+            "gas" => PtbValue::Gas,
+            "true" => PtbValue::Bool(true),
+            "false" => PtbValue::Bool(false),
+            "none" => PtbValue::Option(None),
+            "some" => {
+                self.expect(Token::LParen)?;
+                let inner = self.value()?;
+                self.expect(Token::RParen)?;
+                PtbValue::Option(Some(Box::new(inner)))
+            }
+
+            _ => {
+                if let Some(address) = word.strip_prefix('@') {
+                    PtbValue::Address(SuiAddress::from(parse_object_id(address)?))
+                } else if word.starts_with(|c: char| c.is_ascii_digit()) {
+                    parse_number(&word)?
+                } else if let Some((name, ix)) = word.split_once('.') {
+                    ensure!(is_identifier(name), "Invalid name '{name}' in '{word}'");
+                    let ix = ix
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid result index in '{word}'"))?;
+                    PtbValue::NestedVar(name.to_string(), ix)
+                } else {
+                    ensure!(is_identifier(&word), "Unrecognized value '{word}'");
+                    PtbValue::Var(word)
+                }
+            }
+        })
+    }
+}
+
+/// The shell removes the quotes around an operand containing whitespace or quotes, so unless it is
+/// a vector, an option, type arguments or a quoted literal (which are quoted to keep them in one
+/// piece), such an operand is a string literal.
+fn tokenize_operand(operand: &str) -> Result<Vec<Token>, anyhow::Error> {
+    let trimmed = operand.trim_start();
+    let structured = trimmed.starts_with(['[', '<', '"', '\'']) || trimmed.starts_with("some(");
+    if !structured && operand.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+        return Ok(vec![Token::Str(operand.to_string())]);
+    }
+    tokenize(operand)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, anyhow::Error> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '[' => tokens.push(Token::LBracket),
+            ']' => tokens.push(Token::RBracket),
+            '(' => tokens.push(Token::LParen),
+            ')' => tokens.push(Token::RParen),
+            ',' => tokens.push(Token::Comma),
+
+            '"' | '\'' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some(d) if d == c => break,
+                        Some(d) => s.push(d),
+                        None => bail!("Unterminated string literal"),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+
+            '<' => {
+                let mut depth = 1;
+                let mut s = String::new();
+                loop {
+                    let Some(d) = chars.next() else {
+                        bail!("Unterminated type arguments, expected '>'");
+                    };
+                    match d {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                    s.push(d);
+                }
+                tokens.push(Token::TypeArgs(s));
+            }
+
+            '>' => bail!("Unexpected '>'"),
+
+            c => {
+                let mut word = String::from(c);
+                while let Some(d) =
+                    chars.next_if(|d| !d.is_whitespace() && !"[](),<>\"'".contains(*d))
+                {
+                    word.push(d);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Split the contents of a `<...>` at its top-level commas, and parse each part as a type.
+fn parse_type_args(type_args: &str) -> Result<Vec<TypeTag>, anyhow::Error> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in type_args.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&type_args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&type_args[start..]);
+
+    parts
+        .into_iter()
+        .map(|part| parse_sui_type_tag(part.trim()))
+        .collect()
+}
+
+fn parse_number(word: &str) -> Result<PtbValue, anyhow::Error> {
+    let suffixes = [
+        ("u8", TypeTag::U8),
+        ("u16", TypeTag::U16),
+        ("u32", TypeTag::U32),
+        ("u64", TypeTag::U64),
+        ("u128", TypeTag::U128),
+        ("u256", TypeTag::U256),
+    ];
+
+    let (digits, suffix) = suffixes
+        .into_iter()
+        .find_map(|(suffix, type_)| Some((word.strip_suffix(suffix)?, Some(type_))))
+        .unwrap_or((word, None));
+
+    let digits = digits.replace('_', "");
+    ensure!(
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+        "Invalid number '{word}'"
+    );
+
+    Ok(PtbValue::Number(digits, suffix))
+}
+
+fn number_value(digits: &str, type_: &TypeTag) -> Result<MoveValue, anyhow::Error> {
+    let out_of_range = || anyhow!("{digits} is not a valid {type_}");
+    Ok(match type_ {
+        TypeTag::U8 => MoveValue::U8(digits.parse().map_err(|_| out_of_range())?),
+        TypeTag::U16 => MoveValue::U16(digits.parse().map_err(|_| out_of_range())?),
+        TypeTag::U32 => MoveValue::U32(digits.parse().map_err(|_| out_of_range())?),
+        TypeTag::U64 => MoveValue::U64(digits.parse().map_err(|_| out_of_range())?),
+        TypeTag::U128 => MoveValue::U128(digits.parse().map_err(|_| out_of_range())?),
+        TypeTag::U256 => MoveValue::U256(U256::from_str(digits).map_err(|_| out_of_range())?),
+        _ => bail!("Expected a value of type {type_}, but found the number {digits}"),
+    })
+}
+
+fn parse_object_id(s: &str) -> Result<ObjectID, anyhow::Error> {
+    let s = s.strip_prefix('@').unwrap_or(s);
+    ObjectID::from_hex_literal(s).map_err(|_| anyhow!("Invalid address or object ID '{s}'"))
+}
+
+fn single_operand<'s>(flag: &str, operands: &[&'s str]) -> Result<&'s str, anyhow::Error> {
+    match operands {
+        [operand] => Ok(operand),
+        _ => bail!(
+            "{flag} expects exactly one operand, found {}",
+            operands.len()
+        ),
+    }
+}
+
+fn no_operands(flag: &str, operands: &[&str]) -> Result<(), anyhow::Error> {
+    ensure!(operands.is_empty(), "{flag} does not take any operands");
+    Ok(())
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !["gas", "true", "false", "none", "some"].contains(&s)
+}
+
+fn resolved(tag: &StructTag) -> (&AccountAddress, &IdentStr, &IdentStr) {
+    (
+        &tag.address,
+        tag.module.as_ident_str(),
+        tag.name.as_ident_str(),
+    )
+}
+
+/// Whether values of this type can be passed as pure inputs.
+fn is_pure_type(type_: &TypeTag) -> bool {
+    match type_ {
+        TypeTag::Bool
+        | TypeTag::U8
+        | TypeTag::U16
+        | TypeTag::U32
+        | TypeTag::U64
+        | TypeTag::U128
+        | TypeTag::U256
+        | TypeTag::Address => true,
+        TypeTag::Signer => false,
+        TypeTag::Vector(inner) => is_pure_type(inner),
+        TypeTag::Struct(tag) => {
+            let resolved = resolved(tag);
+            [RESOLVED_SUI_ID, RESOLVED_ASCII_STR, RESOLVED_UTF8_STR].contains(&resolved)
+                || (resolved == RESOLVED_STD_OPTION
+                    && tag.type_params.len() == 1
+                    && is_pure_type(&tag.type_params[0]))
+        }
+    }
+}
+
+fn is_tx_context(param: &SuiMoveNormalizedType) -> bool {
+    let (SuiMoveNormalizedType::Reference(inner) | SuiMoveNormalizedType::MutableReference(inner)) =
+        param
+    else {
+        return false;
+    };
+
+    let SuiMoveNormalizedType::Struct {
+        address,
+        module,
+        name,
+        ..
+    } = inner.as_ref()
+    else {
+        return false;
+    };
+
+    AccountAddress::from_hex_literal(address).is_ok_and(|a| a == SUI_FRAMEWORK_ADDRESS)
+        && module == TX_CONTEXT_MODULE_NAME.as_str()
+        && name == TX_CONTEXT_STRUCT_NAME.as_str()
+}
+
+/// How an argument for a parameter of type `param` should be passed, given the type arguments of
+/// the call.
+fn arg_kind(
+    param: &SuiMoveNormalizedType,
+    type_args: &[TypeTag],
+) -> Result<ArgKind, anyhow::Error> {
+    let (type_, reference) = match param {
+        SuiMoveNormalizedType::Reference(inner) => (type_tag(inner, type_args)?, Some(false)),
+        SuiMoveNormalizedType::MutableReference(inner) => (type_tag(inner, type_args)?, Some(true)),
+        param => (type_tag(param, type_args)?, None),
+    };
+
+    Ok(if is_pure_type(&type_) {
+        ArgKind::Pure(type_)
+    } else if let Some(mutable) = reference {
+        ArgKind::Object { mutable }
+    } else if matches!(&type_, TypeTag::Struct(tag) if resolved(tag) == RESOLVED_RECEIVING_STRUCT) {
+        ArgKind::Receiving
+    } else if matches!(&type_, TypeTag::Vector(_)) {
+        ArgKind::ObjectVector
+    } else {
+        ArgKind::Object { mutable: true }
+    })
+}
+
+/// The type of a (non-reference) parameter, with its type parameters substituted by `type_args`.
+fn type_tag(
+    type_: &SuiMoveNormalizedType,
+    type_args: &[TypeTag],
+) -> Result<TypeTag, anyhow::Error> {
+    use SuiMoveNormalizedType as T;
+    Ok(match type_ {
+        T::Bool => TypeTag::Bool,
+        T::U8 => TypeTag::U8,
+        T::U16 => TypeTag::U16,
+        T::U32 => TypeTag::U32,
+        T::U64 => TypeTag::U64,
+        T::U128 => TypeTag::U128,
+        T::U256 => TypeTag::U256,
+        T::Address => TypeTag::Address,
+        T::Signer => TypeTag::Signer,
+        T::Vector(inner) => TypeTag::Vector(Box::new(type_tag(inner, type_args)?)),
+        T::TypeParameter(ix) => type_args
+            .get(*ix as usize)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing type argument {ix}"))?,
+        T::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => TypeTag::Struct(Box::new(StructTag {
+            address: AccountAddress::from_hex_literal(address)?,
+            module: Identifier::new(module.as_str())?,
+            name: Identifier::new(name.as_str())?,
+            type_params: type_arguments
+                .iter()
+                .map(|t| type_tag(t, type_args))
+                .collect::<Result<_, _>>()?,
+        })),
+        T::Reference(_) | T::MutableReference(_) => {
+            bail!("Unexpected reference type nested within a parameter")
+        }
+    })
+}
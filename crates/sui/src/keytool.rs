@@ -28,6 +28,8 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
+use sui_keys::encrypted_keystore::{read_new_passphrase, read_passphrase, EncryptedFileKeystore};
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
@@ -56,6 +58,14 @@ mod keytool_tests;
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
 pub enum KeyToolCommand {
+    /// Change the passphrase of an encrypted keystore. The current passphrase is read from
+    /// SUI_KEYSTORE_PASSPHRASE or the file named by SUI_KEYSTORE_PASSPHRASE_FILE, or prompted for,
+    /// and the new one is read from the given file, or prompted for. All keys are re-encrypted
+    /// and any unlocked session is ended.
+    ChangePassphrase {
+        #[clap(long)]
+        new_passphrase_file: Option<PathBuf>,
+    },
     /// Convert private key from wallet format (hex of 32 byte private key) to sui.keystore format
     /// (base64 of 33 byte flag || private key) or vice versa.
     Convert { value: String },
@@ -104,6 +114,14 @@ pub enum KeyToolCommand {
     /// (Base64 encoded `privkey`). This prints out the account keypair as Base64 encoded `flag || privkey`,
     /// the network keypair, worker keypair, protocol keypair as Base64 encoded `privkey`.
     LoadKeypair { file: PathBuf },
+    /// Lock an encrypted keystore, ending the session started by `keytool unlock`.
+    Lock,
+    /// Encrypt a plaintext keystore in place, under a passphrase read from the given file, or
+    /// prompted for. The client config is updated to use the encrypted keystore.
+    MigrateKeystore {
+        #[clap(long)]
+        passphrase_file: Option<PathBuf>,
+    },
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
     /// See `keytool list` for example public keys.
     MultiSigAddress {
@@ -176,6 +194,13 @@ pub enum KeyToolCommand {
         #[clap(long)]
         base64pk: String,
     },
+    /// Unlock an encrypted keystore for the given number of seconds, so that other commands can
+    /// sign with its keys without asking for the passphrase. This prints a session token, which
+    /// those commands need to find in SUI_KEYSTORE_SESSION.
+    Unlock {
+        #[clap(long, default_value_t = 900)]
+        timeout_secs: u64,
+    },
     /// This takes [enum SuiKeyPair] of Base64 encoded of 33-byte `flag || privkey`). It
     /// outputs the keypair into a file at the current directory where the address is the filename,
     /// and prints out its Sui address, Base64 encoded public key, the key scheme, and the key scheme flag.
@@ -301,6 +326,15 @@ pub enum ConvertOutput {
     Hex(String),
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreStatus {
+    keystore_path: PathBuf,
+    locked: bool,
+    unlocked_for_secs: Option<u64>,
+    session: Option<String>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PrivateKeyBase64 {
@@ -355,6 +389,7 @@ pub enum CommandOutput {
     Error(String),
     Generate(Key),
     Import(Key),
+    KeystoreStatus(KeystoreStatus),
     List(Vec<Key>),
     LoadKeypair(KeypairData),
     MultiSigAddress(MultiSigAddress),
@@ -371,6 +406,21 @@ pub enum CommandOutput {
 impl KeyToolCommand {
     pub async fn execute(self, keystore: &mut Keystore) -> Result<CommandOutput, anyhow::Error> {
         let cmd_result = Ok(match self {
+            KeyToolCommand::ChangePassphrase {
+                new_passphrase_file,
+            } => {
+                let keystore = encrypted_keystore(keystore)?;
+                let old_passphrase = read_passphrase("Enter current passphrase: ")?;
+                let new_passphrase = read_new_passphrase(new_passphrase_file.as_deref())?;
+                keystore.change_passphrase(&old_passphrase, &new_passphrase)?;
+                CommandOutput::KeystoreStatus(KeystoreStatus {
+                    keystore_path: keystore.path().to_path_buf(),
+                    locked: keystore.is_locked(),
+                    unlocked_for_secs: None,
+                    session: None,
+                })
+            }
+
             KeyToolCommand::Convert { value } => {
                 let result = convert_private_key_to_base64(value)?;
                 CommandOutput::Convert(result)
@@ -508,6 +558,43 @@ impl KeyToolCommand {
                 CommandOutput::List(keys)
             }
 
+            KeyToolCommand::Lock => {
+                let keystore = encrypted_keystore(keystore)?;
+                keystore.lock()?;
+                CommandOutput::KeystoreStatus(KeystoreStatus {
+                    keystore_path: keystore.path().to_path_buf(),
+                    locked: true,
+                    unlocked_for_secs: None,
+                    session: None,
+                })
+            }
+
+            KeyToolCommand::MigrateKeystore { passphrase_file } => {
+                let encrypted = match keystore {
+                    Keystore::File(file) => {
+                        let path = file
+                            .path()
+                            .ok_or_else(|| anyhow!("Keystore has no path to migrate"))?
+                            .to_path_buf();
+                        let passphrase = read_new_passphrase(passphrase_file.as_deref())?;
+                        EncryptedFileKeystore::create(&path, &passphrase, file.key_pairs())?
+                    }
+                    Keystore::Encrypted(_) => return Err(anyhow!("Keystore is already encrypted")),
+                    Keystore::InMem(_) => {
+                        return Err(anyhow!("An in-memory keystore cannot be migrated"))
+                    }
+                };
+
+                let status = KeystoreStatus {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    locked: encrypted.is_locked(),
+                    unlocked_for_secs: None,
+                    session: None,
+                };
+                *keystore = Keystore::Encrypted(encrypted);
+                CommandOutput::KeystoreStatus(status)
+            }
+
             KeyToolCommand::LoadKeypair { file } => {
                 let output = match read_keypair_from_file(&file) {
                     Ok(keypair) => {
@@ -724,6 +811,18 @@ impl KeyToolCommand {
                 })
             }
 
+            KeyToolCommand::Unlock { timeout_secs } => {
+                let keystore = encrypted_keystore(keystore)?;
+                keystore.unlock(&read_passphrase("Enter passphrase: ")?)?;
+                let session = keystore.start_session(Duration::from_secs(timeout_secs))?;
+                CommandOutput::KeystoreStatus(KeystoreStatus {
+                    keystore_path: keystore.path().to_path_buf(),
+                    locked: false,
+                    unlocked_for_secs: Some(timeout_secs),
+                    session: Some(session),
+                })
+            }
+
             KeyToolCommand::Unpack { keypair } => {
                 let keypair: SuiKeyPair = keypair.parse()
                     .expect("Expected a Base64 private key, but could not decode the input string to a SuiKeyPair");
@@ -1031,6 +1130,17 @@ impl Debug for CommandOutput {
     }
 }
 
+fn encrypted_keystore(
+    keystore: &mut Keystore,
+) -> Result<&mut EncryptedFileKeystore, anyhow::Error> {
+    match keystore {
+        Keystore::Encrypted(keystore) => Ok(keystore),
+        _ => Err(anyhow!(
+            "Keystore is not encrypted, encrypt it with `sui keytool migrate-keystore`"
+        )),
+    }
+}
+
 fn convert_private_key_to_base64(value: String) -> Result<ConvertOutput, anyhow::Error> {
     match Base64::decode(&value) {
         Ok(decoded) => {
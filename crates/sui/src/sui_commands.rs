@@ -266,8 +266,13 @@ impl SuiCommand {
             } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+                let mut keystore = Keystore::from_path(&keystore_path)?;
+                let was_encrypted = matches!(keystore, Keystore::Encrypted(_));
                 cmd.execute(&mut keystore).await?.print(!json);
+                if !was_encrypted && matches!(keystore, Keystore::Encrypted(_)) {
+                    let config = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
+                    update_client_config_keystore(&config, &keystore_path)?;
+                }
                 Ok(())
             }
             SuiCommand::Console { config } => {
@@ -361,7 +366,7 @@ async fn genesis(
     if write_config.is_none() && !files.is_empty() {
         if force {
             // check old keystore and client.yaml is compatible
-            let is_compatible = FileBasedKeystore::new(&keystore_path).is_ok()
+            let is_compatible = Keystore::from_path(&keystore_path).is_ok()
                 && PersistedConfig::<SuiClientConfig>::read(&client_path).is_ok();
             // Keep keystore and client.yaml if they are compatible
             if is_compatible {
@@ -411,7 +416,7 @@ async fn genesis(
                 // Make a new genesis config from the provided ip addresses.
                 GenesisConfig::new_for_benchmarks(&ips)
             } else if keystore_path.exists() {
-                let existing_keys = FileBasedKeystore::new(&keystore_path)?.addresses();
+                let existing_keys = Keystore::from_path(&keystore_path)?.addresses();
                 GenesisConfig::for_local_testing_with_addresses(existing_keys)
             } else {
                 GenesisConfig::for_local_testing()
@@ -618,7 +623,7 @@ async fn prompt_if_no_config(
                 .parent()
                 .unwrap_or(&sui_config_dir()?)
                 .join(SUI_KEYSTORE_FILENAME);
-            let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+            let mut keystore = Keystore::from_path(&keystore_path)?;
             let key_scheme = if accept_defaults {
                 SignatureScheme::ED25519
             } else {
@@ -649,6 +654,39 @@ async fn prompt_if_no_config(
     Ok(())
 }
 
+/// Point the client config at `config` to the encrypted keystore at `keystore_path`, if it was
+/// using the plaintext keystore at that path. The config is edited as YAML rather than through
+/// [SuiClientConfig], because the keystore it refers to can no longer be opened as plaintext.
+fn update_client_config_keystore(config: &Path, keystore_path: &Path) -> Result<(), anyhow::Error> {
+    if !config.exists() {
+        return Ok(());
+    }
+
+    let mut value: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(config)?)?;
+    let Some(keystore) = value.get_mut("keystore") else {
+        return Ok(());
+    };
+
+    let path = keystore.get("File").and_then(|path| path.as_str());
+    if path.map(Path::new) != Some(keystore_path) {
+        return Ok(());
+    }
+
+    let mut encrypted = serde_yaml::Mapping::new();
+    encrypted.insert(
+        "Encrypted".into(),
+        keystore_path.to_string_lossy().as_ref().into(),
+    );
+    *keystore = serde_yaml::Value::Mapping(encrypted);
+
+    fs::write(config, serde_yaml::to_string(&value)?)?;
+    info!(
+        "Client config {:?} now uses the encrypted keystore.",
+        config
+    );
+    Ok(())
+}
+
 fn read_line() -> Result<String, anyhow::Error> {
     let mut s = String::new();
     let _ = stdout().flush();
@@ -0,0 +1,201 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+
+use move_core_types::language_storage::TypeTag;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::parse_sui_type_tag;
+
+use super::{Ptb, PtbCommand, PtbValue};
+
+fn parse(args: &str) -> Result<Ptb, anyhow::Error> {
+    let args: Vec<_> = args.split_whitespace().map(String::from).collect();
+    Ptb::parse(&args)
+}
+
+fn num(digits: &str) -> PtbValue {
+    PtbValue::Number(digits.to_string(), None)
+}
+
+fn var(name: &str) -> PtbValue {
+    PtbValue::Var(name.to_string())
+}
+
+#[test]
+fn test_parse_split_and_transfer() {
+    let ptb = parse(
+        "--split-coins gas [1000, 2_000] \
+         --assign coins \
+         --transfer-objects [coins.0, coins.1] @0x42 \
+         --gas-budget 5000000",
+    )
+    .unwrap();
+
+    assert_eq!(
+        ptb,
+        Ptb {
+            commands: vec![
+                PtbCommand::SplitCoins {
+                    coin: PtbValue::Gas,
+                    amounts: vec![num("1000"), num("2000")],
+                },
+                PtbCommand::Assign {
+                    name: "coins".to_string(),
+                    value: None,
+                },
+                PtbCommand::TransferObjects {
+                    objects: vec![
+                        PtbValue::NestedVar("coins".to_string(), 0),
+                        PtbValue::NestedVar("coins".to_string(), 1),
+                    ],
+                    to: PtbValue::Address(SuiAddress::from(
+                        ObjectID::from_hex_literal("0x42").unwrap()
+                    )),
+                },
+            ],
+            gas_budget: Some(5_000_000),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_parse_move_call() {
+    let ptb = parse(
+        "--assign amount 10u8 \
+         --move-call 0x2::coin::value <0x2::sui::SUI> gas \
+         --move-call 0x42::m::f <u64,vector<0x2::coin::Coin<0x2::sui::SUI>>> \
+            amount [true, false] some(\"hi\") none \
+         --dry-run",
+    )
+    .unwrap();
+
+    assert!(ptb.dry_run);
+    assert_eq!(
+        ptb.commands,
+        vec![
+            PtbCommand::Assign {
+                name: "amount".to_string(),
+                value: Some(PtbValue::Number("10".to_string(), Some(TypeTag::U8))),
+            },
+            PtbCommand::MoveCall {
+                package: ObjectID::from_hex_literal("0x2").unwrap(),
+                module: "coin".to_string(),
+                function: "value".to_string(),
+                type_args: vec![parse_sui_type_tag("0x2::sui::SUI").unwrap()],
+                args: vec![PtbValue::Gas],
+            },
+            PtbCommand::MoveCall {
+                package: ObjectID::from_hex_literal("0x42").unwrap(),
+                module: "m".to_string(),
+                function: "f".to_string(),
+                type_args: vec![
+                    TypeTag::U64,
+                    parse_sui_type_tag("vector<0x2::coin::Coin<0x2::sui::SUI>>").unwrap(),
+                ],
+                args: vec![
+                    var("amount"),
+                    PtbValue::Vector(vec![PtbValue::Bool(true), PtbValue::Bool(false)]),
+                    PtbValue::Option(Some(Box::new(PtbValue::String("hi".to_string())))),
+                    PtbValue::Option(None),
+                ],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_merge_make_vec_and_publish() {
+    let ptb = parse(
+        "--merge-coins @0x1 [@0x2, @0x3,] \
+         --make-move-vec <u64> [1, 2, 3] \
+         --publish ./my_package \
+         --serialize-unsigned-transaction",
+    )
+    .unwrap();
+
+    let address = |s| PtbValue::Address(SuiAddress::from(ObjectID::from_hex_literal(s).unwrap()));
+    assert!(ptb.serialize_unsigned_transaction);
+    assert_eq!(
+        ptb.commands,
+        vec![
+            PtbCommand::MergeCoins {
+                into: address("0x1"),
+                coins: vec![address("0x2"), address("0x3")],
+            },
+            PtbCommand::MakeMoveVec {
+                type_: TypeTag::U64,
+                elements: vec![num("1"), num("2"), num("3")],
+            },
+            PtbCommand::Publish {
+                package_path: PathBuf::from("./my_package"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_string_operands() {
+    // Operands as the shell passes them, after removing the quotes around them
+    let args: Vec<_> = [
+        "--move-call",
+        "0x42::m::f",
+        "hello world",
+        "it's",
+        "\"quoted  twice\"",
+        "[\"a b\", 'c']",
+        "some(\"d e\")",
+        "x",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let ptb = Ptb::parse(&args).unwrap();
+
+    let string = |s: &str| PtbValue::String(s.to_string());
+    assert_eq!(
+        ptb.commands,
+        vec![PtbCommand::MoveCall {
+            package: ObjectID::from_hex_literal("0x42").unwrap(),
+            module: "m".to_string(),
+            function: "f".to_string(),
+            type_args: vec![],
+            args: vec![
+                string("hello world"),
+                string("it's"),
+                string("quoted  twice"),
+                PtbValue::Vector(vec![string("a b"), string("c")]),
+                PtbValue::Option(Some(Box::new(string("d e")))),
+                var("x"),
+            ],
+        }]
+    );
+
+    // Literals don't span operands
+    assert!(parse("--move-call 0x42::m::f \"hello world\"").is_err());
+}
+
+#[test]
+fn test_parse_errors() {
+    // No commands
+    assert!(parse("--gas-budget 100").is_err());
+    // Unknown command
+    assert!(parse("--frobnicate gas").is_err());
+    // Operand without a command
+    assert!(parse("gas --split-coins gas [1]").is_err());
+    // Conflicting options
+    assert!(parse(
+        "--split-coins gas [1] --serialize-unsigned-transaction --serialize-signed-transaction"
+    )
+    .is_err());
+    assert!(parse("--split-coins gas [1] --dry-run --serialize-unsigned-transaction").is_err());
+    // Malformed operands
+    assert!(parse("--split-coins gas [1, 2").is_err());
+    assert!(parse("--split-coins gas 1").is_err());
+    assert!(parse("--move-call 0x2::coin gas").is_err());
+    assert!(parse("--make-move-vec [1]").is_err());
+    assert!(parse("--assign gas").is_err());
+    assert!(parse("--transfer-objects [gas] @0x1 extra").is_err());
+    assert!(parse("--split-coins gas [1x]").is_err());
+}
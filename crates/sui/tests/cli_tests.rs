@@ -45,6 +45,7 @@ use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
+use sui_types::gas_coin::GAS;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use test_cluster::TestClusterBuilder;
 
@@ -466,6 +467,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -505,6 +508,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await;
@@ -531,6 +536,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await;
@@ -559,6 +566,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -566,6 +575,98 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_move_call_profile_output() -> Result<(), anyhow::Error> {
+    if !sui_replay::GAS_PROFILER_ENABLED {
+        return Ok(());
+    }
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let dir = tempfile::tempdir()?;
+    let profile_output = dir.path().join("profile.json");
+
+    let resp = SuiClientCommands::Call {
+        package: ObjectID::from_hex_literal("0x2")?,
+        module: "pay".to_string(),
+        function: "split".to_string(),
+        type_args: vec![GAS::type_tag()],
+        args: vec![
+            SuiJsonValue::new(json!(coins[1].coin_object_id))?,
+            SuiJsonValue::new(json!("1000"))?,
+        ],
+        gas: Some(coins[0].coin_object_id),
+        gas_budget: TEST_ONLY_GAS_UNIT_FOR_GENERIC * rgp,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: Some(profile_output.clone()),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(response) = resp else {
+        panic!("Expected a dry run of the call, got: {resp}");
+    };
+    assert!(response.effects.status().is_ok());
+
+    // The profile is written to the requested path, in speedscope format
+    let profile: serde_json::Value = serde_json::from_slice(&std::fs::read(&profile_output)?)?;
+    assert_eq!(
+        profile["$schema"],
+        "https://www.speedscope.app/file-format-schema.json"
+    );
+    assert!(!profile["profiles"][0]["events"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    assert!(profile["shared"]["frames"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|frame| frame["name"] == "split"));
+    Ok(())
+}
+
+#[test]
+fn test_move_call_dry_run_conflicts_with_serialization() {
+    use clap::Parser;
+
+    let call = [
+        "sui",
+        "call",
+        "--package",
+        "0x2",
+        "--module",
+        "pay",
+        "--function",
+        "split",
+        "--gas-budget",
+        "1000",
+    ];
+    let parse = |flags: &[&str]| SuiClientCommands::try_parse_from(call.iter().chain(flags));
+    for dry_run in ["--dry-run", "--profile-output=profile.json"] {
+        for serialize in [
+            "--serialize-unsigned-transaction",
+            "--serialize-signed-transaction",
+        ] {
+            let err = parse(&[dry_run, serialize]).err().unwrap();
+            assert_eq!(
+                err.kind(),
+                clap::error::ErrorKind::ArgumentConflict,
+                "{dry_run} {serialize}"
+            );
+        }
+    }
+    assert!(parse(&["--dry-run", "--profile-output=profile.json"]).is_ok());
+}
+
 #[sim_test]
 async fn test_package_publish_command() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -708,6 +809,8 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -749,6 +852,8 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -835,6 +940,8 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -876,6 +983,8 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -962,6 +1071,8 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -1003,6 +1114,8 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
+        profile_output: None,
     }
     .execute(context)
     .await?;
@@ -2283,6 +2396,51 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let args = |s: String| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+    let ptb = format!(
+        "--split-coins gas [1000, 2000] \
+         --assign coins \
+         --transfer-objects [coins.0, coins.1] @{address1}"
+    );
+
+    // Dry-running the transaction does not change any state.
+    let resp = SuiClientCommands::Ptb {
+        args: args(format!("{ptb} --dry-run")),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(response) = resp else {
+        panic!("Expected dry run response, got {resp:?}");
+    };
+    assert!(response.effects.status().is_ok());
+    assert_eq!(response.effects.created().len(), 2);
+
+    // Without a gas budget, it is estimated before execution.
+    let resp = SuiClientCommands::Ptb { args: args(ptb) }
+        .execute(context)
+        .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        panic!("Expected transaction response, got {resp:?}");
+    };
+    assert!(response.status_ok().unwrap());
+
+    let created = response.effects.as_ref().unwrap().created();
+    assert_eq!(created.len(), 2);
+    for coin in created {
+        assert_eq!(coin.owner, Owner::AddressOwner(address1));
+        let value = get_gas_value(&get_object(coin.reference.object_id, context).await.unwrap());
+        assert!(value == 1000 || value == 2000);
+    }
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -21,11 +21,11 @@ use move_core_types::{
     value::MoveValue,
 };
 use move_package::compilation::compiled_package::CompiledPackage;
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_profiler::GasProfiler;
 use move_vm_runtime::move_vm::MoveVM;
 use move_vm_test_utils::gas_schedule::CostTable;
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_types::gas::GasMeter;
 use std::{fs, path::Path};
 
@@ -105,7 +105,7 @@ move run` must be applied to a module inside `storage/`",
             // script fun. parse module, extract script ID to pass to VM
             let module = CompiledModule::deserialize_with_defaults(&bytecode)
                 .map_err(|e| anyhow!("Error deserializing module: {:?}", e))?;
-            #[cfg(debug_assertions)]
+            #[cfg(any(debug_assertions, feature = "gas-profiler"))]
             {
                 let gas_rem: u64 = gas_status.remaining_gas().into();
                 gas_status.set_profiler(GasProfiler::init(
@@ -20,7 +20,7 @@ pub struct VMConfig {
     // deserializing and check for no metadata bytes
     pub check_no_extraneous_bytes_during_deserialization: bool,
     // Configs for profiling VM
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     pub profiler_config: VMProfilerConfig,
     // When this flag is set to true, errors from the VM will be augmented with execution state
     // (stacktrace etc.)
@@ -36,7 +36,7 @@ impl Default for VMConfig {
             runtime_limits_config: VMRuntimeLimitsConfig::default(),
             enable_invariant_violation_check_in_swap_loc: true,
             check_no_extraneous_bytes_during_deserialization: false,
-            #[cfg(debug_assertions)]
+            #[cfg(any(debug_assertions, feature = "gas-profiler"))]
             profiler_config: VMProfilerConfig::default(),
             error_execution_state: true,
         }
@@ -60,22 +60,45 @@ impl Default for VMRuntimeLimitsConfig {
     }
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
+const MOVE_VM_PROFILER_ENV_VAR_NAME: &str = "MOVE_VM_PROFILE";
+
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 #[derive(Clone, Debug)]
 pub struct VMProfilerConfig {
+    /// Whether or not profiles are recorded and written out
+    pub enabled: bool,
     /// Base path for files
     pub base_path: std::path::PathBuf,
+    /// Write the profile to this file instead of a generated one under `base_path`
+    pub full_path: Option<std::path::PathBuf>,
     /// Whether or not to track bytecode instructions
     pub track_bytecode_instructions: bool,
     /// Whether or not to use the long name for functions
     pub use_long_function_name: bool,
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
+impl VMProfilerConfig {
+    /// Profile into `path`, regardless of the `MOVE_VM_PROFILE` env var. Functions are named
+    /// with their package address and module, so gas is attributed across packages.
+    pub fn with_full_path(path: std::path::PathBuf) -> Self {
+        Self {
+            enabled: true,
+            full_path: Some(path),
+            use_long_function_name: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 impl std::default::Default for VMProfilerConfig {
     fn default() -> Self {
         Self {
+            enabled: std::env::var(MOVE_VM_PROFILER_ENV_VAR_NAME).is_ok(),
             base_path: std::path::PathBuf::from("."),
+            full_path: None,
             track_bytecode_instructions: false,
             use_long_function_name: false,
         }
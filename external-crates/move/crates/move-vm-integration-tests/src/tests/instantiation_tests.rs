@@ -23,7 +23,7 @@ use move_core_types::{
     language_storage::{ModuleId, StructTag, TypeTag},
     vm_status::StatusCode,
 };
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_profiler::GasProfiler;
 use move_vm_runtime::{
     move_vm::MoveVM,
@@ -33,7 +33,7 @@ use move_vm_test_utils::{
     gas_schedule::{Gas, GasStatus, INITIAL_COST_SCHEDULE},
     InMemoryStorage,
 };
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_types::gas::GasMeter;
 use std::time::Instant;
 
@@ -621,7 +621,7 @@ fn run_with_module(
         .map(|tag| session.load_type(&tag))
         .collect::<VMResult<Vec<_>>>();
 
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     gas.set_profiler(GasProfiler::init(
         &session.vm_config().profiler_config,
         entry_name.to_string(),
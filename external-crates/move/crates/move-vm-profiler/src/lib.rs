@@ -1,30 +1,21 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_config::runtime::VMProfilerConfig;
-#[cfg(debug_assertions)]
-use once_cell::sync::Lazy;
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use serde::Serialize;
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use std::collections::BTreeMap;
 
-#[cfg(debug_assertions)]
-const MOVE_VM_PROFILER_ENV_VAR_NAME: &str = "MOVE_VM_PROFILE";
-
-#[cfg(debug_assertions)]
-static PROFILER_ENABLED: Lazy<bool> =
-    Lazy::new(|| std::env::var(MOVE_VM_PROFILER_ENV_VAR_NAME).is_ok());
-
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 #[derive(Debug, Clone, Serialize)]
 pub struct FrameName {
     name: String,
     file: String,
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 #[derive(Debug, Clone, Serialize)]
 pub struct Shared {
     frames: Vec<FrameName>,
@@ -33,7 +24,7 @@ pub struct Shared {
     frame_table: BTreeMap<String, usize>,
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 #[derive(Debug, Clone, Serialize)]
 pub struct Event {
     #[serde(rename(serialize = "type"))]
@@ -42,7 +33,7 @@ pub struct Event {
     at: u64,
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Profile {
@@ -55,7 +46,7 @@ pub struct Profile {
     events: Vec<Event>,
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GasProfiler {
@@ -75,7 +66,7 @@ pub struct GasProfiler {
     finished: bool,
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 impl GasProfiler {
     // Used by profiler viz tool
     const OPEN_FRAME_IDENT: &str = "O";
@@ -154,7 +145,7 @@ impl GasProfiler {
     }
 
     pub fn open_frame(&mut self, frame_name: String, metadata: String, gas_start: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.config.enabled || self.start_gas == 0 {
             return;
         }
 
@@ -169,7 +160,7 @@ impl GasProfiler {
     }
 
     pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.config.enabled || self.start_gas == 0 {
             return;
         }
         let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
@@ -183,9 +174,9 @@ impl GasProfiler {
         self.profiles[0].end_value = start - gas_end;
     }
 
-    pub fn to_file(&self) {
-        if !*PROFILER_ENABLED || !self.is_metered() {
-            return;
+    pub fn to_file(&self) -> std::io::Result<()> {
+        if !self.config.enabled || !self.is_metered() {
+            return Ok(());
         }
         // Get the unix timestamp
         let now = std::time::SystemTime::now()
@@ -193,14 +184,21 @@ impl GasProfiler {
             .expect("Clock may have gone backwards")
             .as_nanos();
 
-        let mut p = self.config.base_path.clone();
-        p.push(format!("gas_profile_{}_{}.json", self.profile_name(), now));
+        let p = match &self.config.full_path {
+            Some(full_path) => full_path.clone(),
+            None => self.config.base_path.join(format!(
+                "gas_profile_{}_{}.json",
+                self.profile_name(),
+                now
+            )),
+        };
         let path_str = p.as_os_str().to_string_lossy().to_string();
-        let mut file = std::fs::File::create(p).expect("Unable to create file");
+        let mut file = std::fs::File::create(p)?;
 
-        let json = serde_json::to_string_pretty(&self).expect("Unable to serialize profile");
-        std::io::Write::write_all(&mut file, json.as_bytes()).expect("Unable to write to file");
+        let json = serde_json::to_string_pretty(&self)?;
+        std::io::Write::write_all(&mut file, json.as_bytes())?;
         println!("Gas profile written to file: {}", path_str);
+        Ok(())
     }
 
     pub fn finish(&mut self) {
@@ -211,11 +209,14 @@ impl GasProfiler {
         let end_gas = self.start_gas() - self.profiles[0].end_value;
         let mut q = Some(self);
         profile_close_frame_impl!(&mut q, Self::TOP_LEVEL_FRAME_NAME.to_string(), end_gas);
-        profile_dump_file!(q.unwrap());
+        // Profiles are written when the profiler is dropped, so errors can't be returned
+        if let Err(err) = q.unwrap().to_file() {
+            eprintln!("Unable to write gas profile: {err}");
+        }
     }
 }
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 impl Drop for GasProfiler {
     fn drop(&mut self) {
         self.finish();
@@ -225,7 +226,7 @@ impl Drop for GasProfiler {
 #[macro_export]
 macro_rules! profile_open_frame {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             move_vm_profiler::profile_open_frame_impl!(
@@ -240,7 +241,7 @@ macro_rules! profile_open_frame {
 #[macro_export]
 macro_rules! profile_open_frame_impl {
     ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             if let Some(profiler) = $profiler {
                 let name = if !profiler.config.use_long_function_name {
@@ -257,7 +258,7 @@ macro_rules! profile_open_frame_impl {
 #[macro_export]
 macro_rules! profile_close_frame {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             move_vm_profiler::profile_close_frame_impl!(
@@ -272,7 +273,7 @@ macro_rules! profile_close_frame {
 #[macro_export]
 macro_rules! profile_close_frame_impl {
     ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             if let Some(profiler) = $profiler {
                 let name = if !profiler.config.use_long_function_name {
@@ -289,7 +290,7 @@ macro_rules! profile_close_frame_impl {
 #[macro_export]
 macro_rules! profile_open_instr {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
@@ -304,7 +305,7 @@ macro_rules! profile_open_instr {
 #[macro_export]
 macro_rules! profile_close_instr {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
@@ -319,7 +320,7 @@ macro_rules! profile_close_instr {
 #[macro_export]
 macro_rules! profile_dump_file {
     ($profiler:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         $profiler.to_file()
     };
 }
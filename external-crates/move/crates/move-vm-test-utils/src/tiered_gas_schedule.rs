@@ -16,7 +16,7 @@ use move_core_types::{
     language_storage::ModuleId,
     vm_status::StatusCode,
 };
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_profiler::GasProfiler;
 use move_vm_types::{
     gas::{GasMeter, SimpleInstruction},
@@ -180,7 +180,7 @@ pub struct GasStatus<'a> {
     instructions_next_tier_start: Option<u64>,
     instructions_current_tier_mult: u64,
 
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     profiler: Option<GasProfiler>,
 }
 
@@ -211,7 +211,7 @@ impl<'a> GasStatus<'a> {
             stack_height_next_tier_start,
             stack_size_next_tier_start,
             instructions_next_tier_start,
-            #[cfg(debug_assertions)]
+            #[cfg(any(debug_assertions, feature = "gas-profiler"))]
             profiler: None,
         }
     }
@@ -236,7 +236,7 @@ impl<'a> GasStatus<'a> {
             stack_height_next_tier_start: None,
             stack_size_next_tier_start: None,
             instructions_next_tier_start: None,
-            #[cfg(debug_assertions)]
+            #[cfg(any(debug_assertions, feature = "gas-profiler"))]
             profiler: None,
         }
     }
@@ -759,12 +759,12 @@ impl<'b> GasMeter for GasStatus<'b> {
         self.gas_left
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     fn get_profiler_mut(&mut self) -> Option<&mut GasProfiler> {
         self.profiler.as_mut()
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     fn set_profiler(&mut self, profiler: GasProfiler) {
         self.profiler = Some(profiler);
     }
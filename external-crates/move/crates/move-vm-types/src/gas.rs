@@ -7,7 +7,7 @@ use move_core_types::{
     gas_algebra::{InternalGas, NumArgs, NumBytes},
     language_storage::ModuleId,
 };
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_profiler::GasProfiler;
 
 /// Enum of instructions that do not need extra information for gas metering.
@@ -236,11 +236,14 @@ pub trait GasMeter {
     /// Returns the gas left
     fn remaining_gas(&self) -> InternalGas;
 
-    #[cfg(debug_assertions)]
-    fn get_profiler_mut(&mut self) -> Option<&mut GasProfiler>;
+    /// Meters that do not keep a profiler are not profiled
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
+    fn get_profiler_mut(&mut self) -> Option<&mut GasProfiler> {
+        None
+    }
 
-    #[cfg(debug_assertions)]
-    fn set_profiler(&mut self, profiler: GasProfiler);
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
+    fn set_profiler(&mut self, _profiler: GasProfiler) {}
 }
 
 /// A dummy gas meter that does not meter anything.
@@ -457,12 +460,4 @@ impl GasMeter for UnmeteredGasMeter {
     fn remaining_gas(&self) -> InternalGas {
         InternalGas::new(u64::MAX)
     }
-
-    #[cfg(debug_assertions)]
-    fn get_profiler_mut(&mut self) -> Option<&mut GasProfiler> {
-        None
-    }
-
-    #[cfg(debug_assertions)]
-    fn set_profiler(&mut self, _profiler: GasProfiler) {}
 }
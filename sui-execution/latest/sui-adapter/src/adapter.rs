@@ -5,13 +5,15 @@ pub use checked::*;
 
 #[sui_macros::with_checked_arithmetic]
 mod checked {
-    use std::{collections::BTreeMap, sync::Arc};
+    use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
     use anyhow::Result;
     use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
     use move_bytecode_verifier::meter::Meter;
     use move_bytecode_verifier::verify_module_with_config_metered;
     use move_core_types::account_address::AccountAddress;
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
+    use move_vm_config::runtime::VMProfilerConfig;
     use move_vm_config::{
         runtime::{VMConfig, VMRuntimeLimitsConfig},
         verifier::VerifierConfig,
@@ -84,7 +86,10 @@ mod checked {
         natives: NativeFunctionTable,
         protocol_config: &ProtocolConfig,
         paranoid_type_checks: bool,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<MoveVM, SuiError> {
+        #[cfg(not(any(debug_assertions, feature = "gas-profiler")))]
+        let _ = enable_profiler;
         MoveVM::new_with_config(
             natives,
             VMConfig {
@@ -102,8 +107,11 @@ mod checked {
                     .disable_invariant_violation_check_in_swap_loc(),
                 check_no_extraneous_bytes_during_deserialization: protocol_config
                     .no_extraneous_module_bytes(),
-                #[cfg(debug_assertions)]
-                profiler_config: std::default::Default::default(),
+                // Profiling is opted into by the caller, e.g. when replaying a transaction
+                #[cfg(any(debug_assertions, feature = "gas-profiler"))]
+                profiler_config: enable_profiler
+                    .map(VMProfilerConfig::with_full_path)
+                    .unwrap_or_default(),
                 // Don't augment errors with execution state on-chain
                 error_execution_state: false,
             },
@@ -3,7 +3,7 @@
 
 // $GENERATED_MESSAGE
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use sui_protocol_config::ProtocolConfig;
 use sui_types::{error::SuiResult, metrics::BytecodeVerifierMetrics};
@@ -24,6 +24,7 @@ pub fn executor(
     protocol_config: &ProtocolConfig,
     paranoid_type_checks: bool,
     silent: bool,
+    enable_profiler: Option<PathBuf>,
 ) -> SuiResult<Arc<dyn Executor + Send + Sync>> {
     let version = protocol_config.execution_version_as_option().unwrap_or(0);
     Ok(match version {
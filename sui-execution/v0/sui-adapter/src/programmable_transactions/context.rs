@@ -24,10 +24,10 @@ mod checked {
         account_address::AccountAddress,
         language_storage::{ModuleId, StructTag, TypeTag},
     };
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     use move_vm_profiler::GasProfiler;
     use move_vm_runtime::{move_vm::MoveVM, session::Session};
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "gas-profiler"))]
     use move_vm_types::gas::GasMeter;
     use move_vm_types::loaded_data::runtime_types::Type;
     use sui_move_natives::object_runtime::{
@@ -202,8 +202,8 @@ mod checked {
                 metrics.clone(),
             );
 
-            // Set the profiler if in debug mode
-            #[cfg(debug_assertions)]
+            // Set the profiler if in debug mode or built with the `gas-profiler` feature
+            #[cfg(any(debug_assertions, feature = "gas-profiler"))]
             {
                 let tx_digest = tx_context.digest();
                 let remaining_gas: u64 =